@@ -0,0 +1,399 @@
+//! `fasttok`: tokenization and corpus preprocessing from the command line.
+//!
+//! Every subcommand reads its input files, or standard input, one line at a
+//! time and treats each line as a document.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use fasttokenizer_core::tiktoken::parse_ranks;
+use fasttokenizer_core::{
+    BpeTrainer, Flags, Form, Limits, Mode, RegexTokenizer, TiktokenEncoder, Tokenize,
+    WhitespaceTokenizer,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "fasttok",
+    version,
+    about = "Tokenize, count and clean text corpora"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the tokens of every input line, separated by spaces.
+    Tokenize {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        tokenizer: TokenizerArgs,
+        /// Print the tokens of each line as a JSON array instead.
+        #[arg(long)]
+        json: bool,
+        /// Print token ids instead of tokens; tiktoken only.
+        #[arg(long)]
+        ids: bool,
+    },
+    /// Count the tokens of each input file, and their total.
+    Count {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        tokenizer: TokenizerArgs,
+    },
+    /// Train a byte-level BPE vocabulary and write `vocab.json` and
+    /// `merges.txt`, as loaded by `BpeTokenizer.from_files`.
+    TrainBpe {
+        #[command(flatten)]
+        input: Input,
+        /// Size of the vocabulary, including the byte alphabet and special
+        /// tokens.
+        #[arg(long, default_value_t = 30000)]
+        vocab_size: usize,
+        /// Never merge pairs seen fewer times than this.
+        #[arg(long, default_value_t = 2)]
+        min_frequency: u64,
+        /// A special token placed at the start of the vocabulary; repeat for
+        /// several.
+        #[arg(long = "special-token")]
+        special_tokens: Vec<String>,
+        /// Directory the files are written to.
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Normalize every line and drop the ones not worth keeping.
+    Clean {
+        #[command(flatten)]
+        input: Input,
+        /// Unicode normalization form: NFC, NFD, NFKC or NFKD.
+        #[arg(long)]
+        form: Option<String>,
+        #[arg(long)]
+        lowercase: bool,
+        /// Replace runs of whitespace with a single space and trim the line.
+        #[arg(long)]
+        collapse_whitespace: bool,
+        /// Remove control characters other than tabs.
+        #[arg(long)]
+        remove_control: bool,
+        /// Drop lines with fewer words than this.
+        #[arg(long, default_value_t = 0)]
+        min_words: usize,
+        /// Drop lines identical to an earlier one after cleaning.
+        #[arg(long)]
+        dedup: bool,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// Input files; standard input when none are given or for `-`.
+    files: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Kind {
+    Whitespace,
+    Regex,
+    Tiktoken,
+}
+
+#[derive(Args)]
+struct TokenizerArgs {
+    #[arg(short, long, value_enum, default_value_t = Kind::Whitespace)]
+    tokenizer: Kind,
+    /// Pattern of the regex tokenizer; repeat for several, tried in order.
+    #[arg(long)]
+    pattern: Vec<String>,
+    /// How the regex tokenizer uses its patterns: find, split or captures.
+    #[arg(long, default_value = "find")]
+    mode: String,
+    /// `.tiktoken` ranks file of the tiktoken encoding.
+    #[arg(long)]
+    ranks: Option<PathBuf>,
+    /// Name of the tiktoken encoding the ranks belong to.
+    #[arg(long, default_value = "cl100k_base")]
+    encoding: String,
+}
+
+enum Tokenizer {
+    Whitespace(WhitespaceTokenizer),
+    Regex(RegexTokenizer),
+    Tiktoken(TiktokenEncoder),
+}
+
+impl TokenizerArgs {
+    fn build(&self) -> Result<Tokenizer> {
+        Ok(match self.tokenizer {
+            Kind::Whitespace => Tokenizer::Whitespace(WhitespaceTokenizer::default()),
+            Kind::Regex => {
+                if self.pattern.is_empty() {
+                    return Err("the regex tokenizer needs at least one --pattern".into());
+                }
+                let mode = Mode::parse(&self.mode)?;
+                let tokenizer =
+                    RegexTokenizer::new(&self.pattern, mode, Flags::default(), Limits::NONE)?;
+                Tokenizer::Regex(tokenizer)
+            }
+            Kind::Tiktoken => {
+                let path = self
+                    .ranks
+                    .as_ref()
+                    .ok_or("the tiktoken tokenizer needs a --ranks file")?;
+                let ranks = parse_ranks(&read_to_string(path)?)?;
+                Tokenizer::Tiktoken(TiktokenEncoder::from_ranks(ranks, &self.encoding)?)
+            }
+        })
+    }
+}
+
+impl Tokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        match self {
+            Tokenizer::Whitespace(tokenizer) => tokenizer.tokenize(text),
+            Tokenizer::Regex(tokenizer) => tokenizer.tokenize(text),
+            Tokenizer::Tiktoken(tokenizer) => tokenizer.tokenize(text),
+        }
+    }
+
+    fn count(&self, text: &str) -> usize {
+        match self {
+            Tokenizer::Whitespace(tokenizer) => tokenizer.tokenize_with_offsets(text).len(),
+            Tokenizer::Regex(tokenizer) => tokenizer.tokenize_with_offsets(text).len(),
+            Tokenizer::Tiktoken(tokenizer) => tokenizer.count_tokens(text),
+        }
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+/// The named inputs, with `-` standing for standard input.
+fn sources(input: &Input) -> Vec<PathBuf> {
+    if input.files.is_empty() {
+        vec![PathBuf::from("-")]
+    } else {
+        input.files.clone()
+    }
+}
+
+/// Call `f` with every line of `path`, without its line break.
+fn for_each_line(path: &Path, mut f: impl FnMut(&str) -> Result<()>) -> Result<()> {
+    let mut reader: Box<dyn BufRead> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Box::new(BufReader::new(file))
+    };
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        f(line.trim_end_matches(['\n', '\r']))?;
+    }
+}
+
+fn tokenize(input: &Input, tokenizer: &Tokenizer, json: bool, ids: bool) -> Result<()> {
+    let encoder = match tokenizer {
+        Tokenizer::Tiktoken(encoder) => Some(encoder),
+        _ if ids => return Err("--ids needs the tiktoken tokenizer".into()),
+        _ => None,
+    };
+    let mut out = BufWriter::new(io::stdout().lock());
+    for path in sources(input) {
+        for_each_line(&path, |line| {
+            match encoder.filter(|_| ids) {
+                Some(encoder) => {
+                    let ids = encoder.encode_ordinary(line);
+                    if json {
+                        serde_json::to_writer(&mut out, &ids)?;
+                    } else {
+                        let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+                        out.write_all(ids.join(" ").as_bytes())?;
+                    }
+                }
+                None => {
+                    let tokens = tokenizer.tokenize(line);
+                    if json {
+                        serde_json::to_writer(&mut out, &tokens)?;
+                    } else {
+                        out.write_all(tokens.join(" ").as_bytes())?;
+                    }
+                }
+            }
+            out.write_all(b"\n")?;
+            Ok(())
+        })?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn count(input: &Input, tokenizer: &Tokenizer) -> Result<()> {
+    let mut out = io::stdout().lock();
+    let paths = sources(input);
+    let mut total = 0;
+    for path in &paths {
+        let mut tokens = 0;
+        for_each_line(path, |line| {
+            tokens += tokenizer.count(line);
+            Ok(())
+        })?;
+        writeln!(out, "{}\t{}", tokens, path.display())?;
+        total += tokens;
+    }
+    if paths.len() > 1 {
+        writeln!(out, "{}\ttotal", total)?;
+    }
+    Ok(())
+}
+
+fn train_bpe(
+    input: &Input,
+    vocab_size: usize,
+    min_frequency: u64,
+    special_tokens: Vec<String>,
+    output: &Path,
+) -> Result<()> {
+    let mut trainer = BpeTrainer::new(vocab_size, min_frequency, special_tokens)?;
+    for path in sources(input) {
+        for_each_line(&path, |line| {
+            trainer.feed(line);
+            Ok(())
+        })?;
+    }
+    let trained = trainer.train();
+    fs::create_dir_all(output)?;
+    let vocab: BTreeMap<String, u32> = trained.vocab_map().into_iter().collect();
+    fs::write(output.join("vocab.json"), serde_json::to_string(&vocab)?)?;
+    fs::write(output.join("merges.txt"), trained.merges_text())?;
+    eprintln!(
+        "Trained {} tokens with {} merges from {} distinct pieces into {}",
+        trained.vocab.len(),
+        trained.merges.len(),
+        trainer.word_count(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Options of the `clean` subcommand.
+struct Cleaning {
+    form: Option<Form>,
+    lowercase: bool,
+    collapse_whitespace: bool,
+    remove_control: bool,
+    min_words: usize,
+    dedup: bool,
+}
+
+impl Cleaning {
+    fn apply(&self, line: &str) -> String {
+        let mut text = if self.remove_control {
+            line.chars()
+                .filter(|&c| c == '\t' || !c.is_control())
+                .collect()
+        } else {
+            line.to_string()
+        };
+        if let Some(form) = self.form {
+            text = form.apply(&text).into_owned();
+        }
+        if self.lowercase {
+            text = text.to_lowercase();
+        }
+        if self.collapse_whitespace {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        text
+    }
+
+    fn run(&self, input: &Input) -> Result<()> {
+        let mut out = BufWriter::new(io::stdout().lock());
+        let mut seen = HashSet::new();
+        for path in sources(input) {
+            for_each_line(&path, |line| {
+                let text = self.apply(line);
+                if text.split_whitespace().count() < self.min_words {
+                    return Ok(());
+                }
+                if self.dedup && !seen.insert(text.clone()) {
+                    return Ok(());
+                }
+                out.write_all(text.as_bytes())?;
+                out.write_all(b"\n")?;
+                Ok(())
+            })?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::Tokenize {
+            input,
+            tokenizer,
+            json,
+            ids,
+        } => tokenize(&input, &tokenizer.build()?, json, ids),
+        Command::Count { input, tokenizer } => count(&input, &tokenizer.build()?),
+        Command::TrainBpe {
+            input,
+            vocab_size,
+            min_frequency,
+            special_tokens,
+            output,
+        } => train_bpe(&input, vocab_size, min_frequency, special_tokens, &output),
+        Command::Clean {
+            input,
+            form,
+            lowercase,
+            collapse_whitespace,
+            remove_control,
+            min_words,
+            dedup,
+        } => {
+            let cleaning = Cleaning {
+                form: form.as_deref().map(Form::parse).transpose()?,
+                lowercase,
+                collapse_whitespace,
+                remove_control,
+                min_words,
+                dedup,
+            };
+            cleaning.run(&input)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped into e.g. `head` that stopped reading.
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("fasttok: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
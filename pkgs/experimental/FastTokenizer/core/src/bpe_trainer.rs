@@ -0,0 +1,187 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::error::{Error, Result};
+use crate::pretokenizer::{byte_level_split, bytes_to_unicode};
+
+/// A trained byte-level BPE model: the vocabulary in id order and the merge
+/// rules, highest priority first, as stored in `vocab.json` and
+/// `merges.txt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrainedBpe {
+    pub vocab: Vec<String>,
+    pub merges: Vec<(String, String)>,
+}
+
+impl TrainedBpe {
+    /// The vocabulary as a token to id mapping.
+    pub fn vocab_map(&self) -> HashMap<String, u32> {
+        self.vocab
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.clone(), id as u32))
+            .collect()
+    }
+
+    /// The merges in `merges.txt` format, with its `#version` header.
+    pub fn merges_text(&self) -> String {
+        let mut out = String::from("#version: 0.2\n");
+        for (a, b) in &self.merges {
+            out.push_str(a);
+            out.push(' ');
+            out.push_str(b);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Learns a GPT-2 style byte-level BPE vocabulary from text.
+///
+/// Text is split with the GPT-2 pre-tokenizer and every piece is counted;
+/// training then repeatedly merges the most frequent adjacent pair of
+/// symbols until the vocabulary is full. Ties go to the pair of symbols
+/// added first, so the result does not depend on the order of the input.
+#[derive(Clone, Debug)]
+pub struct BpeTrainer {
+    vocab_size: usize,
+    min_frequency: u64,
+    special_tokens: Vec<String>,
+    /// Pieces in byte-level characters, with their counts.
+    words: HashMap<String, u64>,
+}
+
+impl BpeTrainer {
+    /// A trainer for a vocabulary of `vocab_size` tokens: the special tokens,
+    /// the 256 byte symbols and as many merges as fit. Pairs seen fewer than
+    /// `min_frequency` times are never merged.
+    pub fn new(vocab_size: usize, min_frequency: u64, special_tokens: Vec<String>) -> Result<Self> {
+        let alphabet = 256 + special_tokens.len();
+        if vocab_size < alphabet {
+            return Err(Error::InvalidArgument(format!(
+                "vocab_size must be at least {} to hold the byte alphabet and special tokens",
+                alphabet
+            )));
+        }
+        Ok(BpeTrainer {
+            vocab_size,
+            min_frequency,
+            special_tokens,
+            words: HashMap::new(),
+        })
+    }
+
+    /// Count the pieces of `text`.
+    pub fn feed(&mut self, text: &str) {
+        let table = bytes_to_unicode();
+        for (start, end) in byte_level_split(text) {
+            let word: String = text.as_bytes()[start..end]
+                .iter()
+                .map(|&b| table[b as usize])
+                .collect();
+            *self.words.entry(word).or_default() += 1;
+        }
+    }
+
+    /// Number of distinct pieces counted so far.
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Learn merges from the pieces counted so far.
+    pub fn train(&self) -> TrainedBpe {
+        let mut vocab: Vec<String> = self.special_tokens.clone();
+        vocab.extend(bytes_to_unicode().iter().map(char::to_string));
+        let mut ids: HashMap<String, u32> = HashMap::new();
+        for (id, token) in vocab.iter().enumerate() {
+            ids.entry(token.clone()).or_insert(id as u32);
+        }
+        let mut entries: Vec<(&String, &u64)> = self.words.iter().collect();
+        entries.sort();
+        let mut words: Vec<(Vec<u32>, u64)> = entries
+            .into_iter()
+            .map(|(word, &count)| {
+                let symbols = word.chars().map(|c| ids[&c.to_string()]).collect();
+                (symbols, count)
+            })
+            .collect();
+        let mut counts: HashMap<(u32, u32), u64> = HashMap::new();
+        let mut locations: HashMap<(u32, u32), HashSet<usize>> = HashMap::new();
+        for (i, (symbols, count)) in words.iter().enumerate() {
+            for pair in symbols.windows(2).map(|w| (w[0], w[1])) {
+                *counts.entry(pair).or_default() += count;
+                locations.entry(pair).or_default().insert(i);
+            }
+        }
+        // Entries go stale when a count drops; they are checked when popped.
+        let mut heap: BinaryHeap<(u64, Reverse<(u32, u32)>)> = counts
+            .iter()
+            .map(|(&pair, &count)| (count, Reverse(pair)))
+            .collect();
+        let mut merges = Vec::new();
+        while vocab.len() < self.vocab_size {
+            let Some((count, Reverse(pair))) = heap.pop() else {
+                break;
+            };
+            let current = counts.get(&pair).copied().unwrap_or(0);
+            if count != current {
+                if current > 0 {
+                    heap.push((current, Reverse(pair)));
+                }
+                continue;
+            }
+            if count < self.min_frequency.max(1) {
+                break;
+            }
+            let (a, b) = pair;
+            let merged = format!("{}{}", vocab[a as usize], vocab[b as usize]);
+            merges.push((vocab[a as usize].clone(), vocab[b as usize].clone()));
+            let id = match ids.get(&merged) {
+                Some(&id) => id,
+                None => {
+                    let id = vocab.len() as u32;
+                    ids.insert(merged.clone(), id);
+                    vocab.push(merged);
+                    id
+                }
+            };
+            counts.remove(&pair);
+            let mut touched = HashSet::new();
+            for i in locations.remove(&pair).unwrap_or_default() {
+                let (symbols, count) = &mut words[i];
+                let count = *count;
+                if !symbols.windows(2).any(|w| (w[0], w[1]) == pair) {
+                    continue;
+                }
+                for old in symbols.windows(2).map(|w| (w[0], w[1])) {
+                    if let Some(c) = counts.get_mut(&old) {
+                        *c -= count;
+                    }
+                }
+                let mut out = Vec::with_capacity(symbols.len());
+                let mut j = 0;
+                while j < symbols.len() {
+                    if j + 1 < symbols.len() && (symbols[j], symbols[j + 1]) == pair {
+                        out.push(id);
+                        j += 2;
+                    } else {
+                        out.push(symbols[j]);
+                        j += 1;
+                    }
+                }
+                *symbols = out;
+                for new in symbols.windows(2).map(|w| (w[0], w[1])) {
+                    *counts.entry(new).or_default() += count;
+                    locations.entry(new).or_default().insert(i);
+                    touched.insert(new);
+                }
+            }
+            for new in touched {
+                if let Some(&count) = counts.get(&new).filter(|&&c| c > 0) {
+                    heap.push((count, Reverse(new)));
+                }
+            }
+        }
+        TrainedBpe { vocab, merges }
+    }
+}
@@ -0,0 +1,24 @@
+/// CJK ideographs, which BERT splits into single-character words.
+pub fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x4E00..=0x9FFF
+            | 0x3400..=0x4DBF
+            | 0x20000..=0x2A6DF
+            | 0x2A700..=0x2B73F
+            | 0x2B740..=0x2B81F
+            | 0x2B820..=0x2CEAF
+            | 0xF900..=0xFAFF
+            | 0x2F800..=0x2FA1F
+    )
+}
+
+/// Characters of scripts written without spaces between words: Han
+/// ideographs, Hiragana and Katakana.
+pub fn is_unspaced(c: char) -> bool {
+    is_cjk(c)
+        || matches!(
+            c as u32,
+            0x3040..=0x309F | 0x30A0..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F | 0x3005..=0x3007
+        )
+}
@@ -0,0 +1,91 @@
+use crate::cjk::is_unspaced;
+
+/// Tokens made only of these attach to the token before them.
+const CLOSING: &str = ".,!?;:%)]}…»”’";
+
+/// Tokens made only of these attach to the token after them.
+const OPENING: &str = "([{¿¡«“‘$£€¥#@";
+
+/// Contractions split off by word tokenizers, attached to the word before.
+const CLITICS: &[&str] = &[
+    "n't", "'s", "'re", "'ve", "'ll", "'d", "'m", "’s", "’re", "’ll",
+];
+
+/// Spaces that decoding word pieces leaves before punctuation and
+/// contractions, in the order HuggingFace `transformers` removes them.
+const CLEAN_UP: &[(&str, &str)] = &[
+    (" .", "."),
+    (" ?", "?"),
+    (" !", "!"),
+    (" ,", ","),
+    (" ' ", "'"),
+    (" n't", "n't"),
+    (" 'm", "'m"),
+    (" 's", "'s"),
+    (" 've", "'ve"),
+    (" 're", "'re"),
+];
+
+/// Decoded text cleaned up like `clean_up_tokenization_spaces=True` does in
+/// `transformers`, or left alone when `clean_up` is false.
+pub fn clean_up_tokenization(text: String, clean_up: bool) -> String {
+    if !clean_up {
+        return text;
+    }
+    CLEAN_UP
+        .iter()
+        .fold(text, |text, (from, to)| text.replace(from, to))
+}
+
+/// Characters of scripts and punctuation written without surrounding spaces.
+fn is_unspaced_boundary(c: char) -> bool {
+    is_unspaced(c) || matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+/// Join tokens into readable text: no space before closing punctuation and
+/// contractions, none after opening brackets and currency signs, straight
+/// quotes paired up, and no spaces around Chinese and Japanese characters or
+/// whitespace tokens.
+pub fn join_tokens<S: AsRef<str>>(tokens: &[S]) -> String {
+    let mut out = String::new();
+    // Whether a straight quote is open, so the next one closes it.
+    let mut double_open = false;
+    let mut single_open = false;
+    let mut glue_next = false;
+    for token in tokens {
+        let token = token.as_ref();
+        let (Some(first), Some(last)) = (token.chars().next(), token.chars().next_back()) else {
+            continue;
+        };
+        let (attach_left, attach_right) = match token {
+            "\"" => {
+                double_open = !double_open;
+                (!double_open, double_open)
+            }
+            "'" => {
+                single_open = !single_open;
+                (!single_open, single_open)
+            }
+            _ if token.chars().all(|c| CLOSING.contains(c)) => (true, false),
+            _ if token.chars().all(|c| OPENING.contains(c)) => (false, true),
+            _ => (CLITICS.iter().any(|c| token.eq_ignore_ascii_case(c)), false),
+        };
+        let space = match out.chars().next_back() {
+            None => false,
+            Some(prev) => {
+                !(glue_next
+                    || attach_left
+                    || prev.is_whitespace()
+                    || first.is_whitespace()
+                    || is_unspaced_boundary(prev)
+                    || is_unspaced_boundary(first))
+            }
+        };
+        if space {
+            out.push(' ');
+        }
+        out.push_str(token);
+        glue_next = attach_right || last.is_whitespace();
+    }
+    out
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// What can go wrong when building or running a tokenizer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A regular expression that does not compile, or compiles to more than
+    /// its size limit.
+    InvalidPattern(String),
+    /// An option outside the accepted values, such as an unknown mode name.
+    InvalidArgument(String),
+    /// Input or a token beyond one of the configured `Limits`.
+    LimitExceeded(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidPattern(error) => write!(f, "Invalid regex pattern: {}", error),
+            Error::InvalidArgument(message) | Error::LimitExceeded(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for String {
+    fn from(error: Error) -> String {
+        error.to_string()
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,34 @@
+//! Tokenization logic of FastTokenizer as a plain Rust library.
+//!
+//! The `fasttokenizer` Python extension is a thin wrapper around these
+//! types; Rust programs can use them directly, without Python:
+//!
+//! ```
+//! use fasttokenizer_core::{join_tokens, Tokenize, WhitespaceTokenizer};
+//!
+//! let tokens = WhitespaceTokenizer::default().tokenize("Hello ,  world");
+//! assert_eq!(tokens, ["Hello", ",", "world"]);
+//! assert_eq!(join_tokens(&tokens), "Hello, world");
+//! ```
+
+pub mod bpe_trainer;
+pub mod cjk;
+pub mod detokenizer;
+pub mod error;
+pub mod limits;
+pub mod normalizer;
+pub mod pretokenizer;
+pub mod regex_tokenizer;
+pub mod tiktoken;
+pub mod token;
+pub mod whitespace_tokenizer;
+
+pub use bpe_trainer::{BpeTrainer, TrainedBpe};
+pub use detokenizer::{clean_up_tokenization, join_tokens};
+pub use error::{Error, Result};
+pub use limits::Limits;
+pub use normalizer::Form;
+pub use regex_tokenizer::{Flags, Mode, RegexTokenizer};
+pub use tiktoken::TiktokenEncoder;
+pub use token::{Token, Tokenize};
+pub use whitespace_tokenizer::WhitespaceTokenizer;
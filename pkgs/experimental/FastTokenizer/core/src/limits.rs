@@ -0,0 +1,68 @@
+use crate::error::{Error, Result};
+use crate::token::Token;
+
+/// Caps on the work a single call may do, so that adversarial input fails
+/// with a clear error instead of growing memory without bound. `None` means
+/// unlimited, which is the default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Longest accepted input, in UTF-8 bytes.
+    pub max_input_length: Option<usize>,
+    /// Longest accepted token, in UTF-8 bytes.
+    pub max_token_length: Option<usize>,
+    /// Most pattern matches scanned in one input; only regex tokenizers
+    /// count them.
+    pub max_matches: Option<usize>,
+}
+
+impl Limits {
+    /// No limits at all, what the infallible tokenization paths use.
+    pub const NONE: Limits = Limits {
+        max_input_length: None,
+        max_token_length: None,
+        max_matches: None,
+    };
+
+    pub fn check_input(&self, text: &str) -> Result<()> {
+        match self.max_input_length {
+            Some(max) if text.len() > max => Err(Error::LimitExceeded(format!(
+                "Input of {} bytes exceeds max_input_length={}",
+                text.len(),
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a token of `len` bytes starting at byte `start`, before it is
+    /// copied out of the text.
+    pub fn check_token(&self, start: usize, len: usize) -> Result<()> {
+        match self.max_token_length {
+            Some(max) if len > max => Err(Error::LimitExceeded(format!(
+                "Token of {} bytes at byte {} exceeds max_token_length={}",
+                len, start, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_tokens(&self, tokens: &[Token]) -> Result<()> {
+        if self.max_token_length.is_some() {
+            for token in tokens {
+                self.check_token(token.start, token.text.len())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `count` matches are still within `max_matches`.
+    pub fn check_matches(&self, count: usize) -> Result<()> {
+        match self.max_matches {
+            Some(max) if count > max => Err(Error::LimitExceeded(format!(
+                "Input has more than max_matches={} pattern matches",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
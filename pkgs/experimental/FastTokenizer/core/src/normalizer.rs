@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+use unicode_normalization::{
+    is_nfc, is_nfc_quick, is_nfd, is_nfd_quick, is_nfkc, is_nfkc_quick, is_nfkd, is_nfkd_quick,
+    IsNormalized, UnicodeNormalization,
+};
+
+use crate::error::{Error, Result};
+
+/// A Unicode normalization form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Form {
+    #[default]
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl Form {
+    /// Parse `"NFC"`, `"NFD"`, `"NFKC"` or `"NFKD"`, in any case.
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "NFC" => Ok(Form::Nfc),
+            "NFD" => Ok(Form::Nfd),
+            "NFKC" => Ok(Form::Nfkc),
+            "NFKD" => Ok(Form::Nfkd),
+            _ => Err(Error::InvalidArgument(format!(
+                "Unknown normalization form '{}', expected 'NFC', 'NFD', 'NFKC' or 'NFKD'",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Form::Nfc => "NFC",
+            Form::Nfd => "NFD",
+            Form::Nfkc => "NFKC",
+            Form::Nfkd => "NFKD",
+        }
+    }
+
+    /// Whether `text` is already in this form.
+    pub fn is_normalized(self, text: &str) -> bool {
+        match self {
+            Form::Nfc => is_nfc(text),
+            Form::Nfd => is_nfd(text),
+            Form::Nfkc => is_nfkc(text),
+            Form::Nfkd => is_nfkd(text),
+        }
+    }
+
+    /// Normalize `text`, borrowing it when the quick check proves it is
+    /// already normalized.
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        let quick = match self {
+            Form::Nfc => is_nfc_quick(text.chars()),
+            Form::Nfd => is_nfd_quick(text.chars()),
+            Form::Nfkc => is_nfkc_quick(text.chars()),
+            Form::Nfkd => is_nfkd_quick(text.chars()),
+        };
+        if quick == IsNormalized::Yes {
+            return Cow::Borrowed(text);
+        }
+        Cow::Owned(match self {
+            Form::Nfc => text.nfc().collect(),
+            Form::Nfd => text.nfd().collect(),
+            Form::Nfkc => text.nfkc().collect(),
+            Form::Nfkd => text.nfkd().collect(),
+        })
+    }
+}
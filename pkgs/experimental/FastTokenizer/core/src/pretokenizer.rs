@@ -0,0 +1,71 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// GPT-2 pre-tokenization pattern. The trailing `(\s+)` group stands in for
+/// `\s+(?!\S)|\s+`, see `split_with_lookahead`.
+pub const GPT2_PATTERN: &str =
+    r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|(\s+)";
+
+fn gpt2_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(GPT2_PATTERN).expect("valid GPT-2 pattern"))
+}
+
+/// GPT-2's reversible mapping from bytes to printable unicode characters.
+pub fn bytes_to_unicode() -> &'static [char; 256] {
+    static TABLE: OnceLock<[char; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = ['\0'; 256];
+        let mut n = 0;
+        for b in 0..=255u8 {
+            let printable = matches!(b, b'!'..=b'~' | 0xA1..=0xAC | 0xAE..=0xFF);
+            table[b as usize] = if printable {
+                b as char
+            } else {
+                n += 1;
+                char::from_u32(255 + n).expect("valid code point")
+            };
+        }
+        table
+    })
+}
+
+/// Inverse of `bytes_to_unicode`.
+pub fn unicode_to_bytes() -> &'static HashMap<char, u8> {
+    static TABLE: OnceLock<HashMap<char, u8>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        bytes_to_unicode()
+            .iter()
+            .enumerate()
+            .map(|(b, &c)| (c, b as u8))
+            .collect()
+    })
+}
+
+/// Split text with an OpenAI-style pre-tokenization pattern, returning byte spans.
+///
+/// The `regex` crate has no lookahead, so such patterns end in a `(\s+)` group
+/// in place of `\s+(?!\S)|\s+`: when that group matches a whitespace run
+/// followed by more text, its last character is left for the next piece.
+pub fn split_with_lookahead(re: &Regex, text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while let Some(caps) = re.captures_at(text, pos) {
+        let m = caps.get(0).expect("group 0 always matches");
+        let mut end = m.end();
+        if let Some(ws) = caps.get(1).filter(|_| end < text.len()) {
+            if let Some((last, _)) = ws.as_str().char_indices().last().filter(|&(i, _)| i > 0) {
+                end = ws.start() + last;
+            }
+        }
+        spans.push((m.start(), end));
+        pos = end;
+    }
+    spans
+}
+
+/// Split text the way GPT-2's byte-level pre-tokenizer does, returning byte spans.
+pub fn byte_level_split(text: &str) -> Vec<(usize, usize)> {
+    split_with_lookahead(gpt2_regex(), text)
+}
@@ -0,0 +1,326 @@
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::error::{Error, Result};
+use crate::limits::Limits;
+use crate::token::{Token, Tokenize};
+
+/// How matches of the pattern become tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Every match is a token.
+    Find,
+    /// The pattern matches delimiters; the text between them is the tokens.
+    Split,
+    /// The named capture groups of every match are typed tokens.
+    Captures,
+}
+
+impl Mode {
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "find" => Ok(Mode::Find),
+            "split" => Ok(Mode::Split),
+            "captures" => Ok(Mode::Captures),
+            other => Err(Error::InvalidArgument(format!(
+                "Unknown mode '{}', expected 'find', 'split' or 'captures'",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Find => "find",
+            Mode::Split => "split",
+            Mode::Captures => "captures",
+        }
+    }
+}
+
+/// Default limit on the compiled size of a pattern, the one the `regex`
+/// crate uses.
+pub const DEFAULT_SIZE_LIMIT: usize = 10 * (1 << 20);
+
+/// Options every pattern of a tokenizer is compiled with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Flags {
+    pub case_insensitive: bool,
+    pub multi_line: bool,
+    pub dot_matches_new_line: bool,
+    pub unicode: bool,
+    pub ignore_whitespace: bool,
+    pub size_limit: usize,
+}
+
+impl Default for Flags {
+    fn default() -> Self {
+        Flags {
+            case_insensitive: false,
+            multi_line: false,
+            dot_matches_new_line: false,
+            unicode: true,
+            ignore_whitespace: false,
+            size_limit: DEFAULT_SIZE_LIMIT,
+        }
+    }
+}
+
+/// Compiled patterns shared by every tokenizer and filter, so constructing
+/// the same tokenizer again (say, once per web request) skips compilation.
+/// The cache is emptied when it reaches `REGEX_CACHE_CAPACITY` entries.
+fn regex_cache() -> &'static Mutex<HashMap<(String, Flags), Regex>> {
+    static CACHE: OnceLock<Mutex<HashMap<(String, Flags), Regex>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+const REGEX_CACHE_CAPACITY: usize = 512;
+
+/// Number of compiled patterns in the process-wide pattern cache.
+pub fn cache_size() -> usize {
+    regex_cache().lock().unwrap().len()
+}
+
+/// Empty the process-wide pattern cache. Existing tokenizers keep their
+/// compiled patterns.
+pub fn clear_cache() {
+    regex_cache().lock().unwrap().clear();
+}
+
+impl Flags {
+    /// Compile `pattern`, rejecting invalid ones and ones whose compiled
+    /// form exceeds the size limit. Compiled patterns are cached; cloning a
+    /// `Regex` only bumps a reference count.
+    pub fn compile(&self, pattern: &str) -> Result<Regex> {
+        let key = (pattern.to_string(), *self);
+        if let Some(regex) = regex_cache().lock().unwrap().get(&key) {
+            return Ok(regex.clone());
+        }
+        let regex = self.build(pattern)?;
+        let mut cache = regex_cache().lock().unwrap();
+        if cache.len() >= REGEX_CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(key, regex.clone());
+        Ok(regex)
+    }
+
+    fn build(&self, pattern: &str) -> Result<Regex> {
+        RegexBuilder::new(pattern)
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_new_line)
+            .unicode(self.unicode)
+            .ignore_whitespace(self.ignore_whitespace)
+            .size_limit(self.size_limit)
+            .build()
+            .map_err(|e| Error::InvalidPattern(e.to_string()))
+    }
+}
+
+/// Tokenizer extracting matches of one or more regular expressions, or the
+/// text between them.
+#[derive(Clone, Debug)]
+pub struct RegexTokenizer {
+    /// The regex patterns used for tokenization, in priority order, shared
+    /// between clones.
+    patterns: Arc<[Regex]>,
+    mode: Mode,
+    flags: Flags,
+    limits: Limits,
+}
+
+impl RegexTokenizer {
+    /// Compile `patterns`, tried in order with the first match at each
+    /// position winning.
+    pub fn new<S: AsRef<str>>(
+        patterns: &[S],
+        mode: Mode,
+        flags: Flags,
+        limits: Limits,
+    ) -> Result<Self> {
+        if patterns.is_empty() {
+            return Err(Error::InvalidArgument(
+                "At least one pattern is required".to_string(),
+            ));
+        }
+        let patterns = patterns
+            .iter()
+            .map(|pattern| flags.compile(pattern.as_ref()))
+            .collect::<Result<Arc<[Regex]>>>()?;
+        Ok(RegexTokenizer {
+            patterns,
+            mode,
+            flags,
+            limits,
+        })
+    }
+
+    /// The patterns in priority order.
+    pub fn patterns(&self) -> Vec<String> {
+        self.patterns
+            .iter()
+            .map(|p| p.as_str().to_string())
+            .collect()
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn flags(&self) -> Flags {
+        self.flags
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Non-overlapping matches as `(start, end, pattern index)`. At each
+    /// position the leftmost match wins, and among matches starting at the
+    /// same place the earliest pattern. Scanning stops once `max_matches`
+    /// is exceeded.
+    fn matches(&self, text: &str, limits: &Limits) -> Result<Vec<(usize, usize, usize)>> {
+        let mut found = Vec::new();
+        if let [pattern] = &self.patterns[..] {
+            for m in pattern.find_iter(text) {
+                limits.check_matches(found.len() + 1)?;
+                found.push((m.start(), m.end(), 0));
+            }
+            return Ok(found);
+        }
+        // The next match of each pattern at or after `pos`, found lazily.
+        let mut next: Vec<Option<(usize, usize)>> = vec![None; self.patterns.len()];
+        let mut pos = 0;
+        while pos <= text.len() {
+            let mut best: Option<(usize, usize, usize)> = None;
+            for (i, pattern) in self.patterns.iter().enumerate() {
+                if next[i].is_none_or(|(start, _)| start < pos) {
+                    next[i] = pattern.find_at(text, pos).map(|m| (m.start(), m.end()));
+                }
+                if let Some((start, end)) = next[i] {
+                    if best.is_none_or(|(best_start, _, _)| start < best_start) {
+                        best = Some((start, end, i));
+                    }
+                }
+            }
+            let Some((start, end, i)) = best else {
+                break;
+            };
+            limits.check_matches(found.len() + 1)?;
+            found.push((start, end, i));
+            pos = if end > start {
+                end
+            } else {
+                // Step over one character after an empty match.
+                end + text[end..].chars().next().map_or(1, char::len_utf8)
+            };
+        }
+        Ok(found)
+    }
+
+    /// Tokens of `text` with the capture group name of each in `captures`
+    /// mode, failing when `limits` are exceeded.
+    pub fn scan(&self, text: &str, limits: &Limits) -> Result<Vec<(Token, Option<String>)>> {
+        Ok(self
+            .scan_spans(text, limits)?
+            .into_iter()
+            .map(|(start, end, name)| {
+                let token = Token::new(&text[start..end], start, end);
+                (token, name.map(str::to_string))
+            })
+            .collect())
+    }
+
+    /// `scan` as `(start, end, capture group name)`, without building the
+    /// token strings.
+    pub fn scan_spans(
+        &self,
+        text: &str,
+        limits: &Limits,
+    ) -> Result<Vec<(usize, usize, Option<&str>)>> {
+        limits.check_input(text)?;
+        let matches = self.matches(text, limits)?;
+        Ok(match self.mode {
+            Mode::Find => {
+                let mut spans = Vec::with_capacity(matches.len());
+                for (start, end, _) in matches {
+                    limits.check_token(start, end - start)?;
+                    spans.push((start, end, None));
+                }
+                spans
+            }
+            Mode::Split => {
+                let mut spans = Vec::new();
+                let mut last = 0;
+                let bounds = matches.into_iter().map(|(start, end, _)| (start, end));
+                for (start, end) in bounds.chain(std::iter::once((text.len(), text.len()))) {
+                    if start > last {
+                        limits.check_token(last, start - last)?;
+                        spans.push((last, start, None));
+                    }
+                    last = last.max(end);
+                }
+                spans
+            }
+            Mode::Captures => {
+                let mut spans = Vec::new();
+                for (start, end, i) in matches {
+                    let pattern = &self.patterns[i];
+                    let caps = pattern
+                        .captures_at(text, start)
+                        .expect("the pattern matched here");
+                    let mut groups = Vec::new();
+                    for name in pattern.capture_names().flatten() {
+                        if let Some(m) = caps.name(name) {
+                            limits.check_token(m.start(), m.len())?;
+                            groups.push((m.start(), m.end(), Some(name)));
+                        }
+                    }
+                    if groups.is_empty() {
+                        limits.check_token(start, end - start)?;
+                        groups.push((start, end, None));
+                    }
+                    groups.sort_by_key(|&(start, _, _)| start);
+                    spans.extend(groups);
+                }
+                spans
+            }
+        })
+    }
+}
+
+impl Tokenize for RegexTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.scan(text, &Limits::NONE)
+            .expect("no limits to exceed")
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    fn try_tokenize_with_offsets(&self, text: &str) -> Result<Vec<Token>> {
+        Ok(self
+            .scan(text, &self.limits)?
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect())
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.scan_spans(text, &Limits::NONE)
+            .expect("no limits to exceed")
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+
+    fn try_token_spans(&self, text: &str) -> Result<Vec<(usize, usize)>> {
+        Ok(self
+            .scan_spans(text, &self.limits)?
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect())
+    }
+}
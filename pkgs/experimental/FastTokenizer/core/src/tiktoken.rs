@@ -0,0 +1,286 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{Error, Result};
+use crate::pretokenizer::{split_with_lookahead, GPT2_PATTERN};
+use crate::token::{Token, Tokenize};
+
+/// `cl100k_base` split pattern. Possessive quantifiers from the original are
+/// plain quantifiers here, which matches identically for this pattern.
+const CL100K_PATTERN: &str = r"'(?i:[sdmt]|ll|ve|re)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]|(\s+)";
+
+/// `o200k_base` split pattern.
+const O200K_PATTERN: &str = concat!(
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|(\s+)",
+);
+
+/// Split pattern and special tokens of a named tiktoken encoding.
+fn encoding_spec(name: &str) -> Option<(&'static str, Vec<(&'static str, u32)>)> {
+    let spec = match name {
+        "r50k_base" | "p50k_base" | "gpt2" => (GPT2_PATTERN, vec![("<|endoftext|>", 50256)]),
+        "cl100k_base" => (
+            CL100K_PATTERN,
+            vec![
+                ("<|endoftext|>", 100257),
+                ("<|fim_prefix|>", 100258),
+                ("<|fim_middle|>", 100259),
+                ("<|fim_suffix|>", 100260),
+                ("<|endofprompt|>", 100276),
+            ],
+        ),
+        "o200k_base" => (
+            O200K_PATTERN,
+            vec![("<|endoftext|>", 199999), ("<|endofprompt|>", 200018)],
+        ),
+        _ => return None,
+    };
+    Some(spec)
+}
+
+/// Parse the contents of a tiktoken `.tiktoken` ranks file
+/// (`<base64 token> <rank>` per line).
+pub fn parse_ranks(data: &str) -> Result<HashMap<Vec<u8>, u32>> {
+    let invalid = |line: &str| Error::InvalidArgument(format!("Invalid ranks line: {}", line));
+    data.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (token, rank) = line.split_once(' ').ok_or_else(|| invalid(line))?;
+            let token = BASE64.decode(token).map_err(|_| invalid(line))?;
+            let rank = rank.trim().parse().map_err(|_| invalid(line))?;
+            Ok((token, rank))
+        })
+        .collect()
+}
+
+/// Byte-pair merge over raw bytes using merge ranks, as tiktoken does.
+fn byte_pair_encode(piece: &[u8], ranks: &HashMap<Vec<u8>, u32>) -> Vec<(u32, usize, usize)> {
+    if let Some(&rank) = ranks.get(piece) {
+        return vec![(rank, 0, piece.len())];
+    }
+    // part boundaries; a merge removes the boundary between two parts
+    let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+    loop {
+        let best = (0..bounds.len().saturating_sub(2))
+            .filter_map(|i| {
+                ranks
+                    .get(&piece[bounds[i]..bounds[i + 2]])
+                    .map(|&rank| (rank, i))
+            })
+            .min();
+        match best {
+            Some((_, i)) => {
+                bounds.remove(i + 1);
+            }
+            None => break,
+        }
+    }
+    // every single byte has a rank in real encodings; anything else is dropped
+    bounds
+        .windows(2)
+        .filter_map(|w| {
+            ranks
+                .get(&piece[w[0]..w[1]])
+                .map(|&rank| (rank, w[0], w[1]))
+        })
+        .collect()
+}
+
+/// Byte-level BPE encoder producing the same ids as OpenAI's tiktoken.
+#[derive(Debug)]
+pub struct TiktokenEncoder {
+    ranks: HashMap<Vec<u8>, u32>,
+    decoder: HashMap<u32, Vec<u8>>,
+    pattern: Regex,
+    special_tokens: HashMap<String, u32>,
+    special_decoder: HashMap<u32, String>,
+    special_pattern: Option<Regex>,
+}
+
+impl TiktokenEncoder {
+    /// Create an encoder from merge ranks, a split pattern and special
+    /// tokens. A final `(\s+)` group in the pattern stands in for
+    /// tiktoken's `\s+(?!\S)|\s+`.
+    pub fn new(
+        ranks: HashMap<Vec<u8>, u32>,
+        pattern: &str,
+        special_tokens: HashMap<String, u32>,
+    ) -> Result<Self> {
+        let pattern = Regex::new(pattern).map_err(|e| Error::InvalidPattern(e.to_string()))?;
+        let decoder = ranks.iter().map(|(k, &v)| (v, k.clone())).collect();
+        let special_decoder = special_tokens
+            .iter()
+            .map(|(k, &v)| (v, k.clone()))
+            .collect();
+        let special_pattern = if special_tokens.is_empty() {
+            None
+        } else {
+            let alternatives: Vec<String> =
+                special_tokens.keys().map(|s| regex::escape(s)).collect();
+            Some(
+                Regex::new(&alternatives.join("|"))
+                    .map_err(|e| Error::InvalidPattern(e.to_string()))?,
+            )
+        };
+        Ok(TiktokenEncoder {
+            ranks,
+            decoder,
+            pattern,
+            special_tokens,
+            special_decoder,
+            special_pattern,
+        })
+    }
+
+    /// One of the standard encodings (`cl100k_base`, `o200k_base`,
+    /// `p50k_base` or `r50k_base`) with already loaded merge ranks.
+    pub fn from_ranks(ranks: HashMap<Vec<u8>, u32>, encoding: &str) -> Result<Self> {
+        let (pattern, special) = encoding_spec(encoding)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown encoding: {}", encoding)))?;
+        let special = special
+            .into_iter()
+            .map(|(token, id)| (token.to_string(), id))
+            .collect();
+        Self::new(ranks, pattern, special)
+    }
+
+    /// Ids of `text` without special-token handling, with the byte span of
+    /// each.
+    pub fn encode_ordinary_with_offsets(&self, text: &str) -> Vec<(u32, usize, usize)> {
+        let mut out = Vec::new();
+        self.encode_ordinary_into(text, 0, &mut out);
+        out
+    }
+
+    fn encode_ordinary_into(&self, text: &str, base: usize, out: &mut Vec<(u32, usize, usize)>) {
+        for (start, end) in split_with_lookahead(&self.pattern, text) {
+            let piece = &text.as_bytes()[start..end];
+            out.extend(
+                byte_pair_encode(piece, &self.ranks)
+                    .into_iter()
+                    .map(|(id, s, e)| (id, base + start + s, base + start + e)),
+            );
+        }
+    }
+
+    /// Ids of `text`, treating special tokens as ordinary text.
+    pub fn encode_ordinary(&self, text: &str) -> Vec<u32> {
+        self.encode_ordinary_with_offsets(text)
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect()
+    }
+
+    /// Ids of `text` like `tiktoken.Encoding.encode`: special tokens in
+    /// `allowed` become single tokens, and text containing one that is in
+    /// `disallowed` but not `allowed` is an error.
+    pub fn encode(
+        &self,
+        text: &str,
+        allowed: &HashSet<String>,
+        disallowed: &HashSet<String>,
+    ) -> Result<Vec<u32>> {
+        if let Some(found) = disallowed
+            .iter()
+            .find(|token| !allowed.contains(*token) && text.contains(token.as_str()))
+        {
+            return Err(Error::InvalidArgument(format!(
+                "Encountered text corresponding to disallowed special token '{}'",
+                found
+            )));
+        }
+        let mut out = Vec::new();
+        let mut pos = 0;
+        if let Some(special) = self
+            .special_pattern
+            .as_ref()
+            .filter(|_| !allowed.is_empty())
+        {
+            let mut search = 0;
+            while let Some(m) = special.find_at(text, search) {
+                if !allowed.contains(m.as_str()) {
+                    search = m.start() + 1;
+                    continue;
+                }
+                self.encode_ordinary_into(&text[pos..m.start()], pos, &mut out);
+                out.push((self.special_tokens[m.as_str()], m.start(), m.end()));
+                pos = m.end();
+                search = pos;
+            }
+        }
+        self.encode_ordinary_into(&text[pos..], pos, &mut out);
+        Ok(out.into_iter().map(|(id, _, _)| id).collect())
+    }
+
+    /// Number of tokens in `text`, counted like `encode_ordinary`.
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.encode_ordinary_with_offsets(text).len()
+    }
+
+    /// The bytes of one ordinary or special token.
+    pub fn token_bytes(&self, id: u32) -> Option<&[u8]> {
+        self.decoder
+            .get(&id)
+            .map(Vec::as_slice)
+            .or_else(|| self.special_decoder.get(&id).map(String::as_bytes))
+    }
+
+    /// Decode ids into bytes, failing on an unknown id.
+    pub fn decode_bytes(&self, ids: &[u32]) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        for &id in ids {
+            let token = self
+                .token_bytes(id)
+                .ok_or_else(|| Error::InvalidArgument(format!("Invalid token id: {}", id)))?;
+            bytes.extend_from_slice(token);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode ids into text, replacing invalid UTF-8 with U+FFFD like
+    /// tiktoken does.
+    pub fn decode(&self, ids: &[u32]) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.decode_bytes(ids)?).into_owned())
+    }
+
+    /// Number of ordinary plus special tokens.
+    pub fn n_vocab(&self) -> usize {
+        self.ranks.len() + self.special_tokens.len()
+    }
+
+    /// Token bytes to rank, as passed to `new`.
+    pub fn ranks(&self) -> &HashMap<Vec<u8>, u32> {
+        &self.ranks
+    }
+
+    /// The split pattern, as passed to `new`.
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    pub fn special_tokens(&self) -> &HashMap<String, u32> {
+        &self.special_tokens
+    }
+}
+
+impl Tokenize for TiktokenEncoder {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.encode_ordinary_with_offsets(text)
+            .into_iter()
+            .map(|(id, start, end)| {
+                let bytes = self.token_bytes(id).unwrap_or_default();
+                Token::new(String::from_utf8_lossy(bytes), start, end)
+            })
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.encode_ordinary_with_offsets(text)
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::error::Result;
+
+/// A single token together with its UTF-8 byte span in the source text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn new(text: impl Into<String>, start: usize, end: usize) -> Self {
+        Token {
+            text: text.into(),
+            start,
+            end,
+        }
+    }
+}
+
+/// Splitting text into tokens.
+///
+/// Implementors only need to provide `tokenize_with_offsets`; offsets are
+/// byte offsets into the text that was passed in.
+pub trait Tokenize {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token>;
+
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        self.tokenize_with_offsets(text)
+            .into_iter()
+            .map(|t| t.text)
+            .collect()
+    }
+
+    /// `tokenize_with_offsets`, failing instead when the text or its tokens
+    /// exceed the tokenizer's `Limits`. The infallible methods ignore the
+    /// limits; tokenizers without limits never fail.
+    fn try_tokenize_with_offsets(&self, text: &str) -> Result<Vec<Token>> {
+        Ok(self.tokenize_with_offsets(text))
+    }
+
+    /// `tokenize`, failing like `try_tokenize_with_offsets`.
+    fn try_tokenize(&self, text: &str) -> Result<Vec<String>> {
+        Ok(self
+            .try_tokenize_with_offsets(text)?
+            .into_iter()
+            .map(|t| t.text)
+            .collect())
+    }
+
+    /// The `(start, end)` spans of `tokenize_with_offsets` alone.
+    /// Tokenizers whose tokens are slices of the text override it to skip
+    /// building the token strings.
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.tokenize_with_offsets(text)
+            .into_iter()
+            .map(|t| (t.start, t.end))
+            .collect()
+    }
+
+    /// `token_spans`, failing like `try_tokenize_with_offsets`.
+    fn try_token_spans(&self, text: &str) -> Result<Vec<(usize, usize)>> {
+        Ok(self
+            .try_tokenize_with_offsets(text)?
+            .into_iter()
+            .map(|t| (t.start, t.end))
+            .collect())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::token::{Token, Tokenize};
+
+/// Splits text on Unicode whitespace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WhitespaceTokenizer {
+    /// Emit the whitespace between words as tokens too.
+    pub preserve_delimiters: bool,
+    /// Emit each line break as a `"\n"`-like token of its own.
+    pub keep_newlines: bool,
+    /// Stop splitting after this many words; the rest is one token.
+    pub max_splits: Option<usize>,
+}
+
+impl WhitespaceTokenizer {
+    fn is_plain(&self) -> bool {
+        !self.preserve_delimiters && !self.keep_newlines && self.max_splits.is_none()
+    }
+
+    /// Emit the whitespace run `text[start..end]` as delimiter and line break
+    /// spans, as the options ask for.
+    fn push_whitespace(
+        &self,
+        text: &str,
+        start: usize,
+        end: usize,
+        spans: &mut Vec<(usize, usize)>,
+    ) {
+        if !self.keep_newlines {
+            if self.preserve_delimiters {
+                spans.push((start, end));
+            }
+            return;
+        }
+        let mut pos = start;
+        while pos < end {
+            let rest = &text[pos..end];
+            let line_break = if rest.starts_with("\r\n") {
+                Some(2)
+            } else if rest.starts_with(['\n', '\r']) {
+                Some(1)
+            } else {
+                None
+            };
+            let len = line_break.unwrap_or_else(|| rest.find(['\n', '\r']).unwrap_or(rest.len()));
+            if line_break.is_some() || self.preserve_delimiters {
+                spans.push((pos, pos + len));
+            }
+            pos += len;
+        }
+    }
+}
+
+impl Tokenize for WhitespaceTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.token_spans(text)
+            .into_iter()
+            .map(|(start, end)| Token::new(&text[start..end], start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        let mut words = 0;
+        // Start of the current word, or of the current whitespace run.
+        let mut word_start = None;
+        let mut space_start = None;
+        for (i, c) in text.char_indices() {
+            match (c.is_whitespace(), word_start, space_start) {
+                (true, Some(s), _) => {
+                    spans.push((s, i));
+                    word_start = None;
+                    space_start = Some(i);
+                }
+                (true, None, None) => space_start = Some(i),
+                (false, None, _) => {
+                    if let Some(s) = space_start.take() {
+                        self.push_whitespace(text, s, i, &mut spans);
+                    }
+                    if self.max_splits == Some(words) {
+                        spans.push((i, text.len()));
+                        return spans;
+                    }
+                    words += 1;
+                    word_start = Some(i);
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = word_start {
+            spans.push((s, text.len()));
+        }
+        if let Some(s) = space_start {
+            self.push_whitespace(text, s, text.len(), &mut spans);
+        }
+        spans
+    }
+
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        if !self.is_plain() {
+            return self
+                .tokenize_with_offsets(text)
+                .into_iter()
+                .map(|t| t.text)
+                .collect();
+        }
+        text.split_whitespace().map(|s| s.to_string()).collect()
+    }
+}
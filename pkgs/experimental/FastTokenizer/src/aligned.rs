@@ -0,0 +1,346 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A part of a text replaced as a whole, untouched by the steps that
+/// transform the rest.
+#[derive(Clone, Debug)]
+pub struct Replacement {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    /// Set the replacement, or the words either side of an empty one, apart
+    /// from the neighbouring text with a space.
+    pub apart: bool,
+}
+
+/// The items of two sorted lists of non-overlapping spans, sorted, leaving
+/// out those of `second` that overlap one of `first`.
+pub fn merge_disjoint<T>(
+    first: Vec<T>,
+    second: Vec<T>,
+    span: impl Fn(&T) -> (usize, usize),
+) -> Vec<T> {
+    let mut merged = Vec::with_capacity(first.len() + second.len());
+    let mut first = first.into_iter().peekable();
+    for item in second {
+        let (start, end) = span(&item);
+        while let Some(next) = first.next_if(|f| span(f).1 <= start) {
+            merged.push(next);
+        }
+        if first.peek().is_none_or(|f| end <= span(f).0) {
+            merged.push(item);
+        }
+    }
+    merged.extend(first);
+    merged
+}
+
+/// Push a space onto `out` unless it is empty or already ends in whitespace.
+fn separate(out: &mut AlignedString, at: usize) {
+    if out
+        .text
+        .chars()
+        .next_back()
+        .is_some_and(|c| !c.is_whitespace())
+    {
+        out.push(' ', (at, at));
+    }
+}
+
+/// A transformed string that remembers, for each of its bytes, the byte span
+/// of the original text it was produced from.
+#[derive(Clone, Debug, Default)]
+pub struct AlignedString {
+    pub text: String,
+    pub spans: Vec<(usize, usize)>,
+}
+
+impl AlignedString {
+    /// Wrap `text`, which starts at byte `base` of the original text.
+    pub fn new(text: &str, base: usize) -> Self {
+        let mut aligned = AlignedString {
+            text: String::with_capacity(text.len()),
+            spans: Vec::with_capacity(text.len()),
+        };
+        for (i, c) in text.char_indices() {
+            aligned.push(c, (base + i, base + i + c.len_utf8()));
+        }
+        aligned
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn push(&mut self, c: char, span: (usize, usize)) {
+        self.text.push(c);
+        self.spans.resize(self.text.len(), span);
+    }
+
+    pub fn push_str(&mut self, s: &str, span: (usize, usize)) {
+        self.text.push_str(s);
+        self.spans.resize(self.text.len(), span);
+    }
+
+    /// Characters with the original span each one maps to.
+    pub fn chars(&self) -> impl Iterator<Item = (usize, char, (usize, usize))> + '_ {
+        self.text
+            .char_indices()
+            .map(move |(i, c)| (i, c, self.spans[i]))
+    }
+
+    /// Original span covered by bytes `start..end` of the transformed text.
+    pub fn span(&self, start: usize, end: usize) -> (usize, usize) {
+        if start >= end {
+            let at = self
+                .spans
+                .get(start)
+                .or(self.spans.last())
+                .map_or(0, |s| s.0);
+            return (at, at);
+        }
+        (self.spans[start].0, self.spans[end - 1].1)
+    }
+
+    /// The part of the string at bytes `start..end`.
+    pub fn slice(&self, start: usize, end: usize) -> AlignedString {
+        AlignedString {
+            text: self.text[start..end].to_string(),
+            spans: self.spans[start..end].to_vec(),
+        }
+    }
+
+    /// Rewrite every character; the output of `f` inherits that character's span.
+    pub fn map_chars(&self, mut f: impl FnMut(char, &mut String)) -> AlignedString {
+        let mut out = AlignedString::default();
+        let mut buf = String::new();
+        for (_, c, span) in self.chars() {
+            buf.clear();
+            f(c, &mut buf);
+            out.push_str(&buf, span);
+        }
+        out
+    }
+
+    /// Rewrite every grapheme cluster; the output covers the whole cluster.
+    pub fn map_graphemes(&self, mut f: impl FnMut(&str, &mut String)) -> AlignedString {
+        let mut out = AlignedString::default();
+        let mut buf = String::new();
+        for (i, grapheme) in self.text.grapheme_indices(true) {
+            buf.clear();
+            f(grapheme, &mut buf);
+            out.push_str(&buf, self.span(i, i + grapheme.len()));
+        }
+        out
+    }
+
+    pub fn append(&mut self, other: AlignedString) {
+        self.text.push_str(&other.text);
+        self.spans.extend(other.spans);
+    }
+
+    /// Apply `replacements`, sorted and not overlapping, passing the text
+    /// between them through `rest`.
+    pub fn replace(
+        &self,
+        replacements: &[Replacement],
+        mut rest: impl FnMut(AlignedString) -> AlignedString,
+    ) -> AlignedString {
+        if replacements.is_empty() {
+            return rest(self.clone());
+        }
+        let mut out = AlignedString::default();
+        let mut last = 0;
+        let mut apart = false;
+        for replacement in replacements {
+            let piece = rest(self.slice(last, replacement.start));
+            if apart && piece.text.starts_with(|c: char| !c.is_whitespace()) {
+                separate(&mut out, self.span(last, last).0);
+            }
+            out.append(piece);
+            let span = self.span(replacement.start, replacement.end);
+            if replacement.apart && !replacement.text.is_empty() {
+                separate(&mut out, span.0);
+            }
+            out.push_str(&replacement.text, span);
+            apart = replacement.apart;
+            last = replacement.end;
+        }
+        let piece = rest(self.slice(last, self.text.len()));
+        if apart && piece.text.starts_with(|c: char| !c.is_whitespace()) {
+            separate(&mut out, self.span(last, last).0);
+        }
+        out.append(piece);
+        out
+    }
+
+    /// Insert `s` at the front, mapped to an empty span where the text starts.
+    pub fn prepend(&mut self, s: &str) {
+        let at = self.span(0, 0);
+        self.text.insert_str(0, s);
+        self.spans.splice(0..0, std::iter::repeat_n(at, s.len()));
+    }
+}
+
+/// Normalized text together with where each of its characters came from in
+/// the original input, as returned by `Normalizer.normalize_with_alignment`.
+///
+/// Offsets found in the normalized text, e.g. by tokenizing it, can be
+/// mapped back to the original text for highlighting or redaction.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct Alignment {
+    original: String,
+    aligned: AlignedString,
+    /// Byte offset of every normalized character, plus the text length.
+    char_bytes: Vec<usize>,
+    /// Character index of every original byte, plus the character count.
+    original_chars: Vec<usize>,
+}
+
+impl Alignment {
+    pub fn new(original: &str, aligned: AlignedString) -> Self {
+        let char_bytes = aligned
+            .text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([aligned.text.len()])
+            .collect();
+        let mut original_chars = Vec::with_capacity(original.len() + 1);
+        for (i, c) in original.chars().enumerate() {
+            original_chars.extend(std::iter::repeat_n(i, c.len_utf8()));
+        }
+        original_chars.push(original.chars().count());
+        Alignment {
+            original: original.to_string(),
+            aligned,
+            char_bytes,
+            original_chars,
+        }
+    }
+
+    /// Original span of the normalized span `start..end` in `unit`.
+    fn map(&self, start: usize, end: usize, unit: Unit) -> PyResult<(usize, usize)> {
+        let (start, end) = match unit {
+            Unit::Byte => {
+                let text = &self.aligned.text;
+                if start > end || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                    return Err(out_of_range(start, end, text.len(), "bytes"));
+                }
+                (start, end)
+            }
+            Unit::Char => match (self.char_bytes.get(start), self.char_bytes.get(end)) {
+                (Some(&start), Some(&end)) if start <= end => (start, end),
+                _ => {
+                    return Err(out_of_range(
+                        start,
+                        end,
+                        self.char_bytes.len() - 1,
+                        "characters",
+                    ))
+                }
+            },
+        };
+        Ok(self.in_unit(self.aligned.span(start, end), unit))
+    }
+
+    /// An original byte span in `unit`.
+    fn in_unit(&self, (start, end): (usize, usize), unit: Unit) -> (usize, usize) {
+        match unit {
+            Unit::Byte => (start, end),
+            Unit::Char => (self.original_chars[start], self.original_chars[end]),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Char,
+    Byte,
+}
+
+impl Unit {
+    fn parse(unit: &str) -> PyResult<Self> {
+        match unit {
+            "char" => Ok(Unit::Char),
+            "byte" => Ok(Unit::Byte),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown offset unit '{}', expected 'char' or 'byte'",
+                unit
+            ))),
+        }
+    }
+}
+
+fn out_of_range(start: usize, end: usize, len: usize, unit: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "Span ({}, {}) is not a valid span of the {} normalized {}",
+        start, end, len, unit
+    ))
+}
+
+#[pymethods]
+impl Alignment {
+    /// The text before normalization.
+    #[getter]
+    fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The normalized text.
+    #[getter]
+    fn normalized(&self) -> &str {
+        &self.aligned.text
+    }
+
+    /// Map the span `start..end` of the normalized text to the span of the
+    /// original text it was produced from.
+    ///
+    /// Args:
+    ///     start (int): Start of the span in the normalized text.
+    ///     end (int): End of the span in the normalized text.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded texts.
+    ///
+    /// Returns:
+    ///     Tuple[int, int]: The span in the original text.
+    #[pyo3(signature = (start, end, unit="char"))]
+    fn to_original(&self, start: usize, end: usize, unit: &str) -> PyResult<(usize, usize)> {
+        self.map(start, end, Unit::parse(unit)?)
+    }
+
+    /// Map `(token, start, end)` tuples, such as those returned by
+    /// `tokenize_with_offsets` over the normalized text, to offsets into the
+    /// original text.
+    #[pyo3(signature = (offsets, unit="char"))]
+    fn map_offsets(
+        &self,
+        offsets: Vec<(String, usize, usize)>,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        let unit = Unit::parse(unit)?;
+        offsets
+            .into_iter()
+            .map(|(token, start, end)| {
+                let (start, end) = self.map(start, end, unit)?;
+                Ok((token, start, end))
+            })
+            .collect()
+    }
+
+    /// The original span of every character of the normalized text.
+    #[pyo3(signature = (unit="char"))]
+    fn spans(&self, unit: &str) -> PyResult<Vec<(usize, usize)>> {
+        let unit = Unit::parse(unit)?;
+        Ok(self
+            .char_bytes
+            .windows(2)
+            .map(|w| self.in_unit(self.aligned.span(w[0], w[1]), unit))
+            .collect())
+    }
+
+    fn __len__(&self) -> usize {
+        self.char_bytes.len() - 1
+    }
+}
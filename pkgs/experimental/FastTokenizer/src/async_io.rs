@@ -0,0 +1,164 @@
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::tokenizer_io::{open_reader, RecordIterator};
+use crate::utf8::Utf8Errors;
+
+/// Lines read per trip to the I/O threads by `AsyncLineIterator`.
+const LINES_PER_READ: usize = 1024;
+
+/// Threads doing file reads for the async methods, kept apart from the
+/// tokenization pools so slow disks do not hold up CPU work.
+fn io_pool() -> &'static rayon::ThreadPool {
+    static POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .thread_name(|i| format!("fasttokenizer-io-{}", i))
+            .build()
+            .expect("failed to start the I/O threads")
+    })
+}
+
+/// Set the result of `future` on its event loop, unless it was cancelled
+/// while the work ran.
+#[pyfunction]
+fn complete(future: &Bound<'_, PyAny>, value: &Bound<'_, PyAny>, failed: bool) -> PyResult<()> {
+    if future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    let method = if failed {
+        "set_exception"
+    } else {
+        "set_result"
+    };
+    future.call_method1(method, (value,))?;
+    Ok(())
+}
+
+/// Run `work` on the I/O threads, returning an asyncio future of the
+/// running event loop that completes with its result.
+pub fn spawn<'py, T, F>(py: Python<'py>, work: F) -> PyResult<Bound<'py, PyAny>>
+where
+    T: for<'a> IntoPyObject<'a> + Send + 'static,
+    F: FnOnce() -> PyResult<T> + Send + 'static,
+{
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let (event_loop, target) = (event_loop.unbind(), future.clone().unbind());
+    io_pool().spawn(move || {
+        let result = work();
+        Python::attach(|py| {
+            let (value, failed) = match result.and_then(|value| value.into_py_any(py)) {
+                Ok(value) => (value, false),
+                Err(e) => (e.into_value(py).into_any(), true),
+            };
+            // A closed loop has nobody left waiting for the result.
+            let _ = wrap_pyfunction!(complete, py).and_then(|complete| {
+                event_loop.call_method1(
+                    py,
+                    "call_soon_threadsafe",
+                    (complete, target, value, failed),
+                )
+            });
+        });
+    });
+    Ok(future)
+}
+
+/// Where an `AsyncLineIterator` is in its file.
+struct LineState {
+    path: String,
+    compression: String,
+    errors: Utf8Errors,
+    /// Opened on the first read, on the I/O threads.
+    records: Option<RecordIterator>,
+    /// Lines read ahead and not yet returned.
+    lines: VecDeque<String>,
+    done: bool,
+}
+
+impl LineState {
+    /// The next line, reading the next batch when none are left.
+    fn next_line(&mut self) -> PyResult<Option<String>> {
+        if self.lines.is_empty() && !self.done {
+            let records = match &mut self.records {
+                Some(records) => records,
+                None => self.records.insert(RecordIterator::new(
+                    open_reader(&self.path, &self.compression)?,
+                    b"\n",
+                    true,
+                    self.errors,
+                )),
+            };
+            while self.lines.len() < LINES_PER_READ {
+                match records.next_text()? {
+                    Some(line) => self.lines.push_back(line),
+                    None => {
+                        self.done = true;
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(self.lines.pop_front())
+    }
+}
+
+/// Asynchronous iterator over the lines of a file, returned by
+/// `TokenizerIO.aiter_lines`.
+///
+/// Lines are read in batches on background threads, so `async for` never
+/// blocks the event loop on the disk or on decompression. Iterate from one
+/// task at a time.
+#[pyclass(frozen)]
+pub struct AsyncLineIterator {
+    state: Arc<Mutex<LineState>>,
+}
+
+impl AsyncLineIterator {
+    pub fn new(path: &str, compression: &str, errors: Utf8Errors) -> Self {
+        AsyncLineIterator {
+            state: Arc::new(Mutex::new(LineState {
+                path: path.to_string(),
+                compression: compression.to_string(),
+                errors,
+                records: None,
+                lines: VecDeque::new(),
+                done: false,
+            })),
+        }
+    }
+}
+
+#[pymethods]
+impl AsyncLineIterator {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if let Ok(mut state) = self.state.try_lock() {
+            if let Some(line) = state.lines.pop_front() {
+                let future = py
+                    .import("asyncio")?
+                    .call_method0("get_running_loop")?
+                    .call_method0("create_future")?;
+                future.call_method1("set_result", (line,))?;
+                return Ok(Some(future));
+            }
+            if state.done {
+                return Ok(None);
+            }
+        }
+        let state = Arc::clone(&self.state);
+        let future = spawn(py, move || match state.lock().unwrap().next_line()? {
+            Some(line) => Ok(line),
+            None => Err(PyStopAsyncIteration::new_err(())),
+        })?;
+        Ok(Some(future))
+    }
+}
@@ -0,0 +1,253 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::random::{seed_or_default, Rng};
+use crate::tokenizer::{extract_tokenizer, Tokenizer};
+
+fn check_probability(name: &str, p: f64) -> PyResult<f64> {
+    if !(0.0..=1.0).contains(&p) {
+        return Err(PyValueError::new_err(format!(
+            "{} must be between 0 and 1, got {}",
+            name, p
+        )));
+    }
+    Ok(p)
+}
+
+/// Seeded random augmentation of token sequences for training data.
+///
+/// Each call applies, in order: synonym substitution from a user
+/// dictionary, random token deletion, random swaps and span masking, each
+/// with its own probability (0 turns it off). The same input and seed
+/// always give the same output; item `i` of a batch is augmented as a
+/// single call with `seed + i` would, whichever thread handles it. Pass a
+/// new seed each epoch for fresh augmentations.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct Augmenter {
+    tokenizer: Option<Arc<dyn Tokenizer>>,
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+    substitute: f64,
+    delete: f64,
+    swap: f64,
+    mask: f64,
+    mask_token: String,
+    max_span: usize,
+    seed: u64,
+}
+
+impl Augmenter {
+    fn apply(&self, tokens: &[String], seed: u64) -> Vec<String> {
+        let mut rng = Rng::new(seed);
+        let mut tokens: Vec<String> = tokens
+            .iter()
+            .map(|token| match self.synonyms.get(token) {
+                Some(choices) if !choices.is_empty() && rng.next_f64() < self.substitute => {
+                    choices[rng.below(choices.len() as u64) as usize].clone()
+                }
+                _ => token.clone(),
+            })
+            .collect();
+        if self.delete > 0.0 && !tokens.is_empty() {
+            let keep: Vec<bool> = tokens
+                .iter()
+                .map(|_| rng.next_f64() >= self.delete)
+                .collect();
+            // Never delete everything: keep one token at random instead.
+            let survivor = if keep.contains(&true) {
+                None
+            } else {
+                Some(rng.below(tokens.len() as u64) as usize)
+            };
+            let mut index = 0;
+            tokens.retain(|_| {
+                index += 1;
+                keep[index - 1] || survivor == Some(index - 1)
+            });
+        }
+        if self.swap > 0.0 && tokens.len() > 1 {
+            for i in 0..tokens.len() {
+                if rng.next_f64() < self.swap {
+                    let j = rng.below(tokens.len() as u64) as usize;
+                    tokens.swap(i, j);
+                }
+            }
+        }
+        if self.mask > 0.0 {
+            let mut i = 0;
+            while i < tokens.len() {
+                if rng.next_f64() < self.mask {
+                    let span = 1 + rng.below(self.max_span as u64) as usize;
+                    for token in tokens.iter_mut().skip(i).take(span) {
+                        token.clone_from(&self.mask_token);
+                    }
+                    i += span;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        tokens
+    }
+
+    fn tokenizer(&self) -> PyResult<&dyn Tokenizer> {
+        self.tokenizer.as_deref().ok_or_else(|| {
+            PyValueError::new_err("Augmenting text needs an Augmenter created with a tokenizer")
+        })
+    }
+}
+
+#[pymethods]
+impl Augmenter {
+    /// Create an augmenter.
+    ///
+    /// Args:
+    ///     tokenizer (optional): Any FastTokenizer tokenizer or pipeline,
+    ///         needed only to
+    ///         augment raw text.
+    ///     synonyms (Dict[str, List[str]], optional): Replacements for
+    ///         tokens, looked up exactly.
+    ///     substitute (float): Chance of replacing a token that has
+    ///         synonyms with one of them.
+    ///     delete (float): Chance of deleting each token; at least one
+    ///         token is always kept.
+    ///     swap (float): Chance of swapping each token with another token at
+    ///         a random position.
+    ///     mask (float): Chance of starting a masked span at each token.
+    ///     mask_token (str): What masked tokens become.
+    ///     max_span (int): Longest masked span; lengths are uniform from 1
+    ///         to `max_span`.
+    ///     seed (int, optional): Default seed for calls that do not pass
+    ///         one; 0, or the next `set_global_seed` seed, when not given.
+    #[new]
+    #[pyo3(signature = (
+        tokenizer=None,
+        synonyms=None,
+        substitute=0.0,
+        delete=0.0,
+        swap=0.0,
+        mask=0.0,
+        mask_token="[MASK]",
+        max_span=1,
+        seed=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        tokenizer: Option<&Bound<'_, PyAny>>,
+        synonyms: Option<HashMap<String, Vec<String>>>,
+        substitute: f64,
+        delete: f64,
+        swap: f64,
+        mask: f64,
+        mask_token: &str,
+        max_span: usize,
+        seed: Option<u64>,
+    ) -> PyResult<Self> {
+        if max_span == 0 {
+            return Err(PyValueError::new_err("max_span must be at least 1"));
+        }
+        Ok(Augmenter {
+            tokenizer: tokenizer.map(extract_tokenizer).transpose()?,
+            synonyms: Arc::new(synonyms.unwrap_or_default()),
+            substitute: check_probability("substitute", substitute)?,
+            delete: check_probability("delete", delete)?,
+            swap: check_probability("swap", swap)?,
+            mask: check_probability("mask", mask)?,
+            mask_token: mask_token.to_string(),
+            max_span,
+            seed: seed_or_default(seed),
+        })
+    }
+
+    /// Constructor arguments recreating this augmenter, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        let tokenizer = self
+            .tokenizer
+            .as_ref()
+            .map(|t| t.to_object(py))
+            .transpose()?;
+        kwargs.set_item("tokenizer", tokenizer)?;
+        kwargs.set_item("synonyms", &*self.synonyms)?;
+        kwargs.set_item("substitute", self.substitute)?;
+        kwargs.set_item("delete", self.delete)?;
+        kwargs.set_item("swap", self.swap)?;
+        kwargs.set_item("mask", self.mask)?;
+        kwargs.set_item("mask_token", &self.mask_token)?;
+        kwargs.set_item("max_span", self.max_span)?;
+        kwargs.set_item("seed", self.seed)?;
+        Ok(((), kwargs))
+    }
+
+    /// Augment one token sequence.
+    ///
+    /// Args:
+    ///     tokens (List[str]): Tokens to augment.
+    ///     seed (int, optional): Overrides the augmenter's seed.
+    #[pyo3(signature = (tokens, seed=None))]
+    fn augment(&self, tokens: Vec<String>, seed: Option<u64>) -> Vec<String> {
+        self.apply(&tokens, seed.unwrap_or(self.seed))
+    }
+
+    /// `augment` for many sequences at once, in parallel without the GIL.
+    /// Sequence `i` is augmented with `seed + i`.
+    #[pyo3(signature = (batch, seed=None))]
+    fn augment_batch(
+        &self,
+        py: Python<'_>,
+        batch: Vec<Vec<String>>,
+        seed: Option<u64>,
+    ) -> Vec<Vec<String>> {
+        let seed = seed.unwrap_or(self.seed);
+        py.detach(|| {
+            batch
+                .par_iter()
+                .enumerate()
+                .map(|(i, tokens)| self.apply(tokens, seed.wrapping_add(i as u64)))
+                .collect()
+        })
+    }
+
+    /// Tokenize `text` with the augmenter's tokenizer, augment the tokens
+    /// and detokenize them again.
+    #[pyo3(signature = (text, seed=None))]
+    fn augment_text(&self, py: Python<'_>, text: &str, seed: Option<u64>) -> PyResult<String> {
+        let tokenizer = self.tokenizer()?;
+        let seed = seed.unwrap_or(self.seed);
+        Ok(py.detach(|| tokenizer.detokenize(&self.apply(&tokenizer.tokenize(text), seed))))
+    }
+
+    /// `augment_text` for many texts at once; text `i` uses `seed + i`.
+    #[pyo3(signature = (texts, seed=None))]
+    fn augment_texts(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+        seed: Option<u64>,
+    ) -> PyResult<Vec<String>> {
+        let tokenizer = self.tokenizer()?;
+        let seed = seed.unwrap_or(self.seed);
+        Ok(py.detach(|| {
+            texts
+                .par_iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let tokens = tokenizer.tokenize(text);
+                    tokenizer.detokenize(&self.apply(&tokens, seed.wrapping_add(i as u64)))
+                })
+                .collect()
+        }))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Augmenter(substitute={:?}, delete={:?}, swap={:?}, mask={:?})",
+            self.substitute, self.delete, self.swap, self.mask
+        )
+    }
+}
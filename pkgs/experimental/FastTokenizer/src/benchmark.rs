@@ -0,0 +1,186 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+#[cfg(feature = "alloc-stats")]
+use std::alloc::{GlobalAlloc, Layout, System};
+#[cfg(feature = "alloc-stats")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::tokenizer::Tokenizer;
+
+/// The system allocator, counting allocations for `benchmark`. Only
+/// installed with the `alloc-stats` feature, since it replaces the
+/// allocator of the whole process.
+#[cfg(feature = "alloc-stats")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-stats")]
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc-stats")]
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "alloc-stats")]
+// SAFETY: every call is forwarded unchanged to the system allocator.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Allocations made so far and their total size in bytes.
+#[cfg(feature = "alloc-stats")]
+fn allocations() -> (u64, u64) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+/// Without the counting allocator there is nothing to count.
+#[cfg(not(feature = "alloc-stats"))]
+fn allocations() -> (u64, u64) {
+    (0, 0)
+}
+
+fn check_iterations(iterations: usize) -> PyResult<()> {
+    if iterations == 0 {
+        return Err(PyValueError::new_err("iterations must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Time `tokenizer` over `texts`, `iterations` times after one warm-up pass,
+/// on the calling thread with the GIL released.
+///
+/// The result holds the number of `iterations`, the `bytes` and `tokens` of
+/// one pass, the total `seconds`, `tokens_per_sec` and `mb_per_sec` (10^6
+/// bytes). Built with the `alloc-stats` feature, it also holds the
+/// `allocations` and `allocated_bytes` of one pass. The allocation counts
+/// are process-wide, so other threads allocating at the same time inflate
+/// them.
+pub fn benchmark<'py>(
+    py: Python<'py>,
+    tokenizer: &dyn Tokenizer,
+    texts: &[String],
+    iterations: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    measure(py, texts, iterations, |text, _| tokenizer.tokenize(text))
+}
+
+/// `benchmark` for a pipeline whose `tokenize` adds the time spent in each
+/// stage to the `StageTimes` it is given. The timed passes are also timed
+/// per stage, and the result maps each stage to its seconds under `stages`.
+pub fn benchmark_stages<'py>(
+    py: Python<'py>,
+    texts: &[String],
+    iterations: usize,
+    tokenize: impl Fn(&str, Option<&mut StageTimes>) -> Vec<String> + Sync,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut times = StageTimes::default();
+    let result = measure(py, texts, iterations, |text, timed| {
+        tokenize(text, timed.then_some(&mut times))
+    })?;
+    let stages = PyDict::new(py);
+    for (stage, elapsed) in times.0 {
+        stages.set_item(stage, elapsed.as_secs_f64())?;
+    }
+    result.set_item("stages", stages)?;
+    Ok(result)
+}
+
+/// The measurements of `benchmark`, calling `tokenize` with `false` for the
+/// warm-up pass and `true` for the timed ones.
+fn measure<'py>(
+    py: Python<'py>,
+    texts: &[String],
+    iterations: usize,
+    mut tokenize: impl FnMut(&str, bool) -> Vec<String> + Send,
+) -> PyResult<Bound<'py, PyDict>> {
+    check_iterations(iterations)?;
+    let (tokens, elapsed, (count, size)) = py.detach(|| {
+        let tokens: usize = texts.iter().map(|t| tokenize(t, false).len()).sum();
+        let before = allocations();
+        let start = Instant::now();
+        for _ in 0..iterations {
+            for text in texts {
+                std::hint::black_box(tokenize(text, true));
+            }
+        }
+        let elapsed = start.elapsed();
+        let after = allocations();
+        (tokens, elapsed, (after.0 - before.0, after.1 - before.1))
+    });
+    let bytes: usize = texts.iter().map(String::len).sum();
+    let seconds = elapsed.as_secs_f64();
+    let passes = iterations as f64;
+    let result = PyDict::new(py);
+    result.set_item("iterations", iterations)?;
+    result.set_item("bytes", bytes)?;
+    result.set_item("tokens", tokens)?;
+    result.set_item("seconds", seconds)?;
+    result.set_item("tokens_per_sec", rate(tokens as f64 * passes, seconds))?;
+    result.set_item("mb_per_sec", rate(bytes as f64 * passes / 1e6, seconds))?;
+    if cfg!(feature = "alloc-stats") {
+        result.set_item("allocations", count / iterations as u64)?;
+        result.set_item("allocated_bytes", size / iterations as u64)?;
+    }
+    Ok(result)
+}
+
+fn rate(amount: f64, seconds: f64) -> f64 {
+    if seconds > 0.0 {
+        amount / seconds
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Time spent in each named stage of a pipeline, in the order the stages
+/// first ran.
+#[derive(Debug, Default)]
+pub struct StageTimes(Vec<(&'static str, Duration)>);
+
+impl StageTimes {
+    /// Run `f`, adding the time it takes to `stage`.
+    pub fn time<T>(&mut self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let value = f();
+        let elapsed = start.elapsed();
+        match self.0.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += elapsed,
+            None => self.0.push((stage, elapsed)),
+        }
+        value
+    }
+
+    /// `time`, or just `f` when there is nothing to time it into.
+    pub fn maybe_time<T>(
+        times: &mut Option<&mut StageTimes>,
+        stage: &'static str,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        match times {
+            Some(times) => times.time(stage, f),
+            None => f(),
+        }
+    }
+}
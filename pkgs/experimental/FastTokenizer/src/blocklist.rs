@@ -0,0 +1,297 @@
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+use crate::aligned::AlignedString;
+use crate::filters::TokenFilter;
+use crate::keyword_tokenizer::Keywords;
+use crate::tokenizer::{offsets, Token};
+
+/// A blocked phrase found in text: `(text, start, end, label)`.
+type Hit = (String, usize, usize, String);
+
+/// The letter a leetspeak character usually stands for.
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '|' => 'l',
+        _ => c,
+    }
+}
+
+/// Append `c` to `folded`, or, when collapsing repeats and `c` repeats the
+/// last character, widen that character's span instead.
+fn push(folded: &mut AlignedString, c: char, span: (usize, usize), collapse: bool) {
+    if collapse && folded.text.ends_with(c) {
+        let len = folded.spans.len();
+        for previous in &mut folded.spans[len - c.len_utf8()..] {
+            previous.1 = span.1;
+        }
+    } else {
+        folded.push(c, span);
+    }
+}
+
+/// Lowercase `text` and collapse whitespace runs; with `normalize`, also
+/// drop diacritics, read leetspeak as letters and collapse runs of a
+/// repeated letter (`fuuuck`), so spelling tricks do not dodge the list.
+fn fold(text: &str, normalize: bool) -> AlignedString {
+    let mut folded = AlignedString::default();
+    for (i, c) in text.char_indices() {
+        let span = (i, i + c.len_utf8());
+        if c.is_whitespace() {
+            push(&mut folded, ' ', span, true);
+        } else if normalize {
+            let mut base = Vec::new();
+            decompose_canonical(c, |d| {
+                if !is_combining_mark(d) {
+                    base.push(d);
+                }
+            });
+            for d in base {
+                for lower in d.to_lowercase() {
+                    push(&mut folded, unleet(lower), span, true);
+                }
+            }
+        } else {
+            for lower in c.to_lowercase() {
+                push(&mut folded, lower, span, false);
+            }
+        }
+    }
+    folded
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Flags, masks or drops words and phrases from a blocklist, such as
+/// profanity, for moderation pre-screening.
+///
+/// Text and list entries are compared after lowercasing and, with
+/// `normalize`, after removing diacritics, reading leetspeak digits and
+/// symbols as letters (`sh1t`, `@ss`) and collapsing repeated letters, so
+/// common spelling tricks are caught. Reported offsets always point into
+/// the original text. In a pipeline's `filters`, it drops tokens that are
+/// blocked words.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct BlocklistFilter {
+    matcher: AhoCorasick,
+    /// The entries as given, with their labels.
+    words: Arc<Vec<String>>,
+    labels: Arc<Vec<String>>,
+    /// The folded single-word entries, for filtering tokens.
+    folded: Arc<HashSet<String>>,
+    normalize: bool,
+    whole_words: bool,
+}
+
+impl BlocklistFilter {
+    /// Non-overlapping matches as `(pattern id, token)`, leftmost-longest.
+    fn scan(&self, text: &str) -> Vec<(usize, Token)> {
+        let folded = fold(text, self.normalize);
+        self.matcher
+            .find_iter(&folded.text)
+            .map(|m| {
+                let (start, end) = folded.span(m.start(), m.end());
+                (
+                    m.pattern().as_usize(),
+                    Token::new(&text[start..end], start, end),
+                )
+            })
+            // Word boundaries are judged on the original text, where a
+            // trailing `!` is punctuation rather than a leetspeak `i`.
+            .filter(|(_, token)| {
+                !self.whole_words
+                    || (!text[..token.start]
+                        .chars()
+                        .next_back()
+                        .is_some_and(is_word_char)
+                        && !text[token.end..].chars().next().is_some_and(is_word_char))
+            })
+            .collect()
+    }
+
+    fn mask(&self, text: &str, replacement: &str) -> String {
+        let mut chars = replacement.chars();
+        let single = match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        };
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (_, token) in self.scan(text) {
+            out.push_str(&text[last..token.start]);
+            match single {
+                Some(c) => out.extend(token.text.chars().map(|t| if t == ' ' { t } else { c })),
+                None => out.push_str(replacement),
+            }
+            last = token.end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+impl TokenFilter for BlocklistFilter {
+    fn keep(&self, token: &str) -> bool {
+        !self.folded.contains(&fold(token, self.normalize).text)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+}
+
+#[pymethods]
+impl BlocklistFilter {
+    /// Create a blocklist filter.
+    ///
+    /// Args:
+    ///     words (List[str] | Dict[str, str]): Words and phrases to block,
+    ///         or a mapping of them to labels such as categories.
+    ///     labels (List[str], optional): A label for each entry of a list
+    ///         of words; entries are their own label otherwise.
+    ///     normalize (bool): See the class documentation; lowercasing and
+    ///         whitespace collapsing always apply.
+    ///     whole_words (bool): Only match entries not embedded in a longer
+    ///         word, so `"ass"` is not found in `"class"`.
+    #[new]
+    #[pyo3(signature = (words, labels=None, normalize=true, whole_words=true))]
+    fn new(
+        words: Keywords,
+        labels: Option<Vec<String>>,
+        normalize: bool,
+        whole_words: bool,
+    ) -> PyResult<Self> {
+        let pairs: Vec<(String, String)> = match (words, labels) {
+            (Keywords::Phrases(words), None) => words.into_iter().map(|w| (w.clone(), w)).collect(),
+            (Keywords::Phrases(words), Some(labels)) if labels.len() == words.len() => {
+                words.into_iter().zip(labels).collect()
+            }
+            (Keywords::Phrases(words), Some(labels)) => {
+                return Err(PyValueError::new_err(format!(
+                    "Got {} labels for {} words",
+                    labels.len(),
+                    words.len()
+                )))
+            }
+            (Keywords::Labeled(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "Pass labels either in a dict or as labels, not both",
+                ))
+            }
+            (Keywords::Labeled(labeled), None) => {
+                let mut pairs: Vec<_> = labeled.into_iter().collect();
+                pairs.sort();
+                pairs
+            }
+        };
+        let patterns: Vec<String> = pairs
+            .iter()
+            .map(|(word, _)| fold(word.trim(), normalize).text)
+            .collect();
+        if patterns.iter().any(String::is_empty) {
+            return Err(PyValueError::new_err("Blocked words must not be empty"));
+        }
+        let matcher = AhoCorasickBuilder::new()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(&patterns)
+            .map_err(|e| PyValueError::new_err(format!("Failed to build blocklist: {}", e)))?;
+        let folded = patterns.into_iter().filter(|p| !p.contains(' ')).collect();
+        let (words, labels) = pairs.into_iter().unzip();
+        Ok(BlocklistFilter {
+            matcher,
+            words: Arc::new(words),
+            labels: Arc::new(labels),
+            folded: Arc::new(folded),
+            normalize,
+            whole_words,
+        })
+    }
+
+    /// Constructor arguments recreating this filter, for `pickle` and `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("words", self.words.as_slice())?;
+        kwargs.set_item("labels", self.labels.as_slice())?;
+        kwargs.set_item("normalize", self.normalize)?;
+        kwargs.set_item("whole_words", self.whole_words)?;
+        Ok(((), kwargs))
+    }
+
+    /// The blocked words and phrases in `text`.
+    ///
+    /// Args:
+    ///     text (str): Text to scan.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int, str]]: `(match, start, end, label)`,
+    ///     where `match` is the text as written, e.g. `"Sh1t"`.
+    #[pyo3(signature = (text, unit="char"))]
+    fn find(&self, text: &str, unit: &str) -> PyResult<Vec<Hit>> {
+        let (ids, tokens): (Vec<_>, Vec<_>) = self.scan(text).into_iter().unzip();
+        Ok(ids
+            .into_iter()
+            .zip(offsets(text, tokens, unit)?)
+            .map(|(id, (matched, start, end))| (matched, start, end, self.labels[id].clone()))
+            .collect())
+    }
+
+    /// Whether `text` contains anything on the list.
+    fn is_flagged(&self, text: &str) -> bool {
+        !self.scan(text).is_empty()
+    }
+
+    /// Hide every match in `text`.
+    ///
+    /// Args:
+    ///     text (str): Text to mask.
+    ///     replacement (str): A single character replaces each character of
+    ///         a match (spaces in phrases are kept); a longer string replaces
+    ///         the whole match.
+    #[pyo3(signature = (text, replacement="*"))]
+    fn mask_text(&self, text: &str, replacement: &str) -> String {
+        self.mask(text, replacement)
+    }
+
+    /// `mask_text` for several texts at once, releasing the GIL while
+    /// working.
+    #[pyo3(signature = (texts, replacement="*"))]
+    fn mask_batch(&self, py: Python<'_>, texts: Vec<String>, replacement: &str) -> Vec<String> {
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| self.mask(text, replacement))
+                .collect()
+        })
+    }
+
+    /// `tokens` without the blocked words.
+    fn filter(&self, tokens: Vec<String>) -> Vec<String> {
+        tokens.into_iter().filter(|t| self.keep(t)).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.words.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BlocklistFilter(words={})", self.words.len())
+    }
+}
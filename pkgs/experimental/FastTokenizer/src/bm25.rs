@@ -0,0 +1,252 @@
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::tokenizer::{extract_tokenizer, Tokenizer};
+
+/// Term counts of one document, by term.
+fn term_counts(tokens: Vec<String>) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for token in tokens {
+        *counts.entry(token).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// In-memory BM25 index over any FastTokenizer tokenizer or pipeline.
+///
+/// Documents are tokenized once when added and kept only as postings, so
+/// the index is small enough to sit next to a vector store for hybrid
+/// retrieval. Scores are Okapi BM25 with Lucene's inverse document
+/// frequency `ln(1 + (N - df + 0.5) / (df + 0.5))`, which is never
+/// negative.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct Bm25Index {
+    tokenizer: Arc<dyn Tokenizer>,
+    k1: f64,
+    b: f64,
+    terms: HashMap<String, u32>,
+    /// Per term, the documents containing it and how often.
+    postings: Vec<Vec<(u32, u32)>>,
+    /// Per document, its distinct terms; empty once removed.
+    documents: Vec<Vec<u32>>,
+    lengths: Vec<u32>,
+    keys: Vec<Option<String>>,
+    ids: HashMap<String, u32>,
+    total_length: u64,
+}
+
+impl Bm25Index {
+    fn insert(&mut self, key: String, counts: HashMap<String, u32>) {
+        let id = self.keys.len() as u32;
+        let mut length = 0;
+        let mut terms = Vec::with_capacity(counts.len());
+        for (term, count) in counts {
+            let next = self.terms.len() as u32;
+            let term = *self.terms.entry(term).or_insert(next);
+            if term == next {
+                self.postings.push(Vec::new());
+            }
+            self.postings[term as usize].push((id, count));
+            terms.push(term);
+            length += count;
+        }
+        self.documents.push(terms);
+        self.lengths.push(length);
+        self.total_length += length as u64;
+        self.ids.insert(key.clone(), id);
+        self.keys.push(Some(key));
+    }
+
+    fn check_new_key(&self, key: &str) -> PyResult<()> {
+        if self.ids.contains_key(key) {
+            return Err(PyKeyError::new_err(format!("'{}' is already indexed", key)));
+        }
+        Ok(())
+    }
+
+    /// The `k` best-scoring documents for `query`, best first.
+    fn top_k(&self, query: &str, k: usize) -> Vec<(String, f64)> {
+        let n = self.ids.len() as f64;
+        if n == 0.0 || k == 0 {
+            return Vec::new();
+        }
+        let average = (self.total_length as f64 / n).max(f64::MIN_POSITIVE);
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+        for token in self.tokenizer.tokenize(query) {
+            let Some(&term) = self.terms.get(&token) else {
+                continue;
+            };
+            let postings = &self.postings[term as usize];
+            let df = postings.len() as f64;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for &(id, tf) in postings {
+                let tf = tf as f64;
+                let norm = 1.0 - self.b + self.b * self.lengths[id as usize] as f64 / average;
+                *scores.entry(id).or_insert(0.0) +=
+                    idf * tf * (self.k1 + 1.0) / (tf + self.k1 * norm);
+            }
+        }
+        let mut results: Vec<(&str, f64)> = scores
+            .into_iter()
+            .filter_map(|(id, score)| Some((self.keys[id as usize].as_deref()?, score)))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        results.truncate(k);
+        results
+            .into_iter()
+            .map(|(key, score)| (key.to_string(), score))
+            .collect()
+    }
+}
+
+#[pymethods]
+impl Bm25Index {
+    /// Create an empty index.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer or `TokenizerPipeline`,
+    ///         applied to documents and queries alike; use a pipeline with a
+    ///         `Normalizer` and `StopWordFilter` for case-insensitive search.
+    ///     k1 (float): Term frequency saturation; 0 ignores repetitions.
+    ///     b (float): Document length normalization, from 0 (none) to 1.
+    #[new]
+    #[pyo3(signature = (tokenizer, k1=1.5, b=0.75))]
+    fn new(tokenizer: &Bound<'_, PyAny>, k1: f64, b: f64) -> PyResult<Self> {
+        if !(0.0..).contains(&k1) {
+            return Err(PyValueError::new_err(format!(
+                "k1 must not be negative, got {}",
+                k1
+            )));
+        }
+        if !(0.0..=1.0).contains(&b) {
+            return Err(PyValueError::new_err(format!(
+                "b must be between 0 and 1, got {}",
+                b
+            )));
+        }
+        Ok(Bm25Index {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            k1,
+            b,
+            terms: HashMap::new(),
+            postings: Vec::new(),
+            documents: Vec::new(),
+            lengths: Vec::new(),
+            keys: Vec::new(),
+            ids: HashMap::new(),
+            total_length: 0,
+        })
+    }
+
+    /// Index `text` under `key`.
+    ///
+    /// Raises:
+    ///     KeyError: If `key` is already in the index.
+    fn add_document(&mut self, key: String, text: &str) -> PyResult<()> {
+        self.check_new_key(&key)?;
+        let counts = term_counts(self.tokenizer.tokenize(text));
+        self.insert(key, counts);
+        Ok(())
+    }
+
+    /// Index many `(key, text)` pairs, tokenizing them in parallel without
+    /// the GIL.
+    ///
+    /// Raises:
+    ///     KeyError: If a key is already in the index or repeated; nothing
+    ///         is added then.
+    fn add_documents(&mut self, py: Python<'_>, documents: Vec<(String, String)>) -> PyResult<()> {
+        let mut seen = HashSet::new();
+        for (key, _) in &documents {
+            self.check_new_key(key)?;
+            if !seen.insert(key.as_str()) {
+                return Err(PyKeyError::new_err(format!("'{}' is repeated", key)));
+            }
+        }
+        let tokenizer = self.tokenizer.clone();
+        let counts: Vec<HashMap<String, u32>> = py.detach(|| {
+            documents
+                .par_iter()
+                .map(|(_, text)| term_counts(tokenizer.tokenize(text)))
+                .collect()
+        });
+        for ((key, _), counts) in documents.into_iter().zip(counts) {
+            self.insert(key, counts);
+        }
+        Ok(())
+    }
+
+    /// Remove the document stored under `key`.
+    fn remove(&mut self, key: &str) -> PyResult<()> {
+        let Some(id) = self.ids.remove(key) else {
+            return Err(PyKeyError::new_err(key.to_string()));
+        };
+        for term in std::mem::take(&mut self.documents[id as usize]) {
+            self.postings[term as usize].retain(|&(other, _)| other != id);
+        }
+        self.total_length -= self.lengths[id as usize] as u64;
+        self.lengths[id as usize] = 0;
+        self.keys[id as usize] = None;
+        Ok(())
+    }
+
+    /// The best matches for `query`, as `(key, score)` pairs best first.
+    ///
+    /// Only documents sharing at least one term with the query are
+    /// returned, so there may be fewer than `k`.
+    #[pyo3(signature = (query, k=10))]
+    fn search(&self, py: Python<'_>, query: &str, k: usize) -> Vec<(String, f64)> {
+        py.detach(|| self.top_k(query, k))
+    }
+
+    /// `search` for several queries at once, in parallel without the GIL.
+    #[pyo3(signature = (queries, k=10))]
+    fn search_batch(
+        &self,
+        py: Python<'_>,
+        queries: Vec<String>,
+        k: usize,
+    ) -> Vec<Vec<(String, f64)>> {
+        py.detach(|| {
+            queries
+                .par_iter()
+                .map(|query| self.top_k(query, k))
+                .collect()
+        })
+    }
+
+    #[getter]
+    fn k1(&self) -> f64 {
+        self.k1
+    }
+
+    #[getter]
+    fn b(&self) -> f64 {
+        self.b
+    }
+
+    /// Average document length in tokens.
+    #[getter]
+    fn average_length(&self) -> f64 {
+        if self.ids.is_empty() {
+            return 0.0;
+        }
+        self.total_length as f64 / self.ids.len() as f64
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.keys.iter().flatten().cloned().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.ids.contains_key(key)
+    }
+}
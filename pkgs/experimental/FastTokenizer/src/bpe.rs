@@ -0,0 +1,663 @@
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::string::FromUtf8Error;
+use std::sync::Arc;
+
+use crate::config;
+use crate::detokenizer::clean_up_tokenization;
+use crate::intern::token_list;
+use crate::mapped::map_file;
+use crate::random::{entropy, Rng};
+use crate::special_tokens::SpecialTokens;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::{decode_error, Text, Utf8Errors};
+use crate::whitespace_tokenizer::WhitespaceTokenizer;
+
+pub use fasttokenizer_core::pretokenizer::{
+    byte_level_split, bytes_to_unicode, split_with_lookahead, unicode_to_bytes,
+};
+
+/// The special tokens of GPT-2 and RoBERTa vocabularies, registered by
+/// `from_files` unless it is given others.
+const SPECIAL_TOKENS: &[&str] = &["<|endoftext|>", "<s>", "</s>", "<pad>", "<mask>", "<unk>"];
+
+/// Options of a BPE model beyond its vocabulary and merges.
+#[derive(Clone, Debug, Default)]
+pub struct BpeOptions {
+    pub byte_level: bool,
+    pub add_prefix_space: bool,
+    pub unk_token: Option<String>,
+    /// Prefix of symbols that do not start a word (e.g. `##`).
+    pub continuing_subword_prefix: Option<String>,
+    /// Suffix of symbols that end a word (e.g. `</w>`).
+    pub end_of_word_suffix: Option<String>,
+    /// Merge consecutive unknown symbols into one.
+    pub fuse_unk: bool,
+    /// Encode unknown characters as `<0xXX>` byte tokens when present.
+    pub byte_fallback: bool,
+    /// Tokens left out when decoding with `skip_special_tokens`, besides
+    /// the unknown token.
+    pub special_tokens: SpecialTokens,
+}
+
+/// Vocabulary and merge table shared by all clones of a `BpeTokenizer`.
+struct BpeModel {
+    vocab: HashMap<String, u32>,
+    vocab_r: HashMap<u32, String>,
+    /// (left id, right id) -> (merge rank, merged id)
+    merges: HashMap<(u32, u32), (usize, u32)>,
+    options: BpeOptions,
+    unk_id: Option<u32>,
+}
+
+impl BpeModel {
+    fn new(
+        vocab: HashMap<String, u32>,
+        merges: Vec<(String, String)>,
+        options: BpeOptions,
+    ) -> Result<Self, String> {
+        let lookup = |token: &str| {
+            vocab
+                .get(token)
+                .copied()
+                .ok_or_else(|| format!("Token '{}' from merges is not in the vocabulary", token))
+        };
+        let prefix = options.continuing_subword_prefix.as_deref().unwrap_or("");
+        let mut merge_map = HashMap::with_capacity(merges.len());
+        for (rank, (left, right)) in merges.iter().enumerate() {
+            let tail = right.strip_prefix(prefix).unwrap_or(right);
+            let merged = lookup(&format!("{}{}", left, tail))?;
+            merge_map.insert((lookup(left)?, lookup(right)?), (rank, merged));
+        }
+        let unk_id =
+            match &options.unk_token {
+                Some(token) => Some(vocab.get(token).copied().ok_or_else(|| {
+                    format!("Unknown token '{}' is not in the vocabulary", token)
+                })?),
+                None => None,
+            };
+        let vocab_r = vocab.iter().map(|(k, &v)| (v, k.clone())).collect();
+        Ok(BpeModel {
+            vocab,
+            vocab_r,
+            merges: merge_map,
+            options,
+            unk_id,
+        })
+    }
+
+    /// Id of a single character, or its byte tokens when falling back to bytes.
+    fn char_ids(&self, symbol: &str, c: char) -> Option<Vec<u32>> {
+        if let Some(&id) = self.vocab.get(symbol) {
+            return Some(vec![id]);
+        }
+        if self.options.byte_fallback {
+            let mut buf = [0u8; 4];
+            let bytes = c.encode_utf8(&mut buf).as_bytes();
+            let ids: Option<Vec<u32>> = bytes
+                .iter()
+                .map(|b| self.vocab.get(&format!("<0x{:02X}>", b)).copied())
+                .collect();
+            if ids.is_some() {
+                return ids;
+            }
+        }
+        None
+    }
+
+    /// Initial symbols of the word at `start..end` as `(id, start, end)`.
+    ///
+    /// Symbols missing from the vocabulary map to the unknown token, or are
+    /// dropped when there is none.
+    fn symbols(&self, text: &str, start: usize, end: usize) -> Vec<(u32, usize, usize)> {
+        let word = &text[start..end];
+        let mut symbols: Vec<(u32, usize, usize)> = Vec::with_capacity(word.len());
+        let mut buf = [0u8; 4];
+        if self.options.byte_level {
+            let table = bytes_to_unicode();
+            for (i, &b) in word.as_bytes().iter().enumerate() {
+                let c = table[b as usize].encode_utf8(&mut buf);
+                if let Some(id) = self.vocab.get(&*c).copied().or(self.unk_id) {
+                    symbols.push((id, start + i, start + i + 1));
+                }
+            }
+        } else {
+            let prefix = self.options.continuing_subword_prefix.as_deref();
+            let suffix = self.options.end_of_word_suffix.as_deref();
+            let mut symbol = String::new();
+            let mut previous_unk = false;
+            for (i, c) in word.char_indices() {
+                let (s, e) = (start + i, start + i + c.len_utf8());
+                symbol.clear();
+                if i > 0 {
+                    symbol.push_str(prefix.unwrap_or(""));
+                }
+                symbol.push(c);
+                if e == end {
+                    symbol.push_str(suffix.unwrap_or(""));
+                }
+                match self.char_ids(&symbol, c) {
+                    Some(ids) => {
+                        symbols.extend(ids.into_iter().map(|id| (id, s, e)));
+                        previous_unk = false;
+                    }
+                    None => match self.unk_id {
+                        Some(_) if previous_unk && self.options.fuse_unk => {
+                            symbols.last_mut().expect("previous unknown symbol").2 = e;
+                        }
+                        Some(unk) => {
+                            symbols.push((unk, s, e));
+                            previous_unk = true;
+                        }
+                        None => {}
+                    },
+                }
+            }
+        }
+        symbols
+    }
+
+    /// Apply merges by rank until none applies.
+    fn merge(&self, mut symbols: Vec<(u32, usize, usize)>) -> Vec<(u32, usize, usize)> {
+        while symbols.len() > 1 {
+            let best = symbols
+                .windows(2)
+                .filter_map(|pair| self.merges.get(&(pair[0].0, pair[1].0)))
+                .min_by_key(|(rank, _)| *rank);
+            let Some(&(rank, merged)) = best else {
+                break;
+            };
+            let mut out = Vec::with_capacity(symbols.len());
+            let mut i = 0;
+            while i < symbols.len() {
+                if i + 1 < symbols.len()
+                    && self.merges.get(&(symbols[i].0, symbols[i + 1].0)) == Some(&(rank, merged))
+                {
+                    out.push((merged, symbols[i].1, symbols[i + 1].2));
+                    i += 2;
+                } else {
+                    out.push(symbols[i]);
+                    i += 1;
+                }
+            }
+            symbols = out;
+        }
+        symbols
+    }
+
+    /// Apply merges by rank like `merge`, but skip each applicable merge
+    /// with probability `dropout` at every step (BPE-dropout), stopping when
+    /// all of them are skipped.
+    fn merge_with_dropout(
+        &self,
+        mut symbols: Vec<(u32, usize, usize)>,
+        dropout: f64,
+        rng: &mut Rng,
+    ) -> Vec<(u32, usize, usize)> {
+        while symbols.len() > 1 {
+            let mut best: Option<(usize, usize, u32)> = None;
+            for i in 0..symbols.len() - 1 {
+                let Some(&(rank, merged)) = self.merges.get(&(symbols[i].0, symbols[i + 1].0))
+                else {
+                    continue;
+                };
+                if rng.next_f64() >= dropout && best.is_none_or(|(r, _, _)| rank < r) {
+                    best = Some((rank, i, merged));
+                }
+            }
+            let Some((_, i, merged)) = best else {
+                break;
+            };
+            symbols[i] = (merged, symbols[i].1, symbols[i + 1].2);
+            symbols.remove(i + 1);
+        }
+        symbols
+    }
+
+    /// Encode text into `(id, start, end)` with byte offsets into `text`.
+    fn encode_with_offsets(&self, text: &str) -> Vec<(u32, usize, usize)> {
+        self.encode_sampled(text, None)
+    }
+
+    /// `encode_with_offsets`, with BPE-dropout when `dropout` is given.
+    fn encode_sampled(
+        &self,
+        text: &str,
+        mut dropout: Option<(f64, &mut Rng)>,
+    ) -> Vec<(u32, usize, usize)> {
+        let prefixed;
+        let (text, shift) = if self.options.add_prefix_space && !text.starts_with(' ') {
+            prefixed = format!(" {}", text);
+            (prefixed.as_str(), 1)
+        } else {
+            (text, 0)
+        };
+        let spans = if self.options.byte_level {
+            byte_level_split(text)
+        } else {
+            WhitespaceTokenizer::default()
+                .tokenize_with_offsets(text)
+                .into_iter()
+                .map(|t| (t.start, t.end))
+                .collect()
+        };
+        let mut out = Vec::new();
+        for (start, end) in spans {
+            let symbols = self.symbols(text, start, end);
+            let merged = match dropout.as_mut() {
+                Some((p, rng)) => self.merge_with_dropout(symbols, *p, rng),
+                None => self.merge(symbols),
+            };
+            out.extend(
+                merged
+                    .into_iter()
+                    .map(|(id, s, e)| (id, s.saturating_sub(shift), e.saturating_sub(shift))),
+            );
+        }
+        out
+    }
+
+    fn is_special(&self, id: u32) -> bool {
+        self.unk_id == Some(id)
+            || self
+                .vocab_r
+                .get(&id)
+                .is_some_and(|token| self.options.special_tokens.contains(token))
+    }
+
+    fn decode(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: bool,
+        errors: Utf8Errors,
+    ) -> Result<String, FromUtf8Error> {
+        let joined: String = ids
+            .iter()
+            .filter(|&&id| !(skip_special_tokens && self.is_special(id)))
+            .filter_map(|id| self.vocab_r.get(id))
+            .map(String::as_str)
+            .collect();
+        if !self.options.byte_level {
+            return Ok(joined);
+        }
+        let table = unicode_to_bytes();
+        let bytes: Vec<u8> = joined
+            .chars()
+            .filter_map(|c| table.get(&c).copied())
+            .collect();
+        errors.decode(bytes)
+    }
+}
+
+/// A BPE-dropout probability, or `None` when it turns dropout off.
+fn check_dropout(dropout: Option<f64>) -> PyResult<Option<f64>> {
+    match dropout {
+        Some(p) if !(0.0..=1.0).contains(&p) => Err(PyValueError::new_err(format!(
+            "dropout must be between 0 and 1, got {}",
+            p
+        ))),
+        Some(p) if p > 0.0 => Ok(Some(p)),
+        _ => Ok(None),
+    }
+}
+
+/// Byte-pair-encoding tokenizer, compatible with GPT-2/RoBERTa byte-level BPE.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct BpeTokenizer {
+    model: Arc<BpeModel>,
+}
+
+impl BpeTokenizer {
+    pub fn with_options(
+        vocab: HashMap<String, u32>,
+        merges: Vec<(String, String)>,
+        options: BpeOptions,
+    ) -> Result<Self, String> {
+        Ok(BpeTokenizer {
+            model: Arc::new(BpeModel::new(vocab, merges, options)?),
+        })
+    }
+
+    /// Encode one pre-tokenized word, without any splitting, as
+    /// `(id, start, end)` with byte offsets into `word`.
+    pub fn encode_word(&self, word: &str) -> Vec<(u32, usize, usize)> {
+        self.model.merge(self.model.symbols(word, 0, word.len()))
+    }
+
+    /// The vocabulary, merges and options as a JSON object, as embedded in
+    /// tokenizer configurations and bundles.
+    pub fn to_value(&self) -> Value {
+        let model = &self.model;
+        let mut merges: Vec<_> = model.merges.iter().collect();
+        merges.sort_by_key(|(_, &(rank, _))| rank);
+        let merges: Vec<_> = merges
+            .into_iter()
+            .map(|(&(left, right), _)| [&model.vocab_r[&left], &model.vocab_r[&right]])
+            .collect();
+        let options = &model.options;
+        json!({
+            "vocab": model.vocab,
+            "merges": merges,
+            "byte_level": options.byte_level,
+            "add_prefix_space": options.add_prefix_space,
+            "unk_token": options.unk_token,
+            "continuing_subword_prefix": options.continuing_subword_prefix,
+            "end_of_word_suffix": options.end_of_word_suffix,
+            "fuse_unk": options.fuse_unk,
+            "byte_fallback": options.byte_fallback,
+            "special_tokens": options.special_tokens.to_value(),
+        })
+    }
+
+    /// Recreate a tokenizer from `to_value` output.
+    pub fn from_value(value: &Value) -> PyResult<Self> {
+        let invalid =
+            |e: serde_json::Error| PyValueError::new_err(format!("Invalid BPE model: {}", e));
+        let vocab = serde_json::from_value(value["vocab"].clone()).map_err(invalid)?;
+        let merges = serde_json::from_value(value["merges"].clone()).map_err(invalid)?;
+        let special_tokens = match &value["special_tokens"] {
+            Value::Null => SpecialTokens::default(),
+            special_tokens => SpecialTokens::from_value(special_tokens)?,
+        };
+        let flag = |name: &str| value[name].as_bool().unwrap_or(false);
+        let text = |name: &str| value[name].as_str().map(str::to_string);
+        let options = BpeOptions {
+            byte_level: flag("byte_level"),
+            add_prefix_space: flag("add_prefix_space"),
+            unk_token: text("unk_token"),
+            continuing_subword_prefix: text("continuing_subword_prefix"),
+            end_of_word_suffix: text("end_of_word_suffix"),
+            fuse_unk: flag("fuse_unk"),
+            byte_fallback: flag("byte_fallback"),
+            special_tokens,
+        };
+        Self::with_options(vocab, merges, options).map_err(PyValueError::new_err)
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(id, start, end)| Token::new(self.model.vocab_r[&id].clone(), start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        let ids: Vec<u32> = tokens.iter().filter_map(|t| self.token_to_id(t)).collect();
+        self.model
+            .decode(&ids, false, Utf8Errors::Replace)
+            .expect("replacing never fails")
+    }
+}
+
+/// The files are parsed from a memory map to skip one copy of their bytes;
+/// the vocabulary and merges themselves are still built as owned tables.
+fn read_vocab(path: &str) -> PyResult<HashMap<String, u32>> {
+    let data = map_file(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read vocab file: {}", e)))?;
+    serde_json::from_slice(&data)
+        .map_err(|e| PyValueError::new_err(format!("Invalid vocab file: {}", e)))
+}
+
+fn read_merges(path: &str) -> PyResult<Vec<(String, String)>> {
+    let data = map_file(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read merges file: {}", e)))?;
+    let data = std::str::from_utf8(&data)
+        .map_err(|e| PyValueError::new_err(format!("Invalid merges file: {}", e)))?;
+    data.lines()
+        .filter(|line| !line.starts_with("#version") && !line.trim().is_empty())
+        .map(|line| match line.split_once(' ') {
+            Some((a, b)) => Ok((a.to_string(), b.to_string())),
+            None => Err(PyValueError::new_err(format!(
+                "Invalid merge line: {}",
+                line
+            ))),
+        })
+        .collect()
+}
+
+tokenizer_pymethods!(BpeTokenizer);
+
+#[pymethods]
+impl BpeTokenizer {
+    /// Create a BPE tokenizer from an in-memory vocabulary and merge list.
+    ///
+    /// Args:
+    ///     vocab (Dict[str, int]): Token to id mapping.
+    ///     merges (List[Tuple[str, str]]): Merge rules, highest priority first.
+    ///     byte_level (bool): Use GPT-2 byte-level pre-tokenization and alphabet.
+    ///     add_prefix_space (bool): Prepend a space so the first word is
+    ///         encoded like any other word (RoBERTa style).
+    ///     unk_token (str, optional): Token used for symbols missing from the vocab.
+    ///     special_tokens (SpecialTokens, optional): Tokens that `decode`
+    ///         leaves out with `skip_special_tokens`.
+    #[new]
+    #[pyo3(signature = (vocab, merges, byte_level=true, add_prefix_space=false, unk_token=None, special_tokens=None))]
+    fn new(
+        vocab: HashMap<String, u32>,
+        merges: Vec<(String, String)>,
+        byte_level: bool,
+        add_prefix_space: bool,
+        unk_token: Option<String>,
+        special_tokens: Option<PyRef<'_, SpecialTokens>>,
+    ) -> PyResult<Self> {
+        let options = BpeOptions {
+            byte_level,
+            add_prefix_space,
+            unk_token,
+            special_tokens: special_tokens.map(|s| s.clone()).unwrap_or_default(),
+            ..BpeOptions::default()
+        };
+        Self::with_options(vocab, merges, options).map_err(PyValueError::new_err)
+    }
+
+    /// Load a tokenizer from standard `vocab.json` and `merges.txt` files.
+    ///
+    /// Without `special_tokens`, the GPT-2/RoBERTa special tokens found in
+    /// the vocabulary (`<|endoftext|>`, `<s>`, `</s>`, `<pad>`, `<mask>`,
+    /// `<unk>`) are registered.
+    #[staticmethod]
+    #[pyo3(signature = (vocab_path, merges_path, byte_level=true, add_prefix_space=false, unk_token=None, special_tokens=None))]
+    fn from_files(
+        vocab_path: &str,
+        merges_path: &str,
+        byte_level: bool,
+        add_prefix_space: bool,
+        unk_token: Option<String>,
+        special_tokens: Option<PyRef<'_, SpecialTokens>>,
+    ) -> PyResult<Self> {
+        let vocab = read_vocab(vocab_path)?;
+        let special_tokens = match special_tokens {
+            Some(special_tokens) => special_tokens.clone(),
+            None => SpecialTokens::from_vocab(&vocab, SPECIAL_TOKENS),
+        };
+        let options = BpeOptions {
+            byte_level,
+            add_prefix_space,
+            unk_token,
+            special_tokens,
+            ..BpeOptions::default()
+        };
+        Self::with_options(vocab, read_merges(merges_path)?, options).map_err(PyValueError::new_err)
+    }
+
+    /// Save the vocabulary, merges and options to a single bundle file, to
+    /// be loaded back with `BpeTokenizer.load` instead of shipping
+    /// `vocab.json` and `merges.txt` separately.
+    fn save(slf: &Bound<'_, Self>, path: &str) -> PyResult<()> {
+        config::save(slf.as_any(), path)
+    }
+
+    /// Load a tokenizer saved with `save`.
+    #[staticmethod]
+    fn load<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, Self>> {
+        config::load(py, path)
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into token ids.
+    ///
+    /// Args:
+    ///     input (str | bytes): The text to encode.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///     dropout (float, optional): BPE-dropout probability: every
+    ///         applicable merge is skipped with this probability at each
+    ///         step, sampling a different segmentation on each call for
+    ///         subword regularization. `None` or 0 encodes deterministically.
+    ///     seed (int, optional): Seed for the dropout sampling; the next
+    ///         `set_global_seed` seed, or random, when not given.
+    #[pyo3(signature = (input, errors="strict", dropout=None, seed=None))]
+    fn encode<'py>(
+        &self,
+        input: Text<'py>,
+        errors: &str,
+        dropout: Option<f64>,
+        seed: Option<u64>,
+    ) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        let dropout = check_dropout(dropout)?;
+        let mut rng = Rng::new(seed.unwrap_or_else(entropy));
+        Ok(self
+            .model
+            .encode_sampled(input, dropout.map(|p| (p, &mut rng)))
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect())
+    }
+
+    /// Encode several strings at once, releasing the GIL while working.
+    /// With `dropout`, input `i` is sampled with `seed + i`.
+    #[pyo3(signature = (inputs, dropout=None, seed=None))]
+    fn encode_batch(
+        &self,
+        py: Python<'_>,
+        inputs: Vec<String>,
+        dropout: Option<f64>,
+        seed: Option<u64>,
+    ) -> PyResult<Vec<Vec<u32>>> {
+        let dropout = check_dropout(dropout)?;
+        let seed = seed.unwrap_or_else(entropy);
+        Ok(py.detach(|| {
+            inputs
+                .iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let mut rng = Rng::new(seed.wrapping_add(i as u64));
+                    self.model
+                        .encode_sampled(text, dropout.map(|p| (p, &mut rng)))
+                        .into_iter()
+                        .map(|(id, _, _)| id)
+                        .collect()
+                })
+                .collect()
+        }))
+    }
+
+    /// Turn token ids, e.g. generated by a model, back into text, mapping
+    /// byte-level symbols back to the bytes they stand for.
+    ///
+    /// Args:
+    ///     ids (List[int]): Token ids.
+    ///     skip_special_tokens (bool): Leave out the unknown token and the
+    ///         registered `special_tokens`.
+    ///     clean_up_tokenization_spaces (bool): Remove spaces before
+    ///         punctuation and contractions like `transformers` does.
+    ///     errors (str): What to do with byte sequences that are not valid
+    ///         UTF-8, e.g. a character cut off by generation: `"replace"`
+    ///         them with U+FFFD, `"ignore"` them or raise a
+    ///         `UnicodeDecodeError` (`"strict"`).
+    #[pyo3(signature = (ids, skip_special_tokens=true, clean_up_tokenization_spaces=true, errors="replace"))]
+    fn decode(
+        &self,
+        py: Python<'_>,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+        errors: &str,
+    ) -> PyResult<String> {
+        let errors = Utf8Errors::parse(errors)?;
+        let text = self
+            .model
+            .decode(&ids, skip_special_tokens, errors)
+            .map_err(|e| decode_error(py, e))?;
+        Ok(clean_up_tokenization(text, clean_up_tokenization_spaces))
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.model.vocab.get(token).copied()
+    }
+
+    pub fn id_to_token(&self, id: u32) -> Option<String> {
+        self.model.vocab_r.get(&id).cloned()
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.model.vocab.len()
+    }
+
+    /// The special tokens that `decode` leaves out.
+    #[getter]
+    fn special_tokens(&self) -> SpecialTokens {
+        self.model.options.special_tokens.clone()
+    }
+}
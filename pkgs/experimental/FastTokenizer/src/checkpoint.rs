@@ -0,0 +1,215 @@
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::dedup::{fnv1a_64_extend, FNV_OFFSET_BASIS};
+use crate::tokenizer_io::partial_path;
+
+/// Marks a JSON file as a `tokenize_corpus` checkpoint.
+const FORMAT: &str = "fasttokenizer-checkpoint";
+
+/// Newest checkpoint version this build reads, and the one it writes.
+/// Version 1 progress has no `prefix_hash`, so those files start over.
+const VERSION: u64 = 2;
+
+/// How far the tokenization of one input got.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes of the input, after decompression, tokenized so far. Always at
+    /// the end of a line.
+    pub offset: usize,
+    /// Lines of the input tokenized so far.
+    pub lines: usize,
+    /// Bytes of the partial output holding their tokens.
+    pub written: usize,
+    /// FNV-1a hash of the lines tokenized so far, to tell whether the input
+    /// is still the one the checkpoint describes.
+    pub prefix_hash: u64,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress {
+            offset: 0,
+            lines: 0,
+            written: 0,
+            prefix_hash: FNV_OFFSET_BASIS,
+        }
+    }
+}
+
+impl Progress {
+    /// Add one line of the input, without its terminator, to `prefix_hash`.
+    pub fn hash_line(&mut self, line: &[u8]) {
+        self.prefix_hash = fnv1a_64_extend(fnv1a_64_extend(self.prefix_hash, line), b"\n");
+    }
+
+    fn to_value(self) -> Value {
+        json!({
+            "offset": self.offset,
+            "lines": self.lines,
+            "written": self.written,
+            "prefix_hash": self.prefix_hash,
+        })
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        let field = |name: &str| value.get(name)?.as_u64();
+        Some(Progress {
+            offset: field("offset")? as usize,
+            lines: field("lines")? as usize,
+            written: field("written")? as usize,
+            prefix_hash: field("prefix_hash")?,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Manifest {
+    completed: BTreeSet<String>,
+    partial: BTreeMap<String, Progress>,
+}
+
+/// The progress manifest of a `tokenize_corpus` job, saved as JSON after
+/// every finished file and every `interval` within a file, so that a job
+/// that crashes resumes from its last save instead of from the start.
+pub struct Checkpoint {
+    path: PathBuf,
+    format: &'static str,
+    pub interval: Duration,
+    manifest: Mutex<Manifest>,
+}
+
+impl Checkpoint {
+    /// Load the checkpoint at `path` of a job writing `format` outputs, or
+    /// start an empty one if the file does not exist yet.
+    pub fn open(path: PathBuf, format: &'static str, interval: f64) -> PyResult<Self> {
+        let interval = Duration::try_from_secs_f64(interval).map_err(|_| {
+            PyValueError::new_err(format!(
+                "checkpoint_interval must be a non-negative number of seconds, got {}",
+                interval
+            ))
+        })?;
+        let manifest = match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&path, &text, format)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Manifest::default(),
+            Err(e) => {
+                return Err(PyIOError::new_err(format!(
+                    "Failed to read checkpoint {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        Ok(Checkpoint {
+            path,
+            format,
+            interval,
+            manifest: Mutex::new(manifest),
+        })
+    }
+
+    fn parse(path: &Path, text: &str, format: &str) -> PyResult<Manifest> {
+        let invalid = || {
+            PyValueError::new_err(format!(
+                "{} is not a fasttokenizer checkpoint",
+                path.display()
+            ))
+        };
+        let value: Value = serde_json::from_str(text).map_err(|_| invalid())?;
+        if value.get("format").and_then(Value::as_str) != Some(FORMAT) {
+            return Err(invalid());
+        }
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+        if version > VERSION {
+            return Err(PyValueError::new_err(format!(
+                "Unsupported checkpoint version {} in {}, expected at most {}",
+                version,
+                path.display(),
+                VERSION
+            )));
+        }
+        let written = value.get("output_format").and_then(Value::as_str);
+        if written != Some(format) {
+            return Err(PyValueError::new_err(format!(
+                "Checkpoint {} was written for format '{}', not '{}'",
+                path.display(),
+                written.unwrap_or_default(),
+                format
+            )));
+        }
+        let completed = value
+            .get("completed")
+            .and_then(Value::as_array)
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|input| input.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect::<PyResult<_>>()?;
+        let partial = value
+            .get("partial")
+            .and_then(Value::as_object)
+            .ok_or_else(invalid)?
+            .iter()
+            .filter(|_| version >= 2)
+            .map(|(input, progress)| {
+                let progress = Progress::from_value(progress).ok_or_else(invalid)?;
+                Ok((input.clone(), progress))
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(Manifest { completed, partial })
+    }
+
+    /// Whether `input` was fully written by an earlier run.
+    pub fn is_completed(&self, input: &str) -> bool {
+        self.manifest.lock().unwrap().completed.contains(input)
+    }
+
+    /// Where an earlier run left `input` off, if it saved any progress.
+    pub fn progress(&self, input: &str) -> Option<Progress> {
+        self.manifest.lock().unwrap().partial.get(input).copied()
+    }
+
+    /// Save the progress of `input`, whose partial output must already be
+    /// flushed to disk.
+    pub fn record(&self, input: &str, progress: Progress) -> io::Result<()> {
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.partial.insert(input.to_string(), progress);
+        self.save(&manifest)
+    }
+
+    /// Save `input` as fully written.
+    pub fn complete(&self, input: &str) -> io::Result<()> {
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.partial.remove(input);
+        manifest.completed.insert(input.to_string());
+        self.save(&manifest)
+    }
+
+    /// Replace the checkpoint file, so a crash mid-save leaves the last one.
+    fn save(&self, manifest: &Manifest) -> io::Result<()> {
+        let partial: serde_json::Map<String, Value> = manifest
+            .partial
+            .iter()
+            .map(|(input, progress)| (input.clone(), progress.to_value()))
+            .collect();
+        let value = json!({
+            "format": FORMAT,
+            "version": VERSION,
+            "output_format": self.format,
+            "completed": manifest.completed,
+            "partial": partial,
+        });
+        let temporary = partial_path(&self.path);
+        let mut file = File::create(&temporary)?;
+        serde_json::to_writer_pretty(&mut file, &value)?;
+        file.write_all(b"\n")?;
+        file.sync_all()?;
+        fs::rename(&temporary, &self.path)
+    }
+}
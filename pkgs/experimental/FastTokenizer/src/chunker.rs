@@ -0,0 +1,474 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+use crate::sentence_splitter::SentenceSplitter;
+use crate::tokenizer::{extract_tokenizer, offsets, Token, Tokenizer};
+
+type Chunks = Vec<(String, usize, usize)>;
+
+/// The tokens of a document, for counting the tokens of any span of it
+/// without tokenizing that span again.
+struct TokenIndex {
+    /// Byte spans into the document, ordered by start. Zero-width tokens,
+    /// such as special tokens a model adds around the input, are left out.
+    spans: Vec<(usize, usize)>,
+}
+
+impl TokenIndex {
+    fn new(tokenizer: &dyn Tokenizer, text: &str) -> Self {
+        let mut spans: Vec<_> = tokenizer
+            .tokenize_with_source_offsets(text)
+            .into_iter()
+            .filter(|t| t.start < t.end)
+            .map(|t| (t.start, t.end))
+            .collect();
+        spans.sort_by_key(|span| span.0);
+        TokenIndex { spans }
+    }
+
+    /// The tokens starting within `start..end`.
+    fn range(&self, start: usize, end: usize) -> Range<usize> {
+        self.spans.partition_point(|s| s.0 < start)..self.spans.partition_point(|s| s.0 < end)
+    }
+
+    fn count(&self, start: usize, end: usize) -> usize {
+        self.range(start, end).len()
+    }
+
+    /// Spans of consecutive windows of at most `size` of the tokens
+    /// starting within `start..end`, each sharing `overlap` tokens with the
+    /// one before.
+    fn windows(
+        &self,
+        text: &str,
+        (start, end): (usize, usize),
+        size: usize,
+        overlap: usize,
+    ) -> Vec<(usize, usize)> {
+        let tokens = &self.spans[self.range(start, end)];
+        let mut windows = Vec::new();
+        let mut first = 0;
+        while first < tokens.len() {
+            let last = (first + size).min(tokens.len());
+            windows.push(cover(text, &tokens[first..last]));
+            if last == tokens.len() {
+                break;
+            }
+            first += size - overlap;
+        }
+        windows
+    }
+}
+
+/// The span covering `spans`, widened to character boundaries for
+/// byte-level tokens.
+fn cover(text: &str, spans: &[(usize, usize)]) -> (usize, usize) {
+    let mut start = spans.iter().map(|s| s.0).min().unwrap_or(0);
+    let mut end = spans.iter().map(|s| s.1).max().unwrap_or(0);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// `start..end` without surrounding whitespace, or `None` if that is empty.
+fn trim(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let piece = &text[start..end];
+    let trimmed_start = start + (piece.len() - piece.trim_start().len());
+    let trimmed_end = start + piece.trim_end().len();
+    (trimmed_start < trimmed_end).then_some((trimmed_start, trimmed_end))
+}
+
+/// Chunks of `text` at the byte `spans`, with offsets in `unit`.
+fn to_chunks(text: &str, spans: Vec<(usize, usize)>, unit: &str) -> PyResult<Chunks> {
+    let tokens = spans
+        .into_iter()
+        .map(|(start, end)| Token::new(&text[start..end], start, end))
+        .collect();
+    offsets(text, tokens, unit)
+}
+
+fn check_chunk_size(chunk_size: usize) -> PyResult<()> {
+    if chunk_size == 0 {
+        return Err(PyValueError::new_err("chunk_size must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Splits documents into chunks of at most `chunk_size` tokens for
+/// retrieval, tokenizing each document only once.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct TokenChunker {
+    tokenizer: Arc<dyn Tokenizer>,
+    chunk_size: usize,
+    overlap: usize,
+}
+
+impl TokenChunker {
+    /// Byte spans of the chunks of `text`.
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        TokenIndex::new(&*self.tokenizer, text).windows(
+            text,
+            (0, text.len()),
+            self.chunk_size,
+            self.overlap,
+        )
+    }
+}
+
+#[pymethods]
+impl TokenChunker {
+    /// Create a chunker.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer, used to count tokens.
+    ///     chunk_size (int): Maximum number of tokens per chunk.
+    ///     overlap (int): Number of tokens each chunk shares with the
+    ///         previous one.
+    #[new]
+    #[pyo3(signature = (tokenizer, chunk_size=512, overlap=0))]
+    fn new(tokenizer: &Bound<'_, PyAny>, chunk_size: usize, overlap: usize) -> PyResult<Self> {
+        check_chunk_size(chunk_size)?;
+        if overlap >= chunk_size {
+            return Err(PyValueError::new_err(format!(
+                "overlap ({}) must be smaller than chunk_size ({})",
+                overlap, chunk_size
+            )));
+        }
+        Ok(TokenChunker {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            chunk_size,
+            overlap,
+        })
+    }
+
+    #[getter]
+    fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    #[getter]
+    fn overlap(&self) -> usize {
+        self.overlap
+    }
+
+    /// Split `text` into chunks.
+    ///
+    /// Each chunk runs from the start of its first token to the end of its
+    /// last, so whitespace between chunks is not part of either.
+    ///
+    /// Args:
+    ///     text (str): The document to split.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int]]: `(chunk, start, end)` into `text`.
+    #[pyo3(signature = (text, unit="char"))]
+    fn chunk(&self, text: &str, unit: &str) -> PyResult<Chunks> {
+        to_chunks(text, self.spans(text), unit)
+    }
+
+    /// Chunk several documents at once, releasing the GIL while working.
+    #[pyo3(signature = (texts, unit="char"))]
+    fn chunk_batch(&self, py: Python<'_>, texts: Vec<String>, unit: &str) -> PyResult<Vec<Chunks>> {
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| to_chunks(text, self.spans(text), unit))
+                .collect()
+        })
+    }
+}
+
+/// Packs whole sentences into chunks of at most `chunk_size` tokens. A
+/// sentence longer than that is split by tokens on its own.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct SentenceChunker {
+    tokenizer: Arc<dyn Tokenizer>,
+    splitter: SentenceSplitter,
+    chunk_size: usize,
+    overlap: usize,
+}
+
+impl SentenceChunker {
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let index = TokenIndex::new(&*self.tokenizer, text);
+        let sentences = self.splitter.sentences(text);
+        let fits =
+            |i: usize, j: usize| index.count(sentences[i].0, sentences[j].1) <= self.chunk_size;
+        let mut chunks = Vec::new();
+        let mut i = 0;
+        while i < sentences.len() {
+            if !fits(i, i) {
+                chunks.extend(index.windows(text, sentences[i], self.chunk_size, 0));
+                i += 1;
+                continue;
+            }
+            let mut j = i;
+            while j + 1 < sentences.len() && fits(i, j + 1) {
+                j += 1;
+            }
+            chunks.push((sentences[i].0, sentences[j].1));
+            // Repeat up to `overlap` sentences, as long as the next chunk
+            // still has room for a new one.
+            let mut next = (j + 1).saturating_sub(self.overlap).max(i + 1);
+            while next <= j && (j + 1 == sentences.len() || !fits(next, j + 1)) {
+                next += 1;
+            }
+            i = next;
+        }
+        chunks
+    }
+}
+
+#[pymethods]
+impl SentenceChunker {
+    /// Create a sentence chunker.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer, used to count tokens.
+    ///     chunk_size (int): Maximum number of tokens per chunk.
+    ///     overlap (int): Number of sentences each chunk repeats from the
+    ///         end of the previous one.
+    ///     splitter (SentenceSplitter, optional): Finds the sentences; the
+    ///         default splitter by default.
+    #[new]
+    #[pyo3(signature = (tokenizer, chunk_size=512, overlap=0, splitter=None))]
+    fn new(
+        tokenizer: &Bound<'_, PyAny>,
+        chunk_size: usize,
+        overlap: usize,
+        splitter: Option<PyRef<'_, SentenceSplitter>>,
+    ) -> PyResult<Self> {
+        check_chunk_size(chunk_size)?;
+        Ok(SentenceChunker {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            splitter: splitter.map(|s| s.clone()).unwrap_or_default(),
+            chunk_size,
+            overlap,
+        })
+    }
+
+    #[getter]
+    fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    #[getter]
+    fn overlap(&self) -> usize {
+        self.overlap
+    }
+
+    /// Split `text` into chunks of whole sentences.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int]]: `(chunk, start, end)` into `text`.
+    #[pyo3(signature = (text, unit="char"))]
+    fn chunk(&self, text: &str, unit: &str) -> PyResult<Chunks> {
+        to_chunks(text, self.spans(text), unit)
+    }
+
+    /// Chunk several documents at once, releasing the GIL while working.
+    #[pyo3(signature = (texts, unit="char"))]
+    fn chunk_batch(&self, py: Python<'_>, texts: Vec<String>, unit: &str) -> PyResult<Vec<Chunks>> {
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| to_chunks(text, self.spans(text), unit))
+                .collect()
+        })
+    }
+}
+
+/// How `RecursiveChunker` splits a piece that is too long.
+#[derive(Clone, Debug)]
+enum Separator {
+    /// Blank lines.
+    Paragraph,
+    Sentence,
+    /// Whitespace.
+    Word,
+    /// A literal string, kept at the end of the piece before it.
+    Literal(String),
+}
+
+fn paragraph_break() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\n[ \t\r]*\n\s*").expect("valid paragraph pattern"))
+}
+
+fn word() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\S+").expect("valid word pattern"))
+}
+
+/// Splits documents at the coarsest separator that gets pieces under
+/// `chunk_size` tokens (paragraphs, then sentences, then words, then
+/// tokens), merging neighbouring pieces back together while they fit.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct RecursiveChunker {
+    tokenizer: Arc<dyn Tokenizer>,
+    splitter: SentenceSplitter,
+    separators: Vec<Separator>,
+    chunk_size: usize,
+}
+
+impl RecursiveChunker {
+    /// Pieces of `text[start..end]` at `separator`, without surrounding
+    /// whitespace.
+    fn split(
+        &self,
+        text: &str,
+        (start, end): (usize, usize),
+        separator: &Separator,
+    ) -> Vec<(usize, usize)> {
+        let piece = &text[start..end];
+        let mut cuts = vec![0];
+        match separator {
+            Separator::Paragraph => {
+                cuts.extend(paragraph_break().find_iter(piece).map(|m| m.start()))
+            }
+            Separator::Sentence => cuts.extend(
+                self.splitter
+                    .sentences(piece)
+                    .into_iter()
+                    .skip(1)
+                    .map(|s| s.0),
+            ),
+            Separator::Word => cuts.extend(word().find_iter(piece).skip(1).map(|m| m.start())),
+            Separator::Literal(separator) => cuts.extend(
+                piece
+                    .match_indices(separator.as_str())
+                    .map(|(i, _)| i + separator.len()),
+            ),
+        }
+        cuts.push(piece.len());
+        cuts.windows(2)
+            .filter_map(|cut| trim(text, start + cut[0], start + cut[1]))
+            .collect()
+    }
+
+    fn chunk_span(
+        &self,
+        text: &str,
+        index: &TokenIndex,
+        span: (usize, usize),
+        level: usize,
+        chunks: &mut Vec<(usize, usize)>,
+    ) {
+        if index.count(span.0, span.1) <= self.chunk_size {
+            chunks.push(span);
+            return;
+        }
+        let Some(separator) = self.separators.get(level) else {
+            chunks.extend(index.windows(text, span, self.chunk_size, 0));
+            return;
+        };
+        let pieces = self.split(text, span, separator);
+        let mut current: Option<(usize, usize)> = None;
+        for piece in pieces {
+            if let Some(open) = current {
+                if index.count(open.0, piece.1) <= self.chunk_size {
+                    current = Some((open.0, piece.1));
+                    continue;
+                }
+                chunks.push(open);
+                current = None;
+            }
+            if index.count(piece.0, piece.1) <= self.chunk_size {
+                current = Some(piece);
+            } else {
+                self.chunk_span(text, index, piece, level + 1, chunks);
+            }
+        }
+        if let Some(span) = current {
+            chunks.push(span);
+        }
+    }
+
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let index = TokenIndex::new(&*self.tokenizer, text);
+        let mut chunks = Vec::new();
+        if let Some(span) = trim(text, 0, text.len()) {
+            self.chunk_span(text, &index, span, 0, &mut chunks);
+        }
+        chunks
+    }
+}
+
+#[pymethods]
+impl RecursiveChunker {
+    /// Create a recursive chunker.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer, used to count tokens.
+    ///     chunk_size (int): Maximum number of tokens per chunk.
+    ///     separators (List[str], optional): Literal separators to try in
+    ///         order, e.g. `["\n\n", "\n", ". ", " "]`. By default pieces
+    ///         are split into paragraphs, then sentences, then words.
+    ///     splitter (SentenceSplitter, optional): Finds sentences for the
+    ///         default separators.
+    #[new]
+    #[pyo3(signature = (tokenizer, chunk_size=512, separators=None, splitter=None))]
+    fn new(
+        tokenizer: &Bound<'_, PyAny>,
+        chunk_size: usize,
+        separators: Option<Vec<String>>,
+        splitter: Option<PyRef<'_, SentenceSplitter>>,
+    ) -> PyResult<Self> {
+        check_chunk_size(chunk_size)?;
+        let separators = match separators {
+            Some(separators) => {
+                if separators.iter().any(String::is_empty) {
+                    return Err(PyValueError::new_err(
+                        "separators must not be empty strings",
+                    ));
+                }
+                separators.into_iter().map(Separator::Literal).collect()
+            }
+            None => vec![Separator::Paragraph, Separator::Sentence, Separator::Word],
+        };
+        Ok(RecursiveChunker {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            splitter: splitter.map(|s| s.clone()).unwrap_or_default(),
+            separators,
+            chunk_size,
+        })
+    }
+
+    #[getter]
+    fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Split `text` into chunks at the coarsest separators that fit.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int]]: `(chunk, start, end)` into `text`.
+    #[pyo3(signature = (text, unit="char"))]
+    fn chunk(&self, text: &str, unit: &str) -> PyResult<Chunks> {
+        to_chunks(text, self.spans(text), unit)
+    }
+
+    /// Chunk several documents at once, releasing the GIL while working.
+    #[pyo3(signature = (texts, unit="char"))]
+    fn chunk_batch(&self, py: Python<'_>, texts: Vec<String>, unit: &str) -> PyResult<Vec<Chunks>> {
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| to_chunks(text, self.spans(text), unit))
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,276 @@
+use fasttokenizer_core::cjk::is_unspaced;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::HashSet;
+use std::fs;
+
+use crate::intern::token_list;
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// Letters, digits and combining marks continue a word; everything else
+/// that is not whitespace or CJK stands alone.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || unicode_normalization::char::is_combining_mark(c)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Space,
+    Unspaced,
+    Word,
+    Other,
+}
+
+fn classify(c: char) -> Class {
+    if c.is_whitespace() {
+        Class::Space
+    } else if is_unspaced(c) {
+        Class::Unspaced
+    } else if is_word_char(c) {
+        Class::Word
+    } else {
+        Class::Other
+    }
+}
+
+/// Segments Chinese and Japanese text into single characters, or into
+/// dictionary words by forward maximum matching when a dictionary is given,
+/// while splitting Latin and other spaced scripts into words.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone, Default)]
+pub struct CjkTokenizer {
+    dictionary: Shared<Dictionary>,
+    vocab: Shared<Option<Vocab>>,
+}
+
+/// Words kept whole when segmenting CJK runs.
+#[derive(Clone, Default)]
+struct Dictionary {
+    words: HashSet<String>,
+    /// Length in characters of the longest word.
+    max_word_chars: usize,
+}
+
+impl Dictionary {
+    /// Split a run of CJK characters starting at byte `base`.
+    fn segment_run(&self, run: &str, base: usize, spans: &mut Vec<(usize, usize)>) {
+        let bounds: Vec<usize> = run
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(run.len()))
+            .collect();
+        let chars = bounds.len() - 1;
+        let mut i = 0;
+        while i < chars {
+            let longest = (2..=self.max_word_chars.min(chars - i))
+                .rev()
+                .find(|&n| self.words.contains(&run[bounds[i]..bounds[i + n]]))
+                .unwrap_or(1);
+            let (start, end) = (bounds[i], bounds[i + longest]);
+            spans.push((base + start, base + end));
+            i += longest;
+        }
+    }
+}
+
+impl CjkTokenizer {
+    fn with_words(words: impl IntoIterator<Item = String>, vocab: Option<Vocab>) -> Self {
+        let tokenizer = CjkTokenizer {
+            vocab: vocab.into(),
+            ..Default::default()
+        };
+        tokenizer.insert(words);
+        tokenizer
+    }
+
+    fn insert(&self, words: impl IntoIterator<Item = String>) {
+        self.dictionary.update(|dictionary| {
+            for word in words {
+                let word = word.trim();
+                if word.is_empty() {
+                    continue;
+                }
+                dictionary.max_word_chars = dictionary.max_word_chars.max(word.chars().count());
+                dictionary.words.insert(word.to_string());
+            }
+        });
+    }
+}
+
+impl Tokenizer for CjkTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.token_spans(text)
+            .into_iter()
+            .map(|(start, end)| Token::new(&text[start..end], start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let dictionary = self.dictionary.load();
+        let mut spans = Vec::new();
+        let mut run: Option<(Class, usize)> = None;
+        let flush =
+            |run: Option<(Class, usize)>, end: usize, spans: &mut Vec<(usize, usize)>| match run {
+                Some((Class::Unspaced, start)) => {
+                    dictionary.segment_run(&text[start..end], start, spans)
+                }
+                Some((Class::Word, start)) => spans.push((start, end)),
+                _ => {}
+            };
+        for (i, c) in text.char_indices() {
+            let class = classify(c);
+            if run.is_some_and(|(current, _)| current == class && class != Class::Other) {
+                continue;
+            }
+            flush(run.take(), i, &mut spans);
+            match class {
+                Class::Space => {}
+                Class::Other => spans.push((i, i + c.len_utf8())),
+                _ => run = Some((class, i)),
+            }
+        }
+        flush(run, text.len(), &mut spans);
+        spans
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+}
+
+tokenizer_pymethods!(CjkTokenizer, encode_batch);
+
+#[pymethods]
+impl CjkTokenizer {
+    /// Create a CJK-aware tokenizer.
+    ///
+    /// Args:
+    ///     dictionary (Iterable[str], optional): Words to keep together in
+    ///         Chinese and Japanese text. Without one, every CJK character is
+    ///         a token.
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    #[new]
+    #[pyo3(signature = (dictionary=None, vocab=None))]
+    fn new(dictionary: Option<Vec<String>>, vocab: Option<PyRef<'_, Vocab>>) -> Self {
+        Self::with_words(dictionary.into_iter().flatten(), vocab.map(|v| v.clone()))
+    }
+
+    /// Load the dictionary from a text file with one word per line. Only the
+    /// first field of each line is used, so jieba-style `word freq tag`
+    /// dictionaries work as they are.
+    #[staticmethod]
+    #[pyo3(signature = (path, vocab=None))]
+    fn from_dictionary_file(path: &str, vocab: Option<PyRef<'_, Vocab>>) -> PyResult<Self> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read dictionary file: {}", e)))?;
+        let words = data
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_string);
+        Ok(Self::with_words(words, vocab.map(|v| v.clone())))
+    }
+
+    /// Add words to the dictionary.
+    fn add_words(&self, words: Vec<String>) {
+        self.insert(words);
+    }
+
+    /// Number of words in the dictionary.
+    #[getter]
+    fn dictionary_size(&self) -> usize {
+        self.dictionary.load().words.len()
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        require_vocab(&self.vocab)?.encode_text(self, input)
+    }
+
+    /// Turn ids back into text, joining tokens with spaces except next to
+    /// Chinese and Japanese characters.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        let tokens = require_vocab(&self.vocab)?
+            .decode_ids(&ids)
+            .map_err(PyValueError::new_err)?;
+        let mut text = String::new();
+        for token in tokens {
+            let unspaced = |c: Option<char>| c.is_none_or(is_unspaced);
+            if !unspaced(text.chars().last()) && !unspaced(token.chars().next()) {
+                text.push(' ');
+            }
+            text.push_str(&token);
+        }
+        Ok(text)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
+    }
+}
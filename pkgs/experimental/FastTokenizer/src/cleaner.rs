@@ -0,0 +1,217 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use std::sync::OnceLock;
+
+use crate::markup::strip_html;
+use crate::mojibake::TextFixes;
+use crate::normalizer::{collapse_whitespace, normalize_spaces, remove_control_chars, Form};
+use crate::tokenizer::py_err;
+
+/// Lines that are boilerplate on any site: cookie banners, navigation,
+/// sharing buttons and footers.
+const BOILERPLATE: &[&str] = &[
+    r"\b(?:we|this (?:web)?site) uses? cookies\b",
+    r"\baccept (?:all )?cookies\b",
+    r"\bcookie (?:policy|settings|preferences)\b",
+    r"\ball rights reserved\b",
+    r"^(?:©|\(c\)|copyright\b)",
+    r"^(?:privacy policy|terms (?:of (?:use|service)|and conditions))\b",
+    r"^skip to (?:main )?content\b",
+    r"\b(?:subscribe|sign up) (?:to|for) (?:our|the) newsletter\b",
+    r"^share (?:this|on)\b",
+    r"^(?:log ?in|sign ?in|sign ?up|register)(?: \| (?:log ?in|sign ?in|sign ?up|register))*$",
+    r"^(?:read more|continue reading|click here|back to top)\W*$",
+    r"\bjavascript (?:is )?(?:disabled|required)\b",
+    r"\benable javascript\b",
+];
+
+fn builtin_boilerplate() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| compile(BOILERPLATE).expect("valid boilerplate patterns"))
+}
+
+/// One case-insensitive regex matching any of `patterns`.
+fn compile(patterns: &[impl AsRef<str>]) -> Result<Regex, regex::Error> {
+    let alternatives: Vec<String> = patterns
+        .iter()
+        .map(|p| format!("(?:{})", p.as_ref()))
+        .collect();
+    RegexBuilder::new(&alternatives.join("|"))
+        .case_insensitive(true)
+        .build()
+}
+
+/// Whether `line` reads like the end of a sentence rather than a menu item
+/// or button label.
+fn ends_sentence(line: &str) -> bool {
+    line.ends_with(['.', '!', '?', '"', '”', '…', ':', '。', '！', '？'])
+}
+
+/// A ready-made cleaning chain for text scraped from the web.
+///
+/// In order: HTML stripping (block tags become line breaks), mojibake
+/// repair, control character removal, space and Unicode normalization,
+/// whitespace collapsing within lines and boilerplate removal. Boilerplate
+/// lines are those matching a built-in list of cookie, navigation, sharing
+/// and footer phrases (plus any extra `boilerplate_patterns`) and short
+/// lines that do not end like a sentence, such as menu entries. Every step
+/// can be turned off.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct WebTextCleaner {
+    strip_html: bool,
+    fix_encoding: bool,
+    remove_control: bool,
+    normalize_spaces: bool,
+    form: Option<Form>,
+    remove_boilerplate: bool,
+    min_words: usize,
+    patterns: Vec<String>,
+    extra: Option<Regex>,
+}
+
+impl WebTextCleaner {
+    fn is_boilerplate(&self, line: &str) -> bool {
+        builtin_boilerplate().is_match(line)
+            || self.extra.as_ref().is_some_and(|re| re.is_match(line))
+            || (line.split_whitespace().count() < self.min_words && !ends_sentence(line))
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = if self.strip_html {
+            strip_html(text)
+        } else {
+            text.to_string()
+        };
+        if self.fix_encoding {
+            out = TextFixes::ALL.apply(&out).into_owned();
+        }
+        if self.remove_control {
+            out = remove_control_chars(&out).into_owned();
+        }
+        if self.normalize_spaces {
+            out = normalize_spaces(&out).into_owned();
+        }
+        if let Some(form) = self.form {
+            out = form.apply(&out).into_owned();
+        }
+        let mut cleaned = String::with_capacity(out.len());
+        for line in out.lines() {
+            let line = collapse_whitespace(line, false);
+            let line = line.trim();
+            if line.is_empty() || (self.remove_boilerplate && self.is_boilerplate(line)) {
+                continue;
+            }
+            if !cleaned.is_empty() {
+                cleaned.push('\n');
+            }
+            cleaned.push_str(line);
+        }
+        cleaned
+    }
+}
+
+#[pymethods]
+impl WebTextCleaner {
+    /// Create a web text cleaner; the defaults suit scraped HTML pages.
+    ///
+    /// Args:
+    ///     strip_html (bool): Remove tags, scripts and styles and decode
+    ///         entities; see `strip_html`.
+    ///     fix_encoding (bool): Repair mojibake and encoding leftovers; see
+    ///         `fix_text`.
+    ///     remove_control (bool): See `remove_control_chars`.
+    ///     normalize_spaces (bool): See `normalize_spaces`.
+    ///     form (str, optional): Unicode normalization form, or `None` to
+    ///         skip it.
+    ///     remove_boilerplate (bool): Drop boilerplate lines.
+    ///     min_words (int): Lines with fewer words are dropped as
+    ///         boilerplate unless they end like a sentence.
+    ///     boilerplate_patterns (List[str], optional): Extra regexes, matched
+    ///         case-insensitively against each line, marking boilerplate.
+    #[new]
+    #[pyo3(signature = (
+        strip_html=true,
+        fix_encoding=true,
+        remove_control=true,
+        normalize_spaces=true,
+        form=Some("NFC"),
+        remove_boilerplate=true,
+        min_words=3,
+        boilerplate_patterns=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        strip_html: bool,
+        fix_encoding: bool,
+        remove_control: bool,
+        normalize_spaces: bool,
+        form: Option<&str>,
+        remove_boilerplate: bool,
+        min_words: usize,
+        boilerplate_patterns: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let patterns = boilerplate_patterns.unwrap_or_default();
+        let extra = if patterns.is_empty() {
+            None
+        } else {
+            Some(compile(&patterns).map_err(|e| {
+                PyValueError::new_err(format!("Invalid boilerplate pattern: {}", e))
+            })?)
+        };
+        Ok(WebTextCleaner {
+            strip_html,
+            fix_encoding,
+            remove_control,
+            normalize_spaces,
+            form: form.map(Form::parse).transpose().map_err(py_err)?,
+            remove_boilerplate,
+            min_words,
+            patterns,
+            extra,
+        })
+    }
+
+    /// Constructor arguments recreating this cleaner, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("strip_html", self.strip_html)?;
+        kwargs.set_item("fix_encoding", self.fix_encoding)?;
+        kwargs.set_item("remove_control", self.remove_control)?;
+        kwargs.set_item("normalize_spaces", self.normalize_spaces)?;
+        kwargs.set_item("form", self.form.map(Form::name))?;
+        kwargs.set_item("remove_boilerplate", self.remove_boilerplate)?;
+        kwargs.set_item("min_words", self.min_words)?;
+        kwargs.set_item("boilerplate_patterns", &self.patterns)?;
+        Ok(((), kwargs))
+    }
+
+    /// Clean one document, releasing the GIL while working.
+    ///
+    /// Returns:
+    ///     str: The kept lines, trimmed and joined by `"\n"`.
+    fn clean(&self, py: Python<'_>, text: &str) -> String {
+        py.detach(|| self.apply(text))
+    }
+
+    /// `clean` for many documents at once, in parallel without the GIL.
+    fn clean_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<String> {
+        py.detach(|| texts.par_iter().map(|text| self.apply(text)).collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WebTextCleaner(remove_boilerplate={}, min_words={})",
+            if self.remove_boilerplate {
+                "True"
+            } else {
+                "False"
+            },
+            self.min_words
+        )
+    }
+}
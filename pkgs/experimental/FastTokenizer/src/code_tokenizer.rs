@@ -0,0 +1,503 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::config;
+use crate::intern::token_list;
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// Operators of two or more characters, longest first so the scanner can
+/// take the first prefix that matches.
+const OPERATORS: &[&str] = &[
+    ">>>=", "<<=", ">>=", "**=", "//=", "...", "===", "!==", "<=>", ">>>", "::", "->", "=>", "==",
+    "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<",
+    ">>", "**", "//", "..", "?.", "??", ":=",
+];
+
+/// Single characters that are operators; other ASCII punctuation is `punct`.
+const OPERATOR_CHARS: &str = "+-*/%=<>!&|^~?:@";
+
+/// The lexical syntax a language family shares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Syntax {
+    name: &'static str,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// `'''` and `"""` strings spanning lines.
+    triple_quotes: bool,
+    /// Backtick strings spanning lines (JavaScript templates, Go raw strings).
+    backticks: bool,
+    /// `'a` is a Rust lifetime or label, not the start of a character literal.
+    lifetimes: bool,
+    /// `r"..."`, `b'...'`, `f"..."` and similar prefixed strings.
+    string_prefixes: bool,
+}
+
+const C_LIKE: Syntax = Syntax {
+    name: "c",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    triple_quotes: false,
+    backticks: true,
+    lifetimes: false,
+    string_prefixes: false,
+};
+
+impl Syntax {
+    fn parse(language: &str) -> PyResult<Self> {
+        let syntax = match language.to_ascii_lowercase().as_str() {
+            "generic" => Syntax {
+                name: "generic",
+                line_comments: &["//", "#"],
+                ..C_LIKE
+            },
+            "c" | "cpp" | "c++" | "csharp" | "c#" | "java" | "javascript" | "js" | "typescript"
+            | "ts" | "go" | "kotlin" | "swift" | "scala" | "php" => C_LIKE,
+            "rust" => Syntax {
+                name: "rust",
+                backticks: false,
+                lifetimes: true,
+                string_prefixes: true,
+                ..C_LIKE
+            },
+            "python" => Syntax {
+                name: "python",
+                line_comments: &["#"],
+                block_comment: None,
+                triple_quotes: true,
+                backticks: false,
+                lifetimes: false,
+                string_prefixes: true,
+            },
+            "shell" | "bash" | "ruby" | "perl" | "r" | "yaml" | "toml" => Syntax {
+                name: "shell",
+                line_comments: &["#"],
+                block_comment: None,
+                backticks: false,
+                ..C_LIKE
+            },
+            "sql" => Syntax {
+                name: "sql",
+                line_comments: &["--"],
+                backticks: false,
+                ..C_LIKE
+            },
+            "lua" => Syntax {
+                name: "lua",
+                line_comments: &["--"],
+                block_comment: Some(("--[[", "]]")),
+                backticks: false,
+                ..C_LIKE
+            },
+            "haskell" => Syntax {
+                name: "haskell",
+                line_comments: &["--"],
+                block_comment: Some(("{-", "-}")),
+                backticks: false,
+                ..C_LIKE
+            },
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown language '{}', expected e.g. 'generic', 'c', 'rust', 'python', 'shell', 'sql', 'lua' or 'haskell'",
+                    other
+                )))
+            }
+        };
+        Ok(syntax)
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Byte ranges of the words of an identifier: `parseHTTPResponse2` gives
+/// `parse`, `HTTP`, `Response`, `2` and `max_len` gives `max`, `len`.
+fn split_identifier(ident: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = ident.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    for (k, &(i, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '$' {
+            if let Some(s) = start.take() {
+                parts.push((s, i));
+            }
+            continue;
+        }
+        if let Some(s) = start {
+            let prev = chars[k - 1].1;
+            let next = chars.get(k + 1).map(|&(_, n)| n);
+            let boundary = (prev.is_lowercase() && c.is_uppercase())
+                || (prev.is_uppercase()
+                    && c.is_uppercase()
+                    && next.is_some_and(char::is_lowercase))
+                || (prev.is_numeric() != c.is_numeric());
+            if boundary {
+                parts.push((s, i));
+                start = Some(i);
+            }
+        } else {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        parts.push((s, ident.len()));
+    }
+    parts
+}
+
+/// Tokenizer for source code that keeps string literals and comments whole,
+/// splits identifiers, numbers and operators apart and can optionally break
+/// identifiers into their camelCase and snake_case words.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct CodeTokenizer {
+    syntax: Syntax,
+    split_identifiers: bool,
+    keep_comments: bool,
+    keep_strings: bool,
+    vocab: Shared<Option<Vocab>>,
+}
+
+impl CodeTokenizer {
+    /// Spans of the tokens of `text` with their type labels.
+    fn scan(&self, text: &str) -> Vec<(usize, usize, &'static str)> {
+        let syntax = &self.syntax;
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        while let Some(c) = text[pos..].chars().next() {
+            let rest = &text[pos..];
+            if c.is_whitespace() {
+                pos += c.len_utf8();
+                continue;
+            }
+            let (len, kind) = if let Some(len) = self.comment_len(rest) {
+                (len, "comment")
+            } else if let Some(len) = self.string_len(rest) {
+                (len, "string")
+            } else if c.is_ascii_digit()
+                || (c == '.' && rest[1..].starts_with(|d: char| d.is_ascii_digit()))
+            {
+                (number_len(rest), "number")
+            } else if is_ident_start(c) {
+                (
+                    rest.find(|c| !is_ident_continue(c)).unwrap_or(rest.len()),
+                    "identifier",
+                )
+            } else if syntax.lifetimes && c == '\'' {
+                // A Rust lifetime or label; character literals were taken above.
+                let name = rest[1..]
+                    .find(|c| !is_ident_continue(c))
+                    .unwrap_or(rest.len() - 1);
+                (1 + name, if name > 0 { "identifier" } else { "punct" })
+            } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                (op.len(), "operator")
+            } else if OPERATOR_CHARS.contains(c) {
+                (1, "operator")
+            } else {
+                (c.len_utf8(), "punct")
+            };
+            let end = pos + len;
+            let keep = match kind {
+                "comment" => self.keep_comments,
+                "string" => self.keep_strings,
+                _ => true,
+            };
+            if kind == "identifier" && self.split_identifiers {
+                for (s, e) in split_identifier(&text[pos..end]) {
+                    tokens.push((pos + s, pos + e, kind));
+                }
+            } else if keep {
+                tokens.push((pos, end, kind));
+            }
+            pos = end;
+        }
+        tokens
+    }
+
+    /// Length of the comment starting `rest`, if one does.
+    fn comment_len(&self, rest: &str) -> Option<usize> {
+        if let Some((open, close)) = self.syntax.block_comment {
+            if let Some(inner) = rest.strip_prefix(open) {
+                return Some(
+                    inner
+                        .find(close)
+                        .map_or(rest.len(), |i| open.len() + i + close.len()),
+                );
+            }
+        }
+        self.syntax
+            .line_comments
+            .iter()
+            .any(|marker| rest.starts_with(marker))
+            .then(|| rest.find('\n').unwrap_or(rest.len()))
+    }
+
+    /// Length of the string literal starting `rest`, if one does. Quotes
+    /// that are not closed on the same line are left to the other rules.
+    fn string_len(&self, rest: &str) -> Option<usize> {
+        let syntax = &self.syntax;
+        let prefix = if syntax.string_prefixes {
+            rest.find(|c: char| !matches!(c, 'r' | 'R' | 'b' | 'B' | 'f' | 'F' | 'u' | 'U'))
+                .filter(|&n| n <= 2)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let body = &rest[prefix..];
+        let quote = body.chars().next()?;
+        if syntax.triple_quotes {
+            for triple in ["\"\"\"", "'''"] {
+                if let Some(inner) = body.strip_prefix(triple) {
+                    let end = find_closing(inner, triple, true).unwrap_or(inner.len());
+                    return Some(prefix + 3 + end);
+                }
+            }
+        }
+        let raw = prefix > 0 && rest[..prefix].contains(['r', 'R']);
+        let end = match quote {
+            '"' => find_closing(&body[1..], "\"", !raw)?,
+            '\'' => {
+                let end = find_closing(&body[1..], "'", !raw)?;
+                // In Rust only short literals such as 'a' or '\n' are characters.
+                if syntax.lifetimes
+                    && !body[1..].starts_with('\\')
+                    && body[1..end].chars().count() > 1
+                {
+                    return None;
+                }
+                end
+            }
+            '`' if syntax.backticks && prefix == 0 => {
+                find_closing(&body[1..], "`", true).unwrap_or(body.len() - 1)
+            }
+            _ => return None,
+        };
+        Some(prefix + 1 + end)
+    }
+}
+
+/// Offset just past the closing `quote` in `text`, skipping escaped
+/// characters. Only multi-line quotes may cross a line break.
+fn find_closing(text: &str, quote: &str, escapes: bool) -> Option<usize> {
+    let multiline = quote.len() > 1 || quote == "`";
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if text[i..].starts_with(quote) {
+            return Some(i + quote.len());
+        }
+        match c {
+            '\\' if escapes => {
+                chars.next();
+            }
+            '\n' if !multiline => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Length of the number literal starting `rest`: digits with `_`
+/// separators, hex and binary prefixes, fractions, exponents and type
+/// suffixes such as `10u32` or `1.5f`.
+fn number_len(rest: &str) -> usize {
+    let bytes = rest.as_bytes();
+    let mut i = 0;
+    let hex = rest.starts_with("0x") || rest.starts_with("0X");
+    while let Some(&b) = bytes.get(i) {
+        let exponent_sign = matches!(b, b'+' | b'-')
+            && !hex
+            && i > 0
+            && matches!(bytes[i - 1], b'e' | b'E')
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        let fraction = b == b'.'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && !bytes[..i].contains(&b'.');
+        if b.is_ascii_alphanumeric() || b == b'_' || exponent_sign || fraction {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    i.max(1)
+}
+
+impl Tokenizer for CodeTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.scan(text)
+            .into_iter()
+            .map(|(start, end, _)| Token::new(&text[start..end], start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.scan(text)
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+}
+
+tokenizer_pymethods!(CodeTokenizer, encode_batch);
+
+#[pymethods]
+impl CodeTokenizer {
+    /// Create a source code tokenizer.
+    ///
+    /// Args:
+    ///     language (str): Comment and string syntax to follow: `"generic"`
+    ///         (`//`, `/* */` and `#` comments), `"c"` (also C++, Java,
+    ///         JavaScript, TypeScript, Go, ...), `"rust"`, `"python"`,
+    ///         `"shell"`, `"sql"`, `"lua"` or `"haskell"`.
+    ///     split_identifiers (bool): Break identifiers into their camelCase
+    ///         and snake_case words, e.g. `getHTTPResponse` into `get`,
+    ///         `HTTP` and `Response`.
+    ///     keep_comments (bool): Emit comments as tokens instead of dropping them.
+    ///     keep_strings (bool): Emit string literals as tokens instead of
+    ///         dropping them.
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    #[new]
+    #[pyo3(signature = (language="generic", split_identifiers=false, keep_comments=true, keep_strings=true, vocab=None))]
+    fn new(
+        language: &str,
+        split_identifiers: bool,
+        keep_comments: bool,
+        keep_strings: bool,
+        vocab: Option<PyRef<'_, Vocab>>,
+    ) -> PyResult<Self> {
+        Ok(CodeTokenizer {
+            syntax: Syntax::parse(language)?,
+            split_identifiers,
+            keep_comments,
+            keep_strings,
+            vocab: vocab.map(|v| v.clone()).into(),
+        })
+    }
+
+    /// Constructor arguments recreating this tokenizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("language", self.syntax.name)?;
+        kwargs.set_item("split_identifiers", self.split_identifiers)?;
+        kwargs.set_item("keep_comments", self.keep_comments)?;
+        kwargs.set_item("keep_strings", self.keep_strings)?;
+        kwargs.set_item("vocab", Option::clone(&self.vocab.load()))?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the tokenizer's configuration to JSON.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a tokenizer from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
+    }
+
+    /// The language family whose syntax is followed.
+    #[getter]
+    fn language(&self) -> &'static str {
+        self.syntax.name
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, type)` pairs, where the type is one of
+    /// `identifier`, `number`, `string`, `comment`, `operator` or `punct`.
+    fn tokenize_with_types(&self, input: &str) -> Vec<(String, &'static str)> {
+        self.scan(input)
+            .into_iter()
+            .map(|(start, end, kind)| (input[start..end].to_string(), kind))
+            .collect()
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        require_vocab(&self.vocab)?.encode_text(self, input)
+    }
+
+    /// Turn ids back into text, joining the tokens with single spaces.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        require_vocab(&self.vocab)?.decode_text(&ids)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
+    }
+}
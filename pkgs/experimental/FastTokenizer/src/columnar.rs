@@ -0,0 +1,256 @@
+use arrow_array::builder::{ListBuilder, StringBuilder, UInt32Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use pyo3::exceptions::{PyIOError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use std::fs::File;
+use std::sync::Arc;
+
+/// Columnar file formats that tokenized rows can be written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Parquet(Compression),
+    ArrowIpc,
+}
+
+impl Format {
+    /// Parquet with the named page compression.
+    pub fn parquet(compression: &str) -> PyResult<Self> {
+        let compression = match compression {
+            "none" => Compression::UNCOMPRESSED,
+            "snappy" => Compression::SNAPPY,
+            "zstd" => Compression::ZSTD(ZstdLevel::default()),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown compression '{}', expected 'snappy', 'zstd' or 'none'",
+                    other
+                )))
+            }
+        };
+        Ok(Format::Parquet(compression))
+    }
+}
+
+/// One row: the tokens or the ids of a document.
+#[derive(FromPyObject)]
+enum Row {
+    Tokens(Vec<String>),
+    Ids(Vec<u32>),
+}
+
+impl Row {
+    fn is_empty(&self) -> bool {
+        match self {
+            Row::Tokens(tokens) => tokens.is_empty(),
+            Row::Ids(ids) => ids.is_empty(),
+        }
+    }
+}
+
+/// Whether the written column holds strings or ids, fixed by the first
+/// non-empty row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Tokens,
+    Ids,
+}
+
+impl Kind {
+    fn default_column(self) -> &'static str {
+        match self {
+            Kind::Tokens => "tokens",
+            Kind::Ids => "input_ids",
+        }
+    }
+}
+
+enum Sink {
+    Parquet(ArrowWriter<File>),
+    ArrowIpc(FileWriter<File>),
+}
+
+impl Sink {
+    fn open(format: Format, file: File, schema: SchemaRef) -> PyResult<Self> {
+        Ok(match format {
+            Format::Parquet(compression) => {
+                let props = WriterProperties::builder()
+                    .set_compression(compression)
+                    .build();
+                Sink::Parquet(
+                    ArrowWriter::try_new(file, schema, Some(props)).map_err(parquet_error)?,
+                )
+            }
+            Format::ArrowIpc => {
+                Sink::ArrowIpc(FileWriter::try_new(file, &schema).map_err(arrow_error)?)
+            }
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> PyResult<()> {
+        match self {
+            Sink::Parquet(writer) => writer.write(batch).map_err(parquet_error),
+            Sink::ArrowIpc(writer) => writer.write(batch).map_err(arrow_error),
+        }
+    }
+
+    fn close(self) -> PyResult<()> {
+        match self {
+            Sink::Parquet(writer) => writer.close().map(|_| ()).map_err(parquet_error),
+            Sink::ArrowIpc(mut writer) => writer.finish().map_err(arrow_error),
+        }
+    }
+}
+
+fn parquet_error(e: ParquetError) -> PyErr {
+    PyIOError::new_err(format!("Failed to write Parquet file: {}", e))
+}
+
+fn arrow_error(e: ArrowError) -> PyErr {
+    PyIOError::new_err(format!("Failed to write Arrow file: {}", e))
+}
+
+/// Streams rows into a single list column, one record batch (or Parquet row
+/// group) every `batch_rows` rows.
+struct RowWriter {
+    format: Format,
+    file: Option<File>,
+    column: Option<String>,
+    batch_rows: usize,
+    kind: Option<Kind>,
+    pending: Vec<Row>,
+    sink: Option<Sink>,
+    rows: usize,
+}
+
+impl RowWriter {
+    fn push(&mut self, py: Python<'_>, row: Row) -> PyResult<()> {
+        let kind = match row {
+            Row::Tokens(_) => Kind::Tokens,
+            Row::Ids(_) => Kind::Ids,
+        };
+        if !row.is_empty() {
+            match self.kind {
+                None => self.kind = Some(kind),
+                Some(expected) if expected != kind => {
+                    return Err(PyTypeError::new_err(format!(
+                        "Row {} mixes token strings and ids",
+                        self.rows + self.pending.len()
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+        self.pending.push(row);
+        // Leading empty rows are held back until the column type is known.
+        if self.pending.len() >= self.batch_rows && self.kind.is_some() {
+            self.flush(py)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, py: Python<'_>) -> PyResult<()> {
+        let kind = self.kind.unwrap_or(Kind::Tokens);
+        let rows = std::mem::take(&mut self.pending);
+        let count = rows.len();
+        let array = build_array(kind, rows);
+        let name = self
+            .column
+            .as_deref()
+            .unwrap_or_else(|| kind.default_column());
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            name,
+            array.data_type().clone(),
+            false,
+        )]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![array]).map_err(arrow_error)?;
+        let sink = match (self.sink.take(), self.file.take()) {
+            (Some(sink), _) => sink,
+            (None, Some(file)) => Sink::open(self.format, file, schema)?,
+            (None, None) => unreachable!("the file is kept until the sink is opened"),
+        };
+        let sink = self.sink.insert(sink);
+        py.detach(|| sink.write(&batch))?;
+        self.rows += count;
+        Ok(())
+    }
+
+    fn finish(mut self, py: Python<'_>) -> PyResult<usize> {
+        if !self.pending.is_empty() || self.sink.is_none() {
+            self.flush(py)?;
+        }
+        if let Some(sink) = self.sink.take() {
+            py.detach(|| sink.close())?;
+        }
+        Ok(self.rows)
+    }
+}
+
+fn build_array(kind: Kind, rows: Vec<Row>) -> ArrayRef {
+    match kind {
+        Kind::Tokens => {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            for row in rows {
+                if let Row::Tokens(tokens) = row {
+                    for token in tokens {
+                        builder.values().append_value(token);
+                    }
+                }
+                builder.append(true);
+            }
+            Arc::new(builder.finish())
+        }
+        Kind::Ids => {
+            let mut builder = ListBuilder::new(UInt32Builder::new());
+            for row in rows {
+                if let Row::Ids(ids) = row {
+                    builder.values().append_slice(&ids);
+                }
+                builder.append(true);
+            }
+            Arc::new(builder.finish())
+        }
+    }
+}
+
+/// Write every row of the iterable `rows` to `output` as a single list
+/// column, returning the number of rows written.
+///
+/// Rows are token lists (a `List<Utf8>` column named `tokens`) or id lists
+/// (a `List<UInt32>` column named `input_ids`); `column` overrides the name.
+pub fn write_rows(
+    py: Python<'_>,
+    output: &str,
+    rows: &Bound<'_, PyAny>,
+    column: Option<String>,
+    batch_rows: usize,
+    format: Format,
+) -> PyResult<usize> {
+    let file = File::create(output)
+        .map_err(|e| PyIOError::new_err(format!("Failed to create file: {}", e)))?;
+    let mut writer = RowWriter {
+        format,
+        file: Some(file),
+        column,
+        batch_rows: batch_rows.max(1),
+        kind: None,
+        pending: Vec::new(),
+        sink: None,
+        rows: 0,
+    };
+    for row in rows.try_iter()? {
+        let row = row?;
+        let Ok(row) = row.extract::<Row>() else {
+            return Err(PyTypeError::new_err(format!(
+                "Expected a list of str or a list of int, got {}",
+                row.get_type().name()?
+            )));
+        };
+        writer.push(py, row)?;
+    }
+    writer.finish(py)
+}
@@ -0,0 +1,250 @@
+use pyo3::exceptions::{PyIOError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple, PyType};
+use pyo3::PyClass;
+use serde_json::{Map, Value};
+
+use crate::blocklist::BlocklistFilter;
+use crate::bpe::BpeTokenizer;
+use crate::code_tokenizer::CodeTokenizer;
+use crate::filters::{LengthFilter, NumericFilter, RegexFilter};
+use crate::grapheme_tokenizer::GraphemeTokenizer;
+use crate::hf_tokenizer::HfTokenizer;
+use crate::normalizer::Normalizer;
+use crate::phrases::PhraseMerger;
+use crate::pipeline::TokenizerPipeline;
+use crate::regex_tokenizer::RegexTokenizer;
+use crate::sentence_splitter::SentenceSplitter;
+use crate::sentencepiece::SentencePieceTokenizer;
+use crate::social_tokenizer::SocialTokenizer;
+use crate::special_tokens::SpecialTokens;
+use crate::stopwords::StopWordFilter;
+use crate::tiktoken::TiktokenEncoder;
+use crate::truecase::Truecaser;
+use crate::vocab::Vocab;
+use crate::whitespace_tokenizer::WhitespaceTokenizer;
+use crate::wordpiece::WordPieceTokenizer;
+
+/// The classes a configuration may name in its `"type"` fields.
+fn class<'py>(py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyType>> {
+    Ok(match name {
+        "BlocklistFilter" => py.get_type::<BlocklistFilter>(),
+        "CodeTokenizer" => py.get_type::<CodeTokenizer>(),
+        "GraphemeTokenizer" => py.get_type::<GraphemeTokenizer>(),
+        "LengthFilter" => py.get_type::<LengthFilter>(),
+        "Normalizer" => py.get_type::<Normalizer>(),
+        "NumericFilter" => py.get_type::<NumericFilter>(),
+        "PhraseMerger" => py.get_type::<PhraseMerger>(),
+        "RegexFilter" => py.get_type::<RegexFilter>(),
+        "RegexTokenizer" => py.get_type::<RegexTokenizer>(),
+        "SentenceSplitter" => py.get_type::<SentenceSplitter>(),
+        "SocialTokenizer" => py.get_type::<SocialTokenizer>(),
+        "SpecialTokens" => py.get_type::<SpecialTokens>(),
+        "StopWordFilter" => py.get_type::<StopWordFilter>(),
+        "TokenizerPipeline" => py.get_type::<TokenizerPipeline>(),
+        "Truecaser" => py.get_type::<Truecaser>(),
+        "WhitespaceTokenizer" => py.get_type::<WhitespaceTokenizer>(),
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown type '{}' in tokenizer configuration",
+                other
+            )))
+        }
+    })
+}
+
+/// The JSON form of a constructor argument. Objects become `{"type": ...}`
+/// maps of their own constructor arguments, and vocabularies embed their
+/// tokens.
+fn to_value(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        return Ok(Value::Null);
+    }
+    if let Ok(b) = obj.cast::<PyBool>() {
+        return Ok(Value::Bool(b.is_true()));
+    }
+    if obj.is_instance_of::<PyInt>() {
+        return Ok(match obj.extract::<i64>() {
+            Ok(i) => i.into(),
+            Err(_) => obj.extract::<u64>()?.into(),
+        });
+    }
+    if obj.is_instance_of::<PyFloat>() {
+        return Ok(obj.extract::<f64>()?.into());
+    }
+    if let Ok(s) = obj.cast::<PyString>() {
+        return Ok(Value::String(s.to_str()?.to_string()));
+    }
+    if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        return obj.try_iter()?.map(|item| to_value(&item?)).collect();
+    }
+    if let Ok(vocab) = obj.cast::<Vocab>() {
+        let mut map = Map::new();
+        map.insert("type".to_string(), "Vocab".into());
+        map.insert("vocab".to_string(), vocab.borrow().to_value());
+        return Ok(Value::Object(map));
+    }
+    let model = if let Ok(bpe) = obj.cast::<BpeTokenizer>() {
+        Some(bpe.get().to_value())
+    } else if let Ok(wordpiece) = obj.cast::<WordPieceTokenizer>() {
+        Some(wordpiece.get().to_value())
+    } else if let Ok(sentencepiece) = obj.cast::<SentencePieceTokenizer>() {
+        Some(sentencepiece.get().to_value())
+    } else if let Ok(tiktoken) = obj.cast::<TiktokenEncoder>() {
+        Some(tiktoken.get().to_value())
+    } else if let Ok(hf) = obj.cast::<HfTokenizer>() {
+        Some(hf.get().to_value())
+    } else {
+        None
+    };
+    let name = obj.get_type().name()?.to_string();
+    if let Some(mut value) = model {
+        value["type"] = name.into();
+        return Ok(value);
+    }
+    if class(obj.py(), &name).is_err() {
+        return Err(PyTypeError::new_err(format!(
+            "{} cannot be serialized to JSON",
+            name
+        )));
+    }
+    let (_, kwargs): (Bound<'_, PyAny>, Bound<'_, PyDict>) =
+        obj.call_method0("__getnewargs_ex__")?.extract()?;
+    let mut map = Map::new();
+    map.insert("type".to_string(), Value::String(name));
+    for (key, value) in kwargs.iter() {
+        map.insert(key.extract()?, to_value(&value)?);
+    }
+    Ok(Value::Object(map))
+}
+
+fn from_value<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_pyobject(py)?.into_any(),
+            (_, Some(u)) => u.into_pyobject(py)?.into_any(),
+            _ => n.as_f64().into_pyobject(py)?.into_any(),
+        },
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(items) => PyList::new(
+            py,
+            items
+                .iter()
+                .map(|item| from_value(py, item))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?
+        .into_any(),
+        Value::Object(map) => {
+            let Some(name) = map.get("type").and_then(Value::as_str) else {
+                return Err(PyValueError::new_err(
+                    "Objects in a tokenizer configuration need a \"type\"",
+                ));
+            };
+            if name == "Vocab" {
+                let vocab = map.get("vocab").unwrap_or(&Value::Null);
+                let vocab = Vocab::from_value(vocab).map_err(PyValueError::new_err)?;
+                return Ok(Bound::new(py, vocab)?.into_any());
+            }
+            let model = match name {
+                "BpeTokenizer" => {
+                    let bpe = BpeTokenizer::from_value(value)?;
+                    Some(Bound::new(py, bpe)?.into_any())
+                }
+                "WordPieceTokenizer" => {
+                    Some(Bound::new(py, WordPieceTokenizer::from_value(value)?)?.into_any())
+                }
+                "SentencePieceTokenizer" => {
+                    Some(Bound::new(py, SentencePieceTokenizer::from_value(value)?)?.into_any())
+                }
+                "TiktokenEncoder" => {
+                    Some(Bound::new(py, TiktokenEncoder::from_value(value)?)?.into_any())
+                }
+                "Tokenizer" => Some(Bound::new(py, HfTokenizer::from_value(value)?)?.into_any()),
+                _ => None,
+            };
+            if let Some(model) = model {
+                return Ok(model);
+            }
+            let kwargs = PyDict::new(py);
+            for (key, value) in map.iter().filter(|(key, _)| *key != "type") {
+                kwargs.set_item(key, from_value(py, value)?)?;
+            }
+            class(py, name)?.call((), Some(&kwargs))?
+        }
+    })
+}
+
+/// Serialize a tokenizer or pipeline to its configuration JSON.
+pub fn to_json(obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(to_value(obj)?.to_string())
+}
+
+/// Recreate an object of class `T` from `to_json` output.
+pub fn from_json<'py, T: PyClass>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, T>> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| PyValueError::new_err(format!("Invalid tokenizer JSON: {}", e)))?;
+    from_config(py, &value)
+}
+
+fn from_config<'py, T: PyClass>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, T>> {
+    let obj = from_value(py, value)?;
+    let name = obj.get_type().name()?.to_string();
+    obj.cast_into::<T>().map_err(|_| {
+        PyValueError::new_err(format!(
+            "Expected a {} configuration, got {}",
+            T::NAME,
+            name
+        ))
+    })
+}
+
+/// Version of the bundle format written by `save`; `load` reads it and any
+/// older one.
+const BUNDLE_VERSION: u64 = 1;
+
+/// Write a tokenizer or pipeline to a single bundle file: its configuration
+/// as `to_json` produces it, under a header naming the format and its
+/// version. Vocabularies, BPE merges, normalizer settings and special tokens
+/// are all embedded, so the file is all a model needs.
+pub fn save(obj: &Bound<'_, PyAny>, path: &str) -> PyResult<()> {
+    let bundle = serde_json::json!({
+        "format": "fasttokenizer",
+        "version": BUNDLE_VERSION,
+        "tokenizer": to_value(obj)?,
+    });
+    std::fs::write(path, bundle.to_string())
+        .map_err(|e| PyIOError::new_err(format!("Failed to write bundle {}: {}", path, e)))
+}
+
+/// Recreate an object of class `T` from a file written by `save`.
+pub fn load<'py, T: PyClass>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, T>> {
+    from_config(py, &read_bundle(path)?)
+}
+
+/// Recreate whatever object a file written by `save` holds.
+pub fn load_any<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyAny>> {
+    from_value(py, &read_bundle(path)?)
+}
+
+/// The configuration in a bundle file, after checking its header.
+fn read_bundle(path: &str) -> PyResult<Value> {
+    let data = std::fs::read(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read bundle {}: {}", path, e)))?;
+    let mut bundle: Value = serde_json::from_slice(&data)
+        .map_err(|e| PyValueError::new_err(format!("Invalid bundle {}: {}", path, e)))?;
+    if bundle["format"] != "fasttokenizer" {
+        return Err(PyValueError::new_err(format!(
+            "{} is not a fasttokenizer bundle",
+            path
+        )));
+    }
+    match bundle["version"].as_u64() {
+        Some(version) if version <= BUNDLE_VERSION => Ok(bundle["tokenizer"].take()),
+        _ => Err(PyValueError::new_err(format!(
+            "Unsupported bundle version {} in {}, expected at most {}",
+            bundle["version"], path, BUNDLE_VERSION
+        ))),
+    }
+}
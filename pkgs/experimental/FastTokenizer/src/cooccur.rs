@@ -0,0 +1,160 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::corpus::for_each_batch;
+use crate::sparse::CsrMatrix;
+use crate::tokenizer::{extract_tokenizer, thread_pool};
+use crate::vocab::Vocab;
+
+/// How much a co-occurrence counts, by the distance between the tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Weighting {
+    /// Every co-occurrence within the window counts 1.
+    Uniform,
+    /// A co-occurrence at distance `d` counts `1 / d`, as in GloVe.
+    Harmonic,
+}
+
+impl Weighting {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "uniform" => Ok(Weighting::Uniform),
+            "harmonic" => Ok(Weighting::Harmonic),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown weighting '{}', expected 'uniform' or 'harmonic'",
+                other
+            ))),
+        }
+    }
+
+    fn weight(self, distance: usize) -> f64 {
+        match self {
+            Weighting::Uniform => 1.0,
+            Weighting::Harmonic => 1.0 / distance as f64,
+        }
+    }
+}
+
+/// Weighted counts of `(token, context)` id pairs.
+type Pairs = HashMap<(u32, u32), f64>;
+
+/// Add the co-occurrences of one document's token ids to `pairs`. Every
+/// token pairs with the `window` tokens after it, and with `symmetric` set
+/// the pair is counted in both directions.
+fn count_pairs(
+    ids: &[u32],
+    window: usize,
+    weighting: Weighting,
+    symmetric: bool,
+    pairs: &mut Pairs,
+) {
+    for (i, &token) in ids.iter().enumerate() {
+        for (distance, &context) in ids[i + 1..].iter().take(window).enumerate() {
+            let weight = weighting.weight(distance + 1);
+            *pairs.entry((token, context)).or_insert(0.0) += weight;
+            if symmetric {
+                *pairs.entry((context, token)).or_insert(0.0) += weight;
+            }
+        }
+    }
+}
+
+/// Add the counts of `b` to `a`, merging the smaller map into the larger.
+fn merge(mut a: Pairs, mut b: Pairs) -> Pairs {
+    if a.len() < b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+    for (pair, weight) in b {
+        *a.entry(pair).or_insert(0.0) += weight;
+    }
+    a
+}
+
+/// Build a sparse token co-occurrence matrix over a corpus, e.g. for
+/// GloVe-style embedding training or keyword association analysis.
+///
+/// Texts are pulled from `corpus` in batches and counted in parallel
+/// without holding the GIL. Tokens missing from `vocab` are dropped before
+/// windows are formed.
+///
+/// Args:
+///     corpus (Iterable[str]): The texts, e.g. a list or `TokenizerIO.iter_lines`.
+///     vocab (Vocab): Maps tokens to the rows and columns of the matrix.
+///     window (int): Number of following tokens every token pairs with.
+///     tokenizer (optional): Any FastTokenizer tokenizer; texts are split
+///         on whitespace by default.
+///     weighting (str): `"harmonic"` counts a pair at distance `d` as
+///         `1 / d` like GloVe; `"uniform"` counts every pair as 1.
+///     symmetric (bool): Count every pair in both directions. Otherwise
+///         row `i`, column `j` only counts `j` following `i`.
+///     batch_size (int): Texts counted per parallel step.
+///     workers (int, optional): Number of threads; defaults to one per CPU
+///         core.
+///
+/// Returns:
+///     CsrMatrix: A `len(vocab)` by `len(vocab)` matrix of weighted counts.
+#[pyfunction]
+#[pyo3(signature = (
+    corpus,
+    vocab,
+    window=5,
+    tokenizer=None,
+    weighting="harmonic",
+    symmetric=true,
+    batch_size=1024,
+    workers=None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn cooccurrence(
+    py: Python<'_>,
+    corpus: &Bound<'_, PyAny>,
+    vocab: PyRef<'_, Vocab>,
+    window: usize,
+    tokenizer: Option<&Bound<'_, PyAny>>,
+    weighting: &str,
+    symmetric: bool,
+    batch_size: usize,
+    workers: Option<usize>,
+) -> PyResult<CsrMatrix> {
+    if window == 0 {
+        return Err(PyValueError::new_err("window must be at least 1"));
+    }
+    let weighting = Weighting::parse(weighting)?;
+    let tokenizer = tokenizer.map(extract_tokenizer).transpose()?;
+    let vocab = vocab.clone();
+    let pool = thread_pool(workers)?;
+    let mut pairs = Pairs::new();
+    for_each_batch(py, corpus, batch_size.max(1), &pool, |texts| {
+        let batch = vocab.with_token_ids(|data| {
+            texts
+                .par_iter()
+                .fold(Pairs::new, |mut pairs, text| {
+                    let ids: Vec<u32> = match &tokenizer {
+                        Some(tokenizer) => tokenizer
+                            .tokenize(text)
+                            .iter()
+                            .filter_map(|token| data.id(token))
+                            .collect(),
+                        None => text
+                            .split_whitespace()
+                            .filter_map(|token| data.id(token))
+                            .collect(),
+                    };
+                    count_pairs(&ids, window, weighting, symmetric, &mut pairs);
+                    pairs
+                })
+                .reduce(Pairs::new, merge)
+        });
+        pairs = merge(std::mem::take(&mut pairs), batch);
+    })?;
+    let size = vocab.with_token_ids(|data| data.len());
+    Ok(py.detach(|| {
+        let mut rows = vec![Vec::new(); size];
+        for ((token, context), weight) in pairs {
+            rows[token as usize].push((context, weight));
+        }
+        CsrMatrix::from_rows(rows, size)
+    }))
+}
@@ -0,0 +1,206 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use crate::tokenizer::{extract_tokenizer, thread_pool, Tokenizer};
+
+pub type Counts = HashMap<String, u64>;
+
+/// Count the tokens of `texts` on the current rayon pool.
+pub fn count_batch(tokenizer: &dyn Tokenizer, texts: &[String]) -> Counts {
+    texts
+        .par_iter()
+        .fold(Counts::new, |mut counts, text| {
+            for token in tokenizer.tokenize(text) {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+            counts
+        })
+        .reduce(Counts::new, merge)
+}
+
+/// Add the counts of `b` to `a`, merging the smaller map into the larger.
+pub fn merge(mut a: Counts, mut b: Counts) -> Counts {
+    if a.len() < b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+    for (token, count) in b {
+        *a.entry(token).or_insert(0) += count;
+    }
+    a
+}
+
+/// A count-min sketch with conservative updates: counts are never
+/// underestimated and overestimated only by collisions in every row.
+struct CountMin {
+    width: usize,
+    depth: usize,
+    table: Vec<u64>,
+}
+
+impl CountMin {
+    fn new(width: usize, depth: usize) -> Self {
+        CountMin {
+            width,
+            depth,
+            table: vec![0; width * depth],
+        }
+    }
+
+    fn cells<'a>(&'a self, token: &'a str) -> impl Iterator<Item = usize> + 'a {
+        (0..self.depth).map(move |row| {
+            let mut hasher = DefaultHasher::new();
+            row.hash(&mut hasher);
+            token.hash(&mut hasher);
+            row * self.width + (hasher.finish() as usize) % self.width
+        })
+    }
+
+    fn add(&mut self, token: &str, count: u64) {
+        let cells: Vec<usize> = self.cells(token).collect();
+        let estimate = cells.iter().map(|&i| self.table[i]).min().unwrap_or(0) + count;
+        for i in cells {
+            self.table[i] = self.table[i].max(estimate);
+        }
+    }
+
+    fn estimate(&self, token: &str) -> u64 {
+        self.cells(token).map(|i| self.table[i]).min().unwrap_or(0)
+    }
+}
+
+/// Approximate counts in bounded memory: a count-min sketch estimates every
+/// count and only the `max_types` most frequent candidates are remembered.
+struct HeavyHitters {
+    sketch: CountMin,
+    candidates: HashSet<String>,
+    max_types: usize,
+}
+
+impl HeavyHitters {
+    fn add(&mut self, counts: Counts) {
+        for (token, count) in counts {
+            self.sketch.add(&token, count);
+            self.candidates.insert(token);
+        }
+        if self.candidates.len() > 2 * self.max_types {
+            let kept = self.top().into_keys().collect();
+            self.candidates = kept;
+        }
+    }
+
+    /// The `max_types` candidates with the highest estimates.
+    fn top(&self) -> Counts {
+        let mut estimates: Vec<(&String, u64)> = self
+            .candidates
+            .iter()
+            .map(|token| (token, self.sketch.estimate(token)))
+            .collect();
+        if estimates.len() > self.max_types {
+            estimates.select_nth_unstable_by(self.max_types, |a, b| {
+                b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0))
+            });
+            estimates.truncate(self.max_types);
+        }
+        estimates
+            .into_iter()
+            .map(|(token, count)| (token.clone(), count))
+            .collect()
+    }
+}
+
+/// Pull texts from the Python iterable `corpus` in batches of `batch_size`
+/// and hand each batch, with the GIL released, to `f` on `pool`.
+pub fn for_each_batch(
+    py: Python<'_>,
+    corpus: &Bound<'_, PyAny>,
+    batch_size: usize,
+    pool: &rayon::ThreadPool,
+    mut f: impl FnMut(&[String]) + Send,
+) -> PyResult<()> {
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut items = corpus.try_iter()?;
+    loop {
+        let next = items.next().transpose()?;
+        if let Some(text) = &next {
+            batch.push(text.extract::<String>()?);
+        }
+        if batch.len() >= batch_size || (next.is_none() && !batch.is_empty()) {
+            py.detach(|| pool.install(|| f(&batch)));
+            batch.clear();
+        }
+        if next.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+/// Count how often every token occurs in a corpus.
+///
+/// Texts are pulled from `corpus` in batches and tokenized in parallel
+/// without holding the GIL.
+///
+/// Args:
+///     corpus (Iterable[str]): The texts, e.g. a list or `TokenizerIO.iter_lines`.
+///     tokenizer: Any FastTokenizer tokenizer.
+///     batch_size (int): Texts tokenized per parallel step.
+///     workers (int, optional): Number of threads; defaults to one per CPU
+///         core.
+///     max_types (int, optional): Bound memory by keeping only the
+///         `max_types` most frequent tokens. Counts are then estimated with a
+///         count-min sketch and may be slightly too high, never too low.
+///     sketch_width (int): Counters per sketch row; wider is more accurate.
+///     sketch_depth (int): Number of sketch rows.
+///
+/// Returns:
+///     Dict[str, int]: The count of every token.
+#[pyfunction]
+#[pyo3(signature = (
+    corpus,
+    tokenizer,
+    batch_size=1024,
+    workers=None,
+    max_types=None,
+    sketch_width=1 << 18,
+    sketch_depth=4
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn count_tokens(
+    py: Python<'_>,
+    corpus: &Bound<'_, PyAny>,
+    tokenizer: &Bound<'_, PyAny>,
+    batch_size: usize,
+    workers: Option<usize>,
+    max_types: Option<usize>,
+    sketch_width: usize,
+    sketch_depth: usize,
+) -> PyResult<Counts> {
+    if sketch_width == 0 || sketch_depth == 0 {
+        return Err(PyValueError::new_err(
+            "sketch_width and sketch_depth must be at least 1",
+        ));
+    }
+    let tokenizer: Arc<dyn Tokenizer> = extract_tokenizer(tokenizer)?;
+    let pool = thread_pool(workers)?;
+    let batch_size = batch_size.max(1);
+    let Some(max_types) = max_types else {
+        let mut counts = Counts::new();
+        for_each_batch(py, corpus, batch_size, &pool, |texts| {
+            counts = merge(std::mem::take(&mut counts), count_batch(&*tokenizer, texts));
+        })?;
+        return Ok(counts);
+    };
+    let mut hitters = HeavyHitters {
+        sketch: CountMin::new(sketch_width, sketch_depth),
+        candidates: HashSet::new(),
+        max_types,
+    };
+    for_each_batch(py, corpus, batch_size, &pool, |texts| {
+        hitters.add(count_batch(&*tokenizer, texts));
+    })?;
+    Ok(py.detach(|| hitters.top()))
+}
@@ -0,0 +1,279 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::sync::Arc;
+
+use crate::corpus::{merge, Counts};
+use crate::tokenizer::{extract_tokenizer, thread_pool, Tokenizer};
+use crate::tokenizer_io::read_text;
+use crate::utf8::Utf8Errors;
+
+/// What counts as one document in `corpus_stats`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Document {
+    File,
+    Line,
+}
+
+impl Document {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "file" => Ok(Document::File),
+            "line" => Ok(Document::Line),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown document unit '{}', expected 'file' or 'line'",
+                name
+            ))),
+        }
+    }
+}
+
+/// Statistics over part of a corpus, merged into the whole.
+#[derive(Default)]
+struct Partial {
+    documents: u64,
+    tokens: u64,
+    characters: u64,
+    min_length: Option<u64>,
+    max_length: u64,
+    /// Document counts by length bucket; see `bucket`.
+    histogram: Vec<u64>,
+    counts: Counts,
+}
+
+impl Partial {
+    fn add(&mut self, tokenizer: &dyn Tokenizer, text: &str) {
+        let tokens = tokenizer.tokenize(text);
+        let length = tokens.len() as u64;
+        self.documents += 1;
+        self.tokens += length;
+        self.characters += text.chars().count() as u64;
+        self.min_length = Some(self.min_length.map_or(length, |min| min.min(length)));
+        self.max_length = self.max_length.max(length);
+        let bucket = bucket(length);
+        if self.histogram.len() <= bucket {
+            self.histogram.resize(bucket + 1, 0);
+        }
+        self.histogram[bucket] += 1;
+        for token in tokens {
+            *self.counts.entry(token).or_insert(0) += 1;
+        }
+    }
+
+    fn merge(mut self, other: Partial) -> Partial {
+        self.documents += other.documents;
+        self.tokens += other.tokens;
+        self.characters += other.characters;
+        self.min_length = match (self.min_length, other.min_length) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max_length = self.max_length.max(other.max_length);
+        if self.histogram.len() < other.histogram.len() {
+            self.histogram.resize(other.histogram.len(), 0);
+        }
+        for (total, count) in self.histogram.iter_mut().zip(other.histogram) {
+            *total += count;
+        }
+        self.counts = merge(self.counts, other.counts);
+        self
+    }
+}
+
+/// Histogram bucket of a document length: 0 for empty documents, otherwise
+/// `k` for lengths in `[2^(k-1), 2^k)`.
+fn bucket(length: u64) -> usize {
+    (u64::BITS - length.leading_zeros()) as usize
+}
+
+/// Summary statistics of a tokenized corpus, as returned by `corpus_stats`.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct CorpusStats {
+    /// Number of documents.
+    #[pyo3(get)]
+    documents: u64,
+    /// Number of tokens over all documents.
+    #[pyo3(get)]
+    tokens: u64,
+    /// Number of characters over all documents.
+    #[pyo3(get)]
+    characters: u64,
+    /// Fewest tokens in a document.
+    #[pyo3(get)]
+    min_length: u64,
+    /// Most tokens in a document.
+    #[pyo3(get)]
+    max_length: u64,
+    histogram: Vec<u64>,
+    counts: Arc<Counts>,
+    top_k: usize,
+}
+
+impl CorpusStats {
+    fn new(partial: Partial, top_k: usize) -> Self {
+        CorpusStats {
+            documents: partial.documents,
+            tokens: partial.tokens,
+            characters: partial.characters,
+            min_length: partial.min_length.unwrap_or(0),
+            max_length: partial.max_length,
+            histogram: partial.histogram,
+            counts: Arc::new(partial.counts),
+            top_k,
+        }
+    }
+}
+
+#[pymethods]
+impl CorpusStats {
+    /// Number of distinct tokens.
+    #[getter]
+    fn types(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Distinct tokens per token, or 0.0 for an empty corpus.
+    #[getter]
+    fn type_token_ratio(&self) -> f64 {
+        if self.tokens == 0 {
+            return 0.0;
+        }
+        self.counts.len() as f64 / self.tokens as f64
+    }
+
+    /// Average number of tokens per document.
+    #[getter]
+    fn mean_length(&self) -> f64 {
+        if self.documents == 0 {
+            return 0.0;
+        }
+        self.tokens as f64 / self.documents as f64
+    }
+
+    /// Document lengths in tokens as `(low, high, documents)` buckets that
+    /// double in width: `[0, 1)`, `[1, 2)`, `[2, 4)`, `[4, 8)` and so on.
+    /// Empty buckets are left out.
+    fn length_histogram(&self) -> Vec<(u64, u64, u64)> {
+        self.histogram
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(k, &count)| match k {
+                0 => (0, 1, count),
+                k => (1 << (k - 1), 1 << k, count),
+            })
+            .collect()
+    }
+
+    /// The most frequent tokens with their counts, most frequent first.
+    ///
+    /// Args:
+    ///     k (int, optional): How many; defaults to the `top_k` passed to
+    ///         `corpus_stats`.
+    #[pyo3(signature = (k=None))]
+    fn top_tokens(&self, k: Option<usize>) -> Vec<(String, u64)> {
+        let mut counts: Vec<(&String, &u64)> = self.counts.iter().collect();
+        counts.sort_unstable_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        counts
+            .into_iter()
+            .take(k.unwrap_or(self.top_k))
+            .map(|(token, &count)| (token.clone(), count))
+            .collect()
+    }
+
+    /// How often every token occurs, as accepted by `Vocab.from_counts`.
+    fn counts(&self) -> Counts {
+        (*self.counts).clone()
+    }
+
+    /// All statistics as a plain dict, e.g. for logging as JSON.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("documents", self.documents)?;
+        dict.set_item("tokens", self.tokens)?;
+        dict.set_item("characters", self.characters)?;
+        dict.set_item("types", self.types())?;
+        dict.set_item("type_token_ratio", self.type_token_ratio())?;
+        dict.set_item("min_length", self.min_length)?;
+        dict.set_item("max_length", self.max_length)?;
+        dict.set_item("mean_length", self.mean_length())?;
+        dict.set_item("length_histogram", self.length_histogram())?;
+        dict.set_item("top_tokens", self.top_tokens(None))?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CorpusStats(documents={}, tokens={}, types={})",
+            self.documents,
+            self.tokens,
+            self.counts.len()
+        )
+    }
+}
+
+/// Compute corpus statistics in one parallel pass over a set of files.
+///
+/// Files are read (and decompressed) and tokenized on a thread pool
+/// without holding the GIL.
+///
+/// Args:
+///     paths (List[str]): Files to read, e.g. from `TokenizerIO.walk`.
+///     tokenizer: Any FastTokenizer tokenizer.
+///     document (str): `"file"` to count each file as one document, or
+///         `"line"` to count each non-empty line.
+///     top_k (int): Number of tokens reported by `top_tokens()`.
+///     workers (int, optional): Number of threads; defaults to one per CPU
+///         core.
+///     compression (str): See `TokenizerIO.read_file`.
+///     errors (str): See `TokenizerIO.read_file`.
+///
+/// Returns:
+///     CorpusStats: Document, token and type counts, length histogram and
+///     most frequent tokens.
+#[pyfunction]
+#[pyo3(signature = (paths, tokenizer, document="file", top_k=20, workers=None, compression="auto", errors="strict"))]
+#[allow(clippy::too_many_arguments)]
+pub fn corpus_stats(
+    py: Python<'_>,
+    paths: Vec<String>,
+    tokenizer: &Bound<'_, PyAny>,
+    document: &str,
+    top_k: usize,
+    workers: Option<usize>,
+    compression: &str,
+    errors: &str,
+) -> PyResult<CorpusStats> {
+    let document = Document::parse(document)?;
+    let errors = Utf8Errors::parse(errors)?;
+    let tokenizer = extract_tokenizer(tokenizer)?;
+    let pool = thread_pool(workers)?;
+    let partial = py.detach(|| {
+        pool.install(|| {
+            paths
+                .par_iter()
+                .map(|path| -> PyResult<Partial> {
+                    let text = read_text(path, compression, errors)?;
+                    Ok(match document {
+                        Document::File => {
+                            let mut partial = Partial::default();
+                            partial.add(&*tokenizer, &text);
+                            partial
+                        }
+                        Document::Line => text
+                            .par_lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .fold(Partial::default, |mut partial, line| {
+                                partial.add(&*tokenizer, line);
+                                partial
+                            })
+                            .reduce(Partial::default, Partial::merge),
+                    })
+                })
+                .try_reduce(Partial::default, |a, b| Ok(a.merge(b)))
+        })
+    })?;
+    Ok(CorpusStats::new(partial, top_k))
+}
@@ -0,0 +1,741 @@
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// The Mersenne prime 2^61 - 1; MinHash permutations work modulo it.
+const MERSENNE_61: u64 = (1 << 61) - 1;
+
+/// The FNV-1a hash of no bytes, which `fnv1a_64_extend` starts from.
+pub const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 64-bit FNV-1a. Used instead of `std`'s hasher because signatures are
+/// stored and compared across processes, so the hash must never change.
+fn fnv1a_64(data: &[u8]) -> u64 {
+    fnv1a_64_extend(FNV_OFFSET_BASIS, data)
+}
+
+/// Continue the FNV-1a `hash` of some bytes with the bytes of `data`.
+pub fn fnv1a_64_extend(hash: u64, data: &[u8]) -> u64 {
+    data.iter().fold(hash, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// The SplitMix64 finalizer: spreads the bits of `x` over the whole word.
+pub fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Stable 64-bit hash of a string.
+pub fn hash64(s: &str) -> u64 {
+    mix64(fnv1a_64(s.as_bytes()))
+}
+
+/// `(a * x + b) mod 2^61 - 1` without overflow.
+fn permute(a: u64, b: u64, x: u64) -> u64 {
+    let product = a as u128 * (x % MERSENNE_61) as u128 + b as u128;
+    (product % MERSENNE_61 as u128) as u64
+}
+
+/// A text, split on whitespace, or its tokens.
+#[derive(FromPyObject)]
+pub enum TextOrTokens {
+    Text(String),
+    Tokens(Vec<String>),
+}
+
+impl TextOrTokens {
+    pub fn into_tokens(self) -> Vec<String> {
+        match self {
+            TextOrTokens::Text(text) => text.split_whitespace().map(str::to_string).collect(),
+            TextOrTokens::Tokens(tokens) => tokens,
+        }
+    }
+}
+
+/// Hashes of the `ngram`-token shingles of `tokens`; fewer tokens than
+/// `ngram` form a single shingle.
+pub fn shingle_hashes(tokens: &[String], ngram: usize) -> Vec<u64> {
+    if tokens.len() <= ngram {
+        return vec![hash64(&tokens.join(" "))];
+    }
+    tokens
+        .windows(ngram)
+        .map(|gram| hash64(&gram.join(" ")))
+        .collect()
+}
+
+/// MinHash permutations `(a, b)` derived from `seed`.
+#[derive(Clone)]
+struct MinHasher {
+    permutations: Vec<(u64, u64)>,
+}
+
+impl MinHasher {
+    fn new(num_perm: usize, seed: u64) -> PyResult<Self> {
+        if num_perm == 0 {
+            return Err(PyValueError::new_err("num_perm must be at least 1"));
+        }
+        let mut state = seed;
+        let mut next = move || {
+            state = state.wrapping_add(1);
+            mix64(state)
+        };
+        let permutations = (0..num_perm)
+            .map(|_| (next() % (MERSENNE_61 - 1) + 1, next() % MERSENNE_61))
+            .collect();
+        Ok(MinHasher { permutations })
+    }
+
+    fn signature(&self, tokens: &[String], ngram: usize) -> Vec<u64> {
+        let shingles = if tokens.is_empty() {
+            Vec::new()
+        } else {
+            shingle_hashes(tokens, ngram)
+        };
+        self.permutations
+            .iter()
+            .map(|&(a, b)| {
+                shingles
+                    .iter()
+                    .map(|&x| permute(a, b, x))
+                    .min()
+                    .unwrap_or(MERSENNE_61)
+            })
+            .collect()
+    }
+}
+
+fn check_ngram(ngram: usize) -> PyResult<()> {
+    if ngram == 0 {
+        return Err(PyValueError::new_err("ngram must be at least 1"));
+    }
+    Ok(())
+}
+
+/// MinHash signature of a document, for estimating Jaccard similarity.
+///
+/// Args:
+///     text_or_tokens (str | List[str]): A text, split on whitespace, or its
+///         tokens from any tokenizer.
+///     num_perm (int): Signature length; the estimate's error shrinks with
+///         `1 / sqrt(num_perm)`.
+///     seed (int): Signatures are only comparable with the same seed.
+///     ngram (int): Tokens per shingle; 1 compares sets of tokens.
+///
+/// Returns:
+///     List[int]: `num_perm` hash minimums.
+#[pyfunction]
+#[pyo3(signature = (text_or_tokens, num_perm=128, seed=1, ngram=1))]
+pub fn minhash(
+    text_or_tokens: TextOrTokens,
+    num_perm: usize,
+    seed: u64,
+    ngram: usize,
+) -> PyResult<Vec<u64>> {
+    check_ngram(ngram)?;
+    let hasher = MinHasher::new(num_perm, seed)?;
+    Ok(hasher.signature(&text_or_tokens.into_tokens(), ngram))
+}
+
+/// `minhash` for many documents at once, in parallel without the GIL.
+#[pyfunction]
+#[pyo3(signature = (documents, num_perm=128, seed=1, ngram=1))]
+pub fn minhash_batch(
+    py: Python<'_>,
+    documents: Vec<TextOrTokens>,
+    num_perm: usize,
+    seed: u64,
+    ngram: usize,
+) -> PyResult<Vec<Vec<u64>>> {
+    check_ngram(ngram)?;
+    let hasher = MinHasher::new(num_perm, seed)?;
+    let documents: Vec<Vec<String>> = documents
+        .into_iter()
+        .map(TextOrTokens::into_tokens)
+        .collect();
+    Ok(py.detach(|| {
+        documents
+            .par_iter()
+            .map(|tokens| hasher.signature(tokens, ngram))
+            .collect()
+    }))
+}
+
+/// Estimated Jaccard similarity of two documents from their signatures.
+#[pyfunction]
+pub fn minhash_similarity(a: Vec<u64>, b: Vec<u64>) -> PyResult<f64> {
+    check_lengths(a.len(), b.len())?;
+    Ok(similarity(&a, &b))
+}
+
+fn similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() {
+        return 0.0;
+    }
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / a.len() as f64
+}
+
+fn check_lengths(expected: usize, got: usize) -> PyResult<()> {
+    if expected != got {
+        return Err(PyValueError::new_err(format!(
+            "Signature has {} values, expected {}",
+            got, expected
+        )));
+    }
+    Ok(())
+}
+
+/// Bands and rows per band whose S-curve `1 - (1 - s^rows)^bands` crosses
+/// 50% closest to `threshold`, using at most `num_perm` values.
+fn optimal_bands(num_perm: usize, threshold: f64) -> (usize, usize) {
+    let mut best = (1, num_perm);
+    let mut best_error = f64::INFINITY;
+    for rows in 1..=num_perm {
+        let bands = num_perm / rows;
+        let crossing = (1.0 - 0.5f64.powf(1.0 / bands as f64)).powf(1.0 / rows as f64);
+        let error = (crossing - threshold).abs();
+        if error < best_error {
+            best = (bands, rows);
+            best_error = error;
+        }
+    }
+    best
+}
+
+/// Locality-sensitive hashing index over MinHash signatures.
+///
+/// Signatures are cut into `bands` bands of `rows` values; documents
+/// sharing any whole band become candidates, so pairs above `threshold`
+/// Jaccard similarity are found with high probability without comparing
+/// every pair.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct LshIndex {
+    num_perm: usize,
+    threshold: f64,
+    bands: usize,
+    rows: usize,
+    /// Per band, the documents in each bucket.
+    buckets: Vec<HashMap<u64, Vec<u32>>>,
+    keys: Vec<Option<String>>,
+    signatures: Vec<Vec<u64>>,
+    ids: HashMap<String, u32>,
+}
+
+impl LshIndex {
+    fn band_hashes<'a>(&'a self, signature: &'a [u64]) -> impl Iterator<Item = u64> + 'a {
+        signature
+            .chunks_exact(self.rows)
+            .take(self.bands)
+            .map(|band| band.iter().fold(0, |h, &v| mix64(h ^ v)))
+    }
+
+    fn candidates(&self, signature: &[u64]) -> HashSet<u32> {
+        let mut found = HashSet::new();
+        for (band, hash) in self.band_hashes(signature).enumerate() {
+            if let Some(ids) = self.buckets[band].get(&hash) {
+                found.extend(ids.iter().copied());
+            }
+        }
+        found
+    }
+}
+
+#[pymethods]
+impl LshIndex {
+    /// Create an empty index.
+    ///
+    /// Args:
+    ///     num_perm (int): Signature length, as passed to `minhash`.
+    ///     threshold (float): Jaccard similarity the banding is tuned for.
+    ///     bands (int, optional): Number of bands; with `rows`, overrides the
+    ///         choice derived from `threshold`.
+    ///     rows (int, optional): Signature values per band.
+    #[new]
+    #[pyo3(signature = (num_perm=128, threshold=0.5, bands=None, rows=None))]
+    fn new(
+        num_perm: usize,
+        threshold: f64,
+        bands: Option<usize>,
+        rows: Option<usize>,
+    ) -> PyResult<Self> {
+        if num_perm == 0 {
+            return Err(PyValueError::new_err("num_perm must be at least 1"));
+        }
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(PyValueError::new_err(format!(
+                "threshold must be between 0 and 1, got {}",
+                threshold
+            )));
+        }
+        let (bands, rows) = match (bands, rows) {
+            (None, None) => optimal_bands(num_perm, threshold),
+            (Some(bands), Some(rows)) if bands > 0 && rows > 0 && bands * rows <= num_perm => {
+                (bands, rows)
+            }
+            (Some(bands), Some(rows)) => {
+                return Err(PyValueError::new_err(format!(
+                    "bands * rows must be between 1 and num_perm ({}), got {} * {}",
+                    num_perm, bands, rows
+                )))
+            }
+            _ => {
+                return Err(PyValueError::new_err(
+                    "Pass both bands and rows, or neither",
+                ))
+            }
+        };
+        Ok(LshIndex {
+            num_perm,
+            threshold,
+            bands,
+            rows,
+            buckets: vec![HashMap::new(); bands],
+            keys: Vec::new(),
+            signatures: Vec::new(),
+            ids: HashMap::new(),
+        })
+    }
+
+    /// Add a document's signature under `key`.
+    ///
+    /// Raises:
+    ///     KeyError: If `key` is already in the index.
+    fn insert(&mut self, key: String, signature: Vec<u64>) -> PyResult<()> {
+        check_lengths(self.num_perm, signature.len())?;
+        if self.ids.contains_key(&key) {
+            return Err(PyKeyError::new_err(format!("'{}' is already indexed", key)));
+        }
+        let id = self.keys.len() as u32;
+        let hashes: Vec<u64> = self.band_hashes(&signature).collect();
+        for (band, hash) in hashes.into_iter().enumerate() {
+            self.buckets[band].entry(hash).or_default().push(id);
+        }
+        self.ids.insert(key.clone(), id);
+        self.keys.push(Some(key));
+        self.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Remove the document stored under `key`.
+    fn remove(&mut self, key: &str) -> PyResult<()> {
+        let Some(id) = self.ids.remove(key) else {
+            return Err(PyKeyError::new_err(key.to_string()));
+        };
+        let signature = std::mem::take(&mut self.signatures[id as usize]);
+        let hashes: Vec<u64> = self.band_hashes(&signature).collect();
+        for (band, hash) in hashes.into_iter().enumerate() {
+            if let Some(ids) = self.buckets[band].get_mut(&hash) {
+                ids.retain(|&other| other != id);
+                if ids.is_empty() {
+                    self.buckets[band].remove(&hash);
+                }
+            }
+        }
+        self.keys[id as usize] = None;
+        Ok(())
+    }
+
+    /// Keys of indexed documents similar to `signature`, most similar
+    /// first, with their estimated Jaccard similarity.
+    ///
+    /// Args:
+    ///     signature (List[int]): The query's MinHash signature.
+    ///     verify (bool): Drop candidates whose estimated similarity is
+    ///         below the index threshold. Without it every document sharing
+    ///         a band is returned.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, float]]: `(key, similarity)` pairs.
+    #[pyo3(signature = (signature, verify=true))]
+    fn query(&self, signature: Vec<u64>, verify: bool) -> PyResult<Vec<(String, f64)>> {
+        check_lengths(self.num_perm, signature.len())?;
+        let mut results: Vec<(String, f64)> = self
+            .candidates(&signature)
+            .into_iter()
+            .filter_map(|id| {
+                let key = self.keys[id as usize].as_ref()?;
+                let score = similarity(&signature, &self.signatures[id as usize]);
+                (!verify || score >= self.threshold).then(|| (key.clone(), score))
+            })
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(results)
+    }
+
+    /// The stored signature of `key`.
+    fn get(&self, key: &str) -> PyResult<Vec<u64>> {
+        match self.ids.get(key) {
+            Some(&id) => Ok(self.signatures[id as usize].clone()),
+            None => Err(PyKeyError::new_err(key.to_string())),
+        }
+    }
+
+    #[getter]
+    fn bands(&self) -> usize {
+        self.bands
+    }
+
+    #[getter]
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[getter]
+    fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.keys.iter().flatten().cloned().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.ids.contains_key(key)
+    }
+}
+
+/// 64-bit SimHash of `tokens`: every shingle votes on each bit with the
+/// bits of its hash, weighted by how often it occurs.
+fn simhash_tokens(tokens: &[String], ngram: usize) -> u64 {
+    if tokens.is_empty() {
+        return 0;
+    }
+    let mut votes = [0i64; 64];
+    for hash in shingle_hashes(tokens, ngram) {
+        for (bit, vote) in votes.iter_mut().enumerate() {
+            if hash >> bit & 1 == 1 {
+                *vote += 1;
+            } else {
+                *vote -= 1;
+            }
+        }
+    }
+    votes
+        .iter()
+        .enumerate()
+        .filter(|(_, &vote)| vote > 0)
+        .fold(0, |fingerprint, (bit, _)| fingerprint | 1 << bit)
+}
+
+/// SimHash fingerprint of a document; similar documents get fingerprints a
+/// small Hamming distance apart.
+///
+/// Args:
+///     text_or_tokens (str | List[str]): A text, split on whitespace, or its
+///         tokens from any tokenizer.
+///     ngram (int): Tokens per shingle.
+///
+/// Returns:
+///     int: The 64-bit fingerprint.
+#[pyfunction]
+#[pyo3(signature = (text_or_tokens, ngram=1))]
+pub fn simhash(text_or_tokens: TextOrTokens, ngram: usize) -> PyResult<u64> {
+    check_ngram(ngram)?;
+    Ok(simhash_tokens(&text_or_tokens.into_tokens(), ngram))
+}
+
+/// `simhash` for many documents at once, in parallel without the GIL.
+#[pyfunction]
+#[pyo3(signature = (documents, ngram=1))]
+pub fn simhash_batch(
+    py: Python<'_>,
+    documents: Vec<TextOrTokens>,
+    ngram: usize,
+) -> PyResult<Vec<u64>> {
+    check_ngram(ngram)?;
+    let documents: Vec<Vec<String>> = documents
+        .into_iter()
+        .map(TextOrTokens::into_tokens)
+        .collect();
+    Ok(py.detach(|| {
+        documents
+            .par_iter()
+            .map(|tokens| simhash_tokens(tokens, ngram))
+            .collect()
+    }))
+}
+
+/// Number of differing bits between two fingerprints.
+#[pyfunction]
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// All pairs of fingerprints at most `max_distance` bits apart.
+///
+/// The 64 bits are cut into `max_distance + 1` blocks; two fingerprints
+/// within the distance agree exactly on at least one block, so only those
+/// sharing a block are compared.
+///
+/// Args:
+///     fingerprints (List[int]): Fingerprints from `simhash`.
+///     max_distance (int): Largest Hamming distance reported, below 64.
+///
+/// Returns:
+///     List[Tuple[int, int, int]]: `(i, j, distance)` with `i < j` indexes
+///     into `fingerprints`, sorted.
+#[pyfunction]
+#[pyo3(signature = (fingerprints, max_distance=3))]
+pub fn simhash_pairs(
+    py: Python<'_>,
+    fingerprints: Vec<u64>,
+    max_distance: u32,
+) -> PyResult<Vec<(usize, usize, u32)>> {
+    if max_distance >= 64 {
+        return Err(PyValueError::new_err(format!(
+            "max_distance must be below 64, got {}",
+            max_distance
+        )));
+    }
+    Ok(py.detach(|| {
+        let blocks = max_distance as usize + 1;
+        let mut pairs = HashSet::new();
+        for block in 0..blocks {
+            let (low, high) = (64 * block / blocks, 64 * (block + 1) / blocks);
+            let mask = if high - low == 64 {
+                u64::MAX
+            } else {
+                ((1u64 << (high - low)) - 1) << low
+            };
+            let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+            for (i, &fingerprint) in fingerprints.iter().enumerate() {
+                buckets.entry(fingerprint & mask).or_default().push(i);
+            }
+            for members in buckets.values() {
+                for (n, &i) in members.iter().enumerate() {
+                    for &j in &members[n + 1..] {
+                        let distance = hamming_distance(fingerprints[i], fingerprints[j]);
+                        if distance <= max_distance {
+                            pairs.insert((i, j, distance));
+                        }
+                    }
+                }
+            }
+        }
+        let mut pairs: Vec<_> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        pairs
+    }))
+}
+
+/// How `shingle_overlap` scores the shingles two documents share.
+#[derive(Clone, Copy)]
+enum Overlap {
+    /// Shared shingles over all distinct shingles of the pair.
+    Jaccard,
+    /// Shared shingles over those of the smaller document, so a document
+    /// copied whole into a longer one scores 1.
+    Containment,
+}
+
+impl Overlap {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "jaccard" => Ok(Overlap::Jaccard),
+            "containment" => Ok(Overlap::Containment),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown overlap metric '{}', expected 'jaccard' or 'containment'",
+                other
+            ))),
+        }
+    }
+
+    fn score(self, shared: usize, a: usize, b: usize) -> f64 {
+        match self {
+            Overlap::Jaccard => shared as f64 / (a + b - shared) as f64,
+            Overlap::Containment => shared as f64 / a.min(b) as f64,
+        }
+    }
+}
+
+fn check_k(k: usize) -> PyResult<()> {
+    if k == 0 {
+        return Err(PyValueError::new_err("k must be at least 1"));
+    }
+    Ok(())
+}
+
+/// The distinct `k`-token shingles of `tokens` as sorted hashes; none for
+/// no tokens.
+fn shingle_set(tokens: &[String], k: usize) -> Vec<u64> {
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+    let mut hashes = shingle_hashes(tokens, k);
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes
+}
+
+/// The distinct `k`-token shingles of `tokens`, joined with spaces, in order
+/// of first occurrence.
+fn shingle_texts(tokens: &[String], k: usize) -> Vec<String> {
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+    if tokens.len() <= k {
+        return vec![tokens.join(" ")];
+    }
+    let mut seen = HashSet::new();
+    tokens
+        .windows(k)
+        .map(|gram| gram.join(" "))
+        .filter(|shingle| seen.insert(shingle.clone()))
+        .collect()
+}
+
+/// Shingles as hashes or as text.
+#[derive(IntoPyObject)]
+pub enum Shingles {
+    Hashed(Vec<u64>),
+    Text(Vec<String>),
+}
+
+fn shingles_of(tokens: &[String], k: usize, hashed: bool) -> Shingles {
+    if hashed {
+        Shingles::Hashed(shingle_set(tokens, k))
+    } else {
+        Shingles::Text(shingle_texts(tokens, k))
+    }
+}
+
+/// The distinct `k`-token shingles of a document, for plagiarism and
+/// overlap checks.
+///
+/// Args:
+///     text_or_tokens (str | List[str]): A text, split on whitespace, or its
+///         tokens from any tokenizer.
+///     k (int): Tokens per shingle; fewer tokens than `k` form a single
+///         shingle.
+///     hashed (bool): Return the 64-bit hashes `minhash` and `simhash` use,
+///         sorted, instead of the shingles themselves in order of first
+///         occurrence.
+///
+/// Returns:
+///     List[int] | List[str]: Each shingle once.
+#[pyfunction]
+#[pyo3(signature = (text_or_tokens, k=5, hashed=true))]
+pub fn shingles(text_or_tokens: TextOrTokens, k: usize, hashed: bool) -> PyResult<Shingles> {
+    check_k(k)?;
+    Ok(shingles_of(&text_or_tokens.into_tokens(), k, hashed))
+}
+
+/// `shingles` for many documents at once, in parallel without the GIL.
+#[pyfunction]
+#[pyo3(signature = (documents, k=5, hashed=true))]
+pub fn shingles_batch(
+    py: Python<'_>,
+    documents: Vec<TextOrTokens>,
+    k: usize,
+    hashed: bool,
+) -> PyResult<Vec<Shingles>> {
+    check_k(k)?;
+    let documents: Vec<Vec<String>> = documents
+        .into_iter()
+        .map(TextOrTokens::into_tokens)
+        .collect();
+    Ok(py.detach(|| {
+        documents
+            .par_iter()
+            .map(|tokens| shingles_of(tokens, k, hashed))
+            .collect()
+    }))
+}
+
+/// Shingle sets of `documents`, hashed in parallel.
+fn shingle_sets(documents: Vec<TextOrTokens>, k: usize) -> Vec<Vec<u64>> {
+    let documents: Vec<Vec<String>> = documents
+        .into_iter()
+        .map(TextOrTokens::into_tokens)
+        .collect();
+    documents
+        .par_iter()
+        .map(|tokens| shingle_set(tokens, k))
+        .collect()
+}
+
+/// Overlap between pairs of documents, from the shingles they share.
+///
+/// Shingles are looked up in an inverted index, so only documents sharing
+/// at least one shingle are compared and pairs sharing none are never
+/// reported. Hashing and comparison run in parallel without the GIL.
+///
+/// Args:
+///     documents (List[str | List[str]]): Texts, split on whitespace, or
+///         their tokens.
+///     others (List[str | List[str]], optional): A second collection, such
+///         as known sources; every document is then compared with each of
+///         these instead of with the other documents.
+///     k (int): Tokens per shingle.
+///     threshold (float): Smallest overlap reported.
+///     metric (str): `"jaccard"` for shared shingles over all shingles of
+///         the pair, or `"containment"` for shared shingles over those of
+///         the smaller document.
+///
+/// Returns:
+///     List[Tuple[int, int, float]]: `(i, j, overlap)` sorted by `i` and
+///     `j`, where `i` indexes `documents` and `j` indexes `others`, or
+///     `documents` again with `i < j`.
+#[pyfunction]
+#[pyo3(signature = (documents, others=None, k=5, threshold=0.0, metric="jaccard"))]
+pub fn shingle_overlap(
+    py: Python<'_>,
+    documents: Vec<TextOrTokens>,
+    others: Option<Vec<TextOrTokens>>,
+    k: usize,
+    threshold: f64,
+    metric: &str,
+) -> PyResult<Vec<(usize, usize, f64)>> {
+    check_k(k)?;
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(PyValueError::new_err(format!(
+            "threshold must be between 0 and 1, got {}",
+            threshold
+        )));
+    }
+    let metric = Overlap::parse(metric)?;
+    let cross = others.is_some();
+    Ok(py.detach(|| {
+        let sets = shingle_sets(documents, k);
+        let other_sets = others.map(|others| shingle_sets(others, k));
+        let targets = other_sets.as_ref().unwrap_or(&sets);
+        let mut postings: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (j, set) in targets.iter().enumerate() {
+            for &hash in set {
+                postings.entry(hash).or_default().push(j);
+            }
+        }
+        let mut pairs: Vec<(usize, usize, f64)> = sets
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(i, set)| {
+                let mut shared: HashMap<usize, usize> = HashMap::new();
+                for hash in set {
+                    for &j in postings.get(hash).into_iter().flatten() {
+                        if cross || j > i {
+                            *shared.entry(j).or_default() += 1;
+                        }
+                    }
+                }
+                shared
+                    .into_iter()
+                    .map(move |(j, count)| (i, j, metric.score(count, set.len(), targets[j].len())))
+                    .filter(|&(_, _, overlap)| overlap >= threshold)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        pairs.sort_unstable_by_key(|&(i, j, _)| (i, j));
+        pairs
+    }))
+}
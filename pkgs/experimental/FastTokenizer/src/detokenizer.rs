@@ -0,0 +1,90 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::truecase::Truecaser;
+
+pub use fasttokenizer_core::detokenizer::{clean_up_tokenization, join_tokens};
+
+/// Byte offset of every character of `text`, plus its length.
+fn char_to_byte(text: &str) -> Vec<usize> {
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect()
+}
+
+/// Join tokens into readable text, putting spaces only around punctuation
+/// where English text has them.
+///
+/// Args:
+///     tokens (List[str]): The tokens, e.g. after filtering.
+///     truecaser (Truecaser, optional): Restores the casing of the joined
+///         text, for tokens that were lowercased.
+///
+/// Returns:
+///     str: The tokens joined into text.
+#[pyfunction]
+#[pyo3(signature = (tokens, truecaser=None))]
+pub fn detokenize(tokens: Vec<String>, truecaser: Option<PyRef<'_, Truecaser>>) -> String {
+    let text = join_tokens(&tokens);
+    match truecaser {
+        Some(truecaser) => truecaser.apply(&text),
+        None => text,
+    }
+}
+
+/// Rebuild text from tokens and their offsets into the original `text`,
+/// keeping the original spacing between tokens.
+///
+/// Tokens that touched in the original are joined directly and whitespace
+/// between them is copied over. Where tokens were removed, a single space
+/// stands in for the gap if it held any whitespace. Token texts are used as
+/// given, so normalized tokens stay normalized.
+///
+/// Args:
+///     text (str): The text the offsets refer to.
+///     tokens (List[Tuple[str, int, int]]): `(token, start, end)` tuples as
+///         returned by `tokenize_with_offsets`, in order.
+///     unit (str): `"char"` or `"byte"`, the unit of the offsets.
+#[pyfunction]
+#[pyo3(signature = (text, tokens, unit="char"))]
+pub fn detokenize_with_offsets(
+    text: &str,
+    tokens: Vec<(String, usize, usize)>,
+    unit: &str,
+) -> PyResult<String> {
+    let to_byte: Option<Vec<usize>> = match unit {
+        "char" => (!text.is_ascii()).then(|| char_to_byte(text)),
+        "byte" => None,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown offset unit '{}', expected 'char' or 'byte'",
+                unit
+            )))
+        }
+    };
+    let byte = |offset: usize| -> PyResult<usize> {
+        let byte = match &to_byte {
+            Some(table) => table.get(offset).copied(),
+            None => (offset <= text.len()).then_some(offset),
+        };
+        byte.filter(|&b| text.is_char_boundary(b))
+            .ok_or_else(|| PyValueError::new_err(format!("Offset {} is outside the text", offset)))
+    };
+    let mut out = String::new();
+    let mut previous_end: Option<usize> = None;
+    for (token, start, end) in &tokens {
+        let (start, end) = (byte(*start)?, byte(*end)?);
+        if let Some(previous_end) = previous_end.filter(|&e| e < start) {
+            let gap = &text[previous_end..start];
+            if gap.chars().all(char::is_whitespace) {
+                out.push_str(gap);
+            } else if gap.chars().any(char::is_whitespace) {
+                out.push(' ');
+            }
+        }
+        out.push_str(token);
+        previous_end = Some(previous_end.map_or(end, |e| e.max(end)));
+    }
+    Ok(out)
+}
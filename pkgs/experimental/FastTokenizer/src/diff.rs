@@ -0,0 +1,263 @@
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+use crate::tokenizer::{extract_tokenizer, offsets, Token};
+
+/// One step of an edit script.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Op::Equal => "equal",
+            Op::Delete => "delete",
+            Op::Insert => "insert",
+        }
+    }
+}
+
+/// A diff opcode: `(tag, old_start, old_end, new_start, new_end)`.
+type Opcode = (&'static str, usize, usize, usize, usize);
+
+/// Append `len` steps of `op` to `script`, merging with the last run.
+fn push(script: &mut Vec<(Op, usize)>, op: Op, len: usize) {
+    if len == 0 {
+        return;
+    }
+    match script.last_mut() {
+        Some((last, run)) if *last == op => *run += len,
+        _ => script.push((op, len)),
+    }
+}
+
+/// A shortest edit script turning `a` into `b`, using Myers' algorithm.
+///
+/// Common prefixes and suffixes are split off first; what remains is cut
+/// at the middle of an optimal path, found by searching from both ends at
+/// once, and each half is diffed in turn. Memory stays linear in the input
+/// however many edits there are.
+fn diff(a: &[u32], b: &[u32], script: &mut Vec<(Op, usize)>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+    push(script, Op::Equal, prefix);
+    if a.is_empty() || b.is_empty() {
+        push(script, Op::Delete, a.len());
+        push(script, Op::Insert, b.len());
+    } else {
+        let (x, y) = middle(a, b);
+        diff(&a[..x], &b[..y], script);
+        diff(&a[x..], &b[y..], script);
+    }
+    push(script, Op::Equal, suffix);
+}
+
+/// A point on an optimal edit path from `(0, 0)` to `(a.len(), b.len())`
+/// that splits it into two strictly smaller problems. `a` and `b` must be
+/// non-empty and differ in their first and last elements.
+fn middle(a: &[u32], b: &[u32]) -> (usize, usize) {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    // Furthest x reached on each diagonal `k = x - y`, from the start
+    // (`forward`) and, in mirrored coordinates, from the end (`backward`).
+    let mut forward = vec![-1isize; 2 * max_d as usize + 1];
+    let mut backward = forward.clone();
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+    let delta = n - m;
+    // With an odd delta the paths meet on a forward step, else a backward one.
+    let odd = delta % 2 != 0;
+    // Diagonals that ran off the grid are trimmed from later rounds.
+    let (mut forward_start, mut forward_end) = (0, 0);
+    let (mut backward_start, mut backward_end) = (0, 0);
+    for d in 0..max_d {
+        for k in (-d + forward_start..=d - forward_end).step_by(2) {
+            let i = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && forward[i - 1] < forward[i + 1]) {
+                forward[i + 1]
+            } else {
+                forward[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[i] = x;
+            if x > n {
+                forward_end += 2;
+            } else if y > m {
+                forward_start += 2;
+            } else if odd {
+                let j = offset + delta - k;
+                if (0..=2 * max_d).contains(&j)
+                    && backward[j as usize] != -1
+                    && x >= n - backward[j as usize]
+                {
+                    return (x as usize, y as usize);
+                }
+            }
+        }
+        for k in (-d + backward_start..=d - backward_end).step_by(2) {
+            let i = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && backward[i - 1] < backward[i + 1]) {
+                backward[i + 1]
+            } else {
+                backward[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[i] = x;
+            if x > n {
+                backward_end += 2;
+            } else if y > m {
+                backward_start += 2;
+            } else if !odd {
+                let j = offset + delta - k;
+                if (0..=2 * max_d).contains(&j) && forward[j as usize] != -1 {
+                    let forward_x = forward[j as usize];
+                    if forward_x >= n - x {
+                        return (forward_x as usize, (forward_x - (j - offset)) as usize);
+                    }
+                }
+            }
+        }
+    }
+    // Unreachable for valid input: the paths always meet by `max_d`.
+    (a.len(), 0)
+}
+
+/// Opcodes over token indices for the edit script between two token lists.
+///
+/// Within each changed region the deletion comes before the insertion.
+fn opcodes<'a>(old: &'a [String], new: &'a [String]) -> Vec<Opcode> {
+    let mut ids: HashMap<&str, u32> = HashMap::new();
+    let mut intern = |tokens: &'a [String]| -> Vec<u32> {
+        tokens
+            .iter()
+            .map(|t| {
+                let next = ids.len() as u32;
+                *ids.entry(t.as_str()).or_insert(next)
+            })
+            .collect()
+    };
+    let (a, b) = (intern(old), intern(new));
+    let mut script = Vec::new();
+    diff(&a, &b, &mut script);
+    let mut codes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut pending = (0, 0);
+    // A final empty run flushes the last changed region.
+    for (op, len) in script.into_iter().chain([(Op::Equal, 0)]) {
+        match op {
+            Op::Delete => pending.0 += len,
+            Op::Insert => pending.1 += len,
+            Op::Equal => {
+                let (deleted, inserted) = std::mem::take(&mut pending);
+                if deleted > 0 {
+                    codes.push((Op::Delete.name(), i, i + deleted, j, j));
+                    i += deleted;
+                }
+                if inserted > 0 {
+                    codes.push((Op::Insert.name(), i, i, j, j + inserted));
+                    j += inserted;
+                }
+                if len > 0 {
+                    codes.push((Op::Equal.name(), i, i + len, j, j + len));
+                    i += len;
+                    j += len;
+                }
+            }
+        }
+    }
+    codes
+}
+
+/// Text spans, in `unit`, of the token ranges `ranges` of `tokens`. An
+/// empty range sits at the end of the token before it.
+fn text_spans(
+    text: &str,
+    tokens: &[Token],
+    ranges: impl Iterator<Item = (usize, usize)>,
+    unit: &str,
+) -> PyResult<Vec<(usize, usize)>> {
+    let spans = ranges
+        .map(|(start, end)| {
+            let at = |i: usize| if i == 0 { 0 } else { tokens[i - 1].end };
+            if start == end {
+                Token::new(String::new(), at(start), at(start))
+            } else {
+                Token::new(String::new(), tokens[start].start, tokens[end - 1].end)
+            }
+        })
+        .collect();
+    Ok(offsets(text, spans, unit)?
+        .into_iter()
+        .map(|(_, start, end)| (start, end))
+        .collect())
+}
+
+/// Diff two texts token by token.
+///
+/// Both texts are tokenized with `tokenizer` and compared with Myers'
+/// algorithm, which finds a shortest edit script, so unchanged tokens are
+/// matched even when the surrounding text moved. Runs in linear memory and
+/// releases the GIL.
+///
+/// Args:
+///     old_text (str): The original text.
+///     new_text (str): The changed text.
+///     tokenizer: Any FastTokenizer tokenizer or pipeline; tokens are
+///         compared by their text.
+///     unit (str, optional): `None` for token indices, or `"char"` or
+///         `"byte"` for offsets into the texts. An empty range sits at the
+///         end of the token before it.
+///
+/// Returns:
+///     List[Tuple[str, int, int, int, int]]: Opcodes `(tag, old_start,
+///     old_end, new_start, new_end)` in the style of `difflib`'s
+///     `get_opcodes`, with tags `"equal"`, `"delete"` and `"insert"`. A
+///     replaced run is a deletion followed by an insertion.
+#[pyfunction]
+#[pyo3(signature = (old_text, new_text, tokenizer, unit=None))]
+pub fn token_diff(
+    py: Python<'_>,
+    old_text: &str,
+    new_text: &str,
+    tokenizer: &Bound<'_, PyAny>,
+    unit: Option<&str>,
+) -> PyResult<Vec<Opcode>> {
+    let tokenizer = extract_tokenizer(tokenizer)?;
+    let (old, new, codes) = py.detach(|| {
+        let old = tokenizer.tokenize_with_source_offsets(old_text);
+        let new = tokenizer.tokenize_with_source_offsets(new_text);
+        let texts = |tokens: &[Token]| tokens.iter().map(|t| t.text.clone()).collect::<Vec<_>>();
+        let codes = opcodes(&texts(&old), &texts(&new));
+        (old, new, codes)
+    });
+    let Some(unit) = unit else {
+        return Ok(codes);
+    };
+    let old_spans = text_spans(old_text, &old, codes.iter().map(|c| (c.1, c.2)), unit)?;
+    let new_spans = text_spans(new_text, &new, codes.iter().map(|c| (c.3, c.4)), unit)?;
+    Ok(codes
+        .into_iter()
+        .zip(old_spans.into_iter().zip(new_spans))
+        .map(|(code, (old, new))| (code.0, old.0, old.1, new.0, new.1))
+        .collect())
+}
@@ -0,0 +1,263 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::aligned::{AlignedString, Replacement};
+
+/// Emoji sequences: flags, keycaps, and pictographs with their skin tone
+/// modifiers, variation selectors and subdivision tags, joined by ZWJ.
+/// Pictographs that default to text presentation, like `©`, only count
+/// when followed by the emoji variation selector or a skin tone.
+const EMOJI_PATTERN: &str = concat!(
+    r"\p{Regional_Indicator}{2}|[0-9#*]\x{FE0F}?\x{20E3}",
+    r"|(?:\p{Emoji_Presentation}|\p{Emoji_Modifier_Base}\p{Emoji_Modifier}",
+    r"|\p{Extended_Pictographic}\x{FE0F})\p{Emoji_Modifier}?\x{FE0F}?[\x{E0020}-\x{E007F}]*",
+    r"(?:\x{200D}\p{Extended_Pictographic}\p{Emoji_Modifier}?\x{FE0F}?)*",
+);
+
+/// Western emoticons, as `SocialTokenizer` recognizes them.
+const EMOTICON_PATTERN: &str = r"<3|[:;=][-']?[()\[\]DPpOo/\\|]";
+
+fn emoji_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(EMOJI_PATTERN).expect("valid emoji pattern"))
+}
+
+fn emoticon_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(EMOTICON_PATTERN).expect("valid emoticon pattern"))
+}
+
+/// What the normalizer does with emoji or emoticons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmojiMode {
+    /// Keep them unchanged and apart from the surrounding text, so they
+    /// come out as tokens of their own.
+    Keep,
+    Strip,
+    /// Replace them with their `:name:`.
+    Shortcode,
+}
+
+impl EmojiMode {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "keep" => Ok(EmojiMode::Keep),
+            "strip" => Ok(EmojiMode::Strip),
+            "shortcode" => Ok(EmojiMode::Shortcode),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown emoji mode '{}', expected 'keep', 'strip' or 'shortcode'",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmojiMode::Keep => "keep",
+            EmojiMode::Strip => "strip",
+            EmojiMode::Shortcode => "shortcode",
+        }
+    }
+}
+
+/// The emoji an emoticon stands for.
+fn emoticon_emoji(emoticon: &str) -> &'static str {
+    if emoticon == "<3" {
+        return "❤️";
+    }
+    let wink = emoticon.starts_with(';');
+    let tears = emoticon.contains('\'');
+    match emoticon.chars().last() {
+        Some(')' | ']') if tears => "😂",
+        Some(')' | ']') if wink => "😉",
+        Some(')' | ']') => "🙂",
+        Some('(' | '[') if tears => "😢",
+        Some('(' | '[') => "🙁",
+        Some('D') => "😃",
+        Some('P' | 'p') if wink => "😜",
+        Some('P' | 'p') => "😛",
+        Some('O' | 'o') => "😮",
+        Some('/' | '\\') => "😕",
+        _ => "😐",
+    }
+}
+
+/// `name` as a shortcode: lowercased, with runs of anything but letters
+/// and digits turned into one underscore.
+fn slug(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 2);
+    out.push(':');
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        } else if !out.ends_with([':', '_']) {
+            out.push('_');
+        }
+    }
+    if out.ends_with('_') {
+        out.pop();
+    }
+    out.push(':');
+    out
+}
+
+/// The `:name:` of an emoji sequence, from its Unicode CLDR short name
+/// (`:thumbs_up_medium_skin_tone:`). Sequences too new to be known are
+/// named after their first emoji, or kept as they are.
+pub fn shortcode(sequence: &str) -> String {
+    let first = sequence
+        .chars()
+        .next()
+        .map(String::from)
+        .unwrap_or_default();
+    match emojis::get(sequence).or_else(|| emojis::get(&first)) {
+        Some(emoji) => slug(emoji.name()),
+        None => sequence.to_string(),
+    }
+}
+
+/// Whether `text[start..end]` is preceded or followed by a letter or digit.
+fn touches_word(text: &str, start: usize, end: usize) -> bool {
+    text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(char::is_alphanumeric)
+        || text[end..]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric)
+}
+
+/// An emoji or emoticon found in text.
+struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub emoticon: bool,
+}
+
+/// The emoji and, with `emoticons`, the emoticons of `text`, in order.
+/// Emoticons glued to a word, like the `:/` of `https://`, are skipped.
+fn find(text: &str, emoji: bool, emoticons: bool) -> Vec<Match> {
+    let mut found: Vec<Match> = Vec::new();
+    if emoji {
+        found.extend(emoji_regex().find_iter(text).map(|m| Match {
+            start: m.start(),
+            end: m.end(),
+            emoticon: false,
+        }));
+    }
+    if emoticons {
+        found.extend(
+            emoticon_regex()
+                .find_iter(text)
+                .filter(|m| !touches_word(text, m.start(), m.end()))
+                .map(|m| Match {
+                    start: m.start(),
+                    end: m.end(),
+                    emoticon: true,
+                }),
+        );
+        found.sort_by_key(|m| m.start);
+    }
+    found
+}
+
+/// What an emoji or emoticon becomes in `mode`.
+fn replace(text: &str, emoticon: bool, mode: EmojiMode) -> String {
+    match mode {
+        EmojiMode::Keep => text.to_string(),
+        EmojiMode::Strip => String::new(),
+        EmojiMode::Shortcode if emoticon => shortcode(emoticon_emoji(text)),
+        EmojiMode::Shortcode => shortcode(text),
+    }
+}
+
+/// The emoji normalization step: what happens to emoji and to emoticons,
+/// each left alone when `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EmojiStage {
+    pub emoji: Option<EmojiMode>,
+    pub emoticons: Option<EmojiMode>,
+}
+
+impl EmojiStage {
+    pub fn is_active(&self) -> bool {
+        self.emoji.is_some() || self.emoticons.is_some()
+    }
+
+    /// The spans of the emoji and emoticons of `text` that are kept whole.
+    pub fn kept(&self, text: &str) -> Vec<(usize, usize)> {
+        find(
+            text,
+            self.emoji == Some(EmojiMode::Keep),
+            self.emoticons == Some(EmojiMode::Keep),
+        )
+        .into_iter()
+        .map(|m| (m.start, m.end))
+        .collect()
+    }
+
+    /// What every emoji and emoticon of `text` becomes, set apart from
+    /// neighbouring words by a space, as are the words on either side of a
+    /// stripped emoji.
+    pub fn replacements(&self, text: &str) -> Vec<Replacement> {
+        find(text, self.emoji.is_some(), self.emoticons.is_some())
+            .into_iter()
+            .map(|m| {
+                let mode = if m.emoticon {
+                    self.emoticons
+                } else {
+                    self.emoji
+                };
+                Replacement {
+                    start: m.start,
+                    end: m.end,
+                    text: replace(
+                        &text[m.start..m.end],
+                        m.emoticon,
+                        mode.unwrap_or(EmojiMode::Keep),
+                    ),
+                    apart: true,
+                }
+            })
+            .collect()
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        AlignedString::new(text, 0)
+            .replace(&self.replacements(text), |piece| piece)
+            .text
+    }
+}
+
+/// Strip emoji and emoticons, replace them with `:name:` shortcodes or set
+/// them apart as tokens of their own.
+///
+/// Emoji are matched as whole sequences, so ZWJ sequences (`👩‍💻`), flags,
+/// keycaps and skin tone modifiers (`👍🏽`) are handled as one. Shortcodes
+/// are derived from the Unicode CLDR short names, as in
+/// `:thumbs_up_medium_skin_tone:`; an emoticon is named after the emoji it
+/// stands for (`:)` becomes `:slightly_smiling_face:`). Emoticons glued to
+/// a word, like the `:/` of `https://`, are left alone.
+///
+/// Args:
+///     text (str): Input text.
+///     emoji (str, optional): `"keep"`, `"strip"` or `"shortcode"`, or
+///         `None` to leave emoji alone.
+///     emoticons (str, optional): The same, for emoticons such as `:-)`
+///         and `<3`.
+#[pyfunction]
+#[pyo3(signature = (text, emoji=Some("shortcode"), emoticons=None))]
+pub fn normalize_emoji(
+    text: &str,
+    emoji: Option<&str>,
+    emoticons: Option<&str>,
+) -> PyResult<String> {
+    let stage = EmojiStage {
+        emoji: emoji.map(EmojiMode::parse).transpose()?,
+        emoticons: emoticons.map(EmojiMode::parse).transpose()?,
+    };
+    Ok(stage.apply(text))
+}
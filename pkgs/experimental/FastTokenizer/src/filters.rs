@@ -0,0 +1,220 @@
+use fasttokenizer_core::Flags;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use std::sync::Arc;
+
+use crate::blocklist::BlocklistFilter;
+use crate::stopwords::StopWordFilter;
+use crate::tokenizer::py_err;
+
+/// A post-tokenization stage deciding which tokens to keep.
+pub trait TokenFilter: Send + Sync {
+    fn keep(&self, token: &str) -> bool;
+
+    /// A new Python object holding this filter.
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>>;
+}
+
+/// Extract a Rust filter from any of the filter classes exposed to Python.
+pub fn extract_filter(obj: &Bound<'_, PyAny>) -> PyResult<Arc<dyn TokenFilter>> {
+    if let Ok(filter) = obj.cast::<BlocklistFilter>() {
+        return Ok(Arc::new(filter.borrow().clone()));
+    }
+    if let Ok(filter) = obj.cast::<StopWordFilter>() {
+        return Ok(Arc::new(filter.borrow().clone()));
+    }
+    if let Ok(filter) = obj.cast::<LengthFilter>() {
+        return Ok(Arc::new(filter.borrow().clone()));
+    }
+    if let Ok(filter) = obj.cast::<NumericFilter>() {
+        return Ok(Arc::new(filter.borrow().clone()));
+    }
+    if let Ok(filter) = obj.cast::<RegexFilter>() {
+        return Ok(Arc::new(filter.borrow().clone()));
+    }
+    Err(PyTypeError::new_err(format!(
+        "Expected a token filter, got {}",
+        obj.get_type().name()?
+    )))
+}
+
+fn filter_tokens(filter: &dyn TokenFilter, tokens: Vec<String>) -> Vec<String> {
+    tokens.into_iter().filter(|t| filter.keep(t)).collect()
+}
+
+/// Keeps tokens whose length in characters lies within bounds.
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Debug)]
+pub struct LengthFilter {
+    #[pyo3(get)]
+    min_length: usize,
+    #[pyo3(get)]
+    max_length: Option<usize>,
+}
+
+impl TokenFilter for LengthFilter {
+    fn keep(&self, token: &str) -> bool {
+        let length = if token.is_ascii() {
+            token.len()
+        } else {
+            token.chars().count()
+        };
+        length >= self.min_length && self.max_length.is_none_or(|max| length <= max)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+}
+
+#[pymethods]
+impl LengthFilter {
+    /// Args:
+    ///     min_length (int): Minimum number of characters.
+    ///     max_length (int, optional): Maximum number of characters.
+    #[new]
+    #[pyo3(signature = (min_length=1, max_length=None))]
+    fn new(min_length: usize, max_length: Option<usize>) -> PyResult<Self> {
+        if max_length.is_some_and(|max| max < min_length) {
+            return Err(PyValueError::new_err(
+                "max_length must not be smaller than min_length",
+            ));
+        }
+        Ok(LengthFilter {
+            min_length,
+            max_length,
+        })
+    }
+
+    /// Constructor arguments recreating this filter, for `pickle` and `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("min_length", self.min_length)?;
+        kwargs.set_item("max_length", self.max_length)?;
+        Ok(((), kwargs))
+    }
+
+    /// `tokens` without those of the wrong length.
+    fn filter(&self, tokens: Vec<String>) -> Vec<String> {
+        filter_tokens(self, tokens)
+    }
+}
+
+/// Drops purely numeric tokens such as `42`, `-3.5`, `1,000` or `²`.
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Debug, Default)]
+pub struct NumericFilter;
+
+/// Whether `token` is a number: numeric characters, optionally with a sign
+/// and decimal or thousands separators.
+pub fn is_numeric(token: &str) -> bool {
+    let digits = token.strip_prefix(['+', '-']).unwrap_or(token);
+    digits.chars().any(char::is_numeric)
+        && digits
+            .chars()
+            .all(|c| c.is_numeric() || matches!(c, '.' | ',' | '_'))
+}
+
+impl TokenFilter for NumericFilter {
+    fn keep(&self, token: &str) -> bool {
+        !is_numeric(token)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+}
+
+#[pymethods]
+impl NumericFilter {
+    #[new]
+    fn new() -> Self {
+        NumericFilter
+    }
+
+    /// Constructor arguments recreating this filter, for `pickle` and `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> ((), Bound<'py, PyDict>) {
+        ((), PyDict::new(py))
+    }
+
+    /// `tokens` without the numbers.
+    fn filter(&self, tokens: Vec<String>) -> Vec<String> {
+        filter_tokens(self, tokens)
+    }
+}
+
+/// Drops, or keeps only, tokens that fully match a regular expression.
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Debug)]
+pub struct RegexFilter {
+    /// `pattern` anchored at both ends.
+    regex: Regex,
+    #[pyo3(get)]
+    pattern: String,
+    /// Keep matching tokens instead of dropping them.
+    keep_matches: bool,
+}
+
+impl TokenFilter for RegexFilter {
+    fn keep(&self, token: &str) -> bool {
+        self.regex.is_match(token) == self.keep_matches
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+}
+
+#[pymethods]
+impl RegexFilter {
+    /// Args:
+    ///     pattern (str): Regular expression a token must match entirely.
+    ///     mode (str): `"drop"` removes matching tokens, `"keep"` removes
+    ///         all others.
+    #[new]
+    #[pyo3(signature = (pattern, mode="drop"))]
+    fn new(pattern: &str, mode: &str) -> PyResult<Self> {
+        let keep_matches = match mode {
+            "drop" => false,
+            "keep" => true,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown mode '{}', expected 'drop' or 'keep'",
+                    other
+                )))
+            }
+        };
+        let regex = Flags::default()
+            .compile(&format!("^(?:{})$", pattern))
+            .map_err(py_err)?;
+        Ok(RegexFilter {
+            regex,
+            pattern: pattern.to_string(),
+            keep_matches,
+        })
+    }
+
+    /// Constructor arguments recreating this filter, for `pickle` and `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("pattern", &self.pattern)?;
+        kwargs.set_item("mode", self.mode())?;
+        Ok(((), kwargs))
+    }
+
+    #[getter]
+    fn mode(&self) -> &'static str {
+        if self.keep_matches {
+            "keep"
+        } else {
+            "drop"
+        }
+    }
+
+    /// `tokens` after dropping or keeping the matches.
+    fn filter(&self, tokens: Vec<String>) -> Vec<String> {
+        filter_tokens(self, tokens)
+    }
+}
@@ -0,0 +1,252 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// A string prepared for computing its Levenshtein distance to many others.
+///
+/// Strings of up to 64 characters use Myers' bit-parallel algorithm, which
+/// processes a whole column of the edit matrix per step; longer ones fall
+/// back to the row-by-row dynamic program.
+struct Pattern {
+    chars: Vec<char>,
+    /// Bit `i` of `masks[c]` is set when the pattern has `c` at position `i`.
+    masks: HashMap<char, u64>,
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut masks = HashMap::new();
+        if chars.len() <= 64 {
+            for (i, &c) in chars.iter().enumerate() {
+                *masks.entry(c).or_insert(0) |= 1 << i;
+            }
+        }
+        Pattern { chars, masks }
+    }
+
+    fn distance(&self, text: &[char]) -> usize {
+        let m = self.chars.len();
+        if m == 0 {
+            return text.len();
+        }
+        if m > 64 {
+            return levenshtein_dp(&self.chars, text);
+        }
+        let last = 1u64 << (m - 1);
+        let mut pv = if m == 64 { u64::MAX } else { (1 << m) - 1 };
+        let mut mv = 0u64;
+        let mut score = m;
+        for c in text {
+            let eq = self.masks.get(c).copied().unwrap_or(0);
+            let xv = eq | mv;
+            let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+            let mut ph = mv | !(xh | pv);
+            let mut mh = pv & xh;
+            if ph & last != 0 {
+                score += 1;
+            } else if mh & last != 0 {
+                score -= 1;
+            }
+            ph = (ph << 1) | 1;
+            mh <<= 1;
+            pv = mh | !(xv | ph);
+            mv = ph & xv;
+        }
+        score
+    }
+}
+
+fn levenshtein_dp(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Unrestricted Damerau-Levenshtein distance: adjacent transpositions
+/// count as one edit, even when the transposed characters are edited again.
+fn damerau_dp(a: &[char], b: &[char]) -> usize {
+    let (n, m) = (a.len(), b.len());
+    let infinity = n + m;
+    let width = m + 2;
+    let mut d = vec![0; (n + 2) * width];
+    d[0] = infinity;
+    for i in 0..=n {
+        d[(i + 1) * width] = infinity;
+        d[(i + 1) * width + 1] = i;
+    }
+    for j in 0..=m {
+        d[j + 1] = infinity;
+        d[width + j + 1] = j;
+    }
+    let mut last_row: HashMap<char, usize> = HashMap::new();
+    for i in 1..=n {
+        let mut last_column = 0;
+        for j in 1..=m {
+            let k = last_row.get(&b[j - 1]).copied().unwrap_or(0);
+            let l = last_column;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_column = j;
+                0
+            } else {
+                1
+            };
+            d[(i + 1) * width + j + 1] = (d[i * width + j] + cost)
+                .min(d[(i + 1) * width + j] + 1)
+                .min(d[i * width + j + 1] + 1)
+                .min(d[k * width + l] + (i - k - 1) + 1 + (j - l - 1));
+        }
+        last_row.insert(a[i - 1], i);
+    }
+    d[(n + 1) * width + m + 1]
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+    for (i, &c) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == c {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+    let a_order = a.iter().zip(&a_matched).filter(|(_, &m)| m).map(|(c, _)| c);
+    let b_order = b.iter().zip(&b_matched).filter(|(_, &m)| m).map(|(c, _)| c);
+    let transpositions = a_order.zip(b_order).filter(|(x, y)| x != y).count() / 2;
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+fn jaro_winkler_chars(a: &[char], b: &[char], prefix_weight: f64) -> f64 {
+    let similarity = jaro(a, b);
+    let prefix = a.iter().zip(b).take(4).take_while(|(x, y)| x == y).count();
+    similarity + prefix as f64 * prefix_weight * (1.0 - similarity)
+}
+
+/// Levenshtein distance: the fewest single-character insertions, deletions
+/// and substitutions turning `a` into `b`.
+#[pyfunction]
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    Pattern::new(a).distance(&b)
+}
+
+/// Damerau-Levenshtein distance: like `levenshtein`, but swapping two
+/// adjacent characters is a single edit.
+#[pyfunction]
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    damerau_dp(&a, &b)
+}
+
+/// Jaro-Winkler similarity between 0.0 and 1.0, favouring strings that
+/// share a prefix of up to four characters.
+///
+/// Args:
+///     a (str): First string.
+///     b (str): Second string.
+///     prefix_weight (float): Boost per shared prefix character, at most 0.25.
+#[pyfunction]
+#[pyo3(signature = (a, b, prefix_weight=0.1))]
+pub fn jaro_winkler(a: &str, b: &str, prefix_weight: f64) -> PyResult<f64> {
+    if !(0.0..=0.25).contains(&prefix_weight) {
+        return Err(PyValueError::new_err(format!(
+            "prefix_weight must be between 0 and 0.25, got {}",
+            prefix_weight
+        )));
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    Ok(jaro_winkler_chars(&a, &b, prefix_weight))
+}
+
+/// The candidates closest to `query` by edit distance, computed in parallel
+/// without holding the GIL.
+///
+/// Args:
+///     query (str): The string to match, e.g. a misspelled word.
+///     candidates (List[str]): Strings to match against, e.g. a dictionary.
+///     max_distance (int, optional): Leave out candidates further away.
+///     limit (int, optional): Return at most this many matches.
+///     metric (str): `"levenshtein"` or `"damerau_levenshtein"`.
+///
+/// Returns:
+///     List[Tuple[str, int, int]]: `(candidate, distance, index)`, closest
+///     first and in candidate order among equal distances.
+#[pyfunction]
+#[pyo3(signature = (query, candidates, max_distance=None, limit=None, metric="levenshtein"))]
+pub fn best_matches(
+    py: Python<'_>,
+    query: &str,
+    candidates: Vec<String>,
+    max_distance: Option<usize>,
+    limit: Option<usize>,
+    metric: &str,
+) -> PyResult<Vec<(String, usize, usize)>> {
+    let damerau = match metric {
+        "levenshtein" => false,
+        "damerau_levenshtein" => true,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown metric '{}', expected 'levenshtein' or 'damerau_levenshtein'",
+                other
+            )))
+        }
+    };
+    let pattern = Pattern::new(query);
+    let query_len = pattern.chars.len();
+    let mut matches: Vec<(usize, usize)> = py.detach(|| {
+        candidates
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| {
+                let chars: Vec<char> = candidate.chars().collect();
+                // The length difference is a lower bound on either distance.
+                if max_distance.is_some_and(|max| query_len.abs_diff(chars.len()) > max) {
+                    return None;
+                }
+                let distance = if damerau {
+                    damerau_dp(&pattern.chars, &chars)
+                } else {
+                    pattern.distance(&chars)
+                };
+                max_distance
+                    .is_none_or(|max| distance <= max)
+                    .then_some((distance, index))
+            })
+            .collect()
+    });
+    matches.sort_unstable();
+    matches.truncate(limit.unwrap_or(usize::MAX));
+    Ok(matches
+        .into_iter()
+        .map(|(distance, index)| (candidates[index].clone(), distance, index))
+        .collect())
+}
@@ -0,0 +1,186 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config;
+use crate::intern::token_list;
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// Splits text into extended grapheme clusters (UAX #29), so emoji ZWJ
+/// sequences, flags and base characters with combining marks each form a
+/// single token.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Default)]
+pub struct GraphemeTokenizer {
+    skip_whitespace: bool,
+    vocab: Shared<Option<Vocab>>,
+}
+
+impl GraphemeTokenizer {
+    /// The graphemes of `text` to emit, with their byte offsets.
+    fn graphemes<'a>(&self, text: &'a str) -> impl Iterator<Item = (usize, &'a str)> {
+        let skip_whitespace = self.skip_whitespace;
+        text.grapheme_indices(true)
+            .filter(move |(_, g)| !(skip_whitespace && g.chars().all(char::is_whitespace)))
+    }
+}
+
+impl Tokenizer for GraphemeTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.graphemes(text)
+            .map(|(i, g)| Token::new(g, i, i + g.len()))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.graphemes(text)
+            .map(|(i, g)| (i, i + g.len()))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        tokens.concat()
+    }
+}
+
+tokenizer_pymethods!(GraphemeTokenizer, encode_batch);
+
+#[pymethods]
+impl GraphemeTokenizer {
+    /// Create a grapheme cluster tokenizer.
+    ///
+    /// Args:
+    ///     skip_whitespace (bool): Leave out whitespace clusters.
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    #[new]
+    #[pyo3(signature = (skip_whitespace=false, vocab=None))]
+    fn new(skip_whitespace: bool, vocab: Option<PyRef<'_, Vocab>>) -> Self {
+        GraphemeTokenizer {
+            skip_whitespace,
+            vocab: vocab.map(|v| v.clone()).into(),
+        }
+    }
+
+    /// Constructor arguments recreating this tokenizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("skip_whitespace", self.skip_whitespace)?;
+        kwargs.set_item("vocab", Option::clone(&self.vocab.load()))?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the tokenizer's configuration to JSON.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a tokenizer from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Number of grapheme clusters in `input`, i.e. its user-perceived length.
+    fn count(&self, input: &str) -> usize {
+        input.graphemes(true).count()
+    }
+
+    /// Cut `input` to at most `max_length` grapheme clusters without
+    /// splitting any of them.
+    fn truncate<'a>(&self, input: &'a str, max_length: usize) -> &'a str {
+        match input.grapheme_indices(true).nth(max_length) {
+            Some((end, _)) => &input[..end],
+            None => input,
+        }
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per grapheme cluster.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        require_vocab(&self.vocab)?.encode_text(self, input)
+    }
+
+    /// Turn ids back into text by concatenating the grapheme clusters.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        require_vocab(&self.vocab)?
+            .decode_ids(&ids)
+            .map(|tokens| tokens.concat())
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
+    }
+}
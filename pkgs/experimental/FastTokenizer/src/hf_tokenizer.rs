@@ -0,0 +1,1594 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use fasttokenizer_core::cjk::is_cjk;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use regex::Regex;
+use serde_json::{json, Value};
+use spm_precompiled::Precompiled;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use unicode_categories::UnicodeCategories;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::aligned::AlignedString;
+use crate::bpe::{
+    bytes_to_unicode, split_with_lookahead, unicode_to_bytes, BpeOptions, BpeTokenizer,
+};
+use crate::config;
+use crate::detokenizer::clean_up_tokenization;
+use crate::intern::token_list;
+use crate::padding::{truncate, windows, BatchInput, BatchOutput, Padding, Truncation, Windows};
+use crate::sentencepiece::{apply_charsmap, SentencePieceTokenizer};
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::wordpiece::{is_bert_control, is_bert_punctuation, WordPieceTokenizer};
+
+type JsonResult<T> = Result<T, String>;
+
+fn field<'a>(value: &'a Value, key: &str) -> JsonResult<&'a Value> {
+    value
+        .get(key)
+        .filter(|v| !v.is_null())
+        .ok_or_else(|| format!("Missing field '{}'", key))
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> JsonResult<&'a str> {
+    field(value, key)?
+        .as_str()
+        .ok_or_else(|| format!("Field '{}' must be a string", key))
+}
+
+fn opt_str(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn bool_field(value: &Value, key: &str, default: bool) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(default)
+}
+
+fn type_name(value: &Value) -> JsonResult<&str> {
+    str_field(value, "type")
+}
+
+fn list<'a>(value: &'a Value, key: &str) -> JsonResult<&'a Vec<Value>> {
+    field(value, key)?
+        .as_array()
+        .ok_or_else(|| format!("Field '{}' must be a list", key))
+}
+
+fn vocab_map(value: &Value) -> JsonResult<HashMap<String, u32>> {
+    serde_json::from_value(field(value, "vocab")?.clone())
+        .map_err(|e| format!("Invalid vocab: {}", e))
+}
+
+/// Translate an Oniguruma pattern to the `regex` crate. Possessive quantifiers
+/// become greedy ones and `\s+(?!\S)|\s+` becomes a trailing `(\s+)` group to be
+/// used with `split_with_lookahead`.
+fn translate_pattern(pattern: &str) -> String {
+    let pattern = pattern.replace(r"\s+(?!\S)|\s+", r"(\s+)");
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    let (mut escaped, mut in_class) = (false, false);
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '?' | '*' | '+' | '}' if !in_class && chars.peek() == Some(&'+') => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// A `String` or `Regex` pattern from a `tokenizer.json`.
+struct Pattern {
+    re: Regex,
+    /// The pattern ends in the `(\s+)` group standing in for a lookahead.
+    lookahead: bool,
+}
+
+impl Pattern {
+    fn from_json(value: &Value) -> JsonResult<Self> {
+        let pattern = field(value, "pattern")?;
+        let source = if let Some(s) = pattern.get("String").and_then(Value::as_str) {
+            regex::escape(s)
+        } else if let Some(r) = pattern.get("Regex").and_then(Value::as_str) {
+            translate_pattern(r)
+        } else {
+            return Err("Pattern must be a String or Regex".to_string());
+        };
+        let re = Regex::new(&source).map_err(|e| format!("Invalid pattern: {}", e))?;
+        Ok(Pattern {
+            lookahead: source.ends_with(r"|(\s+)") && re.captures_len() == 2,
+            re,
+        })
+    }
+
+    /// Byte ranges of all matches in `text`.
+    fn matches(&self, text: &str) -> Vec<(usize, usize)> {
+        if self.lookahead {
+            return split_with_lookahead(&self.re, text);
+        }
+        self.re
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+}
+
+/// Normalizer stages of a `tokenizer.json`.
+enum NormalizerStep {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+    Lowercase,
+    StripAccents,
+    Strip {
+        left: bool,
+        right: bool,
+    },
+    Replace {
+        pattern: Regex,
+        content: String,
+    },
+    Prepend(String),
+    Bert {
+        clean_text: bool,
+        handle_chinese_chars: bool,
+        strip_accents: bool,
+        lowercase: bool,
+    },
+    Precompiled(Box<Precompiled>),
+    Sequence(Vec<NormalizerStep>),
+}
+
+impl NormalizerStep {
+    fn from_json(value: &Value) -> JsonResult<Self> {
+        Ok(match type_name(value)? {
+            "NFC" => NormalizerStep::Nfc,
+            "NFD" => NormalizerStep::Nfd,
+            "NFKC" => NormalizerStep::Nfkc,
+            "NFKD" => NormalizerStep::Nfkd,
+            "Lowercase" => NormalizerStep::Lowercase,
+            "StripAccents" => NormalizerStep::StripAccents,
+            "Strip" => NormalizerStep::Strip {
+                left: bool_field(value, "strip_left", true),
+                right: bool_field(value, "strip_right", true),
+            },
+            "Replace" => NormalizerStep::Replace {
+                pattern: Pattern::from_json(value)?.re,
+                content: str_field(value, "content")?.to_string(),
+            },
+            "Prepend" => NormalizerStep::Prepend(str_field(value, "prepend")?.to_string()),
+            "BertNormalizer" => {
+                let lowercase = bool_field(value, "lowercase", true);
+                NormalizerStep::Bert {
+                    clean_text: bool_field(value, "clean_text", true),
+                    handle_chinese_chars: bool_field(value, "handle_chinese_chars", true),
+                    strip_accents: value
+                        .get("strip_accents")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(lowercase),
+                    lowercase,
+                }
+            }
+            "Precompiled" => {
+                let data = BASE64
+                    .decode(str_field(value, "precompiled_charsmap")?)
+                    .map_err(|e| format!("Invalid precompiled charsmap: {}", e))?;
+                if data.is_empty() {
+                    return Ok(NormalizerStep::Sequence(Vec::new()));
+                }
+                let charsmap = Precompiled::from(&data)
+                    .map_err(|e| format!("Invalid precompiled charsmap: {}", e))?;
+                NormalizerStep::Precompiled(Box::new(charsmap))
+            }
+            "Sequence" => NormalizerStep::Sequence(
+                list(value, "normalizers")?
+                    .iter()
+                    .map(NormalizerStep::from_json)
+                    .collect::<JsonResult<_>>()?,
+            ),
+            other => return Err(format!("Unsupported normalizer type: {}", other)),
+        })
+    }
+
+    fn apply(&self, text: AlignedString) -> AlignedString {
+        match self {
+            NormalizerStep::Nfc => text.map_graphemes(|g, out| out.extend(g.nfc())),
+            NormalizerStep::Nfd => text.map_graphemes(|g, out| out.extend(g.nfd())),
+            NormalizerStep::Nfkc => text.map_graphemes(|g, out| out.extend(g.nfkc())),
+            NormalizerStep::Nfkd => text.map_graphemes(|g, out| out.extend(g.nfkd())),
+            NormalizerStep::Lowercase => text.map_chars(|c, out| out.extend(c.to_lowercase())),
+            NormalizerStep::StripAccents => text.map_chars(|c, out| {
+                if !c.is_mark_nonspacing() {
+                    out.push(c)
+                }
+            }),
+            NormalizerStep::Strip { left, right } => {
+                let mut start = 0;
+                let mut end = text.text.len();
+                if *left {
+                    start = end - text.text.trim_start().len();
+                }
+                if *right {
+                    end = start + text.text[start..].trim_end().len();
+                }
+                text.slice(start, end)
+            }
+            NormalizerStep::Replace { pattern, content } => {
+                let mut out = AlignedString::default();
+                let mut last = 0;
+                for m in pattern.find_iter(&text.text) {
+                    if m.start() == m.end() {
+                        continue;
+                    }
+                    for (_, c, span) in text.slice(last, m.start()).chars() {
+                        out.push(c, span);
+                    }
+                    out.push_str(content, text.span(m.start(), m.end()));
+                    last = m.end();
+                }
+                for (_, c, span) in text.slice(last, text.text.len()).chars() {
+                    out.push(c, span);
+                }
+                out
+            }
+            NormalizerStep::Prepend(prefix) => {
+                let mut text = text;
+                if !text.is_empty() {
+                    text.prepend(prefix);
+                }
+                text
+            }
+            NormalizerStep::Bert {
+                clean_text,
+                handle_chinese_chars,
+                strip_accents,
+                lowercase,
+            } => text.map_chars(|c, out| {
+                if *clean_text && is_bert_control(c) {
+                    return;
+                }
+                if *clean_text && c.is_whitespace() {
+                    out.push(' ');
+                    return;
+                }
+                let mut push = |c: char| {
+                    if *strip_accents {
+                        out.extend(c.to_string().nfd().filter(|c| !c.is_mark_nonspacing()));
+                    } else {
+                        out.push(c);
+                    }
+                };
+                if *handle_chinese_chars && is_cjk(c) {
+                    push(' ');
+                    push(c);
+                    push(' ');
+                } else if *lowercase {
+                    c.to_lowercase().for_each(push);
+                } else {
+                    push(c);
+                }
+            }),
+            NormalizerStep::Precompiled(charsmap) => apply_charsmap(charsmap, &text),
+            NormalizerStep::Sequence(steps) => {
+                steps.iter().fold(text, |text, step| step.apply(text))
+            }
+        }
+    }
+}
+
+/// What happens to the delimiters matched by a splitting pre-tokenizer.
+#[derive(Clone, Copy)]
+enum SplitBehavior {
+    Removed,
+    Isolated,
+    MergedWithPrevious,
+    MergedWithNext,
+    Contiguous,
+}
+
+impl SplitBehavior {
+    fn from_json(value: &Value, default: SplitBehavior) -> JsonResult<Self> {
+        Ok(match value.get("behavior").and_then(Value::as_str) {
+            None => default,
+            Some("Removed") => SplitBehavior::Removed,
+            Some("Isolated") => SplitBehavior::Isolated,
+            Some("MergedWithPrevious") => SplitBehavior::MergedWithPrevious,
+            Some("MergedWithNext") => SplitBehavior::MergedWithNext,
+            Some("Contiguous") => SplitBehavior::Contiguous,
+            Some(other) => return Err(format!("Unsupported split behavior: {}", other)),
+        })
+    }
+}
+
+/// Split `piece` around the byte ranges in `matches`.
+fn split_piece(
+    piece: &AlignedString,
+    matches: &[(usize, usize)],
+    behavior: SplitBehavior,
+    invert: bool,
+) -> Vec<AlignedString> {
+    // (start, end, is delimiter) segments covering the whole piece.
+    let mut segments: Vec<(usize, usize, bool)> = Vec::new();
+    let mut last = 0;
+    for &(start, end) in matches.iter().filter(|(s, e)| s < e) {
+        if last < start {
+            segments.push((last, start, invert));
+        }
+        segments.push((start, end, !invert));
+        last = end;
+    }
+    if last < piece.text.len() {
+        segments.push((last, piece.text.len(), invert));
+    }
+    let mut spans: Vec<(usize, usize)> = Vec::with_capacity(segments.len());
+    let mut previous_delimiter = false;
+    match behavior {
+        SplitBehavior::Removed => spans.extend(
+            segments
+                .into_iter()
+                .filter(|&(_, _, delimiter)| !delimiter)
+                .map(|(start, end, _)| (start, end)),
+        ),
+        SplitBehavior::Isolated => {
+            spans.extend(segments.into_iter().map(|(start, end, _)| (start, end)))
+        }
+        SplitBehavior::MergedWithPrevious => {
+            for (start, end, delimiter) in segments {
+                match spans.last_mut() {
+                    Some(last) if delimiter && !previous_delimiter => last.1 = end,
+                    _ => spans.push((start, end)),
+                }
+                previous_delimiter = delimiter;
+            }
+        }
+        SplitBehavior::MergedWithNext => {
+            for (start, end, delimiter) in segments.into_iter().rev() {
+                match spans.last_mut() {
+                    Some(last) if delimiter && !previous_delimiter => last.0 = start,
+                    _ => spans.push((start, end)),
+                }
+                previous_delimiter = delimiter;
+            }
+            spans.reverse();
+        }
+        SplitBehavior::Contiguous => {
+            for (start, end, delimiter) in segments {
+                match spans.last_mut() {
+                    Some(last) if delimiter && previous_delimiter => last.1 = end,
+                    _ => spans.push((start, end)),
+                }
+                previous_delimiter = delimiter;
+            }
+        }
+    }
+    spans
+        .into_iter()
+        .map(|(start, end)| piece.slice(start, end))
+        .collect()
+}
+
+fn char_matches(text: &str, predicate: impl Fn(char) -> bool) -> Vec<(usize, usize)> {
+    text.char_indices()
+        .filter(|&(_, c)| predicate(c))
+        .map(|(i, c)| (i, i + c.len_utf8()))
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PrependScheme {
+    Always,
+    First,
+    Never,
+}
+
+fn prepend_scheme(value: &Value) -> JsonResult<PrependScheme> {
+    Ok(match value.get("prepend_scheme").and_then(Value::as_str) {
+        Some("always") => PrependScheme::Always,
+        Some("first") => PrependScheme::First,
+        Some("never") => PrependScheme::Never,
+        Some(other) => return Err(format!("Unsupported prepend scheme: {}", other)),
+        None if bool_field(value, "add_prefix_space", true) => PrependScheme::Always,
+        None => PrependScheme::Never,
+    })
+}
+
+fn replacement_char(value: &Value) -> JsonResult<char> {
+    str_field(value, "replacement")?
+        .chars()
+        .next()
+        .ok_or_else(|| "Empty Metaspace replacement".to_string())
+}
+
+/// Pre-tokenizer stages of a `tokenizer.json`.
+enum PreTokenizerStep {
+    Whitespace,
+    WhitespaceSplit,
+    Bert,
+    ByteLevel {
+        add_prefix_space: bool,
+        use_regex: bool,
+    },
+    Metaspace {
+        replacement: char,
+        prepend_scheme: PrependScheme,
+        split: bool,
+    },
+    Split {
+        pattern: Pattern,
+        behavior: SplitBehavior,
+        invert: bool,
+    },
+    Punctuation(SplitBehavior),
+    Digits {
+        individual_digits: bool,
+    },
+    Sequence(Vec<PreTokenizerStep>),
+}
+
+impl PreTokenizerStep {
+    fn from_json(value: &Value) -> JsonResult<Self> {
+        Ok(match type_name(value)? {
+            "Whitespace" => PreTokenizerStep::Whitespace,
+            "WhitespaceSplit" => PreTokenizerStep::WhitespaceSplit,
+            "BertPreTokenizer" => PreTokenizerStep::Bert,
+            "ByteLevel" => PreTokenizerStep::ByteLevel {
+                add_prefix_space: bool_field(value, "add_prefix_space", true),
+                use_regex: bool_field(value, "use_regex", true),
+            },
+            "Metaspace" => PreTokenizerStep::Metaspace {
+                replacement: replacement_char(value)?,
+                prepend_scheme: prepend_scheme(value)?,
+                split: bool_field(value, "split", true),
+            },
+            "Split" => PreTokenizerStep::Split {
+                pattern: Pattern::from_json(value)?,
+                behavior: SplitBehavior::from_json(value, SplitBehavior::Removed)?,
+                invert: bool_field(value, "invert", false),
+            },
+            "Punctuation" => PreTokenizerStep::Punctuation(SplitBehavior::from_json(
+                value,
+                SplitBehavior::Isolated,
+            )?),
+            "Digits" => PreTokenizerStep::Digits {
+                individual_digits: bool_field(value, "individual_digits", false),
+            },
+            "Sequence" => PreTokenizerStep::Sequence(
+                list(value, "pretokenizers")?
+                    .iter()
+                    .map(PreTokenizerStep::from_json)
+                    .collect::<JsonResult<_>>()?,
+            ),
+            other => return Err(format!("Unsupported pre-tokenizer type: {}", other)),
+        })
+    }
+
+    /// Split pieces further. `first` is set when the pieces start the input text.
+    fn split(&self, pieces: Vec<AlignedString>, first: bool) -> Vec<AlignedString> {
+        if let PreTokenizerStep::Sequence(steps) = self {
+            return steps
+                .iter()
+                .fold(pieces, |pieces, step| step.split(pieces, first));
+        }
+        let mut out = Vec::with_capacity(pieces.len());
+        for (i, piece) in pieces.into_iter().enumerate() {
+            out.extend(self.split_one(piece, first && i == 0));
+        }
+        out
+    }
+
+    fn split_one(&self, piece: AlignedString, first: bool) -> Vec<AlignedString> {
+        let text = piece.text.as_str();
+        match self {
+            PreTokenizerStep::Whitespace => {
+                let matches: Vec<(usize, usize)> = whitespace_regex()
+                    .find_iter(text)
+                    .map(|m| (m.start(), m.end()))
+                    .collect();
+                split_piece(&piece, &matches, SplitBehavior::Removed, true)
+            }
+            PreTokenizerStep::WhitespaceSplit => split_piece(
+                &piece,
+                &char_matches(text, char::is_whitespace),
+                SplitBehavior::Removed,
+                false,
+            ),
+            PreTokenizerStep::Bert => split_piece(
+                &piece,
+                &char_matches(text, char::is_whitespace),
+                SplitBehavior::Removed,
+                false,
+            )
+            .into_iter()
+            .flat_map(|word| {
+                let matches = char_matches(&word.text, is_bert_punctuation);
+                split_piece(&word, &matches, SplitBehavior::Isolated, false)
+            })
+            .collect(),
+            PreTokenizerStep::ByteLevel {
+                add_prefix_space,
+                use_regex,
+            } => {
+                let mut piece = piece;
+                if *add_prefix_space && !piece.text.starts_with(' ') {
+                    piece.prepend(" ");
+                }
+                let words = if *use_regex {
+                    let matches = crate::bpe::byte_level_split(&piece.text);
+                    split_piece(&piece, &matches, SplitBehavior::Isolated, false)
+                } else {
+                    vec![piece]
+                };
+                let table = bytes_to_unicode();
+                words
+                    .into_iter()
+                    .map(|word| {
+                        word.map_chars(|c, out| {
+                            let mut buf = [0u8; 4];
+                            for &b in c.encode_utf8(&mut buf).as_bytes() {
+                                out.push(table[b as usize]);
+                            }
+                        })
+                    })
+                    .collect()
+            }
+            PreTokenizerStep::Metaspace {
+                replacement,
+                prepend_scheme,
+                split,
+            } => {
+                let mut piece =
+                    piece.map_chars(|c, out| out.push(if c == ' ' { *replacement } else { c }));
+                let prepend = match prepend_scheme {
+                    PrependScheme::Always => true,
+                    PrependScheme::First => first,
+                    PrependScheme::Never => false,
+                };
+                if prepend && !piece.text.starts_with(*replacement) {
+                    piece.prepend(&replacement.to_string());
+                }
+                if !*split {
+                    return vec![piece];
+                }
+                let matches = char_matches(&piece.text, |c| c == *replacement);
+                split_piece(&piece, &matches, SplitBehavior::MergedWithNext, false)
+            }
+            PreTokenizerStep::Split {
+                pattern,
+                behavior,
+                invert,
+            } => split_piece(&piece, &pattern.matches(text), *behavior, *invert),
+            PreTokenizerStep::Punctuation(behavior) => split_piece(
+                &piece,
+                &char_matches(text, is_bert_punctuation),
+                *behavior,
+                false,
+            ),
+            PreTokenizerStep::Digits { individual_digits } => {
+                let behavior = if *individual_digits {
+                    SplitBehavior::Isolated
+                } else {
+                    SplitBehavior::Contiguous
+                };
+                split_piece(
+                    &piece,
+                    &char_matches(text, char::is_numeric),
+                    behavior,
+                    false,
+                )
+            }
+            PreTokenizerStep::Sequence(_) => self.split(vec![piece], first),
+        }
+    }
+}
+
+fn whitespace_regex() -> &'static Regex {
+    static RE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\w+|[^\w\s]+").expect("valid whitespace pattern"))
+}
+
+/// The word-level model of a `tokenizer.json`.
+enum Model {
+    Bpe {
+        model: BpeTokenizer,
+        ignore_merges: bool,
+    },
+    WordPiece(WordPieceTokenizer),
+    Unigram(SentencePieceTokenizer),
+    WordLevel {
+        vocab: HashMap<String, u32>,
+        vocab_r: HashMap<u32, String>,
+        unk_id: Option<u32>,
+    },
+}
+
+impl Model {
+    fn from_json(value: &Value) -> PyResult<Self> {
+        let invalid = |e: String| PyValueError::new_err(format!("Invalid model: {}", e));
+        let kind = value.get("type").and_then(Value::as_str).unwrap_or("BPE");
+        Ok(match kind {
+            "BPE" => {
+                let merges = list(value, "merges")
+                    .map_err(invalid)?
+                    .iter()
+                    .map(|merge| match merge {
+                        Value::String(s) => s
+                            .split_once(' ')
+                            .map(|(a, b)| (a.to_string(), b.to_string())),
+                        Value::Array(pair) => match pair.as_slice() {
+                            [Value::String(a), Value::String(b)] => Some((a.clone(), b.clone())),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid("Invalid merge".to_string()))?;
+                let options = BpeOptions {
+                    unk_token: opt_str(value, "unk_token"),
+                    continuing_subword_prefix: opt_str(value, "continuing_subword_prefix"),
+                    end_of_word_suffix: opt_str(value, "end_of_word_suffix"),
+                    fuse_unk: bool_field(value, "fuse_unk", false),
+                    byte_fallback: bool_field(value, "byte_fallback", false),
+                    ..BpeOptions::default()
+                };
+                let vocab = vocab_map(value).map_err(invalid)?;
+                Model::Bpe {
+                    model: BpeTokenizer::with_options(vocab, merges, options).map_err(invalid)?,
+                    ignore_merges: bool_field(value, "ignore_merges", false),
+                }
+            }
+            "WordPiece" => Model::WordPiece(WordPieceTokenizer::with_vocab(
+                vocab_map(value).map_err(invalid)?,
+                opt_str(value, "unk_token").as_deref().unwrap_or("[UNK]"),
+                opt_str(value, "continuing_subword_prefix").unwrap_or_else(|| "##".to_string()),
+                value
+                    .get("max_input_chars_per_word")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(100) as usize,
+            )?),
+            "Unigram" => {
+                let pieces: Vec<(String, f32)> =
+                    serde_json::from_value(field(value, "vocab").map_err(invalid)?.clone())
+                        .map_err(|e| invalid(e.to_string()))?;
+                let unk_id = value.get("unk_id").and_then(Value::as_u64).unwrap_or(0) as u32;
+                Model::Unigram(SentencePieceTokenizer::unigram(
+                    pieces,
+                    unk_id,
+                    bool_field(value, "byte_fallback", false),
+                )?)
+            }
+            "WordLevel" => {
+                let vocab = vocab_map(value).map_err(invalid)?;
+                let unk_id = opt_str(value, "unk_token").and_then(|t| vocab.get(&t).copied());
+                let vocab_r = vocab.iter().map(|(k, &v)| (v, k.clone())).collect();
+                Model::WordLevel {
+                    vocab,
+                    vocab_r,
+                    unk_id,
+                }
+            }
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported model type: {}",
+                    other
+                )))
+            }
+        })
+    }
+
+    /// Ids of one pre-tokenized word with byte spans into the word.
+    fn encode_word(&self, word: &str) -> Vec<(u32, usize, usize)> {
+        match self {
+            Model::Bpe {
+                model,
+                ignore_merges,
+            } => match model.token_to_id(word).filter(|_| *ignore_merges) {
+                Some(id) => vec![(id, 0, word.len())],
+                None => model.encode_word(word),
+            },
+            Model::WordPiece(model) => model.encode_word(word),
+            Model::Unigram(model) => model.encode_word(word),
+            Model::WordLevel { vocab, unk_id, .. } => vocab
+                .get(word)
+                .copied()
+                .or(*unk_id)
+                .map(|id| (id, 0, word.len()))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        match self {
+            Model::Bpe { model, .. } => model.token_to_id(token),
+            Model::WordPiece(model) => model.token_to_id(token),
+            Model::Unigram(model) => model.token_to_id(token),
+            Model::WordLevel { vocab, .. } => vocab.get(token).copied(),
+        }
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        match self {
+            Model::Bpe { model, .. } => model.id_to_token(id),
+            Model::WordPiece(model) => model.id_to_token(id),
+            Model::Unigram(model) => model.id_to_token(id),
+            Model::WordLevel { vocab_r, .. } => vocab_r.get(&id).cloned(),
+        }
+    }
+
+    fn vocab_size(&self) -> usize {
+        match self {
+            Model::Bpe { model, .. } => model.vocab_size(),
+            Model::WordPiece(model) => model.vocab_size(),
+            Model::Unigram(model) => model.vocab_size(),
+            Model::WordLevel { vocab, .. } => vocab.len(),
+        }
+    }
+}
+
+/// One element of a post-processing template.
+enum TemplatePiece {
+    Special(Vec<u32>),
+    SequenceA,
+    SequenceB,
+}
+
+/// Post-processor of a `tokenizer.json`, reduced to single/pair templates.
+struct PostProcessor {
+    single: Vec<TemplatePiece>,
+    pair: Vec<TemplatePiece>,
+}
+
+fn special_pair(value: &Value, key: &str) -> JsonResult<u32> {
+    match field(value, key)?.as_array().map(Vec::as_slice) {
+        Some([_, id]) => id
+            .as_u64()
+            .map(|id| id as u32)
+            .ok_or_else(|| format!("Invalid '{}' token", key)),
+        _ => Err(format!("Invalid '{}' token", key)),
+    }
+}
+
+impl PostProcessor {
+    fn from_json(value: &Value) -> JsonResult<Option<Self>> {
+        use TemplatePiece::*;
+        Ok(match type_name(value)? {
+            "TemplateProcessing" => {
+                let specials: HashMap<String, Vec<u32>> = field(value, "special_tokens")?
+                    .as_object()
+                    .ok_or("Invalid special_tokens")?
+                    .iter()
+                    .map(|(name, token)| {
+                        let ids = serde_json::from_value(field(token, "ids")?.clone())
+                            .map_err(|e| format!("Invalid special token ids: {}", e))?;
+                        Ok((name.clone(), ids))
+                    })
+                    .collect::<JsonResult<_>>()?;
+                let template = |key: &str| -> JsonResult<Vec<TemplatePiece>> {
+                    list(value, key)?
+                        .iter()
+                        .map(|piece| {
+                            if let Some(special) = piece.get("SpecialToken") {
+                                let name = str_field(special, "id")?;
+                                let ids = specials
+                                    .get(name)
+                                    .ok_or_else(|| format!("Unknown special token '{}'", name))?;
+                                Ok(Special(ids.clone()))
+                            } else if let Some(sequence) = piece.get("Sequence") {
+                                Ok(match str_field(sequence, "id")? {
+                                    "B" => SequenceB,
+                                    _ => SequenceA,
+                                })
+                            } else {
+                                Err("Invalid template piece".to_string())
+                            }
+                        })
+                        .collect()
+                };
+                Some(PostProcessor {
+                    single: template("single")?,
+                    pair: template("pair")?,
+                })
+            }
+            "BertProcessing" => {
+                let (cls, sep) = (special_pair(value, "cls")?, special_pair(value, "sep")?);
+                Some(PostProcessor {
+                    single: vec![Special(vec![cls]), SequenceA, Special(vec![sep])],
+                    pair: vec![
+                        Special(vec![cls]),
+                        SequenceA,
+                        Special(vec![sep]),
+                        SequenceB,
+                        Special(vec![sep]),
+                    ],
+                })
+            }
+            "RobertaProcessing" => {
+                let (cls, sep) = (special_pair(value, "cls")?, special_pair(value, "sep")?);
+                Some(PostProcessor {
+                    single: vec![Special(vec![cls]), SequenceA, Special(vec![sep])],
+                    pair: vec![
+                        Special(vec![cls]),
+                        SequenceA,
+                        Special(vec![sep, sep]),
+                        SequenceB,
+                        Special(vec![sep]),
+                    ],
+                })
+            }
+            "Sequence" => {
+                let mut found = None;
+                for processor in list(value, "processors")? {
+                    if let Some(processor) = PostProcessor::from_json(processor)? {
+                        found = Some(processor);
+                    }
+                }
+                found
+            }
+            "ByteLevel" => None,
+            other => return Err(format!("Unsupported post-processor type: {}", other)),
+        })
+    }
+
+    /// Number of ids the single or pair template adds.
+    fn num_added(&self, pair: bool) -> usize {
+        let template = if pair { &self.pair } else { &self.single };
+        template
+            .iter()
+            .map(|piece| match piece {
+                TemplatePiece::Special(ids) => ids.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn apply(&self, a: Vec<u32>, b: Option<Vec<u32>>) -> Vec<u32> {
+        self.apply_with(a, b, |id| id)
+    }
+
+    /// `apply` for sequences of any item, made for special ids by `special`.
+    fn apply_with<T: Clone>(
+        &self,
+        a: Vec<T>,
+        b: Option<Vec<T>>,
+        special: impl Fn(u32) -> T,
+    ) -> Vec<T> {
+        let template = if b.is_some() {
+            &self.pair
+        } else {
+            &self.single
+        };
+        let mut out = Vec::with_capacity(a.len() + b.as_ref().map_or(0, Vec::len) + 4);
+        for piece in template {
+            match piece {
+                TemplatePiece::Special(ids) => out.extend(ids.iter().map(|&id| special(id))),
+                TemplatePiece::SequenceA => out.extend_from_slice(&a),
+                TemplatePiece::SequenceB => out.extend(b.iter().flatten().cloned()),
+            }
+        }
+        out
+    }
+}
+
+/// Decoder stages of a `tokenizer.json`, working on the list of token strings.
+enum DecoderStep {
+    ByteLevel,
+    WordPiece {
+        prefix: String,
+        cleanup: bool,
+    },
+    Metaspace {
+        replacement: char,
+        prepend: bool,
+    },
+    Bpe {
+        suffix: String,
+    },
+    ByteFallback,
+    Fuse,
+    Strip {
+        content: char,
+        start: usize,
+        stop: usize,
+    },
+    Replace {
+        pattern: Regex,
+        content: String,
+    },
+    Sequence(Vec<DecoderStep>),
+}
+
+impl DecoderStep {
+    fn from_json(value: &Value) -> JsonResult<Self> {
+        Ok(match type_name(value)? {
+            "ByteLevel" => DecoderStep::ByteLevel,
+            "WordPiece" => DecoderStep::WordPiece {
+                prefix: opt_str(value, "prefix").unwrap_or_else(|| "##".to_string()),
+                cleanup: bool_field(value, "cleanup", true),
+            },
+            "Metaspace" => DecoderStep::Metaspace {
+                replacement: replacement_char(value)?,
+                prepend: prepend_scheme(value)? != PrependScheme::Never,
+            },
+            "BPEDecoder" => DecoderStep::Bpe {
+                suffix: opt_str(value, "suffix").unwrap_or_else(|| "</w>".to_string()),
+            },
+            "ByteFallback" => DecoderStep::ByteFallback,
+            "Fuse" => DecoderStep::Fuse,
+            "Strip" => DecoderStep::Strip {
+                content: str_field(value, "content")?.chars().next().unwrap_or(' '),
+                start: value.get("start").and_then(Value::as_u64).unwrap_or(0) as usize,
+                stop: value.get("stop").and_then(Value::as_u64).unwrap_or(0) as usize,
+            },
+            "Replace" => DecoderStep::Replace {
+                pattern: Pattern::from_json(value)?.re,
+                content: str_field(value, "content")?.to_string(),
+            },
+            "Sequence" => DecoderStep::Sequence(
+                list(value, "decoders")?
+                    .iter()
+                    .map(DecoderStep::from_json)
+                    .collect::<JsonResult<_>>()?,
+            ),
+            other => return Err(format!("Unsupported decoder type: {}", other)),
+        })
+    }
+
+    fn decode(&self, tokens: Vec<String>) -> Vec<String> {
+        match self {
+            DecoderStep::ByteLevel => {
+                let table = unicode_to_bytes();
+                let bytes: Vec<u8> = tokens
+                    .iter()
+                    .flat_map(|t| t.chars())
+                    .filter_map(|c| table.get(&c).copied())
+                    .collect();
+                vec![String::from_utf8_lossy(&bytes).into_owned()]
+            }
+            DecoderStep::WordPiece { prefix, cleanup } => tokens
+                .into_iter()
+                .enumerate()
+                .map(|(i, token)| {
+                    let token = match token.strip_prefix(prefix.as_str()) {
+                        Some(rest) if i > 0 => rest.to_string(),
+                        _ if i > 0 => format!(" {}", token),
+                        _ => token,
+                    };
+                    clean_up_tokenization(token, *cleanup)
+                })
+                .collect(),
+            DecoderStep::Metaspace {
+                replacement,
+                prepend,
+            } => tokens
+                .into_iter()
+                .enumerate()
+                .map(|(i, token)| {
+                    let token = token.replace(*replacement, " ");
+                    match token.strip_prefix(' ') {
+                        Some(rest) if i == 0 && *prepend => rest.to_string(),
+                        _ => token,
+                    }
+                })
+                .collect(),
+            DecoderStep::Bpe { suffix } => {
+                let n = tokens.len();
+                tokens
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, token)| {
+                        let replacement = if i + 1 == n { "" } else { " " };
+                        token.replace(suffix.as_str(), replacement)
+                    })
+                    .collect()
+            }
+            DecoderStep::ByteFallback => {
+                let mut out = Vec::with_capacity(tokens.len());
+                let mut pending: Vec<u8> = Vec::new();
+                let flush = |pending: &mut Vec<u8>, out: &mut Vec<String>| {
+                    if !pending.is_empty() {
+                        match String::from_utf8(std::mem::take(pending)) {
+                            Ok(s) => out.push(s),
+                            Err(e) => out.extend(std::iter::repeat_n(
+                                "\u{fffd}".to_string(),
+                                e.as_bytes().len(),
+                            )),
+                        }
+                    }
+                };
+                for token in tokens {
+                    let byte = token
+                        .strip_prefix("<0x")
+                        .and_then(|t| t.strip_suffix('>'))
+                        .filter(|hex| hex.len() == 2)
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                    match byte {
+                        Some(b) => pending.push(b),
+                        None => {
+                            flush(&mut pending, &mut out);
+                            out.push(token);
+                        }
+                    }
+                }
+                flush(&mut pending, &mut out);
+                out
+            }
+            DecoderStep::Fuse => vec![tokens.concat()],
+            DecoderStep::Strip {
+                content,
+                start,
+                stop,
+            } => tokens
+                .into_iter()
+                .map(|token| {
+                    let mut chars: Vec<char> = token.chars().collect();
+                    let head = chars
+                        .iter()
+                        .take(*start)
+                        .take_while(|&&c| c == *content)
+                        .count();
+                    let tail = chars[head..]
+                        .iter()
+                        .rev()
+                        .take(*stop)
+                        .take_while(|&&c| c == *content)
+                        .count();
+                    chars.truncate(chars.len() - tail);
+                    chars[head..].iter().collect()
+                })
+                .collect(),
+            DecoderStep::Replace { pattern, content } => tokens
+                .into_iter()
+                .map(|token| pattern.replace_all(&token, content.as_str()).into_owned())
+                .collect(),
+            DecoderStep::Sequence(steps) => steps
+                .iter()
+                .fold(tokens, |tokens, step| step.decode(tokens)),
+        }
+    }
+}
+
+/// A token added on top of the model vocabulary, matched before normalization.
+struct AddedToken {
+    id: u32,
+    content: String,
+    special: bool,
+}
+
+/// A HuggingFace `tokenizer.json` translated to FastTokenizer stages.
+struct TokenizerModel {
+    normalizer: Option<NormalizerStep>,
+    pre_tokenizer: Option<PreTokenizerStep>,
+    model: Model,
+    post_processor: Option<PostProcessor>,
+    decoder: Option<DecoderStep>,
+    added_tokens: Vec<AddedToken>,
+    added_pattern: Option<Regex>,
+    /// Id from the `padding` section, used by `encode_batch`.
+    pad_id: Option<u32>,
+}
+
+impl TokenizerModel {
+    fn from_json(json: &str) -> PyResult<Self> {
+        let root: Value = serde_json::from_str(json)
+            .map_err(|e| PyValueError::new_err(format!("Invalid tokenizer.json: {}", e)))?;
+        let invalid = |e: String| PyValueError::new_err(format!("Invalid tokenizer.json: {}", e));
+        let stage = |key: &str| root.get(key).filter(|v| !v.is_null());
+        let normalizer = stage("normalizer")
+            .map(NormalizerStep::from_json)
+            .transpose()
+            .map_err(invalid)?;
+        let pre_tokenizer = stage("pre_tokenizer")
+            .map(PreTokenizerStep::from_json)
+            .transpose()
+            .map_err(invalid)?;
+        let model = Model::from_json(field(&root, "model").map_err(invalid)?)?;
+        let post_processor = match stage("post_processor") {
+            Some(value) => PostProcessor::from_json(value).map_err(invalid)?,
+            None => None,
+        };
+        let decoder = stage("decoder")
+            .map(DecoderStep::from_json)
+            .transpose()
+            .map_err(invalid)?;
+
+        let mut added_tokens = Vec::new();
+        let mut alternatives = Vec::new();
+        for token in stage("added_tokens")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let id = field(token, "id")
+                .ok()
+                .and_then(Value::as_u64)
+                .ok_or_else(|| invalid("Added token without id".to_string()))?;
+            let content = str_field(token, "content").map_err(invalid)?.to_string();
+            let mut alternative = regex::escape(&content);
+            if bool_field(token, "lstrip", false) {
+                alternative.insert_str(0, r"\s*");
+            }
+            if bool_field(token, "rstrip", false) {
+                alternative.push_str(r"\s*");
+            }
+            alternatives.push((content.len(), alternative));
+            added_tokens.push(AddedToken {
+                id: id as u32,
+                content,
+                special: bool_field(token, "special", false),
+            });
+        }
+        // Longest first, so that overlapping added tokens match greedily.
+        alternatives.sort_by_key(|(len, _)| std::cmp::Reverse(*len));
+        let added_pattern = if alternatives.is_empty() {
+            None
+        } else {
+            let source: Vec<String> = alternatives.into_iter().map(|(_, a)| a).collect();
+            Some(Regex::new(&source.join("|")).map_err(|e| invalid(e.to_string()))?)
+        };
+        Ok(TokenizerModel {
+            normalizer,
+            pre_tokenizer,
+            model,
+            post_processor,
+            decoder,
+            added_tokens,
+            added_pattern,
+            pad_id: stage("padding")
+                .and_then(|padding| padding.get("pad_id"))
+                .and_then(Value::as_u64)
+                .map(|id| id as u32),
+        })
+    }
+
+    fn added_by_content(&self, content: &str) -> Option<&AddedToken> {
+        self.added_tokens.iter().find(|t| t.content == content)
+    }
+
+    fn added_by_id(&self, id: u32) -> Option<&AddedToken> {
+        self.added_tokens.iter().find(|t| t.id == id)
+    }
+
+    /// Normalize, pre-tokenize and encode a stretch of text without added tokens.
+    fn encode_segment(&self, text: &str, base: usize, out: &mut Vec<(u32, usize, usize)>) {
+        let mut aligned = AlignedString::new(text, base);
+        if let Some(normalizer) = &self.normalizer {
+            aligned = normalizer.apply(aligned);
+        }
+        let pieces = match &self.pre_tokenizer {
+            Some(pre_tokenizer) => pre_tokenizer.split(vec![aligned], base == 0),
+            None => vec![aligned],
+        };
+        for piece in pieces.iter().filter(|p| !p.is_empty()) {
+            out.extend(
+                self.model
+                    .encode_word(&piece.text)
+                    .into_iter()
+                    .map(|(id, s, e)| {
+                        let (start, end) = piece.span(s, e);
+                        (id, start, end)
+                    }),
+            );
+        }
+    }
+
+    /// Encode text into `(id, start, end)` with byte offsets, without post-processing.
+    fn encode_with_offsets(&self, text: &str) -> Vec<(u32, usize, usize)> {
+        let mut out = Vec::new();
+        let mut last = 0;
+        if let Some(pattern) = &self.added_pattern {
+            for m in pattern.find_iter(text) {
+                let token = self
+                    .added_by_content(m.as_str())
+                    .or_else(|| self.added_by_content(m.as_str().trim()));
+                let Some(token) = token else {
+                    continue;
+                };
+                if last < m.start() {
+                    self.encode_segment(&text[last..m.start()], last, &mut out);
+                }
+                out.push((token.id, m.start(), m.end()));
+                last = m.end();
+            }
+        }
+        if last < text.len() {
+            self.encode_segment(&text[last..], last, &mut out);
+        }
+        out
+    }
+
+    /// Encode a sequence (pair), first truncating it to `max_length` minus
+    /// the number of ids the post-processor adds.
+    fn encode(
+        &self,
+        text: &str,
+        pair: Option<&str>,
+        add_special_tokens: bool,
+        truncation: Option<(usize, Truncation)>,
+    ) -> Result<Vec<u32>, String> {
+        let ids = |text: &str| -> Vec<u32> {
+            self.encode_with_offsets(text)
+                .into_iter()
+                .map(|(id, _, _)| id)
+                .collect()
+        };
+        let mut a = ids(text);
+        let mut b = pair.map(ids);
+        let processor = self.post_processor.as_ref().filter(|_| add_special_tokens);
+        if let Some((max_length, strategy)) = truncation {
+            let added = processor.map_or(0, |p| p.num_added(b.is_some()));
+            truncate(
+                &mut a,
+                b.as_mut(),
+                max_length.saturating_sub(added),
+                strategy,
+            )?;
+        }
+        Ok(match processor {
+            Some(processor) => processor.apply(a, b),
+            None => a.into_iter().chain(b.into_iter().flatten()).collect(),
+        })
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        match self.added_by_id(id) {
+            Some(token) => Some(token.content.clone()),
+            None => self.model.id_to_token(id),
+        }
+    }
+
+    fn decode(&self, ids: &[u32], skip_special_tokens: bool) -> String {
+        let tokens: Vec<String> = ids
+            .iter()
+            .filter(|&&id| {
+                !(skip_special_tokens && self.added_by_id(id).is_some_and(|t| t.special))
+            })
+            .filter_map(|&id| self.id_to_token(id))
+            .collect();
+        match &self.decoder {
+            Some(decoder) => decoder.decode(tokens).concat(),
+            None => tokens.join(" "),
+        }
+    }
+}
+
+/// Tokenizer built from a HuggingFace `tokenizers` `tokenizer.json` file.
+///
+/// The normalizer, pre-tokenizer, model (BPE, WordPiece, Unigram, WordLevel),
+/// post-processor and decoder are translated to their FastTokenizer
+/// equivalents, so existing model assets can be used as they are.
+#[pyclass(name = "Tokenizer", frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct HfTokenizer {
+    model: Arc<TokenizerModel>,
+    /// The `tokenizer.json` the model was built from, kept for `save`.
+    json: Arc<str>,
+}
+
+impl HfTokenizer {
+    /// The `tokenizer.json` contents as a JSON object, as embedded in
+    /// tokenizer configurations and bundles.
+    pub fn to_value(&self) -> Value {
+        let json: Value = serde_json::from_str(&self.json).expect("parsed when built");
+        json!({ "tokenizer_json": json })
+    }
+
+    /// Recreate a tokenizer from `to_value` output.
+    pub fn from_value(value: &Value) -> PyResult<Self> {
+        Self::from_str(&value["tokenizer_json"].to_string())
+    }
+}
+
+impl Tokenizer for HfTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(id, start, end)| {
+                Token::new(self.model.id_to_token(id).unwrap_or_default(), start, end)
+            })
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        let ids: Vec<u32> = tokens.iter().filter_map(|t| self.token_to_id(t)).collect();
+        self.model.decode(&ids, false)
+    }
+}
+
+tokenizer_pymethods!(HfTokenizer);
+
+#[pymethods]
+impl HfTokenizer {
+    /// Load a `tokenizer.json` file.
+    #[staticmethod]
+    fn from_file(path: &str) -> PyResult<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read tokenizer file: {}", e)))?;
+        Self::from_str(&json)
+    }
+
+    /// Build a tokenizer from the contents of a `tokenizer.json` file.
+    #[staticmethod]
+    fn from_str(json: &str) -> PyResult<Self> {
+        Ok(HfTokenizer {
+            model: Arc::new(TokenizerModel::from_json(json)?),
+            json: json.into(),
+        })
+    }
+
+    /// Save the tokenizer to a single bundle file embedding its
+    /// `tokenizer.json`.
+    fn save(slf: &Bound<'_, Self>, path: &str) -> PyResult<()> {
+        config::save(slf.as_any(), path)
+    }
+
+    /// Load a bundle written by the `save` method of any tokenizer class or
+    /// `TokenizerPipeline`, returning an object of the class that saved it.
+    #[staticmethod]
+    fn load<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyAny>> {
+        config::load_any(py, path)
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text (and an optional second sequence) into token ids.
+    ///
+    /// Args:
+    ///     input (str | bytes): The text to encode.
+    ///     pair (str | bytes, optional): Second sequence, e.g. for sentence-pair tasks.
+    ///     add_special_tokens (bool): Apply the post-processor template.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, pair=None, add_special_tokens=true, errors="strict"))]
+    fn encode<'py>(
+        &self,
+        input: Text<'py>,
+        pair: Option<Text<'py>>,
+        add_special_tokens: bool,
+        errors: &str,
+    ) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        let pair = pair.as_ref().map(|pair| pair.decode(errors)).transpose()?;
+        let pair = pair.as_deref();
+        self.model
+            .encode(input, pair, add_special_tokens, None)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Encode a long text as overlapping windows of at most `max_length`
+    /// ids, the usual way to feed long documents to a transformer model.
+    ///
+    /// Args:
+    ///     text (str): The document to encode.
+    ///     max_length (int): Ids per window, special tokens included.
+    ///     stride (int): Number of tokens each window repeats from the end
+    ///         of the one before, as in HuggingFace's `stride`.
+    ///     add_special_tokens (bool): Wrap every window in the post-processor template.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    ///
+    /// Returns:
+    ///     List[Tuple[List[int], List[Tuple[int, int]]]]: The ids of each
+    ///     window and their `(start, end)` offsets into `text`; special
+    ///     tokens have `(0, 0)`.
+    #[pyo3(signature = (text, max_length, stride=0, add_special_tokens=true, unit="char"))]
+    fn encode_windows(
+        &self,
+        text: &str,
+        max_length: usize,
+        stride: usize,
+        add_special_tokens: bool,
+        unit: &str,
+    ) -> PyResult<Windows> {
+        let processor = self
+            .model
+            .post_processor
+            .as_ref()
+            .filter(|_| add_special_tokens);
+        let added = processor.map_or(0, |p| p.num_added(false));
+        let encoded = self.model.encode_with_offsets(text);
+        let mut out = Vec::new();
+        for window in windows(&encoded, max_length.saturating_sub(added), stride)
+            .map_err(PyValueError::new_err)?
+        {
+            let mut window = window.to_vec();
+            if let Some(processor) = processor {
+                window = processor.apply_with(window, None, |id| (id, 0, 0));
+            }
+            let (ids, tokens): (Vec<u32>, Vec<Token>) = window
+                .into_iter()
+                .map(|(id, start, end)| (id, Token::new("", start, end)))
+                .unzip();
+            let spans = offsets(text, tokens, unit)?
+                .into_iter()
+                .map(|(_, start, end)| (start, end))
+                .collect();
+            out.push((ids, spans));
+        }
+        Ok(out)
+    }
+
+    /// Encode several texts or `(text, pair)` tuples at once, releasing the
+    /// GIL while working.
+    ///
+    /// Args:
+    ///     inputs (List[str | Tuple[str, str]]): Texts or sequence pairs.
+    ///     add_special_tokens (bool): Apply the post-processor template.
+    ///     max_length (int, optional): Truncate (and with `padding="max_length"`,
+    ///         pad) every sequence to this many ids, special tokens included.
+    ///     truncation (str, optional): `"longest_first"`, `"only_first"` or
+    ///         `"only_second"`; `None` keeps long sequences whole.
+    ///     padding (str, optional): `"longest"` or `"max_length"`.
+    ///     pad_id (int, optional): Padding id; defaults to the `padding`
+    ///         section of the `tokenizer.json`.
+    ///     padding_side (str): `"right"` or `"left"`.
+    ///     return_tensors (str, optional): `"np"` for a dict of 2-D NumPy
+    ///         arrays instead of Python lists.
+    ///     dtype (str): `"int64"` or `"int32"` for NumPy output.
+    ///
+    /// Returns:
+    ///     List[List[int]] when neither `max_length` nor `padding` is given,
+    ///     otherwise a `BatchEncoding` with `input_ids` and `attention_mask`,
+    ///     or with `return_tensors="np"` a dict of both as arrays.
+    #[pyo3(signature = (
+        inputs,
+        add_special_tokens=true,
+        max_length=None,
+        truncation=Some("longest_first"),
+        padding=None,
+        pad_id=None,
+        padding_side="right",
+        return_tensors=None,
+        dtype="int64"
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn encode_batch<'py>(
+        &self,
+        py: Python<'py>,
+        inputs: Vec<BatchInput>,
+        add_special_tokens: bool,
+        max_length: Option<usize>,
+        truncation: Option<&str>,
+        padding: Option<&str>,
+        pad_id: Option<u32>,
+        padding_side: &str,
+        return_tensors: Option<&str>,
+        dtype: &str,
+    ) -> PyResult<BatchOutput<'py>> {
+        let truncation = max_length.zip(Truncation::parse(truncation)?);
+        let padding = Padding::parse(padding)?;
+        let ids = py
+            .detach(|| {
+                inputs
+                    .iter()
+                    .map(|input| {
+                        let (text, pair) = input.parts();
+                        self.model
+                            .encode(text, pair, add_special_tokens, truncation)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(PyValueError::new_err)?;
+        BatchOutput::build(
+            py,
+            ids,
+            max_length,
+            padding,
+            || Ok(pad_id.or(self.model.pad_id)),
+            padding_side,
+            return_tensors,
+            dtype,
+        )
+    }
+
+    /// Turn token ids back into text using the configured decoder, which
+    /// maps byte-level symbols back to bytes.
+    ///
+    /// Args:
+    ///     ids (List[int]): Token ids.
+    ///     skip_special_tokens (bool): Leave out added tokens marked special.
+    ///     clean_up_tokenization_spaces (bool): Remove spaces before
+    ///         punctuation and contractions like `transformers` does; set it
+    ///         as the model's `tokenizer_config.json` does to match it.
+    #[pyo3(signature = (ids, skip_special_tokens=true, clean_up_tokenization_spaces=true))]
+    fn decode(
+        &self,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> PyResult<String> {
+        Ok(clean_up_tokenization(
+            self.model.decode(&ids, skip_special_tokens),
+            clean_up_tokenization_spaces,
+        ))
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        match self.model.added_by_content(token) {
+            Some(added) => Some(added.id),
+            None => self.model.model.token_to_id(token),
+        }
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.model.id_to_token(id)
+    }
+
+    /// Size of the vocabulary including added tokens.
+    fn vocab_size(&self) -> usize {
+        let extra = self
+            .model
+            .added_tokens
+            .iter()
+            .filter(|t| self.model.model.id_to_token(t.id).is_none())
+            .count();
+        self.model.model.vocab_size() + extra
+    }
+}
@@ -0,0 +1,105 @@
+use pyo3::prelude::*;
+use std::sync::Arc;
+
+use crate::tokenizer::{extract_tokenizer, last_whitespace_run, offsets, Token, Tokenizer};
+
+/// Tokenizes text that arrives in fragments, such as streamed model output
+/// or websocket messages, emitting each token once it is complete.
+///
+/// Text is held back from the start of its last whitespace run until more
+/// arrives, so a token split across fragments comes out whole, as long as
+/// the tokenizer does not match across whitespace.
+#[pyclass]
+pub struct IncrementalTokenizer {
+    tokenizer: Arc<dyn Tokenizer>,
+    with_offsets: bool,
+    unit: String,
+    /// Text pushed but not yet tokenized.
+    pending: String,
+    /// Bytes and characters consumed before `pending`.
+    consumed: (usize, usize),
+}
+
+impl IncrementalTokenizer {
+    /// Tokenize and consume the first `cut` bytes of the pending text, with
+    /// offsets from the start of the stream.
+    fn take(&mut self, cut: usize) -> PyResult<Vec<(String, usize, usize)>> {
+        let text = &self.pending[..cut];
+        let base = if self.unit == "char" {
+            self.consumed.1
+        } else {
+            self.consumed.0
+        };
+        let tokens: Vec<Token> = self.tokenizer.tokenize_with_source_offsets(text);
+        let tokens = offsets(text, tokens, &self.unit)?
+            .into_iter()
+            .map(|(token, start, end)| (token, start + base, end + base))
+            .collect();
+        self.consumed.0 += cut;
+        self.consumed.1 += text.chars().count();
+        self.pending.drain(..cut);
+        Ok(tokens)
+    }
+
+    fn output<'py>(
+        &self,
+        py: Python<'py>,
+        tokens: Vec<(String, usize, usize)>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if self.with_offsets {
+            return Ok(tokens.into_pyobject(py)?.into_any());
+        }
+        let tokens: Vec<String> = tokens.into_iter().map(|t| t.0).collect();
+        Ok(tokens.into_pyobject(py)?.into_any())
+    }
+}
+
+#[pymethods]
+impl IncrementalTokenizer {
+    /// Create an incremental tokenizer.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer.
+    ///     with_offsets (bool): Emit `(token, start, end)` with offsets from
+    ///         the start of the stream instead of plain tokens.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    #[new]
+    #[pyo3(signature = (tokenizer, with_offsets=false, unit="char"))]
+    fn new(tokenizer: &Bound<'_, PyAny>, with_offsets: bool, unit: &str) -> PyResult<Self> {
+        offsets("", Vec::new(), unit)?;
+        Ok(IncrementalTokenizer {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            with_offsets,
+            unit: unit.to_string(),
+            pending: String::new(),
+            consumed: (0, 0),
+        })
+    }
+
+    /// Text pushed but not yet emitted as tokens.
+    #[getter]
+    fn pending(&self) -> &str {
+        &self.pending
+    }
+
+    /// Add the next fragment of text and return the tokens it completes,
+    /// which may be none.
+    fn push<'py>(&mut self, py: Python<'py>, fragment: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.pending.push_str(fragment);
+        let tokens = match last_whitespace_run(&self.pending) {
+            Some(cut) => py.detach(|| self.take(cut))?,
+            None => Vec::new(),
+        };
+        self.output(py, tokens)
+    }
+
+    /// End the stream: return the tokens of the remaining text and start
+    /// over, so the tokenizer can be reused for the next stream.
+    fn finish<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let cut = self.pending.len();
+        let tokens = py.detach(|| self.take(cut))?;
+        self.consumed = (0, 0);
+        self.output(py, tokens)
+    }
+}
@@ -0,0 +1,141 @@
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::sync::MutexExt;
+use pyo3::types::{PyBool, PyList, PyString};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Python strings for tokens seen before, so that repeats of a token share
+/// one string object instead of each allocating its own.
+///
+/// Pass a cache as `intern=` to `tokenize` or `tokenize_batch` to share
+/// strings across calls, e.g. one cache per tokenizer over a whole corpus.
+/// A cache can be shared by several threads.
+#[pyclass(frozen)]
+pub struct StringCache {
+    strings: Mutex<HashMap<String, Py<PyString>>>,
+    max_size: Option<usize>,
+}
+
+impl StringCache {
+    /// Lock the cache, without holding up other threads while waiting.
+    fn strings(&self, py: Python<'_>) -> MutexGuard<'_, HashMap<String, Py<PyString>>> {
+        self.strings.lock_py_attached(py).unwrap()
+    }
+}
+
+/// The cached string for `token`, adding it while the cache has room.
+fn get<'py>(
+    py: Python<'py>,
+    strings: &mut HashMap<String, Py<PyString>>,
+    max_size: Option<usize>,
+    token: String,
+) -> Bound<'py, PyString> {
+    if let Some(string) = strings.get(&token) {
+        return string.bind(py).clone();
+    }
+    let string = PyString::new(py, &token);
+    if max_size.is_none_or(|max| strings.len() < max) {
+        strings.insert(token, string.clone().unbind());
+    }
+    string
+}
+
+#[pymethods]
+impl StringCache {
+    /// Create an empty cache.
+    ///
+    /// Args:
+    ///     max_size (int, optional): Stop caching new tokens once this many
+    ///         are held; later tokens are returned as fresh strings.
+    #[new]
+    #[pyo3(signature = (max_size=None))]
+    fn new(max_size: Option<usize>) -> Self {
+        StringCache {
+            strings: Mutex::new(HashMap::new()),
+            max_size,
+        }
+    }
+
+    /// Drop every cached string.
+    fn clear(&self, py: Python<'_>) {
+        self.strings(py).clear();
+    }
+
+    fn __len__(&self, py: Python<'_>) -> usize {
+        self.strings(py).len()
+    }
+
+    fn __contains__(&self, py: Python<'_>, token: &str) -> bool {
+        self.strings(py).contains_key(token)
+    }
+}
+
+/// The cache selected by an `intern` argument: none for `None` or `False`,
+/// a fresh one for `True`, or the given `StringCache`.
+fn cache<'py>(
+    py: Python<'py>,
+    intern: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Option<Bound<'py, StringCache>>> {
+    let Some(intern) = intern else {
+        return Ok(None);
+    };
+    if let Ok(cache) = intern.cast::<StringCache>() {
+        return Ok(Some(cache.clone()));
+    }
+    if !intern.is_instance_of::<PyBool>() {
+        return Err(PyTypeError::new_err(format!(
+            "intern must be a bool or a StringCache, got {}",
+            intern.get_type().name()?
+        )));
+    }
+    if intern.is_truthy()? {
+        return Bound::new(py, StringCache::new(None)).map(Some);
+    }
+    Ok(None)
+}
+
+fn list<'py>(
+    py: Python<'py>,
+    tokens: Vec<String>,
+    cache: &StringCache,
+) -> PyResult<Bound<'py, PyList>> {
+    let mut strings = cache.strings(py);
+    PyList::new(
+        py,
+        tokens
+            .into_iter()
+            .map(|token| get(py, &mut strings, cache.max_size, token)),
+    )
+}
+
+/// Convert `tokens` to a Python list, interning the strings as `intern`
+/// asks.
+pub fn token_list<'py>(
+    py: Python<'py>,
+    tokens: Vec<String>,
+    intern: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    match cache(py, intern)? {
+        Some(cache) => Ok(list(py, tokens, cache.get())?.into_any()),
+        None => Ok(tokens.into_pyobject(py)?.into_any()),
+    }
+}
+
+/// `token_list` for the rows of a batch, all interned in the same cache.
+pub fn token_lists<'py>(
+    py: Python<'py>,
+    rows: Vec<Vec<String>>,
+    intern: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    match cache(py, intern)? {
+        Some(cache) => {
+            let rows = rows
+                .into_iter()
+                .map(|tokens| list(py, tokens, cache.get()))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, rows)?.into_any())
+        }
+        None => Ok(rows.into_pyobject(py)?.into_any()),
+    }
+}
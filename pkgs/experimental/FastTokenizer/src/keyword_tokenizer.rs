@@ -0,0 +1,273 @@
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::intern::token_list;
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// A matched phrase: `(text, start, end, label)`.
+type Extraction = (String, usize, usize, String);
+
+/// A phrase list, or a mapping of phrases to labels.
+#[derive(FromPyObject)]
+pub enum Keywords {
+    Labeled(HashMap<String, String>),
+    Phrases(Vec<String>),
+}
+
+/// Lowercase `text` char by char, returning the lowered text and, for every
+/// byte of it plus its end, the matching byte offset in `text`.
+fn fold(text: &str) -> (String, Vec<usize>) {
+    let mut lowered = String::with_capacity(text.len());
+    let mut map = Vec::with_capacity(text.len() + 1);
+    for (i, c) in text.char_indices() {
+        let before = lowered.len();
+        lowered.extend(c.to_lowercase());
+        map.extend(std::iter::repeat_n(i, lowered.len() - before));
+    }
+    map.push(text.len());
+    (lowered, map)
+}
+
+fn fold_phrase(phrase: &str) -> String {
+    phrase.chars().flat_map(char::to_lowercase).collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Finds the occurrences of a phrase list in text with Aho-Corasick,
+/// taking the leftmost-longest match at each position, for gazetteer and
+/// dictionary matching over large corpora.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct KeywordTokenizer {
+    matcher: AhoCorasick,
+    /// Label of each pattern, by pattern id.
+    labels: Arc<Vec<String>>,
+    #[pyo3(get)]
+    case_insensitive: bool,
+    #[pyo3(get)]
+    whole_words: bool,
+    vocab: Shared<Option<Vocab>>,
+}
+
+impl KeywordTokenizer {
+    /// Matches in `text` as `(start, end, pattern id)` byte ranges.
+    fn find(&self, text: &str) -> Vec<(usize, usize, usize)> {
+        let folded = (self.case_insensitive && !text.is_ascii()).then(|| fold(text));
+        let haystack = folded
+            .as_ref()
+            .map_or(text, |(lowered, _)| lowered.as_str());
+        self.matcher
+            .find_iter(haystack)
+            .map(|m| match &folded {
+                Some((_, map)) => (map[m.start()], map[m.end()], m.pattern().as_usize()),
+                None => (m.start(), m.end(), m.pattern().as_usize()),
+            })
+            .filter(|&(start, end, _)| {
+                !self.whole_words
+                    || (!text[..start].chars().next_back().is_some_and(is_word_char)
+                        && !text[end..].chars().next().is_some_and(is_word_char))
+            })
+            .collect()
+    }
+}
+
+impl Tokenizer for KeywordTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.find(text)
+            .into_iter()
+            .map(|(start, end, _)| Token::new(&text[start..end], start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.find(text)
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+}
+
+tokenizer_pymethods!(KeywordTokenizer, encode_batch);
+
+#[pymethods]
+impl KeywordTokenizer {
+    /// Create a keyword tokenizer.
+    ///
+    /// Args:
+    ///     keywords (List[str] | Dict[str, str]): Phrases to find, or a
+    ///         mapping of phrases to labels such as entity types.
+    ///     case_insensitive (bool): Match regardless of case.
+    ///     whole_words (bool): Only report matches not embedded in a longer
+    ///         word, so `"cat"` is not found in `"concatenate"`.
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    #[new]
+    #[pyo3(signature = (keywords, case_insensitive=false, whole_words=true, vocab=None))]
+    fn new(
+        keywords: Keywords,
+        case_insensitive: bool,
+        whole_words: bool,
+        vocab: Option<PyRef<'_, Vocab>>,
+    ) -> PyResult<Self> {
+        let pairs: Vec<(String, String)> = match keywords {
+            Keywords::Phrases(phrases) => phrases.into_iter().map(|p| (p.clone(), p)).collect(),
+            Keywords::Labeled(labeled) => {
+                let mut pairs: Vec<_> = labeled.into_iter().collect();
+                pairs.sort();
+                pairs
+            }
+        };
+        if pairs.iter().any(|(phrase, _)| phrase.is_empty()) {
+            return Err(PyValueError::new_err("Keywords must not be empty"));
+        }
+        let patterns = pairs.iter().map(|(phrase, _)| match case_insensitive {
+            true => fold_phrase(phrase),
+            false => phrase.clone(),
+        });
+        let matcher = AhoCorasickBuilder::new()
+            .match_kind(MatchKind::LeftmostLongest)
+            .ascii_case_insensitive(case_insensitive)
+            .build(patterns)
+            .map_err(|e| {
+                PyValueError::new_err(format!("Failed to build keyword matcher: {}", e))
+            })?;
+        Ok(KeywordTokenizer {
+            matcher,
+            labels: Arc::new(pairs.into_iter().map(|(_, label)| label).collect()),
+            case_insensitive,
+            whole_words,
+            vocab: vocab.map(|v| v.clone()).into(),
+        })
+    }
+
+    /// Number of keywords.
+    fn __len__(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// The matched phrases of `input`, as they appear in the text.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Find the keywords in `input` together with their labels.
+    ///
+    /// Args:
+    ///     input (str): The text to search.
+    ///     unit (str): `"char"` or `"byte"` offsets, as for `tokenize_with_offsets`.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int, str]]: `(text, start, end, label)`, where
+    ///     the label is the keyword itself unless labels were given.
+    #[pyo3(signature = (input, unit="char"))]
+    fn extract(&self, input: &str, unit: &str) -> PyResult<Vec<Extraction>> {
+        let matches = self.find(input);
+        let tokens = matches
+            .iter()
+            .map(|&(start, end, _)| Token::new(&input[start..end], start, end))
+            .collect();
+        Ok(offsets(input, tokens, unit)?
+            .into_iter()
+            .zip(matches)
+            .map(|((text, start, end), (_, _, id))| (text, start, end, self.labels[id].clone()))
+            .collect())
+    }
+
+    /// Run `extract` over several texts, releasing the GIL while working.
+    #[pyo3(signature = (inputs, unit="char"))]
+    fn extract_batch(
+        &self,
+        py: Python<'_>,
+        inputs: Vec<String>,
+        unit: &str,
+    ) -> PyResult<Vec<Vec<Extraction>>> {
+        py.detach(|| {
+            inputs
+                .iter()
+                .map(|input| self.extract(input, unit))
+                .collect()
+        })
+    }
+
+    /// Encode the matched phrases into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The text to encode.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        require_vocab(&self.vocab)?.encode_text(self, input)
+    }
+
+    /// Turn ids back into text, joining the tokens with single spaces.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        require_vocab(&self.vocab)?.decode_text(&ids)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
+    }
+}
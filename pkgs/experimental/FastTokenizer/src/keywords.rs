@@ -0,0 +1,439 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+
+use crate::stopwords::{Languages, StopWordFilter};
+use crate::tokenizer::{extract_tokenizer, Tokenizer};
+
+/// The keyword scoring method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Rake,
+    Yake,
+}
+
+impl Method {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "rake" => Ok(Method::Rake),
+            "yake" => Ok(Method::Yake),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown method '{}', expected 'rake' or 'yake'",
+                other
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Method::Rake => "rake",
+            Method::Yake => "yake",
+        }
+    }
+}
+
+/// Words, numbers and single punctuation marks, used when no tokenizer is
+/// given.
+fn default_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\w+(?:['’]\w+)*|[^\w\s]").expect("valid word pattern"))
+}
+
+fn ends_sentence(text: &str) -> bool {
+    text.contains(['.', '!', '?', '。', '！', '？'])
+}
+
+/// A word of the text, with what the scorers need to know about it.
+#[derive(Debug)]
+struct Word {
+    /// The word lowercased.
+    key: String,
+    stop: bool,
+    /// Runs of words not broken by punctuation or other non-words.
+    chunk: usize,
+    sentence: usize,
+    sentence_start: bool,
+    capitalized: bool,
+    acronym: bool,
+}
+
+/// Ranked keyphrases as `(phrase, score)`.
+type Ranking = Vec<(String, f64)>;
+
+/// Extracts ranked keyphrases from text with RAKE or YAKE.
+///
+/// The text is tokenized with the given tokenizer (or pipeline), and
+/// candidate phrases are runs of words between stop words, punctuation and
+/// sentence ends.
+/// RAKE scores a phrase by the sum of its words' degree-to-frequency
+/// ratios; higher is better. YAKE combines casing, position, frequency,
+/// context diversity and sentence spread of every word into a score where
+/// lower is better. Either way the best phrases come first.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct KeywordExtractor {
+    method: Method,
+    stop_words: StopWordFilter,
+    tokenizer: Option<Arc<dyn Tokenizer>>,
+    max_ngram: usize,
+    top_k: usize,
+    window: usize,
+}
+
+impl KeywordExtractor {
+    /// The words of `text`, with chunk and sentence numbers.
+    fn words(&self, text: &str) -> Vec<Word> {
+        let spans: Vec<(usize, usize)> = match &self.tokenizer {
+            Some(tokenizer) => tokenizer
+                .tokenize_with_source_offsets(text)
+                .into_iter()
+                .map(|t| (t.start, t.end))
+                .collect(),
+            None => default_pattern()
+                .find_iter(text)
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        };
+        let mut words = Vec::with_capacity(spans.len());
+        let (mut chunk, mut sentence, mut sentence_start) = (0, 0, true);
+        let mut last = 0;
+        for (start, end) in spans {
+            let gap = text.get(last..start).unwrap_or("");
+            let token = &text[start..end];
+            last = last.max(end);
+            if !gap.trim().is_empty() || !token.chars().any(char::is_alphabetic) {
+                chunk += 1;
+                if ends_sentence(gap) || ends_sentence(token) {
+                    sentence += usize::from(!sentence_start);
+                    sentence_start = true;
+                }
+                if !token.chars().any(char::is_alphabetic) {
+                    continue;
+                }
+            }
+            let key = token.to_lowercase();
+            words.push(Word {
+                stop: self.stop_words.contains(&key),
+                chunk,
+                sentence,
+                sentence_start,
+                capitalized: token.chars().next().is_some_and(char::is_uppercase),
+                acronym: token.chars().count() > 1 && token.chars().all(|c| !c.is_lowercase()),
+                key,
+            });
+            sentence_start = false;
+        }
+        words
+    }
+
+    fn rank(&self, text: &str, top_k: usize) -> Ranking {
+        let words = self.words(text);
+        let mut ranking = match self.method {
+            Method::Rake => rake(&words, self.max_ngram),
+            Method::Yake => yake(&words, self.max_ngram, self.window),
+        };
+        ranking.sort_by(|a, b| {
+            let order = match self.method {
+                Method::Rake => b.1.total_cmp(&a.1),
+                Method::Yake => a.1.total_cmp(&b.1),
+            };
+            order.then_with(|| a.0.cmp(&b.0))
+        });
+        ranking.truncate(top_k);
+        ranking
+    }
+}
+
+/// RAKE: phrases are maximal runs of content words, and each word scores
+/// its degree (the total length of the phrases it occurs in) divided by its
+/// frequency.
+fn rake(words: &[Word], max_ngram: usize) -> Ranking {
+    let mut phrases: Vec<&[Word]> = Vec::new();
+    for run in words.chunk_by(|a, b| a.chunk == b.chunk && !a.stop && !b.stop) {
+        if !run[0].stop && run.len() <= max_ngram {
+            phrases.push(run);
+        }
+    }
+    let mut degree: HashMap<&str, (f64, f64)> = HashMap::new();
+    for phrase in &phrases {
+        for word in phrase.iter() {
+            let entry = degree.entry(&word.key).or_insert((0.0, 0.0));
+            entry.0 += phrase.len() as f64;
+            entry.1 += 1.0;
+        }
+    }
+    let mut seen = HashSet::new();
+    phrases
+        .into_iter()
+        .filter_map(|phrase| {
+            let key = join(phrase);
+            let score = phrase
+                .iter()
+                .map(|w| {
+                    let (degree, frequency) = degree[w.key.as_str()];
+                    degree / frequency
+                })
+                .sum();
+            seen.insert(key.clone()).then_some((key, score))
+        })
+        .collect()
+}
+
+fn join(words: &[Word]) -> String {
+    words
+        .iter()
+        .map(|w| w.key.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Statistics of one word for YAKE.
+#[derive(Default)]
+struct Term {
+    frequency: f64,
+    capitalized: f64,
+    acronyms: f64,
+    sentences: Vec<usize>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+fn median(values: &mut [usize]) -> f64 {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid] as f64
+    } else {
+        (values[mid - 1] + values[mid]) as f64 / 2.0
+    }
+}
+
+/// The share of distinct items among `items`, 0 when there are none.
+fn diversity(items: &[usize]) -> f64 {
+    if items.is_empty() {
+        return 0.0;
+    }
+    items.iter().collect::<HashSet<_>>().len() as f64 / items.len() as f64
+}
+
+/// YAKE (Campos et al., 2020): every word gets a score from its casing,
+/// position, frequency, relatedness to its neighbours and spread over
+/// sentences, and a phrase the product of its words' scores over its
+/// frequency. Stop words may join the words of a phrase but not start or
+/// end it.
+fn yake(words: &[Word], max_ngram: usize, window: usize) -> Ranking {
+    // Ids in order of first occurrence, so that sums over terms do not
+    // depend on hash order.
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut stop = Vec::new();
+    for word in words {
+        ids.entry(&word.key).or_insert_with(|| {
+            stop.push(word.stop);
+            stop.len() - 1
+        });
+    }
+    let mut terms: Vec<Term> = (0..ids.len()).map(|_| Term::default()).collect();
+    for (i, word) in words.iter().enumerate() {
+        let term = &mut terms[ids[word.key.as_str()]];
+        term.frequency += 1.0;
+        term.capitalized += f64::from(word.capitalized && !word.sentence_start);
+        term.acronyms += f64::from(word.acronym);
+        term.sentences.push(word.sentence);
+        let near = |j: &usize| words[*j].chunk == word.chunk && !words[*j].stop;
+        for j in (i.saturating_sub(window)..i).filter(near) {
+            term.left.push(ids[words[j].key.as_str()]);
+        }
+        for j in (i + 1..(i + 1 + window).min(words.len())).filter(near) {
+            term.right.push(ids[words[j].key.as_str()]);
+        }
+    }
+    let content: Vec<f64> = terms
+        .iter()
+        .zip(&stop)
+        .filter(|(_, &stop)| !stop)
+        .map(|(term, _)| term.frequency)
+        .collect();
+    if content.is_empty() {
+        return Vec::new();
+    }
+    let mean = content.iter().sum::<f64>() / content.len() as f64;
+    let std =
+        (content.iter().map(|f| (f - mean).powi(2)).sum::<f64>() / content.len() as f64).sqrt();
+    let max = content.iter().copied().fold(0.0, f64::max);
+    let sentences = words.last().map_or(1, |w| w.sentence + 1) as f64;
+    let scores: Vec<f64> = terms
+        .iter_mut()
+        .map(|term| {
+            let case = term.capitalized.max(term.acronyms) / (1.0 + term.frequency.ln());
+            let position = (3.0 + median(&mut term.sentences)).ln().ln();
+            let frequency = term.frequency / (mean + std);
+            let relatedness =
+                1.0 + (diversity(&term.left) + diversity(&term.right)) * term.frequency / max;
+            term.sentences.dedup();
+            let spread = term.sentences.len() as f64 / sentences;
+            relatedness * position / (case + frequency / relatedness + spread / relatedness)
+        })
+        .collect();
+    let mut candidates: HashMap<String, (f64, f64)> = HashMap::new();
+    for start in 0..words.len() {
+        if words[start].stop {
+            continue;
+        }
+        for end in start + 1..=(start + max_ngram).min(words.len()) {
+            let ngram = &words[start..end];
+            if ngram[ngram.len() - 1].chunk != ngram[0].chunk {
+                break;
+            }
+            if ngram[ngram.len() - 1].stop {
+                continue;
+            }
+            let word_scores = ngram
+                .iter()
+                .filter(|w| !w.stop)
+                .map(|w| scores[ids[w.key.as_str()]]);
+            let (product, sum) = word_scores.fold((1.0, 0.0), |(p, s), x| (p * x, s + x));
+            let entry = candidates.entry(join(ngram)).or_insert((0.0, 0.0));
+            entry.0 += 1.0;
+            entry.1 = product / (1.0 + sum);
+        }
+    }
+    candidates
+        .into_iter()
+        .map(|(phrase, (frequency, score))| (phrase, score / frequency))
+        .collect()
+}
+
+#[pymethods]
+impl KeywordExtractor {
+    /// Create a keyword extractor.
+    ///
+    /// Args:
+    ///     method (str): `"rake"` or `"yake"`.
+    ///     language (str | List[str]): Built-in stop word list(s), as for
+    ///         `StopWordFilter`. Ignored when `stop_words` is given.
+    ///     stop_words (StopWordFilter, optional): The stop words to use.
+    ///     tokenizer (optional): Any FastTokenizer tokenizer or pipeline;
+    ///         words and punctuation marks are split by a regex otherwise.
+    ///     max_ngram (int): Longest keyphrase in words.
+    ///     top_k (int): Keyphrases returned by default.
+    ///     window (int): YAKE's context window on either side of a word.
+    #[new]
+    #[pyo3(signature = (
+        method="yake",
+        language=None,
+        stop_words=None,
+        tokenizer=None,
+        max_ngram=3,
+        top_k=10,
+        window=1
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        method: &str,
+        language: Option<Languages>,
+        stop_words: Option<PyRef<'_, StopWordFilter>>,
+        tokenizer: Option<&Bound<'_, PyAny>>,
+        max_ngram: usize,
+        top_k: usize,
+        window: usize,
+    ) -> PyResult<Self> {
+        if max_ngram == 0 {
+            return Err(PyValueError::new_err("max_ngram must be at least 1"));
+        }
+        let stop_words = match stop_words {
+            Some(stop_words) => stop_words.clone(),
+            None => StopWordFilter::new(
+                Some(language.unwrap_or(Languages::One("en".to_string()))),
+                None,
+                false,
+            )?,
+        };
+        Ok(KeywordExtractor {
+            method: Method::parse(method)?,
+            stop_words,
+            tokenizer: tokenizer.map(extract_tokenizer).transpose()?,
+            max_ngram,
+            top_k,
+            window,
+        })
+    }
+
+    /// Constructor arguments recreating this extractor, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("method", self.method.name())?;
+        kwargs.set_item("stop_words", self.stop_words.clone())?;
+        kwargs.set_item(
+            "tokenizer",
+            self.tokenizer
+                .as_ref()
+                .map(|t| t.to_object(py))
+                .transpose()?,
+        )?;
+        kwargs.set_item("max_ngram", self.max_ngram)?;
+        kwargs.set_item("top_k", self.top_k)?;
+        kwargs.set_item("window", self.window)?;
+        Ok(((), kwargs))
+    }
+
+    /// The best keyphrases of `text`, lowercased, as `(phrase, score)`
+    /// pairs best first.
+    ///
+    /// Args:
+    ///     text (str): The document.
+    ///     top_k (int, optional): Number of keyphrases; the extractor's
+    ///         `top_k` by default.
+    #[pyo3(signature = (text, top_k=None))]
+    fn extract(&self, py: Python<'_>, text: &str, top_k: Option<usize>) -> Ranking {
+        let top_k = top_k.unwrap_or(self.top_k);
+        py.detach(|| self.rank(text, top_k))
+    }
+
+    /// `extract` for several documents at once, in parallel and without
+    /// holding the GIL.
+    #[pyo3(signature = (texts, top_k=None))]
+    fn extract_batch(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+        top_k: Option<usize>,
+    ) -> Vec<Ranking> {
+        let top_k = top_k.unwrap_or(self.top_k);
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| self.rank(text, top_k))
+                .collect()
+        })
+    }
+
+    /// The scoring method: `"rake"` or `"yake"`.
+    #[getter]
+    fn method(&self) -> &'static str {
+        self.method.name()
+    }
+
+    #[getter]
+    fn max_ngram(&self) -> usize {
+        self.max_ngram
+    }
+
+    #[getter]
+    fn top_k(&self) -> usize {
+        self.top_k
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "KeywordExtractor(method='{}', max_ngram={}, top_k={})",
+            self.method.name(),
+            self.max_ngram,
+            self.top_k
+        )
+    }
+}
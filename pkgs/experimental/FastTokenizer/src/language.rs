@@ -0,0 +1,135 @@
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use unicode_script::{Script, UnicodeScript};
+
+use crate::stopwords::LANGUAGES;
+
+/// Label for text whose language could not be determined.
+pub const UNDETERMINED: &str = "und";
+
+/// Letters that only some Latin-script languages use, as `(letter, code)`.
+const LETTER_HINTS: &[(char, &str)] = &[
+    ('ñ', "es"),
+    ('¿', "es"),
+    ('¡', "es"),
+    ('ß', "de"),
+    ('ä', "de"),
+    ('ö', "de"),
+    ('ü', "de"),
+    ('ã', "pt"),
+    ('õ', "pt"),
+    ('œ', "fr"),
+    ('ê', "fr"),
+    ('û', "fr"),
+    ('ì', "it"),
+    ('ò', "it"),
+];
+
+/// For every stop word of the Latin-script languages with a built-in list,
+/// the languages using it.
+fn stop_word_languages() -> &'static HashMap<&'static str, Vec<&'static str>> {
+    static WORDS: OnceLock<HashMap<&'static str, Vec<&'static str>>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        let mut words: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (code, _, list) in LANGUAGES.iter().filter(|(code, _, _)| *code != "ru") {
+            for word in list.lines().map(str::trim).filter(|w| !w.is_empty()) {
+                words.entry(word).or_default().push(code);
+            }
+        }
+        words
+    })
+}
+
+/// The language a script is written in, when it is mostly used for one.
+fn script_language(script: Script) -> Option<&'static str> {
+    Some(match script {
+        Script::Arabic => "ar",
+        Script::Cyrillic => "ru",
+        Script::Devanagari => "hi",
+        Script::Greek => "el",
+        Script::Hangul => "ko",
+        Script::Hebrew => "he",
+        Script::Hiragana | Script::Katakana => "ja",
+        Script::Han => "zh",
+        Script::Thai => "th",
+        _ => return None,
+    })
+}
+
+/// The language of Latin-script text, scored by its stop words and
+/// language-specific letters. A stop word shared by several languages
+/// counts for each of them in equal parts.
+fn latin_language(text: &str) -> Option<(&'static str, f64)> {
+    let mut scores: HashMap<&'static str, f64> = HashMap::new();
+    let words = stop_word_languages();
+    for word in text
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|w| !w.is_empty())
+    {
+        let word = word.to_lowercase();
+        if let Some(languages) = words.get(word.as_str()) {
+            for language in languages {
+                *scores.entry(language).or_default() += 1.0 / languages.len() as f64;
+            }
+        }
+    }
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if let Some((_, language)) = LETTER_HINTS.iter().find(|(hint, _)| *hint == c) {
+            *scores.entry(language).or_default() += 0.5;
+        }
+    }
+    let total: f64 = LANGUAGES
+        .iter()
+        .filter_map(|(code, _, _)| scores.get(code))
+        .sum();
+    LANGUAGES
+        .iter()
+        .filter_map(|(code, _, _)| scores.get(code).map(|&score| (*code, score)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(code, score)| (code, score / total))
+}
+
+/// The most likely language of `text` as an ISO 639-1 code, with a
+/// confidence between 0 and 1, or `None` when there is no evidence.
+pub fn detect(text: &str) -> Option<(&'static str, f64)> {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    let mut letters = 0;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        *counts.entry(c.script()).or_default() += 1;
+        letters += 1;
+    }
+    let kana =
+        counts.get(&Script::Hiragana).unwrap_or(&0) + counts.get(&Script::Katakana).unwrap_or(&0);
+    if kana > 0 {
+        // Japanese mixes kana with Han characters; Chinese has no kana.
+        let japanese = kana + counts.remove(&Script::Han).unwrap_or(0);
+        counts.remove(&Script::Katakana);
+        counts.insert(Script::Hiragana, japanese);
+    }
+    let (script, count) = counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.full_name().cmp(a.0.full_name())))?;
+    let share = count as f64 / letters as f64;
+    if script == Script::Latin {
+        return latin_language(text).map(|(code, confidence)| (code, confidence * share));
+    }
+    script_language(script).map(|code| (code, share))
+}
+
+/// Guess the language of `text`.
+///
+/// Text in a script used mainly by one language (Cyrillic, Greek, Arabic,
+/// Hebrew, Devanagari, Thai, Hangul, Han or kana) is assigned that
+/// language; Cyrillic is reported as Russian. Latin-script text is told
+/// apart by the stop words of the languages with built-in lists (German,
+/// English, Spanish, French, Italian, Dutch and Portuguese) and a few
+/// language-specific letters, so it needs a few words to go on.
+///
+/// Returns:
+///     Tuple[str, float]: An ISO 639-1 code and a confidence between 0 and
+///     1, or `("und", 0.0)` when the language could not be determined.
+#[pyfunction]
+pub fn detect_language(text: &str) -> (&'static str, f64) {
+    detect(text).unwrap_or((UNDETERMINED, 0.0))
+}
@@ -1,29 +1,253 @@
 use pyo3::prelude::*;
 
+mod aligned;
+#[cfg(feature = "io")]
+mod async_io;
+mod augment;
+mod benchmark;
+mod blocklist;
+mod bm25;
+mod bpe;
+#[cfg(feature = "io")]
+mod checkpoint;
+mod chunker;
+mod cjk_tokenizer;
+mod cleaner;
+mod code_tokenizer;
+#[cfg(feature = "io")]
+mod columnar;
+mod config;
+mod cooccur;
+mod corpus;
+#[cfg(feature = "io")]
+mod corpus_files;
+mod dedup;
+mod detokenizer;
+mod diff;
+mod emoji;
+mod filters;
+mod fuzzy;
+mod grapheme_tokenizer;
+mod hf_tokenizer;
+mod incremental;
+mod intern;
+mod keyword_tokenizer;
+mod keywords;
+mod language;
+mod limits;
+mod logging;
+mod mapped;
+mod markup;
+mod mojibake;
 mod normalizer;
+mod numbers;
+mod padding;
+mod phrases;
+mod pipeline;
+mod polyglot;
+mod random;
+mod redact;
 mod regex_tokenizer;
+#[cfg(feature = "remote")]
+mod remote;
+mod scripts;
+mod sentence_splitter;
+mod sentencepiece;
+mod shapes;
+mod shared;
+mod social_tokenizer;
+mod sparse;
+mod special_tokens;
+mod stopwords;
+mod tiktoken;
+mod tokenizer;
+#[cfg(feature = "io")]
 mod tokenizer_io;
+mod trie_tokenizer;
+mod truecase;
+mod utf8;
+mod vectorizer;
+mod vocab;
 mod whitespace_tokenizer;
+mod wordpiece;
 
 // Import the specific functions and classes
-use normalizer::{lowercase, normalize_unicode, remove_punctuation, Normalizer};
+use aligned::Alignment;
+#[cfg(feature = "io")]
+use async_io::AsyncLineIterator;
+use augment::Augmenter;
+use blocklist::BlocklistFilter;
+use bm25::Bm25Index;
+use bpe::BpeTokenizer;
+use chunker::{RecursiveChunker, SentenceChunker, TokenChunker};
+use cjk_tokenizer::CjkTokenizer;
+use cleaner::WebTextCleaner;
+use code_tokenizer::CodeTokenizer;
+use cooccur::cooccurrence;
+use corpus::count_tokens;
+#[cfg(feature = "io")]
+use corpus_files::{corpus_stats, CorpusStats};
+use dedup::{
+    hamming_distance, minhash, minhash_batch, minhash_similarity, shingle_overlap, shingles,
+    shingles_batch, simhash, simhash_batch, simhash_pairs, LshIndex,
+};
+use detokenizer::{detokenize, detokenize_with_offsets};
+use diff::token_diff;
+use emoji::normalize_emoji;
+use filters::{LengthFilter, NumericFilter, RegexFilter};
+use fuzzy::{best_matches, damerau_levenshtein, jaro_winkler, levenshtein};
+use grapheme_tokenizer::GraphemeTokenizer;
+use hf_tokenizer::HfTokenizer;
+use incremental::IncrementalTokenizer;
+use intern::StringCache;
+use keyword_tokenizer::KeywordTokenizer;
+use keywords::KeywordExtractor;
+use language::detect_language;
+use logging::{get_log_level, set_log_level};
+use markup::{strip_html, strip_markdown};
+use mojibake::{fix_text, fix_text_batch};
+use normalizer::{
+    casefold, collapse_whitespace, is_normalized, lowercase, normalize_spaces, normalize_unicode,
+    remove_control_chars, remove_punctuation, Normalizer,
+};
+use numbers::normalize_numbers;
+use padding::BatchEncoding;
+use phrases::PhraseMerger;
+use pipeline::TokenizerPipeline;
+use polyglot::PolyglotTokenizer;
+use random::set_global_seed;
+use redact::Redactor;
 use regex_tokenizer::RegexTokenizer;
-use tokenizer_io::TokenizerIO;
+use scripts::{detect_scripts, filter_scripts};
+use sentence_splitter::SentenceSplitter;
+use sentencepiece::SentencePieceTokenizer;
+use shapes::token_shapes;
+use social_tokenizer::SocialTokenizer;
+use sparse::CsrMatrix;
+use special_tokens::SpecialTokens;
+use stopwords::StopWordFilter;
+use tiktoken::TiktokenEncoder;
+use tokenizer::TokenIterator;
+#[cfg(feature = "io")]
+use tokenizer_io::{CsvIterator, JsonlIterator, RecordIterator, TokenStream, TokenizerIO};
+use trie_tokenizer::TrieTokenizer;
+use truecase::Truecaser;
+use vectorizer::{CountVectorizer, HashingVectorizer, TfidfVectorizer};
+use vocab::{remap_ids, Vocab};
 use whitespace_tokenizer::WhitespaceTokenizer;
+use wordpiece::WordPieceTokenizer;
+
+/// Counts allocations for the `benchmark` methods of the tokenizers.
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: benchmark::CountingAllocator = benchmark::CountingAllocator;
 
 /// A Python module implemented in Rust.
-#[pymodule]
+#[pymodule(gil_used = false)]
 fn fasttokenizer(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    logging::install();
+
     // Register classes
+    m.add_class::<Alignment>()?;
+    m.add_class::<Augmenter>()?;
+    m.add_class::<BatchEncoding>()?;
+    m.add_class::<BlocklistFilter>()?;
+    m.add_class::<Bm25Index>()?;
+    m.add_class::<BpeTokenizer>()?;
+    m.add_class::<CjkTokenizer>()?;
+    m.add_class::<CodeTokenizer>()?;
+    m.add_class::<CountVectorizer>()?;
+    m.add_class::<CsrMatrix>()?;
+    m.add_class::<GraphemeTokenizer>()?;
+    m.add_class::<HashingVectorizer>()?;
+    m.add_class::<HfTokenizer>()?;
+    m.add_class::<IncrementalTokenizer>()?;
+    m.add_class::<KeywordTokenizer>()?;
+    m.add_class::<KeywordExtractor>()?;
+    m.add_class::<LengthFilter>()?;
+    m.add_class::<LshIndex>()?;
     m.add_class::<Normalizer>()?;
+    m.add_class::<NumericFilter>()?;
+    m.add_class::<PolyglotTokenizer>()?;
+    m.add_class::<RecursiveChunker>()?;
+    m.add_class::<Redactor>()?;
+    m.add_class::<RegexFilter>()?;
     m.add_class::<RegexTokenizer>()?;
-    m.add_class::<TokenizerIO>()?;
+    m.add_class::<SentencePieceTokenizer>()?;
+    m.add_class::<SentenceChunker>()?;
+    m.add_class::<SentenceSplitter>()?;
+    m.add_class::<SocialTokenizer>()?;
+    m.add_class::<SpecialTokens>()?;
+    m.add_class::<StopWordFilter>()?;
+    m.add_class::<StringCache>()?;
+    m.add_class::<TfidfVectorizer>()?;
+    m.add_class::<TiktokenEncoder>()?;
+    m.add_class::<TokenChunker>()?;
+    m.add_class::<TokenIterator>()?;
+    m.add_class::<PhraseMerger>()?;
+    m.add_class::<TokenizerPipeline>()?;
+    m.add_class::<TrieTokenizer>()?;
+    m.add_class::<Truecaser>()?;
+    m.add_class::<Vocab>()?;
+    m.add_class::<WebTextCleaner>()?;
     m.add_class::<WhitespaceTokenizer>()?;
+    m.add_class::<WordPieceTokenizer>()?;
 
     // Register standalone functions
     m.add_function(wrap_pyfunction!(lowercase, m)?)?;
+    m.add_function(wrap_pyfunction!(casefold, m)?)?;
     m.add_function(wrap_pyfunction!(remove_punctuation, m)?)?;
     m.add_function(wrap_pyfunction!(normalize_unicode, m)?)?;
+    m.add_function(wrap_pyfunction!(is_normalized, m)?)?;
+    m.add_function(wrap_pyfunction!(collapse_whitespace, m)?)?;
+    m.add_function(wrap_pyfunction!(remove_control_chars, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_spaces, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_emoji, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_numbers, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_html, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_markdown, m)?)?;
+    m.add_function(wrap_pyfunction!(fix_text, m)?)?;
+    m.add_function(wrap_pyfunction!(fix_text_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_scripts, m)?)?;
+    m.add_function(wrap_pyfunction!(filter_scripts, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_language, m)?)?;
+    m.add_function(wrap_pyfunction!(detokenize, m)?)?;
+    m.add_function(wrap_pyfunction!(detokenize_with_offsets, m)?)?;
+    m.add_function(wrap_pyfunction!(token_diff, m)?)?;
+    m.add_function(wrap_pyfunction!(remap_ids, m)?)?;
+    m.add_function(wrap_pyfunction!(count_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(cooccurrence, m)?)?;
+    m.add_function(wrap_pyfunction!(minhash, m)?)?;
+    m.add_function(wrap_pyfunction!(minhash_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(minhash_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(simhash, m)?)?;
+    m.add_function(wrap_pyfunction!(simhash_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(simhash_pairs, m)?)?;
+    m.add_function(wrap_pyfunction!(hamming_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(shingles, m)?)?;
+    m.add_function(wrap_pyfunction!(shingles_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(shingle_overlap, m)?)?;
+    m.add_function(wrap_pyfunction!(levenshtein, m)?)?;
+    m.add_function(wrap_pyfunction!(damerau_levenshtein, m)?)?;
+    m.add_function(wrap_pyfunction!(jaro_winkler, m)?)?;
+    m.add_function(wrap_pyfunction!(best_matches, m)?)?;
+    m.add_function(wrap_pyfunction!(token_shapes, m)?)?;
+    m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
+    m.add_function(wrap_pyfunction!(get_log_level, m)?)?;
+    m.add_function(wrap_pyfunction!(set_global_seed, m)?)?;
+
+    // Register the APIs behind optional Cargo features
+    #[cfg(feature = "io")]
+    {
+        m.add_class::<AsyncLineIterator>()?;
+        m.add_class::<CorpusStats>()?;
+        m.add_class::<CsvIterator>()?;
+        m.add_class::<JsonlIterator>()?;
+        m.add_class::<RecordIterator>()?;
+        m.add_class::<TokenStream>()?;
+        m.add_class::<TokenizerIO>()?;
+        m.add_function(wrap_pyfunction!(corpus_stats, m)?)?;
+    }
 
     // Add the module version
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
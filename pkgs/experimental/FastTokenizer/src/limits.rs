@@ -0,0 +1,11 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+pub use fasttokenizer_core::Limits;
+
+/// Add the input and token length limits to constructor keyword arguments.
+pub fn set_items(limits: &Limits, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+    dict.set_item("max_input_length", limits.max_input_length)?;
+    dict.set_item("max_token_length", limits.max_token_length)?;
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+/// Names of the verbosity levels, from silent to most verbose.
+const LEVELS: [&str; 6] = ["off", "error", "warning", "info", "debug", "trace"];
+
+/// Index into `LEVELS` of the most verbose level forwarded to Python.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+fn rank(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        Level::TRACE => 5,
+    }
+}
+
+/// The Python `logging` level of a `tracing` level; trace is below DEBUG.
+fn python_level(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        Level::DEBUG => 10,
+        Level::TRACE => 5,
+    }
+}
+
+/// Parse a level name, or a Python `logging` level such as `logging.DEBUG`.
+fn parse_level(level: &Bound<'_, PyAny>) -> PyResult<u8> {
+    if level.is_none() {
+        return Ok(0);
+    }
+    if let Ok(number) = level.extract::<i64>() {
+        return Ok(match number {
+            40.. => 1,
+            30..=39 => 2,
+            20..=29 => 3,
+            10..=19 => 4,
+            _ => 5,
+        });
+    }
+    let name = level.extract::<String>()?.to_ascii_lowercase();
+    match name.as_str() {
+        "none" => Ok(0),
+        "warn" => Ok(2),
+        "critical" => Ok(1),
+        _ => LEVELS
+            .iter()
+            .position(|&known| known == name)
+            .map(|rank| rank as u8)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Unknown log level '{}', expected 'off', 'error', 'warning', 'info', \
+                     'debug' or 'trace'",
+                    name
+                ))
+            }),
+    }
+}
+
+/// Message and fields of an event or span, as `message key=value ...`.
+#[derive(Default)]
+struct Fields {
+    message: String,
+    fields: String,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+/// What a span keeps for the events inside it: its fields and start time.
+struct SpanData {
+    fields: String,
+    started: Instant,
+}
+
+/// Forwards events to Python's `logging`, to the logger named after the
+/// module that emitted them (`fasttokenizer.pipeline`, ...). Messages are
+/// prefixed with the spans they happened in, like `pipeline{bytes=12}:`, and
+/// every span logs its duration when it closes.
+struct PythonLogging;
+
+impl PythonLogging {
+    fn emit(metadata: &Metadata<'_>, message: String) {
+        let logger = metadata.target().replace("::", ".");
+        let level = python_level(metadata.level());
+        Python::attach(|py| {
+            let logged = py
+                .import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (logger,)))
+                .and_then(|logger| logger.call_method1("log", (level, message)));
+            // A failing handler must not turn tokenization into an error.
+            if let Err(e) = logged {
+                e.print(py);
+            }
+        });
+    }
+
+    /// `outer{a=1}:inner{b=2}: ` for the spans around the current position.
+    fn scope<S>(ctx: &Context<'_, S>, event: Option<&Event<'_>>) -> String
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut prefix = String::new();
+        let scope = match event {
+            Some(event) => ctx.event_scope(event),
+            None => ctx.lookup_current().map(|span| span.scope()),
+        };
+        for span in scope.into_iter().flat_map(|scope| scope.from_root()) {
+            prefix.push_str(span.name());
+            if let Some(data) = span.extensions().get::<SpanData>() {
+                if !data.fields.is_empty() {
+                    let _ = write!(prefix, "{{{}}}", data.fields.trim_start());
+                }
+            }
+            prefix.push(':');
+        }
+        if !prefix.is_empty() {
+            prefix.push(' ');
+        }
+        prefix
+    }
+}
+
+impl<S> Layer<S> for PythonLogging
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+        // The level can change at any time, so ask `enabled` every time.
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, S>) -> bool {
+        rank(metadata.level()) <= MAX_LEVEL.load(Ordering::Relaxed)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanData {
+                fields: fields.fields,
+                started: Instant::now(),
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let prefix = Self::scope(&ctx, Some(event));
+        Self::emit(
+            event.metadata(),
+            format!("{}{}{}", prefix, fields.message, fields.fields),
+        );
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let mut message = String::new();
+        for outer in span.scope().skip(1).collect::<Vec<_>>().into_iter().rev() {
+            message.push_str(outer.name());
+            message.push(':');
+        }
+        message.push_str(span.name());
+        let extensions = span.extensions();
+        if let Some(data) = extensions.get::<SpanData>() {
+            if !data.fields.is_empty() {
+                let _ = write!(message, "{{{}}}", data.fields.trim_start());
+            }
+            let _ = write!(message, ": done in {:.3?}", data.started.elapsed());
+        }
+        Self::emit(span.metadata(), message);
+    }
+}
+
+/// Route the crate's `tracing` spans and events to Python `logging`, at the
+/// level of the `FASTTOKENIZER_LOG` environment variable or off. Another
+/// global subscriber, if one was installed first, is left in place.
+pub fn install() {
+    if let Ok(level) = std::env::var("FASTTOKENIZER_LOG") {
+        if let Some(rank) = LEVELS.iter().position(|&known| known == level) {
+            MAX_LEVEL.store(rank as u8, Ordering::Relaxed);
+        }
+    }
+    let _ = tracing::subscriber::set_global_default(Registry::default().with(PythonLogging));
+}
+
+/// Set how much the Rust code logs.
+///
+/// Spans and events at or above `level` are forwarded to Python's `logging`
+/// module, to loggers named after the Rust modules (`fasttokenizer.pipeline`,
+/// `fasttokenizer.tokenizer_io`, ...), where the usual logger levels and
+/// handlers decide what is shown. Tokenizing a single string logs only at
+/// `"trace"`; batch and file operations log at `"debug"`. Logging is off by
+/// default.
+///
+/// Args:
+///     level (str | int | None): `"off"`, `"error"`, `"warning"`, `"info"`,
+///         `"debug"` or `"trace"`, a `logging` level such as
+///         `logging.DEBUG`, or `None` to turn logging off.
+#[pyfunction]
+pub fn set_log_level(level: &Bound<'_, PyAny>) -> PyResult<()> {
+    MAX_LEVEL.store(parse_level(level)?, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The level set with `set_log_level`, such as `"off"` or `"debug"`.
+#[pyfunction]
+pub fn get_log_level() -> &'static str {
+    LEVELS[MAX_LEVEL.load(Ordering::Relaxed) as usize]
+}
@@ -0,0 +1,146 @@
+use memmap2::Mmap;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::fs::File;
+use std::hash::BuildHasher;
+use std::io;
+use std::sync::OnceLock;
+
+/// Map the file at `path` into memory read-only. The pages come from the
+/// OS page cache, so every process mapping the same file shares them.
+pub fn map_file(path: &str) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    // SAFETY: the map is read-only; like every memory-mapped reader we rely
+    // on the file not being truncated or rewritten while it is mapped.
+    unsafe { Mmap::map(&file) }
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+/// Append `tokens` to `out` as a token table: `count + 1` little-endian
+/// `u32` offsets, then the UTF-8 bytes of every token back to back, token
+/// `i` spanning offsets `i` to `i + 1`. Tokens can be read in place without
+/// parsing the whole table.
+pub fn write_table<'a>(out: &mut Vec<u8>, tokens: impl ExactSizeIterator<Item = &'a str> + Clone) {
+    let mut offset = 0u32;
+    out.extend_from_slice(&offset.to_le_bytes());
+    for token in tokens.clone() {
+        offset += token.len() as u32;
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    for token in tokens {
+        out.extend_from_slice(token.as_bytes());
+    }
+}
+
+/// Check the table of `count` tokens at `start` of `bytes`, returning
+/// where it ends.
+pub fn check_table(bytes: &[u8], start: usize, count: usize) -> Result<usize, String> {
+    let data = (count + 1)
+        .checked_mul(4)
+        .and_then(|size| size.checked_add(start))
+        .filter(|&data| data <= bytes.len())
+        .ok_or("Truncated vocabulary data")?;
+    let offset = |i: usize| read_u32(bytes, start + 4 * i) as usize;
+    if offset(0) != 0 || (0..count).any(|i| offset(i) > offset(i + 1)) {
+        return Err("Invalid token offsets in vocabulary data".to_string());
+    }
+    let end = data + offset(count);
+    let text = bytes
+        .get(data..end)
+        .ok_or("Truncated vocabulary data")
+        .and_then(|b| std::str::from_utf8(b).map_err(|_| "Invalid UTF-8 in vocabulary data"))?;
+    if !(0..=count).all(|i| text.is_char_boundary(offset(i))) {
+        return Err("Invalid UTF-8 in vocabulary data".to_string());
+    }
+    Ok(end)
+}
+
+/// Token `id` of a table that passed `check_table`.
+pub fn table_token(bytes: &[u8], start: usize, count: usize, id: usize) -> &str {
+    let data = start + 4 * (count + 1);
+    let from = data + read_u32(bytes, start + 4 * id) as usize;
+    let to = data + read_u32(bytes, start + 4 * (id + 1)) as usize;
+    std::str::from_utf8(&bytes[from..to]).expect("checked token table")
+}
+
+/// The tokens of a memory-mapped token table, read in place. The index
+/// for looking up ids by token is only built on the first lookup, so
+/// loading costs neither time nor memory up front; built before forking,
+/// it is shared with the child processes too.
+pub struct MappedTokens {
+    map: Mmap,
+    start: usize,
+    count: usize,
+    /// Open addressing table of ids by token hash, `u32::MAX` when empty.
+    index: OnceLock<Vec<u32>>,
+    hasher: RandomState,
+}
+
+impl MappedTokens {
+    /// Use the table of `count` tokens at `start` of `map`.
+    pub fn new(map: Mmap, start: usize, count: usize) -> Result<Self, String> {
+        check_table(&map, start, count)?;
+        Ok(MappedTokens {
+            map,
+            start,
+            count,
+            index: OnceLock::new(),
+            hasher: RandomState::new(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn get(&self, id: usize) -> Option<&str> {
+        (id < self.count).then(|| table_token(&self.map, self.start, self.count, id))
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &str> + Clone {
+        (0..self.count).map(|id| table_token(&self.map, self.start, self.count, id))
+    }
+
+    /// Id of `token`; the first one when the table repeats it.
+    pub fn id(&self, token: &str) -> Option<u32> {
+        let index = self.index.get_or_init(|| self.build_index());
+        let mask = index.len() - 1;
+        let mut slot = self.hasher.hash_one(token) as usize & mask;
+        loop {
+            let id = index[slot];
+            if id == u32::MAX {
+                return None;
+            }
+            if self.get(id as usize) == Some(token) {
+                return Some(id);
+            }
+            slot = (slot + 1) & mask;
+        }
+    }
+
+    fn build_index(&self) -> Vec<u32> {
+        let mut index = vec![u32::MAX; (self.count * 2).next_power_of_two().max(2)];
+        let mask = index.len() - 1;
+        for (id, token) in self.iter().enumerate() {
+            let mut slot = self.hasher.hash_one(token) as usize & mask;
+            while index[slot] != u32::MAX && self.get(index[slot] as usize) != Some(token) {
+                slot = (slot + 1) & mask;
+            }
+            if index[slot] == u32::MAX {
+                index[slot] = id as u32;
+            }
+        }
+        index
+    }
+}
+
+impl fmt::Debug for MappedTokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedTokens")
+            .field("len", &self.count)
+            .finish()
+    }
+}
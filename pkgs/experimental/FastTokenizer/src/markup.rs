@@ -0,0 +1,315 @@
+use pulldown_cmark::{Event, Options, Parser, TagEnd};
+use pyo3::prelude::*;
+use std::borrow::Cow;
+
+use crate::aligned::AlignedString;
+
+/// Tags whose contents are not text.
+const RAW_TEXT_TAGS: &[&str] = &["script", "style", "noscript", "template", "textarea"];
+
+/// Tags that start a new line of text, so words on either side of them are
+/// not glued together.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// Tags separated from their neighbours by a space.
+const CELL_TAGS: &[&str] = &["td", "th"];
+
+/// Output of markup stripping: a plain `String`, or an `AlignedString` that
+/// also records where each piece of text came from.
+pub trait Sink: Default {
+    fn text(&self) -> &str;
+
+    fn append(&mut self, other: Self);
+
+    fn truncate(&mut self, len: usize);
+
+    /// Append text produced from bytes `span` of the original.
+    fn push_span(&mut self, s: &str, span: (usize, usize));
+
+    /// Append `s`, copied verbatim from byte `base` of the original.
+    fn push_source(&mut self, s: &str, base: usize);
+
+    /// Append `s`, found at byte `base` of the original, with HTML entities
+    /// decoded.
+    fn push_decoded(&mut self, s: &str, base: usize);
+}
+
+impl Sink for String {
+    fn text(&self) -> &str {
+        self
+    }
+
+    fn append(&mut self, other: Self) {
+        self.push_str(&other);
+    }
+
+    fn truncate(&mut self, len: usize) {
+        String::truncate(self, len);
+    }
+
+    fn push_span(&mut self, s: &str, _span: (usize, usize)) {
+        self.push_str(s);
+    }
+
+    fn push_source(&mut self, s: &str, _base: usize) {
+        self.push_str(s);
+    }
+
+    fn push_decoded(&mut self, s: &str, _base: usize) {
+        self.push_str(&html_escape::decode_html_entities(s));
+    }
+}
+
+impl Sink for AlignedString {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn append(&mut self, other: Self) {
+        self.text.push_str(&other.text);
+        self.spans.extend(other.spans);
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.text.truncate(len);
+        self.spans.truncate(len);
+    }
+
+    fn push_span(&mut self, s: &str, span: (usize, usize)) {
+        self.push_str(s, span);
+    }
+
+    fn push_source(&mut self, s: &str, base: usize) {
+        self.append(AlignedString::new(s, base));
+    }
+
+    /// Entities are decoded one at a time so that only the decoded entity,
+    /// not the text around it, maps to the entity as a whole.
+    fn push_decoded(&mut self, s: &str, base: usize) {
+        let mut from = 0;
+        while from < s.len() {
+            // Split before every `&` and after the `;` closing an entity.
+            let rest = &s[from..];
+            let len = if let Some(entity) = rest.strip_prefix('&') {
+                let next = entity.find('&').map_or(rest.len(), |i| i + 1);
+                rest[..next].find(';').map_or(next, |i| i + 1)
+            } else {
+                rest.find('&').unwrap_or(rest.len())
+            };
+            let piece = &rest[..len];
+            match html_escape::decode_html_entities(piece) {
+                Cow::Borrowed(_) => self.push_source(piece, base + from),
+                Cow::Owned(decoded) => self.push_str(&decoded, (base + from, base + from + len)),
+            }
+            from += len;
+        }
+    }
+}
+
+/// Remove HTML tags, comments, scripts and styles and decode entities.
+///
+/// Block-level tags such as `<p>`, `<div>` and `<br>` become line breaks.
+/// A `<` that does not start a tag (as in `a < b`) is kept.
+#[pyfunction]
+pub fn strip_html(text: &str) -> String {
+    strip_html_from(text, 0)
+}
+
+/// `strip_html`, remembering the original span of every output character.
+pub fn strip_html_aligned(text: &str) -> AlignedString {
+    strip_html_from(text, 0)
+}
+
+/// Strip the HTML in `text`, which starts at byte `base` of the original.
+fn strip_html_from<S: Sink>(text: &str, base: usize) -> S {
+    let mut out = S::default();
+    let at = |rest: &str| base + text.len() - rest.len();
+    let mut rest = text;
+    while let Some(lt) = rest.find('<') {
+        out.push_decoded(&rest[..lt], at(rest));
+        rest = &rest[lt..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").unwrap_or(after.len());
+            out.push_source(&after[..end], at(after));
+            rest = after.get(end + 3..).unwrap_or("");
+            continue;
+        }
+        let Some((name, closing, len)) = parse_tag(rest) else {
+            out.push_source("<", at(rest));
+            rest = &rest[1..];
+            continue;
+        };
+        let tag = (at(rest), at(rest) + len);
+        rest = &rest[len..];
+        if !closing && RAW_TEXT_TAGS.contains(&name.as_str()) {
+            rest = skip_raw_text(rest, &name);
+        }
+        if BLOCK_TAGS.contains(&name.as_str()) {
+            push_break(&mut out, '\n', tag);
+        } else if CELL_TAGS.contains(&name.as_str()) {
+            push_break(&mut out, ' ', tag);
+        }
+    }
+    out.push_decoded(rest, at(rest));
+    let trimmed = out.text().trim_end_matches('\n').len();
+    out.truncate(trimmed);
+    out
+}
+
+/// Remove Markdown syntax, keeping the text of headings, paragraphs, lists,
+/// links, images (their alt text), code and tables.
+///
+/// Inline and block HTML is stripped as by `strip_html`.
+#[pyfunction]
+pub fn strip_markdown(text: &str) -> String {
+    strip_markdown_from(text)
+}
+
+/// `strip_markdown`, remembering the original span of every output
+/// character.
+pub fn strip_markdown_aligned(text: &str) -> AlignedString {
+    strip_markdown_from(text)
+}
+
+fn strip_markdown_from<S: Sink>(text: &str) -> S {
+    let mut out = S::default();
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    for (event, range) in Parser::new_ext(text, options).into_offset_iter() {
+        let source = &text[range.clone()];
+        let span = (range.start, range.end);
+        match event {
+            // Escapes and entities make the text differ from its source;
+            // those pieces map to the whole source range.
+            Event::Text(piece) | Event::Code(piece) => match source.find(&*piece) {
+                Some(i) => out.push_source(&piece, range.start + i),
+                None => out.push_span(&piece, span),
+            },
+            Event::Html(html) | Event::InlineHtml(html) => match source.find(&*html) {
+                Some(i) => out.append(strip_html_from(&html, range.start + i)),
+                None => out.push_span(&strip_html(&html), span),
+            },
+            Event::SoftBreak => out.push_span(" ", span),
+            Event::HardBreak | Event::Rule => push_break(&mut out, '\n', span),
+            Event::End(TagEnd::TableCell) => push_break(&mut out, ' ', (range.end, range.end)),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::BlockQuote(_)
+                | TagEnd::CodeBlock
+                | TagEnd::HtmlBlock
+                | TagEnd::Item
+                | TagEnd::TableHead
+                | TagEnd::TableRow
+                | TagEnd::FootnoteDefinition,
+            ) => push_break(&mut out, '\n', (range.end, range.end)),
+            _ => {}
+        }
+    }
+    let trimmed = out.text().trim_end().len();
+    out.truncate(trimmed);
+    out
+}
+
+/// Parse the tag at the start of `text`, returning its lowercased name,
+/// whether it is a closing tag and its length including the `>`.
+fn parse_tag(text: &str) -> Option<(String, bool, usize)> {
+    let bytes = text.as_bytes();
+    let mut i = 1;
+    let closing = bytes.get(i) == Some(&b'/');
+    if closing {
+        i += 1;
+    }
+    let declaration = matches!(bytes.get(i), Some(b'!' | b'?'));
+    if !declaration && !bytes.get(i).is_some_and(u8::is_ascii_alphabetic) {
+        return None;
+    }
+    let start = i;
+    while bytes
+        .get(i)
+        .is_some_and(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b':' | b'!' | b'?'))
+    {
+        i += 1;
+    }
+    let name = text[start..i].to_ascii_lowercase();
+    let mut quote = None;
+    while let Some(&b) = bytes.get(i) {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), _) if q == b => quote = None,
+            (None, b'>') => return Some((name, closing, i + 1)),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Skip the contents of a raw text element up to and including its end tag.
+fn skip_raw_text<'a>(text: &'a str, name: &str) -> &'a str {
+    let mut from = 0;
+    while let Some(pos) = text[from..].find("</") {
+        let start = from + pos;
+        let candidate = &text.as_bytes()[start + 2..];
+        if candidate.len() >= name.len()
+            && candidate[..name.len()].eq_ignore_ascii_case(name.as_bytes())
+        {
+            let after = &text[start..];
+            return after.find('>').map_or("", |end| &after[end + 1..]);
+        }
+        from = start + 2;
+    }
+    ""
+}
+
+/// Push a separator unless the output is empty or already ends with one;
+/// a line break replaces trailing spaces.
+fn push_break(out: &mut impl Sink, separator: char, span: (usize, usize)) {
+    if separator == '\n' {
+        let trimmed = out.text().trim_end_matches(' ').len();
+        out.truncate(trimmed);
+    }
+    match out.text().chars().last() {
+        None | Some('\n') => {}
+        Some(' ') if separator == ' ' => {}
+        Some(_) => out.push_span(separator.encode_utf8(&mut [0; 4]), span),
+    }
+}
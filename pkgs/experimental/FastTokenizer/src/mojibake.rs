@@ -0,0 +1,256 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// The characters Windows-1252 decodes bytes 0x80 to 0x9F to; bytes it
+/// leaves undefined decode to the C1 control of the same value, as Python's
+/// and browsers' fallbacks do.
+const CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// The byte Windows-1252 (or Latin-1, for the C1 controls) encodes `c` as.
+fn cp1252_byte(c: char) -> Option<u8> {
+    match c as u32 {
+        0..=0xFF => Some(c as u8),
+        _ => CP1252_HIGH
+            .iter()
+            .position(|&high| high == c)
+            .map(|i| 0x80 + i as u8),
+    }
+}
+
+/// `word` re-encoded as Windows-1252 and decoded as UTF-8, when that
+/// succeeds and changes it.
+fn fix_word(word: &str) -> Option<String> {
+    if word.is_ascii() {
+        return None;
+    }
+    let bytes: Vec<u8> = word.chars().map(cp1252_byte).collect::<Option<_>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Undo one round of UTF-8 text decoded as Windows-1252 or Latin-1, as in
+/// `cafÃ©` for `café` or `Itâ€™s` for `It’s`.
+///
+/// Text is repaired a whitespace-separated word at a time, and only when
+/// the whole word re-encodes to valid UTF-8. Correct words that merely
+/// contain accented letters, like `naïve`, never do, so they are left
+/// alone.
+fn fix_round(text: &str) -> Cow<'_, str> {
+    let mut out: Option<String> = None;
+    let mut copied = 0;
+    for (start, word) in words(text) {
+        if let Some(fixed) = fix_word(word) {
+            let out = out.get_or_insert_with(|| String::with_capacity(text.len()));
+            out.push_str(&text[copied..start]);
+            out.push_str(&fixed);
+            copied = start + word.len();
+        }
+    }
+    match out {
+        Some(mut out) => {
+            out.push_str(&text[copied..]);
+            Cow::Owned(out)
+        }
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// The words of `text` between ASCII whitespace, with their byte offsets.
+///
+/// Only ASCII whitespace separates words: a no-break space or `U+0085` may
+/// itself be part of the damage (`Ã\u{a0}` for `à`).
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_ascii_whitespace(), start) {
+            (true, Some(from)) => {
+                words.push((from, &text[from..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        words.push((from, &text[from..]));
+    }
+    words
+}
+
+/// Repair mojibake from UTF-8 decoded as Windows-1252 or Latin-1, up to
+/// three layers deep.
+fn fix_mojibake(text: &str) -> Cow<'_, str> {
+    let mut fixed = fix_round(text);
+    for _ in 0..2 {
+        match fix_round(&fixed) {
+            Cow::Owned(again) => fixed = Cow::Owned(again),
+            Cow::Borrowed(_) => break,
+        }
+    }
+    fixed
+}
+
+/// Replace C1 control characters with the Windows-1252 characters they
+/// stand for, as in `It\u{92}s` for `It’s`. The five bytes Windows-1252
+/// leaves undefined are kept.
+fn fix_c1_controls(text: &str) -> Cow<'_, str> {
+    let is_c1 = |c: char| ('\u{80}'..='\u{9F}').contains(&c);
+    if !text.contains(is_c1) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .map(|c| {
+                if is_c1(c) {
+                    CP1252_HIGH[c as usize - 0x80]
+                } else {
+                    c
+                }
+            })
+            .collect(),
+    )
+}
+
+/// When `fix_text` decodes HTML entities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unescape {
+    /// Only in text without tags, which is not HTML itself.
+    Auto,
+    Always,
+    Never,
+}
+
+impl Unescape {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "auto" => Ok(Unescape::Auto),
+            "always" => Ok(Unescape::Always),
+            "never" => Ok(Unescape::Never),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown unescape_html mode '{}', expected 'auto', 'always' or 'never'",
+                other
+            ))),
+        }
+    }
+
+    fn applies(self, text: &str) -> bool {
+        match self {
+            Unescape::Auto => !text.contains('<') || !text.contains('>'),
+            Unescape::Always => true,
+            Unescape::Never => false,
+        }
+    }
+}
+
+/// Decode HTML entities, twice for doubly escaped text like `&amp;quot;`.
+fn unescape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    match html_escape::decode_html_entities(text) {
+        Cow::Owned(once) => match html_escape::decode_html_entities(&once) {
+            Cow::Owned(twice) => Cow::Owned(twice),
+            Cow::Borrowed(_) => Cow::Owned(once),
+        },
+        Cow::Borrowed(_) => Cow::Borrowed(text),
+    }
+}
+
+/// The repairs `fix_text` makes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextFixes {
+    pub encoding: bool,
+    pub c1_controls: bool,
+    pub html: Unescape,
+}
+
+impl TextFixes {
+    pub const ALL: TextFixes = TextFixes {
+        encoding: true,
+        c1_controls: true,
+        html: Unescape::Auto,
+    };
+
+    /// Entities are decoded first, since they may spell out mojibake
+    /// (`caf&Atilde;&copy;`); C1 controls are replaced last, since
+    /// mojibake may contain them.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        if self.html.applies(text) {
+            if let Cow::Owned(fixed) = unescape_html(&out) {
+                out = Cow::Owned(fixed);
+            }
+        }
+        if self.encoding {
+            if let Cow::Owned(fixed) = fix_mojibake(&out) {
+                out = Cow::Owned(fixed);
+            }
+        }
+        if self.c1_controls {
+            if let Cow::Owned(fixed) = fix_c1_controls(&out) {
+                out = Cow::Owned(fixed);
+            }
+        }
+        out
+    }
+}
+
+/// Repair common mojibake and encoding leftovers in text.
+///
+/// Fixes UTF-8 that was decoded as Windows-1252 or Latin-1, up to three
+/// times over (`cafÃ©` becomes `café`, `â€œ` becomes `“`), C1 control
+/// characters standing in for Windows-1252 punctuation (`\x92` becomes `’`)
+/// and leftover or doubly escaped HTML entities (`&amp;quot;` becomes `"`).
+/// A word is only re-decoded when all of it is valid UTF-8 afterwards, so
+/// correct text is returned unchanged.
+///
+/// Args:
+///     text (str): Input text.
+///     fix_encoding (bool): Undo UTF-8 decoded with the wrong codec.
+///     fix_c1_controls (bool): Replace C1 controls with the Windows-1252
+///         characters they encode.
+///     unescape_html (str): `"auto"` decodes entities unless the text
+///         contains tags, `"always"` or `"never"`.
+#[pyfunction]
+#[pyo3(signature = (text, fix_encoding=true, fix_c1_controls=true, unescape_html="auto"))]
+pub fn fix_text<'a>(
+    text: &'a str,
+    fix_encoding: bool,
+    fix_c1_controls: bool,
+    unescape_html: &str,
+) -> PyResult<Cow<'a, str>> {
+    let fixes = TextFixes {
+        encoding: fix_encoding,
+        c1_controls: fix_c1_controls,
+        html: Unescape::parse(unescape_html)?,
+    };
+    Ok(fixes.apply(text))
+}
+
+/// `fix_text` for many texts at once, in parallel without the GIL.
+#[pyfunction]
+#[pyo3(signature = (texts, fix_encoding=true, fix_c1_controls=true, unescape_html="auto"))]
+pub fn fix_text_batch(
+    py: Python<'_>,
+    texts: Vec<String>,
+    fix_encoding: bool,
+    fix_c1_controls: bool,
+    unescape_html: &str,
+) -> PyResult<Vec<String>> {
+    let fixes = TextFixes {
+        encoding: fix_encoding,
+        c1_controls: fix_c1_controls,
+        html: Unescape::parse(unescape_html)?,
+    };
+    Ok(py.detach(|| {
+        texts
+            .par_iter()
+            .map(|text| fixes.apply(text).into_owned())
+            .collect()
+    }))
+}
@@ -1,6 +1,18 @@
+use caseless::Caseless;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use std::borrow::Cow;
-use unicode_normalization::UnicodeNormalization;
+use unicode_categories::UnicodeCategories;
+
+use crate::aligned::{merge_disjoint, AlignedString, Alignment};
+use crate::emoji::{EmojiMode, EmojiStage};
+use crate::markup::{strip_html, strip_html_aligned, strip_markdown, strip_markdown_aligned};
+use crate::numbers::{NumberMode, NumberStage};
+use crate::scripts::ScriptFilter;
+use crate::tokenizer::py_err;
+
+pub use fasttokenizer_core::Form;
 
 // Add 'pub' keyword to make these functions public
 #[pyfunction]
@@ -9,45 +21,605 @@ pub fn lowercase<'a>(text: &'a str) -> PyResult<Cow<'a, str>> {
     Ok(Cow::from(text.to_lowercase()))
 }
 
+/// Remove punctuation from `text`.
+///
+/// Args:
+///     text (str): Input text.
+///     unicode (bool): Remove every character in a Unicode punctuation
+///         category (Pc, Pd, Ps, Pe, Pi, Pf, Po) instead of ASCII punctuation
+///         only.
+///     keep (str, optional): Characters to keep even if they are punctuation,
+///         e.g. `"'-"`.
+#[pyfunction]
+#[pyo3(signature = (text, unicode=false, keep=None))]
+pub fn remove_punctuation<'a>(
+    text: &'a str,
+    unicode: bool,
+    keep: Option<&str>,
+) -> PyResult<Cow<'a, str>> {
+    Ok(Punctuation::new(unicode, keep).remove(text))
+}
+
+/// Casefold `text` for caseless matching.
+///
+/// Unlike `lowercase`, full case folding maps e.g. `"ß"` to `"ss"` and
+/// `"ς"` to `"σ"`. With `locale="tr"` (or `"az"`), `"I"` folds to the
+/// dotless `"ı"` and `"İ"` to `"i"` as in Turkish.
+#[pyfunction]
+#[pyo3(signature = (text, locale=None))]
+pub fn casefold(text: &str, locale: Option<&str>) -> PyResult<String> {
+    Ok(CaseFold::parse(locale)?.apply(text))
+}
+
+/// Replace every run of whitespace with a single space.
+///
+/// Args:
+///     text (str): Input text.
+///     keep_newlines (bool): Replace runs containing a line break with
+///         `"\n"` instead, preserving line structure.
+#[pyfunction]
+#[pyo3(signature = (text, keep_newlines=false))]
+pub fn collapse_whitespace(text: &str, keep_newlines: bool) -> Cow<'_, str> {
+    collapse(text, keep_newlines)
+}
+
+/// Remove control characters and invisible format characters such as
+/// zero-width spaces and joiners, bidi marks and byte order marks.
+/// Tabs and line breaks are kept.
 #[pyfunction]
-pub fn remove_punctuation<'a>(text: &'a str) -> PyResult<Cow<'a, str>> {
-    // Remove punctuation from the input string
-    Ok(Cow::from(
+pub fn remove_control_chars(text: &str) -> Cow<'_, str> {
+    remove_if(text, is_control)
+}
+
+/// Replace Unicode space separators (no-break, em, ideographic, ... spaces)
+/// with an ASCII space.
+#[pyfunction]
+pub fn normalize_spaces(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_unicode_space) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
         text.chars()
-            .filter(|c| !c.is_ascii_punctuation())
-            .collect::<String>(),
-    ))
+            .map(|c| if is_unicode_space(c) { ' ' } else { c })
+            .collect(),
+    )
+}
+
+fn is_control(c: char) -> bool {
+    (c.is_other_control() && !c.is_whitespace()) || c.is_other_format()
+}
+
+fn is_unicode_space(c: char) -> bool {
+    c != ' ' && c.is_separator_space()
+}
+
+fn is_line_break(c: char) -> bool {
+    matches!(
+        c,
+        '\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}'
+    )
+}
+
+/// `text` without the characters matching `remove`, borrowed when there are
+/// none.
+fn remove_if(text: &str, remove: impl Fn(char) -> bool) -> Cow<'_, str> {
+    if !text.chars().any(&remove) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().filter(|&c| !remove(c)).collect())
+}
+
+fn collapse(text: &str, keep_newlines: bool) -> Cow<'_, str> {
+    let mut out = String::with_capacity(text.len());
+    let mut run: Option<char> = None;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            let separator = if keep_newlines && is_line_break(c) {
+                '\n'
+            } else {
+                ' '
+            };
+            run = Some(match run {
+                Some('\n') => '\n',
+                _ => separator,
+            });
+            continue;
+        }
+        if let Some(separator) = run.take() {
+            out.push(separator);
+        }
+        out.push(c);
+    }
+    if let Some(separator) = run {
+        out.push(separator);
+    }
+    if out == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+/// `collapse` without line breaks, where every run of whitespace maps to the
+/// whole run in the original.
+fn collapse_aligned(text: &AlignedString) -> AlignedString {
+    let mut out = AlignedString::default();
+    let mut run: Option<(usize, usize)> = None;
+    for (_, c, span) in text.chars() {
+        if c.is_whitespace() {
+            run = Some(run.map_or(span, |(start, _)| (start, span.1)));
+            continue;
+        }
+        if let Some(run) = run.take() {
+            out.push(' ', run);
+        }
+        out.push(c, span);
+    }
+    if let Some(run) = run {
+        out.push(' ', run);
+    }
+    out
+}
+
+/// Which characters punctuation removal drops.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Punctuation {
+    /// Use the Unicode punctuation categories rather than ASCII only.
+    unicode: bool,
+    keep: Vec<char>,
+}
+
+impl Punctuation {
+    pub fn new(unicode: bool, keep: Option<&str>) -> Self {
+        Punctuation {
+            unicode,
+            keep: keep.unwrap_or_default().chars().collect(),
+        }
+    }
+
+    pub fn is_removed(&self, c: char) -> bool {
+        let punctuation = if self.unicode {
+            c.is_punctuation()
+        } else {
+            c.is_ascii_punctuation()
+        };
+        punctuation && !self.keep.contains(&c)
+    }
+
+    /// `text` without punctuation, borrowed when there is none.
+    pub fn remove<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        remove_if(text, |c| self.is_removed(c))
+    }
+}
+
+/// Full Unicode case folding, optionally with Turkic dotted/dotless i rules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaseFold {
+    #[default]
+    Default,
+    Turkic,
 }
 
+impl CaseFold {
+    /// Parse a locale; only Turkish and Azerbaijani fold differently.
+    pub fn parse(locale: Option<&str>) -> PyResult<Self> {
+        let Some(locale) = locale else {
+            return Ok(CaseFold::Default);
+        };
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "tr" | "az" => Ok(CaseFold::Turkic),
+            "" => Err(PyValueError::new_err(format!(
+                "Invalid locale '{}'",
+                locale
+            ))),
+            _ => Ok(CaseFold::Default),
+        }
+    }
+
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            CaseFold::Default => text.chars().default_case_fold().collect(),
+            CaseFold::Turkic => text.chars().map(turkic).default_case_fold().collect(),
+        }
+    }
+
+    /// Push the folded form of a single character.
+    pub fn push(self, c: char, out: &mut String) {
+        let c = match self {
+            CaseFold::Default => c,
+            CaseFold::Turkic => turkic(c),
+        };
+        out.extend(std::iter::once(c).default_case_fold());
+    }
+}
+
+/// Map the dotted capital I and dotless capital I to their Turkic
+/// lowercase forms ahead of default folding.
+fn turkic(c: char) -> char {
+    match c {
+        'I' => '\u{131}',
+        '\u{130}' => 'i',
+        c => c,
+    }
+}
+
+/// Normalize `text` to the Unicode normalization `form` (`"NFC"`, `"NFD"`,
+/// `"NFKC"` or `"NFKD"`). Case is left unchanged; see `lowercase`.
+#[pyfunction]
+#[pyo3(signature = (text, form="NFC"))]
+pub fn normalize_unicode<'a>(text: &'a str, form: &str) -> PyResult<Cow<'a, str>> {
+    Ok(Form::parse(form).map_err(py_err)?.apply(text))
+}
+
+/// Check whether `text` is already in the Unicode normalization `form`,
+/// without building a normalized copy.
 #[pyfunction]
-pub fn normalize_unicode<'a>(text: &'a str) -> PyResult<Cow<'a, str>> {
-    // Normalize the input string
-    Ok(Cow::from(text.nfc().collect::<String>()))
+#[pyo3(signature = (text, form="NFC"))]
+pub fn is_normalized(text: &str, form: &str) -> PyResult<bool> {
+    Ok(Form::parse(form).map_err(py_err)?.is_normalized(text))
 }
 
 // Create a Normalizer class to expose to Python
-#[pyclass]
-pub struct Normalizer {}
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Default)]
+pub struct Normalizer {
+    /// Unicode normalization form, or `None` to leave the text as is.
+    form: Option<Form>,
+    lowercase: bool,
+    casefold: bool,
+    /// Casefolding rules for `locale`.
+    fold: CaseFold,
+    punctuation: Punctuation,
+    remove_punctuation: bool,
+    remove_control: bool,
+    normalize_spaces: bool,
+    collapse_whitespace: bool,
+    strip: bool,
+    strip_html: bool,
+    strip_markdown: bool,
+    /// Characters outside these scripts are dropped or replaced.
+    scripts: Option<ScriptFilter>,
+    emoji: EmojiStage,
+    numbers: NumberStage,
+}
+
+impl Normalizer {
+    /// Apply the enabled steps in order: Markdown and HTML stripping, emoji,
+    /// number and date handling, control character removal, space
+    /// normalization, Unicode normalization, script filtering, lowercasing
+    /// or casefolding, punctuation removal, whitespace collapsing and
+    /// stripping. What emoji, number and date handling write passes through
+    /// the steps before whitespace collapsing untouched.
+    pub fn apply(&self, text: &str) -> String {
+        if self.emoji.is_active() || self.numbers.is_active() {
+            return self.apply_aligned(text).text;
+        }
+        let mut out = if self.strip_markdown {
+            strip_markdown(text)
+        } else if self.strip_html {
+            strip_html(text)
+        } else {
+            text.to_string()
+        };
+        if self.remove_control {
+            out.retain(|c| !is_control(c));
+        }
+        if self.normalize_spaces {
+            out = normalize_spaces(&out).into_owned();
+        }
+        if let Some(form) = self.form {
+            out = form.apply(&out).into_owned();
+        }
+        if let Some(scripts) = &self.scripts {
+            out = scripts.apply(&out).into_owned();
+        }
+        if self.casefold {
+            out = self.fold.apply(&out);
+        } else if self.lowercase {
+            out = out.to_lowercase();
+        }
+        if self.remove_punctuation {
+            out.retain(|c| !self.punctuation.is_removed(c));
+        }
+        if self.collapse_whitespace {
+            out = collapse(&out, false).into_owned();
+        }
+        if self.strip {
+            out = out.trim().to_string();
+        }
+        out
+    }
+
+    /// `apply`, remembering the span of `text` that every character of the
+    /// result was produced from.
+    pub fn apply_aligned(&self, text: &str) -> AlignedString {
+        let mut out = if self.strip_markdown {
+            strip_markdown_aligned(text)
+        } else if self.strip_html {
+            strip_html_aligned(text)
+        } else {
+            AlignedString::new(text, 0)
+        };
+        if self.emoji.is_active() || self.numbers.is_active() {
+            let replacements = merge_disjoint(
+                self.emoji.replacements(&out.text),
+                self.numbers.replacements(&out.text),
+                |r| (r.start, r.end),
+            );
+            out = out.replace(&replacements, |piece| self.transform_aligned(piece));
+        } else {
+            out = self.transform_aligned(out);
+        }
+        if self.collapse_whitespace {
+            out = collapse_aligned(&out);
+        }
+        if self.strip {
+            let start = out.text.len() - out.text.trim_start().len();
+            let end = out.text.trim_end().len().max(start);
+            out = out.slice(start, end);
+        }
+        out
+    }
+
+    /// The steps of `apply_aligned` that work a character at a time, from
+    /// control character removal to punctuation removal.
+    fn transform_aligned(&self, mut out: AlignedString) -> AlignedString {
+        if self.remove_control {
+            out = out.map_chars(|c, s| {
+                if !is_control(c) {
+                    s.push(c)
+                }
+            });
+        }
+        if self.normalize_spaces {
+            out = out.map_chars(|c, s| s.push(if is_unicode_space(c) { ' ' } else { c }));
+        }
+        if let Some(form) = self.form {
+            if let Cow::Owned(_) = form.apply(&out.text) {
+                out = out.map_graphemes(|g, s| s.push_str(&form.apply(g)));
+            }
+        }
+        if let Some(scripts) = &self.scripts {
+            out = out.map_chars(|c, s| scripts.push(c, s));
+        }
+        if self.casefold {
+            out = out.map_chars(|c, s| self.fold.push(c, s));
+        } else if self.lowercase {
+            // Only a final sigma lowercases differently in context, and both
+            // forms of sigma have the same length.
+            let lowered = out.text.to_lowercase();
+            out = out.map_chars(|c, s| s.extend(c.to_lowercase()));
+            out.text = lowered;
+        }
+        if self.remove_punctuation {
+            out = out.map_chars(|c, s| {
+                if !self.punctuation.is_removed(c) {
+                    s.push(c)
+                }
+            });
+        }
+        out
+    }
+
+    /// The spans of normalized text that pipelines emit as single tokens:
+    /// kept emoji and emoticons, and what the number and date step wrote.
+    pub fn atomic_spans(&self, normalized: &str) -> Vec<(usize, usize)> {
+        merge_disjoint(
+            self.emoji.kept(normalized),
+            self.numbers.atoms(normalized),
+            |&span| span,
+        )
+    }
+
+    fn resolve(&self, form: Option<&str>) -> PyResult<Form> {
+        match form {
+            Some(form) => Form::parse(form).map_err(py_err),
+            None => Ok(self.form.unwrap_or_default()),
+        }
+    }
+}
 
 #[pymethods]
 impl Normalizer {
+    /// Create a normalizer.
+    ///
+    /// Args:
+    ///     lowercase (bool): Lowercase the text.
+    ///     remove_punctuation (bool): Remove punctuation.
+    ///     normalize_unicode (bool): Apply Unicode normalization (NFC unless
+    ///         `form` says otherwise).
+    ///     form (str, optional): `"NFC"`, `"NFD"`, `"NFKC"` or `"NFKD"`;
+    ///         implies `normalize_unicode`.
+    ///     casefold (bool): Casefold instead of lowercasing; see `casefold`.
+    ///     locale (str, optional): Locale for casefolding, e.g. `"tr"`.
+    ///     unicode_punctuation (bool): Remove all Unicode punctuation rather
+    ///         than ASCII punctuation only.
+    ///     keep_punctuation (str, optional): Punctuation characters to keep.
+    ///     remove_control (bool): Remove control and zero-width characters;
+    ///         see `remove_control_chars`.
+    ///     normalize_spaces (bool): Replace Unicode spaces with ASCII spaces.
+    ///     collapse_whitespace (bool): Replace whitespace runs with one space.
+    ///     strip (bool): Trim leading and trailing whitespace.
+    ///     strip_html (bool): Remove HTML tags and decode entities first.
+    ///     strip_markdown (bool): Remove Markdown syntax (and embedded HTML)
+    ///         first.
+    ///     allowed_scripts (List[str], optional): Drop characters outside
+    ///         these scripts; see `filter_scripts`.
+    ///     script_replacement (str, optional): Replace each dropped character
+    ///         with this instead.
+    ///     emoji (str, optional): `"keep"` to set emoji sequences apart as
+    ///         tokens of their own and shield them from the other steps,
+    ///         `"strip"` or `"shortcode"`; see `normalize_emoji`.
+    ///     emoticons (str, optional): The same, for emoticons such as `:-)`.
+    ///     numbers (str, optional): `"canonical"` to drop thousands
+    ///         separators and use a decimal point, or `"placeholder"` for
+    ///         `<NUM>`; see `normalize_numbers`.
+    ///     dates (str, optional): `"canonical"` for `YYYY-MM-DD`, or
+    ///         `"placeholder"` for `<DATE>`.
+    ///     decimal_comma (bool): Numbers are written `1.234,5`.
+    ///     day_first (bool): Numeric dates are written day first.
     #[new]
-    fn new() -> Self {
-        Normalizer {}
+    #[pyo3(signature = (
+        lowercase=false,
+        remove_punctuation=false,
+        normalize_unicode=false,
+        form=None,
+        casefold=false,
+        locale=None,
+        unicode_punctuation=false,
+        keep_punctuation=None,
+        remove_control=false,
+        normalize_spaces=false,
+        collapse_whitespace=false,
+        strip=false,
+        strip_html=false,
+        strip_markdown=false,
+        allowed_scripts=None,
+        script_replacement=None,
+        emoji=None,
+        emoticons=None,
+        numbers=None,
+        dates=None,
+        decimal_comma=false,
+        day_first=false
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        lowercase: bool,
+        remove_punctuation: bool,
+        normalize_unicode: bool,
+        form: Option<&str>,
+        casefold: bool,
+        locale: Option<&str>,
+        unicode_punctuation: bool,
+        keep_punctuation: Option<&str>,
+        remove_control: bool,
+        normalize_spaces: bool,
+        collapse_whitespace: bool,
+        strip: bool,
+        strip_html: bool,
+        strip_markdown: bool,
+        allowed_scripts: Option<Vec<String>>,
+        script_replacement: Option<&str>,
+        emoji: Option<&str>,
+        emoticons: Option<&str>,
+        numbers: Option<&str>,
+        dates: Option<&str>,
+        decimal_comma: bool,
+        day_first: bool,
+    ) -> PyResult<Self> {
+        let form = match form {
+            Some(form) => Some(Form::parse(form).map_err(py_err)?),
+            None => normalize_unicode.then_some(Form::Nfc),
+        };
+        Ok(Normalizer {
+            form,
+            lowercase,
+            casefold,
+            fold: CaseFold::parse(locale)?,
+            punctuation: Punctuation::new(unicode_punctuation, keep_punctuation),
+            remove_punctuation,
+            remove_control,
+            normalize_spaces,
+            collapse_whitespace,
+            strip,
+            strip_html,
+            strip_markdown,
+            scripts: allowed_scripts
+                .map(|allowed| ScriptFilter::new(&allowed, script_replacement))
+                .transpose()?,
+            emoji: EmojiStage {
+                emoji: emoji.map(EmojiMode::parse).transpose()?,
+                emoticons: emoticons.map(EmojiMode::parse).transpose()?,
+            },
+            numbers: NumberStage {
+                numbers: numbers.map(NumberMode::parse).transpose()?,
+                dates: dates.map(NumberMode::parse).transpose()?,
+                decimal_comma,
+                day_first,
+            },
+        })
+    }
+
+    /// Constructor arguments recreating this normalizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("lowercase", self.lowercase)?;
+        kwargs.set_item("remove_punctuation", self.remove_punctuation)?;
+        kwargs.set_item("form", self.form.map(Form::name))?;
+        kwargs.set_item("casefold", self.casefold)?;
+        let locale = (self.fold == CaseFold::Turkic).then_some("tr");
+        kwargs.set_item("locale", locale)?;
+        kwargs.set_item("unicode_punctuation", self.punctuation.unicode)?;
+        let keep: String = self.punctuation.keep.iter().collect();
+        kwargs.set_item("keep_punctuation", keep)?;
+        kwargs.set_item("remove_control", self.remove_control)?;
+        kwargs.set_item("normalize_spaces", self.normalize_spaces)?;
+        kwargs.set_item("collapse_whitespace", self.collapse_whitespace)?;
+        kwargs.set_item("strip", self.strip)?;
+        kwargs.set_item("strip_html", self.strip_html)?;
+        kwargs.set_item("strip_markdown", self.strip_markdown)?;
+        if let Some(scripts) = &self.scripts {
+            kwargs.set_item("allowed_scripts", &scripts.names)?;
+            kwargs.set_item("script_replacement", &scripts.replacement)?;
+        }
+        kwargs.set_item("emoji", self.emoji.emoji.map(EmojiMode::name))?;
+        kwargs.set_item("emoticons", self.emoji.emoticons.map(EmojiMode::name))?;
+        kwargs.set_item("numbers", self.numbers.numbers.map(NumberMode::name))?;
+        kwargs.set_item("dates", self.numbers.dates.map(NumberMode::name))?;
+        kwargs.set_item("decimal_comma", self.numbers.decimal_comma)?;
+        kwargs.set_item("day_first", self.numbers.day_first)?;
+        Ok(((), kwargs))
+    }
+
+    /// The Unicode normalization form applied by `normalize`, if any.
+    #[getter]
+    fn form(&self) -> Option<&'static str> {
+        self.form.map(Form::name)
+    }
+
+    /// Apply every step enabled in the constructor.
+    fn normalize(&self, text: &str) -> PyResult<String> {
+        Ok(self.apply(text))
+    }
+
+    /// Apply every step enabled in the constructor, keeping track of where
+    /// each character of the result came from in `text`.
+    ///
+    /// Returns:
+    ///     Alignment: The normalized text and its mapping back to `text`.
+    fn normalize_with_alignment(&self, text: &str) -> Alignment {
+        Alignment::new(text, self.apply_aligned(text))
     }
 
     fn lowercase(&self, text: &str) -> PyResult<String> {
         Ok(text.to_lowercase())
     }
 
+    /// Remove punctuation as configured by `unicode_punctuation` and
+    /// `keep_punctuation`.
     fn remove_punctuation(&self, text: &str) -> PyResult<String> {
-        Ok(text
-            .chars()
-            .filter(|c| !c.is_ascii_punctuation())
-            .collect::<String>())
+        Ok(self.punctuation.remove(text).into_owned())
+    }
+
+    /// Casefold `text`, using this normalizer's locale.
+    fn casefold(&self, text: &str) -> PyResult<String> {
+        Ok(self.fold.apply(text))
+    }
+
+    /// Normalize `text` to `form`, defaulting to this normalizer's form or
+    /// NFC. Unlike `normalize`, this never changes case.
+    #[pyo3(signature = (text, form=None))]
+    fn normalize_unicode(&self, text: &str, form: Option<&str>) -> PyResult<String> {
+        Ok(self.resolve(form)?.apply(text).into_owned())
     }
 
-    fn normalize_unicode(&self, text: &str) -> PyResult<String> {
-        Ok(text.to_lowercase().nfc().collect::<String>())
+    /// Check whether `text` is already in `form`, defaulting to this
+    /// normalizer's form or NFC.
+    #[pyo3(signature = (text, form=None))]
+    fn is_normalized(&self, text: &str, form: Option<&str>) -> PyResult<bool> {
+        Ok(self.resolve(form)?.is_normalized(text))
     }
 }
@@ -0,0 +1,362 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+use crate::aligned::{merge_disjoint, AlignedString, Replacement};
+
+pub const NUMBER_PLACEHOLDER: &str = "<NUM>";
+pub const DATE_PLACEHOLDER: &str = "<DATE>";
+
+/// Digit runs joined by separators; which of them are numbers is decided
+/// by `canonical_number`.
+const NUMBER_PATTERN: &str = r"[0-9]+(?:[.,'’\x{A0}\x{202F}][0-9]+)*";
+
+const MONTHS: &str =
+    "january|february|march|april|may|june|july|august|september|october|november|december\
+    |jan|feb|mar|apr|jun|jul|aug|sept|sep|oct|nov|dec";
+
+/// Year first (`2024-03-05`), all numeric (`3/5/24`, `05.03.2024`), day
+/// before month name (`5th March 2024`) and month name before day
+/// (`March 5, 2024`).
+fn date_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        let pattern = format!(
+            concat!(
+                r"(?P<iy>[0-9]{{4}})(?P<s1>[-/.])(?P<im>[0-9]{{1,2}})(?P<s2>[-/.])(?P<id>[0-9]{{1,2}})",
+                r"|(?P<na>[0-9]{{1,2}})(?P<s3>[-/.])(?P<nb>[0-9]{{1,2}})(?P<s4>[-/.])(?P<ny>[0-9]{{4}}|[0-9]{{2}})",
+                r"|(?i:(?P<dd>[0-9]{{1,2}})(?:st|nd|rd|th)?\s+(?:of\s+)?(?P<dm>{months})\.?,?\s+(?P<dy>[0-9]{{4}}))",
+                r"|(?i:(?P<mm>{months})\.?\s+(?P<md>[0-9]{{1,2}})(?:st|nd|rd|th)?,?\s+(?P<my>[0-9]{{4}}))",
+            ),
+            months = MONTHS
+        );
+        Regex::new(&pattern).expect("valid date pattern")
+    })
+}
+
+/// Dates in the canonical form only.
+fn iso_date_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?P<iy>[0-9]{4})-(?P<im>[0-9]{2})-(?P<id>[0-9]{2})")
+            .expect("valid date pattern")
+    })
+}
+
+fn number_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(NUMBER_PATTERN).expect("valid number pattern"))
+}
+
+/// What the normalizer does with numbers or dates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberMode {
+    /// Rewrite them in a standard form: numbers without thousands
+    /// separators and with a decimal point, dates as `YYYY-MM-DD`.
+    Canonical,
+    /// Replace them with `<NUM>` or `<DATE>`.
+    Placeholder,
+}
+
+impl NumberMode {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "canonical" => Ok(NumberMode::Canonical),
+            "placeholder" => Ok(NumberMode::Placeholder),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown number mode '{}', expected 'canonical' or 'placeholder'",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NumberMode::Canonical => "canonical",
+            NumberMode::Placeholder => "placeholder",
+        }
+    }
+}
+
+/// Whether `text[start..end]` is glued to a letter or digit on either side,
+/// as in `v2`, `A4` or `10km`.
+fn touches_word(text: &str, start: usize, end: usize) -> bool {
+    text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(char::is_alphanumeric)
+        || text[end..]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric)
+}
+
+/// The month numbered by a name or its abbreviation.
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.to_ascii_lowercase();
+    [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ]
+    .iter()
+    .position(|prefix| name.starts_with(prefix))
+    .map(|i| i as u32 + 1)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// `(year, month, day)` when that is a real date; two-digit years are read
+/// as 1969 to 2068, like POSIX `strptime`.
+fn valid_date(year: &str, month: u32, day: u32) -> Option<(u32, u32, u32)> {
+    let mut year: u32 = year.parse().ok()?;
+    if year < 100 {
+        year += if year < 69 { 2000 } else { 1900 };
+    }
+    ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+        .then_some((year, month, day))
+}
+
+fn placeholders(text: &str, placeholder: &str) -> Vec<(usize, usize)> {
+    text.match_indices(placeholder)
+        .map(|(start, found)| (start, start + found.len()))
+        .collect()
+}
+
+fn spans<T>(found: Vec<(usize, usize, T)>) -> Vec<(usize, usize)> {
+    found
+        .into_iter()
+        .map(|(start, end, _)| (start, end))
+        .collect()
+}
+
+/// Sorted, non-overlapping spans of `text`, found by `regex` and turned
+/// into values by `parse`, skipping matches glued to a word.
+fn scan<T>(
+    text: &str,
+    regex: &Regex,
+    mut parse: impl FnMut(&Captures<'_>) -> Option<T>,
+) -> Vec<(usize, usize, T)> {
+    regex
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let m = caps.get(0)?;
+            if touches_word(text, m.start(), m.end()) {
+                return None;
+            }
+            parse(&caps).map(|value| (m.start(), m.end(), value))
+        })
+        .collect()
+}
+
+/// How the number and date step reads and rewrites text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NumberStage {
+    pub numbers: Option<NumberMode>,
+    pub dates: Option<NumberMode>,
+    /// Numbers are written `1.234,5` rather than `1,234.5`.
+    pub decimal_comma: bool,
+    /// Numeric dates are written day first, as in `05/03/2024`.
+    pub day_first: bool,
+}
+
+impl NumberStage {
+    pub fn is_active(&self) -> bool {
+        self.numbers.is_some() || self.dates.is_some()
+    }
+
+    /// `number` without thousands separators and with a decimal point, or
+    /// `None` when it is not a well-formed number, like `1.2.3` or `12,34`.
+    fn canonical_number(&self, number: &str) -> Option<String> {
+        let decimal = if self.decimal_comma { ',' } else { '.' };
+        let (integer, fraction) = match number.split_once(decimal) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+        if fraction.is_some_and(|f| !f.bytes().all(|b| b.is_ascii_digit())) {
+            return None;
+        }
+        let mut separators = integer.matches(|c: char| !c.is_ascii_digit());
+        let mut canonical: String = match separators.next() {
+            None => integer.to_string(),
+            Some(separator) => {
+                let mut groups = integer.split(separator);
+                let first = groups.next()?;
+                let rest: Vec<&str> = groups.collect();
+                if first.is_empty()
+                    || first.len() > 3
+                    || separators.any(|other| other != separator)
+                    || rest.iter().any(|group| group.len() != 3)
+                {
+                    return None;
+                }
+                std::iter::once(first).chain(rest).collect()
+            }
+        };
+        if let Some(fraction) = fraction {
+            canonical.push('.');
+            canonical.push_str(fraction);
+        }
+        Some(canonical)
+    }
+
+    /// The date a match of `date_regex` stands for, trying the other order
+    /// of day and month for numeric dates when the configured one is not a
+    /// real date (`25/12/2024` in month-first text).
+    fn parse_date(&self, caps: &Captures<'_>) -> Option<(u32, u32, u32)> {
+        let number = |name: &str| caps.name(name)?.as_str().parse::<u32>().ok();
+        // Both separators of a numeric date must be the same.
+        let same =
+            |a: &str, b: &str| caps.name(a).map(|m| m.as_str()) == caps.name(b).map(|m| m.as_str());
+        if let Some(year) = caps.name("iy") {
+            if !same("s1", "s2") {
+                return None;
+            }
+            return valid_date(year.as_str(), number("im")?, number("id")?);
+        }
+        if let Some(year) = caps.name("ny") {
+            if !same("s3", "s4") {
+                return None;
+            }
+            let (a, b) = (number("na")?, number("nb")?);
+            let (month, day) = if self.day_first { (b, a) } else { (a, b) };
+            return valid_date(year.as_str(), month, day)
+                .or_else(|| valid_date(year.as_str(), day, month));
+        }
+        if let Some(year) = caps.name("dy") {
+            return valid_date(
+                year.as_str(),
+                month_number(caps.name("dm")?.as_str())?,
+                number("dd")?,
+            );
+        }
+        valid_date(
+            caps.name("my")?.as_str(),
+            month_number(caps.name("mm")?.as_str())?,
+            number("md")?,
+        )
+    }
+
+    /// What every date and number of `text` becomes.
+    pub fn replacements(&self, text: &str) -> Vec<Replacement> {
+        let dates = match self.dates {
+            Some(mode) => scan(text, date_regex(), |caps| self.parse_date(caps))
+                .into_iter()
+                .map(|(start, end, (year, month, day))| Replacement {
+                    start,
+                    end,
+                    text: match mode {
+                        NumberMode::Canonical => format!("{:04}-{:02}-{:02}", year, month, day),
+                        NumberMode::Placeholder => DATE_PLACEHOLDER.to_string(),
+                    },
+                    apart: false,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let numbers = match self.numbers {
+            Some(mode) => scan(text, number_regex(), |caps| self.canonical_number(&caps[0]))
+                .into_iter()
+                .map(|(start, end, canonical)| Replacement {
+                    start,
+                    end,
+                    text: match mode {
+                        NumberMode::Canonical => canonical,
+                        NumberMode::Placeholder => NUMBER_PLACEHOLDER.to_string(),
+                    },
+                    apart: false,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        merge_disjoint(dates, numbers, |r| (r.start, r.end))
+    }
+
+    /// The spans of normalized text that this step produced, for pipelines
+    /// to emit as single tokens: placeholders, or canonical dates and
+    /// numbers.
+    pub fn atoms(&self, normalized: &str) -> Vec<(usize, usize)> {
+        let canonical = NumberStage {
+            decimal_comma: false,
+            ..*self
+        };
+        let dates = match self.dates {
+            Some(NumberMode::Placeholder) => placeholders(normalized, DATE_PLACEHOLDER),
+            Some(NumberMode::Canonical) => spans(scan(normalized, iso_date_regex(), |caps| {
+                canonical.parse_date(caps)
+            })),
+            None => Vec::new(),
+        };
+        let numbers = match self.numbers {
+            Some(NumberMode::Placeholder) => placeholders(normalized, NUMBER_PLACEHOLDER),
+            Some(NumberMode::Canonical) => spans(scan(normalized, number_regex(), |caps| {
+                canonical.canonical_number(&caps[0])
+            })),
+            None => Vec::new(),
+        };
+        merge_disjoint(dates, numbers, |&span| span)
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        AlignedString::new(text, 0)
+            .replace(&self.replacements(text), |piece| piece)
+            .text
+    }
+}
+
+/// Rewrite numbers and dates in a standard form, or replace them with
+/// placeholder tokens.
+///
+/// Numbers lose their thousands separators (commas, apostrophes or
+/// no-break spaces) and get a decimal point: `1,234,567.5` becomes
+/// `1234567.5`, or with `decimal_comma=True`, `1.234.567,5` does. Numbers
+/// that are not well formed in the chosen convention, like version numbers
+/// (`1.2.3`), and numbers glued to letters (`A4`, `10km`) are left alone.
+///
+/// Dates become `YYYY-MM-DD`. Recognized forms are year first
+/// (`2024-03-05`, `2024/3/5`), all numeric (`3/5/2024`, `05.03.24`) and
+/// with English month names (`March 5, 2024`, `5th Mar 2024`). Numeric
+/// dates are read month first unless `day_first` is set, falling back to
+/// the other order when the first is not a real date. Two-digit years are
+/// read as 1969 to 2068.
+///
+/// Args:
+///     text (str): Input text.
+///     numbers (str, optional): `"canonical"`, `"placeholder"` for `<NUM>`,
+///         or `None` to leave numbers alone.
+///     dates (str, optional): `"canonical"`, `"placeholder"` for `<DATE>`,
+///         or `None` to leave dates alone.
+///     decimal_comma (bool): Read `,` as the decimal separator and `.` as
+///         a thousands separator.
+///     day_first (bool): Read numeric dates as day, month, year.
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    numbers=Some("canonical"),
+    dates=Some("canonical"),
+    decimal_comma=false,
+    day_first=false
+))]
+pub fn normalize_numbers(
+    text: &str,
+    numbers: Option<&str>,
+    dates: Option<&str>,
+    decimal_comma: bool,
+    day_first: bool,
+) -> PyResult<String> {
+    let stage = NumberStage {
+        numbers: numbers.map(NumberMode::parse).transpose()?,
+        dates: dates.map(NumberMode::parse).transpose()?,
+        decimal_comma,
+        day_first,
+    };
+    Ok(stage.apply(text))
+}
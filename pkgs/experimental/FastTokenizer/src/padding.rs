@@ -0,0 +1,322 @@
+use numpy::ndarray::Array2;
+use numpy::{Element, IntoPyArray, PyArray2};
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// How a sequence (pair) is shortened to fit `max_length`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// Remove tokens one at a time from whichever sequence is longer.
+    LongestFirst,
+    OnlyFirst,
+    OnlySecond,
+}
+
+impl Truncation {
+    /// Parse a strategy name; `None` and `"do_not_truncate"` disable truncation.
+    pub fn parse(name: Option<&str>) -> PyResult<Option<Self>> {
+        match name {
+            None | Some("do_not_truncate") => Ok(None),
+            Some("longest_first") => Ok(Some(Truncation::LongestFirst)),
+            Some("only_first") => Ok(Some(Truncation::OnlyFirst)),
+            Some("only_second") => Ok(Some(Truncation::OnlySecond)),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Unknown truncation strategy '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Target length of padded batches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    /// Pad to the longest sequence in the batch.
+    Longest,
+    /// Pad to `max_length`.
+    MaxLength,
+}
+
+impl Padding {
+    /// Parse a padding name; `None` and `"do_not_pad"` disable padding.
+    pub fn parse(name: Option<&str>) -> PyResult<Option<Self>> {
+        match name {
+            None | Some("do_not_pad") => Ok(None),
+            Some("longest") => Ok(Some(Padding::Longest)),
+            Some("max_length") => Ok(Some(Padding::MaxLength)),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Unknown padding strategy '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// A batch input: a single text or a `(text, pair)` tuple.
+#[derive(FromPyObject)]
+pub enum BatchInput {
+    Single(String),
+    Pair(String, String),
+}
+
+impl BatchInput {
+    pub fn parts(&self) -> (&str, Option<&str>) {
+        match self {
+            BatchInput::Single(text) => (text, None),
+            BatchInput::Pair(text, pair) => (text, Some(pair)),
+        }
+    }
+}
+
+/// Shorten `a` and `b` so that together they hold at most `budget` items.
+pub fn truncate<T>(
+    a: &mut Vec<T>,
+    b: Option<&mut Vec<T>>,
+    budget: usize,
+    strategy: Truncation,
+) -> Result<(), String> {
+    let mut empty = Vec::new();
+    let b = b.unwrap_or(&mut empty);
+    let total = a.len() + b.len();
+    if total <= budget {
+        return Ok(());
+    }
+    match strategy {
+        Truncation::LongestFirst => {
+            let excess = total - budget;
+            for _ in 0..excess {
+                if a.len() > b.len() {
+                    a.pop();
+                } else {
+                    b.pop();
+                }
+            }
+        }
+        Truncation::OnlyFirst | Truncation::OnlySecond => {
+            let (target, other) = match strategy {
+                Truncation::OnlyFirst => (a, b.len()),
+                _ => (b, a.len()),
+            };
+            if other > budget {
+                return Err(format!(
+                    "Cannot truncate to {} tokens with strategy {:?}: the other sequence is too long",
+                    budget, strategy
+                ));
+            }
+            target.truncate(budget - other);
+        }
+    }
+    Ok(())
+}
+
+/// Ids of overlapping windows over a long text, with each id's offsets.
+pub type Windows = Vec<(Vec<u32>, Vec<(usize, usize)>)>;
+
+/// Split `items` into windows of at most `size`, where each window repeats
+/// the last `stride` items of the one before. There is always at least one
+/// window, even for no items.
+pub fn windows<T>(items: &[T], size: usize, stride: usize) -> Result<Vec<&[T]>, String> {
+    if size == 0 {
+        return Err("max_length leaves no room for tokens besides the special tokens".to_string());
+    }
+    if stride >= size {
+        return Err(format!(
+            "stride ({}) must be smaller than the {} tokens that fit in a window",
+            stride, size
+        ));
+    }
+    let mut windows = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + size).min(items.len());
+        windows.push(&items[start..end]);
+        if end == items.len() {
+            return Ok(windows);
+        }
+        start = end - stride;
+    }
+}
+
+/// Ids padded to a common length together with their attention masks.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug, Default)]
+pub struct BatchEncoding {
+    #[pyo3(get)]
+    pub input_ids: Vec<Vec<u32>>,
+    #[pyo3(get)]
+    pub attention_mask: Vec<Vec<u32>>,
+}
+
+impl BatchEncoding {
+    /// Pad `ids` with `pad_id` according to `padding`, on the left when
+    /// `left` is set.
+    pub fn pad(
+        ids: Vec<Vec<u32>>,
+        padding: Option<Padding>,
+        max_length: Option<usize>,
+        pad_id: Option<u32>,
+        left: bool,
+    ) -> PyResult<Self> {
+        let target = match padding {
+            None => 0,
+            Some(Padding::Longest) => ids.iter().map(Vec::len).max().unwrap_or(0),
+            Some(Padding::MaxLength) => max_length
+                .ok_or_else(|| PyValueError::new_err("padding='max_length' requires max_length"))?,
+        };
+        let needs_padding = ids.iter().any(|seq| seq.len() < target);
+        let pad_id = match pad_id {
+            Some(id) => id,
+            None if needs_padding => {
+                return Err(PyValueError::new_err(
+                    "Padding requires a pad token; pass pad_id or register one",
+                ))
+            }
+            None => 0,
+        };
+        let mut batch = BatchEncoding::default();
+        for mut seq in ids {
+            let missing = target.saturating_sub(seq.len());
+            let mut mask = vec![1; seq.len()];
+            if left {
+                seq.splice(0..0, std::iter::repeat_n(pad_id, missing));
+                mask.splice(0..0, std::iter::repeat_n(0, missing));
+            } else {
+                seq.resize(seq.len() + missing, pad_id);
+                mask.resize(mask.len() + missing, 0);
+            }
+            batch.input_ids.push(seq);
+            batch.attention_mask.push(mask);
+        }
+        Ok(batch)
+    }
+
+    /// Both fields as 2-D NumPy arrays, built without intermediate lists.
+    pub fn to_arrays<'py>(&self, py: Python<'py>, dtype: &str) -> PyResult<Bound<'py, PyDict>> {
+        let arrays = PyDict::new(py);
+        match dtype {
+            "int64" => {
+                arrays.set_item("input_ids", to_array(py, &self.input_ids, i64::from)?)?;
+                arrays.set_item(
+                    "attention_mask",
+                    to_array(py, &self.attention_mask, i64::from)?,
+                )?;
+            }
+            "int32" => {
+                arrays.set_item("input_ids", to_array(py, &self.input_ids, |id| id as i32)?)?;
+                arrays.set_item(
+                    "attention_mask",
+                    to_array(py, &self.attention_mask, |m| m as i32)?,
+                )?;
+            }
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported dtype '{}', expected 'int32' or 'int64'",
+                    other
+                )))
+            }
+        }
+        Ok(arrays)
+    }
+}
+
+fn to_array<'py, T: Element>(
+    py: Python<'py>,
+    rows: &[Vec<u32>],
+    cast: impl Fn(u32) -> T,
+) -> PyResult<Bound<'py, PyArray2<T>>> {
+    let width = rows.first().map_or(0, Vec::len);
+    if rows.iter().any(|row| row.len() != width) {
+        return Err(PyValueError::new_err(
+            "Sequences have different lengths; pass padding to get arrays",
+        ));
+    }
+    let flat = rows.iter().flatten().map(|&v| cast(v)).collect();
+    let array = Array2::from_shape_vec((rows.len(), width), flat)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(array.into_pyarray(py))
+}
+
+#[pymethods]
+impl BatchEncoding {
+    fn keys(&self) -> Vec<&'static str> {
+        vec!["input_ids", "attention_mask"]
+    }
+
+    fn __getitem__(&self, key: &str) -> PyResult<Vec<Vec<u32>>> {
+        match key {
+            "input_ids" => Ok(self.input_ids.clone()),
+            "attention_mask" => Ok(self.attention_mask.clone()),
+            _ => Err(PyKeyError::new_err(key.to_string())),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.input_ids.len()
+    }
+
+    /// Convert to `{"input_ids": ndarray, "attention_mask": ndarray}`.
+    ///
+    /// Args:
+    ///     dtype (str): `"int64"` or `"int32"`.
+    #[pyo3(signature = (dtype="int64"))]
+    fn to_numpy<'py>(&self, py: Python<'py>, dtype: &str) -> PyResult<Bound<'py, PyDict>> {
+        self.to_arrays(py, dtype)
+    }
+}
+
+/// Either plain id lists or, when padding or truncation was requested, a
+/// `BatchEncoding` with attention masks, or NumPy arrays of both.
+#[derive(IntoPyObject)]
+pub enum BatchOutput<'py> {
+    Ids(Vec<Vec<u32>>),
+    Encoding(BatchEncoding),
+    Arrays(Bound<'py, PyDict>),
+}
+
+impl<'py> BatchOutput<'py> {
+    /// Assemble the result of `encode_batch` from the encoded ids.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        py: Python<'py>,
+        ids: Vec<Vec<u32>>,
+        max_length: Option<usize>,
+        padding: Option<Padding>,
+        pad_id: impl FnOnce() -> PyResult<Option<u32>>,
+        padding_side: &str,
+        return_tensors: Option<&str>,
+        dtype: &str,
+    ) -> PyResult<Self> {
+        let numpy = match return_tensors {
+            None => false,
+            Some("np") => true,
+            Some(other) => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported return_tensors '{}', expected 'np'",
+                    other
+                )))
+            }
+        };
+        if max_length.is_none() && padding.is_none() && !numpy {
+            return Ok(BatchOutput::Ids(ids));
+        }
+        let left = pad_left(padding_side)?;
+        let batch = BatchEncoding::pad(ids, padding, max_length, pad_id()?, left)?;
+        if numpy {
+            return batch.to_arrays(py, dtype).map(BatchOutput::Arrays);
+        }
+        Ok(BatchOutput::Encoding(batch))
+    }
+}
+
+/// Parse the `padding_side` argument.
+fn pad_left(side: &str) -> PyResult<bool> {
+    match side {
+        "right" => Ok(false),
+        "left" => Ok(true),
+        other => Err(PyValueError::new_err(format!(
+            "Unknown padding side '{}', expected 'left' or 'right'",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,206 @@
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::tokenizer::Token;
+
+/// A phrase given as text split on whitespace, or as its tokens.
+#[derive(FromPyObject)]
+pub enum Phrase {
+    Text(String),
+    Tokens(Vec<String>),
+}
+
+/// Merges multi-word expressions such as "New York" or "machine learning"
+/// into single tokens, for phrase-aware indexing.
+///
+/// Tokens are mapped to symbols and the phrases found with Aho-Corasick
+/// over the token stream, so the cost does not grow with the number of
+/// phrases. Where phrases overlap the longest one starting first wins. A
+/// merged token spans from the start of its first token to the end of its
+/// last, and can be passed to `TokenizerPipeline(phrases=...)` to run
+/// after tokenization and before the filters.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct PhraseMerger {
+    phrases: Arc<[Vec<String>]>,
+    separator: String,
+    case_sensitive: bool,
+    /// Symbol of every token that occurs in a phrase, keyed lowercased
+    /// unless matching is case sensitive.
+    symbols: Arc<HashMap<String, char>>,
+    matcher: AhoCorasick,
+}
+
+/// The symbol standing for the `n`th distinct phrase token; `'\0'` stands
+/// for every other token.
+fn symbol(n: usize) -> Option<char> {
+    let code = u32::try_from(n + 1).ok()?;
+    // Skip the surrogates, which are not chars.
+    char::from_u32(if code < 0xD800 { code } else { code + 0x800 })
+}
+
+/// `token` as it is looked up: lowercased unless `case_sensitive`.
+fn key(token: &str, case_sensitive: bool) -> Cow<'_, str> {
+    if case_sensitive || !token.chars().any(char::is_uppercase) {
+        Cow::Borrowed(token)
+    } else {
+        Cow::Owned(token.to_lowercase())
+    }
+}
+
+impl PhraseMerger {
+    /// `tokens` with every phrase replaced by one token joining them with
+    /// the separator.
+    pub fn merge(&self, tokens: Vec<Token>) -> Vec<Token> {
+        if tokens.len() < 2 {
+            return tokens;
+        }
+        // Symbols are UTF-8 encoded, so matches always start and end at the
+        // boundary between two tokens.
+        let mut haystack = String::with_capacity(tokens.len());
+        let mut starts = Vec::with_capacity(tokens.len() + 1);
+        for token in &tokens {
+            starts.push(haystack.len());
+            let key = key(&token.text, self.case_sensitive);
+            haystack.push(self.symbols.get(key.as_ref()).copied().unwrap_or('\0'));
+        }
+        starts.push(haystack.len());
+        let index = |byte: usize| starts.binary_search(&byte).expect("token boundary");
+        let mut merged = Vec::with_capacity(tokens.len());
+        let mut tokens = tokens.into_iter();
+        let mut pos = 0;
+        for m in self.matcher.find_iter(&haystack) {
+            let (first, last) = (index(m.start()), index(m.end()));
+            merged.extend(tokens.by_ref().take(first - pos));
+            let phrase: Vec<Token> = tokens.by_ref().take(last - first).collect();
+            let text = phrase
+                .iter()
+                .map(|t| t.text.as_str())
+                .collect::<Vec<_>>()
+                .join(&self.separator);
+            merged.push(Token::new(
+                text,
+                phrase[0].start,
+                phrase[phrase.len() - 1].end,
+            ));
+            pos = last;
+        }
+        merged.extend(tokens);
+        merged
+    }
+}
+
+#[pymethods]
+impl PhraseMerger {
+    /// Create a phrase merger.
+    ///
+    /// Args:
+    ///     phrases (List[str | List[str]]): The expressions to merge, as text
+    ///         split on whitespace or as the tokens the tokenizer produces,
+    ///         e.g. `["state", "-", "of", "-", "the", "-", "art"]`.
+    ///     separator (str): Joins the tokens of a merged phrase.
+    ///     case_sensitive (bool): Match tokens exactly instead of ignoring
+    ///         case. Merged tokens keep the case of the text either way.
+    #[new]
+    #[pyo3(signature = (phrases, separator=" ", case_sensitive=false))]
+    fn new(phrases: Vec<Phrase>, separator: &str, case_sensitive: bool) -> PyResult<Self> {
+        let phrases: Vec<Vec<String>> = phrases
+            .into_iter()
+            .map(|phrase| match phrase {
+                Phrase::Text(text) => text.split_whitespace().map(str::to_string).collect(),
+                Phrase::Tokens(tokens) => tokens,
+            })
+            .collect();
+        if phrases.iter().any(Vec::is_empty) {
+            return Err(PyValueError::new_err(
+                "Phrases must have at least one token",
+            ));
+        }
+        let mut symbols = HashMap::new();
+        let mut patterns = Vec::with_capacity(phrases.len());
+        for phrase in &phrases {
+            let mut pattern = String::new();
+            for token in phrase {
+                let next = symbols.len();
+                let symbol = *symbols
+                    .entry(key(token, case_sensitive).into_owned())
+                    .or_insert_with(|| symbol(next).expect("fewer tokens than chars"));
+                pattern.push(symbol);
+            }
+            patterns.push(pattern);
+        }
+        let matcher = AhoCorasickBuilder::new()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(&patterns)
+            .map_err(|e| PyValueError::new_err(format!("Cannot build phrase matcher: {}", e)))?;
+        Ok(PhraseMerger {
+            phrases: phrases.into(),
+            separator: separator.to_string(),
+            case_sensitive,
+            symbols: Arc::new(symbols),
+            matcher,
+        })
+    }
+
+    /// Constructor arguments recreating this merger, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("phrases", self.phrases())?;
+        kwargs.set_item("separator", &self.separator)?;
+        kwargs.set_item("case_sensitive", self.case_sensitive)?;
+        Ok(((), kwargs))
+    }
+
+    /// `tokens` with every phrase merged into one token.
+    #[pyo3(name = "merge")]
+    fn merge_tokens(&self, tokens: Vec<String>) -> Vec<String> {
+        let tokens = tokens.into_iter().map(|t| Token::new(t, 0, 0)).collect();
+        self.merge(tokens).into_iter().map(|t| t.text).collect()
+    }
+
+    /// Merge phrases in `tokenize_with_offsets` output; a merged token
+    /// spans all of its parts.
+    fn merge_with_offsets(
+        &self,
+        tokens: Vec<(String, usize, usize)>,
+    ) -> Vec<(String, usize, usize)> {
+        let tokens = tokens
+            .into_iter()
+            .map(|(text, start, end)| Token::new(text, start, end))
+            .collect();
+        self.merge(tokens)
+            .into_iter()
+            .map(|t| (t.text, t.start, t.end))
+            .collect()
+    }
+
+    /// The phrases as token lists, in the order given.
+    #[getter]
+    fn phrases(&self) -> Vec<Vec<String>> {
+        self.phrases.to_vec()
+    }
+
+    #[getter]
+    fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    #[getter]
+    fn case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    fn __len__(&self) -> usize {
+        self.phrases.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PhraseMerger(phrases={})", self.phrases.len())
+    }
+}
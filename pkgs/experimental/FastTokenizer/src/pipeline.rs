@@ -0,0 +1,934 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::aligned::AlignedString;
+use crate::benchmark::{benchmark_stages, StageTimes};
+use crate::config;
+use crate::detokenizer::clean_up_tokenization;
+use crate::filters::{extract_filter, TokenFilter};
+use crate::intern::{token_list, token_lists};
+use crate::limits::{self, Limits};
+use crate::normalizer::Normalizer;
+use crate::padding::{truncate, windows, BatchInput, BatchOutput, Padding, Truncation, Windows};
+use crate::phrases::PhraseMerger;
+use crate::shared::Shared;
+use crate::special_tokens::SpecialTokens;
+use crate::tokenizer::{extract_tokenizer, offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::truecase::Truecaser;
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// Normalizer, pre-tokenizer, tokenizer and filters chained into a single
+/// call.
+///
+/// The text is normalized first, then split into coarse pieces by the
+/// pre-tokenizer, every piece is split by the tokenizer, multi-word
+/// phrases are merged and the filters finally drop unwanted tokens.
+/// Registered special tokens are cut out beforehand and kept whole.
+/// Offsets refer to the normalized text unless `original=True` asks for
+/// offsets into the input.
+/// Calls made on the pipeline check its own limits and those of its
+/// tokenizers.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct TokenizerPipeline {
+    normalizer: Option<Normalizer>,
+    pre_tokenizer: Option<Arc<dyn Tokenizer>>,
+    tokenizer: Arc<dyn Tokenizer>,
+    phrases: Option<PhraseMerger>,
+    truecaser: Option<Truecaser>,
+    filters: Vec<Arc<dyn TokenFilter>>,
+    vocab: Shared<Option<Vocab>>,
+    special_tokens: Shared<SpecialTokens>,
+    limits: Limits,
+}
+
+/// The tokens of `text`, checked against the limits of `tokenizer` when
+/// `checked` is set.
+fn tokens_of(tokenizer: &dyn Tokenizer, text: &str, checked: bool) -> Result<Vec<Token>, String> {
+    if checked {
+        tokenizer.try_tokenize_with_offsets(text)
+    } else {
+        Ok(tokenizer.tokenize_with_offsets(text))
+    }
+}
+
+/// `Tokenizer::try_token_spans` when `checked` is set, otherwise
+/// `token_spans`.
+fn spans_of(
+    tokenizer: &dyn Tokenizer,
+    text: &str,
+    checked: bool,
+) -> Result<Vec<(usize, usize)>, String> {
+    if checked {
+        tokenizer.try_token_spans(text)
+    } else {
+        Ok(tokenizer.token_spans(text))
+    }
+}
+
+impl TokenizerPipeline {
+    /// `text` with its casing restored by the truecaser, if any.
+    fn truecase(&self, text: String) -> String {
+        match &self.truecaser {
+            Some(truecaser) => truecaser.apply(&text),
+            None => text,
+        }
+    }
+
+    pub fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.normalize_timed(text, &mut None)
+    }
+
+    /// `normalize`, adding the time it takes to `times`.
+    fn normalize_timed<'a>(
+        &self,
+        text: &'a str,
+        times: &mut Option<&mut StageTimes>,
+    ) -> Cow<'a, str> {
+        match &self.normalizer {
+            Some(normalizer) => {
+                StageTimes::maybe_time(times, "normalizer", || Cow::Owned(normalizer.apply(text)))
+            }
+            None => Cow::Borrowed(text),
+        }
+    }
+
+    /// Run the pre-tokenizer, tokenizer, phrase merger and filters over
+    /// already normalized text, adding the time of each stage to `times`.
+    fn split(
+        &self,
+        normalized: &str,
+        checked: bool,
+        times: &mut Option<&mut StageTimes>,
+    ) -> Result<Vec<Token>, String> {
+        let mut tokens = self.pre_tokenize(normalized, checked, times)?;
+        if let Some(phrases) = &self.phrases {
+            tokens = StageTimes::maybe_time(times, "phrases", || phrases.merge(tokens));
+        }
+        if !self.filters.is_empty() {
+            StageTimes::maybe_time(times, "filters", || {
+                tokens.retain(|t| self.filters.iter().all(|f| f.keep(&t.text)))
+            });
+        }
+        Ok(tokens)
+    }
+
+    /// `pre_tokenize_piece`, with the spans the normalizer keeps whole, like
+    /// emoji and `<NUM>` placeholders, emitted as single tokens.
+    fn pre_tokenize(
+        &self,
+        normalized: &str,
+        checked: bool,
+        times: &mut Option<&mut StageTimes>,
+    ) -> Result<Vec<Token>, String> {
+        let kept = match &self.normalizer {
+            Some(normalizer) => {
+                StageTimes::maybe_time(times, "normalizer", || normalizer.atomic_spans(normalized))
+            }
+            None => Vec::new(),
+        };
+        if kept.is_empty() {
+            return self.pre_tokenize_piece(normalized, checked, times);
+        }
+        let mut tokens = Vec::new();
+        let mut last = 0;
+        for (start, end) in kept {
+            tokens.extend(
+                self.pre_tokenize_piece(&normalized[last..start], checked, times)?
+                    .into_iter()
+                    .map(|t| Token::new(t.text, t.start + last, t.end + last)),
+            );
+            tokens.push(Token::new(&normalized[start..end], start, end));
+            last = end;
+        }
+        tokens.extend(
+            self.pre_tokenize_piece(&normalized[last..], checked, times)?
+                .into_iter()
+                .map(|t| Token::new(t.text, t.start + last, t.end + last)),
+        );
+        Ok(tokens)
+    }
+
+    fn pre_tokenize_piece(
+        &self,
+        normalized: &str,
+        checked: bool,
+        times: &mut Option<&mut StageTimes>,
+    ) -> Result<Vec<Token>, String> {
+        let tokenizer = self.tokenizer.as_ref();
+        let Some(pre_tokenizer) = &self.pre_tokenizer else {
+            return StageTimes::maybe_time(times, "tokenizer", || {
+                tokens_of(tokenizer, normalized, checked)
+            });
+        };
+        let pieces = StageTimes::maybe_time(times, "pre_tokenizer", || {
+            tokens_of(pre_tokenizer.as_ref(), normalized, checked)
+        })?;
+        let mut tokens = Vec::new();
+        for piece in pieces {
+            let text = &normalized[piece.start..piece.end];
+            tokens.extend(
+                StageTimes::maybe_time(times, "tokenizer", || tokens_of(tokenizer, text, checked))?
+                    .into_iter()
+                    .map(|t| Token::new(t.text, t.start + piece.start, t.end + piece.start)),
+            );
+        }
+        Ok(tokens)
+    }
+
+    /// `pre_tokenize` as spans alone, without the stage times.
+    fn pre_tokenize_spans(
+        &self,
+        normalized: &str,
+        checked: bool,
+    ) -> Result<Vec<(usize, usize)>, String> {
+        let kept = match &self.normalizer {
+            Some(normalizer) => normalizer.atomic_spans(normalized),
+            None => Vec::new(),
+        };
+        let mut spans = Vec::new();
+        let mut last = 0;
+        for (start, end) in kept
+            .into_iter()
+            .chain([(normalized.len(), normalized.len())])
+        {
+            spans.extend(
+                self.pre_tokenize_piece_spans(&normalized[last..start], checked)?
+                    .into_iter()
+                    .map(|(s, e)| (s + last, e + last)),
+            );
+            if end > start {
+                spans.push((start, end));
+            }
+            last = end;
+        }
+        Ok(spans)
+    }
+
+    /// `pre_tokenize_piece` as spans alone.
+    fn pre_tokenize_piece_spans(
+        &self,
+        normalized: &str,
+        checked: bool,
+    ) -> Result<Vec<(usize, usize)>, String> {
+        let tokenizer = self.tokenizer.as_ref();
+        let Some(pre_tokenizer) = &self.pre_tokenizer else {
+            return spans_of(tokenizer, normalized, checked);
+        };
+        let mut spans = Vec::new();
+        for (start, end) in spans_of(pre_tokenizer.as_ref(), normalized, checked)? {
+            spans.extend(
+                spans_of(tokenizer, &normalized[start..end], checked)?
+                    .into_iter()
+                    .map(|(s, e)| (s + start, e + start)),
+            );
+        }
+        Ok(spans)
+    }
+
+    /// The pipeline's own limits when `checked` is set, otherwise none.
+    fn active_limits(&self, checked: bool) -> Limits {
+        if checked {
+            self.limits
+        } else {
+            Limits::NONE
+        }
+    }
+
+    /// Normalize and tokenize the text between special tokens, returning the
+    /// normalized text together with tokens offset into it. With `checked`
+    /// set, exceeding a limit fails instead. The time spent in each stage is
+    /// added to `times`, if given.
+    fn run<'a>(
+        &self,
+        text: &'a str,
+        checked: bool,
+        mut times: Option<&mut StageTimes>,
+    ) -> Result<(Cow<'a, str>, Vec<Token>), String> {
+        let _span = tracing::trace_span!("pipeline", bytes = text.len()).entered();
+        let limits = self.active_limits(checked);
+        limits.check_input(text)?;
+        let special_tokens = self.special_tokens.load();
+        if special_tokens.is_empty() {
+            let normalized = self.normalize_timed(text, &mut times);
+            let tokens = self.split(&normalized, checked, &mut times)?;
+            limits.check_tokens(&tokens)?;
+            return Ok((normalized, tokens));
+        }
+        let mut normalized = String::with_capacity(text.len());
+        let mut tokens = Vec::new();
+        let pieces =
+            StageTimes::maybe_time(&mut times, "special_tokens", || special_tokens.split(text));
+        for (special, start, end) in pieces {
+            let base = normalized.len();
+            if special {
+                normalized.push_str(&text[start..end]);
+                tokens.push(Token::new(&text[start..end], base, normalized.len()));
+                continue;
+            }
+            let piece = self.normalize_timed(&text[start..end], &mut times);
+            tokens.extend(
+                self.split(&piece, checked, &mut times)?
+                    .into_iter()
+                    .map(|t| Token::new(t.text, t.start + base, t.end + base)),
+            );
+            normalized.push_str(&piece);
+        }
+        limits.check_tokens(&tokens)?;
+        Ok((Cow::Owned(normalized), tokens))
+    }
+
+    /// `normalize`, remembering the original span of every character for
+    /// text starting at byte `base` of the input.
+    fn normalize_aligned(&self, text: &str, base: usize) -> AlignedString {
+        let Some(normalizer) = &self.normalizer else {
+            return AlignedString::new(text, base);
+        };
+        let mut aligned = normalizer.apply_aligned(text);
+        for span in &mut aligned.spans {
+            *span = (span.0 + base, span.1 + base);
+        }
+        aligned
+    }
+
+    /// `run`, with the tokens offset into `text` itself.
+    fn run_original(&self, text: &str, checked: bool) -> Result<Vec<Token>, String> {
+        let limits = self.active_limits(checked);
+        limits.check_input(text)?;
+        let mut tokens = Vec::new();
+        let special_tokens = self.special_tokens.load();
+        let pieces = if special_tokens.is_empty() {
+            vec![(false, 0, text.len())]
+        } else {
+            special_tokens.split(text)
+        };
+        for (special, start, end) in pieces {
+            if special {
+                tokens.push(Token::new(&text[start..end], start, end));
+                continue;
+            }
+            let aligned = self.normalize_aligned(&text[start..end], start);
+            tokens.extend(
+                self.split(&aligned.text, checked, &mut None)?
+                    .into_iter()
+                    .map(|t| {
+                        let (start, end) = aligned.span(t.start, t.end);
+                        Token::new(t.text, start, end)
+                    }),
+            );
+        }
+        limits.check_tokens(&tokens)?;
+        Ok(tokens)
+    }
+
+    /// `run_original` as spans alone. Phrases, filters and the token length
+    /// limit look at the token strings, so pipelines using them take their
+    /// spans from `run_original` instead.
+    fn spans_original(&self, text: &str, checked: bool) -> Result<Vec<(usize, usize)>, String> {
+        let limits = self.active_limits(checked);
+        if self.phrases.is_some() || !self.filters.is_empty() || limits.max_token_length.is_some() {
+            return Ok(self
+                .run_original(text, checked)?
+                .into_iter()
+                .map(|t| (t.start, t.end))
+                .collect());
+        }
+        limits.check_input(text)?;
+        let special_tokens = self.special_tokens.load();
+        let pieces = if special_tokens.is_empty() {
+            vec![(false, 0, text.len())]
+        } else {
+            special_tokens.split(text)
+        };
+        let mut spans = Vec::new();
+        for (special, start, end) in pieces {
+            if special {
+                spans.push((start, end));
+                continue;
+            }
+            let aligned = self.normalize_aligned(&text[start..end], start);
+            spans.extend(
+                self.pre_tokenize_spans(&aligned.text, checked)?
+                    .into_iter()
+                    .map(|(start, end)| aligned.span(start, end)),
+            );
+        }
+        Ok(spans)
+    }
+
+    /// Tokens of `input`, and of `pair` if given, wrapped in the special
+    /// token template when `add_special_tokens` is set. Limits are checked.
+    /// Sequences are first truncated to `max_length` minus the number of
+    /// special tokens the template adds.
+    fn encode_tokens(
+        &self,
+        input: &str,
+        pair: Option<&str>,
+        add_special_tokens: bool,
+        truncation: Option<(usize, Truncation)>,
+    ) -> Result<Vec<String>, String> {
+        let special_tokens = self.special_tokens.load();
+        let mut a = self.try_tokenize(input)?;
+        let mut b = pair.map(|pair| self.try_tokenize(pair)).transpose()?;
+        if let Some((max_length, strategy)) = truncation {
+            let added = if add_special_tokens {
+                special_tokens.num_added(b.is_some())
+            } else {
+                0
+            };
+            truncate(
+                &mut a,
+                b.as_mut(),
+                max_length.saturating_sub(added),
+                strategy,
+            )?;
+        }
+        if add_special_tokens {
+            return Ok(special_tokens.apply(a, b));
+        }
+        Ok(a.into_iter().chain(b.into_iter().flatten()).collect())
+    }
+
+    /// Make sure the vocabulary knows the special tokens, giving them ids
+    /// before any regular token when it is still empty.
+    fn register_special_tokens(&self) {
+        if let Some(vocab) = &*self.vocab.load() {
+            if !vocab.is_frozen() {
+                let _ = vocab.encode_tokens(self.special_tokens.load().registered());
+            }
+        }
+    }
+}
+
+impl Tokenizer for TokenizerPipeline {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.run(text, false, None)
+            .expect("unchecked runs cannot fail")
+            .1
+    }
+
+    fn try_tokenize_with_offsets(&self, text: &str) -> Result<Vec<Token>, String> {
+        Ok(self.run(text, true, None)?.1)
+    }
+
+    fn try_tokenize(&self, text: &str) -> Result<Vec<String>, String> {
+        Ok(self
+            .try_tokenize_with_offsets(text)?
+            .into_iter()
+            .map(|t| t.text)
+            .collect())
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        self.normalizer.is_none() && self.phrases.is_none() && self.tokenizer.tokens_are_slices()
+    }
+
+    fn tokenize_with_source_offsets(&self, text: &str) -> Vec<Token> {
+        self.run_original(text, false)
+            .expect("unchecked runs cannot fail")
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.spans_original(text, false)
+            .expect("unchecked runs cannot fail")
+    }
+
+    fn try_token_spans(&self, text: &str) -> Result<Vec<(usize, usize)>, String> {
+        self.spans_original(text, true)
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        self.truecase(self.tokenizer.detokenize(tokens))
+    }
+}
+
+tokenizer_pymethods!(TokenizerPipeline: tokenize_iter, tokenize_foreach, tokenize_spans);
+
+#[pymethods]
+impl TokenizerPipeline {
+    /// Create a pipeline around `tokenizer`.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer.
+    ///     normalizer (Normalizer, optional): Applied before tokenization.
+    ///     pre_tokenizer (optional): Tokenizer producing the pieces that
+    ///         `tokenizer` is run on.
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    ///     special_tokens (SpecialTokens, optional): Tokens kept whole and
+    ///         inserted by `encode`.
+    ///     phrases (PhraseMerger, optional): Multi-word expressions merged
+    ///         into single tokens before filtering.
+    ///     filters (List, optional): Token filters such as `StopWordFilter`,
+    ///         applied in order after tokenization. Special tokens are never
+    ///         filtered.
+    ///     truecaser (Truecaser, optional): Restores the casing of the text
+    ///         produced by `detokenize` and `decode`.
+    ///     max_input_length (int, optional): Longest input in UTF-8 bytes;
+    ///         longer ones raise `ValueError` before any work is done.
+    ///     max_token_length (int, optional): Longest token in UTF-8 bytes
+    ///         after normalization; longer ones raise `ValueError`.
+    #[new]
+    #[pyo3(signature = (
+        tokenizer,
+        normalizer=None,
+        pre_tokenizer=None,
+        vocab=None,
+        special_tokens=None,
+        filters=None,
+        phrases=None,
+        truecaser=None,
+        max_input_length=None,
+        max_token_length=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        tokenizer: &Bound<'_, PyAny>,
+        normalizer: Option<PyRef<'_, Normalizer>>,
+        pre_tokenizer: Option<&Bound<'_, PyAny>>,
+        vocab: Option<PyRef<'_, Vocab>>,
+        special_tokens: Option<PyRef<'_, SpecialTokens>>,
+        filters: Option<Vec<Bound<'_, PyAny>>>,
+        phrases: Option<PyRef<'_, PhraseMerger>>,
+        truecaser: Option<PyRef<'_, Truecaser>>,
+        max_input_length: Option<usize>,
+        max_token_length: Option<usize>,
+    ) -> PyResult<Self> {
+        let pipeline = TokenizerPipeline {
+            normalizer: normalizer.map(|n| n.clone()),
+            pre_tokenizer: pre_tokenizer.map(extract_tokenizer).transpose()?,
+            tokenizer: extract_tokenizer(tokenizer)?,
+            phrases: phrases.map(|p| p.clone()),
+            truecaser: truecaser.map(|t| t.clone()),
+            filters: filters
+                .iter()
+                .flatten()
+                .map(extract_filter)
+                .collect::<PyResult<_>>()?,
+            vocab: vocab.map(|v| v.clone()).into(),
+            special_tokens: special_tokens.map(|s| s.clone()).unwrap_or_default().into(),
+            limits: Limits {
+                max_input_length,
+                max_token_length,
+                max_matches: None,
+            },
+        };
+        pipeline.register_special_tokens();
+        Ok(pipeline)
+    }
+
+    /// Constructor arguments recreating this pipeline, for `pickle` and
+    /// `copy`. Its tokenizers and filters must support pickling too.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("tokenizer", self.tokenizer.to_object(py)?)?;
+        kwargs.set_item("normalizer", self.normalizer.clone())?;
+        kwargs.set_item(
+            "pre_tokenizer",
+            self.pre_tokenizer
+                .as_ref()
+                .map(|t| t.to_object(py))
+                .transpose()?,
+        )?;
+        kwargs.set_item("vocab", Option::clone(&self.vocab.load()))?;
+        kwargs.set_item(
+            "special_tokens",
+            SpecialTokens::clone(&self.special_tokens.load()),
+        )?;
+        kwargs.set_item(
+            "filters",
+            self.filters
+                .iter()
+                .map(|f| f.to_object(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        kwargs.set_item("phrases", self.phrases.clone())?;
+        kwargs.set_item("truecaser", self.truecaser.clone())?;
+        limits::set_items(&self.limits, &kwargs)?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the pipeline's configuration (tokenizer, normalizer,
+    /// vocabulary, special tokens and filters) to JSON, for versioning
+    /// alongside a model. The vocabulary is embedded in full.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a pipeline from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
+    }
+
+    /// Save the pipeline to a single bundle file holding its configuration,
+    /// vocabulary, BPE merges, normalizer settings and special tokens, with
+    /// a format version header. Load it back with `TokenizerPipeline.load`.
+    fn save(slf: &Bound<'_, Self>, path: &str) -> PyResult<()> {
+        config::save(slf.as_any(), path)
+    }
+
+    /// Load a pipeline saved with `save`.
+    #[staticmethod]
+    fn load<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, Self>> {
+        config::load(py, path)
+    }
+
+    /// Apply only the normalizer stage.
+    fn normalize_text(&self, text: &str) -> PyResult<String> {
+        Ok(self.normalize(text).into_owned())
+    }
+
+    /// Normalize and tokenize the input string.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[str]: A list of extracted tokens.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        let tokens = self.try_tokenize(input).map_err(PyValueError::new_err)?;
+        token_list(py, tokens, intern)
+    }
+
+    /// Tokenize several strings at once, releasing the GIL while working.
+    ///
+    /// Pass `intern` as for `tokenize`; a call shares one cache across all
+    /// inputs.
+    #[pyo3(signature = (inputs, intern=None))]
+    fn tokenize_batch<'py>(
+        &self,
+        py: Python<'py>,
+        inputs: Vec<String>,
+        intern: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let _span = tracing::debug_span!("tokenize_batch", inputs = inputs.len()).entered();
+        let tokens = py
+            .detach(|| {
+                inputs
+                    .iter()
+                    .map(|input| self.try_tokenize(input))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(PyValueError::new_err)?;
+        token_lists(py, tokens, intern)
+    }
+
+    /// Measure how fast `texts` are tokenized, e.g. to compare settings.
+    ///
+    /// Args:
+    ///     texts (List[str]): Sample inputs, tokenized once to warm up and
+    ///         then `iterations` times while timed.
+    ///     iterations (int): Timed passes over `texts`.
+    ///
+    /// Returns:
+    ///     dict: `tokens_per_sec`, `mb_per_sec` and the total `seconds`; the
+    ///     `bytes` and `tokens` of one pass; the number of `iterations`;
+    ///     and, in builds with the `alloc-stats` feature, the `allocations`
+    ///     and `allocated_bytes` of one pass.
+    ///     `stages` maps each stage of the pipeline (`special_tokens`,
+    ///     `normalizer`, `pre_tokenizer`, `tokenizer`, `phrases`, `filters`)
+    ///     to the seconds spent in it during the timed passes.
+    #[pyo3(signature = (texts, iterations=10))]
+    fn benchmark<'py>(
+        &self,
+        py: Python<'py>,
+        texts: Vec<String>,
+        iterations: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        benchmark_stages(py, &texts, iterations, |text, times| {
+            let (_, tokens) = self
+                .run(text, false, times)
+                .expect("unchecked runs cannot fail");
+            tokens.into_iter().map(|t| t.text).collect()
+        })
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples with offsets into
+    /// the normalized text.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets or `"byte"` for offsets
+    ///         into the UTF-8 encoded normalized text.
+    ///     original (bool): Offset into `input` instead of the normalized
+    ///         text; a token covers every input character it was produced
+    ///         from.
+    #[pyo3(signature = (input, unit="char", original=false))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+        original: bool,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        if original {
+            let tokens = self
+                .run_original(input, true)
+                .map_err(PyValueError::new_err)?;
+            return offsets(input, tokens, unit);
+        }
+        let (normalized, tokens) = self.run(input, true, None).map_err(PyValueError::new_err)?;
+        offsets(&normalized, tokens, unit)
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     pair (str | bytes, optional): A second sequence, encoded with the pair template.
+    ///     add_special_tokens (bool): Wrap the tokens in the special token template.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, pair=None, add_special_tokens=true, errors="strict"))]
+    fn encode<'py>(
+        &self,
+        input: Text<'py>,
+        pair: Option<Text<'py>>,
+        add_special_tokens: bool,
+        errors: &str,
+    ) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        let pair = pair.as_ref().map(|pair| pair.decode(errors)).transpose()?;
+        let pair = pair.as_deref();
+        let vocab = require_vocab(&self.vocab)?;
+        self.encode_tokens(input, pair, add_special_tokens, None)
+            .and_then(|tokens| vocab.encode_tokens(&tokens))
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Encode a long text as overlapping windows of at most `max_length`
+    /// ids, the usual way to feed long documents to a transformer model.
+    ///
+    /// Args:
+    ///     text (str): The document to encode.
+    ///     max_length (int): Ids per window, special tokens included.
+    ///     stride (int): Number of tokens each window repeats from the end
+    ///         of the one before, as in HuggingFace's `stride`.
+    ///     add_special_tokens (bool): Wrap every window in the special token template.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    ///
+    /// Returns:
+    ///     List[Tuple[List[int], List[Tuple[int, int]]]]: The ids of each
+    ///     window and their `(start, end)` offsets into `text`; special
+    ///     tokens have `(0, 0)`.
+    #[pyo3(signature = (text, max_length, stride=0, add_special_tokens=true, unit="char"))]
+    fn encode_windows(
+        &self,
+        text: &str,
+        max_length: usize,
+        stride: usize,
+        add_special_tokens: bool,
+        unit: &str,
+    ) -> PyResult<Windows> {
+        let vocab = require_vocab(&self.vocab)?;
+        let special_tokens = self.special_tokens.load();
+        let added = if add_special_tokens {
+            special_tokens.num_added(false)
+        } else {
+            0
+        };
+        let tokens = self
+            .run_original(text, true)
+            .map_err(PyValueError::new_err)?;
+        let mut out = Vec::new();
+        for window in windows(&tokens, max_length.saturating_sub(added), stride)
+            .map_err(PyValueError::new_err)?
+        {
+            let mut window = window.to_vec();
+            if add_special_tokens {
+                window = special_tokens.apply_with(window, None, |t| Token::new(t, 0, 0));
+            }
+            let ids = vocab
+                .encode_tokens(&window.iter().map(|t| &t.text).collect::<Vec<_>>())
+                .map_err(PyValueError::new_err)?;
+            let spans = offsets(text, window, unit)?
+                .into_iter()
+                .map(|(_, start, end)| (start, end))
+                .collect();
+            out.push((ids, spans));
+        }
+        Ok(out)
+    }
+
+    /// Encode several texts or `(text, pair)` tuples at once, releasing the
+    /// GIL while working.
+    ///
+    /// Args:
+    ///     inputs (List[str | Tuple[str, str]]): Texts or sequence pairs.
+    ///     add_special_tokens (bool): Wrap the tokens in the special token template.
+    ///     max_length (int, optional): Truncate (and with `padding="max_length"`,
+    ///         pad) every sequence to this many ids, special tokens included.
+    ///     truncation (str, optional): `"longest_first"`, `"only_first"` or
+    ///         `"only_second"`; `None` keeps long sequences whole.
+    ///     padding (str, optional): `"longest"` or `"max_length"`.
+    ///     pad_id (int, optional): Padding id; defaults to the id of the `pad`
+    ///         special token.
+    ///     padding_side (str): `"right"` or `"left"`.
+    ///     return_tensors (str, optional): `"np"` for a dict of 2-D NumPy
+    ///         arrays instead of Python lists.
+    ///     dtype (str): `"int64"` or `"int32"` for NumPy output.
+    ///
+    /// Returns:
+    ///     List[List[int]] when neither `max_length` nor `padding` is given,
+    ///     otherwise a `BatchEncoding` with `input_ids` and `attention_mask`,
+    ///     or with `return_tensors="np"` a dict of both as arrays.
+    #[pyo3(signature = (
+        inputs,
+        add_special_tokens=true,
+        max_length=None,
+        truncation=Some("longest_first"),
+        padding=None,
+        pad_id=None,
+        padding_side="right",
+        return_tensors=None,
+        dtype="int64"
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn encode_batch<'py>(
+        &self,
+        py: Python<'py>,
+        inputs: Vec<BatchInput>,
+        add_special_tokens: bool,
+        max_length: Option<usize>,
+        truncation: Option<&str>,
+        padding: Option<&str>,
+        pad_id: Option<u32>,
+        padding_side: &str,
+        return_tensors: Option<&str>,
+        dtype: &str,
+    ) -> PyResult<BatchOutput<'py>> {
+        let vocab = require_vocab(&self.vocab)?;
+        let truncation = max_length.zip(Truncation::parse(truncation)?);
+        let padding = Padding::parse(padding)?;
+        let _span = tracing::debug_span!("encode_batch", inputs = inputs.len()).entered();
+        let ids = py.detach(|| {
+            inputs
+                .iter()
+                .map(|input| {
+                    let (text, pair) = input.parts();
+                    self.encode_tokens(text, pair, add_special_tokens, truncation)
+                        .and_then(|tokens| vocab.encode_tokens(&tokens))
+                        .map_err(PyValueError::new_err)
+                })
+                .collect::<PyResult<Vec<_>>>()
+        })?;
+        let pad_id = || match (pad_id, self.special_tokens.load().pad_token()) {
+            (Some(id), _) => Ok(Some(id)),
+            (None, Some(pad)) => vocab
+                .encode_tokens(&[pad])
+                .map(|ids| Some(ids[0]))
+                .map_err(PyValueError::new_err),
+            (None, None) => Ok(None),
+        };
+        BatchOutput::build(
+            py,
+            ids,
+            max_length,
+            padding,
+            pad_id,
+            padding_side,
+            return_tensors,
+            dtype,
+        )
+    }
+
+    /// Turn ids back into text the way the tokenizer detokenizes, e.g.
+    /// undoing byte-level BPE or joining WordPiece continuations, and
+    /// restore its casing if the pipeline has a truecaser.
+    ///
+    /// Args:
+    ///     ids (List[int]): Token ids.
+    ///     skip_special_tokens (bool): Leave out registered special tokens.
+    ///     clean_up_tokenization_spaces (bool): Remove spaces before
+    ///         punctuation and contractions like `transformers` does.
+    #[pyo3(signature = (ids, skip_special_tokens=true, clean_up_tokenization_spaces=true))]
+    fn decode(
+        &self,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> PyResult<String> {
+        let special_tokens = self.special_tokens.load();
+        let tokens = require_vocab(&self.vocab)?
+            .decode_ids(&ids)
+            .map_err(PyValueError::new_err)?;
+        let tokens: Vec<String> = tokens
+            .into_iter()
+            .filter(|t| !skip_special_tokens || !special_tokens.contains(t))
+            .collect();
+        Ok(clean_up_tokenization(
+            Tokenizer::detokenize(self, &tokens),
+            clean_up_tokenization_spaces,
+        ))
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
+        self.register_special_tokens();
+    }
+
+    /// The special token registry of this pipeline.
+    #[getter]
+    fn special_tokens(&self) -> SpecialTokens {
+        SpecialTokens::clone(&self.special_tokens.load())
+    }
+
+    #[setter]
+    fn set_special_tokens(&self, special_tokens: PyRef<'_, SpecialTokens>) {
+        self.special_tokens.store(special_tokens.clone());
+        self.register_special_tokens();
+    }
+
+    /// The limits every call is checked against, `None` where unlimited.
+    #[getter]
+    fn limits<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let limits = PyDict::new(py);
+        limits::set_items(&self.limits, &limits)?;
+        Ok(limits)
+    }
+
+    /// Register further special tokens, protecting them from being split.
+    fn add_special_tokens(&self, tokens: Vec<String>) {
+        self.special_tokens
+            .update(|special| special.add_tokens(tokens));
+        self.register_special_tokens();
+    }
+}
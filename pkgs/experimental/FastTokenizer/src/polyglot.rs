@@ -0,0 +1,264 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::language::{detect, UNDETERMINED};
+use crate::normalizer::Normalizer;
+use crate::tokenizer::{extract_tokenizer, offsets, Token, Tokenizer};
+use crate::whitespace_tokenizer::WhitespaceTokenizer;
+
+/// Routes text to a tokenizer and normalizer chosen by its language.
+///
+/// The language of each text (or, with `by_line`, each line) is detected
+/// as by `detect_language`, and the text is normalized and tokenized with
+/// the normalizer and tokenizer registered for that language code, or the
+/// defaults when there are none. Tokens come back tagged with the language
+/// they were routed by; offsets always point into the input.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct PolyglotTokenizer {
+    tokenizers: Arc<BTreeMap<String, Arc<dyn Tokenizer>>>,
+    default: Arc<dyn Tokenizer>,
+    normalizers: Arc<BTreeMap<String, Normalizer>>,
+    by_line: bool,
+    min_confidence: f64,
+}
+
+impl PolyglotTokenizer {
+    /// The language label of `text`, `"und"` when detection fails or is
+    /// less confident than `min_confidence`.
+    fn language(&self, text: &str) -> &'static str {
+        match detect(text) {
+            Some((code, confidence)) if confidence >= self.min_confidence => code,
+            _ => UNDETERMINED,
+        }
+    }
+
+    /// The pieces of `text` routed on their own, with their byte offsets
+    /// and languages.
+    fn segments<'a>(&self, text: &'a str) -> Vec<(usize, &'a str, &'static str)> {
+        let language = self.language(text);
+        if !self.by_line {
+            return vec![(0, text, language)];
+        }
+        let mut start = 0;
+        text.split_inclusive('\n')
+            .map(|line| {
+                let at = start;
+                start += line.len();
+                // Lines too short to tell inherit the language of the text.
+                let line_language = match self.language(line) {
+                    UNDETERMINED => language,
+                    code => code,
+                };
+                (at, line, line_language)
+            })
+            .collect()
+    }
+
+    /// Tokens with byte offsets into `text`, each with its language.
+    fn run(&self, text: &str) -> Vec<(Token, &'static str)> {
+        let mut tokens = Vec::new();
+        for (at, segment, language) in self.segments(text) {
+            let tokenizer = self.tokenizers.get(language).unwrap_or(&self.default);
+            match self.normalizers.get(language) {
+                Some(normalizer) => {
+                    let aligned = normalizer.apply_aligned(segment);
+                    for token in tokenizer.tokenize_with_source_offsets(&aligned.text) {
+                        let (start, end) = aligned.span(token.start, token.end);
+                        tokens.push((Token::new(token.text, at + start, at + end), language));
+                    }
+                }
+                None => tokens.extend(
+                    tokenizer
+                        .tokenize_with_source_offsets(segment)
+                        .into_iter()
+                        .map(|t| (Token::new(t.text, at + t.start, at + t.end), language)),
+                ),
+            }
+        }
+        tokens
+    }
+}
+
+impl Tokenizer for PolyglotTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.run(text).into_iter().map(|(token, _)| token).collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        for (at, segment, language) in self.segments(text) {
+            let tokenizer = self.tokenizers.get(language).unwrap_or(&self.default);
+            match self.normalizers.get(language) {
+                Some(normalizer) => {
+                    let aligned = normalizer.apply_aligned(segment);
+                    spans.extend(tokenizer.token_spans(&aligned.text).into_iter().map(
+                        |(start, end)| {
+                            let (start, end) = aligned.span(start, end);
+                            (at + start, at + end)
+                        },
+                    ));
+                }
+                None => spans.extend(
+                    tokenizer
+                        .token_spans(segment)
+                        .into_iter()
+                        .map(|(start, end)| (at + start, at + end)),
+                ),
+            }
+        }
+        spans
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+}
+
+#[pymethods]
+impl PolyglotTokenizer {
+    /// Create a language-routing tokenizer.
+    ///
+    /// Args:
+    ///     tokenizers (Dict[str, tokenizer]): Tokenizers or pipelines by
+    ///         ISO 639-1 code, such as `"en"` or `"ja"`; `"und"` catches text
+    ///         whose language could not be determined.
+    ///     default (optional): Tokenizer for all other languages; a plain
+    ///         `WhitespaceTokenizer` by default.
+    ///     normalizers (Dict[str, Normalizer], optional): Normalizers by
+    ///         language code, applied before tokenizing.
+    ///     by_line (bool): Detect the language of each line separately, for
+    ///         texts mixing languages line by line. Lines too short to tell
+    ///         take the language of the whole text.
+    ///     min_confidence (float): Detections less confident than this are
+    ///         treated as `"und"`.
+    #[new]
+    #[pyo3(signature = (tokenizers, default=None, normalizers=None, by_line=false, min_confidence=0.0))]
+    fn new(
+        tokenizers: HashMap<String, Bound<'_, PyAny>>,
+        default: Option<&Bound<'_, PyAny>>,
+        normalizers: Option<HashMap<String, PyRef<'_, Normalizer>>>,
+        by_line: bool,
+        min_confidence: f64,
+    ) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(PyValueError::new_err(format!(
+                "min_confidence must be between 0 and 1, got {}",
+                min_confidence
+            )));
+        }
+        let tokenizers = tokenizers
+            .iter()
+            .map(|(code, tokenizer)| Ok((code.clone(), extract_tokenizer(tokenizer)?)))
+            .collect::<PyResult<_>>()?;
+        let default = match default {
+            Some(tokenizer) => extract_tokenizer(tokenizer)?,
+            None => Arc::new(WhitespaceTokenizer::default()),
+        };
+        let normalizers = normalizers
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(code, normalizer)| (code, normalizer.clone()))
+            .collect();
+        Ok(PolyglotTokenizer {
+            tokenizers: Arc::new(tokenizers),
+            default,
+            normalizers: Arc::new(normalizers),
+            by_line,
+            min_confidence,
+        })
+    }
+
+    /// Constructor arguments recreating this tokenizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        let tokenizers = PyDict::new(py);
+        for (code, tokenizer) in self.tokenizers.iter() {
+            tokenizers.set_item(code, tokenizer.to_object(py)?)?;
+        }
+        let normalizers = PyDict::new(py);
+        for (code, normalizer) in self.normalizers.iter() {
+            normalizers.set_item(code, normalizer.clone())?;
+        }
+        kwargs.set_item("tokenizers", tokenizers)?;
+        kwargs.set_item("default", self.default.to_object(py)?)?;
+        kwargs.set_item("normalizers", normalizers)?;
+        kwargs.set_item("by_line", self.by_line)?;
+        kwargs.set_item("min_confidence", self.min_confidence)?;
+        Ok(((), kwargs))
+    }
+
+    /// The language label `text` is routed by: an ISO 639-1 code or
+    /// `"und"`. With `by_line`, this is the label of the whole text.
+    fn detect(&self, text: &str) -> &'static str {
+        self.language(text)
+    }
+
+    /// Split `text` into tokens, each paired with its language label.
+    fn tokenize(&self, py: Python<'_>, text: &str) -> Vec<(String, &'static str)> {
+        py.detach(|| {
+            self.run(text)
+                .into_iter()
+                .map(|(token, language)| (token.text, language))
+                .collect()
+        })
+    }
+
+    /// Split `text` into tokens with their offsets into `text` and language.
+    ///
+    /// Args:
+    ///     text (str): Input text.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int, str]]: `(token, start, end, language)`.
+    #[pyo3(signature = (text, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        py: Python<'_>,
+        text: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize, &'static str)>> {
+        let (tokens, languages): (Vec<_>, Vec<_>) =
+            py.detach(|| self.run(text).into_iter().unzip());
+        Ok(offsets(text, tokens, unit)?
+            .into_iter()
+            .zip(languages)
+            .map(|((token, start, end), language)| (token, start, end, language))
+            .collect())
+    }
+
+    /// `tokenize` for many texts at once, in parallel without the GIL.
+    fn tokenize_batch(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+    ) -> Vec<Vec<(String, &'static str)>> {
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| {
+                    self.run(text)
+                        .into_iter()
+                        .map(|(token, language)| (token.text, language))
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        let codes: Vec<&str> = self.tokenizers.keys().map(String::as_str).collect();
+        format!(
+            "PolyglotTokenizer(languages=[{}], by_line={})",
+            codes.join(", "),
+            if self.by_line { "True" } else { "False" }
+        )
+    }
+}
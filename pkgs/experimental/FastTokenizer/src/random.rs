@@ -0,0 +1,72 @@
+use pyo3::prelude::*;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+
+use crate::dedup::mix64;
+
+/// A small seeded SplitMix64 generator.
+///
+/// Not cryptographic, but fast and stable: the same seed gives the same
+/// sequence on every platform and release, so seeded sampling is
+/// reproducible.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: mix64(seed) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix64(self.state)
+    }
+
+    /// A uniform float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniform integer in `0..n`, for `n > 0`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+}
+
+/// Where calls without a seed get theirs from, once `set_global_seed` set
+/// one.
+static SEEDS: Mutex<Option<Rng>> = Mutex::new(None);
+
+/// The next seed of the `set_global_seed` stream, if one was set.
+pub fn next_seed() -> Option<u64> {
+    SEEDS.lock().unwrap().as_mut().map(Rng::next_u64)
+}
+
+/// A seed that differs between calls, for sampling when no seed is given:
+/// the next one of the `set_global_seed` stream, or a random one.
+pub fn entropy() -> u64 {
+    next_seed().unwrap_or_else(|| RandomState::new().build_hasher().finish())
+}
+
+/// `seed`, or else the next seed of the `set_global_seed` stream, or else
+/// 0, for features that are deterministic by default.
+pub fn seed_or_default(seed: Option<u64>) -> u64 {
+    seed.or_else(next_seed).unwrap_or(0)
+}
+
+/// Make every stochastic feature reproducible without passing seeds.
+///
+/// Calls that take a `seed` and are not given one (BPE-dropout and
+/// SentencePiece sampling, `Augmenter`, `TokenizerIO.sample` and `split`)
+/// draw it from a stream started from `seed`, so running the same
+/// preprocessing again gives bit-identical results on any machine. Seeds
+/// are handed out in call order, so calls racing on several threads should
+/// pass their own. `None` goes back to random sampling seeds and the
+/// default seed 0 elsewhere.
+#[pyfunction]
+pub fn set_global_seed(seed: Option<u64>) {
+    *SEEDS.lock().unwrap() = seed.map(Rng::new);
+}
@@ -0,0 +1,335 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use regex::Regex;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::tokenizer::{offsets, Token};
+
+/// Built-in kinds of personal information, in the order they are tried when
+/// several could match at the same position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Entity {
+    Email,
+    CreditCard,
+    Ssn,
+    IpAddress,
+    Phone,
+}
+
+const ENTITIES: &[Entity] = &[
+    Entity::Email,
+    Entity::CreditCard,
+    Entity::Ssn,
+    Entity::IpAddress,
+    Entity::Phone,
+];
+
+impl Entity {
+    fn parse(name: &str) -> PyResult<Self> {
+        ENTITIES
+            .iter()
+            .copied()
+            .find(|entity| entity.label() == name)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Unknown entity '{}', expected one of {}",
+                    name,
+                    ENTITIES
+                        .iter()
+                        .map(|e| format!("'{}'", e.label()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Entity::Email => "email",
+            Entity::CreditCard => "credit_card",
+            Entity::Ssn => "ssn",
+            Entity::IpAddress => "ip_address",
+            Entity::Phone => "phone",
+        }
+    }
+
+    /// A pattern for candidates, which `is_valid` then checks.
+    fn pattern(self) -> &'static str {
+        match self {
+            Entity::Email => r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+",
+            Entity::CreditCard => r"\b(?:\d[ -]?){12,18}\d\b",
+            Entity::Ssn => r"\b\d{3}-\d{2}-\d{4}\b",
+            Entity::IpAddress => {
+                r"\b\d{1,3}(?:\.\d{1,3}){3}\b|(?:[0-9A-Fa-f]{0,4}:){2,7}[0-9A-Fa-f]{0,4}"
+            }
+            Entity::Phone => concat!(
+                r"(?:\+\d{1,3}[ .-]?(?:\(\d{1,4}\)|\d{1,4})|\(\d{2,4}\)|\b\d{2,4})",
+                r"[ .-]?\d{3,4}[ .-]?\d{3,4}\b",
+            ),
+        }
+    }
+
+    /// Whether the candidate `text[start..end]` is a real match.
+    fn is_valid(self, text: &str, start: usize, end: usize) -> bool {
+        let (before, text, after) = (&text[..start], &text[start..end], &text[end..]);
+        match self {
+            Entity::Email => true,
+            Entity::CreditCard => luhn(text),
+            Entity::Ssn => {
+                let (area, rest) = text.split_at(3);
+                area != "000"
+                    && area != "666"
+                    && !area.starts_with('9')
+                    && &rest[1..3] != "00"
+                    && &rest[4..] != "0000"
+            }
+            Entity::IpAddress => {
+                text.parse::<Ipv4Addr>().is_ok()
+                    || (text.parse::<Ipv6Addr>().is_ok()
+                        && text.contains(|c: char| c.is_ascii_hexdigit()))
+            }
+            Entity::Phone => {
+                // Not part of a longer run of digit groups split the same
+                // way, such as a card number failing its checksum.
+                let digit_group = |s: &str| {
+                    let mut chars = s.chars();
+                    chars
+                        .next()
+                        .is_some_and(|c| " .-".contains(c) && text.contains(c))
+                        && chars.next().is_some_and(|c| c.is_ascii_digit())
+                };
+                let digits = text.chars().filter(char::is_ascii_digit).count();
+                (7..=15).contains(&digits)
+                    && !digit_group(after)
+                    && !digit_group(&before.chars().rev().collect::<String>())
+            }
+        }
+    }
+}
+
+/// The Luhn checksum used by payment card numbers.
+fn luhn(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// One alternative of the combined pattern.
+#[derive(Clone, Debug)]
+struct Rule {
+    label: String,
+    /// The rule's pattern on its own, for retrying a position.
+    regex: Regex,
+    /// Built-in rules have their matches validated.
+    entity: Option<Entity>,
+}
+
+impl Rule {
+    fn accepts(&self, text: &str, m: regex::Match) -> bool {
+        !m.is_empty()
+            && self
+                .entity
+                .is_none_or(|entity| entity.is_valid(text, m.start(), m.end()))
+    }
+}
+
+/// Finds and masks personal information (emails, phone numbers, payment card
+/// numbers, IP addresses and US social security numbers) plus any custom
+/// patterns, in a single scan over the text.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct Redactor {
+    regex: Regex,
+    rules: Vec<Rule>,
+    /// The capture group of each rule in `regex`.
+    groups: Vec<usize>,
+}
+
+impl Redactor {
+    /// Non-overlapping matches as `(rule, token)`, leftmost first. Custom
+    /// patterns take precedence over built-in ones starting at the same
+    /// position.
+    fn scan(&self, text: &str) -> Vec<(usize, Token)> {
+        let mut found = Vec::new();
+        let mut pos = 0;
+        while let Some(caps) = self.regex.captures_at(text, pos) {
+            let (first, m) = (self.groups.iter().enumerate())
+                .find_map(|(rule, &group)| caps.get(group).map(|m| (rule, m)))
+                .expect("one group matches");
+            let start = m.start();
+            // A match failing validation gives the later rules a chance at
+            // the same position.
+            let accepted = (first..self.rules.len()).find_map(|rule| {
+                let m = if rule == first {
+                    m
+                } else {
+                    self.rules[rule]
+                        .regex
+                        .find_at(text, start)
+                        .filter(|m| m.start() == start)?
+                };
+                self.rules[rule].accepts(text, m).then_some((rule, m))
+            });
+            match accepted {
+                Some((rule, m)) => {
+                    found.push((rule, Token::new(m.as_str(), start, m.end())));
+                    pos = m.end();
+                }
+                None => pos = start + text[start..].chars().next().map_or(1, char::len_utf8),
+            }
+            if pos >= text.len() {
+                break;
+            }
+        }
+        found
+    }
+
+    fn apply(&self, text: &str, replacement: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (rule, token) in self.scan(text) {
+            out.push_str(&text[last..token.start]);
+            out.push_str(&replacement.replace("{label}", &self.rules[rule].label));
+            last = token.end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+#[pymethods]
+impl Redactor {
+    /// Create a redactor.
+    ///
+    /// Args:
+    ///     entities (List[str], optional): Built-in kinds to detect, from
+    ///         `email`, `credit_card`, `ssn`, `ip_address` and `phone`;
+    ///         all of them by default.
+    ///     patterns (Dict[str, str], optional): Extra regex patterns keyed
+    ///         by label, tried before the built-in kinds.
+    #[new]
+    #[pyo3(signature = (entities=None, patterns=None))]
+    fn new(entities: Option<Vec<String>>, patterns: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut rules = Vec::new();
+        let mut sources = Vec::new();
+        if let Some(patterns) = patterns {
+            for (label, pattern) in patterns.iter() {
+                let pattern: String = pattern.extract()?;
+                rules.push(Rule {
+                    label: label.extract()?,
+                    regex: Regex::new(&pattern).map_err(|e| {
+                        PyValueError::new_err(format!("Invalid regex pattern: {}", e))
+                    })?,
+                    entity: None,
+                });
+                sources.push(pattern);
+            }
+        }
+        let entities = match entities {
+            Some(names) => names
+                .iter()
+                .map(|name| Entity::parse(name))
+                .collect::<PyResult<Vec<_>>>()?,
+            None => ENTITIES.to_vec(),
+        };
+        for entity in ENTITIES.iter().filter(|e| entities.contains(e)) {
+            rules.push(Rule {
+                label: entity.label().to_string(),
+                regex: Regex::new(entity.pattern()).expect("valid entity pattern"),
+                entity: Some(*entity),
+            });
+            sources.push(entity.pattern().to_string());
+        }
+        if rules.is_empty() {
+            return Err(PyValueError::new_err(
+                "Redactor needs at least one entity or pattern",
+            ));
+        }
+        let combined = sources
+            .iter()
+            .enumerate()
+            .map(|(i, source)| format!("(?P<_p{}>{})", i, source))
+            .collect::<Vec<_>>()
+            .join("|");
+        let regex = Regex::new(&combined)
+            .map_err(|e| PyValueError::new_err(format!("Invalid regex pattern: {}", e)))?;
+        let names: Vec<_> = regex.capture_names().collect();
+        let groups = (0..rules.len())
+            .map(|i| {
+                let name = format!("_p{}", i);
+                names
+                    .iter()
+                    .position(|n| *n == Some(name.as_str()))
+                    .expect("rule group exists")
+            })
+            .collect();
+        Ok(Redactor {
+            regex,
+            rules,
+            groups,
+        })
+    }
+
+    /// The labels this redactor reports, in order of precedence.
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.rules.iter().map(|rule| rule.label.clone()).collect()
+    }
+
+    /// Find personal information in `text`.
+    ///
+    /// Args:
+    ///     text (str): Text to scan.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for UTF-8 byte offsets.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, str, int, int]]: `(label, match, start, end)`.
+    #[pyo3(signature = (text, unit="char"))]
+    fn find(&self, text: &str, unit: &str) -> PyResult<Vec<(String, String, usize, usize)>> {
+        let (rules, tokens): (Vec<_>, Vec<_>) = self.scan(text).into_iter().unzip();
+        Ok(rules
+            .into_iter()
+            .zip(offsets(text, tokens, unit)?)
+            .map(|(rule, (matched, start, end))| {
+                (self.rules[rule].label.clone(), matched, start, end)
+            })
+            .collect())
+    }
+
+    /// Replace every match in `text` with `replacement`, where `{label}`
+    /// stands for the kind of match.
+    #[pyo3(signature = (text, replacement="[{label}]"))]
+    fn redact(&self, text: &str, replacement: &str) -> String {
+        self.apply(text, replacement)
+    }
+
+    /// Redact several texts at once, releasing the GIL while working.
+    #[pyo3(signature = (texts, replacement="[{label}]"))]
+    fn redact_batch(&self, py: Python<'_>, texts: Vec<String>, replacement: &str) -> Vec<String> {
+        py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| self.apply(text, replacement))
+                .collect()
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Redactor(labels={:?})", self.labels())
+    }
+}
@@ -1,54 +1,339 @@
-use log::info;
+use fasttokenizer_core::regex_tokenizer::{cache_size, clear_cache, DEFAULT_SIZE_LIMIT};
+use fasttokenizer_core::{Flags, Mode, Tokenize};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use regex::Regex;
+use pyo3::types::PyDict;
+
+use crate::config;
+use crate::intern::token_list;
+use crate::limits::{self, Limits};
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, py_err, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// One pattern or an ordered list of them.
+#[derive(FromPyObject)]
+pub enum Patterns {
+    One(String),
+    Many(Vec<String>),
+}
 
 /// High-speed regex-based tokenizer.
 ///
 /// This class provides an implementation of a Rust-accelerated tokenizer
 /// that leverages optimized regex processing for extremely fast token extraction.
-#[pyclass]
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
 pub struct RegexTokenizer {
-    /// The regex pattern used for tokenization.
-    pattern: Regex,
+    inner: fasttokenizer_core::RegexTokenizer,
+    /// Vocabulary used by `encode` and `decode`.
+    vocab: Shared<Option<Vocab>>,
+}
+
+impl Tokenizer for RegexTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.inner.tokenize_with_offsets(text)
+    }
+
+    fn try_tokenize_with_offsets(&self, text: &str) -> Result<Vec<Token>, String> {
+        Ok(self.inner.try_tokenize_with_offsets(text)?)
+    }
+
+    fn try_tokenize(&self, text: &str) -> Result<Vec<String>, String> {
+        Ok(self.inner.try_tokenize(text)?)
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.inner.token_spans(text)
+    }
+
+    fn try_token_spans(&self, text: &str) -> Result<Vec<(usize, usize)>, String> {
+        Ok(self.inner.try_token_spans(text)?)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
 }
 
+tokenizer_pymethods!(RegexTokenizer, encode_batch);
+
 #[pymethods]
 impl RegexTokenizer {
-    /// Create a new RegexTokenizer with the given pattern
+    /// Create a new RegexTokenizer.
+    ///
+    /// Args:
+    ///     pattern (str | List[str]): The pattern, or patterns tried in
+    ///         order with the first match at each position winning.
+    ///     mode (str): `"find"` makes every match a token, `"split"` treats
+    ///         the pattern as the delimiter between tokens, and
+    ///         `"captures"` emits the named capture groups of every match as
+    ///         typed tokens (see `tokenize_with_types`).
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    ///     case_insensitive (bool): Match letters regardless of case.
+    ///     multi_line (bool): `^` and `$` match at line starts and ends.
+    ///     dot_matches_new_line (bool): `.` also matches `\n`.
+    ///     unicode (bool): Unicode-aware classes such as `\w`, `\d` and
+    ///         `\b`; turn off to restrict them to ASCII.
+    ///     ignore_whitespace (bool): Verbose mode, ignoring whitespace and
+    ///         `#` comments in the pattern.
+    ///     size_limit (int, optional): Maximum size in bytes of each compiled
+    ///         pattern, 10 MiB by default. Larger patterns raise `ValueError`
+    ///         here instead of slowing down tokenization.
+    ///     max_input_length (int, optional): Longest input in UTF-8 bytes;
+    ///         longer ones raise `ValueError` before any matching.
+    ///     max_token_length (int, optional): Longest token in UTF-8 bytes,
+    ///         e.g. to stop a greedy pattern from returning huge tokens.
+    ///     max_matches (int, optional): Most pattern matches scanned in one
+    ///         input; scanning stops with a `ValueError` beyond it.
     #[new]
-    fn new(pattern: &str) -> PyResult<Self> {
-        match Regex::new(pattern) {
-            Ok(regex) => Ok(RegexTokenizer { pattern: regex }),
-            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid regex pattern: {}",
-                e
-            ))),
-        }
+    #[pyo3(signature = (
+        pattern,
+        mode="find",
+        vocab=None,
+        case_insensitive=false,
+        multi_line=false,
+        dot_matches_new_line=false,
+        unicode=true,
+        ignore_whitespace=false,
+        size_limit=None,
+        max_input_length=None,
+        max_token_length=None,
+        max_matches=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        pattern: Patterns,
+        mode: &str,
+        vocab: Option<PyRef<'_, Vocab>>,
+        case_insensitive: bool,
+        multi_line: bool,
+        dot_matches_new_line: bool,
+        unicode: bool,
+        ignore_whitespace: bool,
+        size_limit: Option<usize>,
+        max_input_length: Option<usize>,
+        max_token_length: Option<usize>,
+        max_matches: Option<usize>,
+    ) -> PyResult<Self> {
+        let flags = Flags {
+            case_insensitive,
+            multi_line,
+            dot_matches_new_line,
+            unicode,
+            ignore_whitespace,
+            size_limit: size_limit.unwrap_or(DEFAULT_SIZE_LIMIT),
+        };
+        let patterns = match pattern {
+            Patterns::One(pattern) => vec![pattern],
+            Patterns::Many(patterns) => patterns,
+        };
+        let limits = Limits {
+            max_input_length,
+            max_token_length,
+            max_matches,
+        };
+        let mode = Mode::parse(mode).map_err(py_err)?;
+        Ok(RegexTokenizer {
+            inner: fasttokenizer_core::RegexTokenizer::new(&patterns, mode, flags, limits)
+                .map_err(py_err)?,
+            vocab: vocab.map(|v| v.clone()).into(),
+        })
+    }
+
+    /// Constructor arguments recreating this tokenizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let flags = self.inner.flags();
+        let limits = self.inner.limits();
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("pattern", self.inner.patterns())?;
+        kwargs.set_item("mode", self.inner.mode().name())?;
+        kwargs.set_item("vocab", Option::clone(&self.vocab.load()))?;
+        kwargs.set_item("case_insensitive", flags.case_insensitive)?;
+        kwargs.set_item("multi_line", flags.multi_line)?;
+        kwargs.set_item("dot_matches_new_line", flags.dot_matches_new_line)?;
+        kwargs.set_item("unicode", flags.unicode)?;
+        kwargs.set_item("ignore_whitespace", flags.ignore_whitespace)?;
+        kwargs.set_item("size_limit", flags.size_limit)?;
+        limits::set_items(&limits, &kwargs)?;
+        kwargs.set_item("max_matches", limits.max_matches)?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the tokenizer's configuration to JSON.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a tokenizer from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
     }
 
     /// Tokenize the input string using the provided regex pattern.
     ///
     /// Args:
-    ///     input (str): The input string to be tokenized.
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
     ///
     /// Returns:
     ///     List[str]: A list of extracted tokens.
-    fn tokenize(&self, input: &str) -> PyResult<Vec<String>> {
-        info!("Tokenizing input string...");
-        let tokens: Vec<String> = self
-            .pattern
-            .find_iter(input)
-            .map(|m| m.as_str().to_string())
-            .collect();
-        Ok(tokens)
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        let _span = tracing::trace_span!("regex_tokenize", bytes = input.len()).entered();
+        let tokens = self.try_tokenize(input).map_err(PyValueError::new_err)?;
+        token_list(py, tokens, intern)
+    }
+
+    /// Tokenize and return `(token, type)` pairs. In `captures` mode the
+    /// type is the name of the capture group; otherwise, and for matches
+    /// without a named group, it is `None`.
+    fn tokenize_with_types(&self, input: &str) -> PyResult<Vec<(String, Option<String>)>> {
+        Ok(self
+            .inner
+            .scan(input, &self.inner.limits())
+            .map_err(py_err)?
+            .into_iter()
+            .map(|(token, kind)| (token.text, kind))
+            .collect())
+    }
+
+    /// Tokenize the input string and report where each token was found.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    ///
+    /// Returns:
+    ///     List[Tuple[str, int, int]]: `(token, start, end)` in the chosen unit.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        let tokens = self
+            .try_tokenize_with_offsets(input)
+            .map_err(PyValueError::new_err)?;
+        offsets(input, tokens, unit)
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        let vocab = require_vocab(&self.vocab)?;
+        self.try_tokenize(input)
+            .and_then(|tokens| vocab.encode_tokens(&tokens))
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Turn ids back into text, joining the tokens with single spaces.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        require_vocab(&self.vocab)?.decode_text(&ids)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
     }
 
     /// Get the regex pattern used for tokenization.
     ///
     /// Returns:
-    ///     str: The regex pattern as a string.
+    ///     str: The regex pattern as a string; a pattern list is joined
+    ///     with `|`.
     fn get_pattern(&self) -> PyResult<String> {
-        Ok(self.pattern.as_str().to_string())
+        Ok(self.inner.patterns().join("|"))
+    }
+
+    /// Number of compiled patterns in the process-wide pattern cache.
+    #[staticmethod]
+    fn cache_size() -> usize {
+        cache_size()
+    }
+
+    /// Empty the process-wide pattern cache. Existing tokenizers keep their
+    /// compiled patterns.
+    #[staticmethod]
+    fn clear_cache() {
+        clear_cache();
+    }
+
+    /// The flags the patterns were compiled with.
+    #[getter]
+    fn flags<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let flags = self.inner.flags();
+        let dict = PyDict::new(py);
+        dict.set_item("case_insensitive", flags.case_insensitive)?;
+        dict.set_item("multi_line", flags.multi_line)?;
+        dict.set_item("dot_matches_new_line", flags.dot_matches_new_line)?;
+        dict.set_item("unicode", flags.unicode)?;
+        dict.set_item("ignore_whitespace", flags.ignore_whitespace)?;
+        dict.set_item("size_limit", flags.size_limit)?;
+        Ok(dict)
+    }
+
+    /// The limits every call is checked against, `None` where unlimited.
+    #[getter]
+    fn limits<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let limits = self.inner.limits();
+        let dict = PyDict::new(py);
+        limits::set_items(&limits, &dict)?;
+        dict.set_item("max_matches", limits.max_matches)?;
+        Ok(dict)
+    }
+
+    /// The patterns in priority order.
+    #[getter]
+    fn patterns(&self) -> Vec<String> {
+        self.inner.patterns()
+    }
+
+    /// The tokenization mode: `"find"`, `"split"` or `"captures"`.
+    #[getter]
+    fn mode(&self) -> &'static str {
+        self.inner.mode().name()
     }
 
     /// Class documentation for Python
@@ -0,0 +1,128 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use unicode_script::{Script, ScriptExtension, UnicodeScript};
+
+/// Parse a script by its full name (`"Latin"`) or four-letter ISO 15924
+/// code in any case (`"latn"`).
+fn parse_script(name: &str) -> PyResult<Script> {
+    let code = name
+        .char_indices()
+        .map(|(i, c)| {
+            if i == 0 {
+                c.to_ascii_uppercase()
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect::<String>();
+    Script::from_full_name(name)
+        .or_else(|| Script::from_short_name(&code))
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown script '{}'", name)))
+}
+
+/// Keeps characters of a set of scripts, dropping or replacing the rest.
+///
+/// Characters shared between scripts, such as digits, punctuation and
+/// combining marks, are always kept, except those that Unicode only lists
+/// for scripts outside the set (the Arabic comma, say).
+#[derive(Clone, Debug)]
+pub struct ScriptFilter {
+    allowed: ScriptExtension,
+    /// The allowed scripts as given.
+    pub names: Vec<String>,
+    pub replacement: Option<String>,
+}
+
+impl ScriptFilter {
+    pub fn new(allowed: &[String], replacement: Option<&str>) -> PyResult<Self> {
+        if allowed.is_empty() {
+            return Err(PyValueError::new_err("allowed scripts must not be empty"));
+        }
+        let mut scripts = ScriptExtension::from(parse_script(&allowed[0])?);
+        for name in &allowed[1..] {
+            scripts = scripts.union(parse_script(name)?.into());
+        }
+        Ok(ScriptFilter {
+            allowed: scripts,
+            names: allowed.to_vec(),
+            replacement: replacement.map(str::to_string),
+        })
+    }
+
+    pub fn keeps(&self, c: char) -> bool {
+        !c.script_extension().intersection(self.allowed).is_empty()
+    }
+
+    /// Push `c`, or its replacement when its script is not allowed.
+    pub fn push(&self, c: char, out: &mut String) {
+        if self.keeps(c) {
+            out.push(c);
+        } else if let Some(replacement) = &self.replacement {
+            out.push_str(replacement);
+        }
+    }
+
+    /// `text` with disallowed characters dropped or replaced, borrowed when
+    /// every character is allowed.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if text.chars().all(|c| self.keeps(c)) {
+            return Cow::Borrowed(text);
+        }
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            self.push(c, &mut out);
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Report the scripts `text` is written in, as proportions of its
+/// characters.
+///
+/// Characters shared between scripts (spaces, digits, punctuation, emoji and
+/// combining marks) are not counted; unassigned and private-use characters
+/// are reported as `"Unknown"`.
+///
+/// Returns:
+///     List[Tuple[str, float]]: `(script, proportion)`, most frequent first.
+#[pyfunction]
+pub fn detect_scripts(text: &str) -> Vec<(&'static str, f64)> {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for c in text.chars() {
+        let script = c.script();
+        if !matches!(script, Script::Common | Script::Inherited) {
+            *counts.entry(script).or_default() += 1;
+        }
+    }
+    let total: usize = counts.values().sum();
+    let mut scripts: Vec<_> = counts
+        .into_iter()
+        .map(|(script, count)| (script.full_name(), count))
+        .collect();
+    scripts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    scripts
+        .into_iter()
+        .map(|(name, count)| (name, count as f64 / total as f64))
+        .collect()
+}
+
+/// Drop the characters of `text` outside the `allowed` scripts, or replace
+/// each with `replacement`.
+///
+/// Args:
+///     text (str): Text to filter.
+///     allowed (List[str]): Script names (`"Latin"`) or ISO 15924 codes
+///         (`"Cyrl"`). Characters shared between scripts are kept.
+///     replacement (str, optional): Put this in place of every dropped
+///         character.
+#[pyfunction]
+#[pyo3(signature = (text, allowed, replacement=None))]
+pub fn filter_scripts<'a>(
+    text: &'a str,
+    allowed: Vec<String>,
+    replacement: Option<&str>,
+) -> PyResult<Cow<'a, str>> {
+    Ok(ScriptFilter::new(&allowed, replacement)?.apply(text))
+}
@@ -0,0 +1,220 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashSet;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+
+/// Abbreviations (lowercase, without the final period) after which a period
+/// does not end a sentence.
+pub const ABBREVIATIONS: &[&str] = &[
+    "a.m", "al", "apr", "approx", "assn", "aug", "ave", "bros", "capt", "cf", "co", "col", "corp",
+    "dec", "dept", "dr", "e.g", "est", "feb", "fig", "figs", "gen", "gov", "i.e", "inc", "jan",
+    "jr", "jul", "jun", "lt", "ltd", "mar", "mr", "mrs", "ms", "mt", "no", "nov", "nos", "oct",
+    "p.m", "pp", "prof", "rep", "rev", "sen", "sep", "sept", "sgt", "sr", "st", "u.k", "u.s",
+    "vol", "vols", "vs",
+];
+
+/// Splits text into sentences using the Unicode sentence boundaries of
+/// UAX #29, then undoes boundaries after known abbreviations (`e.g.`,
+/// `Dr.`) and initials (`J. Smith`).
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct SentenceSplitter {
+    abbreviations: Arc<HashSet<String>>,
+    split_on_newlines: bool,
+}
+
+impl SentenceSplitter {
+    /// Whether the sentence candidate `text` ends with an abbreviation or an
+    /// initial rather than a real sentence end.
+    fn ends_with_abbreviation(&self, text: &str) -> bool {
+        let text = text.trim_end();
+        let Some(stem) = text.strip_suffix('.') else {
+            return false;
+        };
+        let word = stem
+            .rsplit(|c: char| c.is_whitespace() || matches!(c, '(' | '[' | '"' | '\''))
+            .next()
+            .unwrap_or_default();
+        let mut chars = word.chars();
+        let initial = matches!((chars.next(), chars.next()), (Some(c), None) if c.is_uppercase());
+        initial || self.abbreviations.contains(&word.to_lowercase())
+    }
+
+    /// Byte ranges of the sentences of `text`, without surrounding whitespace.
+    pub fn sentences(&self, text: &str) -> Vec<(usize, usize)> {
+        // Single line breaks become spaces of the same length, so offsets
+        // stay valid while wrapped lines are not split apart.
+        let joined;
+        let source = if self.split_on_newlines {
+            text
+        } else {
+            joined = join_lines(text);
+            &joined
+        };
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        let mut merge = false;
+        for (start, sentence) in source.split_sentence_bound_indices() {
+            let end = start + sentence.len();
+            match ranges.last_mut() {
+                Some(last) if merge => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+            merge = self.ends_with_abbreviation(sentence);
+        }
+        ranges
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let piece = &text[start..end];
+                let trimmed_start = start + (piece.len() - piece.trim_start().len());
+                let trimmed_end = start + piece.trim_end().len();
+                (trimmed_start < trimmed_end).then_some((trimmed_start, trimmed_end))
+            })
+            .collect()
+    }
+}
+
+impl Default for SentenceSplitter {
+    fn default() -> Self {
+        SentenceSplitter::new(None, true, false)
+    }
+}
+
+/// Replace line breaks that are not part of a blank line with spaces.
+fn join_lines(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        if c == '\n' {
+            let before = text[..i].trim_end_matches([' ', '\t', '\r']);
+            let after = text[i + 1..].trim_start_matches([' ', '\t', '\r']);
+            if !before.ends_with('\n') && !after.starts_with('\n') && !before.is_empty() {
+                out.push(' ');
+                continue;
+            }
+        } else if c == '\r' && bytes.get(i + 1) == Some(&b'\n') {
+            out.push(' ');
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+impl Tokenizer for SentenceSplitter {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.sentences(text)
+            .into_iter()
+            .map(|(start, end)| Token::new(&text[start..end], start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.sentences(text)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+}
+
+tokenizer_pymethods!(SentenceSplitter: benchmark);
+
+#[pymethods]
+impl SentenceSplitter {
+    /// Create a sentence splitter.
+    ///
+    /// Args:
+    ///     abbreviations (Iterable[str], optional): Further abbreviations,
+    ///         with or without the final period, e.g. `["approx.", "Ph.D"]`.
+    ///     use_default_abbreviations (bool): Include the built-in English
+    ///         abbreviations such as `e.g.`, `Dr.` and `Jan.`.
+    ///     split_on_newlines (bool): Treat every line break as a sentence
+    ///         end. By default only blank lines are, so wrapped lines are
+    ///         joined.
+    #[new]
+    #[pyo3(signature = (abbreviations=None, use_default_abbreviations=true, split_on_newlines=false))]
+    fn new(
+        abbreviations: Option<Vec<String>>,
+        use_default_abbreviations: bool,
+        split_on_newlines: bool,
+    ) -> Self {
+        let mut set: HashSet<String> = HashSet::new();
+        if use_default_abbreviations {
+            set.extend(ABBREVIATIONS.iter().map(|a| a.to_string()));
+        }
+        set.extend(
+            abbreviations
+                .into_iter()
+                .flatten()
+                .map(|a| a.trim_end_matches('.').to_lowercase()),
+        );
+        SentenceSplitter {
+            abbreviations: Arc::new(set),
+            split_on_newlines,
+        }
+    }
+
+    /// Constructor arguments recreating this splitter, for `pickle` and
+    /// `copy`. The abbreviations include the built-in ones in use.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(
+            "abbreviations",
+            self.abbreviations.iter().collect::<Vec<_>>(),
+        )?;
+        kwargs.set_item("use_default_abbreviations", false)?;
+        kwargs.set_item("split_on_newlines", self.split_on_newlines)?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the splitter's configuration to JSON.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a splitter from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
+    }
+
+    /// Split `text` into sentences.
+    ///
+    /// Returns:
+    ///     List[str]: Sentences without surrounding whitespace.
+    fn split(&self, text: &str) -> Vec<String> {
+        self.sentences(text)
+            .into_iter()
+            .map(|(start, end)| text[start..end].to_string())
+            .collect()
+    }
+
+    /// Split several texts at once, releasing the GIL while working.
+    fn split_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<Vec<String>> {
+        py.detach(|| Tokenizer::tokenize_batch(self, &texts))
+    }
+
+    /// Split `text` into `(sentence, start, end)` tuples.
+    ///
+    /// Args:
+    ///     text (str): The text to split.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (text, unit="char"))]
+    fn split_with_offsets(&self, text: &str, unit: &str) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(text, Tokenizer::tokenize_with_offsets(self, text), unit)
+    }
+
+    /// Join sentences back into one text.
+    fn detokenize(&self, sentences: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &sentences)
+    }
+}
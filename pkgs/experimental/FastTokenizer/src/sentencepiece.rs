@@ -0,0 +1,1123 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use spm_precompiled::Precompiled;
+use std::collections::HashMap;
+use std::fs;
+use std::string::FromUtf8Error;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::aligned::AlignedString;
+use crate::config;
+use crate::detokenizer::clean_up_tokenization;
+use crate::intern::token_list;
+use crate::random::{entropy, Rng};
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::{decode_error, Text, Utf8Errors};
+
+/// SentencePiece's whitespace marker.
+const SPACE: char = '\u{2581}';
+
+/// Penalty applied to unknown characters in the unigram lattice, as in SentencePiece.
+const UNK_PENALTY: f32 = 10.0;
+
+/// Minimal protobuf wire-format reader, enough for `sentencepiece_model.proto`.
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+enum Field<'a> {
+    Varint(u64),
+    Fixed32(u32),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        ProtoReader { buf, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos).ok_or("Truncated varint")?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Varint too long".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).ok_or("Truncated field")?;
+        let bytes = self.buf.get(self.pos..end).ok_or("Truncated field")?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, Field<'a>)>, String> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let field = match key & 0x7 {
+            0 => Field::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                return self.next_field();
+            }
+            2 => {
+                let len = self.varint()? as usize;
+                Field::Bytes(self.take(len)?)
+            }
+            5 => {
+                let bytes = self.take(4)?;
+                Field::Fixed32(u32::from_le_bytes(bytes.try_into().expect("4 bytes")))
+            }
+            wire => return Err(format!("Unsupported wire type {}", wire)),
+        };
+        Ok(Some((key >> 3, field)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PieceType {
+    Normal,
+    Unknown,
+    Control,
+    UserDefined,
+    Unused,
+    Byte,
+}
+
+impl PieceType {
+    fn from_proto(value: u64) -> Self {
+        match value {
+            2 => PieceType::Unknown,
+            3 => PieceType::Control,
+            4 => PieceType::UserDefined,
+            5 => PieceType::Unused,
+            6 => PieceType::Byte,
+            _ => PieceType::Normal,
+        }
+    }
+
+    fn to_proto(self) -> u64 {
+        match self {
+            PieceType::Normal => 1,
+            PieceType::Unknown => 2,
+            PieceType::Control => 3,
+            PieceType::UserDefined => 4,
+            PieceType::Unused => 5,
+            PieceType::Byte => 6,
+        }
+    }
+
+    /// Whether pieces of this type can be produced from input text.
+    fn matchable(self) -> bool {
+        matches!(self, PieceType::Normal | PieceType::UserDefined)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ModelType {
+    Unigram,
+    Bpe,
+}
+
+/// The parts of a SentencePiece `ModelProto` used for encoding and decoding.
+struct ModelSpec {
+    pieces: Vec<(String, f32, PieceType)>,
+    model_type: ModelType,
+    byte_fallback: bool,
+    unk_id: Option<u32>,
+    bos_id: Option<u32>,
+    eos_id: Option<u32>,
+    pad_id: Option<u32>,
+    precompiled_charsmap: Vec<u8>,
+    add_dummy_prefix: bool,
+    remove_extra_whitespaces: bool,
+    escape_whitespaces: bool,
+}
+
+fn optional_id(value: i64) -> Option<u32> {
+    u32::try_from(value).ok()
+}
+
+fn parse_piece(bytes: &[u8]) -> Result<(String, f32, PieceType), String> {
+    let mut reader = ProtoReader::new(bytes);
+    let (mut piece, mut score, mut kind) = (String::new(), 0.0, PieceType::Normal);
+    while let Some((tag, field)) = reader.next_field()? {
+        match (tag, field) {
+            (1, Field::Bytes(b)) => {
+                piece = String::from_utf8(b.to_vec()).map_err(|_| "Piece is not UTF-8")?
+            }
+            (2, Field::Fixed32(bits)) => score = f32::from_bits(bits),
+            (3, Field::Varint(v)) => kind = PieceType::from_proto(v),
+            _ => {}
+        }
+    }
+    Ok((piece, score, kind))
+}
+
+fn parse_model(bytes: &[u8]) -> Result<ModelSpec, String> {
+    let mut spec = ModelSpec {
+        pieces: Vec::new(),
+        model_type: ModelType::Unigram,
+        byte_fallback: false,
+        unk_id: Some(0),
+        bos_id: Some(1),
+        eos_id: Some(2),
+        pad_id: None,
+        precompiled_charsmap: Vec::new(),
+        add_dummy_prefix: true,
+        remove_extra_whitespaces: true,
+        escape_whitespaces: true,
+    };
+    let mut reader = ProtoReader::new(bytes);
+    while let Some((tag, field)) = reader.next_field()? {
+        match (tag, field) {
+            (1, Field::Bytes(b)) => spec.pieces.push(parse_piece(b)?),
+            (2, Field::Bytes(b)) => {
+                let mut trainer = ProtoReader::new(b);
+                while let Some((tag, field)) = trainer.next_field()? {
+                    match (tag, field) {
+                        (3, Field::Varint(v)) => {
+                            spec.model_type = match v {
+                                1 => ModelType::Unigram,
+                                2 => ModelType::Bpe,
+                                _ => return Err(format!("Unsupported model type {}", v)),
+                            }
+                        }
+                        (35, Field::Varint(v)) => spec.byte_fallback = v != 0,
+                        (40, Field::Varint(v)) => spec.unk_id = optional_id(v as i64),
+                        (41, Field::Varint(v)) => spec.bos_id = optional_id(v as i64),
+                        (42, Field::Varint(v)) => spec.eos_id = optional_id(v as i64),
+                        (43, Field::Varint(v)) => spec.pad_id = optional_id(v as i64),
+                        _ => {}
+                    }
+                }
+            }
+            (3, Field::Bytes(b)) => {
+                let mut normalizer = ProtoReader::new(b);
+                while let Some((tag, field)) = normalizer.next_field()? {
+                    match (tag, field) {
+                        (2, Field::Bytes(charsmap)) => {
+                            spec.precompiled_charsmap = charsmap.to_vec()
+                        }
+                        (3, Field::Varint(v)) => spec.add_dummy_prefix = v != 0,
+                        (4, Field::Varint(v)) => spec.remove_extra_whitespaces = v != 0,
+                        (5, Field::Varint(v)) => spec.escape_whitespaces = v != 0,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if spec.pieces.is_empty() {
+        return Err("Model has no pieces".to_string());
+    }
+    Ok(spec)
+}
+
+/// Apply a SentencePiece precompiled charsmap, the way `sentencepiece` does:
+/// whole grapheme clusters first, then character by character.
+pub fn apply_charsmap(charsmap: &Precompiled, text: &AlignedString) -> AlignedString {
+    let mut out = AlignedString::default();
+    for (offset, grapheme) in text.text.grapheme_indices(true) {
+        if grapheme.len() < 6 {
+            if let Some(norm) = charsmap.transform(grapheme) {
+                out.push_str(norm, text.span(offset, offset + grapheme.len()));
+                continue;
+            }
+        }
+        for (i, c) in grapheme.char_indices() {
+            let span = text.span(offset + i, offset + i + c.len_utf8());
+            match charsmap.transform(&grapheme[i..i + c.len_utf8()]) {
+                Some(norm) => out.push_str(norm, span),
+                None => out.push(c, span),
+            }
+        }
+    }
+    out
+}
+
+/// Parse `<0xAB>` byte-fallback piece names.
+fn byte_piece_value(piece: &str) -> Option<u8> {
+    let hex = piece.strip_prefix("<0x")?.strip_suffix('>')?;
+    if hex.len() != 2 {
+        return None;
+    }
+    u8::from_str_radix(hex, 16).ok()
+}
+
+/// `ln(exp(a) + exp(b))` without overflow.
+fn log_add(a: f64, b: f64) -> f64 {
+    let (high, low) = if a > b { (a, b) } else { (b, a) };
+    if low == f64::NEG_INFINITY {
+        return high;
+    }
+    high + (low - high).exp().ln_1p()
+}
+
+/// A loaded unigram or BPE SentencePiece model.
+struct SentencePieceModel {
+    pieces: Vec<(String, f32, PieceType)>,
+    vocab: HashMap<String, u32>,
+    model_type: ModelType,
+    unk_id: u32,
+    bos_id: Option<u32>,
+    eos_id: Option<u32>,
+    pad_id: Option<u32>,
+    byte_fallback: bool,
+    byte_ids: [Option<u32>; 256],
+    charsmap: Option<Precompiled>,
+    add_dummy_prefix: bool,
+    remove_extra_whitespaces: bool,
+    escape_whitespaces: bool,
+    /// Longest matchable piece, in characters.
+    max_piece_chars: usize,
+    min_score: f32,
+    max_score: f32,
+}
+
+impl SentencePieceModel {
+    fn new(spec: ModelSpec) -> Result<Self, String> {
+        let unk_id = spec
+            .unk_id
+            .or_else(|| {
+                spec.pieces
+                    .iter()
+                    .position(|p| p.2 == PieceType::Unknown)
+                    .map(|i| i as u32)
+            })
+            .filter(|&id| (id as usize) < spec.pieces.len())
+            .ok_or("Model has no unknown piece")?;
+        let charsmap = if spec.precompiled_charsmap.is_empty() {
+            None
+        } else {
+            Some(
+                Precompiled::from(&spec.precompiled_charsmap)
+                    .map_err(|e| format!("Invalid precompiled charsmap: {}", e))?,
+            )
+        };
+        let mut byte_ids = [None; 256];
+        let mut vocab = HashMap::with_capacity(spec.pieces.len());
+        let (mut max_piece_chars, mut min_score, mut max_score) = (1, f32::MAX, f32::MIN);
+        for (id, (piece, score, kind)) in spec.pieces.iter().enumerate() {
+            vocab.entry(piece.clone()).or_insert(id as u32);
+            if *kind == PieceType::Byte {
+                if let Some(b) = byte_piece_value(piece) {
+                    byte_ids[b as usize] = Some(id as u32);
+                }
+            }
+            if kind.matchable() {
+                max_piece_chars = max_piece_chars.max(piece.chars().count());
+                min_score = min_score.min(*score);
+                max_score = max_score.max(*score);
+            }
+        }
+        let known = |id: Option<u32>| id.filter(|&id| (id as usize) < spec.pieces.len());
+        let (bos_id, eos_id, pad_id) = (known(spec.bos_id), known(spec.eos_id), known(spec.pad_id));
+        Ok(SentencePieceModel {
+            pieces: spec.pieces,
+            vocab,
+            model_type: spec.model_type,
+            unk_id,
+            bos_id,
+            eos_id,
+            pad_id,
+            byte_fallback: spec.byte_fallback,
+            byte_ids,
+            charsmap,
+            add_dummy_prefix: spec.add_dummy_prefix,
+            remove_extra_whitespaces: spec.remove_extra_whitespaces,
+            escape_whitespaces: spec.escape_whitespaces,
+            max_piece_chars,
+            min_score: if min_score == f32::MAX {
+                0.0
+            } else {
+                min_score
+            },
+            max_score: if max_score == f32::MIN {
+                0.0
+            } else {
+                max_score
+            },
+        })
+    }
+
+    /// Look up a piece that may be produced from text.
+    fn matchable_id(&self, piece: &str) -> Option<u32> {
+        self.vocab
+            .get(piece)
+            .copied()
+            .filter(|&id| self.pieces[id as usize].2.matchable())
+    }
+
+    /// Normalize text the way SentencePiece does, keeping the original span
+    /// of every normalized byte.
+    fn normalize(&self, text: &str) -> AlignedString {
+        let mut aligned = AlignedString::new(text, 0);
+        if let Some(charsmap) = &self.charsmap {
+            aligned = apply_charsmap(charsmap, &aligned);
+        }
+        if self.remove_extra_whitespaces {
+            let mut collapsed = AlignedString::default();
+            for (_, c, span) in aligned.chars() {
+                if c == ' ' && collapsed.text.chars().next_back().is_none_or(|p| p == ' ') {
+                    continue;
+                }
+                collapsed.push(c, span);
+            }
+            if collapsed.text.ends_with(' ') {
+                collapsed.text.pop();
+                collapsed.spans.pop();
+            }
+            aligned = collapsed;
+        }
+        if self.add_dummy_prefix && !aligned.is_empty() {
+            aligned.prepend(" ");
+        }
+        if self.escape_whitespaces {
+            aligned = aligned.map_chars(|c, out| out.push(if c == ' ' { SPACE } else { c }));
+        }
+        aligned
+    }
+
+    /// Call `f(end, id, score)` for every piece of the unigram lattice that
+    /// starts at char `start`, with `end` in chars. A character no piece
+    /// covers alone gets an unknown (`None`) piece with a penalized score.
+    fn for_each_piece(
+        &self,
+        text: &str,
+        boundaries: &[usize],
+        start: usize,
+        mut f: impl FnMut(usize, Option<u32>, f32),
+    ) {
+        let n = boundaries.len() - 1;
+        let mut has_single = false;
+        for end in start + 1..=n.min(start + self.max_piece_chars) {
+            let piece = &text[boundaries[start]..boundaries[end]];
+            let Some(id) = self.matchable_id(piece) else {
+                continue;
+            };
+            let (_, score, kind) = &self.pieces[id as usize];
+            let score = if *kind == PieceType::UserDefined {
+                (end - start) as f32 * self.max_score - 0.1
+            } else {
+                *score
+            };
+            has_single |= end == start + 1;
+            f(end, Some(id), score);
+        }
+        if !has_single {
+            f(start + 1, None, self.min_score - UNK_PENALTY);
+        }
+    }
+
+    /// Byte offsets of the characters of `text`, plus its length.
+    fn boundaries(text: &str) -> Vec<usize> {
+        text.char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()))
+            .collect()
+    }
+
+    /// Best segmentation of normalized text under the unigram model (Viterbi).
+    /// Unknown characters are returned with `None` ids.
+    fn unigram_segments(&self, text: &str) -> Vec<(Option<u32>, usize, usize)> {
+        let boundaries = Self::boundaries(text);
+        let n = boundaries.len() - 1;
+        // best[i] = (score, previous char index, id) of the best path ending at char i.
+        let mut best: Vec<Option<(f32, usize, Option<u32>)>> = vec![None; n + 1];
+        best[0] = Some((0.0, 0, None));
+        for start in 0..n {
+            let Some((base, _, _)) = best[start] else {
+                continue;
+            };
+            self.for_each_piece(text, &boundaries, start, |end, id, score| {
+                let candidate = base + score;
+                if best[end].is_none_or(|(s, _, _)| candidate > s) {
+                    best[end] = Some((candidate, start, id));
+                }
+            });
+        }
+        let mut segments = Vec::new();
+        let mut end = n;
+        while end > 0 {
+            let (_, start, id) = best[end].expect("every position is reachable");
+            segments.push((id, boundaries[start], boundaries[end]));
+            end = start;
+        }
+        segments.reverse();
+        segments
+    }
+
+    /// A segmentation sampled from the unigram lattice with probability
+    /// proportional to `exp(alpha * score)`, by forward filtering and
+    /// backward sampling, as SentencePiece's subword regularization does.
+    fn sample_unigram_segments(
+        &self,
+        text: &str,
+        alpha: f64,
+        rng: &mut Rng,
+    ) -> Vec<(Option<u32>, usize, usize)> {
+        let boundaries = Self::boundaries(text);
+        let n = boundaries.len() - 1;
+        // forward[i] = log of the summed weight of all paths ending at char i.
+        let mut forward = vec![f64::NEG_INFINITY; n + 1];
+        forward[0] = 0.0;
+        // Pieces ending at each char: (start, id, weighted score).
+        let mut incoming: Vec<Vec<(usize, Option<u32>, f64)>> = vec![Vec::new(); n + 1];
+        for start in 0..n {
+            if forward[start] == f64::NEG_INFINITY {
+                continue;
+            }
+            let base = forward[start];
+            self.for_each_piece(text, &boundaries, start, |end, id, score| {
+                let weight = alpha * score as f64;
+                forward[end] = log_add(forward[end], base + weight);
+                incoming[end].push((start, id, weight));
+            });
+        }
+        let mut segments = Vec::new();
+        let mut end = n;
+        while end > 0 {
+            let pieces = &incoming[end];
+            let mut target = rng.next_f64();
+            let mut chosen = pieces[pieces.len() - 1];
+            for &piece in pieces {
+                target -= (forward[piece.0] + piece.2 - forward[end]).exp();
+                if target < 0.0 {
+                    chosen = piece;
+                    break;
+                }
+            }
+            segments.push((chosen.1, boundaries[chosen.0], boundaries[end]));
+            end = chosen.0;
+        }
+        segments.reverse();
+        segments
+    }
+
+    /// Merge symbols by piece score, the way SentencePiece's BPE model does.
+    /// Words never cross a whitespace marker, so each one is merged separately.
+    /// With `dropout`, each possible merge is skipped with that probability
+    /// at every step.
+    fn bpe_segments(
+        &self,
+        text: &str,
+        mut dropout: Option<(f64, &mut Rng)>,
+    ) -> Vec<(Option<u32>, usize, usize)> {
+        let mut segments = Vec::new();
+        let mut word_starts: Vec<usize> = text
+            .match_indices(SPACE)
+            .map(|(i, _)| i)
+            .filter(|&i| i > 0)
+            .collect();
+        word_starts.insert(0, 0);
+        word_starts.push(text.len());
+        for bounds in word_starts.windows(2) {
+            let mut symbols: Vec<(usize, usize)> = text[bounds[0]..bounds[1]]
+                .char_indices()
+                .map(|(i, c)| (bounds[0] + i, bounds[0] + i + c.len_utf8()))
+                .collect();
+            loop {
+                let mut best: Option<(f32, usize)> = None;
+                for i in 0..symbols.len().saturating_sub(1) {
+                    let merged = &text[symbols[i].0..symbols[i + 1].1];
+                    if let Some(id) = self.matchable_id(merged) {
+                        if let Some((p, rng)) = dropout.as_mut() {
+                            if rng.next_f64() < *p {
+                                continue;
+                            }
+                        }
+                        let score = self.pieces[id as usize].1;
+                        if best.is_none_or(|(s, _)| score > s) {
+                            best = Some((score, i));
+                        }
+                    }
+                }
+                let Some((_, i)) = best else {
+                    break;
+                };
+                symbols[i].1 = symbols[i + 1].1;
+                symbols.remove(i + 1);
+            }
+            segments.extend(
+                symbols
+                    .into_iter()
+                    .map(|(s, e)| (self.matchable_id(&text[s..e]), s, e)),
+            );
+        }
+        segments
+    }
+
+    /// Encode text into ids with byte spans into the original text.
+    fn encode_with_offsets(&self, text: &str) -> Vec<(u32, usize, usize)> {
+        self.encode_sampled(text, None)
+    }
+
+    /// `encode_with_offsets`, sampling the segmentation when `sampling` is
+    /// given: from the unigram lattice smoothed by `alpha`, or for BPE
+    /// models with `alpha` as the dropout probability.
+    fn encode_sampled(
+        &self,
+        text: &str,
+        sampling: Option<(f64, &mut Rng)>,
+    ) -> Vec<(u32, usize, usize)> {
+        let aligned = self.normalize(text);
+        let normalized = aligned.text.as_str();
+        if normalized.is_empty() {
+            return Vec::new();
+        }
+        let segments = match (self.model_type, sampling) {
+            (ModelType::Unigram, None) => self.unigram_segments(normalized),
+            (ModelType::Unigram, Some((alpha, rng))) => {
+                self.sample_unigram_segments(normalized, alpha, rng)
+            }
+            (ModelType::Bpe, dropout) => self.bpe_segments(normalized, dropout),
+        };
+        let mut out: Vec<(u32, usize, usize)> = Vec::with_capacity(segments.len());
+        let mut previous_unk = false;
+        for (id, s, e) in segments {
+            let span = aligned.span(s, e);
+            match id {
+                Some(id) => {
+                    out.push((id, span.0, span.1));
+                    previous_unk = false;
+                }
+                None if self.byte_fallback => {
+                    let bytes = normalized[s..e].bytes();
+                    match bytes
+                        .map(|b| self.byte_ids[b as usize])
+                        .collect::<Option<Vec<u32>>>()
+                    {
+                        Some(ids) => out.extend(ids.into_iter().map(|id| (id, span.0, span.1))),
+                        None => out.push((self.unk_id, span.0, span.1)),
+                    }
+                }
+                None if previous_unk => {
+                    out.last_mut().expect("previous unk token").2 = span.1;
+                }
+                None => {
+                    out.push((self.unk_id, span.0, span.1));
+                    previous_unk = true;
+                }
+            }
+        }
+        out
+    }
+
+    fn decode(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: bool,
+        errors: Utf8Errors,
+    ) -> Result<String, FromUtf8Error> {
+        let mut bytes = Vec::new();
+        for &id in ids {
+            let Some((piece, _, kind)) = self.pieces.get(id as usize) else {
+                continue;
+            };
+            match kind {
+                PieceType::Control if !skip_special_tokens => {
+                    bytes.extend_from_slice(piece.as_bytes())
+                }
+                PieceType::Control | PieceType::Unused => {}
+                PieceType::Unknown => bytes.extend_from_slice(" \u{2047} ".as_bytes()),
+                PieceType::Byte => match byte_piece_value(piece) {
+                    Some(b) => bytes.push(b),
+                    None => bytes.extend_from_slice(piece.as_bytes()),
+                },
+                _ => bytes.extend_from_slice(piece.as_bytes()),
+            }
+        }
+        let text = errors.decode(bytes)?.replace(SPACE, " ");
+        Ok(match text.strip_prefix(' ') {
+            Some(rest) if self.add_dummy_prefix => rest.to_string(),
+            _ => text,
+        })
+    }
+}
+
+/// Tokenizer backed by a SentencePiece unigram or BPE model (T5, XLM-R, Llama, ...).
+///
+/// Loads `.model` files directly, applying the model's own normalization
+/// (precompiled charsmap, whitespace handling, dummy prefix) and byte fallback.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct SentencePieceTokenizer {
+    model: Arc<SentencePieceModel>,
+}
+
+impl SentencePieceTokenizer {
+    fn from_spec(spec: ModelSpec) -> PyResult<Self> {
+        let model = SentencePieceModel::new(spec).map_err(PyValueError::new_err)?;
+        Ok(SentencePieceTokenizer {
+            model: Arc::new(model),
+        })
+    }
+
+    /// A bare unigram model over `(piece, score)` pairs, with none of
+    /// SentencePiece's own normalization, for use behind pipeline stages.
+    pub fn unigram(pieces: Vec<(String, f32)>, unk_id: u32, byte_fallback: bool) -> PyResult<Self> {
+        let pieces = pieces
+            .into_iter()
+            .enumerate()
+            .map(|(id, (piece, score))| {
+                let kind = if id as u32 == unk_id {
+                    PieceType::Unknown
+                } else if byte_fallback && byte_piece_value(&piece).is_some() {
+                    PieceType::Byte
+                } else {
+                    PieceType::Normal
+                };
+                (piece, score, kind)
+            })
+            .collect();
+        Self::from_spec(ModelSpec {
+            pieces,
+            model_type: ModelType::Unigram,
+            byte_fallback,
+            unk_id: Some(unk_id),
+            bos_id: None,
+            eos_id: None,
+            pad_id: None,
+            precompiled_charsmap: Vec::new(),
+            add_dummy_prefix: false,
+            remove_extra_whitespaces: false,
+            escape_whitespaces: false,
+        })
+    }
+
+    /// The sampling parameter of `encode`: any positive smoothing for
+    /// unigram models, a dropout probability for BPE models.
+    fn check_alpha(&self, alpha: Option<f64>) -> PyResult<Option<f64>> {
+        let Some(alpha) = alpha else {
+            return Ok(None);
+        };
+        let valid = match self.model.model_type {
+            ModelType::Unigram => alpha > 0.0 && alpha.is_finite(),
+            ModelType::Bpe => (0.0..=1.0).contains(&alpha),
+        };
+        if !valid {
+            return Err(PyValueError::new_err(format!(
+                "Invalid alpha {} for a {} model",
+                alpha,
+                self.model_type()
+            )));
+        }
+        Ok(Some(alpha))
+    }
+
+    /// Encode one pre-tokenized word as `(id, start, end)` with byte offsets into `word`.
+    pub fn encode_word(&self, word: &str) -> Vec<(u32, usize, usize)> {
+        self.model.encode_with_offsets(word)
+    }
+
+    /// The pieces, normalization and special ids as a JSON object, as
+    /// embedded in tokenizer configurations and bundles. Piece types are
+    /// numbered as in `sentencepiece_model.proto`, and the precompiled
+    /// charsmap is base64 encoded as in HuggingFace's `tokenizer.json`.
+    pub fn to_value(&self) -> Value {
+        let model = &self.model;
+        let pieces: Vec<_> = model
+            .pieces
+            .iter()
+            .map(|(piece, score, kind)| json!([piece, score, kind.to_proto()]))
+            .collect();
+        let charsmap = model.charsmap.as_ref().map(|charsmap| {
+            serde_json::to_value(charsmap).expect("charsmaps serialize")["precompiled_charsmap"]
+                .take()
+        });
+        json!({
+            "pieces": pieces,
+            "model_type": self.model_type(),
+            "byte_fallback": model.byte_fallback,
+            "unk_id": model.unk_id,
+            "bos_id": model.bos_id,
+            "eos_id": model.eos_id,
+            "pad_id": model.pad_id,
+            "precompiled_charsmap": charsmap,
+            "add_dummy_prefix": model.add_dummy_prefix,
+            "remove_extra_whitespaces": model.remove_extra_whitespaces,
+            "escape_whitespaces": model.escape_whitespaces,
+        })
+    }
+
+    /// Recreate a tokenizer from `to_value` output.
+    pub fn from_value(value: &Value) -> PyResult<Self> {
+        let invalid =
+            |e: String| PyValueError::new_err(format!("Invalid SentencePiece model: {}", e));
+        let pieces: Vec<(String, f32, u64)> =
+            serde_json::from_value(value["pieces"].clone()).map_err(|e| invalid(e.to_string()))?;
+        let model_type = match value["model_type"].as_str() {
+            Some("unigram") => ModelType::Unigram,
+            Some("bpe") => ModelType::Bpe,
+            other => return Err(invalid(format!("unsupported model type {:?}", other))),
+        };
+        let precompiled_charsmap = match value["precompiled_charsmap"].as_str() {
+            Some(data) => BASE64.decode(data).map_err(|e| invalid(e.to_string()))?,
+            None => Vec::new(),
+        };
+        let id = |name: &str| value[name].as_u64().map(|id| id as u32);
+        let flag = |name: &str| value[name].as_bool().unwrap_or(false);
+        Self::from_spec(ModelSpec {
+            pieces: pieces
+                .into_iter()
+                .map(|(piece, score, kind)| (piece, score, PieceType::from_proto(kind)))
+                .collect(),
+            model_type,
+            byte_fallback: flag("byte_fallback"),
+            unk_id: id("unk_id"),
+            bos_id: id("bos_id"),
+            eos_id: id("eos_id"),
+            pad_id: id("pad_id"),
+            precompiled_charsmap,
+            add_dummy_prefix: flag("add_dummy_prefix"),
+            remove_extra_whitespaces: flag("remove_extra_whitespaces"),
+            escape_whitespaces: flag("escape_whitespaces"),
+        })
+    }
+
+    fn encode_ids(
+        &self,
+        text: &str,
+        add_bos: bool,
+        add_eos: bool,
+        sampling: Option<(f64, &mut Rng)>,
+    ) -> Vec<u32> {
+        let mut ids = Vec::new();
+        if add_bos {
+            ids.extend(self.model.bos_id);
+        }
+        ids.extend(
+            self.model
+                .encode_sampled(text, sampling)
+                .into_iter()
+                .map(|(id, _, _)| id),
+        );
+        if add_eos {
+            ids.extend(self.model.eos_id);
+        }
+        ids
+    }
+}
+
+impl Tokenizer for SentencePieceTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(id, start, end)| {
+                Token::new(self.model.pieces[id as usize].0.clone(), start, end)
+            })
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        let ids: Vec<u32> = tokens.iter().filter_map(|t| self.token_to_id(t)).collect();
+        self.model
+            .decode(&ids, true, Utf8Errors::Replace)
+            .expect("replacing never fails")
+    }
+}
+
+tokenizer_pymethods!(SentencePieceTokenizer);
+
+#[pymethods]
+impl SentencePieceTokenizer {
+    /// Create a model from `(piece, score)` pairs.
+    ///
+    /// Args:
+    ///     pieces (List[Tuple[str, float]]): Pieces in id order.
+    ///     model_type (str): `"unigram"` or `"bpe"`.
+    ///     unk_id (int): Id of the unknown piece.
+    ///     bos_id (int, optional): Id of the beginning-of-sentence piece.
+    ///     eos_id (int, optional): Id of the end-of-sentence piece.
+    ///     byte_fallback (bool): Encode unknown characters as `<0xXX>` pieces.
+    ///     add_dummy_prefix (bool): Prepend a space to the text before encoding.
+    ///     remove_extra_whitespaces (bool): Strip and collapse runs of spaces.
+    #[new]
+    #[pyo3(signature = (
+        pieces,
+        model_type="unigram",
+        unk_id=0,
+        bos_id=None,
+        eos_id=None,
+        byte_fallback=false,
+        add_dummy_prefix=true,
+        remove_extra_whitespaces=true
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        pieces: Vec<(String, f32)>,
+        model_type: &str,
+        unk_id: u32,
+        bos_id: Option<u32>,
+        eos_id: Option<u32>,
+        byte_fallback: bool,
+        add_dummy_prefix: bool,
+        remove_extra_whitespaces: bool,
+    ) -> PyResult<Self> {
+        let model_type = match model_type {
+            "unigram" => ModelType::Unigram,
+            "bpe" => ModelType::Bpe,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported model type: {}",
+                    other
+                )))
+            }
+        };
+        let pieces = pieces
+            .into_iter()
+            .enumerate()
+            .map(|(id, (piece, score))| {
+                let id = Some(id as u32);
+                let kind = if id == Some(unk_id) {
+                    PieceType::Unknown
+                } else if id == bos_id || id == eos_id {
+                    PieceType::Control
+                } else if byte_fallback && byte_piece_value(&piece).is_some() {
+                    PieceType::Byte
+                } else {
+                    PieceType::Normal
+                };
+                (piece, score, kind)
+            })
+            .collect();
+        Self::from_spec(ModelSpec {
+            pieces,
+            model_type,
+            byte_fallback,
+            unk_id: Some(unk_id),
+            bos_id,
+            eos_id,
+            pad_id: None,
+            precompiled_charsmap: Vec::new(),
+            add_dummy_prefix,
+            remove_extra_whitespaces,
+            escape_whitespaces: true,
+        })
+    }
+
+    /// Load a SentencePiece `.model` file.
+    #[staticmethod]
+    fn from_file(path: &str) -> PyResult<Self> {
+        let data = fs::read(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read model file: {}", e)))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Load a serialized SentencePiece `ModelProto`.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let spec = parse_model(data)
+            .map_err(|e| PyValueError::new_err(format!("Invalid SentencePiece model: {}", e)))?;
+        Self::from_spec(spec)
+    }
+
+    /// Save the pieces, normalization and special ids to a single bundle
+    /// file, to be loaded back with `SentencePieceTokenizer.load`.
+    fn save(slf: &Bound<'_, Self>, path: &str) -> PyResult<()> {
+        config::save(slf.as_any(), path)
+    }
+
+    /// Load a tokenizer saved with `save`.
+    #[staticmethod]
+    fn load<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, Self>> {
+        config::load(py, path)
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into piece ids, optionally adding BOS/EOS.
+    ///
+    /// Args:
+    ///     input (str | bytes): The text to encode.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///     alpha (float, optional): Sample the segmentation for subword
+    ///         regularization, differently on each call. Unigram models
+    ///         draw from all segmentations with probability proportional to
+    ///         `exp(alpha * score)`, so smaller values give more varied
+    ///         output; BPE models use `alpha` as the BPE-dropout
+    ///         probability. `None` encodes deterministically.
+    ///     seed (int, optional): Seed for the sampling; the next
+    ///         `set_global_seed` seed, or random, when not given.
+    #[pyo3(signature = (input, add_bos=false, add_eos=false, errors="strict", alpha=None, seed=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn encode<'py>(
+        &self,
+        input: Text<'py>,
+        add_bos: bool,
+        add_eos: bool,
+        errors: &str,
+        alpha: Option<f64>,
+        seed: Option<u64>,
+    ) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        let alpha = self.check_alpha(alpha)?;
+        let mut rng = Rng::new(seed.unwrap_or_else(entropy));
+        Ok(self.encode_ids(input, add_bos, add_eos, alpha.map(|a| (a, &mut rng))))
+    }
+
+    /// Encode several strings at once, releasing the GIL while working.
+    /// With `alpha`, input `i` is sampled with `seed + i`.
+    #[pyo3(signature = (inputs, add_bos=false, add_eos=false, alpha=None, seed=None))]
+    fn encode_batch(
+        &self,
+        py: Python<'_>,
+        inputs: Vec<String>,
+        add_bos: bool,
+        add_eos: bool,
+        alpha: Option<f64>,
+        seed: Option<u64>,
+    ) -> PyResult<Vec<Vec<u32>>> {
+        let alpha = self.check_alpha(alpha)?;
+        let seed = seed.unwrap_or_else(entropy);
+        Ok(py.detach(|| {
+            inputs
+                .iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let mut rng = Rng::new(seed.wrapping_add(i as u64));
+                    self.encode_ids(text, add_bos, add_eos, alpha.map(|a| (a, &mut rng)))
+                })
+                .collect()
+        }))
+    }
+
+    /// Turn piece ids back into text, mapping byte pieces back to bytes.
+    ///
+    /// Args:
+    ///     ids (List[int]): Piece ids.
+    ///     skip_special_tokens (bool): Leave out control pieces such as
+    ///         `<s>` and `</s>`.
+    ///     clean_up_tokenization_spaces (bool): Remove spaces before
+    ///         punctuation and contractions like `transformers` does.
+    ///     errors (str): What to do with byte sequences that are not valid
+    ///         UTF-8, e.g. a character cut off by generation: `"replace"`
+    ///         them with U+FFFD, `"ignore"` them or raise a
+    ///         `UnicodeDecodeError` (`"strict"`).
+    #[pyo3(signature = (ids, skip_special_tokens=true, clean_up_tokenization_spaces=true, errors="replace"))]
+    fn decode(
+        &self,
+        py: Python<'_>,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+        errors: &str,
+    ) -> PyResult<String> {
+        let errors = Utf8Errors::parse(errors)?;
+        let text = self
+            .model
+            .decode(&ids, skip_special_tokens, errors)
+            .map_err(|e| decode_error(py, e))?;
+        Ok(clean_up_tokenization(text, clean_up_tokenization_spaces))
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.model.vocab.get(token).copied()
+    }
+
+    pub fn id_to_token(&self, id: u32) -> Option<String> {
+        self.model.pieces.get(id as usize).map(|p| p.0.clone())
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.model.pieces.len()
+    }
+
+    /// `"unigram"` or `"bpe"`.
+    #[getter]
+    fn model_type(&self) -> &'static str {
+        match self.model.model_type {
+            ModelType::Unigram => "unigram",
+            ModelType::Bpe => "bpe",
+        }
+    }
+
+    #[getter]
+    fn unk_id(&self) -> u32 {
+        self.model.unk_id
+    }
+
+    #[getter]
+    fn bos_id(&self) -> Option<u32> {
+        self.model.bos_id
+    }
+
+    #[getter]
+    fn eos_id(&self) -> Option<u32> {
+        self.model.eos_id
+    }
+
+    #[getter]
+    fn pad_id(&self) -> Option<u32> {
+        self.model.pad_id
+    }
+}
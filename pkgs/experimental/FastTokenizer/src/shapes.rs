@@ -0,0 +1,184 @@
+use numpy::IntoPyArray;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use unicode_categories::UnicodeCategories;
+
+use crate::tokenizer::extract_tokenizer;
+
+/// The class a character stands for in a shape: `X` for uppercase, `x` for
+/// lowercase and `d` for digits; other characters stand for themselves.
+fn shape_char(c: char) -> char {
+    if c.is_uppercase() {
+        'X'
+    } else if c.is_lowercase() || c.is_alphabetic() {
+        'x'
+    } else if c.is_numeric() {
+        'd'
+    } else {
+        c
+    }
+}
+
+/// The shape of `token`, e.g. `Xxxx` for "John", `dddd` for "20245" and
+/// `xx-xx` for "ab-cd": characters are replaced by their class and runs
+/// of one class are cut to `max_repeat`.
+pub fn shape(token: &str, max_repeat: usize) -> String {
+    let mut shape = String::with_capacity(token.len());
+    let mut last = None;
+    let mut run = 0;
+    for c in token.chars().map(shape_char) {
+        run = if last == Some(c) { run + 1 } else { 1 };
+        last = Some(c);
+        if run <= max_repeat {
+            shape.push(c);
+        }
+    }
+    shape
+}
+
+/// Casing and character class flags of one token, following the `str`
+/// methods of the same names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenFlags {
+    pub is_alpha: bool,
+    pub is_digit: bool,
+    pub is_lower: bool,
+    pub is_upper: bool,
+    pub is_title: bool,
+    pub is_punct: bool,
+}
+
+const FLAG_NAMES: [&str; 6] = [
+    "is_alpha", "is_digit", "is_lower", "is_upper", "is_title", "is_punct",
+];
+
+impl TokenFlags {
+    pub fn of(token: &str) -> Self {
+        let mut flags = TokenFlags {
+            is_alpha: !token.is_empty(),
+            is_digit: !token.is_empty(),
+            is_punct: !token.is_empty(),
+            ..TokenFlags::default()
+        };
+        let (mut upper, mut lower) = (false, false);
+        // As in `str.istitle`: uppercase only after uncased characters,
+        // lowercase only after cased ones.
+        let mut title = true;
+        let mut previous_cased = false;
+        for c in token.chars() {
+            flags.is_alpha &= c.is_alphabetic();
+            flags.is_digit &= c.is_number_decimal_digit();
+            flags.is_punct &= c.is_ascii_punctuation() || c.is_punctuation();
+            let cased = c.is_uppercase() || c.is_lowercase();
+            if c.is_uppercase() {
+                upper = true;
+                title &= !previous_cased;
+            } else if c.is_lowercase() {
+                lower = true;
+                title &= previous_cased;
+            }
+            previous_cased = cased;
+        }
+        flags.is_lower = lower && !upper;
+        flags.is_upper = upper && !lower;
+        flags.is_title = upper && title;
+        flags
+    }
+
+    fn values(self) -> [bool; 6] {
+        [
+            self.is_alpha,
+            self.is_digit,
+            self.is_lower,
+            self.is_upper,
+            self.is_title,
+            self.is_punct,
+        ]
+    }
+}
+
+/// Tokens given directly, or text for `token_shapes` to tokenize.
+#[derive(FromPyObject)]
+pub enum ShapeInput {
+    Text(String),
+    Tokens(Vec<String>),
+}
+
+/// Shape strings and casing flags of tokens, as parallel arrays for
+/// sequence-labeling features.
+///
+/// Args:
+///     tokens (List[str] | str): The tokens, or text that `tokenizer` splits
+///         in the same pass.
+///     tokenizer (optional): Any FastTokenizer tokenizer; required when
+///         `tokens` is a string.
+///     max_repeat (int): Longest run of one character class kept in a
+///         shape, 4 as in spaCy.
+///     return_tensors (str, optional): `"np"` for the flags as NumPy bool
+///         arrays instead of lists.
+///
+/// Returns:
+///     dict: `shapes` (e.g. `"Xxxx"`, `"dddd"`, `"xx-xx"`) and the flags
+///     `is_alpha`, `is_digit`, `is_lower`, `is_upper`, `is_title` and
+///     `is_punct`, one entry per token, plus the `tokens` themselves when
+///     text was tokenized.
+#[pyfunction]
+#[pyo3(signature = (tokens, tokenizer=None, max_repeat=4, return_tensors=None))]
+pub fn token_shapes<'py>(
+    py: Python<'py>,
+    tokens: ShapeInput,
+    tokenizer: Option<&Bound<'py, PyAny>>,
+    max_repeat: usize,
+    return_tensors: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if max_repeat == 0 {
+        return Err(PyValueError::new_err("max_repeat must be at least 1"));
+    }
+    if let Some(other) = return_tensors.filter(|t| *t != "np") {
+        return Err(PyValueError::new_err(format!(
+            "Unsupported return_tensors '{}', expected 'np'",
+            other
+        )));
+    }
+    let tokenizer = tokenizer.map(extract_tokenizer).transpose()?;
+    let tokenized = match (&tokens, &tokenizer) {
+        (ShapeInput::Tokens(_), None) => false,
+        (ShapeInput::Text(_), Some(_)) => true,
+        (ShapeInput::Text(_), None) => {
+            return Err(PyValueError::new_err(
+                "Pass a tokenizer to compute the shapes of text",
+            ))
+        }
+        (ShapeInput::Tokens(_), Some(_)) => {
+            return Err(PyValueError::new_err(
+                "Pass text, not tokens, together with a tokenizer",
+            ))
+        }
+    };
+    let (tokens, shapes, flags) = py.detach(|| {
+        let tokens = match (tokens, tokenizer) {
+            (ShapeInput::Text(text), Some(tokenizer)) => tokenizer.tokenize(&text),
+            (ShapeInput::Tokens(tokens), _) => tokens,
+            (ShapeInput::Text(_), None) => unreachable!("checked above"),
+        };
+        let (shapes, flags): (Vec<String>, Vec<[bool; 6]>) = tokens
+            .iter()
+            .map(|token| (shape(token, max_repeat), TokenFlags::of(token).values()))
+            .unzip();
+        (tokens, shapes, flags)
+    });
+    let result = PyDict::new(py);
+    if tokenized {
+        result.set_item("tokens", &tokens)?;
+    }
+    result.set_item("shapes", shapes)?;
+    for (i, name) in FLAG_NAMES.iter().enumerate() {
+        let column: Vec<bool> = flags.iter().map(|f| f[i]).collect();
+        match return_tensors {
+            Some(_) => result.set_item(name, column.into_pyarray(py))?,
+            None => result.set_item(name, column)?,
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// A value that can be replaced through a shared reference, for the parts
+/// of a tokenizer that Python code may change after construction, such as
+/// its vocabulary.
+///
+/// Readers take a snapshot with `load` and keep using it, so a call already
+/// running on another thread finishes with the value it started with while
+/// a writer swaps in a new one. The lock is only held for the swap itself.
+pub struct Shared<T>(RwLock<Arc<T>>);
+
+impl<T> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Shared(RwLock::new(Arc::new(value)))
+    }
+
+    /// The current value.
+    pub fn load(&self) -> Arc<T> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Replace the value.
+    pub fn store(&self, value: T) {
+        *self.0.write().unwrap() = Arc::new(value);
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// Change the value in place, copying it first if readers still hold a
+    /// snapshot.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(Arc::make_mut(&mut self.0.write().unwrap()))
+    }
+}
+
+/// Copies get their own slot, so replacing the value of one copy leaves
+/// the others alone.
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(RwLock::new(self.load()))
+    }
+}
+
+impl<T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Shared::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.load().fmt(f)
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Shared::new(value)
+    }
+}
@@ -0,0 +1,243 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::config;
+use crate::intern::token_list;
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+/// Entity patterns, tried in this order at each position. Each group name is
+/// the type label reported for its matches; numbers running into letters
+/// (`2nd`, `3.5x`) are reported as words.
+const SOCIAL_PATTERN: &str = concat!(
+    r"(?P<url>(?i:https?://|www\.)[^\s<>]+)",
+    r"|(?P<email>[\w.+-]+@[\w-]+(?:\.[\w-]+)+)",
+    r"|(?P<mention>@\w+)",
+    r"|(?P<hashtag>#\w+)",
+    r"|(?P<emoji>\p{Regional_Indicator}{2}|[0-9#*]\x{FE0F}?\x{20E3}",
+    r"|\p{Extended_Pictographic}\p{Emoji_Modifier}?\x{FE0F}?",
+    r"(?:\x{200D}\p{Extended_Pictographic}\p{Emoji_Modifier}?\x{FE0F}?)*)",
+    r"|(?P<emoticon><3|[:;=][-']?[()\[\]DPpOo/\\|])",
+    r"|(?P<number>\d+(?:[.,]\d+)*\w*)",
+    r"|(?P<word>\w+(?:['’]\w+)*)",
+    r"|(?P<punct>\S)",
+);
+
+const KINDS: &[&str] = &[
+    "url", "email", "mention", "hashtag", "emoji", "emoticon", "number", "word", "punct",
+];
+
+fn social_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(SOCIAL_PATTERN).expect("valid social pattern"))
+}
+
+/// Trim punctuation that ends the sentence around a URL rather than the URL
+/// itself; a closing bracket is kept when the URL opened one.
+fn url_end(url: &str) -> usize {
+    let mut end = url.len();
+    while let Some(c) = url[..end].chars().last() {
+        let unbalanced =
+            |open: char| url[..end].matches(open).count() < url[..end].matches(c).count();
+        let trailing = match c {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' => true,
+            ')' => unbalanced('('),
+            ']' => unbalanced('['),
+            _ => false,
+        };
+        if !trailing {
+            break;
+        }
+        end -= c.len_utf8();
+    }
+    end
+}
+
+/// Tokenizer for social media and support text that keeps URLs, emails,
+/// @mentions, #hashtags, emoji sequences and emoticons as single tokens and
+/// can label each token with its type.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Default)]
+pub struct SocialTokenizer {
+    vocab: Shared<Option<Vocab>>,
+}
+
+impl SocialTokenizer {
+    /// Spans of the tokens of `text` with their type labels.
+    fn scan(&self, text: &str) -> Vec<(usize, usize, &'static str)> {
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        let re = social_regex();
+        while let Some(caps) = re.captures_at(text, pos) {
+            let (kind, m) = KINDS
+                .iter()
+                .find_map(|&kind| caps.name(kind).map(|m| (kind, m)))
+                .expect("one group matches");
+            let end = match kind {
+                "url" => m.start() + url_end(m.as_str()),
+                _ => m.end(),
+            };
+            let kind = match kind {
+                "number" if m.as_str().chars().any(char::is_alphabetic) => "word",
+                _ => kind,
+            };
+            tokens.push((m.start(), end, kind));
+            pos = end;
+        }
+        tokens
+    }
+}
+
+impl Tokenizer for SocialTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.scan(text)
+            .into_iter()
+            .map(|(start, end, _)| Token::new(&text[start..end], start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.scan(text)
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+}
+
+tokenizer_pymethods!(SocialTokenizer, encode_batch);
+
+#[pymethods]
+impl SocialTokenizer {
+    /// Create a social media tokenizer.
+    ///
+    /// Args:
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    #[new]
+    #[pyo3(signature = (vocab=None))]
+    fn new(vocab: Option<PyRef<'_, Vocab>>) -> Self {
+        SocialTokenizer {
+            vocab: vocab.map(|v| v.clone()).into(),
+        }
+    }
+
+    /// Constructor arguments recreating this tokenizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("vocab", Option::clone(&self.vocab.load()))?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the tokenizer's configuration to JSON.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a tokenizer from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
+    }
+
+    /// The type labels `tokenize_with_types` can report.
+    #[staticmethod]
+    fn types() -> Vec<&'static str> {
+        KINDS.to_vec()
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, type)` pairs, where the type is one of
+    /// `url`, `email`, `mention`, `hashtag`, `emoji`, `emoticon`, `number`,
+    /// `word` or `punct`.
+    fn tokenize_with_types(&self, input: &str) -> Vec<(String, &'static str)> {
+        self.scan(input)
+            .into_iter()
+            .map(|(start, end, kind)| (input[start..end].to_string(), kind))
+            .collect()
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        require_vocab(&self.vocab)?.encode_text(self, input)
+    }
+
+    /// Turn ids back into text, joining the tokens with single spaces.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        require_vocab(&self.vocab)?.decode_text(&ids)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
+    }
+
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
+    }
+}
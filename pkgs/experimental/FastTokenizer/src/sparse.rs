@@ -0,0 +1,166 @@
+use numpy::IntoPyArray;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+/// Row normalization applied by the vectorizers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Norm {
+    L2,
+    L1,
+}
+
+impl Norm {
+    /// Parse `"l2"`, `"l1"` or `None` for no normalization.
+    pub fn parse(name: Option<&str>) -> PyResult<Option<Self>> {
+        match name {
+            None => Ok(None),
+            Some("l2") => Ok(Some(Norm::L2)),
+            Some("l1") => Ok(Some(Norm::L1)),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Unknown norm '{}', expected 'l2', 'l1' or None",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Norm::L2 => "l2",
+            Norm::L1 => "l1",
+        }
+    }
+
+    /// Scale `row` to unit length; all-zero rows are left alone.
+    pub fn apply(self, row: &mut [(u32, f64)]) {
+        let length = match self {
+            Norm::L2 => row.iter().map(|(_, v)| v * v).sum::<f64>().sqrt(),
+            Norm::L1 => row.iter().map(|(_, v)| v.abs()).sum(),
+        };
+        if length > 0.0 {
+            for (_, value) in row {
+                *value /= length;
+            }
+        }
+    }
+}
+
+/// A sparse matrix in compressed sparse row (CSR) layout, as produced by the
+/// vectorizers.
+///
+/// Row `i` stores its non-zero values in `data[indptr[i]..indptr[i + 1]]`,
+/// with their column numbers at the same positions in `indices`, sorted.
+/// `to_scipy()` converts it to a `scipy.sparse.csr_matrix`.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct CsrMatrix {
+    /// Non-zero values, row by row.
+    #[pyo3(get)]
+    data: Vec<f64>,
+    /// Column of every value in `data`.
+    #[pyo3(get)]
+    indices: Vec<u32>,
+    /// Where every row starts in `data`, plus the total count.
+    #[pyo3(get)]
+    indptr: Vec<u64>,
+    columns: usize,
+}
+
+impl CsrMatrix {
+    /// Assemble a matrix from rows of `(column, value)` pairs, which are
+    /// sorted by column here.
+    pub fn from_rows(rows: Vec<Vec<(u32, f64)>>, columns: usize) -> Self {
+        let nnz = rows.iter().map(Vec::len).sum();
+        let mut matrix = CsrMatrix {
+            data: Vec::with_capacity(nnz),
+            indices: Vec::with_capacity(nnz),
+            indptr: Vec::with_capacity(rows.len() + 1),
+            columns,
+        };
+        matrix.indptr.push(0);
+        for mut row in rows {
+            row.sort_unstable_by_key(|&(column, _)| column);
+            for (column, value) in row {
+                matrix.indices.push(column);
+                matrix.data.push(value);
+            }
+            matrix.indptr.push(matrix.data.len() as u64);
+        }
+        matrix
+    }
+
+    fn rows(&self) -> usize {
+        self.indptr.len() - 1
+    }
+}
+
+#[pymethods]
+impl CsrMatrix {
+    /// `(rows, columns)`.
+    #[getter]
+    fn shape(&self) -> (usize, usize) {
+        (self.rows(), self.columns)
+    }
+
+    /// Number of stored values.
+    #[getter]
+    fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The non-zero entries of row `i` as `(column, value)` pairs.
+    fn row(&self, i: usize) -> PyResult<Vec<(u32, f64)>> {
+        if i >= self.rows() {
+            return Err(PyIndexError::new_err(format!(
+                "Row {} out of range for a matrix with {} rows",
+                i,
+                self.rows()
+            )));
+        }
+        let (start, end) = (self.indptr[i] as usize, self.indptr[i + 1] as usize);
+        Ok(self.indices[start..end]
+            .iter()
+            .copied()
+            .zip(self.data[start..end].iter().copied())
+            .collect())
+    }
+
+    /// The matrix as nested lists; only sensible for small matrices.
+    fn to_dense(&self) -> Vec<Vec<f64>> {
+        (0..self.rows())
+            .map(|i| {
+                let mut row = vec![0.0; self.columns];
+                let (start, end) = (self.indptr[i] as usize, self.indptr[i + 1] as usize);
+                for k in start..end {
+                    row[self.indices[k] as usize] = self.data[k];
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// Convert to a `scipy.sparse.csr_matrix` backed by NumPy arrays.
+    fn to_scipy<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let sparse = py.import("scipy.sparse")?;
+        let arrays = (
+            self.data.clone().into_pyarray(py),
+            self.indices.clone().into_pyarray(py),
+            self.indptr.clone().into_pyarray(py),
+        );
+        sparse
+            .getattr("csr_matrix")?
+            .call1((arrays, (self.rows(), self.columns)))
+    }
+
+    fn __len__(&self) -> usize {
+        self.rows()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CsrMatrix(shape=({}, {}), nnz={})",
+            self.rows(),
+            self.columns,
+            self.data.len()
+        )
+    }
+}
@@ -0,0 +1,362 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// One element of a post-processing template.
+#[derive(Clone, Debug, PartialEq)]
+enum Piece {
+    /// The tokens of the first sequence (`$A`).
+    A,
+    /// The tokens of the second sequence (`$B`).
+    B,
+    Special(String),
+}
+
+/// A whitespace separated template such as `[CLS] $A [SEP] $B [SEP]`.
+#[derive(Clone, Debug, Default)]
+struct Template(Vec<Piece>);
+
+impl Template {
+    fn parse(template: &str) -> Self {
+        Template(
+            template
+                .split_whitespace()
+                .map(|piece| match piece {
+                    "$A" | "$a" => Piece::A,
+                    "$B" | "$b" => Piece::B,
+                    token => Piece::Special(token.to_string()),
+                })
+                .collect(),
+        )
+    }
+
+    fn specials(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().filter_map(|piece| match piece {
+            Piece::Special(token) => Some(token),
+            _ => None,
+        })
+    }
+
+    fn source(&self) -> String {
+        self.0
+            .iter()
+            .map(|piece| match piece {
+                Piece::A => "$A",
+                Piece::B => "$B",
+                Piece::Special(token) => token.as_str(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Registry of special tokens and the templates that insert them.
+///
+/// Registered tokens are never normalized or split by a pipeline, and
+/// `encode` wraps sequences according to the single or pair template.
+/// Without explicit templates, `cls`/`sep` give `[CLS] $A [SEP]` and
+/// `[CLS] $A [SEP] $B [SEP]`, otherwise `bos`/`eos` give `<s> $A </s>`
+/// and `<s> $A </s> $B </s>`.
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Debug, Default)]
+pub struct SpecialTokens {
+    #[pyo3(get)]
+    bos: Option<String>,
+    #[pyo3(get)]
+    eos: Option<String>,
+    #[pyo3(get)]
+    pad: Option<String>,
+    #[pyo3(get)]
+    unk: Option<String>,
+    #[pyo3(get)]
+    cls: Option<String>,
+    #[pyo3(get)]
+    sep: Option<String>,
+    tokens: Vec<String>,
+    single: Template,
+    pair: Template,
+    matcher: Option<Regex>,
+}
+
+impl SpecialTokens {
+    fn register(&mut self, token: &str) {
+        if !token.is_empty() && !self.tokens.iter().any(|t| t == token) {
+            self.tokens.push(token.to_string());
+        }
+    }
+
+    /// Rebuild the matcher, preferring longer tokens where several match.
+    fn rebuild(&mut self) {
+        let mut tokens: Vec<&String> = self.tokens.iter().collect();
+        tokens.sort_by_key(|t| std::cmp::Reverse(t.len()));
+        let source: Vec<String> = tokens.iter().map(|t| regex::escape(t)).collect();
+        self.matcher = (!source.is_empty())
+            .then(|| Regex::new(&source.join("|")).expect("escaped special tokens"));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// All registered special tokens in registration order.
+    pub fn registered(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// A registry of those of `tokens` that `vocab` contains, as the
+    /// defaults of models loaded from their usual files.
+    pub fn from_vocab(vocab: &HashMap<String, u32>, tokens: &[&str]) -> Self {
+        let mut special = SpecialTokens::default();
+        special.add_tokens(
+            tokens
+                .iter()
+                .filter(|token| vocab.contains_key(**token))
+                .map(|token| token.to_string())
+                .collect(),
+        );
+        special
+    }
+
+    /// Register further special tokens.
+    pub fn add_tokens(&mut self, tokens: Vec<String>) {
+        for token in &tokens {
+            self.register(token);
+        }
+        self.rebuild();
+    }
+
+    pub fn pad_token(&self) -> Option<&str> {
+        self.pad.as_deref()
+    }
+
+    /// Number of tokens the single or pair template adds.
+    pub fn num_added(&self, pair: bool) -> usize {
+        let template = if pair { &self.pair } else { &self.single };
+        template.specials().count()
+    }
+
+    pub fn contains(&self, token: &str) -> bool {
+        self.tokens.iter().any(|t| t == token)
+    }
+
+    /// Byte ranges of `text` as `(is_special, start, end)`, covering the
+    /// whole text in order.
+    pub fn split(&self, text: &str) -> Vec<(bool, usize, usize)> {
+        let Some(matcher) = &self.matcher else {
+            return vec![(false, 0, text.len())];
+        };
+        let mut segments = Vec::new();
+        let mut last = 0;
+        for m in matcher.find_iter(text) {
+            if m.start() > last {
+                segments.push((false, last, m.start()));
+            }
+            segments.push((true, m.start(), m.end()));
+            last = m.end();
+        }
+        if last < text.len() {
+            segments.push((false, last, text.len()));
+        }
+        segments
+    }
+
+    /// The registry as a JSON object, in the form tokenizer configurations
+    /// give it, for models that embed their special tokens.
+    pub fn to_value(&self) -> Value {
+        json!({
+            "type": "SpecialTokens",
+            "bos": self.bos,
+            "eos": self.eos,
+            "pad": self.pad,
+            "unk": self.unk,
+            "cls": self.cls,
+            "sep": self.sep,
+            "additional": self.tokens,
+            "single": self.single.source(),
+            "pair": self.pair.source(),
+        })
+    }
+
+    /// Recreate a registry from `to_value` output.
+    pub fn from_value(value: &Value) -> PyResult<Self> {
+        let text = |name: &str| value[name].as_str().map(str::to_string);
+        let additional = serde_json::from_value(value["additional"].clone())
+            .map_err(|e| PyValueError::new_err(format!("Invalid special tokens: {}", e)))?;
+        Self::new(
+            text("bos"),
+            text("eos"),
+            text("pad"),
+            text("unk"),
+            text("cls"),
+            text("sep"),
+            additional,
+            value["single"].as_str(),
+            value["pair"].as_str(),
+        )
+    }
+
+    /// Wrap one or two token sequences with the matching template.
+    pub fn apply(&self, a: Vec<String>, b: Option<Vec<String>>) -> Vec<String> {
+        self.apply_with(a, b, str::to_string)
+    }
+
+    /// `apply` for sequences of any item, made for special tokens by
+    /// `special`.
+    pub fn apply_with<T>(
+        &self,
+        a: Vec<T>,
+        b: Option<Vec<T>>,
+        mut special: impl FnMut(&str) -> T,
+    ) -> Vec<T> {
+        let (template, mut b) = match b {
+            Some(b) => (&self.pair, Some(b)),
+            None => (&self.single, None),
+        };
+        let mut a = Some(a);
+        let mut out = Vec::new();
+        for piece in &template.0 {
+            match piece {
+                Piece::A => out.extend(a.take().unwrap_or_default()),
+                Piece::B => out.extend(b.take().unwrap_or_default()),
+                Piece::Special(token) => out.push(special(token)),
+            }
+        }
+        // Sequences missing from the template are still kept, in order.
+        out.extend(a.into_iter().flatten());
+        out.extend(b.into_iter().flatten());
+        out
+    }
+}
+
+#[pymethods]
+impl SpecialTokens {
+    /// Create a special token registry.
+    ///
+    /// Args:
+    ///     bos, eos, pad, unk, cls, sep (str, optional): Tokens for each role.
+    ///     additional (List[str], optional): Further tokens to protect.
+    ///     single (str, optional): Template for single sequences, e.g.
+    ///         `"[CLS] $A [SEP]"`.
+    ///     pair (str, optional): Template for sequence pairs, e.g.
+    ///         `"[CLS] $A [SEP] $B [SEP]"`.
+    #[new]
+    #[pyo3(signature = (
+        bos=None,
+        eos=None,
+        pad=None,
+        unk=None,
+        cls=None,
+        sep=None,
+        additional=None,
+        single=None,
+        pair=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        bos: Option<String>,
+        eos: Option<String>,
+        pad: Option<String>,
+        unk: Option<String>,
+        cls: Option<String>,
+        sep: Option<String>,
+        additional: Option<Vec<String>>,
+        single: Option<&str>,
+        pair: Option<&str>,
+    ) -> PyResult<Self> {
+        let (default_single, default_pair) = match (&cls, &sep, &bos, &eos) {
+            (Some(cls), Some(sep), _, _) => (
+                format!("{cls} $A {sep}"),
+                format!("{cls} $A {sep} $B {sep}"),
+            ),
+            (_, _, bos, eos) => {
+                let bos = bos.as_deref().unwrap_or_default();
+                let eos = eos.as_deref().unwrap_or_default();
+                (
+                    format!("{bos} $A {eos}"),
+                    format!("{bos} $A {eos} $B {eos}"),
+                )
+            }
+        };
+        let single = Template::parse(single.unwrap_or(&default_single));
+        let pair = Template::parse(pair.unwrap_or(&default_pair));
+        if single.0.contains(&Piece::B) {
+            return Err(PyValueError::new_err(
+                "The single template cannot reference $B",
+            ));
+        }
+        let mut special = SpecialTokens {
+            bos,
+            eos,
+            pad,
+            unk,
+            cls,
+            sep,
+            ..Default::default()
+        };
+        let roles = [
+            &special.bos,
+            &special.eos,
+            &special.pad,
+            &special.unk,
+            &special.cls,
+            &special.sep,
+        ];
+        let mut tokens: Vec<String> = roles.into_iter().flatten().cloned().collect();
+        tokens.extend(additional.into_iter().flatten());
+        tokens.extend(single.specials().chain(pair.specials()).cloned());
+        for token in &tokens {
+            special.register(token);
+        }
+        special.single = single;
+        special.pair = pair;
+        special.rebuild();
+        Ok(special)
+    }
+
+    /// Constructor arguments recreating this registry, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("bos", &self.bos)?;
+        kwargs.set_item("eos", &self.eos)?;
+        kwargs.set_item("pad", &self.pad)?;
+        kwargs.set_item("unk", &self.unk)?;
+        kwargs.set_item("cls", &self.cls)?;
+        kwargs.set_item("sep", &self.sep)?;
+        kwargs.set_item("additional", &self.tokens)?;
+        kwargs.set_item("single", self.single.source())?;
+        kwargs.set_item("pair", self.pair.source())?;
+        Ok(((), kwargs))
+    }
+
+    /// Register further special tokens.
+    fn add(&mut self, tokens: Vec<String>) {
+        self.add_tokens(tokens);
+    }
+
+    /// All registered special tokens.
+    fn tokens(&self) -> Vec<String> {
+        self.tokens.clone()
+    }
+
+    #[getter]
+    fn single(&self) -> String {
+        self.single.source()
+    }
+
+    #[getter]
+    fn pair(&self) -> String {
+        self.pair.source()
+    }
+
+    fn __contains__(&self, token: &str) -> bool {
+        self.contains(token)
+    }
+
+    fn __len__(&self) -> usize {
+        self.tokens.len()
+    }
+}
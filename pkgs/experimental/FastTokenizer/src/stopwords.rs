@@ -0,0 +1,180 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::filters::TokenFilter;
+
+/// Built-in stop word lists as `(code, name, words)`.
+pub const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("de", "german", include_str!("stopwords/de.txt")),
+    ("en", "english", include_str!("stopwords/en.txt")),
+    ("es", "spanish", include_str!("stopwords/es.txt")),
+    ("fr", "french", include_str!("stopwords/fr.txt")),
+    ("it", "italian", include_str!("stopwords/it.txt")),
+    ("nl", "dutch", include_str!("stopwords/nl.txt")),
+    ("pt", "portuguese", include_str!("stopwords/pt.txt")),
+    ("ru", "russian", include_str!("stopwords/ru.txt")),
+];
+
+fn builtin(language: &str) -> PyResult<&'static str> {
+    let language = language.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(code, name, _)| *code == language || *name == language)
+        .map(|(_, _, words)| *words)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "No stop word list for '{}'; available: {}",
+                language,
+                LANGUAGES
+                    .iter()
+                    .map(|(code, _, _)| *code)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        })
+}
+
+/// One language code or several.
+#[derive(FromPyObject)]
+pub enum Languages {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Drops stop words from token lists.
+///
+/// Combines built-in lists for major languages with user-supplied words,
+/// and can be passed to `TokenizerPipeline(filters=[...])` to run after
+/// tokenization. Matching ignores case unless `case_sensitive` is set.
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Debug, Default)]
+pub struct StopWordFilter {
+    /// Stored lowercased unless matching is case sensitive.
+    words: Arc<HashSet<String>>,
+    #[pyo3(get)]
+    case_sensitive: bool,
+}
+
+impl StopWordFilter {
+    fn key(&self, word: &str) -> String {
+        if self.case_sensitive {
+            word.to_string()
+        } else {
+            word.to_lowercase()
+        }
+    }
+
+    pub fn contains(&self, token: &str) -> bool {
+        if self.case_sensitive || !token.chars().any(char::is_uppercase) {
+            self.words.contains(token)
+        } else {
+            self.words.contains(&token.to_lowercase())
+        }
+    }
+}
+
+impl TokenFilter for StopWordFilter {
+    fn keep(&self, token: &str) -> bool {
+        !self.contains(token)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+}
+
+#[pymethods]
+impl StopWordFilter {
+    /// Create a stop word filter.
+    ///
+    /// Args:
+    ///     language (str | List[str], optional): Built-in list(s) to load, by
+    ///         ISO code or English name, e.g. `"en"` or `["english", "de"]`.
+    ///     words (Iterable[str], optional): Additional stop words.
+    ///     case_sensitive (bool): Match tokens exactly instead of ignoring case.
+    #[new]
+    #[pyo3(signature = (language=None, words=None, case_sensitive=false))]
+    pub fn new(
+        language: Option<Languages>,
+        words: Option<Vec<String>>,
+        case_sensitive: bool,
+    ) -> PyResult<Self> {
+        let languages = match language {
+            None => Vec::new(),
+            Some(Languages::One(language)) => vec![language],
+            Some(Languages::Many(languages)) => languages,
+        };
+        let mut filter = StopWordFilter {
+            words: Arc::default(),
+            case_sensitive,
+        };
+        let mut set = HashSet::new();
+        for language in &languages {
+            set.extend(builtin(language)?.lines().map(|word| filter.key(word)));
+        }
+        set.extend(words.into_iter().flatten().map(|word| filter.key(&word)));
+        filter.words = Arc::new(set);
+        Ok(filter)
+    }
+
+    /// Constructor arguments recreating this filter, for `pickle` and `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("words", self.words())?;
+        kwargs.set_item("case_sensitive", self.case_sensitive)?;
+        Ok(((), kwargs))
+    }
+
+    /// Codes of the languages with a built-in list.
+    #[staticmethod]
+    fn languages() -> Vec<&'static str> {
+        LANGUAGES.iter().map(|(code, _, _)| *code).collect()
+    }
+
+    /// Add stop words.
+    fn add(&mut self, words: Vec<String>) {
+        let keys: Vec<String> = words.iter().map(|word| self.key(word)).collect();
+        Arc::make_mut(&mut self.words).extend(keys);
+    }
+
+    /// Remove stop words, e.g. negations that matter for sentiment.
+    fn remove(&mut self, words: Vec<String>) {
+        let keys: Vec<String> = words.iter().map(|word| self.key(word)).collect();
+        let set = Arc::make_mut(&mut self.words);
+        for key in &keys {
+            set.remove(key);
+        }
+    }
+
+    /// Whether `token` is a stop word.
+    fn is_stop_word(&self, token: &str) -> bool {
+        self.contains(token)
+    }
+
+    /// `tokens` without the stop words.
+    fn filter(&self, tokens: Vec<String>) -> Vec<String> {
+        tokens.into_iter().filter(|t| self.keep(t)).collect()
+    }
+
+    /// All stop words, sorted.
+    fn words(&self) -> Vec<String> {
+        let mut words: Vec<String> = self.words.iter().cloned().collect();
+        words.sort();
+        words
+    }
+
+    fn __contains__(&self, token: &str) -> bool {
+        self.contains(token)
+    }
+
+    fn __len__(&self) -> usize {
+        self.words.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("StopWordFilter(size={})", self.words.len())
+    }
+}
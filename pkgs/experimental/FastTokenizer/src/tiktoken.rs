@@ -0,0 +1,309 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use fasttokenizer_core::tiktoken::parse_ranks;
+use fasttokenizer_core::Tokenize;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::Arc;
+
+use crate::config;
+use crate::intern::token_list;
+use crate::tokenizer::{offsets, py_err, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::{decode_error, Text, Utf8Errors};
+
+/// Byte-level BPE encoder producing the same ids as OpenAI's tiktoken.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct TiktokenEncoder {
+    model: Arc<fasttokenizer_core::TiktokenEncoder>,
+    #[pyo3(get)]
+    name: String,
+}
+
+impl TiktokenEncoder {
+    /// The merge ranks, split pattern, special tokens and name as a JSON
+    /// object, as embedded in tokenizer configurations and bundles. Ranks
+    /// are keyed by their base64 encoded bytes, as in `.tiktoken` files.
+    pub fn to_value(&self) -> Value {
+        let ranks: Map<String, Value> = self
+            .model
+            .ranks()
+            .iter()
+            .map(|(bytes, &rank)| (BASE64.encode(bytes), rank.into()))
+            .collect();
+        json!({
+            "mergeable_ranks": ranks,
+            "pattern": self.model.pattern(),
+            "special_tokens": self.model.special_tokens(),
+            "name": self.name,
+        })
+    }
+
+    /// Recreate an encoder from `to_value` output.
+    pub fn from_value(value: &Value) -> PyResult<Self> {
+        let invalid = |e: String| PyValueError::new_err(format!("Invalid tiktoken model: {}", e));
+        let ranks: HashMap<String, u32> = serde_json::from_value(value["mergeable_ranks"].clone())
+            .map_err(|e| invalid(e.to_string()))?;
+        let ranks = ranks
+            .into_iter()
+            .map(|(token, rank)| {
+                Ok((
+                    BASE64.decode(token).map_err(|e| invalid(e.to_string()))?,
+                    rank,
+                ))
+            })
+            .collect::<PyResult<_>>()?;
+        let special_tokens = serde_json::from_value(value["special_tokens"].clone())
+            .map_err(|e| invalid(e.to_string()))?;
+        Self::new(
+            ranks,
+            value["pattern"].as_str().unwrap_or_default(),
+            Some(special_tokens),
+            value["name"].as_str().unwrap_or("custom").to_string(),
+        )
+    }
+}
+
+impl Tokenizer for TiktokenEncoder {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.model.tokenize_with_offsets(text)
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.model.token_spans(text)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        tokens.concat()
+    }
+}
+
+/// Read a tiktoken `.tiktoken` ranks file.
+fn read_ranks(path: &str) -> PyResult<HashMap<Vec<u8>, u32>> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read ranks file: {}", e)))?;
+    parse_ranks(&data).map_err(py_err)
+}
+
+/// Resolve tiktoken's `"all"` or a collection of token strings.
+fn special_set(
+    value: &Bound<'_, PyAny>,
+    special_tokens: &HashMap<String, u32>,
+) -> PyResult<HashSet<String>> {
+    if let Ok(s) = value.cast::<PyString>() {
+        if s.to_str()? == "all" {
+            return Ok(special_tokens.keys().cloned().collect());
+        }
+    }
+    match value.extract::<HashSet<String>>() {
+        Ok(set) => Ok(set),
+        Err(_) => Ok(value.extract::<Vec<String>>()?.into_iter().collect()),
+    }
+}
+
+tokenizer_pymethods!(TiktokenEncoder);
+
+#[pymethods]
+impl TiktokenEncoder {
+    /// Create an encoder from merge ranks, a split pattern and special tokens.
+    ///
+    /// Args:
+    ///     mergeable_ranks (Dict[bytes, int]): Token bytes to rank/id.
+    ///     pattern (str): Split pattern; a final `(\s+)` group stands in
+    ///         for tiktoken's `\s+(?!\S)|\s+`.
+    ///     special_tokens (Dict[str, int], optional): Special token ids.
+    ///     name (str): Name reported by the `name` attribute.
+    #[new]
+    #[pyo3(signature = (mergeable_ranks, pattern, special_tokens=None, name="custom".to_string()))]
+    fn new(
+        mergeable_ranks: HashMap<Vec<u8>, u32>,
+        pattern: &str,
+        special_tokens: Option<HashMap<String, u32>>,
+        name: String,
+    ) -> PyResult<Self> {
+        let model = fasttokenizer_core::TiktokenEncoder::new(
+            mergeable_ranks,
+            pattern,
+            special_tokens.unwrap_or_default(),
+        )
+        .map_err(py_err)?;
+        Ok(TiktokenEncoder {
+            model: Arc::new(model),
+            name,
+        })
+    }
+
+    /// Load a `.tiktoken` ranks file for one of the standard encodings.
+    ///
+    /// Args:
+    ///     path (str): Path to e.g. `cl100k_base.tiktoken`.
+    ///     encoding (str): `cl100k_base`, `o200k_base`, `p50k_base` or `r50k_base`.
+    #[staticmethod]
+    #[pyo3(signature = (path, encoding="cl100k_base"))]
+    fn from_file(path: &str, encoding: &str) -> PyResult<Self> {
+        Self::from_ranks(read_ranks(path)?, encoding)
+    }
+
+    /// Build one of the standard encodings from already loaded merge ranks,
+    /// e.g. `tiktoken.get_encoding(name)._mergeable_ranks`.
+    #[staticmethod]
+    #[pyo3(signature = (mergeable_ranks, encoding="cl100k_base"))]
+    fn from_ranks(mergeable_ranks: HashMap<Vec<u8>, u32>, encoding: &str) -> PyResult<Self> {
+        let model = fasttokenizer_core::TiktokenEncoder::from_ranks(mergeable_ranks, encoding)
+            .map_err(py_err)?;
+        Ok(TiktokenEncoder {
+            model: Arc::new(model),
+            name: encoding.to_string(),
+        })
+    }
+
+    /// Save the merge ranks, split pattern and special tokens to a single
+    /// bundle file, to be loaded back with `TiktokenEncoder.load`.
+    fn save(slf: &Bound<'_, Self>, path: &str) -> PyResult<()> {
+        config::save(slf.as_any(), path)
+    }
+
+    /// Load an encoder saved with `save`.
+    #[staticmethod]
+    fn load<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, Self>> {
+        config::load(py, path)
+    }
+
+    /// Encode text like `tiktoken.Encoding.encode`.
+    ///
+    /// Raises ValueError when the text contains a disallowed special token.
+    ///
+    /// Args:
+    ///     text (str | bytes): The text to encode.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (text, allowed_special=None, disallowed_special=None, errors="strict"))]
+    fn encode<'py>(
+        &self,
+        text: Text<'py>,
+        allowed_special: Option<&Bound<'_, PyAny>>,
+        disallowed_special: Option<&Bound<'_, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Vec<u32>> {
+        let text: &str = &text.decode(errors)?;
+        let specials = self.model.special_tokens();
+        let allowed = match allowed_special {
+            Some(value) => special_set(value, specials)?,
+            None => HashSet::new(),
+        };
+        let disallowed = match disallowed_special {
+            Some(value) => special_set(value, specials)?,
+            None => specials.keys().cloned().collect(),
+        };
+        self.model
+            .encode(text, &allowed, &disallowed)
+            .map_err(py_err)
+    }
+
+    /// Encode text treating special tokens as ordinary text.
+    fn encode_ordinary(&self, text: &str) -> PyResult<Vec<u32>> {
+        Ok(self.model.encode_ordinary(text))
+    }
+
+    /// Encode several strings at once, releasing the GIL while working.
+    fn encode_ordinary_batch(&self, py: Python<'_>, texts: Vec<String>) -> PyResult<Vec<Vec<u32>>> {
+        Ok(py.detach(|| {
+            texts
+                .iter()
+                .map(|text| self.model.encode_ordinary(text))
+                .collect()
+        }))
+    }
+
+    /// Number of tokens in `text`, without materializing the ids in Python.
+    fn count_tokens(&self, text: &str) -> usize {
+        self.model.count_tokens(text)
+    }
+
+    /// Token counts of several strings, releasing the GIL while working.
+    fn count_tokens_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<usize> {
+        py.detach(|| texts.iter().map(|text| self.count_tokens(text)).collect())
+    }
+
+    /// Decode ids into bytes.
+    fn decode_bytes(&self, ids: Vec<u32>) -> PyResult<Vec<u8>> {
+        self.model.decode_bytes(&ids).map_err(py_err)
+    }
+
+    /// Decode ids into text.
+    ///
+    /// Args:
+    ///     ids (List[int]): Token ids.
+    ///     errors (str): What to do with byte sequences that are not valid
+    ///         UTF-8: `"replace"` them with U+FFFD like tiktoken does,
+    ///         `"ignore"` them or raise a `UnicodeDecodeError` (`"strict"`).
+    #[pyo3(signature = (ids, errors="replace"))]
+    fn decode(&self, py: Python<'_>, ids: Vec<u32>, errors: &str) -> PyResult<String> {
+        let errors = Utf8Errors::parse(errors)?;
+        let bytes = self.decode_bytes(ids)?;
+        errors.decode(bytes).map_err(|e| decode_error(py, e))
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Number of ordinary plus special tokens.
+    #[getter]
+    fn n_vocab(&self) -> usize {
+        self.model.n_vocab()
+    }
+
+    /// Special token ids, like `tiktoken.Encoding.special_tokens_set` plus ids.
+    #[getter]
+    fn special_tokens(&self) -> HashMap<String, u32> {
+        self.model.special_tokens().clone()
+    }
+}
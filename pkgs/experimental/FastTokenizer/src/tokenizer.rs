@@ -0,0 +1,525 @@
+use numpy::ndarray::Array2;
+use numpy::IntoPyArray;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyString};
+use std::sync::Arc;
+
+use crate::bpe::BpeTokenizer;
+use crate::cjk_tokenizer::CjkTokenizer;
+use crate::code_tokenizer::CodeTokenizer;
+use crate::detokenizer::join_tokens;
+use crate::grapheme_tokenizer::GraphemeTokenizer;
+use crate::hf_tokenizer::HfTokenizer;
+use crate::keyword_tokenizer::KeywordTokenizer;
+use crate::regex_tokenizer::RegexTokenizer;
+use crate::sentence_splitter::SentenceSplitter;
+use crate::sentencepiece::SentencePieceTokenizer;
+use crate::social_tokenizer::SocialTokenizer;
+use crate::tiktoken::TiktokenEncoder;
+use crate::trie_tokenizer::TrieTokenizer;
+use crate::whitespace_tokenizer::WhitespaceTokenizer;
+use crate::wordpiece::WordPieceTokenizer;
+
+/// A rayon pool with `workers` threads, or one per CPU core.
+pub fn thread_pool(workers: Option<usize>) -> PyResult<rayon::ThreadPool> {
+    if workers == Some(0) {
+        return Err(PyValueError::new_err("workers must be at least 1"));
+    }
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(workers.unwrap_or(0))
+        .build()
+        .map_err(|e| PyValueError::new_err(format!("Failed to start worker threads: {}", e)))
+}
+
+/// Start of the trailing whitespace run of `text`, or `None` when the text
+/// has no whitespace after its first character.
+pub fn last_whitespace_run(text: &str) -> Option<usize> {
+    let mut start = None;
+    for (i, c) in text.char_indices().rev() {
+        if c.is_whitespace() {
+            start = Some(i);
+        } else if start.is_some() {
+            break;
+        }
+    }
+    start.filter(|&i| i > 0)
+}
+
+pub use fasttokenizer_core::Token;
+
+/// A `ValueError` carrying the message of a core error.
+pub fn py_err(error: fasttokenizer_core::Error) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+/// Common interface implemented by every tokenizer.
+///
+/// Implementors provide `tokenize_with_offsets` and `token_spans`; offsets
+/// are byte offsets into the text that was passed in. Tokenizers are shared
+/// between Python threads without locking, so state that can change after
+/// construction lives in a `Shared` slot.
+pub trait Tokenizer: Send + Sync {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token>;
+
+    /// A new Python object holding this tokenizer, for pickling the classes
+    /// built around it.
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>>;
+
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        self.tokenize_with_offsets(text)
+            .into_iter()
+            .map(|t| t.text)
+            .collect()
+    }
+
+    /// `tokenize_with_offsets`, with offsets into `text` itself even when
+    /// the tokenizer normalizes it first.
+    fn tokenize_with_source_offsets(&self, text: &str) -> Vec<Token> {
+        self.tokenize_with_offsets(text)
+    }
+
+    /// The `(start, end)` spans of `tokenize_with_source_offsets` alone,
+    /// found without building the token strings.
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)>;
+
+    /// `token_spans`, failing like `try_tokenize_with_offsets`.
+    fn try_token_spans(&self, text: &str) -> Result<Vec<(usize, usize)>, String> {
+        Ok(self.token_spans(text))
+    }
+
+    /// Whether every token `tokenize` returns is the text at its span, so
+    /// that tokens can be cut out of the text from `token_spans` alone.
+    /// Subword models and tokenizers that normalize the text first return
+    /// other strings.
+    fn tokens_are_slices(&self) -> bool {
+        false
+    }
+
+    /// `tokenize_with_offsets`, failing with a message instead when the
+    /// text or its tokens exceed the tokenizer's `Limits`. The infallible
+    /// methods ignore the limits; tokenizers without limits never fail.
+    fn try_tokenize_with_offsets(&self, text: &str) -> Result<Vec<Token>, String> {
+        Ok(self.tokenize_with_offsets(text))
+    }
+
+    /// `tokenize`, failing like `try_tokenize_with_offsets`.
+    fn try_tokenize(&self, text: &str) -> Result<Vec<String>, String> {
+        Ok(self.tokenize(text))
+    }
+
+    fn tokenize_batch(&self, texts: &[String]) -> Vec<Vec<String>> {
+        texts.iter().map(|text| self.tokenize(text)).collect()
+    }
+
+    /// Turn tokens back into text. Word-level tokenizers join them with
+    /// punctuation-aware spacing; subword models undo their own markers.
+    fn detokenize(&self, tokens: &[String]) -> String {
+        join_tokens(tokens)
+    }
+}
+
+/// Extract a Rust tokenizer from any of the tokenizer classes exposed to Python.
+pub fn extract_tokenizer(obj: &Bound<'_, PyAny>) -> PyResult<Arc<dyn Tokenizer>> {
+    if let Ok(tokenizer) = obj.cast::<WhitespaceTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<HfTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<RegexTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<BpeTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<SentencePieceTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<TiktokenEncoder>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<WordPieceTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<CodeTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<CjkTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<GraphemeTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<TrieTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<KeywordTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<SocialTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(splitter) = obj.cast::<SentenceSplitter>() {
+        return Ok(Arc::new(splitter.borrow().clone()));
+    }
+    if let Ok(tokenizer) = obj.cast::<crate::polyglot::PolyglotTokenizer>() {
+        return Ok(Arc::new(tokenizer.borrow().clone()));
+    }
+    if let Ok(pipeline) = obj.cast::<crate::pipeline::TokenizerPipeline>() {
+        return Ok(Arc::new(pipeline.borrow().clone()));
+    }
+    Err(PyTypeError::new_err(format!(
+        "Expected a tokenizer, got {}",
+        obj.get_type().name()?
+    )))
+}
+
+/// The tokens of a text one at a time. When the tokenizer's tokens are
+/// slices of the text, only their spans are found up front and each token
+/// is cut out of the text as it is reached; other tokenizers' tokens are
+/// tokenized up front.
+enum Tokens<T> {
+    Slices {
+        text: T,
+        spans: std::vec::IntoIter<(usize, usize)>,
+    },
+    Owned(std::vec::IntoIter<String>),
+}
+
+impl<T: AsRef<str>> Tokens<T> {
+    /// The tokens of `text`, failing when it exceeds the tokenizer's limits.
+    fn new(tokenizer: &dyn Tokenizer, text: T) -> PyResult<Self> {
+        if tokenizer.tokens_are_slices() {
+            let spans = tokenizer
+                .try_token_spans(text.as_ref())
+                .map_err(PyValueError::new_err)?;
+            return Ok(Tokens::Slices {
+                text,
+                spans: spans.into_iter(),
+            });
+        }
+        let tokens = tokenizer
+            .try_tokenize(text.as_ref())
+            .map_err(PyValueError::new_err)?;
+        Ok(Tokens::Owned(tokens.into_iter()))
+    }
+
+    /// The next token as a Python string.
+    fn next<'py>(&mut self, py: Python<'py>) -> Option<Bound<'py, PyString>> {
+        match self {
+            Tokens::Slices { text, spans } => spans
+                .next()
+                .map(|(start, end)| PyString::new(py, &text.as_ref()[start..end])),
+            Tokens::Owned(tokens) => tokens.next().map(|token| PyString::new(py, &token)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Tokens::Slices { spans, .. } => spans.len(),
+            Tokens::Owned(tokens) => tokens.len(),
+        }
+    }
+}
+
+/// Call `callback` with each token of `text` in turn, converting one token
+/// at a time instead of building a Python list. A callback returning
+/// `False` stops the walk; the number of tokens visited is returned.
+pub fn for_each_token(
+    tokenizer: &dyn Tokenizer,
+    text: &str,
+    callback: &Bound<'_, PyAny>,
+) -> PyResult<usize> {
+    let mut tokens = Tokens::new(tokenizer, text)?;
+    let mut visited = 0;
+    while let Some(token) = tokens.next(callback.py()) {
+        visited += 1;
+        let result = callback.call1((token,))?;
+        if result.is_instance_of::<PyBool>() && !result.is_truthy()? {
+            break;
+        }
+    }
+    Ok(visited)
+}
+
+/// Lazy iterator over the tokens of a text, returned by `tokenize_iter`.
+/// It keeps its own copy of the text and cuts each token out of it only as
+/// it is reached, when the tokenizer's tokens are slices of the text.
+#[pyclass]
+pub struct TokenIterator {
+    tokens: Tokens<Arc<str>>,
+}
+
+impl TokenIterator {
+    pub fn new(tokenizer: &dyn Tokenizer, text: &str) -> PyResult<Self> {
+        Ok(TokenIterator {
+            tokens: Tokens::new(tokenizer, Arc::from(text))?,
+        })
+    }
+}
+
+#[pymethods]
+impl TokenIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> Option<Bound<'py, PyString>> {
+        self.tokens.next(py)
+    }
+
+    fn __length_hint__(&self) -> usize {
+        self.tokens.len()
+    }
+}
+
+/// A function converting byte offsets into `text` into character offsets,
+/// which is what Python slicing uses, or `None` for ASCII text where the
+/// two agree.
+///
+/// Byte-level tokens may end inside a multi-byte character; their end is
+/// rounded up so the span still covers that character.
+fn char_converter(text: &str) -> Option<impl Fn(usize, usize) -> (usize, usize) + '_> {
+    if text.is_ascii() {
+        return None;
+    }
+    let mut byte_to_char = vec![0; text.len() + 1];
+    let mut chars = 0;
+    for (i, c) in text.char_indices() {
+        for slot in &mut byte_to_char[i..i + c.len_utf8()] {
+            *slot = chars;
+        }
+        chars += 1;
+    }
+    byte_to_char[text.len()] = chars;
+    Some(move |start: usize, end: usize| {
+        let end = if text.is_char_boundary(end) {
+            byte_to_char[end]
+        } else {
+            byte_to_char[end] + 1
+        };
+        (byte_to_char[start], end)
+    })
+}
+
+/// Check that `unit` is `"char"` or `"byte"`.
+fn check_unit(unit: &str) -> PyResult<()> {
+    match unit {
+        "char" | "byte" => Ok(()),
+        _ => Err(PyValueError::new_err(format!(
+            "Unknown offset unit '{}', expected 'char' or 'byte'",
+            unit
+        ))),
+    }
+}
+
+/// Offsets of `tokens` in the requested unit: `"char"` or `"byte"`.
+pub fn offsets(
+    text: &str,
+    tokens: Vec<Token>,
+    unit: &str,
+) -> PyResult<Vec<(String, usize, usize)>> {
+    check_unit(unit)?;
+    let tokens = tokens.into_iter();
+    let convert = if unit == "char" {
+        char_converter(text)
+    } else {
+        None
+    };
+    Ok(match convert {
+        Some(convert) => tokens
+            .map(|t| {
+                let (start, end) = convert(t.start, t.end);
+                (t.text, start, end)
+            })
+            .collect(),
+        None => tokens.map(|t| (t.text, t.start, t.end)).collect(),
+    })
+}
+
+/// Byte `spans` of `text` as `(start, end)` tuples or, with
+/// `return_tensors="np"`, an `(n, 2)` int64 NumPy array, in the requested
+/// unit.
+pub fn spans<'py>(
+    py: Python<'py>,
+    text: &str,
+    mut spans: Vec<(usize, usize)>,
+    unit: &str,
+    return_tensors: Option<&str>,
+) -> PyResult<Bound<'py, PyAny>> {
+    check_unit(unit)?;
+    let convert = if unit == "char" {
+        char_converter(text)
+    } else {
+        None
+    };
+    if let Some(convert) = convert {
+        for span in &mut spans {
+            *span = convert(span.0, span.1);
+        }
+    }
+    match return_tensors {
+        None => Ok(spans.into_pyobject(py)?.into_any()),
+        Some("np") => {
+            let flat = spans
+                .iter()
+                .flat_map(|&(start, end)| [start as i64, end as i64])
+                .collect();
+            let array = Array2::from_shape_vec((spans.len(), 2), flat)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            Ok(array.into_pyarray(py).into_any())
+        }
+        Some(other) => Err(PyValueError::new_err(format!(
+            "Unsupported return_tensors '{}', expected 'np'",
+            other
+        ))),
+    }
+}
+
+/// Add the Python methods the tokenizer classes share to `$class`, each in a
+/// `#[pymethods]` block of its own (pyo3's `multiple-pymethods` feature).
+///
+/// `tokenizer_pymethods!(Class)` adds `benchmark`, `tokenize_batch`,
+/// `tokenize_iter`, `tokenize_foreach` and `tokenize_spans`, and methods
+/// listed after it, like `tokenizer_pymethods!(Class, encode_batch)`, too.
+/// `tokenizer_pymethods!(Class: ...)` adds only the listed methods, for
+/// classes with their own versions of the others. `encode_batch` needs a
+/// `vocab: Shared<Option<Vocab>>` field.
+macro_rules! tokenizer_pymethods {
+    (@benchmark $class:ty) => {
+        #[pyo3::pymethods]
+        impl $class {
+            /// Measure how fast `texts` are tokenized, e.g. to compare settings.
+            ///
+            /// Args:
+            ///     texts (List[str]): Sample inputs, tokenized once to warm up and
+            ///         then `iterations` times while timed.
+            ///     iterations (int): Timed passes over `texts`.
+            ///
+            /// Returns:
+            ///     dict: `tokens_per_sec`, `mb_per_sec` and the total `seconds`; the
+            ///     `bytes` and `tokens` of one pass; the number of `iterations`;
+            ///     and, in builds with the `alloc-stats` feature, the `allocations`
+            ///     and `allocated_bytes` of one pass.
+            #[pyo3(signature = (texts, iterations=10))]
+            fn benchmark<'py>(
+                &self,
+                py: pyo3::Python<'py>,
+                texts: Vec<String>,
+                iterations: usize,
+            ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::types::PyDict>> {
+                $crate::benchmark::benchmark(py, self, &texts, iterations)
+            }
+        }
+    };
+    (@tokenize_batch $class:ty) => {
+        #[pyo3::pymethods]
+        impl $class {
+            /// Tokenize several strings at once, releasing the GIL while working.
+            ///
+            /// Pass `intern` as for `tokenize`; a call shares one cache across all
+            /// inputs.
+            #[pyo3(signature = (inputs, intern=None))]
+            fn tokenize_batch<'py>(
+                &self,
+                py: pyo3::Python<'py>,
+                inputs: Vec<String>,
+                intern: Option<&pyo3::Bound<'py, pyo3::PyAny>>,
+            ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
+                let tokens = py
+                    .detach(|| {
+                        inputs
+                            .iter()
+                            .map(|input| $crate::tokenizer::Tokenizer::try_tokenize(self, input))
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                $crate::intern::token_lists(py, tokens, intern)
+            }
+        }
+    };
+    (@tokenize_iter $class:ty) => {
+        #[pyo3::pymethods]
+        impl $class {
+            /// Iterate over the tokens of `input`, each converted to a Python string
+            /// only when reached.
+            fn tokenize_iter(&self, input: &str) -> pyo3::PyResult<$crate::tokenizer::TokenIterator> {
+                $crate::tokenizer::TokenIterator::new(self, input)
+            }
+        }
+    };
+    (@tokenize_foreach $class:ty) => {
+        #[pyo3::pymethods]
+        impl $class {
+            /// Call `callback(token)` for each token of `input` without building a
+            /// list of them; a callback returning `False` stops early.
+            ///
+            /// Returns:
+            ///     int: Number of tokens visited.
+            fn tokenize_foreach(
+                &self,
+                input: &str,
+                callback: &pyo3::Bound<'_, pyo3::PyAny>,
+            ) -> pyo3::PyResult<usize> {
+                $crate::tokenizer::for_each_token(self, input, callback)
+            }
+        }
+    };
+    (@tokenize_spans $class:ty) => {
+        #[pyo3::pymethods]
+        impl $class {
+            /// Tokenize and return only the `(start, end)` offsets of the tokens
+            /// into `input`, for callers that slice the string themselves.
+            ///
+            /// Args:
+            ///     input (str): The input string to be tokenized.
+            ///     unit (str): `"char"` for character offsets (Python slicing) or
+            ///         `"byte"` for offsets into the UTF-8 encoded text.
+            ///     return_tensors (str, optional): `"np"` for an `(n, 2)` int64
+            ///         NumPy array instead of a list of tuples.
+            #[pyo3(signature = (input, unit="char", return_tensors=None))]
+            fn tokenize_spans<'py>(
+                &self,
+                py: pyo3::Python<'py>,
+                input: &str,
+                unit: &str,
+                return_tensors: Option<&str>,
+            ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
+                let token_spans = $crate::tokenizer::Tokenizer::try_token_spans(self, input)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                $crate::tokenizer::spans(py, input, token_spans, unit, return_tensors)
+            }
+        }
+    };
+    (@encode_batch $class:ty) => {
+        #[pyo3::pymethods]
+        impl $class {
+            /// Encode several strings at once, releasing the GIL while working.
+            fn encode_batch(
+                &self,
+                py: pyo3::Python<'_>,
+                inputs: Vec<String>,
+            ) -> pyo3::PyResult<Vec<Vec<u32>>> {
+                let vocab = $crate::vocab::require_vocab(&self.vocab)?;
+                py.detach(|| {
+                    inputs
+                        .iter()
+                        .map(|text| vocab.encode_text(self, text))
+                        .collect()
+                })
+            }
+        }
+    };
+    ($class:ty: $($method:ident),+) => {
+        $($crate::tokenizer::tokenizer_pymethods!(@$method $class);)+
+    };
+    ($class:ty $(, $method:ident)*) => {
+        $crate::tokenizer::tokenizer_pymethods!(
+            $class: benchmark, tokenize_batch, tokenize_iter, tokenize_foreach, tokenize_spans
+            $(, $method)*
+        );
+    };
+}
+
+pub(crate) use tokenizer_pymethods;
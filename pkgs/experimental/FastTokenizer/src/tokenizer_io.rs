@@ -1,6 +1,27 @@
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use pyo3::exceptions::{PyIOError, PyUserWarning, PyValueError};
 use pyo3::prelude::*;
-use std::fs::File;
-use std::io::{BufReader, Read};
+use pyo3::types::PyDict;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
+
+use crate::async_io::{self, AsyncLineIterator};
+use crate::checkpoint::{Checkpoint, Progress};
+use crate::columnar::{write_rows, Format};
+use crate::normalizer::Normalizer;
+use crate::random::{seed_or_default, Rng};
+#[cfg(feature = "remote")]
+use crate::remote::open_url;
+use crate::tokenizer::{extract_tokenizer, last_whitespace_run, thread_pool, Token, Tokenizer};
+use crate::utf8::Utf8Errors;
 
 #[pyclass]
 pub struct TokenizerIO {
@@ -15,22 +36,1385 @@ impl TokenizerIO {
     }
 
     // Add methods for file I/O operations
-    fn read_file(&self, path: &str) -> PyResult<String> {
-        match File::open(path) {
-            Ok(file) => {
-                let mut reader = BufReader::new(file);
-                let mut content = String::new();
-                if let Err(e) = reader.read_to_string(&mut content) {
-                    return Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
-                        "Failed to read file: {}",
-                        e
-                    )));
-                }
-                Ok(content)
-            }
-            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
-                "Failed to open file: {}",
-                e
+    /// Read a whole file into a string.
+    ///
+    /// Every reader also takes `http://`, `https://` and `s3://` URLs as
+    /// `path`, streaming the object instead of staging it on disk.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     compression (str): `"auto"` detects gzip, zstd and bzip2 from the
+    ///         file's magic bytes; `"gzip"`, `"zstd"`, `"bz2"` or `"none"`
+    ///         force a format.
+    ///     errors (str): What to do with bytes that are not valid UTF-8:
+    ///         raise an IOError (`"strict"`), `"replace"` them with U+FFFD
+    ///         or `"ignore"` them.
+    #[pyo3(signature = (path, compression="auto", errors="strict"))]
+    fn read_file(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<String> {
+        let errors = Utf8Errors::parse(errors)?;
+        py.detach(|| read_text(path, compression, errors))
+    }
+
+    /// Iterate over the lines of a file without loading it into memory.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`.
+    ///
+    /// Returns:
+    ///     Iterator[str]: Lines without their `\n` or `\r\n` terminator.
+    #[pyo3(signature = (path, compression="auto", errors="strict"))]
+    fn iter_lines(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<RecordIterator> {
+        Ok(RecordIterator::new(
+            py.detach(|| open_reader(path, compression))?,
+            b"\n",
+            true,
+            Utf8Errors::parse(errors)?,
+        ))
+    }
+
+    /// Read a whole file into a string on a background thread, for use
+    /// with `await` inside a running event loop.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`.
+    ///
+    /// Returns:
+    ///     Awaitable[str]: The contents of the file.
+    #[pyo3(signature = (path, compression="auto", errors="strict"))]
+    fn aread_text<'py>(
+        &self,
+        py: Python<'py>,
+        path: String,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let errors = Utf8Errors::parse(errors)?;
+        let compression = compression.to_string();
+        async_io::spawn(py, move || read_text(&path, &compression, errors))
+    }
+
+    /// Iterate over the lines of a file with `async for`, reading ahead on
+    /// background threads.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`.
+    ///
+    /// Returns:
+    ///     AsyncIterator[str]: Lines without their `\n` or `\r\n`
+    ///     terminator.
+    #[pyo3(signature = (path, compression="auto", errors="strict"))]
+    fn aiter_lines(
+        &self,
+        path: &str,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<AsyncLineIterator> {
+        Ok(AsyncLineIterator::new(
+            path,
+            compression,
+            Utf8Errors::parse(errors)?,
+        ))
+    }
+
+    /// Iterate over the records of a file separated by `delimiter`.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     delimiter (str): Record separator, e.g. `"\n\n"` or `"\x1e"`.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`.
+    ///
+    /// Returns:
+    ///     Iterator[str]: Records without the delimiter. A delimiter at the
+    ///     very end of the file does not produce an empty last record.
+    #[pyo3(signature = (path, delimiter, compression="auto", errors="strict"))]
+    fn iter_records(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        delimiter: &str,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<RecordIterator> {
+        if delimiter.is_empty() {
+            return Err(PyValueError::new_err("delimiter must not be empty"));
+        }
+        Ok(RecordIterator::new(
+            py.detach(|| open_reader(path, compression))?,
+            delimiter.as_bytes(),
+            false,
+            Utf8Errors::parse(errors)?,
+        ))
+    }
+
+    /// Iterate over one string field of a JSON lines file.
+    ///
+    /// Args:
+    ///     path (str): JSON lines file, optionally compressed.
+    ///     field (str): Key of the field to yield from each object.
+    ///     on_error (str): What to do with lines that are not valid JSON
+    ///         objects or lack a string `field`: `"raise"` a ValueError,
+    ///         `"skip"` them silently or `"warn"` and skip them. Skipped
+    ///         lines are counted in the iterator's `skipped` attribute.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): What to do with bytes that are not valid UTF-8:
+    ///         treat their line as malformed (`"strict"`), `"replace"` them
+    ///         with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     Iterator[str]: The field of each valid line. Blank lines are ignored.
+    #[pyo3(signature = (path, field="text", on_error="raise", compression="auto", errors="strict"))]
+    fn iter_jsonl(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        field: &str,
+        on_error: &str,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<JsonlIterator> {
+        let on_error = match on_error {
+            "raise" => OnError::Raise,
+            "skip" => OnError::Skip,
+            "warn" => OnError::Warn,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown on_error policy '{}', expected 'raise', 'skip' or 'warn'",
+                    other
+                )))
+            }
+        };
+        let errors = Utf8Errors::parse(errors)?;
+        let reader = py.detach(|| open_reader(path, compression))?;
+        Ok(JsonlIterator {
+            lines: RecordIterator::new(reader, b"\n", true, errors),
+            field: field.to_string(),
+            on_error,
+            line: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Iterate over one column of a CSV or TSV file.
+    ///
+    /// Quoted fields may contain delimiters, doubled quotes and newlines.
+    ///
+    /// Args:
+    ///     path (str): Delimited text file, optionally compressed.
+    ///     column (int | str): Zero-based column index, or a header name when
+    ///         `has_header` is set.
+    ///     delimiter (str): Single-byte field separator, e.g. `"\t"` for TSV.
+    ///     has_header (bool): Whether the first row holds column names rather
+    ///         than data.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`; applies to the selected column.
+    ///
+    /// Returns:
+    ///     Iterator[str]: The column's value in each row.
+    #[pyo3(signature = (path, column, delimiter=",", has_header=true, compression="auto", errors="strict"))]
+    #[allow(clippy::too_many_arguments)]
+    fn iter_csv(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        column: Column,
+        delimiter: &str,
+        has_header: bool,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<CsvIterator> {
+        let errors = Utf8Errors::parse(errors)?;
+        let &[delimiter] = delimiter.as_bytes() else {
+            return Err(PyValueError::new_err(
+                "delimiter must be a single-byte character",
+            ));
+        };
+        let reader = py.detach(|| open_reader(path, compression))?;
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(has_header)
+            .flexible(true)
+            .from_reader(reader);
+        let column = match column {
+            Column::Index(index) => index,
+            Column::Name(name) if has_header => py
+                .detach(|| {
+                    reader
+                        .headers()
+                        .map(|h| h.iter().position(|header| header == name))
+                })
+                .map_err(csv_error)?
+                .ok_or_else(|| PyValueError::new_err(format!("No column named '{}'", name)))?,
+            Column::Name(_) => {
+                return Err(PyValueError::new_err(
+                    "Selecting a column by name requires has_header=True",
+                ))
+            }
+        };
+        Ok(CsvIterator {
+            reader,
+            column,
+            errors,
+            record: csv::ByteRecord::new(),
+        })
+    }
+
+    /// List the files of a directory or glob pattern.
+    ///
+    /// Args:
+    ///     pattern_or_dir (str): A directory, or a glob pattern such as
+    ///         `"data/**/*.txt"`.
+    ///     recursive (bool): Descend into subdirectories of a directory.
+    ///     extensions (List[str], optional): Keep only files whose name ends
+    ///         with one of these extensions, e.g. `["txt", "jsonl.gz"]`.
+    ///     min_size (int, optional): Minimum file size in bytes.
+    ///     max_size (int, optional): Maximum file size in bytes.
+    ///
+    /// Returns:
+    ///     List[str]: Sorted file paths.
+    #[pyo3(signature = (pattern_or_dir, recursive=true, extensions=None, min_size=None, max_size=None))]
+    fn walk(
+        &self,
+        pattern_or_dir: &str,
+        recursive: bool,
+        extensions: Option<Vec<String>>,
+        min_size: Option<u64>,
+        max_size: Option<u64>,
+    ) -> PyResult<Vec<String>> {
+        let walk_error = |e: String| PyIOError::new_err(format!("Failed to list files: {}", e));
+        let candidates: Vec<PathBuf> = if Path::new(pattern_or_dir).is_dir() {
+            let depth = if recursive { usize::MAX } else { 1 };
+            walkdir::WalkDir::new(pattern_or_dir)
+                .max_depth(depth)
+                .into_iter()
+                .map(|entry| entry.map(walkdir::DirEntry::into_path))
+                .collect::<Result<_, _>>()
+                .map_err(|e| walk_error(e.to_string()))?
+        } else {
+            glob::glob(pattern_or_dir)
+                .map_err(|e| PyValueError::new_err(format!("Invalid glob pattern: {}", e)))?
+                .collect::<Result<_, _>>()
+                .map_err(|e| walk_error(e.to_string()))?
+        };
+        let suffixes: Option<Vec<String>> = extensions.map(|extensions| {
+            extensions
+                .iter()
+                .map(|ext| format!(".{}", ext.trim_start_matches('.')))
+                .collect()
+        });
+        let mut paths = Vec::new();
+        for path in candidates {
+            let metadata = fs::metadata(&path).map_err(|e| walk_error(e.to_string()))?;
+            if !metadata.is_file()
+                || min_size.is_some_and(|min| metadata.len() < min)
+                || max_size.is_some_and(|max| metadata.len() > max)
+            {
+                continue;
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if let Some(suffixes) = &suffixes {
+                if !suffixes
+                    .iter()
+                    .any(|suffix| name.ends_with(suffix.as_str()))
+                {
+                    continue;
+                }
+            }
+            paths.push(path.to_string_lossy().into_owned());
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Draw a uniform random sample of the lines (or records) of a file.
+    ///
+    /// Reservoir sampling reads the file once and keeps only `n` records in
+    /// memory, however large the file is.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     n (int): Number of records to draw; all of them when the file
+    ///         has fewer.
+    ///     seed (int, optional): The same seed draws the same sample; 0, or
+    ///         the next `set_global_seed` seed, when not given.
+    ///     delimiter (str, optional): Record separator; lines by default.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`.
+    ///
+    /// Returns:
+    ///     List[str]: The sampled records, in file order.
+    #[pyo3(signature = (path, n, seed=None, delimiter=None, compression="auto", errors="strict"))]
+    #[allow(clippy::too_many_arguments)]
+    fn sample(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        n: usize,
+        seed: Option<u64>,
+        delimiter: Option<&str>,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<Vec<String>> {
+        let errors = Utf8Errors::parse(errors)?;
+        let reader = py.detach(|| open_reader(path, compression))?;
+        let mut records = records_of(reader, delimiter, errors)?;
+        let mut rng = Rng::new(seed_or_default(seed));
+        let mut reservoir: Vec<(u64, Vec<u8>)> = Vec::with_capacity(n.min(1 << 16));
+        py.detach(|| {
+            let mut seen = 0u64;
+            while let Some(record) = records.read_record()? {
+                if reservoir.len() < n {
+                    reservoir.push((seen, record));
+                } else {
+                    let slot = rng.below(seen + 1) as usize;
+                    if slot < n {
+                        reservoir[slot] = (seen, record);
+                    }
+                }
+                seen += 1;
+            }
+            Ok::<_, PyErr>(())
+        })?;
+        reservoir.sort_unstable_by_key(|&(index, _)| index);
+        reservoir
+            .into_iter()
+            .map(|(index, bytes)| {
+                errors.decode(bytes).map_err(|_| {
+                    PyIOError::new_err(format!(
+                        "Failed to read file {}: invalid UTF-8 in record {}",
+                        path,
+                        index + 1
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Split the lines (or records) of a file into random shards, such as
+    /// train, validation and test sets, in one streaming pass.
+    ///
+    /// Each record goes to a shard with probability proportional to its
+    /// ratio, so shard sizes match the ratios up to sampling noise. Records
+    /// are copied byte for byte and keep their order within a shard.
+    ///
+    /// Args:
+    ///     path (str): Text file, optionally compressed.
+    ///     output_dir (str): Directory to write the shards to, named after the
+    ///         input with the shard name before its extension, e.g.
+    ///         `corpus.train.txt` for `corpus.txt.gz`. A shard only appears
+    ///         once all of them are complete.
+    ///     ratios (List[float]): Relative shard sizes.
+    ///     names (List[str], optional): Shard names; `train` and `test` for
+    ///         two ratios and `train`, `val` and `test` for three by default.
+    ///     seed (int, optional): The same seed gives the same split; 0, or
+    ///         the next `set_global_seed` seed, when not given.
+    ///     delimiter (str, optional): Record separator; lines by default.
+    ///     compression (str): See `read_file`.
+    ///
+    /// Returns:
+    ///     Dict[str, str]: The path written for each shard name.
+    #[pyo3(signature = (path, output_dir, ratios=vec![0.8, 0.1, 0.1], names=None, seed=None, delimiter=None, compression="auto"))]
+    #[allow(clippy::too_many_arguments)]
+    fn split<'py>(
+        &self,
+        py: Python<'py>,
+        path: &str,
+        output_dir: PathBuf,
+        ratios: Vec<f64>,
+        names: Option<Vec<String>>,
+        seed: Option<u64>,
+        delimiter: Option<&str>,
+        compression: &str,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let names = split_names(names, ratios.len())?;
+        let seed = seed_or_default(seed);
+        let total: f64 = ratios.iter().sum();
+        if ratios.iter().any(|r| !(0.0..).contains(r)) || !(total > 0.0 && total.is_finite()) {
+            return Err(PyValueError::new_err(
+                "ratios must be non-negative with a positive sum",
+            ));
+        }
+        let reader = py.detach(|| open_reader(path, compression))?;
+        let mut records = records_of(reader, delimiter, Utf8Errors::Strict)?;
+        let name = Path::new(path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let name = [".gz", ".zst", ".bz2"]
+            .iter()
+            .find_map(|ext| name.strip_suffix(ext))
+            .unwrap_or(&name);
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+            _ => (name, String::new()),
+        };
+        let outputs: Vec<PathBuf> = names
+            .iter()
+            .map(|shard| output_dir.join(format!("{}.{}{}", stem, shard, extension)))
+            .collect();
+        fs::create_dir_all(&output_dir).map_err(|e| {
+            PyIOError::new_err(format!("Failed to create {}: {}", output_dir.display(), e))
+        })?;
+        let separator = delimiter.unwrap_or("\n").as_bytes();
+        let mut bounds: Vec<f64> = ratios
+            .iter()
+            .scan(0.0, |sum, ratio| {
+                *sum += ratio / total;
+                Some(*sum)
+            })
+            .collect();
+        if let Some(last) = bounds.last_mut() {
+            *last = f64::INFINITY;
+        }
+        let written = py.detach(|| {
+            let partials: Vec<PathBuf> =
+                outputs.iter().map(|output| partial_path(output)).collect();
+            let result = (|| {
+                let mut writers = partials
+                    .iter()
+                    .map(|partial| File::create(partial).map(BufWriter::new))
+                    .collect::<io::Result<Vec<_>>>()?;
+                let mut rng = Rng::new(seed);
+                while let Some(record) = records.next_record()? {
+                    let u = rng.next_f64();
+                    let shard = bounds.iter().position(|&bound| u < bound).unwrap_or(0);
+                    writers[shard].write_all(&record)?;
+                    writers[shard].write_all(separator)?;
+                }
+                for writer in writers {
+                    writer
+                        .into_inner()
+                        .map_err(|e| e.into_error())?
+                        .sync_all()?;
+                }
+                for (partial, output) in partials.iter().zip(&outputs) {
+                    fs::rename(partial, output)?;
+                }
+                Ok(())
+            })();
+            if result.is_err() {
+                for partial in &partials {
+                    let _ = fs::remove_file(partial);
+                }
+            }
+            result
+        });
+        written.map_err(|e: io::Error| {
+            PyIOError::new_err(format!("Failed to split {}: {}", path, e))
+        })?;
+        let shards = PyDict::new(py);
+        for (shard, output) in names.iter().zip(&outputs) {
+            shards.set_item(shard, output.to_string_lossy())?;
+        }
+        Ok(shards)
+    }
+
+    /// Tokenize many files in parallel.
+    ///
+    /// Files are read (decompressing them if needed), normalized and
+    /// tokenized on a thread pool without holding the GIL. Given an
+    /// `output_dir`, each file is streamed line by line and its tokens are
+    /// written there instead of being returned, so corpora far larger than
+    /// memory are processed in one call.
+    ///
+    /// Args:
+    ///     paths (List[str]): Files to tokenize, e.g. from `walk`.
+    ///     tokenizer: Any FastTokenizer tokenizer.
+    ///     output_dir (str, optional): Directory to write one token file per
+    ///         input to, named after the input without its compression
+    ///         extension plus `.tokens.txt` or `.tokens.jsonl`. Input names
+    ///         must be unique. Each output line holds the tokens of one input
+    ///         line; a file only appears once it is complete.
+    ///     workers (int, optional): Number of threads; defaults to one per
+    ///         CPU core.
+    ///     normalizer (Normalizer, optional): Applied before tokenization.
+    ///     progress (Callable[[int, int], Any], optional): Called on the
+    ///         calling thread with the number of files done and the total
+    ///         after each file. An exception it raises stops the job.
+    ///     format (str): `"txt"` writes the tokens of a line separated by
+    ///         spaces, `"jsonl"` as a JSON array.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`. With `"replace"` or `"ignore"`, an
+    ///         invalid byte no longer aborts a long job.
+    ///     checkpoint (str, optional): JSON file to save the job's progress
+    ///         to, requiring `output_dir`: the files completed and, for files
+    ///         being written, the input bytes tokenized so far. Rerunning the
+    ///         job with the same checkpoint skips the completed files and
+    ///         continues the others from where they were saved.
+    ///     checkpoint_interval (float): Seconds between saves while a file
+    ///         is written; the checkpoint is also saved after every file.
+    ///
+    /// Returns:
+    ///     List[List[str]] | List[str]: The tokens of each file, or with
+    ///     `output_dir` the path of each file written, in the order of `paths`.
+    #[pyo3(signature = (paths, tokenizer, output_dir=None, workers=None, normalizer=None, progress=None, format="txt", compression="auto", errors="strict", checkpoint=None, checkpoint_interval=60.0))]
+    #[allow(clippy::too_many_arguments)]
+    fn tokenize_corpus<'py>(
+        &self,
+        py: Python<'py>,
+        paths: Vec<String>,
+        tokenizer: &Bound<'py, PyAny>,
+        output_dir: Option<PathBuf>,
+        workers: Option<usize>,
+        normalizer: Option<PyRef<'_, Normalizer>>,
+        progress: Option<&Bound<'py, PyAny>>,
+        format: &str,
+        compression: &str,
+        errors: &str,
+        checkpoint: Option<PathBuf>,
+        checkpoint_interval: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let tokenizer = extract_tokenizer(tokenizer)?;
+        let errors = Utf8Errors::parse(errors)?;
+        let normalizer = normalizer.map(|n| n.clone());
+        let format = OutputFormat::parse(format)?;
+        let pool = thread_pool(workers)?;
+        let tokenize = |text: &str| match &normalizer {
+            Some(normalizer) => tokenizer.tokenize(&normalizer.apply(text)),
+            None => tokenizer.tokenize(text),
+        };
+        let Some(output_dir) = output_dir else {
+            if checkpoint.is_some() {
+                return Err(PyValueError::new_err("checkpoint needs an output_dir"));
+            }
+            let tokens = run_jobs(py, &pool, &paths, progress, |path| {
+                Ok(tokenize(&read_text(path, compression, errors)?))
+            })?;
+            return Ok(tokens.into_pyobject(py)?.into_any());
+        };
+        let outputs = output_paths(&paths, &output_dir, format)?;
+        fs::create_dir_all(&output_dir).map_err(|e| {
+            PyIOError::new_err(format!("Failed to create {}: {}", output_dir.display(), e))
+        })?;
+        let checkpoint = checkpoint
+            .map(|path| Checkpoint::open(path, format.name(), checkpoint_interval))
+            .transpose()?;
+        let jobs: Vec<(&String, &PathBuf)> = paths.iter().zip(&outputs).collect();
+        run_jobs(py, &pool, &jobs, progress, |(path, output)| {
+            let checkpoint = checkpoint.as_ref();
+            if checkpoint.is_some_and(|c| c.is_completed(path)) && output.exists() {
+                return Ok(());
+            }
+            let reader = open_reader(path, compression)?;
+            write_token_file(reader, path, output, format, errors, &tokenize, checkpoint)
+                .map_err(|e| PyIOError::new_err(format!("Failed to tokenize {}: {}", path, e)))
+        })?;
+        let outputs: Vec<String> = outputs
+            .iter()
+            .map(|output| output.to_string_lossy().into_owned())
+            .collect();
+        Ok(outputs.into_pyobject(py)?.into_any())
+    }
+
+    /// Write tokenized rows to a Parquet file.
+    ///
+    /// Args:
+    ///     output (str): Path of the Parquet file to create.
+    ///     batches (Iterable[List[str] | List[int]]): One row per item, e.g.
+    ///         a `tokenize_file` stream or the result of `tokenize_corpus`.
+    ///         Token lists become a `list<string>` column named `tokens`, id
+    ///         lists a `list<uint32>` column named `input_ids`.
+    ///     column (str, optional): Overrides the column name.
+    ///     compression (str): `"snappy"`, `"zstd"` or `"none"`.
+    ///     row_group_size (int): Rows buffered per Parquet row group.
+    ///
+    /// Returns:
+    ///     int: Number of rows written.
+    #[pyo3(signature = (output, batches, column=None, compression="snappy", row_group_size=8192))]
+    fn write_tokens_parquet(
+        &self,
+        py: Python<'_>,
+        output: &str,
+        batches: &Bound<'_, PyAny>,
+        column: Option<String>,
+        compression: &str,
+        row_group_size: usize,
+    ) -> PyResult<usize> {
+        let format = Format::parquet(compression)?;
+        write_rows(py, output, batches, column, row_group_size, format)
+    }
+
+    /// Write tokenized rows to an Arrow IPC (Feather v2) file.
+    ///
+    /// Args:
+    ///     output (str): Path of the Arrow file to create.
+    ///     batches (Iterable[List[str] | List[int]]): See `write_tokens_parquet`.
+    ///     column (str, optional): Overrides the column name.
+    ///     batch_size (int): Rows per Arrow record batch.
+    ///
+    /// Returns:
+    ///     int: Number of rows written.
+    #[pyo3(signature = (output, batches, column=None, batch_size=8192))]
+    fn write_tokens_arrow(
+        &self,
+        py: Python<'_>,
+        output: &str,
+        batches: &Bound<'_, PyAny>,
+        column: Option<String>,
+        batch_size: usize,
+    ) -> PyResult<usize> {
+        write_rows(py, output, batches, column, batch_size, Format::ArrowIpc)
+    }
+
+    /// Tokenize a file of any size, reading it in chunks.
+    ///
+    /// Chunks are cut where their last whitespace run begins and the rest is
+    /// carried over to the next chunk, so tokens never straddle a chunk
+    /// boundary as long as the tokenizer does not match across whitespace.
+    ///
+    /// Args:
+    ///     path (str): UTF-8 text file, optionally compressed.
+    ///     tokenizer: Any FastTokenizer tokenizer.
+    ///     chunk_size (int): Bytes read per step.
+    ///     with_offsets (bool): Yield `(token, start, end)` with byte offsets
+    ///         into the (decompressed) text instead of plain tokens.
+    ///     compression (str): See `read_file`.
+    ///     errors (str): See `read_file`. Offsets then count replacement
+    ///         characters instead of the invalid bytes they stand for.
+    ///
+    /// Returns:
+    ///     Iterator[List[str]]: The tokens of one chunk per step, produced lazily.
+    #[pyo3(signature = (path, tokenizer, chunk_size=1 << 20, with_offsets=false, compression="auto", errors="strict"))]
+    #[allow(clippy::too_many_arguments)]
+    fn tokenize_file(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        tokenizer: &Bound<'_, PyAny>,
+        chunk_size: usize,
+        with_offsets: bool,
+        compression: &str,
+        errors: &str,
+    ) -> PyResult<TokenStream> {
+        Ok(TokenStream {
+            reader: py.detach(|| open_reader(path, compression))?,
+            tokenizer: extract_tokenizer(tokenizer)?,
+            chunk_size: chunk_size.max(1),
+            with_offsets,
+            errors: Utf8Errors::parse(errors)?,
+            buffer: Vec::new(),
+            offset: 0,
+            eof: false,
+        })
+    }
+}
+
+/// A buffered reader that can live inside a Python object.
+pub type Reader = Box<dyn BufRead + Send + Sync>;
+
+/// Whether `path` is a URL for `open_url` rather than a local file.
+fn is_url(path: &str) -> bool {
+    ["http://", "https://", "s3://"]
+        .iter()
+        .any(|scheme| path.starts_with(scheme))
+}
+
+#[cfg(not(feature = "remote"))]
+fn open_url(url: &str) -> PyResult<Box<dyn Read + Send + Sync>> {
+    Err(PyValueError::new_err(format!(
+        "Reading {} needs fasttokenizer built with the `remote` feature",
+        url
+    )))
+}
+
+/// Compression formats recognised by `open_reader`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Recognise a compressed stream by its magic bytes.
+    fn sniff(head: &[u8]) -> Self {
+        if head.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else if head.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Open `path` for buffered reading, decompressing it on the fly.
+///
+/// `compression` is `"auto"` to detect gzip, zstd and bzip2 from the file's
+/// magic bytes, or one of `"gzip"`, `"zstd"`, `"bz2"` and `"none"` to force
+/// a format.
+pub fn open_reader(path: &str, compression: &str) -> PyResult<Reader> {
+    let open_error = |e: io::Error| PyIOError::new_err(format!("Failed to open file: {}", e));
+    let source: Box<dyn Read + Send + Sync> = if is_url(path) {
+        open_url(path)?
+    } else {
+        Box::new(File::open(path).map_err(open_error)?)
+    };
+    let mut file = BufReader::new(source);
+    let compression = match compression {
+        "auto" => Compression::sniff(file.fill_buf().map_err(open_error)?),
+        "none" => Compression::None,
+        "gzip" | "gz" => Compression::Gzip,
+        "zstd" | "zst" => Compression::Zstd,
+        "bz2" | "bzip2" => Compression::Bzip2,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown compression '{}', expected 'auto', 'gzip', 'zstd', 'bz2' or 'none'",
+                other
+            )))
+        }
+    };
+    tracing::debug!(path, ?compression, "opened file");
+    Ok(match compression {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        Compression::Zstd => Box::new(BufReader::new(
+            zstd::Decoder::with_buffer(file).map_err(open_error)?,
+        )),
+        Compression::Bzip2 => Box::new(BufReader::new(MultiBzDecoder::new(file))),
+    })
+}
+
+/// Read all of `path` into a string, decompressing it if needed and
+/// handling invalid UTF-8 as `errors` says.
+pub fn read_text(path: &str, compression: &str, errors: Utf8Errors) -> PyResult<String> {
+    let mut bytes = Vec::new();
+    open_reader(path, compression)?
+        .read_to_end(&mut bytes)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read file {}: {}", path, e)))?;
+    errors.decode(bytes).map_err(|e| {
+        PyIOError::new_err(format!(
+            "Failed to read file {}: invalid UTF-8 at byte {}",
+            path,
+            e.utf8_error().valid_up_to()
+        ))
+    })
+}
+
+/// Run `work` on every job on `pool` with the GIL released, returning the
+/// results in the order of `jobs`.
+///
+/// The calling thread waits for the workers, calling `progress` (if any)
+/// with the number of jobs done and the total after each one and checking
+/// for signals such as Ctrl-C. The first error stops the jobs not yet
+/// started and is returned once the running ones finish.
+fn run_jobs<J: Sync, T: Send>(
+    py: Python<'_>,
+    pool: &rayon::ThreadPool,
+    jobs: &[J],
+    progress: Option<&Bound<'_, PyAny>>,
+    work: impl Fn(&J) -> PyResult<T> + Sync,
+) -> PyResult<Vec<T>> {
+    let progress = progress.map(|callback| callback.clone().unbind());
+    let cancelled = AtomicBool::new(false);
+    let mut results: Vec<Option<T>> = jobs.iter().map(|_| None).collect();
+    let mut error = None;
+    tracing::debug!(
+        jobs = jobs.len(),
+        threads = pool.current_num_threads(),
+        "running jobs"
+    );
+    py.detach(|| {
+        pool.in_place_scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for (index, job) in jobs.iter().enumerate() {
+                let sender = sender.clone();
+                let (work, cancelled) = (&work, &cancelled);
+                scope.spawn(move |_| {
+                    let result = (!cancelled.load(Ordering::Relaxed)).then(|| work(job));
+                    // The receiver outlives the scope, so sending cannot fail.
+                    let _ = sender.send((index, result));
+                });
+            }
+            drop(sender);
+            for (done, (index, result)) in receiver.into_iter().enumerate() {
+                match result {
+                    Some(Ok(value)) => results[index] = Some(value),
+                    Some(Err(e)) => {
+                        cancelled.store(true, Ordering::Relaxed);
+                        error.get_or_insert(e);
+                    }
+                    None => {}
+                }
+                if error.is_some() {
+                    continue;
+                }
+                let reported = Python::attach(|py| {
+                    py.check_signals()?;
+                    if let Some(progress) = &progress {
+                        progress.call1(py, (done + 1, jobs.len()))?;
+                    }
+                    Ok(())
+                });
+                if let Err(e) = reported {
+                    cancelled.store(true, Ordering::Relaxed);
+                    error = Some(e);
+                }
+            }
+        })
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(results
+            .into_iter()
+            .map(|result| result.expect("every job finished"))
+            .collect()),
+    }
+}
+
+/// How `tokenize_corpus` writes the tokens of a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Jsonl,
+}
+
+impl OutputFormat {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "txt" => Ok(OutputFormat::Text),
+            "jsonl" => Ok(OutputFormat::Jsonl),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown format '{}', expected 'txt' or 'jsonl'",
+                other
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Jsonl => "jsonl",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "tokens.txt",
+            OutputFormat::Jsonl => "tokens.jsonl",
+        }
+    }
+}
+
+/// The file in `output_dir` that `tokenize_corpus` writes for each path.
+fn output_paths(
+    paths: &[String],
+    output_dir: &Path,
+    format: OutputFormat,
+) -> PyResult<Vec<PathBuf>> {
+    let mut seen = HashSet::new();
+    paths
+        .iter()
+        .map(|path| {
+            let name = Path::new(path)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let stem = [".gz", ".zst", ".bz2"]
+                .iter()
+                .find_map(|ext| name.strip_suffix(ext))
+                .unwrap_or(&name);
+            let output = output_dir.join(format!("{}.{}", stem, format.extension()));
+            if !seen.insert(output.clone()) {
+                return Err(PyValueError::new_err(format!(
+                    "Several inputs would be written to {}",
+                    output.display()
+                )));
+            }
+            Ok(output)
+        })
+        .collect()
+}
+
+/// A record iterator over lines, or over records separated by `delimiter`.
+fn records_of(
+    reader: Reader,
+    delimiter: Option<&str>,
+    errors: Utf8Errors,
+) -> PyResult<RecordIterator> {
+    match delimiter {
+        None => Ok(RecordIterator::new(reader, b"\n", true, errors)),
+        Some("") => Err(PyValueError::new_err("delimiter must not be empty")),
+        Some(delimiter) => Ok(RecordIterator::new(
+            reader,
+            delimiter.as_bytes(),
+            false,
+            errors,
+        )),
+    }
+}
+
+/// Shard names for `split`: the given ones, or defaults for two or three
+/// shards.
+fn split_names(names: Option<Vec<String>>, shards: usize) -> PyResult<Vec<String>> {
+    let names = match names {
+        Some(names) => names,
+        None => match shards {
+            2 => vec!["train".to_string(), "test".to_string()],
+            3 => vec!["train".to_string(), "val".to_string(), "test".to_string()],
+            _ => {
+                return Err(PyValueError::new_err(
+                    "names are required unless there are two or three ratios",
+                ))
+            }
+        },
+    };
+    if names.len() != shards || names.is_empty() {
+        return Err(PyValueError::new_err(format!(
+            "Got {} names for {} ratios",
+            names.len(),
+            shards
+        )));
+    }
+    if names.iter().collect::<HashSet<_>>().len() != names.len() {
+        return Err(PyValueError::new_err("Shard names must be unique"));
+    }
+    Ok(names)
+}
+
+/// The temporary file `output` is written to before being renamed.
+pub fn partial_path(output: &Path) -> PathBuf {
+    let mut partial = output.as_os_str().to_owned();
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+/// Tokenize `reader` line by line into `output`, writing to a temporary
+/// file first so that a failed or interrupted job leaves no partial output.
+///
+/// With a `checkpoint`, the temporary file is kept instead and its progress
+/// saved every `checkpoint.interval`, and a file the checkpoint has progress
+/// for continues from there. Resuming fails if the lines before that point
+/// are no longer the ones tokenized, since their tokens would be stale.
+fn write_token_file(
+    reader: Reader,
+    input: &str,
+    output: &Path,
+    format: OutputFormat,
+    errors: Utf8Errors,
+    tokenize: &dyn Fn(&str) -> Vec<String>,
+    checkpoint: Option<&Checkpoint>,
+) -> io::Result<()> {
+    let partial = partial_path(output);
+    let written = (|| {
+        // Progress only counts if the output it describes is still there.
+        let resumed = checkpoint
+            .and_then(|checkpoint| checkpoint.progress(input))
+            .filter(|progress| {
+                fs::metadata(&partial).is_ok_and(|meta| meta.len() >= progress.written as u64)
+            });
+        let mut progress = Progress::default();
+        let mut lines = RecordIterator::new(reader, b"\n", true, errors);
+        let file = match resumed {
+            Some(resumed) => {
+                while lines.offset < resumed.offset {
+                    let Some(bytes) = lines.next_record()? else {
+                        break;
+                    };
+                    progress.hash_line(&bytes);
+                }
+                if lines.offset < resumed.offset {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "input is shorter than at its checkpoint",
+                    ));
+                }
+                if lines.offset != resumed.offset || progress.prefix_hash != resumed.prefix_hash {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "input changed since its checkpoint; remove its partial output to start over",
+                    ));
+                }
+                progress = resumed;
+                let file = OpenOptions::new().append(true).open(&partial)?;
+                file.set_len(progress.written as u64)?;
+                tracing::debug!(input, progress.offset, "resumed file");
+                file
+            }
+            None => File::create(&partial)?,
+        };
+        let mut writer = BufWriter::new(file);
+        let mut saved = Instant::now();
+        while let Some(bytes) = lines.next_record()? {
+            progress.lines += 1;
+            progress.hash_line(&bytes);
+            let text = errors.decode(bytes).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid UTF-8 on line {}", progress.lines),
+                )
+            })?;
+            let tokens = tokenize(&text);
+            let mut line = match format {
+                OutputFormat::Text => tokens.join(" ").into_bytes(),
+                OutputFormat::Jsonl => serde_json::to_vec(&tokens)?,
+            };
+            line.push(b'\n');
+            writer.write_all(&line)?;
+            progress.offset = lines.offset;
+            progress.written += line.len();
+            if let Some(checkpoint) = checkpoint.filter(|c| saved.elapsed() >= c.interval) {
+                writer.flush()?;
+                writer.get_ref().sync_data()?;
+                checkpoint.record(input, progress)?;
+                saved = Instant::now();
+            }
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&partial, output)?;
+        match checkpoint {
+            Some(checkpoint) => checkpoint.complete(input),
+            None => Ok(()),
+        }
+    })();
+    if written.is_err() && checkpoint.is_none() {
+        let _ = fs::remove_file(&partial);
+    }
+    written
+}
+
+/// Lazy iterator over the token batches of a file, returned by
+/// `TokenizerIO.tokenize_file`.
+#[pyclass]
+pub struct TokenStream {
+    reader: Reader,
+    tokenizer: Arc<dyn Tokenizer>,
+    chunk_size: usize,
+    with_offsets: bool,
+    errors: Utf8Errors,
+    /// Bytes read but not yet tokenized.
+    buffer: Vec<u8>,
+    /// File offset of `buffer[0]`.
+    offset: usize,
+    eof: bool,
+}
+
+impl TokenStream {
+    fn next_batch(&mut self) -> io::Result<Option<Vec<Token>>> {
+        loop {
+            if !self.eof {
+                let read = (&mut self.reader)
+                    .take(self.chunk_size as u64)
+                    .read_to_end(&mut self.buffer)?;
+                self.eof = read == 0;
+            }
+            if self.eof && self.buffer.is_empty() {
+                return Ok(None);
+            }
+            let repaired = match self.errors.repair(&self.buffer, self.eof) {
+                Ok(Cow::Owned(repaired)) => Some(repaired),
+                Ok(Cow::Borrowed(_)) => None,
+                Err(pos) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid UTF-8 at byte {}", self.offset + pos),
+                    ))
+                }
+            };
+            if let Some(repaired) = repaired {
+                self.buffer = repaired;
+            }
+            // Only a character cut off at the end of the chunk can be left.
+            let valid = match std::str::from_utf8(&self.buffer) {
+                Ok(text) => text.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            let text = std::str::from_utf8(&self.buffer[..valid]).expect("validated above");
+            let cut = if self.eof {
+                Some(valid)
+            } else {
+                last_whitespace_run(text)
+            };
+            // Without a safe place to cut, keep reading into the same chunk.
+            let Some(cut) = cut else { continue };
+            let base = self.offset;
+            let tokens: Vec<Token> = self
+                .tokenizer
+                .tokenize_with_offsets(&text[..cut])
+                .into_iter()
+                .map(|t| Token::new(t.text, t.start + base, t.end + base))
+                .collect();
+            self.buffer.drain(..cut);
+            self.offset += cut;
+            if !tokens.is_empty() {
+                return Ok(Some(tokens));
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl TokenStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let batch = py
+            .detach(|| self.next_batch())
+            .map_err(|e| PyIOError::new_err(format!("Failed to read file: {}", e)))?;
+        let Some(tokens) = batch else {
+            return Ok(None);
+        };
+        if self.with_offsets {
+            let tokens: Vec<(String, usize, usize)> = tokens
+                .into_iter()
+                .map(|t| (t.text, t.start, t.end))
+                .collect();
+            return Ok(Some(tokens.into_pyobject(py)?.into_any()));
+        }
+        let tokens: Vec<String> = tokens.into_iter().map(|t| t.text).collect();
+        Ok(Some(tokens.into_pyobject(py)?.into_any()))
+    }
+}
+
+/// Lazy iterator over the lines or records of a file, returned by
+/// `TokenizerIO.iter_lines` and `TokenizerIO.iter_records`.
+#[pyclass]
+pub struct RecordIterator {
+    reader: Reader,
+    delimiter: Vec<u8>,
+    /// Drop a `\r` before the delimiter, for `\r\n` line endings.
+    strip_cr: bool,
+    errors: Utf8Errors,
+    /// Bytes read past the last record returned.
+    buffer: Vec<u8>,
+    /// Prefix of `buffer` already known not to contain the delimiter.
+    searched: usize,
+    /// File offset of `buffer[0]`.
+    offset: usize,
+    eof: bool,
+}
+
+impl RecordIterator {
+    pub fn new(reader: Reader, delimiter: &[u8], strip_cr: bool, errors: Utf8Errors) -> Self {
+        RecordIterator {
+            reader,
+            delimiter: delimiter.to_vec(),
+            strip_cr,
+            errors,
+            buffer: Vec::new(),
+            searched: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    fn next_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let width = self.delimiter.len();
+        loop {
+            let found = self.buffer[self.searched..]
+                .windows(width)
+                .position(|w| w == self.delimiter.as_slice());
+            if let Some(pos) = found {
+                let end = self.searched + pos;
+                let mut record: Vec<u8> = self.buffer.drain(..end + width).collect();
+                record.truncate(end);
+                self.searched = 0;
+                self.offset += end + width;
+                return Ok(Some(self.finish(record)));
+            }
+            if self.eof {
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                self.offset += self.buffer.len();
+                let record = std::mem::take(&mut self.buffer);
+                self.searched = 0;
+                return Ok(Some(self.finish(record)));
+            }
+            self.searched = (self.buffer.len() + 1).saturating_sub(width);
+            let chunk = self.reader.fill_buf()?;
+            self.eof = chunk.is_empty();
+            let read = chunk.len();
+            self.buffer.extend_from_slice(chunk);
+            self.reader.consume(read);
+        }
+    }
+
+    fn read_record(&mut self) -> PyResult<Option<Vec<u8>>> {
+        self.next_record()
+            .map_err(|e| PyIOError::new_err(format!("Failed to read file: {}", e)))
+    }
+
+    fn finish(&self, mut record: Vec<u8>) -> Vec<u8> {
+        if self.strip_cr && record.last() == Some(&b'\r') {
+            record.pop();
+        }
+        record
+    }
+
+    /// The next record decoded as text, or `None` at the end of the file.
+    pub fn next_text(&mut self) -> PyResult<Option<String>> {
+        let start = self.offset;
+        let record = self.read_record()?;
+        record
+            .map(|bytes| {
+                self.errors.decode(bytes).map_err(|e| {
+                    PyIOError::new_err(format!(
+                        "Failed to read file: invalid UTF-8 at byte {}",
+                        start + e.utf8_error().valid_up_to()
+                    ))
+                })
+            })
+            .transpose()
+    }
+}
+
+#[pymethods]
+impl RecordIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        py.detach(|| self.next_text())
+    }
+}
+
+/// What `iter_jsonl` does with a malformed line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OnError {
+    Raise,
+    Skip,
+    Warn,
+}
+
+/// Lazy iterator over one field of a JSON lines file, returned by
+/// `TokenizerIO.iter_jsonl`.
+#[pyclass]
+pub struct JsonlIterator {
+    lines: RecordIterator,
+    field: String,
+    on_error: OnError,
+    /// 1-based number of the last line read.
+    line: usize,
+    /// Number of malformed lines skipped so far.
+    #[pyo3(get)]
+    skipped: usize,
+}
+
+impl JsonlIterator {
+    /// Extract the field from one line, or describe why it cannot be.
+    fn extract(&self, line: &[u8]) -> Result<String, String> {
+        let line = self
+            .lines
+            .errors
+            .repair(line, true)
+            .map_err(|pos| format!("invalid UTF-8 at byte {} of the line", pos))?;
+        let value: serde_json::Value = serde_json::from_slice(&line).map_err(|e| e.to_string())?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| "expected a JSON object".to_string())?;
+        match object.get(&self.field) {
+            Some(serde_json::Value::String(text)) => Ok(text.clone()),
+            Some(_) => Err(format!("field '{}' is not a string", self.field)),
+            None => Err(format!("missing field '{}'", self.field)),
+        }
+    }
+}
+
+#[pymethods]
+impl JsonlIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        while let Some(line) = py.detach(|| self.lines.read_record())? {
+            self.line += 1;
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let reason = match self.extract(&line) {
+                Ok(text) => return Ok(Some(text)),
+                Err(reason) => format!("Malformed JSON line {}: {}", self.line, reason),
+            };
+            match self.on_error {
+                OnError::Raise => return Err(PyValueError::new_err(reason)),
+                OnError::Warn => {
+                    let message =
+                        CString::new(reason.replace('\0', "")).expect("NUL bytes removed");
+                    PyErr::warn(py, &py.get_type::<PyUserWarning>(), &message, 1)?;
+                }
+                OnError::Skip => {}
+            }
+            self.skipped += 1;
+        }
+        Ok(None)
+    }
+}
+
+/// A CSV column selected by position or header name.
+#[derive(FromPyObject)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+fn csv_error(e: csv::Error) -> PyErr {
+    PyIOError::new_err(format!("Failed to read CSV file: {}", e))
+}
+
+/// Lazy iterator over one column of a CSV file, returned by
+/// `TokenizerIO.iter_csv`.
+#[pyclass]
+pub struct CsvIterator {
+    reader: csv::Reader<Reader>,
+    column: usize,
+    errors: Utf8Errors,
+    /// Reused between rows to avoid reallocating.
+    record: csv::ByteRecord,
+}
+
+#[pymethods]
+impl CsvIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        if !py
+            .detach(|| self.reader.read_byte_record(&mut self.record))
+            .map_err(csv_error)?
+        {
+            return Ok(None);
+        }
+        let line = self.record.position().map_or(0, |p| p.line());
+        match self.record.get(self.column) {
+            Some(field) => self.errors.decode(field.to_vec()).map(Some).map_err(|_| {
+                PyIOError::new_err(format!(
+                    "Failed to read CSV file: invalid UTF-8 in column {} on line {}",
+                    self.column, line
+                ))
+            }),
+            None => Err(PyValueError::new_err(format!(
+                "Row on line {} has no column {}",
+                line, self.column
             ))),
         }
     }
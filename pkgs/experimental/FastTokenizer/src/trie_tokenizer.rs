@@ -0,0 +1,335 @@
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Arc;
+
+use crate::intern::token_list;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::Vocab;
+
+/// A byte trie stored as flat arrays: the children of a node are a sorted
+/// run of `edges`, found by binary search.
+#[derive(Debug, Default)]
+struct Trie {
+    /// `(first edge, edge count, is the end of a token)` per node; the root
+    /// is node 0.
+    nodes: Vec<(u32, u32, bool)>,
+    /// `(byte, child node)` pairs.
+    edges: Vec<(u8, u32)>,
+}
+
+impl Trie {
+    fn build<'a>(tokens: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut children: Vec<BTreeMap<u8, u32>> = vec![BTreeMap::new()];
+        let mut terminal = vec![false];
+        for token in tokens {
+            let mut node = 0;
+            for &b in token.as_bytes() {
+                let next = children.len() as u32;
+                node = *children[node].entry(b).or_insert(next) as usize;
+                if node == next as usize {
+                    children.push(BTreeMap::new());
+                    terminal.push(false);
+                }
+            }
+            terminal[node] = !token.is_empty();
+        }
+        let mut trie = Trie::default();
+        for (node_children, is_token) in children.into_iter().zip(terminal) {
+            let start = trie.edges.len() as u32;
+            trie.edges.extend(node_children);
+            trie.nodes
+                .push((start, trie.edges.len() as u32 - start, is_token));
+        }
+        trie
+    }
+
+    fn child(&self, node: u32, byte: u8) -> Option<u32> {
+        let (start, len, _) = self.nodes[node as usize];
+        let edges = &self.edges[start as usize..(start + len) as usize];
+        edges
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| edges[i].1)
+    }
+
+    /// Length in bytes of the longest token that `text` starts with.
+    fn longest_prefix(&self, text: &str) -> Option<usize> {
+        let mut node = 0;
+        let mut longest = None;
+        for (i, &b) in text.as_bytes().iter().enumerate() {
+            let Some(next) = self.child(node, b) else {
+                break;
+            };
+            node = next;
+            if self.nodes[node as usize].2 {
+                longest = Some(i + 1);
+            }
+        }
+        longest
+    }
+}
+
+/// What to emit for text that no vocabulary token covers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Unknown {
+    /// Each uncovered character on its own.
+    Char,
+    /// The unknown token, once per uncovered run.
+    Token(String),
+    /// Nothing.
+    Skip,
+}
+
+/// Segments text greedily by longest match against a vocabulary stored in a
+/// byte trie, a building block for custom domain vocabularies.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct TrieTokenizer {
+    trie: Arc<Trie>,
+    unknown: Unknown,
+    skip_whitespace: bool,
+    vocab: Vocab,
+}
+
+impl TrieTokenizer {
+    fn build(
+        vocab: Vocab,
+        unknown: &str,
+        unk_token: &str,
+        skip_whitespace: bool,
+    ) -> PyResult<Self> {
+        let unknown = match unknown {
+            "char" => Unknown::Char,
+            "token" => Unknown::Token(unk_token.to_string()),
+            "skip" => Unknown::Skip,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown unknown-token behavior '{}', expected 'char', 'token' or 'skip'",
+                    other
+                )))
+            }
+        };
+        let tokens = vocab.tokens();
+        let trie = Trie::build(
+            tokens
+                .iter()
+                .map(String::as_str)
+                .filter(|t| !matches!(&unknown, Unknown::Token(unk) if unk == t)),
+        );
+        Ok(TrieTokenizer {
+            trie: Arc::new(trie),
+            unknown,
+            skip_whitespace,
+            vocab,
+        })
+    }
+}
+
+impl TrieTokenizer {
+    /// Spans of the tokens of `text`, each flagged when it is a run of
+    /// uncovered characters standing for the unknown token.
+    fn segment(&self, text: &str) -> Vec<(usize, usize, bool)> {
+        let mut spans = Vec::new();
+        // Start of the current run of uncovered characters.
+        let mut unknown_start: Option<usize> = None;
+        let mut pos = 0;
+        while let Some(c) = text[pos..].chars().next() {
+            let matched = self.trie.longest_prefix(&text[pos..]);
+            let skipped = matched.is_none() && self.skip_whitespace && c.is_whitespace();
+            if matched.is_some() || skipped {
+                if let Some(start) = unknown_start.take() {
+                    spans.push((start, pos, true));
+                }
+            }
+            let len = matched.unwrap_or(c.len_utf8());
+            if matched.is_some() {
+                spans.push((pos, pos + len, false));
+            } else if !skipped {
+                match self.unknown {
+                    Unknown::Char => spans.push((pos, pos + len, false)),
+                    Unknown::Token(_) => {
+                        unknown_start.get_or_insert(pos);
+                    }
+                    Unknown::Skip => {}
+                }
+            }
+            pos += len;
+        }
+        if let Some(start) = unknown_start {
+            spans.push((start, text.len(), true));
+        }
+        spans
+    }
+}
+
+impl Tokenizer for TrieTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.segment(text)
+            .into_iter()
+            .map(|(start, end, unknown)| match (&self.unknown, unknown) {
+                (Unknown::Token(unk), true) => Token::new(unk.as_str(), start, end),
+                _ => Token::new(&text[start..end], start, end),
+            })
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.segment(text)
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        !matches!(self.unknown, Unknown::Token(_))
+    }
+}
+
+tokenizer_pymethods!(TrieTokenizer);
+
+#[pymethods]
+impl TrieTokenizer {
+    /// Create a longest-match tokenizer.
+    ///
+    /// Args:
+    ///     vocab (Vocab | List[str]): The tokens to match. A list becomes a
+    ///         frozen `Vocab` used by `encode`.
+    ///     unknown (str): What to emit for text no token covers: `"char"`
+    ///         for each character on its own, `"token"` for `unk_token` once
+    ///         per uncovered run, or `"skip"` to drop it.
+    ///     unk_token (str): The unknown token for `unknown="token"`.
+    ///     skip_whitespace (bool): Drop whitespace that no token covers
+    ///         instead of treating it as unknown.
+    #[new]
+    #[pyo3(signature = (vocab, unknown="char", unk_token="[UNK]", skip_whitespace=true))]
+    fn new(
+        vocab: &Bound<'_, PyAny>,
+        unknown: &str,
+        unk_token: &str,
+        skip_whitespace: bool,
+    ) -> PyResult<Self> {
+        let vocab = match vocab.cast::<Vocab>() {
+            Ok(vocab) => vocab.borrow().clone(),
+            Err(_) => {
+                let tokens: Vec<String> = vocab.extract()?;
+                let unk = (unknown == "token").then(|| unk_token.to_string());
+                Vocab::new(Some(tokens), unk, true)
+            }
+        };
+        Self::build(vocab, unknown, unk_token, skip_whitespace)
+    }
+
+    /// Load the vocabulary from a text file with one token per line; the
+    /// line number is the token's id.
+    #[staticmethod]
+    #[pyo3(signature = (path, unknown="char", unk_token="[UNK]", skip_whitespace=true))]
+    fn from_file(
+        path: &str,
+        unknown: &str,
+        unk_token: &str,
+        skip_whitespace: bool,
+    ) -> PyResult<Self> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read vocab file: {}", e)))?;
+        let tokens = data
+            .lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect();
+        let unk = (unknown == "token").then(|| unk_token.to_string());
+        Self::build(
+            Vocab::new(Some(tokens), unk, true),
+            unknown,
+            unk_token,
+            skip_whitespace,
+        )
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into ids with the vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        self.vocab.encode_text(self, input)
+    }
+
+    /// Encode several strings at once, releasing the GIL while working.
+    fn encode_batch(&self, py: Python<'_>, inputs: Vec<String>) -> PyResult<Vec<Vec<u32>>> {
+        py.detach(|| {
+            inputs
+                .iter()
+                .map(|text| self.vocab.encode_text(self, text))
+                .collect()
+        })
+    }
+
+    /// Turn ids back into text by concatenating the tokens.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        self.vocab
+            .decode_ids(&ids)
+            .map(|tokens| tokens.concat())
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    /// The vocabulary the tokens are matched against.
+    #[getter]
+    fn vocab(&self) -> Vocab {
+        self.vocab.clone()
+    }
+}
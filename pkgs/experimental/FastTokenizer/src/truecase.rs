@@ -0,0 +1,208 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::detokenizer::join_tokens;
+use crate::sentence_splitter::ABBREVIATIONS;
+
+/// Characters that end a sentence.
+const TERMINATORS: &str = ".!?…。！？";
+
+/// Rule-based case restoration for text built from lowercased tokens.
+///
+/// Known proper nouns get back their casing, matched case-insensitively on
+/// whole words with the longest entry winning, so `"new york"` becomes
+/// `"New York"` and `"nasa"` becomes `"NASA"`. The first word of every
+/// sentence is capitalized, as is the pronoun `i` and its contractions.
+/// A period after a known abbreviation like `e.g.` does not start a new
+/// sentence.
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone)]
+pub struct Truecaser {
+    proper_nouns: Arc<Vec<String>>,
+    /// Lowercased entries mapped to their casing.
+    casings: Arc<HashMap<String, String>>,
+    /// Words in the longest entry.
+    longest: usize,
+    capitalize_sentences: bool,
+    capitalize_i: bool,
+}
+
+/// `word` with its first character uppercased.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether `word` is the pronoun `i`, alone or in a contraction like `i'm`.
+fn is_pronoun_i(word: &str) -> bool {
+    match word.strip_prefix('i') {
+        Some(rest) => rest.is_empty() || rest.starts_with(['\'', '’']),
+        None => false,
+    }
+}
+
+/// Whether the text between two words ends a sentence: it holds a
+/// terminator or a line break, unless it is the period of an abbreviation.
+fn ends_sentence(previous: &str, gap: &str) -> bool {
+    if gap.contains('\n') {
+        return true;
+    }
+    if !gap.contains(|c| TERMINATORS.contains(c)) {
+        return false;
+    }
+    let abbreviation = gap.trim_end() == "."
+        && ABBREVIATIONS
+            .iter()
+            .any(|a| previous.eq_ignore_ascii_case(a));
+    !abbreviation
+}
+
+impl Truecaser {
+    pub fn new(proper_nouns: Vec<String>, capitalize_sentences: bool, capitalize_i: bool) -> Self {
+        let casings: HashMap<String, String> = proper_nouns
+            .iter()
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| (name.to_lowercase(), name.clone()))
+            .collect();
+        let longest = casings
+            .keys()
+            .map(|key| key.unicode_words().count())
+            .max()
+            .unwrap_or(0);
+        Truecaser {
+            proper_nouns: Arc::new(proper_nouns),
+            casings: Arc::new(casings),
+            longest,
+            capitalize_sentences,
+            capitalize_i,
+        }
+    }
+
+    /// The known casing of `span`, also in the possessive (`nasa's`).
+    fn casing(&self, span: &str) -> Option<String> {
+        let key = span.to_lowercase();
+        if let Some(casing) = self.casings.get(&key) {
+            return Some(casing.clone());
+        }
+        let stem = key.strip_suffix("'s").or_else(|| key.strip_suffix("’s"))?;
+        let casing = self.casings.get(stem)?;
+        Some(format!("{}{}", casing, &key[stem.len()..]))
+    }
+
+    /// `text` with its casing restored.
+    pub fn apply(&self, text: &str) -> String {
+        let words: Vec<(usize, &str)> = text
+            .split_word_bound_indices()
+            .filter(|(_, word)| word.chars().any(char::is_alphanumeric))
+            .collect();
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        let mut i = 0;
+        while i < words.len() {
+            let (start, word) = words[i];
+            let gap = &text[last..start];
+            let initial =
+                self.capitalize_sentences && (i == 0 || ends_sentence(words[i - 1].1, gap));
+            out.push_str(gap);
+            // The longest known entry starting at this word.
+            let known = (1..=self.longest.min(words.len() - i)).rev().find_map(|n| {
+                let (at, end_word) = words[i + n - 1];
+                let end = at + end_word.len();
+                self.casing(&text[start..end])
+                    .map(|casing| (n, end, casing))
+            });
+            if let Some((n, end, casing)) = known {
+                out.push_str(&casing);
+                last = end;
+                i += n;
+                continue;
+            }
+            if initial || (self.capitalize_i && is_pronoun_i(word)) {
+                out.push_str(&capitalize(word));
+            } else {
+                out.push_str(word);
+            }
+            last = start + word.len();
+            i += 1;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+#[pymethods]
+impl Truecaser {
+    /// Create a truecaser.
+    ///
+    /// Args:
+    ///     proper_nouns (List[str], optional): Names in the casing to
+    ///         restore, such as `"London"`, `"NASA"`, `"New York"` or
+    ///         `"iPhone"`. Entries differing only in case keep the last one.
+    ///     capitalize_sentences (bool): Capitalize the first word of the
+    ///         text and of every sentence after it.
+    ///     capitalize_i (bool): Write the pronoun `i` as `I`, also in
+    ///         contractions such as `i'm`.
+    #[new]
+    #[pyo3(signature = (proper_nouns=None, capitalize_sentences=true, capitalize_i=true))]
+    fn py_new(
+        proper_nouns: Option<Vec<String>>,
+        capitalize_sentences: bool,
+        capitalize_i: bool,
+    ) -> Self {
+        Truecaser::new(
+            proper_nouns.unwrap_or_default(),
+            capitalize_sentences,
+            capitalize_i,
+        )
+    }
+
+    /// Constructor arguments recreating this truecaser, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("proper_nouns", &*self.proper_nouns)?;
+        kwargs.set_item("capitalize_sentences", self.capitalize_sentences)?;
+        kwargs.set_item("capitalize_i", self.capitalize_i)?;
+        Ok(((), kwargs))
+    }
+
+    /// Restore the casing of `text`.
+    fn truecase(&self, text: &str) -> String {
+        self.apply(text)
+    }
+
+    /// `truecase` for many texts at once, in parallel without the GIL.
+    fn truecase_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<String> {
+        py.detach(|| texts.par_iter().map(|text| self.apply(text)).collect())
+    }
+
+    /// Join tokens like `detokenize` does and restore the casing of the
+    /// result.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        self.apply(&join_tokens(&tokens))
+    }
+
+    fn __len__(&self) -> usize {
+        self.casings.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Truecaser(proper_nouns={}, capitalize_sentences={}, capitalize_i={})",
+            self.casings.len(),
+            if self.capitalize_sentences {
+                "True"
+            } else {
+                "False"
+            },
+            if self.capitalize_i { "True" } else { "False" }
+        )
+    }
+}
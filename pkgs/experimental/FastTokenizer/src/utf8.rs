@@ -0,0 +1,121 @@
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyUnicodeDecodeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use std::borrow::Cow;
+use std::string::FromUtf8Error;
+
+/// What to do with bytes that are not valid UTF-8, like the `errors`
+/// argument of Python's `bytes.decode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Utf8Errors {
+    /// Fail on the first invalid byte.
+    Strict,
+    /// Replace each invalid sequence with U+FFFD.
+    Replace,
+    /// Drop invalid sequences.
+    Ignore,
+}
+
+impl Utf8Errors {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "strict" => Ok(Utf8Errors::Strict),
+            "replace" => Ok(Utf8Errors::Replace),
+            "ignore" => Ok(Utf8Errors::Ignore),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown errors policy '{}', expected 'strict', 'replace' or 'ignore'",
+                other
+            ))),
+        }
+    }
+
+    /// `bytes` with their invalid sequences replaced or dropped. When more
+    /// bytes may follow (`complete` is false), a character cut off at the
+    /// very end is kept as is. Under `Strict` the error is the offset of the
+    /// first invalid byte.
+    pub fn repair(self, bytes: &[u8], complete: bool) -> Result<Cow<'_, [u8]>, usize> {
+        let mut repaired: Option<Vec<u8>> = None;
+        let mut pos = 0;
+        while let Err(e) = std::str::from_utf8(&bytes[pos..]) {
+            let valid = pos + e.valid_up_to();
+            let invalid = match e.error_len() {
+                Some(len) => len,
+                None if !complete => break,
+                None => bytes.len() - valid,
+            };
+            if self == Utf8Errors::Strict {
+                return Err(valid);
+            }
+            let out = repaired.get_or_insert_with(Vec::new);
+            out.extend_from_slice(&bytes[pos..valid]);
+            if self == Utf8Errors::Replace {
+                out.extend_from_slice("\u{FFFD}".as_bytes());
+            }
+            pos = valid + invalid;
+        }
+        Ok(match repaired {
+            Some(mut out) => {
+                out.extend_from_slice(&bytes[pos..]);
+                Cow::Owned(out)
+            }
+            None => Cow::Borrowed(bytes),
+        })
+    }
+
+    /// Decode `bytes` following the policy.
+    pub fn decode(self, bytes: Vec<u8>) -> Result<String, FromUtf8Error> {
+        if self == Utf8Errors::Strict {
+            return String::from_utf8(bytes);
+        }
+        if let Cow::Owned(repaired) = self.repair(&bytes, true).expect("only strict fails") {
+            return Ok(String::from_utf8(repaired).expect("repaired UTF-8"));
+        }
+        String::from_utf8(bytes)
+    }
+}
+
+/// The `UnicodeDecodeError` Python's `bytes.decode` would raise for `e`.
+pub fn decode_error(py: Python<'_>, e: FromUtf8Error) -> PyErr {
+    match PyUnicodeDecodeError::new_utf8(py, e.as_bytes(), e.utf8_error()) {
+        Ok(error) => PyErr::from_value(error.into_any()),
+        Err(error) => error,
+    }
+}
+
+/// Text passed to `tokenize` or `encode`: a `str`, or UTF-8 in `bytes` or
+/// any other buffer such as a `bytearray`, `memoryview` or `mmap`. Bytes are
+/// read in place; other buffers are copied once, in Rust.
+#[derive(FromPyObject)]
+pub enum Text<'py> {
+    Str(Bound<'py, PyString>),
+    Bytes(Bound<'py, PyBytes>),
+    Buffer(PyBuffer<u8>),
+}
+
+impl Text<'_> {
+    /// The text, decoding bytes as `errors` (see `Utf8Errors::parse`) says.
+    /// Under `"strict"` invalid UTF-8 raises `UnicodeDecodeError`.
+    pub fn decode(&self, errors: &str) -> PyResult<Cow<'_, str>> {
+        let errors = Utf8Errors::parse(errors)?;
+        match self {
+            Text::Str(text) => Ok(Cow::Borrowed(text.to_str()?)),
+            Text::Bytes(bytes) => {
+                let py = bytes.py();
+                match std::str::from_utf8(bytes.as_bytes()) {
+                    Ok(text) => Ok(Cow::Borrowed(text)),
+                    Err(_) => errors
+                        .decode(bytes.as_bytes().to_vec())
+                        .map(Cow::Owned)
+                        .map_err(|e| decode_error(py, e)),
+                }
+            }
+            Text::Buffer(buffer) => Python::attach(|py| {
+                errors
+                    .decode(buffer.to_vec(py)?)
+                    .map(Cow::Owned)
+                    .map_err(|e| decode_error(py, e))
+            }),
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::corpus::for_each_batch;
+use crate::sparse::{CsrMatrix, Norm};
+use crate::tokenizer::{extract_tokenizer, thread_pool, Tokenizer};
+use crate::vocab::{Vocab, VocabData};
+
+/// A document frequency bound: an absolute number of documents, or a
+/// fraction of them.
+#[derive(Clone, Copy, Debug, FromPyObject)]
+pub enum DocFrequency {
+    Count(u64),
+    Fraction(f64),
+}
+
+impl DocFrequency {
+    fn validate(self, name: &str) -> PyResult<Self> {
+        match self {
+            DocFrequency::Fraction(f) if !(0.0..=1.0).contains(&f) => {
+                Err(PyValueError::new_err(format!(
+                    "{} must be a count or a fraction between 0 and 1, got {}",
+                    name, f
+                )))
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// Smallest document count allowed when used as a lower bound.
+    fn lower(self, documents: u64) -> u64 {
+        match self {
+            DocFrequency::Count(count) => count,
+            DocFrequency::Fraction(f) => (f * documents as f64).ceil() as u64,
+        }
+    }
+
+    /// Largest document count allowed when used as an upper bound.
+    fn upper(self, documents: u64) -> u64 {
+        match self {
+            DocFrequency::Count(count) => count,
+            DocFrequency::Fraction(f) => (f * documents as f64).floor() as u64,
+        }
+    }
+}
+
+/// Term counts of one document.
+fn term_counts(tokenizer: &dyn Tokenizer, text: &str) -> HashMap<String, u64> {
+    let mut counts = HashMap::new();
+    for token in tokenizer.tokenize(text) {
+        *counts.entry(token).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Document and corpus frequency of every term seen while fitting.
+#[derive(Default)]
+struct DocumentFrequencies {
+    documents: u64,
+    /// `(documents containing the term, total occurrences)`.
+    terms: HashMap<String, (u64, u64)>,
+}
+
+impl DocumentFrequencies {
+    fn add_batch(&mut self, tokenizer: &dyn Tokenizer, texts: &[String]) {
+        let batch = texts
+            .par_iter()
+            .fold(HashMap::new, |mut terms, text| {
+                for (term, count) in term_counts(tokenizer, text) {
+                    let entry: &mut (u64, u64) = terms.entry(term).or_default();
+                    entry.0 += 1;
+                    entry.1 += count;
+                }
+                terms
+            })
+            .reduce(HashMap::new, merge_frequencies);
+        self.documents += texts.len() as u64;
+        self.terms = merge_frequencies(std::mem::take(&mut self.terms), batch);
+    }
+}
+
+fn merge_frequencies(
+    mut a: HashMap<String, (u64, u64)>,
+    mut b: HashMap<String, (u64, u64)>,
+) -> HashMap<String, (u64, u64)> {
+    if a.len() < b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+    for (term, (df, tf)) in b {
+        let entry = a.entry(term).or_default();
+        entry.0 += df;
+        entry.1 += tf;
+    }
+    a
+}
+
+/// What a fitted `TfidfVectorizer` learned: the terms in column order and
+/// their inverse document frequencies.
+#[derive(Clone)]
+struct Fitted {
+    columns: Arc<HashMap<String, u32>>,
+    features: Arc<Vec<String>>,
+    idf: Arc<Vec<f64>>,
+}
+
+/// TF-IDF features over any FastTokenizer tokenizer or pipeline.
+///
+/// `fit` learns the vocabulary and inverse document frequencies from a
+/// corpus; `transform` turns texts into L2-normalized TF-IDF rows of a
+/// sparse `CsrMatrix`, ready for `to_scipy()`. Columns are the terms in
+/// alphabetical order, and weights follow scikit-learn's `TfidfVectorizer`.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct TfidfVectorizer {
+    tokenizer: Arc<dyn Tokenizer>,
+    min_df: DocFrequency,
+    max_df: DocFrequency,
+    max_features: Option<usize>,
+    sublinear_tf: bool,
+    smooth_idf: bool,
+    norm: Option<Norm>,
+    fitted: Option<Fitted>,
+}
+
+impl TfidfVectorizer {
+    fn fit_frequencies(&mut self, frequencies: DocumentFrequencies) -> PyResult<()> {
+        let n = frequencies.documents;
+        let (min_df, max_df) = (self.min_df.lower(n), self.max_df.upper(n));
+        let mut terms: Vec<(String, u64, u64)> = frequencies
+            .terms
+            .into_iter()
+            .filter(|(_, (df, _))| (min_df..=max_df).contains(df))
+            .map(|(term, (df, tf))| (term, df, tf))
+            .collect();
+        if terms.is_empty() {
+            return Err(PyValueError::new_err(
+                "No terms remain after applying min_df and max_df; lower min_df or raise max_df",
+            ));
+        }
+        if let Some(max_features) = self.max_features {
+            terms.sort_unstable_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+            terms.truncate(max_features);
+        }
+        terms.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let smooth = if self.smooth_idf { 1.0 } else { 0.0 };
+        let idf = terms
+            .iter()
+            .map(|(_, df, _)| ((n as f64 + smooth) / (*df as f64 + smooth)).ln() + 1.0)
+            .collect();
+        let features: Vec<String> = terms.into_iter().map(|(term, _, _)| term).collect();
+        let columns = features
+            .iter()
+            .enumerate()
+            .map(|(i, term)| (term.clone(), i as u32))
+            .collect();
+        self.fitted = Some(Fitted {
+            columns: Arc::new(columns),
+            features: Arc::new(features),
+            idf: Arc::new(idf),
+        });
+        Ok(())
+    }
+
+    fn require_fitted(&self) -> PyResult<&Fitted> {
+        self.fitted.as_ref().ok_or_else(|| {
+            PyValueError::new_err("TfidfVectorizer is not fitted yet; call fit first")
+        })
+    }
+
+    fn row(&self, fitted: &Fitted, text: &str) -> Vec<(u32, f64)> {
+        let mut row: Vec<(u32, f64)> = term_counts(&*self.tokenizer, text)
+            .into_iter()
+            .filter_map(|(term, count)| {
+                let column = *fitted.columns.get(&term)?;
+                let tf = if self.sublinear_tf {
+                    1.0 + (count as f64).ln()
+                } else {
+                    count as f64
+                };
+                Some((column, tf * fitted.idf[column as usize]))
+            })
+            .collect();
+        if let Some(norm) = self.norm {
+            norm.apply(&mut row);
+        }
+        row
+    }
+}
+
+#[pymethods]
+impl TfidfVectorizer {
+    /// Create a TF-IDF vectorizer.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer or `TokenizerPipeline`; use a
+    ///         pipeline with a `Normalizer` for lowercasing.
+    ///     min_df (int | float): Ignore terms in fewer documents than this
+    ///         count, or this fraction of the documents.
+    ///     max_df (int | float): Ignore terms in more documents than this
+    ///         count, or this fraction of the documents.
+    ///     max_features (int, optional): Keep only the terms most frequent
+    ///         across the corpus.
+    ///     sublinear_tf (bool): Use `1 + log(tf)` instead of raw counts.
+    ///     smooth_idf (bool): Add one to document frequencies, as if a document
+    ///         containing every term had been seen.
+    ///     norm (str, optional): `"l2"`, `"l1"` or `None`.
+    #[new]
+    #[pyo3(signature = (
+        tokenizer,
+        min_df=DocFrequency::Count(1),
+        max_df=DocFrequency::Fraction(1.0),
+        max_features=None,
+        sublinear_tf=false,
+        smooth_idf=true,
+        norm=Some("l2")
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        tokenizer: &Bound<'_, PyAny>,
+        min_df: DocFrequency,
+        max_df: DocFrequency,
+        max_features: Option<usize>,
+        sublinear_tf: bool,
+        smooth_idf: bool,
+        norm: Option<&str>,
+    ) -> PyResult<Self> {
+        Ok(TfidfVectorizer {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            min_df: min_df.validate("min_df")?,
+            max_df: max_df.validate("max_df")?,
+            max_features,
+            sublinear_tf,
+            smooth_idf,
+            norm: Norm::parse(norm)?,
+            fitted: None,
+        })
+    }
+
+    /// Learn the vocabulary and inverse document frequencies.
+    ///
+    /// Args:
+    ///     corpus (Iterable[str]): The training documents, e.g. a list or
+    ///         `TokenizerIO.iter_lines`; read in batches, so it may be larger
+    ///         than memory.
+    ///     batch_size (int): Documents tokenized per parallel step.
+    #[pyo3(signature = (corpus, batch_size=1024))]
+    fn fit(
+        &mut self,
+        py: Python<'_>,
+        corpus: &Bound<'_, PyAny>,
+        batch_size: usize,
+    ) -> PyResult<()> {
+        let pool = thread_pool(None)?;
+        let mut frequencies = DocumentFrequencies::default();
+        let tokenizer = self.tokenizer.clone();
+        for_each_batch(py, corpus, batch_size.max(1), &pool, |texts| {
+            frequencies.add_batch(&*tokenizer, texts);
+        })?;
+        self.fit_frequencies(frequencies)
+    }
+
+    /// Turn texts into TF-IDF rows, releasing the GIL while working.
+    ///
+    /// Terms not seen during `fit` are ignored.
+    ///
+    /// Returns:
+    ///     CsrMatrix: One row per text, one column per vocabulary term.
+    fn transform(&self, py: Python<'_>, texts: Vec<String>) -> PyResult<CsrMatrix> {
+        let fitted = self.require_fitted()?;
+        let rows = py.detach(|| {
+            texts
+                .par_iter()
+                .map(|text| self.row(fitted, text))
+                .collect()
+        });
+        Ok(CsrMatrix::from_rows(rows, fitted.features.len()))
+    }
+
+    /// `fit` on `texts`, then `transform` them.
+    fn fit_transform(&mut self, py: Python<'_>, texts: Vec<String>) -> PyResult<CsrMatrix> {
+        let mut frequencies = DocumentFrequencies::default();
+        py.detach(|| frequencies.add_batch(&*self.tokenizer, &texts));
+        self.fit_frequencies(frequencies)?;
+        self.transform(py, texts)
+    }
+
+    /// The terms in column order, or `None` before fitting.
+    fn feature_names(&self) -> Option<Vec<String>> {
+        self.fitted.as_ref().map(|f| f.features.to_vec())
+    }
+
+    /// A frozen `Vocab` mapping every term to its column, or `None` before
+    /// fitting.
+    #[getter]
+    fn vocabulary(&self) -> Option<Vocab> {
+        self.fitted
+            .as_ref()
+            .map(|f| Vocab::new(Some(f.features.to_vec()), None, true))
+    }
+
+    /// The inverse document frequency of every column, or `None` before
+    /// fitting.
+    #[getter]
+    fn idf(&self) -> Option<Vec<f64>> {
+        self.fitted.as_ref().map(|f| f.idf.to_vec())
+    }
+
+    /// The row normalization: `"l2"`, `"l1"` or `None`.
+    #[getter]
+    fn norm(&self) -> Option<&'static str> {
+        self.norm.map(Norm::name)
+    }
+}
+
+/// MurmurHash3 (x86, 32-bit) of `data`, the hash scikit-learn's
+/// `HashingVectorizer` uses.
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mut h = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let k = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        h ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .rev()
+            .fold(0u32, |k, &byte| (k << 8) | byte as u32);
+        h ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+/// Token counts hashed into a fixed number of columns, without a vocabulary.
+///
+/// Stateless, so texts can be vectorized as they stream in and batches can
+/// be processed independently. Column and sign come from a seeded
+/// MurmurHash3, matching scikit-learn's `HashingVectorizer` for the same
+/// tokens, `n_features` and seed 0.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct HashingVectorizer {
+    tokenizer: Arc<dyn Tokenizer>,
+    n_features: u32,
+    seed: u32,
+    alternate_sign: bool,
+    binary: bool,
+    norm: Option<Norm>,
+}
+
+impl HashingVectorizer {
+    /// Column of `token`, and the sign its occurrences are counted with.
+    fn hash(&self, token: &str) -> (u32, f64) {
+        let h = murmur3_32(token.as_bytes(), self.seed) as i32;
+        let column = (h as i64).unsigned_abs() % self.n_features as u64;
+        let sign = if self.alternate_sign && h < 0 {
+            -1.0
+        } else {
+            1.0
+        };
+        (column as u32, sign)
+    }
+
+    fn row(&self, text: &str) -> Vec<(u32, f64)> {
+        let mut values: HashMap<u32, f64> = HashMap::new();
+        for token in self.tokenizer.tokenize(text) {
+            let (column, sign) = self.hash(&token);
+            *values.entry(column).or_insert(0.0) += sign;
+        }
+        let mut row: Vec<(u32, f64)> = values
+            .into_iter()
+            .filter(|&(_, value)| value != 0.0)
+            .map(|(column, value)| (column, if self.binary { 1.0 } else { value }))
+            .collect();
+        if let Some(norm) = self.norm {
+            norm.apply(&mut row);
+        }
+        row
+    }
+}
+
+#[pymethods]
+impl HashingVectorizer {
+    /// Create a hashing vectorizer.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer or `TokenizerPipeline`.
+    ///     n_features (int): Number of columns.
+    ///     seed (int): Hash seed; different seeds give independent hashings.
+    ///     alternate_sign (bool): Count tokens with a hash-dependent sign, so
+    ///         collisions tend to cancel out instead of adding up.
+    ///     binary (bool): Record presence (1.0) rather than counts.
+    ///     norm (str, optional): `"l2"`, `"l1"` or `None`.
+    #[new]
+    #[pyo3(signature = (
+        tokenizer,
+        n_features=1 << 20,
+        seed=0,
+        alternate_sign=true,
+        binary=false,
+        norm=Some("l2")
+    ))]
+    fn new(
+        tokenizer: &Bound<'_, PyAny>,
+        n_features: u32,
+        seed: u32,
+        alternate_sign: bool,
+        binary: bool,
+        norm: Option<&str>,
+    ) -> PyResult<Self> {
+        if n_features == 0 {
+            return Err(PyValueError::new_err("n_features must be at least 1"));
+        }
+        Ok(HashingVectorizer {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            n_features,
+            seed,
+            alternate_sign,
+            binary,
+            norm: Norm::parse(norm)?,
+        })
+    }
+
+    /// Turn texts into hashed rows, releasing the GIL while working.
+    ///
+    /// Returns:
+    ///     CsrMatrix: One row per text with `n_features` columns.
+    fn transform(&self, py: Python<'_>, texts: Vec<String>) -> CsrMatrix {
+        let rows = py.detach(|| texts.par_iter().map(|text| self.row(text)).collect());
+        CsrMatrix::from_rows(rows, self.n_features as usize)
+    }
+
+    /// The column a token is hashed to and the sign it is counted with.
+    fn token_column(&self, token: &str) -> (u32, i8) {
+        let (column, sign) = self.hash(token);
+        (column, sign as i8)
+    }
+
+    #[getter]
+    fn n_features(&self) -> u32 {
+        self.n_features
+    }
+
+    #[getter]
+    fn seed(&self) -> u32 {
+        self.seed
+    }
+}
+
+/// Term counts restricted to a fixed vocabulary.
+///
+/// Column `i` counts the vocabulary token with id `i`; tokens and n-grams
+/// outside the vocabulary are ignored and never added to it, even when it
+/// is not frozen.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone)]
+pub struct CountVectorizer {
+    tokenizer: Arc<dyn Tokenizer>,
+    vocab: Vocab,
+    binary: bool,
+    ngram_range: (usize, usize),
+    separator: String,
+}
+
+impl CountVectorizer {
+    fn row(&self, ids: &VocabData, text: &str) -> Vec<(u32, f64)> {
+        let tokens = self.tokenizer.tokenize(text);
+        let mut counts: HashMap<u32, f64> = HashMap::new();
+        let mut count = |term: &str| {
+            if let Some(id) = ids.id(term) {
+                *counts.entry(id).or_insert(0.0) += 1.0;
+            }
+        };
+        let (min_n, max_n) = self.ngram_range;
+        for n in min_n..=max_n.min(tokens.len()) {
+            if n == 1 {
+                tokens.iter().for_each(|token| count(token));
+            } else {
+                tokens
+                    .windows(n)
+                    .for_each(|gram| count(&gram.join(&self.separator)));
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(id, value)| (id, if self.binary { 1.0 } else { value }))
+            .collect()
+    }
+}
+
+#[pymethods]
+impl CountVectorizer {
+    /// Create a count vectorizer over a fixed vocabulary.
+    ///
+    /// Args:
+    ///     tokenizer: Any FastTokenizer tokenizer or `TokenizerPipeline`.
+    ///     vocab (Vocab): The terms to count; ids are the columns. N-gram
+    ///         terms are their tokens joined by `separator`.
+    ///     binary (bool): Record presence (1.0) rather than counts.
+    ///     ngram_range (Tuple[int, int]): Smallest and largest n-gram length
+    ///         to count, e.g. `(1, 2)` for unigrams and bigrams.
+    ///     separator (str): Joins the tokens of an n-gram.
+    #[new]
+    #[pyo3(signature = (tokenizer, vocab, binary=false, ngram_range=(1, 1), separator=" "))]
+    fn new(
+        tokenizer: &Bound<'_, PyAny>,
+        vocab: PyRef<'_, Vocab>,
+        binary: bool,
+        ngram_range: (usize, usize),
+        separator: &str,
+    ) -> PyResult<Self> {
+        let (min_n, max_n) = ngram_range;
+        if min_n == 0 || min_n > max_n {
+            return Err(PyValueError::new_err(format!(
+                "Invalid ngram_range ({}, {}); expected 1 <= min_n <= max_n",
+                min_n, max_n
+            )));
+        }
+        Ok(CountVectorizer {
+            tokenizer: extract_tokenizer(tokenizer)?,
+            vocab: vocab.clone(),
+            binary,
+            ngram_range,
+            separator: separator.to_string(),
+        })
+    }
+
+    /// Turn texts into count rows, releasing the GIL while working.
+    ///
+    /// Returns:
+    ///     CsrMatrix: One row per text, one column per vocabulary id.
+    fn transform(&self, py: Python<'_>, texts: Vec<String>) -> CsrMatrix {
+        let vocab = &self.vocab;
+        py.detach(|| {
+            vocab.with_token_ids(|ids| {
+                let rows = texts.par_iter().map(|text| self.row(ids, text)).collect();
+                CsrMatrix::from_rows(rows, ids.len())
+            })
+        })
+    }
+
+    /// The vocabulary tokens in column order.
+    fn feature_names(&self) -> Vec<String> {
+        self.vocab.tokens()
+    }
+
+    #[getter]
+    fn vocab(&self) -> Vocab {
+        self.vocab.clone()
+    }
+
+    #[setter]
+    fn set_vocab(&mut self, vocab: PyRef<'_, Vocab>) {
+        self.vocab = vocab.clone();
+    }
+
+    #[getter]
+    fn ngram_range(&self) -> (usize, usize) {
+        self.ngram_range
+    }
+}
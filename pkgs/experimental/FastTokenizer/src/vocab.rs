@@ -0,0 +1,766 @@
+use memmap2::Mmap;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rayon::prelude::*;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, RwLock};
+
+use crate::mapped::{check_table, map_file, table_token, write_table, MappedTokens};
+use crate::shared::Shared;
+use crate::tokenizer::Tokenizer;
+
+/// Magic bytes at the start of the binary vocabulary format.
+const BINARY_MAGIC: &[u8; 4] = b"FTVB";
+const BINARY_VERSION: u8 = 2;
+/// Size of the header before the tokens.
+const BINARY_HEADER: usize = 14;
+
+#[derive(Debug, Default)]
+pub struct VocabData {
+    /// Tokens read in place from a memory-mapped file, which take the ids
+    /// before the ones in `tokens`.
+    mapped: Option<MappedTokens>,
+    token_to_id: HashMap<String, u32>,
+    tokens: Vec<String>,
+    unk_token: Option<String>,
+    frozen: bool,
+}
+
+impl VocabData {
+    pub fn len(&self) -> usize {
+        self.mapped.as_ref().map_or(0, MappedTokens::len) + self.tokens.len()
+    }
+
+    /// Id of `token`, if it is in the vocabulary.
+    pub fn id(&self, token: &str) -> Option<u32> {
+        self.mapped
+            .as_ref()
+            .and_then(|mapped| mapped.id(token))
+            .or_else(|| self.token_to_id.get(token).copied())
+    }
+
+    fn token(&self, id: u32) -> Option<&str> {
+        let id = id as usize;
+        match &self.mapped {
+            Some(mapped) if id < mapped.len() => mapped.get(id),
+            Some(mapped) => self.tokens.get(id - mapped.len()).map(String::as_str),
+            None => self.tokens.get(id).map(String::as_str),
+        }
+    }
+
+    /// All tokens in id order.
+    fn iter(&self) -> impl ExactSizeIterator<Item = &str> + Clone {
+        let mapped = self.mapped.as_ref().map_or(0, MappedTokens::len);
+        let tokens = self.tokens.len();
+        (0..mapped + tokens).map(move |id| self.token(id as u32).expect("id in range"))
+    }
+
+    fn insert(&mut self, token: &str) -> u32 {
+        if let Some(id) = self.id(token) {
+            return id;
+        }
+        let id = self.len() as u32;
+        self.tokens.push(token.to_string());
+        self.token_to_id.insert(token.to_string(), id);
+        id
+    }
+
+    fn unk_id(&self) -> Option<u32> {
+        self.unk_token.as_ref().and_then(|unk| self.id(unk))
+    }
+
+    /// Id of `token` without adding it: the unknown token's id when it is
+    /// missing, or an error when there is no unknown token either.
+    fn lookup(&self, token: &str) -> Result<u32, String> {
+        self.id(token)
+            .or_else(|| self.unk_id())
+            .ok_or_else(|| format!("Token '{}' is not in the vocabulary", token))
+    }
+
+    fn to_json(&self) -> Value {
+        let vocab: Map<String, Value> = self
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), json!(id)))
+            .collect();
+        json!({
+            "unk_token": self.unk_token,
+            "frozen": self.frozen,
+            "vocab": vocab,
+        })
+    }
+
+    /// Read either the format written by `to_json` or a bare `{token: id}`
+    /// mapping such as a `vocab.json` file.
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let (mapping, unk_token, frozen) = match value.get("vocab") {
+            Some(Value::Object(vocab)) => (
+                vocab,
+                value
+                    .get("unk_token")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                value
+                    .get("frozen")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            ),
+            _ => match value {
+                Value::Object(vocab) => (vocab, None, false),
+                _ => return Err("Vocabulary JSON must be an object".to_string()),
+            },
+        };
+        let mut entries = mapping
+            .iter()
+            .map(|(token, id)| {
+                id.as_u64()
+                    .map(|id| (id, token.clone()))
+                    .ok_or_else(|| format!("Invalid id for token '{}'", token))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort_unstable();
+        let mut data = VocabData {
+            unk_token,
+            frozen,
+            ..Default::default()
+        };
+        for (expected, (id, token)) in entries.into_iter().enumerate() {
+            if id != expected as u64 {
+                return Err(format!(
+                    "Token ids must be contiguous from 0, missing id {}",
+                    expected
+                ));
+            }
+            data.insert(&token);
+        }
+        if let Some(unk) = data.unk_token.clone() {
+            data.insert(&unk);
+        }
+        Ok(data)
+    }
+
+    /// Binary layout, all integers little-endian:
+    /// `b"FTVB"`, version `u8`, flags `u8` (bit 0: frozen, bit 1: has unk),
+    /// unk id `u32`, token count `u32`, then the tokens in id order as a
+    /// token table (see `write_table`), which `load_binary` can map into
+    /// memory. Version 1 stored each token as its length `u32` and UTF-8
+    /// bytes instead.
+    fn to_bytes(&self) -> Vec<u8> {
+        let tokens = self.iter();
+        let size = tokens.clone().map(|t| t.len() + 4).sum::<usize>();
+        let mut out = Vec::with_capacity(BINARY_HEADER + 4 + size);
+        out.extend_from_slice(BINARY_MAGIC);
+        out.push(BINARY_VERSION);
+        let unk_id = self.unk_id();
+        out.push(self.frozen as u8 | (unk_id.is_some() as u8) << 1);
+        out.extend_from_slice(&unk_id.unwrap_or(0).to_le_bytes());
+        out.extend_from_slice(&(tokens.len() as u32).to_le_bytes());
+        write_table(&mut out, tokens);
+        out
+    }
+
+    /// The header fields of binary data: flags, unk id, token count and
+    /// format version.
+    fn read_header(data: &[u8]) -> Result<(u8, u32, u32, u8), String> {
+        let mut reader = ByteReader { data, pos: 0 };
+        if reader.take(4)? != BINARY_MAGIC {
+            return Err("Not a binary vocabulary file".to_string());
+        }
+        let version = reader.take(1)?[0];
+        if version == 0 || version > BINARY_VERSION {
+            return Err(format!("Unsupported vocabulary format version {}", version));
+        }
+        let flags = reader.take(1)?[0];
+        Ok((flags, reader.u32()?, reader.u32()?, version))
+    }
+
+    /// Set the flags and unknown token read from the header.
+    fn apply_header(&mut self, flags: u8, unk_id: u32) -> Result<(), String> {
+        self.frozen = flags & 1 != 0;
+        if flags & 2 != 0 {
+            self.unk_token = Some(
+                self.token(unk_id)
+                    .ok_or_else(|| format!("Invalid unknown token id {}", unk_id))?
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let (flags, unk_id, count, version) = Self::read_header(data)?;
+        let mut vocab = VocabData::default();
+        if version == 1 {
+            let mut reader = ByteReader {
+                data,
+                pos: BINARY_HEADER,
+            };
+            for _ in 0..count {
+                let len = reader.u32()? as usize;
+                let token = std::str::from_utf8(reader.take(len)?)
+                    .map_err(|e| format!("Invalid token: {}", e))?;
+                vocab.insert(token);
+            }
+        } else {
+            let count = count as usize;
+            check_table(data, BINARY_HEADER, count)?;
+            for id in 0..count {
+                vocab.insert(table_token(data, BINARY_HEADER, count, id));
+            }
+        }
+        vocab.apply_header(flags, unk_id)?;
+        Ok(vocab)
+    }
+
+    /// Use the tokens of a memory-mapped binary vocabulary in place.
+    fn from_map(map: Mmap) -> Result<Self, String> {
+        let (flags, unk_id, count, version) = Self::read_header(&map)?;
+        if version == 1 {
+            return Err(
+                "Vocabulary file uses format version 1, which cannot be memory-mapped; \
+                 load it without mmap and save it again with save_binary"
+                    .to_string(),
+            );
+        }
+        let mut vocab = VocabData {
+            mapped: Some(MappedTokens::new(map, BINARY_HEADER, count as usize)?),
+            ..Default::default()
+        };
+        vocab.apply_header(flags, unk_id)?;
+        Ok(vocab)
+    }
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + n)
+            .ok_or("Truncated vocabulary data")?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+/// Mapping between tokens and integer ids.
+///
+/// A mutable vocabulary assigns the next free id to every new token it
+/// encodes; a frozen one maps unseen tokens to `unk_token`, or raises when
+/// there is none. Copies of a `Vocab`, including the ones held by
+/// tokenizers, share the same underlying mapping.
+#[pyclass(skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Debug, Default)]
+pub struct Vocab {
+    data: Arc<RwLock<VocabData>>,
+}
+
+impl Vocab {
+    fn from_data(data: VocabData) -> Self {
+        Vocab {
+            data: Arc::new(RwLock::new(data)),
+        }
+    }
+
+    /// The JSON value written by `to_json`.
+    pub fn to_value(&self) -> Value {
+        self.data.read().unwrap().to_json()
+    }
+
+    /// Load a vocabulary from a JSON value accepted by `from_json`.
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        VocabData::from_json(value).map(Vocab::from_data)
+    }
+
+    /// Ids of `tokens`, adding unseen ones unless the vocabulary is frozen.
+    pub fn encode_tokens<S: AsRef<str>>(&self, tokens: &[S]) -> Result<Vec<u32>, String> {
+        {
+            let data = self.data.read().unwrap();
+            if data.frozen {
+                return tokens.iter().map(|t| data.lookup(t.as_ref())).collect();
+            }
+            let ids: Option<Vec<u32>> = tokens.iter().map(|t| data.id(t.as_ref())).collect();
+            if let Some(ids) = ids {
+                return Ok(ids);
+            }
+        }
+        let mut data = self.data.write().unwrap();
+        if data.frozen {
+            return tokens.iter().map(|t| data.lookup(t.as_ref())).collect();
+        }
+        Ok(tokens.iter().map(|t| data.insert(t.as_ref())).collect())
+    }
+
+    /// Run `f` with read access to the vocabulary, e.g. to look up many
+    /// tokens without taking the lock for each.
+    pub fn with_token_ids<R>(&self, f: impl FnOnce(&VocabData) -> R) -> R {
+        f(&self.data.read().unwrap())
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.data.read().unwrap().frozen
+    }
+
+    /// Tokens for `ids`; unknown ids are an error.
+    pub fn decode_ids(&self, ids: &[u32]) -> Result<Vec<String>, String> {
+        let data = self.data.read().unwrap();
+        ids.iter()
+            .map(|&id| {
+                data.token(id)
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("Invalid token id {}", id))
+            })
+            .collect()
+    }
+
+    /// Tokenize `text` and map the tokens to ids, failing when the text
+    /// exceeds the tokenizer's limits.
+    pub fn encode_text(&self, tokenizer: &dyn Tokenizer, text: &str) -> PyResult<Vec<u32>> {
+        tokenizer
+            .try_tokenize(text)
+            .and_then(|tokens| self.encode_tokens(&tokens))
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Map ids back to tokens and join them with single spaces.
+    pub fn decode_text(&self, ids: &[u32]) -> PyResult<String> {
+        self.decode_ids(ids)
+            .map(|tokens| tokens.join(" "))
+            .map_err(PyValueError::new_err)
+    }
+}
+
+/// Token frequencies for `Vocab.prune`: by token, or by id.
+#[derive(FromPyObject)]
+pub enum Frequencies {
+    ByToken(HashMap<String, u64>),
+    ById(Vec<u64>),
+}
+
+/// Map `ids` through a `Vocab.prune` table, dropping ids mapped to -1.
+fn remap(ids: &[u32], table: &[i64]) -> Result<Vec<u32>, String> {
+    let mut out = Vec::with_capacity(ids.len());
+    for &id in ids {
+        match table.get(id as usize) {
+            Some(&new) if new >= 0 => out.push(new as u32),
+            Some(_) => {}
+            None => return Err(format!("Invalid token id {}", id)),
+        }
+    }
+    Ok(out)
+}
+
+/// Token ids, or a batch of them.
+#[derive(FromPyObject)]
+pub enum IdInput {
+    Batch(Vec<Vec<u32>>),
+    Single(Vec<u32>),
+}
+
+/// Re-encode token ids after a vocabulary change, e.g. with the table
+/// returned by `Vocab.prune`.
+///
+/// Args:
+///     ids (List[int] | List[List[int]]): Ids in the old vocabulary, or a
+///         batch of id lists, remapped in parallel without the GIL.
+///     table (List[int]): The new id of every old id; ids mapped to -1 are
+///         dropped.
+///
+/// Returns:
+///     List[int] | List[List[int]]: The ids in the new vocabulary, in the
+///     shape of `ids`.
+///
+/// Raises:
+///     ValueError: If an id is outside the table.
+#[pyfunction]
+pub fn remap_ids(py: Python<'_>, ids: IdInput, table: Vec<i64>) -> PyResult<Py<PyAny>> {
+    match ids {
+        IdInput::Single(ids) => Ok(remap(&ids, &table)
+            .map_err(PyValueError::new_err)?
+            .into_pyobject(py)?
+            .into_any()
+            .unbind()),
+        IdInput::Batch(batch) => {
+            let remapped: Result<Vec<Vec<u32>>, String> =
+                py.detach(|| batch.par_iter().map(|ids| remap(ids, &table)).collect());
+            Ok(remapped
+                .map_err(PyValueError::new_err)?
+                .into_pyobject(py)?
+                .into_any()
+                .unbind())
+        }
+    }
+}
+
+/// The vocabulary attached to a tokenizer, required by `encode` and `decode`.
+pub fn require_vocab(vocab: &Shared<Option<Vocab>>) -> PyResult<Vocab> {
+    Option::clone(&vocab.load()).ok_or_else(|| {
+        PyValueError::new_err("No vocabulary attached; pass `vocab=` or set `tokenizer.vocab`")
+    })
+}
+
+#[pymethods]
+impl Vocab {
+    /// Create a vocabulary.
+    ///
+    /// Args:
+    ///     tokens (List[str], optional): Initial tokens, numbered in order.
+    ///         Duplicates keep their first id.
+    ///     unk_token (str, optional): Token that stands in for unseen tokens
+    ///         once the vocabulary is frozen. Added if not among `tokens`.
+    ///     frozen (bool): Start in frozen mode.
+    #[new]
+    #[pyo3(signature = (tokens=None, unk_token=None, frozen=false))]
+    pub fn new(tokens: Option<Vec<String>>, unk_token: Option<String>, frozen: bool) -> Self {
+        let mut data = VocabData::default();
+        for token in tokens.iter().flatten() {
+            data.insert(token);
+        }
+        if let Some(unk) = &unk_token {
+            data.insert(unk);
+        }
+        data.unk_token = unk_token;
+        data.frozen = frozen;
+        Vocab::from_data(data)
+    }
+
+    /// The vocabulary in the compact binary format, for `pickle` and `copy`.
+    fn __getstate__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.to_bytes())
+    }
+
+    fn __setstate__(&mut self, state: &[u8]) -> PyResult<()> {
+        let data = VocabData::from_bytes(state).map_err(PyValueError::new_err)?;
+        self.data = Arc::new(RwLock::new(data));
+        Ok(())
+    }
+
+    /// Build a vocabulary from token counts, e.g. from `count_tokens`.
+    ///
+    /// Special tokens come first, then the counted tokens from most to least
+    /// frequent, ties broken alphabetically so the ids are reproducible.
+    ///
+    /// Args:
+    ///     counts (Dict[str, int]): How often each token occurs.
+    ///     max_size (int, optional): Largest vocabulary size, special tokens
+    ///         included.
+    ///     min_freq (int): Drop tokens counted fewer times than this.
+    ///     unk_token (str, optional): Unknown token, given the first id.
+    ///     special_tokens (List[str], optional): Tokens that always get the
+    ///         ids after `unk_token`, whatever their count.
+    ///     frozen (bool): Freeze the vocabulary, so unseen tokens map to
+    ///         `unk_token` instead of being added.
+    ///
+    /// Returns:
+    ///     Vocab: The new vocabulary.
+    #[staticmethod]
+    #[pyo3(signature = (counts, max_size=None, min_freq=1, unk_token=None, special_tokens=None, frozen=true))]
+    fn from_counts(
+        counts: HashMap<String, u64>,
+        max_size: Option<usize>,
+        min_freq: u64,
+        unk_token: Option<String>,
+        special_tokens: Option<Vec<String>>,
+        frozen: bool,
+    ) -> Self {
+        let mut data = VocabData::default();
+        for token in unk_token.iter().chain(special_tokens.iter().flatten()) {
+            data.insert(token);
+        }
+        let mut counts: Vec<(String, u64)> = counts
+            .into_iter()
+            .filter(|(token, count)| *count >= min_freq && data.id(token).is_none())
+            .collect();
+        counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let room = max_size.map_or(usize::MAX, |max| max.saturating_sub(data.len()));
+        for (token, _) in counts.into_iter().take(room) {
+            data.insert(&token);
+        }
+        data.unk_token = unk_token;
+        data.frozen = frozen;
+        Vocab::from_data(data)
+    }
+
+    /// Drop rare tokens, for a smaller vocabulary.
+    ///
+    /// Kept tokens stay in their current order and are renumbered from 0,
+    /// so the unknown and special tokens at the front keep their ids.
+    ///
+    /// Args:
+    ///     counts (Dict[str, int] | List[int]): How often each token occurs,
+    ///         by token (e.g. from `count_tokens`) or indexed by id. Tokens
+    ///         without a count have count 0.
+    ///     min_freq (int): Drop tokens counted fewer times than this.
+    ///     max_size (int, optional): Largest new size; the most frequent
+    ///         tokens are kept, ties going to lower ids.
+    ///     keep (List[str], optional): Tokens always kept, such as special
+    ///         tokens. The unknown token is always kept.
+    ///
+    /// Returns:
+    ///     Tuple[Vocab, List[int]]: The pruned vocabulary, with the same
+    ///     unknown token and frozen state, and the new id of every old id
+    ///     for `remap_ids`. Dropped tokens map to the new unknown token's
+    ///     id, or to -1 when there is none.
+    #[pyo3(signature = (counts, min_freq=1, max_size=None, keep=None))]
+    fn prune(
+        &self,
+        counts: Frequencies,
+        min_freq: u64,
+        max_size: Option<usize>,
+        keep: Option<Vec<String>>,
+    ) -> PyResult<(Vocab, Vec<i64>)> {
+        let data = self.data.read().unwrap();
+        let mut frequencies = vec![0u64; data.len()];
+        match counts {
+            Frequencies::ByToken(counts) => {
+                for (token, count) in counts {
+                    if let Some(id) = data.id(&token) {
+                        frequencies[id as usize] = count;
+                    }
+                }
+            }
+            Frequencies::ById(counts) => {
+                if counts.len() > data.len() {
+                    return Err(PyValueError::new_err(format!(
+                        "Got {} counts for a vocabulary of {} tokens",
+                        counts.len(),
+                        data.len()
+                    )));
+                }
+                frequencies[..counts.len()].copy_from_slice(&counts);
+            }
+        }
+        let mut forced = vec![false; data.len()];
+        for token in keep.iter().flatten().chain(data.unk_token.iter()) {
+            if let Some(id) = data.id(token) {
+                forced[id as usize] = true;
+            }
+        }
+        let mut candidates: Vec<usize> = (0..data.len())
+            .filter(|&id| !forced[id] && frequencies[id] >= min_freq)
+            .collect();
+        if let Some(max_size) = max_size {
+            let room = max_size.saturating_sub(forced.iter().filter(|&&f| f).count());
+            candidates.sort_by(|&a, &b| frequencies[b].cmp(&frequencies[a]).then(a.cmp(&b)));
+            candidates.truncate(room);
+        }
+        let mut kept = forced;
+        for id in candidates {
+            kept[id] = true;
+        }
+        let mut pruned = VocabData::default();
+        let mut table: Vec<i64> = data
+            .iter()
+            .zip(&kept)
+            .map(|(token, &kept)| {
+                if kept {
+                    pruned.insert(token) as i64
+                } else {
+                    -1
+                }
+            })
+            .collect();
+        pruned.unk_token = data.unk_token.clone();
+        pruned.frozen = data.frozen;
+        if let Some(unk) = pruned.unk_id() {
+            for new in table.iter_mut().filter(|new| **new < 0) {
+                *new = unk as i64;
+            }
+        }
+        Ok((Vocab::from_data(pruned), table))
+    }
+
+    /// Add a token and return its id. Existing tokens keep their id.
+    ///
+    /// Raises:
+    ///     ValueError: If the token is new and the vocabulary is frozen.
+    fn add(&self, token: &str) -> PyResult<u32> {
+        Ok(self.add_tokens(vec![token.to_string()])?[0])
+    }
+
+    /// Add several tokens and return their ids.
+    fn add_tokens(&self, tokens: Vec<String>) -> PyResult<Vec<u32>> {
+        let mut data = self.data.write().unwrap();
+        if data.frozen {
+            if let Some(token) = tokens.iter().find(|t| data.id(t).is_none()) {
+                return Err(PyValueError::new_err(format!(
+                    "Cannot add '{}' to a frozen vocabulary",
+                    token
+                )));
+            }
+        }
+        Ok(tokens.iter().map(|t| data.insert(t)).collect())
+    }
+
+    /// Id of `token`, or `None` when it is not in the vocabulary.
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.data.read().unwrap().id(token)
+    }
+
+    /// Token with the given id, or `None` when the id is out of range.
+    pub fn id_to_token(&self, id: u32) -> Option<String> {
+        self.data.read().unwrap().token(id).map(str::to_string)
+    }
+
+    /// Map tokens to ids.
+    ///
+    /// Args:
+    ///     tokens (List[str]): The tokens to look up.
+    ///
+    /// Returns:
+    ///     List[int]: Their ids. A mutable vocabulary adds unseen tokens; a
+    ///     frozen one maps them to `unk_token`.
+    fn encode(&self, tokens: Vec<String>) -> PyResult<Vec<u32>> {
+        self.encode_tokens(&tokens).map_err(PyValueError::new_err)
+    }
+
+    /// Map ids back to tokens.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<Vec<String>> {
+        self.decode_ids(&ids).map_err(PyValueError::new_err)
+    }
+
+    /// Stop adding new tokens; unseen tokens map to `unk_token` from now on.
+    fn freeze(&self) {
+        self.data.write().unwrap().frozen = true;
+    }
+
+    /// Allow new tokens to be added again.
+    fn unfreeze(&self) {
+        self.data.write().unwrap().frozen = false;
+    }
+
+    #[getter]
+    fn frozen(&self) -> bool {
+        self.is_frozen()
+    }
+
+    #[getter]
+    fn unk_token(&self) -> Option<String> {
+        self.data.read().unwrap().unk_token.clone()
+    }
+
+    #[getter]
+    fn unk_id(&self) -> Option<u32> {
+        self.data.read().unwrap().unk_id()
+    }
+
+    /// All tokens in id order.
+    pub fn tokens(&self) -> Vec<String> {
+        let data = self.data.read().unwrap();
+        data.iter().map(str::to_string).collect()
+    }
+
+    /// The `{token: id}` mapping.
+    fn to_dict(&self) -> HashMap<String, u32> {
+        let data = self.data.read().unwrap();
+        data.iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as u32))
+            .collect()
+    }
+
+    /// Serialize to a JSON string.
+    fn to_json(&self) -> String {
+        self.data.read().unwrap().to_json().to_string()
+    }
+
+    /// Load a vocabulary from a JSON string written by `to_json`, or from a
+    /// plain `{token: id}` object with contiguous ids.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| PyValueError::new_err(format!("Invalid vocabulary JSON: {}", e)))?;
+        VocabData::from_json(&value)
+            .map(Vocab::from_data)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Save the vocabulary as JSON.
+    fn save(&self, path: &str) -> PyResult<()> {
+        fs::write(path, self.to_json())
+            .map_err(|e| PyIOError::new_err(format!("Failed to write vocab file: {}", e)))
+    }
+
+    /// Load a vocabulary saved with `save`, or a `vocab.json` mapping.
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read vocab file: {}", e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Serialize to the compact binary format.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.data.read().unwrap().to_bytes()
+    }
+
+    /// Load a vocabulary from bytes produced by `to_bytes`.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        VocabData::from_bytes(data)
+            .map(Vocab::from_data)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Save the vocabulary in the compact binary format.
+    fn save_binary(&self, path: &str) -> PyResult<()> {
+        fs::write(path, self.to_bytes())
+            .map_err(|e| PyIOError::new_err(format!("Failed to write vocab file: {}", e)))
+    }
+
+    /// Load a vocabulary saved with `save_binary`.
+    ///
+    /// Args:
+    ///     path (str): The vocabulary file.
+    ///     mmap (bool): Map the file into memory and read the tokens in
+    ///         place instead of copying them, so loading is nearly free and
+    ///         worker processes share the pages. The index used to look up
+    ///         ids is built on first use. The file must not change while
+    ///         the vocabulary is in use.
+    #[staticmethod]
+    #[pyo3(signature = (path, mmap=false))]
+    fn load_binary(path: &str, mmap: bool) -> PyResult<Self> {
+        let read_error = |e| PyIOError::new_err(format!("Failed to read vocab file: {}", e));
+        if mmap {
+            let map = map_file(path).map_err(read_error)?;
+            return VocabData::from_map(map)
+                .map(Vocab::from_data)
+                .map_err(PyValueError::new_err);
+        }
+        Self::from_bytes(&fs::read(path).map_err(read_error)?)
+    }
+
+    /// Whether the tokens are read in place from a memory-mapped file.
+    #[getter]
+    fn memory_mapped(&self) -> bool {
+        self.data.read().unwrap().mapped.is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.data.read().unwrap().len()
+    }
+
+    fn __contains__(&self, token: &str) -> bool {
+        self.data.read().unwrap().id(token).is_some()
+    }
+
+    fn __repr__(&self) -> String {
+        let data = self.data.read().unwrap();
+        format!(
+            "Vocab(size={}, frozen={})",
+            data.len(),
+            if data.frozen { "True" } else { "False" }
+        )
+    }
+}
@@ -1,17 +1,202 @@
+use fasttokenizer_core::Tokenize;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
-#[pyclass]
-pub struct WhitespaceTokenizer {}
+use crate::config;
+use crate::intern::token_list;
+use crate::shared::Shared;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+use crate::vocab::{require_vocab, Vocab};
+
+#[pyclass(frozen, skip_from_py_object, module = "fasttokenizer")]
+#[derive(Clone, Default)]
+pub struct WhitespaceTokenizer {
+    inner: fasttokenizer_core::WhitespaceTokenizer,
+    vocab: Shared<Option<Vocab>>,
+}
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.inner.tokenize_with_offsets(text)
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn tokens_are_slices(&self) -> bool {
+        true
+    }
+
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        self.inner.tokenize(text)
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.inner.token_spans(text)
+    }
+}
+
+tokenizer_pymethods!(WhitespaceTokenizer, encode_batch);
 
 #[pymethods]
 impl WhitespaceTokenizer {
+    /// Create a whitespace tokenizer.
+    ///
+    /// Args:
+    ///     vocab (Vocab, optional): Vocabulary used by `encode` and `decode`.
+    ///     preserve_delimiters (bool): Also emit the whitespace between words
+    ///         as tokens, so joining the tokens gives back the text.
+    ///     keep_newlines (bool): Emit every line break (`\n`, `\r\n` or
+    ///         `\r`) as a token of its own.
+    ///     max_splits (int, optional): Split off at most this many words;
+    ///         the remainder of the text becomes the last token, like
+    ///         `str.split(maxsplit=...)`.
     #[new]
-    fn new() -> Self {
-        WhitespaceTokenizer {}
+    #[pyo3(signature = (vocab=None, preserve_delimiters=false, keep_newlines=false, max_splits=None))]
+    fn new(
+        vocab: Option<PyRef<'_, Vocab>>,
+        preserve_delimiters: bool,
+        keep_newlines: bool,
+        max_splits: Option<usize>,
+    ) -> Self {
+        WhitespaceTokenizer {
+            inner: fasttokenizer_core::WhitespaceTokenizer {
+                preserve_delimiters,
+                keep_newlines,
+                max_splits,
+            },
+            vocab: vocab.map(|v| v.clone()).into(),
+        }
+    }
+
+    /// Constructor arguments recreating this tokenizer, for `pickle` and
+    /// `copy`.
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<((), Bound<'py, PyDict>)> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("vocab", Option::clone(&self.vocab.load()))?;
+        kwargs.set_item("preserve_delimiters", self.inner.preserve_delimiters)?;
+        kwargs.set_item("keep_newlines", self.inner.keep_newlines)?;
+        kwargs.set_item("max_splits", self.inner.max_splits)?;
+        Ok(((), kwargs))
+    }
+
+    /// Serialize the tokenizer's configuration to JSON.
+    fn to_json(slf: &Bound<'_, Self>) -> PyResult<String> {
+        config::to_json(slf.as_any())
+    }
+
+    /// Recreate a tokenizer from `to_json` output.
+    #[staticmethod]
+    fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, Self>> {
+        config::from_json(py, json)
+    }
+
+    /// Split `input` on whitespace.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     return_offsets (bool): Return `(token, start, end)` tuples with
+    ///         character offsets, as `tokenize_with_offsets` does.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, return_offsets=false, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        return_offsets: bool,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        if return_offsets {
+            return self
+                .tokenize_with_offsets(input, "char")?
+                .into_pyobject(py)
+                .map(Bound::into_any);
+        }
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into ids with the attached vocabulary.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be encoded.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    ///
+    /// Returns:
+    ///     List[int]: One id per token.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        require_vocab(&self.vocab)?.encode_text(self, input)
+    }
+
+    /// Turn ids back into text, joining the tokens with single spaces, or
+    /// concatenating them when delimiters are preserved.
+    fn decode(&self, ids: Vec<u32>) -> PyResult<String> {
+        let vocab = require_vocab(&self.vocab)?;
+        if !self.inner.preserve_delimiters {
+            return vocab.decode_text(&ids);
+        }
+        vocab
+            .decode_ids(&ids)
+            .map(|tokens| tokens.concat())
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    #[getter]
+    fn preserve_delimiters(&self) -> bool {
+        self.inner.preserve_delimiters
+    }
+
+    #[getter]
+    fn keep_newlines(&self) -> bool {
+        self.inner.keep_newlines
+    }
+
+    #[getter]
+    fn max_splits(&self) -> Option<usize> {
+        self.inner.max_splits
+    }
+
+    /// The vocabulary used by `encode` and `decode`, if any.
+    #[getter]
+    fn vocab(&self) -> Option<Vocab> {
+        Option::clone(&self.vocab.load())
     }
 
-    fn tokenize(&self, input: &str) -> PyResult<Vec<String>> {
-        let tokens: Vec<String> = input.split_whitespace().map(|s| s.to_string()).collect();
-        Ok(tokens)
+    #[setter]
+    fn set_vocab(&self, vocab: Option<PyRef<'_, Vocab>>) {
+        self.vocab.store(vocab.map(|v| v.clone()));
     }
 }
@@ -0,0 +1,545 @@
+use fasttokenizer_core::cjk::is_cjk;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use unicode_categories::UnicodeCategories;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config;
+use crate::detokenizer::clean_up_tokenization;
+use crate::intern::token_list;
+use crate::special_tokens::SpecialTokens;
+use crate::tokenizer::{offsets, tokenizer_pymethods, Token, Tokenizer};
+use crate::utf8::Text;
+
+pub fn is_bert_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || c.is_punctuation()
+}
+
+pub fn is_bert_control(c: char) -> bool {
+    if matches!(c, '\t' | '\n' | '\r') {
+        return false;
+    }
+    c == '\0' || c == '\u{fffd}' || c.is_other_control() || c.is_other_format()
+}
+
+/// The BERT special tokens, registered by `from_file` unless it is given
+/// others.
+const SPECIAL_TOKENS: &[&str] = &["[PAD]", "[CLS]", "[SEP]", "[MASK]"];
+
+/// The word-level model behind `WordPieceTokenizer`.
+struct WordPieceModel {
+    vocab: HashMap<String, u32>,
+    vocab_r: HashMap<u32, String>,
+    unk_id: u32,
+    continuing_subword_prefix: String,
+    max_input_chars_per_word: usize,
+    lowercase: bool,
+    strip_accents: bool,
+    tokenize_chinese_chars: bool,
+    /// Tokens left out when decoding with `skip_special_tokens`, besides
+    /// the unknown token.
+    special_tokens: SpecialTokens,
+}
+
+impl WordPieceModel {
+    /// BERT basic tokenization: byte spans of words split on whitespace,
+    /// punctuation and (optionally) CJK characters, dropping control characters.
+    fn split_words(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (i, c) in text.char_indices() {
+            let single = is_bert_punctuation(c) || (self.tokenize_chinese_chars && is_cjk(c));
+            if c.is_whitespace() || is_bert_control(c) || single {
+                if let Some(s) = start.take() {
+                    words.push((s, i));
+                }
+                if single {
+                    words.push((i, i + c.len_utf8()));
+                }
+            } else if start.is_none() {
+                start = Some(i);
+            }
+        }
+        if let Some(s) = start {
+            words.push((s, text.len()));
+        }
+        words
+    }
+
+    /// Lowercase and strip accents from a word, keeping for every byte of the
+    /// result the byte span of the original character it came from.
+    fn normalize_word(&self, word: &str, base: usize) -> (String, Vec<(usize, usize)>) {
+        let mut normalized = String::with_capacity(word.len());
+        let mut spans = Vec::with_capacity(word.len());
+        for (i, c) in word.char_indices() {
+            let span = (base + i, base + i + c.len_utf8());
+            let before = normalized.len();
+            let lowered: String = if self.lowercase {
+                c.to_lowercase().collect()
+            } else {
+                c.to_string()
+            };
+            if self.strip_accents {
+                normalized.extend(lowered.nfd().filter(|c| !c.is_mark_nonspacing()));
+            } else {
+                normalized.push_str(&lowered);
+            }
+            spans.resize(spans.len() + normalized.len() - before, span);
+        }
+        (normalized, spans)
+    }
+
+    /// Greedy longest-match-first split of one normalized word into vocab ids
+    /// with byte spans into the word. Returns `None` when some part has no match.
+    fn split_word(&self, word: &str) -> Option<Vec<(u32, usize, usize)>> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        let mut candidate = String::new();
+        while start < word.len() {
+            let mut end = word.len();
+            let mut found = None;
+            while start < end {
+                candidate.clear();
+                if start > 0 {
+                    candidate.push_str(&self.continuing_subword_prefix);
+                }
+                candidate.push_str(&word[start..end]);
+                if let Some(&id) = self.vocab.get(&candidate) {
+                    found = Some(id);
+                    break;
+                }
+                end -= word[..end].chars().next_back().map_or(1, char::len_utf8);
+            }
+            pieces.push((found?, start, end));
+            start = end;
+        }
+        Some(pieces)
+    }
+
+    /// WordPiece ids of one normalized word, with byte spans into the word.
+    /// Words that are too long or cannot be covered become a single unknown token.
+    fn encode_word(&self, word: &str) -> Vec<(u32, usize, usize)> {
+        if word.chars().count() > self.max_input_chars_per_word {
+            return vec![(self.unk_id, 0, word.len())];
+        }
+        self.split_word(word)
+            .unwrap_or_else(|| vec![(self.unk_id, 0, word.len())])
+    }
+
+    fn encode_with_offsets(&self, text: &str) -> Vec<(u32, usize, usize)> {
+        let mut out = Vec::new();
+        for (start, end) in self.split_words(text) {
+            let (word, spans) = self.normalize_word(&text[start..end], start);
+            if word.is_empty() {
+                continue;
+            }
+            out.extend(
+                self.encode_word(&word)
+                    .into_iter()
+                    .map(|(id, s, e)| (id, spans[s].0, spans[e - 1].1)),
+            );
+        }
+        out
+    }
+
+    fn is_special(&self, id: u32) -> bool {
+        id == self.unk_id
+            || self
+                .vocab_r
+                .get(&id)
+                .is_some_and(|token| self.special_tokens.contains(token))
+    }
+
+    fn decode(&self, ids: &[u32], skip_special_tokens: bool) -> String {
+        let mut text = String::new();
+        let ids = ids
+            .iter()
+            .filter(|&&id| !(skip_special_tokens && self.is_special(id)));
+        for token in ids.filter_map(|id| self.vocab_r.get(id)) {
+            match token.strip_prefix(self.continuing_subword_prefix.as_str()) {
+                Some(rest) if !text.is_empty() => text.push_str(rest),
+                _ => {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(token);
+                }
+            }
+        }
+        text
+    }
+}
+
+/// BERT-style WordPiece tokenizer.
+///
+/// Text is split on whitespace, punctuation and CJK characters, optionally
+/// lowercased and stripped of accents, and every word is broken into the
+/// longest vocabulary pieces from left to right. Words that cannot be covered
+/// by the vocabulary become the unknown token.
+#[pyclass(frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub struct WordPieceTokenizer {
+    model: Arc<WordPieceModel>,
+}
+
+impl WordPieceTokenizer {
+    /// A bare WordPiece model without BERT's normalization and word splitting,
+    /// for use behind separately configured pipeline stages.
+    pub fn with_vocab(
+        vocab: HashMap<String, u32>,
+        unk_token: &str,
+        continuing_subword_prefix: String,
+        max_input_chars_per_word: usize,
+    ) -> PyResult<Self> {
+        Self::with_options(
+            vocab,
+            unk_token,
+            continuing_subword_prefix,
+            max_input_chars_per_word,
+            false,
+            Some(false),
+            false,
+            SpecialTokens::default(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn with_options(
+        vocab: HashMap<String, u32>,
+        unk_token: &str,
+        continuing_subword_prefix: String,
+        max_input_chars_per_word: usize,
+        lowercase: bool,
+        strip_accents: Option<bool>,
+        tokenize_chinese_chars: bool,
+        special_tokens: SpecialTokens,
+    ) -> PyResult<Self> {
+        let unk_id = *vocab.get(unk_token).ok_or_else(|| {
+            PyValueError::new_err(format!("Unknown token '{}' is not in the vocab", unk_token))
+        })?;
+        let vocab_r = vocab.iter().map(|(k, &v)| (v, k.clone())).collect();
+        Ok(WordPieceTokenizer {
+            model: Arc::new(WordPieceModel {
+                vocab,
+                vocab_r,
+                unk_id,
+                continuing_subword_prefix,
+                max_input_chars_per_word,
+                lowercase,
+                strip_accents: strip_accents.unwrap_or(lowercase),
+                tokenize_chinese_chars,
+                special_tokens,
+            }),
+        })
+    }
+
+    /// Encode one pre-tokenized word as `(id, start, end)` with byte offsets into `word`.
+    pub fn encode_word(&self, word: &str) -> Vec<(u32, usize, usize)> {
+        self.model.encode_word(word)
+    }
+
+    /// The vocabulary and options as a JSON object, as embedded in tokenizer
+    /// configurations and bundles.
+    pub fn to_value(&self) -> Value {
+        let model = &self.model;
+        json!({
+            "vocab": model.vocab,
+            "unk_token": model.vocab_r[&model.unk_id],
+            "continuing_subword_prefix": model.continuing_subword_prefix,
+            "max_input_chars_per_word": model.max_input_chars_per_word,
+            "lowercase": model.lowercase,
+            "strip_accents": model.strip_accents,
+            "tokenize_chinese_chars": model.tokenize_chinese_chars,
+            "special_tokens": model.special_tokens.to_value(),
+        })
+    }
+
+    /// Recreate a tokenizer from `to_value` output.
+    pub fn from_value(value: &Value) -> PyResult<Self> {
+        let vocab = serde_json::from_value(value["vocab"].clone())
+            .map_err(|e| PyValueError::new_err(format!("Invalid WordPiece model: {}", e)))?;
+        let flag = |name: &str| value[name].as_bool();
+        let special_tokens = match &value["special_tokens"] {
+            Value::Null => SpecialTokens::default(),
+            special_tokens => SpecialTokens::from_value(special_tokens)?,
+        };
+        Self::with_options(
+            vocab,
+            value["unk_token"].as_str().unwrap_or("[UNK]"),
+            value["continuing_subword_prefix"]
+                .as_str()
+                .unwrap_or("##")
+                .to_string(),
+            value["max_input_chars_per_word"].as_u64().unwrap_or(100) as usize,
+            flag("lowercase").unwrap_or(true),
+            flag("strip_accents"),
+            flag("tokenize_chinese_chars").unwrap_or(true),
+            special_tokens,
+        )
+    }
+}
+
+impl Tokenizer for WordPieceTokenizer {
+    fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(id, start, end)| Token::new(self.model.vocab_r[&id].clone(), start, end))
+            .collect()
+    }
+
+    fn token_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.model
+            .encode_with_offsets(text)
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect()
+    }
+
+    fn to_object(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(Py::new(py, self.clone())?.into_any())
+    }
+
+    fn detokenize(&self, tokens: &[String]) -> String {
+        let ids: Vec<u32> = tokens.iter().filter_map(|t| self.token_to_id(t)).collect();
+        self.model.decode(&ids, false)
+    }
+}
+
+/// Read a BERT `vocab.txt`: one token per line, the line number is its id.
+fn read_vocab(path: &str) -> PyResult<HashMap<String, u32>> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read vocab file: {}", e)))?;
+    Ok(data
+        .lines()
+        .enumerate()
+        .map(|(id, token)| (token.trim_end().to_string(), id as u32))
+        .collect())
+}
+
+tokenizer_pymethods!(WordPieceTokenizer);
+
+#[pymethods]
+impl WordPieceTokenizer {
+    /// Create a WordPiece tokenizer from an in-memory vocabulary.
+    ///
+    /// Args:
+    ///     vocab (Dict[str, int]): Token to id mapping.
+    ///     unk_token (str): Token used for words the vocab cannot cover.
+    ///     continuing_subword_prefix (str): Prefix marking word continuations.
+    ///     max_input_chars_per_word (int): Longer words become `unk_token`.
+    ///     lowercase (bool): Lowercase text before matching (uncased models).
+    ///     strip_accents (bool, optional): Remove accents; follows `lowercase`
+    ///         when not given, like BERT.
+    ///     tokenize_chinese_chars (bool): Split CJK ideographs into single characters.
+    ///     special_tokens (SpecialTokens, optional): Tokens that `decode`
+    ///         leaves out with `skip_special_tokens`.
+    #[new]
+    #[pyo3(signature = (
+        vocab,
+        unk_token="[UNK]",
+        continuing_subword_prefix="##".to_string(),
+        max_input_chars_per_word=100,
+        lowercase=true,
+        strip_accents=None,
+        tokenize_chinese_chars=true,
+        special_tokens=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        vocab: HashMap<String, u32>,
+        unk_token: &str,
+        continuing_subword_prefix: String,
+        max_input_chars_per_word: usize,
+        lowercase: bool,
+        strip_accents: Option<bool>,
+        tokenize_chinese_chars: bool,
+        special_tokens: Option<PyRef<'_, SpecialTokens>>,
+    ) -> PyResult<Self> {
+        Self::with_options(
+            vocab,
+            unk_token,
+            continuing_subword_prefix,
+            max_input_chars_per_word,
+            lowercase,
+            strip_accents,
+            tokenize_chinese_chars,
+            special_tokens.map(|s| s.clone()).unwrap_or_default(),
+        )
+    }
+
+    /// Load a tokenizer from a BERT `vocab.txt` file.
+    ///
+    /// Without `special_tokens`, the BERT special tokens found in the
+    /// vocabulary (`[PAD]`, `[CLS]`, `[SEP]`, `[MASK]`) are registered.
+    #[staticmethod]
+    #[pyo3(signature = (
+        vocab_path,
+        unk_token="[UNK]",
+        continuing_subword_prefix="##".to_string(),
+        max_input_chars_per_word=100,
+        lowercase=true,
+        strip_accents=None,
+        tokenize_chinese_chars=true,
+        special_tokens=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_file(
+        vocab_path: &str,
+        unk_token: &str,
+        continuing_subword_prefix: String,
+        max_input_chars_per_word: usize,
+        lowercase: bool,
+        strip_accents: Option<bool>,
+        tokenize_chinese_chars: bool,
+        special_tokens: Option<PyRef<'_, SpecialTokens>>,
+    ) -> PyResult<Self> {
+        let vocab = read_vocab(vocab_path)?;
+        let special_tokens = match special_tokens {
+            Some(special_tokens) => special_tokens.clone(),
+            None => SpecialTokens::from_vocab(&vocab, SPECIAL_TOKENS),
+        };
+        Self::with_options(
+            vocab,
+            unk_token,
+            continuing_subword_prefix,
+            max_input_chars_per_word,
+            lowercase,
+            strip_accents,
+            tokenize_chinese_chars,
+            special_tokens,
+        )
+    }
+
+    /// Save the vocabulary and options to a single bundle file, to be loaded
+    /// back with `WordPieceTokenizer.load` instead of shipping `vocab.txt`
+    /// and the settings separately.
+    fn save(slf: &Bound<'_, Self>, path: &str) -> PyResult<()> {
+        config::save(slf.as_any(), path)
+    }
+
+    /// Load a tokenizer saved with `save`.
+    #[staticmethod]
+    fn load<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, Self>> {
+        config::load(py, path)
+    }
+
+    /// Tokenize `input`.
+    ///
+    /// Args:
+    ///     input (str | bytes): The input string to be tokenized.
+    ///     intern (bool | StringCache): Share one Python string between
+    ///         repeats of a token, within the call, or across calls through
+    ///         a `StringCache`.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, intern=None, errors="strict"))]
+    fn tokenize<'py>(
+        &self,
+        py: Python<'py>,
+        input: Text<'py>,
+        intern: Option<&Bound<'py, PyAny>>,
+        errors: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input: &str = &input.decode(errors)?;
+        token_list(py, Tokenizer::tokenize(self, input), intern)
+    }
+
+    /// Tokenize and return `(token, start, end)` tuples.
+    ///
+    /// Args:
+    ///     input (str): The input string to be tokenized.
+    ///     unit (str): `"char"` for character offsets (Python slicing) or
+    ///         `"byte"` for offsets into the UTF-8 encoded text.
+    #[pyo3(signature = (input, unit="char"))]
+    fn tokenize_with_offsets(
+        &self,
+        input: &str,
+        unit: &str,
+    ) -> PyResult<Vec<(String, usize, usize)>> {
+        offsets(input, Tokenizer::tokenize_with_offsets(self, input), unit)
+    }
+
+    /// Encode text into token ids.
+    ///
+    /// Args:
+    ///     input (str | bytes): The text to encode.
+    ///     errors (str): How to decode `bytes` input that is not valid
+    ///         UTF-8: raise a `UnicodeDecodeError` (`"strict"`),
+    ///         `"replace"` the bad bytes with U+FFFD or `"ignore"` them.
+    #[pyo3(signature = (input, errors="strict"))]
+    fn encode<'py>(&self, input: Text<'py>, errors: &str) -> PyResult<Vec<u32>> {
+        let input: &str = &input.decode(errors)?;
+        Ok(self
+            .model
+            .encode_with_offsets(input)
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect())
+    }
+
+    /// Encode several strings at once, releasing the GIL while working.
+    fn encode_batch(&self, py: Python<'_>, inputs: Vec<String>) -> PyResult<Vec<Vec<u32>>> {
+        Ok(py.detach(|| {
+            inputs
+                .iter()
+                .map(|text| {
+                    self.model
+                        .encode_with_offsets(text)
+                        .into_iter()
+                        .map(|(id, _, _)| id)
+                        .collect()
+                })
+                .collect()
+        }))
+    }
+
+    /// Turn token ids back into text, joining continuation pieces to their word.
+    ///
+    /// Args:
+    ///     ids (List[int]): Token ids.
+    ///     skip_special_tokens (bool): Leave out the unknown token and the
+    ///         registered `special_tokens`.
+    ///     clean_up_tokenization_spaces (bool): Remove spaces before
+    ///         punctuation and contractions like `transformers` does.
+    #[pyo3(signature = (ids, skip_special_tokens=true, clean_up_tokenization_spaces=true))]
+    fn decode(
+        &self,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> PyResult<String> {
+        Ok(clean_up_tokenization(
+            self.model.decode(&ids, skip_special_tokens),
+            clean_up_tokenization_spaces,
+        ))
+    }
+
+    /// Turn tokens, e.g. filtered ones, back into readable text.
+    fn detokenize(&self, tokens: Vec<String>) -> String {
+        Tokenizer::detokenize(self, &tokens)
+    }
+
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.model.vocab.get(token).copied()
+    }
+
+    pub fn id_to_token(&self, id: u32) -> Option<String> {
+        self.model.vocab_r.get(&id).cloned()
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.model.vocab.len()
+    }
+
+    /// The special tokens that `decode` leaves out.
+    #[getter]
+    fn special_tokens(&self) -> SpecialTokens {
+        self.model.special_tokens.clone()
+    }
+}
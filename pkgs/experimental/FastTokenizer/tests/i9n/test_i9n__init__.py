@@ -1,6 +1,7 @@
 import pytest
 import logging
-from fasttokenizer import __init__
+
+import fasttokenizer
 
 # Set up logging
 logging.basicConfig(level=logging.DEBUG)
@@ -9,30 +10,24 @@ logger = logging.getLogger(__name__)
 
 @pytest.mark.i9n
 def test_i9n_init():
-    # Test that the Rust extension loads correctly
-    try:
-        __init__.rust_init()
-        logger.debug("Rust extension loaded successfully")
-        assert True
-    except Exception as e:
-        logger.error(f"Error loading Rust extension: {str(e)}")
-        assert False
+    # Test that the Rust extension loads and exports the default features
+    for name in ("Normalizer", "RegexTokenizer", "WhitespaceTokenizer", "TokenizerIO"):
+        assert hasattr(fasttokenizer, name), f"{name} is missing from fasttokenizer"
+    logger.debug("Rust extension loaded successfully")
 
 
 @pytest.mark.i9n
-def test_i9n_teardown():
-    # Test that the Rust extension unloads correctly
-    try:
-        __init__.rust_teardown()
-        logger.debug("Rust extension unloaded successfully")
-        assert True
-    except Exception as e:
-        logger.error(f"Error unloading Rust extension: {str(e)}")
-        assert False
+def test_i9n_pipeline():
+    # Test that the feature-gated pieces work together
+    pipeline = fasttokenizer.TokenizerPipeline(
+        fasttokenizer.RegexTokenizer(r"\w+"),
+        normalizer=fasttokenizer.Normalizer(lowercase=True),
+    )
+    assert pipeline.tokenize("Hello, World!") == ["hello", "world"]
 
 
 @pytest.mark.i9n
 def test_i9n_version():
     # Test that the version number is correctly retrieved
-    version = __init__.get_version()
+    version = fasttokenizer.__version__
     assert isinstance(version, str) and version != ""
@@ -0,0 +1,86 @@
+import pytest
+
+from fasttokenizer import Normalizer, TokenizerPipeline, WhitespaceTokenizer
+
+CONFIGS = [
+    dict(lowercase=True, collapse_whitespace=True, strip=True),
+    dict(form="NFKC", casefold=True, remove_punctuation=True),
+    dict(remove_control=True, normalize_spaces=True, collapse_whitespace=True),
+    dict(strip_html=True, collapse_whitespace=True, strip=True),
+    dict(strip_markdown=True, lowercase=True),
+]
+
+TEXTS = [
+    "  Hello,   WORLD!  ",
+    "Straße ﬁle ① ΟΔΟΣ",
+    "a\u200bb c\u3000d\r\n e",
+    "<p>Fish &amp; <b>chips</b></p><script>x()</script><br>caf&eacute; &lt;3",
+    "# Title\n\nSome *bold* and `code` \\* [link](http://x.org) <i>it</i>\n\n- item",
+]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("config", CONFIGS)
+@pytest.mark.parametrize("text", TEXTS)
+def test_alignment_matches_normalize(config, text):
+    normalizer = Normalizer(**config)
+    alignment = normalizer.normalize_with_alignment(text)
+    assert alignment.normalized == normalizer.normalize(text)
+    assert alignment.original == text
+    assert len(alignment) == len(alignment.normalized)
+    assert len(alignment.spans()) == len(alignment)
+
+
+@pytest.mark.unit
+def test_map_offsets_back_to_raw_text():
+    text = "  The   QUICK fox  "
+    normalizer = Normalizer(lowercase=True, normalize_spaces=True, collapse_whitespace=True, strip=True)
+    alignment = normalizer.normalize_with_alignment(text)
+    assert alignment.normalized == "the quick fox"
+    tokens = WhitespaceTokenizer().tokenize_with_offsets(alignment.normalized)
+    mapped = alignment.map_offsets(tokens)
+    assert [(t, text[s:e]) for t, s, e in mapped] == [
+        ("the", "The"),
+        ("quick", "QUICK"),
+        ("fox", "fox"),
+    ]
+
+
+@pytest.mark.unit
+def test_expanding_and_decoded_characters():
+    text = "Straße &amp; ﬁn"
+    alignment = Normalizer(strip_html=True, form="NFKC", casefold=True).normalize_with_alignment(text)
+    assert alignment.normalized == "strasse & fin"
+    # Both characters of the folded sharp s map to the sharp s.
+    assert alignment.to_original(4, 6) == (4, 5)
+    # The decoded entity maps to the whole entity.
+    assert alignment.to_original(8, 9) == (7, 12)
+    # The ligature expands to two characters of the same original span.
+    assert alignment.to_original(10, 12) == (13, 14)
+    assert alignment.to_original(10, 13) == (13, 15)
+
+
+@pytest.mark.unit
+def test_byte_unit_and_errors():
+    text = "café ÉTÉ"
+    alignment = Normalizer(lowercase=True).normalize_with_alignment(text)
+    assert alignment.to_original(6, 11, unit="byte") == (6, 11)
+    assert alignment.spans(unit="byte")[3] == (3, 5)
+    with pytest.raises(ValueError):
+        alignment.to_original(0, 99)
+    with pytest.raises(ValueError):
+        alignment.to_original(4, 5, unit="byte")
+    with pytest.raises(ValueError):
+        alignment.to_original(0, 1, unit="word")
+
+
+@pytest.mark.unit
+def test_pipeline_offsets_into_original_input():
+    text = "<p>Hello&nbsp;<b>WORLD</b></p>"
+    pipeline = TokenizerPipeline(
+        WhitespaceTokenizer(),
+        normalizer=Normalizer(strip_html=True, normalize_spaces=True, lowercase=True),
+    )
+    assert pipeline.tokenize_with_offsets(text) == [("hello", 0, 5), ("world", 6, 11)]
+    tokens = pipeline.tokenize_with_offsets(text, original=True)
+    assert [(t, text[s:e]) for t, s, e in tokens] == [("hello", "Hello"), ("world", "WORLD")]
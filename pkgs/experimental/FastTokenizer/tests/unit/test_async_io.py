@@ -0,0 +1,72 @@
+import asyncio
+import gzip
+
+import pytest
+
+from fasttokenizer import TokenizerIO
+
+LINES = [f"line {i} naïve café" for i in range(3000)]
+
+
+@pytest.fixture
+def corpus(tmp_path):
+    path = tmp_path / "corpus.txt.gz"
+    with gzip.open(path, "wt", encoding="utf-8") as f:
+        f.write("\r\n".join(LINES) + "\n")
+    return str(path)
+
+
+async def collect(lines):
+    return [line async for line in lines]
+
+
+async def read(path, **kwargs):
+    return await TokenizerIO().aread_text(path, **kwargs)
+
+
+@pytest.mark.unit
+class TestAsyncIO:
+    """Unit tests for reading files from asyncio without blocking the loop."""
+
+    def test_aread_text(self, corpus):
+        text = asyncio.run(read(corpus))
+        assert text == TokenizerIO().read_file(corpus)
+
+    def test_aiter_lines(self, corpus):
+        lines = asyncio.run(collect(TokenizerIO().aiter_lines(corpus)))
+        assert lines == LINES == list(TokenizerIO().iter_lines(corpus))
+
+    def test_concurrent_reads(self, corpus, tmp_path):
+        other = tmp_path / "other.txt"
+        other.write_text("a\nb\n", encoding="utf-8")
+        io = TokenizerIO()
+
+        async def main():
+            return await asyncio.gather(
+                collect(io.aiter_lines(corpus)),
+                collect(io.aiter_lines(str(other))),
+                io.aread_text(str(other)),
+            )
+
+        assert asyncio.run(main()) == [LINES, ["a", "b"], "a\nb\n"]
+
+    def test_errors_raise_when_awaited(self, tmp_path):
+        missing = str(tmp_path / "missing.txt")
+        with pytest.raises(IOError, match="Failed to open file"):
+            asyncio.run(read(missing))
+        with pytest.raises(IOError, match="Failed to open file"):
+            asyncio.run(collect(TokenizerIO().aiter_lines(missing)))
+        bad = tmp_path / "bad.txt"
+        bad.write_bytes(b"ok\n\xff\n")
+        with pytest.raises(IOError, match="invalid UTF-8 at byte 3"):
+            asyncio.run(collect(TokenizerIO().aiter_lines(str(bad))))
+        assert asyncio.run(collect(TokenizerIO().aiter_lines(str(bad), errors="replace"))) == [
+            "ok",
+            "�",
+        ]
+
+    def test_needs_running_loop(self, corpus):
+        with pytest.raises(RuntimeError):
+            TokenizerIO().aread_text(corpus)
+        with pytest.raises(ValueError):
+            TokenizerIO().aread_text(corpus, errors="loud")
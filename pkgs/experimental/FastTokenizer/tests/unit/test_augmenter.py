@@ -0,0 +1,76 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import Augmenter, WhitespaceTokenizer
+
+TOKENS = "the quick brown fox jumps over the lazy dog".split()
+
+
+@pytest.mark.unit
+class TestAugmenter:
+    """Unit tests for seeded token augmentation."""
+
+    def test_defaults_are_identity(self):
+        assert Augmenter().augment(TOKENS) == TOKENS
+        assert Augmenter().augment([]) == []
+
+    def test_seeded_and_reproducible(self):
+        augmenter = Augmenter(delete=0.2, swap=0.2, mask=0.2, seed=3)
+        first = augmenter.augment(TOKENS)
+        assert augmenter.augment(TOKENS) == first
+        assert augmenter.augment(TOKENS, seed=3) == first
+        assert any(augmenter.augment(TOKENS, seed=s) != first for s in range(4, 10))
+
+    def test_delete_keeps_a_token(self):
+        augmenter = Augmenter(delete=1.0)
+        for seed in range(20):
+            kept = augmenter.augment(TOKENS, seed=seed)
+            assert len(kept) == 1 and kept[0] in TOKENS
+        deleted = Augmenter(delete=0.5).augment(TOKENS * 20)
+        assert 40 < len(deleted) < 140
+
+    def test_swap_permutes(self):
+        swapped = Augmenter(swap=0.5, seed=1).augment(TOKENS)
+        assert sorted(swapped) == sorted(TOKENS) and swapped != TOKENS
+
+    def test_synonyms(self):
+        synonyms = {"quick": ["fast", "speedy"], "lazy": ["idle"], "dog": []}
+        out = Augmenter(synonyms=synonyms, substitute=1.0).augment(TOKENS)
+        assert out[1] in ("fast", "speedy") and out[7] == "idle" and out[8] == "dog"
+        assert out[0] == "the" and len(out) == len(TOKENS)
+
+    def test_span_masking(self):
+        out = Augmenter(mask=1.0, mask_token="<m>").augment(TOKENS)
+        assert out == ["<m>"] * len(TOKENS)
+        out = Augmenter(mask=0.2, max_span=3, seed=5).augment(TOKENS * 10)
+        assert len(out) == len(TOKENS) * 10
+        assert 0 < out.count("[MASK]") < len(out)
+        for token, original in zip(out, TOKENS * 10):
+            assert token in ("[MASK]", original)
+
+    def test_batch_matches_single_calls(self):
+        augmenter = Augmenter(delete=0.3, swap=0.3, mask=0.1, seed=11)
+        batch = [TOKENS, TOKENS[:3], []]
+        assert augmenter.augment_batch(batch) == [augmenter.augment(t, seed=11 + i) for i, t in enumerate(batch)]
+        assert augmenter.augment_batch(batch, seed=2)[1] == augmenter.augment(batch[1], seed=3)
+
+    def test_text(self):
+        augmenter = Augmenter(WhitespaceTokenizer(), synonyms={"quick": ["fast"]}, substitute=1.0)
+        assert augmenter.augment_text("the quick fox") == "the fast fox"
+        assert augmenter.augment_texts(["quick", "slow"]) == ["fast", "slow"]
+        with pytest.raises(ValueError):
+            Augmenter().augment_text("the quick fox")
+
+    def test_validation_and_pickle(self):
+        with pytest.raises(ValueError):
+            Augmenter(delete=1.5)
+        with pytest.raises(ValueError):
+            Augmenter(mask=-0.1)
+        with pytest.raises(ValueError):
+            Augmenter(max_span=0)
+        augmenter = Augmenter(WhitespaceTokenizer(), synonyms={"a": ["b"]}, substitute=0.5, swap=0.1, seed=9)
+        copy = pickle.loads(pickle.dumps(augmenter))
+        assert copy.augment(TOKENS * 3) == augmenter.augment(TOKENS * 3)
+        assert copy.augment_text("a a a a") == augmenter.augment_text("a a a a")
+        assert repr(copy) == "Augmenter(substitute=0.5, delete=0.0, swap=0.1, mask=0.0)"
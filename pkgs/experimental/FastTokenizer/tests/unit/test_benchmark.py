@@ -0,0 +1,73 @@
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    RegexTokenizer,
+    SentenceSplitter,
+    StopWordFilter,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+)
+
+TEXTS = ["The quick brown fox jumps over the lazy dog."] * 20
+
+KEYS = {
+    "iterations",
+    "bytes",
+    "tokens",
+    "seconds",
+    "tokens_per_sec",
+    "mb_per_sec",
+}
+# Only reported by builds with the `alloc-stats` Cargo feature.
+ALLOCATION_KEYS = {"allocations", "allocated_bytes"}
+
+
+@pytest.mark.unit
+class TestBenchmark:
+    """Unit tests for the benchmark method of tokenizers."""
+
+    def test_stats(self):
+        stats = WhitespaceTokenizer().benchmark(TEXTS, iterations=3)
+        assert set(stats) - ALLOCATION_KEYS == KEYS
+        assert stats["iterations"] == 3
+        assert stats["bytes"] == sum(len(t) for t in TEXTS)
+        assert stats["tokens"] == 9 * len(TEXTS)
+        assert stats["seconds"] > 0
+        assert stats["tokens_per_sec"] > 0 and stats["mb_per_sec"] > 0
+        if ALLOCATION_KEYS <= set(stats):
+            # Every call returns a freshly allocated token list.
+            assert stats["allocations"] >= len(TEXTS)
+            assert stats["allocated_bytes"] > 0
+        else:
+            assert not ALLOCATION_KEYS & set(stats)
+
+    def test_other_tokenizers(self):
+        assert RegexTokenizer(r"\w+").benchmark(TEXTS, 1)["tokens"] == 9 * len(TEXTS)
+        assert SentenceSplitter().benchmark(TEXTS, 1)["tokens"] == len(TEXTS)
+
+    def test_pipeline_stages(self):
+        pipeline = TokenizerPipeline(
+            WhitespaceTokenizer(),
+            normalizer=Normalizer(lowercase=True),
+            pre_tokenizer=RegexTokenizer(r"\S+"),
+            filters=[StopWordFilter(words=["the"])],
+        )
+        stats = pipeline.benchmark(TEXTS, iterations=2)
+        assert stats["tokens"] == 7 * len(TEXTS)
+        assert list(stats["stages"]) == ["normalizer", "pre_tokenizer", "tokenizer", "filters"]
+        assert all(seconds >= 0 for seconds in stats["stages"].values())
+        assert sum(stats["stages"].values()) <= stats["seconds"]
+        bare = TokenizerPipeline(WhitespaceTokenizer()).benchmark(TEXTS, 1)
+        assert list(bare["stages"]) == ["tokenizer"]
+
+    def test_pipeline_stages_include_special_tokens(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), normalizer=Normalizer(lowercase=True))
+        pipeline.add_special_tokens(["<mask>"])
+        stats = pipeline.benchmark(["The <mask> fox."] * 5, iterations=2)
+        assert stats["tokens"] == 3 * 5
+        assert list(stats["stages"]) == ["special_tokens", "normalizer", "tokenizer"]
+
+    def test_invalid_iterations(self):
+        with pytest.raises(ValueError):
+            WhitespaceTokenizer().benchmark(TEXTS, iterations=0)
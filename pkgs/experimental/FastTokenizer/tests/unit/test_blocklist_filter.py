@@ -0,0 +1,73 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import BlocklistFilter, TokenizerPipeline, WhitespaceTokenizer
+
+
+@pytest.mark.unit
+class TestBlocklistFilter:
+    """Unit tests for blocklist flagging and masking."""
+
+    def test_find_reports_original_offsets(self):
+        blocklist = BlocklistFilter(["darn", "heck"])
+        text = "Well, DARN it — what the h3ck?"
+        assert blocklist.find(text) == [("DARN", 6, 10, "darn"), ("h3ck", 25, 29, "heck")]
+        assert blocklist.find(text, unit="byte")[1][1:3] == (27, 31)
+        assert blocklist.is_flagged(text)
+        assert not blocklist.is_flagged("Nothing to see here.")
+        with pytest.raises(ValueError):
+            blocklist.find(text, unit="word")
+
+    @pytest.mark.parametrize(
+        "text",
+        ["darn", "D4RN", "dárn", "daaaarn", "d@rn", "DÁRN!"],
+    )
+    def test_normalization_catches_spelling_tricks(self, text):
+        assert BlocklistFilter(["darn"]).is_flagged(text)
+
+    def test_without_normalization(self):
+        blocklist = BlocklistFilter(["darn"], normalize=False)
+        assert blocklist.is_flagged("DARN")
+        assert not blocklist.is_flagged("d4rn")
+        assert not blocklist.is_flagged("dárn")
+
+    def test_whole_words_and_phrases(self):
+        blocklist = BlocklistFilter(["ass", "bad   word"])
+        assert not blocklist.is_flagged("a classic assignment")
+        assert blocklist.find("what a Bad\nWord") == [("Bad\nWord", 7, 15, "bad   word")]
+        assert BlocklistFilter(["ass"], whole_words=False).is_flagged("classic")
+
+    def test_labels(self):
+        labeled = BlocklistFilter({"darn": "mild", "scum": "insult"})
+        assert [hit[3] for hit in labeled.find("scum, darn")] == ["insult", "mild"]
+        listed = BlocklistFilter(["darn", "scum"], labels=["mild", "insult"])
+        assert listed.find("scum, darn") == labeled.find("scum, darn")
+        with pytest.raises(ValueError):
+            BlocklistFilter(["darn"], labels=["a", "b"])
+        with pytest.raises(ValueError):
+            BlocklistFilter({"darn": "mild"}, labels=["mild"])
+        with pytest.raises(ValueError):
+            BlocklistFilter(["darn", " "])
+
+    def test_mask(self):
+        blocklist = BlocklistFilter(["darn", "bad word"])
+        assert blocklist.mask_text("Oh d4rn, a BAD word.") == "Oh ****, a *** ****."
+        assert blocklist.mask_text("Oh d4rn!", replacement="[censored]") == "Oh [censored]!"
+        texts = ["darn", "fine", "daaarn it"]
+        assert blocklist.mask_batch(texts, replacement="#") == ["####", "fine", "###### it"]
+
+    def test_filter_tokens_and_pipeline(self):
+        blocklist = BlocklistFilter(["darn", "bad word"])
+        assert blocklist.filter(["oh", "D4RN", "bad", "word"]) == ["oh", "bad", "word"]
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), filters=[blocklist])
+        assert pipeline.tokenize("oh darn it") == ["oh", "it"]
+        restored = TokenizerPipeline.from_json(pipeline.to_json())
+        assert restored.tokenize("oh darn it") == ["oh", "it"]
+
+    def test_pickle(self):
+        blocklist = BlocklistFilter({"darn": "mild"}, whole_words=False)
+        copy = pickle.loads(pickle.dumps(blocklist))
+        assert copy.find("darnit") == blocklist.find("darnit") == [("darn", 0, 4, "mild")]
+        assert len(copy) == 1
+        assert repr(copy) == "BlocklistFilter(words=1)"
@@ -0,0 +1,83 @@
+import math
+
+import pytest
+
+from fasttokenizer import Bm25Index, Normalizer, RegexTokenizer, TokenizerPipeline, WhitespaceTokenizer
+
+DOCUMENTS = [
+    ("cats", "the cat sat on the mat"),
+    ("dogs", "the dog chased the cat"),
+    ("birds", "birds sing in the morning"),
+]
+
+
+def bm25(tf, df, n, length, average, k1=1.5, b=0.75):
+    idf = math.log(1 + (n - df + 0.5) / (df + 0.5))
+    return idf * tf * (k1 + 1) / (tf + k1 * (1 - b + b * length / average))
+
+
+@pytest.mark.unit
+class TestBm25Index:
+    """Unit tests for the in-memory BM25 index."""
+
+    def index(self, **kwargs):
+        index = Bm25Index(WhitespaceTokenizer(), **kwargs)
+        for key, text in DOCUMENTS:
+            index.add_document(key, text)
+        return index
+
+    def test_scores(self):
+        index = self.index()
+        average = 16 / 3
+        results = index.search("cat")
+        assert [key for key, _ in results] == ["dogs", "cats"]
+        assert results[0][1] == pytest.approx(bm25(1, 2, 3, 5, average))
+        assert results[1][1] == pytest.approx(bm25(1, 2, 3, 6, average))
+        assert index.average_length == pytest.approx(average)
+
+    def test_rare_terms_weigh_more(self):
+        index = self.index()
+        assert [key for key, _ in index.search("sat the", k=1)] == ["cats"]
+        assert index.search("the")[0][1] < index.search("mat")[0][1]
+        assert index.search("unicorn") == []
+        assert index.search("cat", k=0) == []
+
+    def test_parameters(self):
+        flat = self.index(k1=0.0)
+        assert flat.search("the")[0][1] == pytest.approx(flat.search("the")[-1][1])
+        assert (flat.k1, flat.b) == (0.0, 0.75)
+        with pytest.raises(ValueError):
+            Bm25Index(WhitespaceTokenizer(), k1=-1)
+        with pytest.raises(ValueError):
+            Bm25Index(WhitespaceTokenizer(), b=1.5)
+
+    def test_pipeline(self):
+        pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), normalizer=Normalizer(lowercase=True))
+        index = Bm25Index(pipeline)
+        index.add_document("a", "Rust, Python and Go.")
+        assert index.search("PYTHON")[0][0] == "a"
+
+    def test_add_documents_matches_add_document(self):
+        index = Bm25Index(WhitespaceTokenizer())
+        index.add_documents(DOCUMENTS)
+        assert index.search_batch(["cat", "morning"]) == [self.index().search("cat"), self.index().search("morning")]
+        with pytest.raises(KeyError):
+            index.add_documents([("new", "a"), ("cats", "b")])
+        with pytest.raises(KeyError):
+            index.add_documents([("x", "a"), ("x", "b")])
+        assert len(index) == 3
+
+    def test_remove(self):
+        index = self.index()
+        index.remove("cats")
+        assert "cats" not in index and len(index) == 2
+        assert index.keys() == ["dogs", "birds"]
+        fresh = Bm25Index(WhitespaceTokenizer())
+        fresh.add_documents(DOCUMENTS[1:])
+        assert index.search("the cat") == fresh.search("the cat")
+        with pytest.raises(KeyError):
+            index.remove("cats")
+        with pytest.raises(KeyError):
+            index.add_document("dogs", "again")
+        index.add_document("cats", "a cat again")
+        assert index.search("cat", k=1)[0][0] == "cats"
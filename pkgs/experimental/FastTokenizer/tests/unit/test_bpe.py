@@ -0,0 +1,104 @@
+import json
+
+import pytest
+
+from fasttokenizer import BpeTokenizer
+
+from conftest import bytes_to_unicode
+
+
+MERGES = [("h", "e"), ("l", "l"), ("he", "ll"), ("hell", "o"), ("Ġ", "w"), ("Ġ", "b")]
+
+
+@pytest.fixture
+def vocab():
+    tokens = list(bytes_to_unicode().values())
+    tokens += [a + b for a, b in MERGES]
+    return {token: i for i, token in enumerate(tokens)}
+
+
+@pytest.fixture
+def tokenizer(vocab):
+    return BpeTokenizer(vocab, MERGES)
+
+
+@pytest.mark.unit
+class TestBpeTokenizer:
+    """Unit tests for the byte-level BpeTokenizer."""
+
+    def test_tokenize(self, tokenizer):
+        assert tokenizer.tokenize("hello world") == ["hello", "Ġw", "o", "r", "l", "d"]
+
+    def test_encode_decode_roundtrip(self, tokenizer):
+        text = "hello wörld 🎉\n\tdone"
+        ids = tokenizer.encode(text)
+        assert tokenizer.decode(ids) == text
+
+    def test_detokenize(self, tokenizer):
+        tokens = tokenizer.tokenize("hello wörld")
+        assert tokenizer.detokenize(tokens) == "hello wörld"
+
+    def test_encode_matches_vocab(self, tokenizer, vocab):
+        assert tokenizer.encode("hello") == [vocab["hello"]]
+        assert tokenizer.token_to_id("Ġw") == vocab["Ġw"]
+        assert tokenizer.id_to_token(vocab["Ġw"]) == "Ġw"
+        assert tokenizer.vocab_size() == len(vocab)
+
+    def test_whitespace_lookahead(self, tokenizer):
+        """The last space of a run is attached to the following word."""
+        assert tokenizer.tokenize("a  b") == ["a", "Ġ", "Ġb"]
+        assert tokenizer.tokenize("a  ") == ["a", "Ġ", "Ġ"]
+
+    def test_contractions(self, tokenizer):
+        assert tokenizer.tokenize("he's") == ["he", "'", "s"]
+
+    def test_add_prefix_space(self, vocab):
+        tokenizer = BpeTokenizer(vocab, MERGES, add_prefix_space=True)
+        assert tokenizer.tokenize("world") == ["Ġw", "o", "r", "l", "d"]
+        assert tokenizer.tokenize_with_offsets("world")[0] == ("Ġw", 0, 1)
+
+    def test_offsets(self, tokenizer):
+        text = "hello world"
+        tokens = tokenizer.tokenize_with_offsets(text)
+        assert tokens[0] == ("hello", 0, 5)
+        assert tokens[1] == ("Ġw", 5, 7)
+
+    def test_encode_batch(self, tokenizer):
+        assert tokenizer.encode_batch(["hello", "hello"]) == [
+            tokenizer.encode("hello")
+        ] * 2
+
+    def test_from_files(self, tmp_path, vocab, tokenizer):
+        vocab_path = tmp_path / "vocab.json"
+        merges_path = tmp_path / "merges.txt"
+        vocab_path.write_text(json.dumps(vocab), encoding="utf-8")
+        merges_path.write_text(
+            "#version: 0.2\n" + "\n".join(f"{a} {b}" for a, b in MERGES) + "\n",
+            encoding="utf-8",
+        )
+        loaded = BpeTokenizer.from_files(str(vocab_path), str(merges_path))
+        assert loaded.encode("hello world") == tokenizer.encode("hello world")
+
+    def test_invalid_merge(self, vocab):
+        with pytest.raises(ValueError):
+            BpeTokenizer(vocab, [("x", "y")])
+
+    def test_dropout(self, tokenizer, vocab):
+        text = "hello hello world"
+        plain = tokenizer.encode(text)
+        assert tokenizer.encode(text, dropout=0.0) == plain
+        chars = tokenizer.encode(text, dropout=1.0)
+        assert chars == [vocab[c] for c in "hello" + "Ġhello" + "Ġworld"]
+        samples = {tuple(tokenizer.encode(text, dropout=0.5, seed=s)) for s in range(30)}
+        assert len(samples) > 3
+        for ids in samples:
+            assert tokenizer.decode(list(ids)) == text
+        assert tokenizer.encode(text, dropout=0.5, seed=4) == tokenizer.encode(text, dropout=0.5, seed=4)
+
+    def test_dropout_batch(self, tokenizer):
+        texts = ["hello world", "hello", ""]
+        batch = tokenizer.encode_batch(texts, dropout=0.3, seed=10)
+        assert batch == [tokenizer.encode(t, dropout=0.3, seed=10 + i) for i, t in enumerate(texts)]
+        assert tokenizer.encode_batch(texts) == [tokenizer.encode(t) for t in texts]
+        with pytest.raises(ValueError):
+            tokenizer.encode("hello", dropout=1.5)
@@ -0,0 +1,194 @@
+import json
+
+import pytest
+
+from fasttokenizer import (
+    BpeTokenizer,
+    Normalizer,
+    RegexTokenizer,
+    SentencePieceTokenizer,
+    SpecialTokens,
+    TiktokenEncoder,
+    Tokenizer,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+TEXT = "Hello World, hello again"
+
+
+@pytest.fixture
+def bpe():
+    tokens = ["h", "e", "l", "o", "w", "r", "d", "he", "ll", "hell", "hello", "[UNK]"]
+    merges = [("h", "e"), ("l", "l"), ("he", "ll"), ("hell", "o")]
+    return BpeTokenizer(
+        {token: i for i, token in enumerate(tokens)},
+        merges,
+        byte_level=False,
+        unk_token="[UNK]",
+    )
+
+
+@pytest.fixture
+def wordpiece():
+    tokens = ["[UNK]", "hello", "world", "again", "##s", ",", "Hello"]
+    return WordPieceTokenizer(
+        {token: i for i, token in enumerate(tokens)},
+        lowercase=False,
+        strip_accents=True,
+        tokenize_chinese_chars=False,
+    )
+
+
+@pytest.fixture
+def sentencepiece():
+    pieces = [("<unk>", 0.0), ("<s>", 0.0), ("</s>", 0.0)]
+    pieces += [(c, -5.0) for c in "▁HWadeghilnorW,"] + [("▁hello", -1.0), ("▁again", -1.5)]
+    return SentencePieceTokenizer(pieces, model_type="bpe", bos_id=1, eos_id=2)
+
+
+@pytest.fixture
+def tiktoken():
+    ranks = {bytes([b]): b for b in range(256)}
+    for token in [b"he", b"ll", b"hello", b" hello", b" again"]:
+        ranks[token] = len(ranks)
+    return TiktokenEncoder(ranks, r"\w+| ?[^\s\w]+|\s+", {"<|end|>": 1000}, name="tiny")
+
+
+@pytest.fixture
+def hf():
+    vocab = {"[UNK]": 0, "hello": 1, "world": 2, "again": 3, ",": 4}
+    return Tokenizer.from_str(
+        json.dumps(
+            {
+                "normalizer": {"type": "Lowercase"},
+                "pre_tokenizer": {"type": "BertPreTokenizer"},
+                "model": {"type": "WordLevel", "vocab": vocab, "unk_token": "[UNK]"},
+            }
+        )
+    )
+
+
+@pytest.mark.unit
+class TestBundles:
+    """Unit tests for saving and loading single-file tokenizer bundles."""
+
+    def test_bpe_roundtrip(self, tmp_path, bpe):
+        path = tmp_path / "tokenizer.json"
+        bpe.save(str(path))
+        restored = BpeTokenizer.load(str(path))
+        assert restored.tokenize("hello world") == bpe.tokenize("hello world")
+        assert restored.encode("hello") == bpe.encode("hello")
+        assert restored.vocab_size() == bpe.vocab_size()
+
+    def test_wordpiece_roundtrip(self, tmp_path, wordpiece):
+        path = tmp_path / "tokenizer.json"
+        wordpiece.save(str(path))
+        restored = WordPieceTokenizer.load(str(path))
+        assert restored.tokenize(TEXT) == wordpiece.tokenize(TEXT) == ["Hello", "[UNK]", ",", "hello", "again"]
+        assert restored.encode(TEXT) == wordpiece.encode(TEXT)
+
+    def test_sentencepiece_roundtrip(self, tmp_path, sentencepiece):
+        path = tmp_path / "tokenizer.json"
+        sentencepiece.save(str(path))
+        restored = SentencePieceTokenizer.load(str(path))
+        assert restored.model_type == "bpe"
+        assert (restored.bos_id, restored.eos_id) == (1, 2)
+        assert restored.tokenize(TEXT) == sentencepiece.tokenize(TEXT)
+        ids = sentencepiece.encode(TEXT, add_bos=True, add_eos=True)
+        assert restored.encode(TEXT, add_bos=True, add_eos=True) == ids
+        assert restored.decode(ids) == sentencepiece.decode(ids)
+
+    def test_tiktoken_roundtrip(self, tmp_path, tiktoken):
+        path = tmp_path / "tokenizer.json"
+        tiktoken.save(str(path))
+        restored = TiktokenEncoder.load(str(path))
+        assert restored.name == "tiny"
+        text = TEXT + "<|end|>"
+        assert restored.encode(text, allowed_special="all") == tiktoken.encode(text, allowed_special="all")
+        assert restored.n_vocab == tiktoken.n_vocab
+
+    def test_hf_tokenizer_roundtrip(self, tmp_path, hf):
+        path = tmp_path / "tokenizer.json"
+        hf.save(str(path))
+        restored = Tokenizer.load(str(path))
+        assert isinstance(restored, Tokenizer)
+        assert restored.tokenize(TEXT) == hf.tokenize(TEXT) == ["hello", "world", ",", "hello", "again"]
+        assert restored.encode(TEXT) == hf.encode(TEXT)
+
+    @pytest.mark.parametrize("name", ["bpe", "wordpiece", "sentencepiece", "tiktoken", "hf"])
+    def test_tokenizer_load_returns_the_saved_class(
+        self, tmp_path, name, bpe, wordpiece, sentencepiece, tiktoken, hf
+    ):
+        tokenizer = {
+            "bpe": bpe,
+            "wordpiece": wordpiece,
+            "sentencepiece": sentencepiece,
+            "tiktoken": tiktoken,
+            "hf": hf,
+        }[name]
+        path = tmp_path / "tokenizer.json"
+        tokenizer.save(str(path))
+        restored = Tokenizer.load(str(path))
+        assert type(restored) is type(tokenizer)
+        assert restored.tokenize(TEXT) == tokenizer.tokenize(TEXT)
+
+    def test_tokenizer_load_of_a_pipeline(self, tmp_path, wordpiece):
+        pipeline = TokenizerPipeline(wordpiece, normalizer=Normalizer(lowercase=True))
+        path = tmp_path / "pipeline.json"
+        pipeline.save(str(path))
+        restored = Tokenizer.load(str(path))
+        assert isinstance(restored, TokenizerPipeline)
+        assert restored.tokenize(TEXT) == pipeline.tokenize(TEXT)
+
+    def test_pipeline_roundtrip(self, tmp_path, bpe):
+        pipeline = TokenizerPipeline(
+            bpe,
+            normalizer=Normalizer(lowercase=True),
+            pre_tokenizer=RegexTokenizer(r"\w+"),
+            vocab=Vocab(unk_token="[UNK]"),
+            special_tokens=SpecialTokens(cls="[CLS]", sep="[SEP]", single="[CLS] $A [SEP]"),
+        )
+        ids = pipeline.encode(TEXT)
+        path = tmp_path / "pipeline.json"
+        pipeline.save(str(path))
+        restored = TokenizerPipeline.load(str(path))
+        assert restored.tokenize(TEXT) == pipeline.tokenize(TEXT)
+        assert restored.encode(TEXT) == ids
+
+    def test_header(self, tmp_path):
+        path = tmp_path / "tokenizer.json"
+        TokenizerPipeline(WhitespaceTokenizer()).save(str(path))
+        bundle = json.loads(path.read_text())
+        assert bundle["format"] == "fasttokenizer"
+        assert bundle["version"] == 1
+        assert bundle["tokenizer"]["type"] == "TokenizerPipeline"
+
+    def test_type_mismatch(self, tmp_path):
+        path = tmp_path / "tokenizer.json"
+        TokenizerPipeline(WhitespaceTokenizer()).save(str(path))
+        with pytest.raises(ValueError, match="Expected a BpeTokenizer"):
+            BpeTokenizer.load(str(path))
+
+    def test_newer_version(self, tmp_path):
+        path = tmp_path / "tokenizer.json"
+        TokenizerPipeline(WhitespaceTokenizer()).save(str(path))
+        bundle = json.loads(path.read_text())
+        bundle["version"] = 99
+        path.write_text(json.dumps(bundle))
+        with pytest.raises(ValueError, match="Unsupported bundle version 99"):
+            TokenizerPipeline.load(str(path))
+
+    def test_not_a_bundle(self, tmp_path):
+        path = tmp_path / "vocab.json"
+        path.write_text('{"a": 0}')
+        with pytest.raises(ValueError, match="is not a fasttokenizer bundle"):
+            TokenizerPipeline.load(str(path))
+
+    def test_missing_file(self, tmp_path):
+        with pytest.raises(IOError, match="Failed to read bundle"):
+            TokenizerPipeline.load(str(tmp_path / "missing.json"))
+        with pytest.raises(IOError, match="Failed to read bundle"):
+            Tokenizer.load(str(tmp_path / "missing.json"))
@@ -0,0 +1,66 @@
+import mmap
+
+import pytest
+
+from fasttokenizer import (
+    BpeTokenizer,
+    RegexTokenizer,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+TEXT = "naïve café au lait"
+
+
+@pytest.mark.unit
+class TestBytesInput:
+    """Unit tests for passing bytes-like objects to tokenize and encode."""
+
+    @pytest.mark.parametrize(
+        "data",
+        [
+            TEXT.encode("utf-8"),
+            bytearray(TEXT.encode("utf-8")),
+            memoryview(TEXT.encode("utf-8")),
+        ],
+    )
+    def test_tokenize(self, data):
+        tokenizer = WhitespaceTokenizer()
+        assert tokenizer.tokenize(data) == tokenizer.tokenize(TEXT)
+        assert RegexTokenizer(r"\w+").tokenize(data) == ["naïve", "café", "au", "lait"]
+
+    def test_memoryview_slice(self):
+        data = memoryview(b"xx " + TEXT.encode("utf-8"))[3:]
+        assert WhitespaceTokenizer().tokenize(data) == TEXT.split()
+
+    def test_mmap(self, tmp_path):
+        path = tmp_path / "text.txt"
+        path.write_bytes(TEXT.encode("utf-8"))
+        with open(path, "rb") as f, mmap.mmap(f.fileno(), 0, access=mmap.ACCESS_READ) as data:
+            assert WhitespaceTokenizer().tokenize(data) == TEXT.split()
+
+    def test_invalid_utf8(self):
+        tokenizer = WhitespaceTokenizer()
+        with pytest.raises(UnicodeDecodeError):
+            tokenizer.tokenize(b"bad \xff byte")
+        assert tokenizer.tokenize(b"bad \xff byte", errors="replace") == ["bad", "�", "byte"]
+        assert tokenizer.tokenize(memoryview(b"bad \xff byte"), errors="ignore") == ["bad", "byte"]
+        with pytest.raises(ValueError):
+            tokenizer.tokenize(b"text", errors="backslashreplace")
+
+    def test_rejects_other_types(self):
+        with pytest.raises(TypeError):
+            WhitespaceTokenizer().tokenize(42)
+
+    def test_encode(self):
+        tokens = ["[UNK]", "[CLS]", "[SEP]", "hello", "world"]
+        wordpiece = WordPieceTokenizer({token: i for i, token in enumerate(tokens)})
+        assert wordpiece.encode(b"hello world") == wordpiece.encode("hello world") == [3, 4]
+        bpe = BpeTokenizer({"a": 0, "b": 1, "ab": 2}, [("a", "b")])
+        assert bpe.encode(bytearray(b"ab")) == [2]
+
+    def test_pipeline_pair(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), vocab=Vocab(["hello", "world"]))
+        assert pipeline.encode(b"hello", pair=memoryview(b"world")) == [0, 1]
@@ -0,0 +1,55 @@
+import pytest
+
+from fasttokenizer import CjkTokenizer, TokenizerPipeline, Vocab
+
+
+@pytest.mark.unit
+def test_characters_without_dictionary():
+    tokenizer = CjkTokenizer()
+    assert tokenizer.tokenize("我爱北京 and Tokyo!") == [
+        "我", "爱", "北", "京", "and", "Tokyo", "!",
+    ]
+
+
+@pytest.mark.unit
+def test_japanese_kana_and_punctuation():
+    tokenizer = CjkTokenizer()
+    assert tokenizer.tokenize("東京へ行く。GPU2台") == [
+        "東", "京", "へ", "行", "く", "。", "GPU2", "台",
+    ]
+
+
+@pytest.mark.unit
+def test_dictionary_longest_match():
+    tokenizer = CjkTokenizer(dictionary=["北京", "北京大学", "大学生", "学生"])
+    assert tokenizer.tokenize("北京大学生活") == ["北京大学", "生", "活"]
+    tokenizer.add_words(["生活"])
+    assert tokenizer.tokenize("北京大学生活") == ["北京大学", "生活"]
+    assert tokenizer.dictionary_size == 5
+
+
+@pytest.mark.unit
+def test_dictionary_file(tmp_path):
+    path = tmp_path / "dict.txt"
+    path.write_text("自然语言 120 n\n处理 80 v\n\n", encoding="utf-8")
+    tokenizer = CjkTokenizer.from_dictionary_file(str(path))
+    assert tokenizer.tokenize("自然语言处理") == ["自然语言", "处理"]
+    with pytest.raises(IOError):
+        CjkTokenizer.from_dictionary_file(str(tmp_path / "missing.txt"))
+
+
+@pytest.mark.unit
+def test_offsets():
+    text = "café 北京!"
+    tokenizer = CjkTokenizer(dictionary=["北京"])
+    spans = tokenizer.tokenize_with_offsets(text)
+    assert spans == [("café", 0, 4), ("北京", 5, 7), ("!", 7, 8)]
+
+
+@pytest.mark.unit
+def test_encode_decode_and_pipeline():
+    tokenizer = CjkTokenizer(vocab=Vocab())
+    ids = tokenizer.encode("你好 hello world")
+    assert tokenizer.decode(ids) == "你好hello world"
+    pipeline = TokenizerPipeline(CjkTokenizer())
+    assert pipeline.tokenize("中文 text") == ["中", "文", "text"]
@@ -0,0 +1,81 @@
+import pytest
+
+from fasttokenizer import CodeTokenizer, TokenizerPipeline
+
+
+@pytest.mark.unit
+def test_member_call_is_split():
+    assert CodeTokenizer().tokenize("foo.bar(baz)") == ["foo", ".", "bar", "(", "baz", ")"]
+
+
+@pytest.mark.unit
+def test_types_for_c_like_code():
+    code = 'if (x >= 0x1F && name != "a \\"b\\"") { y += 3.5e-2; } // done'
+    assert CodeTokenizer(language="c").tokenize_with_types(code) == [
+        ("if", "identifier"),
+        ("(", "punct"),
+        ("x", "identifier"),
+        (">=", "operator"),
+        ("0x1F", "number"),
+        ("&&", "operator"),
+        ("name", "identifier"),
+        ("!=", "operator"),
+        ('"a \\"b\\""', "string"),
+        (")", "punct"),
+        ("{", "punct"),
+        ("y", "identifier"),
+        ("+=", "operator"),
+        ("3.5e-2", "number"),
+        (";", "punct"),
+        ("}", "punct"),
+        ("// done", "comment"),
+    ]
+
+
+@pytest.mark.unit
+def test_python_strings_and_comments():
+    code = 'x = f"{a}" # note\ndoc = """multi\nline"""\ny = a // 2'
+    tokens = CodeTokenizer(language="python").tokenize_with_types(code)
+    assert ('f"{a}"', "string") in tokens
+    assert ("# note", "comment") in tokens
+    assert ('"""multi\nline"""', "string") in tokens
+    assert ("//", "operator") in tokens
+
+
+@pytest.mark.unit
+def test_rust_lifetimes_and_chars():
+    code = "fn f<'a>(s: &'a str) -> char { 'x' }"
+    tokens = CodeTokenizer(language="rust").tokenize_with_types(code)
+    assert tokens.count(("'a", "identifier")) == 2
+    assert ("'x'", "string") in tokens
+    assert ("->", "operator") in tokens
+
+
+@pytest.mark.unit
+def test_split_identifiers():
+    tokenizer = CodeTokenizer(split_identifiers=True)
+    assert tokenizer.tokenize("parseHTTPResponse2(max_len)") == [
+        "parse", "HTTP", "Response", "2", "(", "max", "len", ")",
+    ]
+
+
+@pytest.mark.unit
+def test_dropping_comments_and_strings():
+    tokenizer = CodeTokenizer(language="sql", keep_comments=False, keep_strings=False)
+    assert tokenizer.tokenize("SELECT 'x' -- comment\nFROM t /* c */") == ["SELECT", "FROM", "t"]
+
+
+@pytest.mark.unit
+def test_unknown_language():
+    with pytest.raises(ValueError):
+        CodeTokenizer(language="brainfuck")
+    assert CodeTokenizer(language="TypeScript").language == "c"
+
+
+@pytest.mark.unit
+def test_offsets_and_pipeline():
+    code = "naïve = 'é'  # ok"
+    tokenizer = CodeTokenizer()
+    for token, start, end in tokenizer.tokenize_with_offsets(code):
+        assert code[start:end] == token
+    assert TokenizerPipeline(tokenizer).tokenize("a.b") == ["a", ".", "b"]
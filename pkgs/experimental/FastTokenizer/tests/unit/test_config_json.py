@@ -0,0 +1,126 @@
+import json
+
+import pytest
+
+from fasttokenizer import (
+    BpeTokenizer,
+    CjkTokenizer,
+    CodeTokenizer,
+    GraphemeTokenizer,
+    LengthFilter,
+    Normalizer,
+    NumericFilter,
+    RegexFilter,
+    RegexTokenizer,
+    SentenceSplitter,
+    SocialTokenizer,
+    SpecialTokens,
+    StopWordFilter,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+TEXT = "The Quick  brown fox, 42 jumps!\nOver the lazy dog. e.g. Привет"
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "tokenizer",
+    [
+        RegexTokenizer([r"\w+", r"[^\w\s]"], mode="find", case_insensitive=True),
+        RegexTokenizer(r"\s+", mode="split"),
+        WhitespaceTokenizer(preserve_delimiters=True, keep_newlines=True),
+        GraphemeTokenizer(skip_whitespace=True),
+        SocialTokenizer(),
+        CodeTokenizer("python"),
+    ],
+)
+def test_tokenizer_roundtrip(tokenizer):
+    restored = type(tokenizer).from_json(tokenizer.to_json())
+    assert restored.tokenize(TEXT) == tokenizer.tokenize(TEXT)
+    assert restored.to_json() == tokenizer.to_json()
+
+
+@pytest.mark.unit
+def test_sentence_splitter_roundtrip():
+    splitter = SentenceSplitter(abbreviations=["approx"], use_default_abbreviations=False)
+    restored = SentenceSplitter.from_json(splitter.to_json())
+    assert restored.split(TEXT) == splitter.split(TEXT)
+
+
+@pytest.mark.unit
+def test_pipeline_roundtrip():
+    pipeline = TokenizerPipeline(
+        RegexTokenizer(r"\w+"),
+        normalizer=Normalizer(lowercase=True, allowed_scripts=["Latin"], script_replacement=" "),
+        vocab=Vocab(unk_token="[UNK]"),
+        special_tokens=SpecialTokens(cls="[CLS]", sep="[SEP]", single="[CLS] $A [SEP]"),
+        filters=[
+            LengthFilter(min_length=2),
+            NumericFilter(),
+            RegexFilter(r"^x", mode="drop"),
+            StopWordFilter(words=["the"]),
+        ],
+    )
+    ids = pipeline.encode(TEXT)
+    restored = TokenizerPipeline.from_json(pipeline.to_json())
+    assert restored.tokenize(TEXT) == pipeline.tokenize(TEXT)
+    assert restored.encode(TEXT) == ids
+    assert restored.to_json() == pipeline.to_json()
+
+
+@pytest.mark.unit
+def test_json_is_readable():
+    config = json.loads(TokenizerPipeline(RegexTokenizer(r"\d+"), vocab=Vocab(["a", "b"])).to_json())
+    assert config["type"] == "TokenizerPipeline"
+    assert config["tokenizer"]["pattern"] == [r"\d+"]
+    assert config["vocab"]["vocab"]["vocab"] == {"a": 0, "b": 1}
+
+
+@pytest.mark.unit
+def test_type_mismatch():
+    with pytest.raises(ValueError, match="Expected a RegexTokenizer"):
+        RegexTokenizer.from_json(WhitespaceTokenizer().to_json())
+
+
+@pytest.mark.unit
+def test_unknown_type():
+    with pytest.raises(ValueError, match="Unknown type"):
+        TokenizerPipeline.from_json('{"type": "Popen"}')
+
+
+@pytest.mark.unit
+def test_invalid_json():
+    with pytest.raises(ValueError, match="Invalid tokenizer JSON"):
+        RegexTokenizer.from_json("{")
+
+
+@pytest.mark.unit
+def test_unsupported_tokenizer():
+    pipeline = TokenizerPipeline(CjkTokenizer())
+    with pytest.raises(TypeError, match="CjkTokenizer cannot be serialized"):
+        pipeline.to_json()
+
+
+@pytest.mark.unit
+def test_bpe_roundtrip():
+    vocab = {"a": 0, "b": 1, "ab": 2, "<unk>": 3}
+    tokenizer = BpeTokenizer(vocab, [("a", "b")], byte_level=False, unk_token="<unk>")
+    pipeline = TokenizerPipeline(tokenizer, normalizer=Normalizer(lowercase=True))
+    config = json.loads(pipeline.to_json())
+    assert config["tokenizer"]["merges"] == [["a", "b"]]
+    restored = TokenizerPipeline.from_json(pipeline.to_json())
+    assert restored.tokenize("AB abc") == pipeline.tokenize("AB abc") == ["ab", "ab", "<unk>"]
+
+
+@pytest.mark.unit
+def test_wordpiece_roundtrip():
+    tokenizer = WordPieceTokenizer({"[UNK]": 0, "the": 1, "quick": 2, "##ly": 3}, lowercase=False)
+    pipeline = TokenizerPipeline(tokenizer, normalizer=Normalizer(lowercase=True))
+    config = json.loads(pipeline.to_json())
+    assert config["tokenizer"]["type"] == "WordPieceTokenizer"
+    assert config["tokenizer"]["lowercase"] is False
+    restored = TokenizerPipeline.from_json(pipeline.to_json())
+    assert restored.tokenize("The Quickly") == pipeline.tokenize("The Quickly") == ["the", "quick", "##ly"]
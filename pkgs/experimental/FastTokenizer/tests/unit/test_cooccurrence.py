@@ -0,0 +1,48 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer, Vocab, cooccurrence
+
+VOCAB = ["a", "b", "c"]
+
+
+@pytest.mark.unit
+class TestCooccurrence:
+    """Unit tests for the sparse co-occurrence matrix builder."""
+
+    def test_uniform_symmetric(self):
+        matrix = cooccurrence(["a b c"], Vocab(VOCAB), window=1, weighting="uniform")
+        assert matrix.shape == (3, 3)
+        assert matrix.to_dense() == [[0, 1, 0], [1, 0, 1], [0, 1, 0]]
+
+    def test_harmonic_window(self):
+        matrix = cooccurrence(["a b c"], Vocab(VOCAB), window=2)
+        assert matrix.row(0) == [(1, 1.0), (2, 0.5)]
+        assert matrix.row(2) == [(0, 0.5), (1, 1.0)]
+
+    def test_directed(self):
+        matrix = cooccurrence(["a b", "b a b"], Vocab(VOCAB), window=1, symmetric=False, weighting="uniform")
+        assert matrix.to_dense() == [[0, 2, 0], [1, 0, 0], [0, 0, 0]]
+
+    def test_out_of_vocabulary_tokens_dropped(self):
+        matrix = cooccurrence(["a x y b"], Vocab(VOCAB), window=1, weighting="uniform")
+        assert matrix.row(0) == [(1, 1.0)]
+
+    def test_batches_and_workers_agree(self):
+        corpus = ["a b c a", "c b", "b b a"] * 50
+        expected = cooccurrence(corpus, Vocab(VOCAB), window=3).to_dense()
+        for batch_size, workers in [(1, 1), (7, 2), (1000, 4)]:
+            matrix = cooccurrence(iter(corpus), Vocab(VOCAB), window=3, batch_size=batch_size, workers=workers)
+            for row, expected_row in zip(matrix.to_dense(), expected):
+                assert row == pytest.approx(expected_row)
+
+    def test_tokenizer(self):
+        matrix = cooccurrence(["a,b;c"], Vocab(VOCAB), window=1, tokenizer=RegexTokenizer(r"\w"), weighting="uniform")
+        assert matrix.nnz == 4
+
+    def test_invalid(self):
+        with pytest.raises(ValueError):
+            cooccurrence(["a b"], Vocab(VOCAB), window=0)
+        with pytest.raises(ValueError):
+            cooccurrence(["a b"], Vocab(VOCAB), weighting="linear")
+        with pytest.raises(TypeError):
+            cooccurrence([1, 2], Vocab(VOCAB))
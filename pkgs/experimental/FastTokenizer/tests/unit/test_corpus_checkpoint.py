@@ -0,0 +1,124 @@
+import gzip
+import json
+
+import pytest
+
+from fasttokenizer import TokenizerIO, WhitespaceTokenizer
+
+LINES = [f"line {i} of the corpus" for i in range(100)]
+
+
+@pytest.fixture
+def job(tmp_path):
+    (tmp_path / "one.txt").write_text("\n".join(LINES) + "\n", encoding="utf-8")
+    (tmp_path / "two.txt.gz").write_bytes(gzip.compress("alpha beta\ngamma\n".encode("utf-8")))
+    paths = [str(tmp_path / "one.txt"), str(tmp_path / "two.txt.gz")]
+    return paths, tmp_path / "out", tmp_path / "checkpoint.json"
+
+
+def run(paths, out, checkpoint, **kwargs):
+    return TokenizerIO().tokenize_corpus(
+        paths, WhitespaceTokenizer(), output_dir=str(out), workers=1, checkpoint=str(checkpoint), **kwargs
+    )
+
+
+@pytest.mark.unit
+class TestCorpusCheckpoint:
+    """Unit tests for resuming tokenize_corpus jobs from a checkpoint."""
+
+    def test_completed_job(self, job):
+        paths, out, checkpoint = job
+        outputs = run(paths, out, checkpoint)
+        manifest = json.loads(checkpoint.read_text())
+        assert manifest["format"] == "fasttokenizer-checkpoint"
+        assert manifest["output_format"] == "txt"
+        assert sorted(manifest["completed"]) == sorted(paths)
+        assert manifest["partial"] == {}
+        assert open(outputs[1], encoding="utf-8").read() == "alpha beta\ngamma\n"
+
+    def test_skips_completed_files(self, job):
+        paths, out, checkpoint = job
+        outputs = run(paths, out, checkpoint)
+        (out.parent / "one.txt").write_text("changed\n", encoding="utf-8")
+        calls = []
+        assert run(paths, out, checkpoint, progress=lambda done, total: calls.append(done)) == outputs
+        assert open(outputs[0], encoding="utf-8").readline() == "line 0 of the corpus\n"
+        assert calls == [1, 2]
+
+        # A completed file whose output is gone is written again.
+        (out / "one.txt.tokens.txt").unlink()
+        run(paths, out, checkpoint)
+        assert open(outputs[0], encoding="utf-8").read() == "changed\n"
+
+    def test_resumes_partial_file(self, job):
+        paths, out, checkpoint = job
+        source = out.parent / "one.txt"
+        broken = LINES[:60] + ["bad \xff byte"] + LINES[61:]
+        source.write_bytes("\n".join(broken).encode("latin-1") + b"\n")
+        with pytest.raises(IOError, match="line 61"):
+            run(paths[:1], out, checkpoint, checkpoint_interval=0)
+        progress = json.loads(checkpoint.read_text())["partial"][paths[0]]
+        assert progress["lines"] == 60
+        assert progress["offset"] == len("\n".join(LINES[:60])) + 1
+        assert (out / "one.txt.tokens.txt.partial").exists()
+
+        # Fix the bad line: only the lines after the checkpoint are
+        # tokenized again.
+        source.write_text("\n".join(LINES) + "\n", encoding="utf-8")
+        [output] = run(paths[:1], out, checkpoint, checkpoint_interval=0)
+        assert open(output, encoding="utf-8").read().splitlines() == LINES
+        assert not (out / "one.txt.tokens.txt.partial").exists()
+        assert json.loads(checkpoint.read_text())["partial"] == {}
+
+    def test_refuses_to_resume_changed_input(self, job):
+        paths, out, checkpoint = job
+        source = out.parent / "one.txt"
+        source.write_bytes("\n".join(LINES[:60] + ["bad \xff byte"]).encode("latin-1") + b"\n")
+        with pytest.raises(IOError, match="line 61"):
+            run(paths[:1], out, checkpoint, checkpoint_interval=0)
+
+        # An earlier line changed, so the tokens already written are stale.
+        source.write_text("\n".join(["LINE 0 of the corpus"] + LINES[1:]) + "\n", encoding="utf-8")
+        with pytest.raises(IOError, match="input changed since its checkpoint"):
+            run(paths[:1], out, checkpoint, checkpoint_interval=0)
+        source.write_text("\n".join(LINES[:30]) + "\n", encoding="utf-8")
+        with pytest.raises(IOError, match="input is shorter than at its checkpoint"):
+            run(paths[:1], out, checkpoint, checkpoint_interval=0)
+
+        # Without the partial output, the file starts over.
+        (out / "one.txt.tokens.txt.partial").unlink()
+        [output] = run(paths[:1], out, checkpoint, checkpoint_interval=0)
+        assert open(output, encoding="utf-8").read().splitlines() == LINES[:30]
+
+    def test_resumes_compressed_file(self, job):
+        paths, out, checkpoint = job
+        source = out.parent / "two.txt.gz"
+        source.write_bytes(gzip.compress(b"alpha beta\ngam\xffma\n"))
+        with pytest.raises(IOError, match="line 2"):
+            run(paths[1:], out, checkpoint, format="jsonl", checkpoint_interval=0)
+        progress = json.loads(checkpoint.read_text())["partial"][paths[1]]
+        assert (progress["offset"], progress["lines"], progress["written"]) == (11, 1, 17)
+
+        # Output past the checkpoint is dropped.
+        partial = out / "two.txt.tokens.jsonl.partial"
+        with open(partial, "a", encoding="utf-8") as f:
+            f.write('["stale"]\n')
+        source.write_bytes(gzip.compress(b"alpha beta\ngamma\n"))
+        run(paths[1:], out, checkpoint, format="jsonl")
+        assert (out / "two.txt.tokens.jsonl").read_text(encoding="utf-8") == '["alpha","beta"]\n["gamma"]\n'
+
+    def test_invalid_checkpoints(self, job):
+        paths, out, checkpoint = job
+        with pytest.raises(ValueError, match="checkpoint needs an output_dir"):
+            TokenizerIO().tokenize_corpus(paths, WhitespaceTokenizer(), checkpoint=str(checkpoint))
+        with pytest.raises(ValueError, match="checkpoint_interval"):
+            run(paths, out, checkpoint, checkpoint_interval=-1)
+        run(paths, out, checkpoint)
+        with pytest.raises(ValueError, match="was written for format 'txt', not 'jsonl'"):
+            run(paths, out, checkpoint, format="jsonl")
+        checkpoint.write_text('{"format": "something else"}')
+        with pytest.raises(ValueError, match="is not a fasttokenizer checkpoint"):
+            run(paths, out, checkpoint)
+        checkpoint.write_text('{"format": "fasttokenizer-checkpoint", "version": 99}')
+        with pytest.raises(ValueError, match="Unsupported checkpoint version 99"):
+            run(paths, out, checkpoint)
@@ -0,0 +1,94 @@
+import gzip
+
+import pytest
+
+from fasttokenizer import TokenizerIO
+
+LINES = [f"line {i}" for i in range(1000)]
+
+
+@pytest.fixture
+def corpus(tmp_path):
+    path = tmp_path / "corpus.txt"
+    path.write_text("\n".join(LINES) + "\n", encoding="utf-8")
+    return str(path)
+
+
+@pytest.mark.unit
+def test_sample_is_seeded_and_in_file_order(corpus):
+    io = TokenizerIO()
+    sample = io.sample(corpus, 50, seed=7)
+    assert len(sample) == 50 and len(set(sample)) == 50
+    assert sample == sorted(sample, key=LINES.index)
+    assert io.sample(corpus, 50, seed=7) == sample
+    assert io.sample(corpus, 50, seed=8) != sample
+
+
+@pytest.mark.unit
+def test_sample_is_uniform(corpus):
+    io = TokenizerIO()
+    hits = [0] * 10
+    for seed in range(200):
+        for line in io.sample(corpus, 10, seed=seed):
+            hits[LINES.index(line) // 100] += 1
+    # Each decile of the file should get about a tenth of the 2000 draws.
+    assert all(120 < count < 280 for count in hits)
+
+
+@pytest.mark.unit
+def test_sample_small_file_and_records(tmp_path):
+    path = tmp_path / "records.txt.gz"
+    path.write_bytes(gzip.compress(b"a\x1eb\x1ec"))
+    io = TokenizerIO()
+    assert io.sample(str(path), 10, delimiter="\x1e") == ["a", "b", "c"]
+    assert io.sample(str(path), 0) == []
+    with pytest.raises(ValueError):
+        io.sample(str(path), 1, delimiter="")
+
+
+@pytest.mark.unit
+def test_split_writes_shards(corpus, tmp_path):
+    out = tmp_path / "shards"
+    paths = TokenizerIO().split(corpus, str(out), seed=3)
+    assert list(paths) == ["train", "val", "test"]
+    assert paths["train"] == str(out / "corpus.train.txt")
+    shards = {name: open(p, encoding="utf-8").read().splitlines() for name, p in paths.items()}
+    assert sorted(sum(shards.values(), []), key=LINES.index) == LINES
+    for lines in shards.values():
+        assert lines == sorted(lines, key=LINES.index)
+    assert 720 < len(shards["train"]) < 880
+    assert 50 < len(shards["val"]) < 150
+    assert not [p for p in out.iterdir() if p.name.endswith(".partial")]
+
+
+@pytest.mark.unit
+def test_split_is_seeded(corpus, tmp_path):
+    io = TokenizerIO()
+    a = io.split(corpus, str(tmp_path / "a"), ratios=[1, 1], seed=5)
+    b = io.split(corpus, str(tmp_path / "b"), ratios=[1, 1], seed=5)
+    assert list(a) == ["train", "test"]
+    for name in a:
+        assert open(a[name], "rb").read() == open(b[name], "rb").read()
+
+
+@pytest.mark.unit
+def test_split_names_and_compressed_input(tmp_path):
+    path = tmp_path / "data.jsonl.gz"
+    path.write_bytes(gzip.compress(b'{"a": 1}\n{"a": 2}\n'))
+    paths = TokenizerIO().split(str(path), str(tmp_path), ratios=[1, 0], names=["keep", "drop"])
+    assert paths["keep"].endswith("data.keep.jsonl")
+    assert open(paths["keep"]).read() == '{"a": 1}\n{"a": 2}\n'
+    assert open(paths["drop"]).read() == ""
+
+
+@pytest.mark.unit
+def test_split_errors(corpus, tmp_path):
+    io = TokenizerIO()
+    with pytest.raises(ValueError):
+        io.split(corpus, str(tmp_path), ratios=[1, 1, 1, 1])
+    with pytest.raises(ValueError):
+        io.split(corpus, str(tmp_path), ratios=[1, 1], names=["a", "a"])
+    with pytest.raises(ValueError):
+        io.split(corpus, str(tmp_path), ratios=[0.5, -0.5])
+    with pytest.raises(IOError):
+        io.split(str(tmp_path / "missing.txt"), str(tmp_path))
@@ -0,0 +1,60 @@
+import gzip
+
+import pytest
+
+from fasttokenizer import CorpusStats, Vocab, WhitespaceTokenizer, corpus_stats
+
+
+@pytest.fixture
+def paths(tmp_path):
+    first = tmp_path / "a.txt"
+    first.write_text("the cat sat\nthe dog\n\n", encoding="utf-8")
+    second = tmp_path / "b.txt.gz"
+    second.write_bytes(gzip.compress("a cat on the mat with the hat\n".encode("utf-8")))
+    return [str(first), str(second)]
+
+
+@pytest.mark.unit
+def test_file_documents(paths):
+    stats = corpus_stats(paths, WhitespaceTokenizer(), workers=2)
+    assert isinstance(stats, CorpusStats)
+    assert stats.documents == 2
+    assert stats.tokens == 13
+    assert stats.types == 9
+    assert stats.type_token_ratio == pytest.approx(9 / 13)
+    assert (stats.min_length, stats.max_length) == (5, 8)
+    assert stats.mean_length == pytest.approx(6.5)
+    assert stats.length_histogram() == [(4, 8, 1), (8, 16, 1)]
+    assert stats.top_tokens(2) == [("the", 4), ("cat", 2)]
+
+
+@pytest.mark.unit
+def test_line_documents(paths):
+    stats = corpus_stats(paths, WhitespaceTokenizer(), document="line", top_k=1)
+    assert stats.documents == 3
+    assert (stats.min_length, stats.max_length) == (2, 8)
+    assert stats.length_histogram() == [(2, 4, 2), (8, 16, 1)]
+    assert stats.top_tokens() == [("the", 4)]
+
+
+@pytest.mark.unit
+def test_to_dict_and_counts(paths):
+    stats = corpus_stats(paths, WhitespaceTokenizer())
+    summary = stats.to_dict()
+    assert summary["documents"] == 2
+    assert summary["types"] == 9
+    assert summary["top_tokens"][0] == ("the", 4)
+    vocab = Vocab.from_counts(stats.counts(), max_size=2)
+    assert vocab.tokens() == ["the", "cat"]
+    assert "CorpusStats(documents=2" in repr(stats)
+
+
+@pytest.mark.unit
+def test_empty_corpus_and_errors(paths):
+    stats = corpus_stats([], WhitespaceTokenizer())
+    assert (stats.documents, stats.tokens, stats.type_token_ratio) == (0, 0, 0.0)
+    assert stats.length_histogram() == []
+    with pytest.raises(ValueError):
+        corpus_stats(paths, WhitespaceTokenizer(), document="page")
+    with pytest.raises(IOError):
+        corpus_stats(["/nonexistent/file.txt"], WhitespaceTokenizer())
@@ -0,0 +1,59 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer, Vocab, WhitespaceTokenizer, count_tokens
+
+CORPUS = ["the cat sat", "the dog sat on the mat", "a cat"]
+
+
+@pytest.mark.unit
+def test_exact_counts():
+    counts = count_tokens(CORPUS, WhitespaceTokenizer(), batch_size=2, workers=2)
+    assert counts == {
+        "the": 3,
+        "cat": 2,
+        "sat": 2,
+        "dog": 1,
+        "on": 1,
+        "mat": 1,
+        "a": 1,
+    }
+
+
+@pytest.mark.unit
+def test_counts_from_generator():
+    counts = count_tokens((line.upper() for line in CORPUS), RegexTokenizer(r"\w+"))
+    assert counts["THE"] == 3
+    assert count_tokens([], WhitespaceTokenizer()) == {}
+
+
+@pytest.mark.unit
+def test_bounded_counts_keep_frequent_tokens():
+    corpus = ["common"] * 500 + [f"rare{i} common" for i in range(200)]
+    counts = count_tokens(corpus, WhitespaceTokenizer(), batch_size=50, max_types=5, sketch_width=1024)
+    assert len(counts) == 5
+    assert counts["common"] >= 700
+    assert all(count >= 1 for count in counts.values())
+
+
+@pytest.mark.unit
+def test_vocab_from_counts():
+    counts = {"the": 3, "cat": 2, "sat": 2, "dog": 1, "a": 1}
+    vocab = Vocab.from_counts(counts, unk_token="<unk>", special_tokens=["<pad>"])
+    assert vocab.tokens() == ["<unk>", "<pad>", "the", "cat", "sat", "a", "dog"]
+    assert vocab.frozen
+    assert vocab.encode(["the", "bird"]) == [2, 0]
+
+    small = Vocab.from_counts(counts, max_size=3, min_freq=2, unk_token="<unk>", frozen=False)
+    assert small.tokens() == ["<unk>", "the", "cat"]
+    assert not small.frozen
+    assert Vocab.from_counts(counts, min_freq=2).tokens() == ["the", "cat", "sat"]
+
+
+@pytest.mark.unit
+def test_errors():
+    with pytest.raises(ValueError):
+        count_tokens(CORPUS, WhitespaceTokenizer(), workers=0)
+    with pytest.raises(ValueError):
+        count_tokens(CORPUS, WhitespaceTokenizer(), max_types=3, sketch_width=0)
+    with pytest.raises(TypeError):
+        count_tokens([1, 2], WhitespaceTokenizer())
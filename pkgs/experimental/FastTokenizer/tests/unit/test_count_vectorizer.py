@@ -0,0 +1,49 @@
+import pytest
+
+from fasttokenizer import CountVectorizer, Vocab, WhitespaceTokenizer, count_tokens
+
+
+@pytest.mark.unit
+def test_counts_restricted_to_vocab():
+    vocab = Vocab(["cat", "dog", "the"])
+    vectorizer = CountVectorizer(WhitespaceTokenizer(), vocab)
+    matrix = vectorizer.transform(["the cat and the dog", "bird"])
+    assert matrix.shape == (2, 3)
+    assert matrix.to_dense() == [[1.0, 1.0, 2.0], [0.0, 0.0, 0.0]]
+    # Unknown tokens are not added to a mutable vocabulary.
+    assert len(vocab) == 3
+    assert vectorizer.feature_names() == ["cat", "dog", "the"]
+
+
+@pytest.mark.unit
+def test_binary():
+    vectorizer = CountVectorizer(WhitespaceTokenizer(), Vocab(["a", "b"]), binary=True)
+    assert vectorizer.transform(["a a a b"]).row(0) == [(0, 1.0), (1, 1.0)]
+
+
+@pytest.mark.unit
+def test_ngrams():
+    vocab = Vocab(["new", "york", "new york", "new york city", "city"])
+    vectorizer = CountVectorizer(WhitespaceTokenizer(), vocab, ngram_range=(1, 3))
+    assert vectorizer.transform(["new york city"]).to_dense() == [[1.0, 1.0, 1.0, 1.0, 1.0]]
+    bigrams = CountVectorizer(WhitespaceTokenizer(), Vocab(["new_york"]), ngram_range=(2, 2), separator="_")
+    assert bigrams.transform(["new york new york", "new"]).to_dense() == [[2.0], [0.0]]
+    assert bigrams.ngram_range == (2, 2)
+
+
+@pytest.mark.unit
+def test_vocab_from_counts_and_setter():
+    corpus = ["a b c", "a b", "a"]
+    vocab = Vocab.from_counts(count_tokens(corpus, WhitespaceTokenizer()), max_size=2)
+    vectorizer = CountVectorizer(WhitespaceTokenizer(), vocab)
+    assert vectorizer.transform(corpus).to_dense() == [[1.0, 1.0], [1.0, 1.0], [1.0, 0.0]]
+    vectorizer.vocab = Vocab(["c"])
+    assert vectorizer.transform(corpus).shape == (3, 1)
+
+
+@pytest.mark.unit
+def test_invalid_ngram_range():
+    with pytest.raises(ValueError):
+        CountVectorizer(WhitespaceTokenizer(), Vocab(), ngram_range=(0, 1))
+    with pytest.raises(ValueError):
+        CountVectorizer(WhitespaceTokenizer(), Vocab(), ngram_range=(3, 2))
@@ -0,0 +1,117 @@
+import pytest
+
+from fasttokenizer import (
+    BpeTokenizer,
+    SentencePieceTokenizer,
+    SpecialTokens,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+from conftest import bytes_to_unicode
+
+
+@pytest.fixture
+def gpt2():
+    tokens = list(bytes_to_unicode().values()) + ["<|endoftext|>"]
+    return BpeTokenizer(
+        {token: i for i, token in enumerate(tokens)},
+        [],
+        special_tokens=SpecialTokens(eos="<|endoftext|>"),
+    )
+
+
+@pytest.mark.unit
+class TestDecode:
+    """Unit tests for decoding generated token ids back into text."""
+
+    def test_byte_level_roundtrip(self, gpt2):
+        text = "naïve café 🤖 done"
+        assert gpt2.decode(gpt2.encode(text)) == text
+
+    def test_skip_special_tokens(self, gpt2):
+        eos = gpt2.token_to_id("<|endoftext|>")
+        ids = gpt2.encode("Hi") + [eos]
+        assert gpt2.decode(ids) == "Hi"
+        assert gpt2.decode(ids, skip_special_tokens=False) == "Hi<|endoftext|>"
+
+    def test_clean_up_tokenization_spaces(self, gpt2):
+        ids = gpt2.encode("I do n't know , ok ?")
+        assert gpt2.decode(ids) == "I don't know, ok?"
+        assert gpt2.decode(ids, clean_up_tokenization_spaces=False) == "I do n't know , ok ?"
+
+    def test_special_tokens_are_registered(self, gpt2):
+        assert gpt2.special_tokens.tokens() == ["<|endoftext|>"]
+        plain = BpeTokenizer({gpt2.id_to_token(i): i for i in range(gpt2.vocab_size())}, [])
+        eos = gpt2.token_to_id("<|endoftext|>")
+        assert plain.decode([eos]) == "<|endoftext|>"
+
+    def test_special_tokens_are_saved(self, tmp_path, gpt2):
+        path = tmp_path / "tokenizer.json"
+        gpt2.save(str(path))
+        restored = BpeTokenizer.load(str(path))
+        assert restored.special_tokens.tokens() == ["<|endoftext|>"]
+        assert restored.decode(restored.encode("Hi") + [restored.token_to_id("<|endoftext|>")]) == "Hi"
+
+    def test_wordpiece(self):
+        tokens = ["[UNK]", "[CLS]", "[SEP]", "hello", ",", "world", "!"]
+        tokenizer = WordPieceTokenizer(
+            {token: i for i, token in enumerate(tokens)},
+            special_tokens=SpecialTokens(cls="[CLS]", sep="[SEP]"),
+        )
+        ids = [1, 3, 4, 5, 6, 2]
+        assert tokenizer.decode(ids) == "hello, world!"
+        assert (
+            tokenizer.decode(ids, skip_special_tokens=False, clean_up_tokenization_spaces=False)
+            == "[CLS] hello , world ! [SEP]"
+        )
+
+    def test_wordpiece_from_file_defaults(self, tmp_path):
+        path = tmp_path / "vocab.txt"
+        path.write_text("[PAD]\n[UNK]\n[CLS]\n[SEP]\nhello\n", encoding="utf-8")
+        tokenizer = WordPieceTokenizer.from_file(str(path))
+        assert tokenizer.special_tokens.tokens() == ["[PAD]", "[CLS]", "[SEP]"]
+        assert tokenizer.decode([2, 4, 3, 0]) == "hello"
+        tokenizer = WordPieceTokenizer.from_file(str(path), special_tokens=SpecialTokens(cls="[CLS]"))
+        assert tokenizer.decode([2, 4, 3, 0]) == "hello [SEP] [PAD]"
+
+    def test_sentencepiece_control_pieces(self):
+        pieces = [("<unk>", 0.0), ("<s>", 0.0), ("</s>", 0.0)]
+        pieces += [(c, -5.0) for c in "▁hi"] + [("▁hi", -1.0)]
+        model = SentencePieceTokenizer(pieces, bos_id=1, eos_id=2)
+        ids = model.encode("hi", add_bos=True, add_eos=True)
+        assert model.decode(ids) == "hi"
+        assert model.decode(ids, skip_special_tokens=False) == "<s> hi</s>"
+
+    def test_pipeline(self):
+        vocab = Vocab(["[CLS]", "[SEP]", "hello", ",", "world"])
+        pipeline = TokenizerPipeline(
+            WhitespaceTokenizer(),
+            vocab=vocab,
+            special_tokens=SpecialTokens(cls="[CLS]", sep="[SEP]"),
+        )
+        ids = [0, 2, 3, 4, 1]
+        assert pipeline.decode(ids) == "hello, world"
+        assert pipeline.decode(ids, skip_special_tokens=False) == "[CLS] hello, world [SEP]"
+
+    def test_byte_level_pipeline(self):
+        alphabet = list(bytes_to_unicode().values())
+        tokens = alphabet + ["Ġw", "Ġwo"]
+        tokenizer = BpeTokenizer({token: i for i, token in enumerate(tokens)}, [("Ġ", "w"), ("Ġw", "o")])
+        pipeline = TokenizerPipeline(tokenizer, vocab=Vocab(tokens))
+        text = "Hello world, wörld 🤖"
+        assert "Ġwo" in pipeline.tokenize(text)
+        assert pipeline.decode(pipeline.encode(text)) == text
+
+    def test_wordpiece_pipeline(self):
+        tokens = ["[UNK]", "[CLS]", "[SEP]", "un", "##aff", "##able", "is", "fine", "."]
+        tokenizer = WordPieceTokenizer({token: i for i, token in enumerate(tokens)})
+        pipeline = TokenizerPipeline(
+            tokenizer,
+            vocab=Vocab(tokens),
+            special_tokens=SpecialTokens(cls="[CLS]", sep="[SEP]"),
+        )
+        ids = pipeline.encode("Unaffable is fine.")
+        assert pipeline.decode(ids) == "unaffable is fine."
@@ -0,0 +1,64 @@
+import pytest
+
+from fasttokenizer import (
+    GraphemeTokenizer,
+    RegexTokenizer,
+    StopWordFilter,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+    detokenize,
+    detokenize_with_offsets,
+)
+
+
+@pytest.mark.unit
+def test_punctuation_spacing():
+    tokens = ["Hello", ",", "world", "!", "It", "costs", "$", "5", "(", "or", "less", ")", "."]
+    assert detokenize(tokens) == "Hello, world! It costs $5 (or less)."
+
+
+@pytest.mark.unit
+def test_quotes_and_contractions():
+    tokens = ["She", "said", '"', "I", "do", "n't", "know", '"', "and", "it", "'s", "fine"]
+    assert detokenize(tokens) == 'She said "I don\'t know" and it\'s fine'
+
+
+@pytest.mark.unit
+def test_cjk_and_whitespace_tokens():
+    assert detokenize(["我", "爱", "北京", "。"]) == "我爱北京。"
+    assert detokenize(["a", "\n", "b"]) == "a\nb"
+    assert detokenize([]) == ""
+
+
+@pytest.mark.unit
+def test_tokenizer_methods():
+    regex = RegexTokenizer(r"\w+|[^\w\s]")
+    text = "Wait, what? (Really.)"
+    assert regex.detokenize(regex.tokenize(text)) == text
+    graphemes = GraphemeTokenizer()
+    assert graphemes.detokenize(graphemes.tokenize("a b\u0301")) == "a b\u0301"
+    pipeline = TokenizerPipeline(regex)
+    assert pipeline.detokenize(["Hi", "!"]) == "Hi!"
+    assert WhitespaceTokenizer().detokenize(["a", "b"]) == "a b"
+
+
+@pytest.mark.unit
+def test_offsets_keep_original_spacing():
+    text = "The  café,\tthe end"
+    tokenizer = RegexTokenizer(r"\w+|[^\w\s]")
+    spans = tokenizer.tokenize_with_offsets(text)
+    assert detokenize_with_offsets(text, spans) == text
+    stop = StopWordFilter("en")
+    kept = [s for s in spans if not stop.is_stop_word(s[0])]
+    assert detokenize_with_offsets(text, kept) == "café, end"
+    byte_spans = tokenizer.tokenize_with_offsets(text, unit="byte")
+    assert detokenize_with_offsets(text, byte_spans, unit="byte") == text
+
+
+@pytest.mark.unit
+def test_offsets_use_token_text_and_validate():
+    assert detokenize_with_offsets("Ab-cd", [("ab", 0, 2), ("cd", 3, 5)]) == "abcd"
+    with pytest.raises(ValueError):
+        detokenize_with_offsets("short", [("x", 0, 50)])
+    with pytest.raises(ValueError):
+        detokenize_with_offsets("short", [], unit="word")
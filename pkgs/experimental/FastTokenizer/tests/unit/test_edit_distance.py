@@ -0,0 +1,75 @@
+import random
+
+import pytest
+
+from fasttokenizer import best_matches, damerau_levenshtein, jaro_winkler, levenshtein
+
+
+def reference_levenshtein(a, b):
+    row = list(range(len(b) + 1))
+    for i, ca in enumerate(a, 1):
+        previous, row[0] = row[0], i
+        for j, cb in enumerate(b, 1):
+            previous, row[j] = row[j], min(row[j] + 1, row[j - 1] + 1, previous + (ca != cb))
+    return row[-1]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "a,b,expected",
+    [
+        ("kitten", "sitting", 3),
+        ("", "abc", 3),
+        ("abc", "", 3),
+        ("flaw", "lawn", 2),
+        ("naïve", "naive", 1),
+        ("same", "same", 0),
+    ],
+)
+def test_levenshtein(a, b, expected):
+    assert levenshtein(a, b) == expected
+    assert levenshtein(b, a) == expected
+
+
+@pytest.mark.unit
+def test_levenshtein_matches_reference_across_word_boundary():
+    rng = random.Random(7)
+    for length in (1, 10, 63, 64, 65, 150):
+        for _ in range(20):
+            a = "".join(rng.choice("abcd") for _ in range(length))
+            b = "".join(rng.choice("abcd") for _ in range(rng.randint(0, length + 5)))
+            assert levenshtein(a, b) == reference_levenshtein(a, b)
+
+
+@pytest.mark.unit
+def test_damerau_levenshtein_counts_transpositions():
+    assert levenshtein("ab", "ba") == 2
+    assert damerau_levenshtein("ab", "ba") == 1
+    assert damerau_levenshtein("ca", "abc") == 2
+    assert damerau_levenshtein("recieve", "receive") == 1
+    assert damerau_levenshtein("", "") == 0
+
+
+@pytest.mark.unit
+def test_jaro_winkler():
+    assert jaro_winkler("MARTHA", "MARHTA") == pytest.approx(0.9611, abs=1e-4)
+    assert jaro_winkler("DIXON", "DICKSONX") == pytest.approx(0.8133, abs=1e-4)
+    assert jaro_winkler("abc", "abc") == 1.0
+    assert jaro_winkler("abc", "xyz") == 0.0
+    assert jaro_winkler("MARTHA", "MARHTA", prefix_weight=0.0) == pytest.approx(0.9444, abs=1e-4)
+    with pytest.raises(ValueError):
+        jaro_winkler("a", "b", prefix_weight=0.5)
+
+
+@pytest.mark.unit
+def test_best_matches():
+    words = ["receive", "recipe", "deceive", "relieve", "banana"]
+    assert best_matches("recieve", words, max_distance=2) == [
+        ("relieve", 1, 3),
+        ("receive", 2, 0),
+        ("recipe", 2, 1),
+    ]
+    assert best_matches("recieve", words, limit=1, metric="damerau_levenshtein") == [("receive", 1, 0)]
+    assert best_matches("x", [], max_distance=1) == []
+    with pytest.raises(ValueError):
+        best_matches("a", words, metric="hamming")
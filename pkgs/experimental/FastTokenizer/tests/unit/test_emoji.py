@@ -0,0 +1,68 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import Normalizer, RegexTokenizer, TokenizerPipeline, normalize_emoji
+
+TEXT = "Great job 👍🏽!! 👩‍💻 on it 🇺🇸 :) see https://example.com"
+
+
+@pytest.mark.unit
+class TestEmojiNormalization:
+    """Unit tests for the emoji and emoticon normalization stage."""
+
+    def test_shortcode(self):
+        assert normalize_emoji(TEXT) == (
+            "Great job :thumbs_up_medium_skin_tone: !! :woman_technologist: on it "
+            ":flag_united_states: :) see https://example.com"
+        )
+        assert normalize_emoji("pizza🍕time <3 :-(", emoticons="shortcode") == (
+            "pizza :pizza: time :red_heart: :slightly_frowning_face:"
+        )
+
+    def test_strip(self):
+        assert normalize_emoji("pizza🍕time", "strip") == "pizza time"
+        assert normalize_emoji("done 👍🏽", "strip") == "done "
+        # The colon and slash of a URL are not an emoticon.
+        assert normalize_emoji(TEXT, None, "strip").endswith("🇺🇸  see https://example.com")
+
+    def test_keep_sets_sequences_apart(self):
+        assert normalize_emoji("ok👩‍💻👍🏽 thanks", "keep") == "ok 👩‍💻 👍🏽 thanks"
+        # Text-style symbols are not emoji unless asked to be.
+        assert normalize_emoji("Acme© ™ ©️", "strip") == "Acme© ™ "
+
+    def test_invalid_mode(self):
+        with pytest.raises(ValueError, match="Unknown emoji mode"):
+            normalize_emoji(TEXT, "drop")
+        with pytest.raises(ValueError, match="Unknown emoji mode"):
+            Normalizer(emoticons="remove")
+
+    def test_normalizer_shields_kept_emoji(self):
+        normalizer = Normalizer(
+            lowercase=True,
+            remove_punctuation=True,
+            remove_control=True,
+            collapse_whitespace=True,
+            emoji="keep",
+            emoticons="keep",
+        )
+        assert normalizer.normalize("Great job👍🏽!! 👩‍💻 :D") == "great job 👍🏽 👩‍💻 :D"
+        alignment = normalizer.normalize_with_alignment("Hi👋🏽")
+        assert alignment.normalized == "hi 👋🏽"
+        assert alignment.to_original(3, 5) == (2, 4)
+        restored = pickle.loads(pickle.dumps(normalizer))
+        assert restored.normalize(TEXT) == normalizer.normalize(TEXT)
+
+    def test_shortcodes_after_other_steps(self):
+        normalizer = Normalizer(lowercase=True, collapse_whitespace=True, emoji="shortcode")
+        assert normalizer.normalize("LOVE IT 😍") == "love it :smiling_face_with_heart_eyes:"
+        assert Normalizer(emoji="strip", strip=True).normalize("🎉 Launched 🎉") == "Launched"
+
+    def test_pipeline_keeps_emoji_whole(self):
+        normalizer = Normalizer(lowercase=True, emoji="keep", emoticons="keep")
+        pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), normalizer=normalizer)
+        text = "Thanks👍🏽 team :) 👩‍💻"
+        assert pipeline.tokenize(text) == ["thanks", "👍🏽", "team", ":)", "👩‍💻"]
+        offsets = pipeline.tokenize_with_offsets(text, original=True)
+        assert [text[start:end] for _, start, end in offsets] == ["Thanks", "👍🏽", "team", ":)", "👩‍💻"]
+        assert "👩‍💻" not in TokenizerPipeline(RegexTokenizer(r"\w+")).tokenize(text)
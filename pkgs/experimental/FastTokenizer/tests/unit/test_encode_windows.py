@@ -0,0 +1,64 @@
+import json
+
+import pytest
+
+from fasttokenizer import RegexTokenizer, SpecialTokens, Tokenizer, TokenizerPipeline, Vocab
+
+
+@pytest.fixture
+def pipeline():
+    special = SpecialTokens(cls="[CLS]", sep="[SEP]", pad="[PAD]")
+    vocab = Vocab(["[PAD]", "[CLS]", "[SEP]", "a", "b", "c", "d", "e", "é"])
+    return TokenizerPipeline(RegexTokenizer(r"\w+"), vocab=vocab, special_tokens=special)
+
+
+@pytest.fixture
+def word_level():
+    vocab = {"[PAD]": 0, "[CLS]": 1, "[SEP]": 2, "a": 3, "b": 4, "c": 5, "d": 6, "e": 7}
+    return Tokenizer.from_str(
+        json.dumps(
+            {
+                "pre_tokenizer": {"type": "WhitespaceSplit"},
+                "model": {"type": "WordLevel", "vocab": vocab, "unk_token": "[PAD]"},
+                "post_processor": {"type": "BertProcessing", "cls": ["[CLS]", 1], "sep": ["[SEP]", 2]},
+            }
+        )
+    )
+
+
+@pytest.mark.unit
+def test_windows_overlap_by_stride(pipeline, word_level):
+    for tokenizer in (pipeline, word_level):
+        windows = tokenizer.encode_windows("a b c d e", max_length=5, stride=1)
+        assert [ids for ids, _ in windows] == [[1, 3, 4, 5, 2], [1, 5, 6, 7, 2]]
+        assert [spans for _, spans in windows] == [
+            [(0, 0), (0, 1), (2, 3), (4, 5), (0, 0)],
+            [(0, 0), (4, 5), (6, 7), (8, 9), (0, 0)],
+        ]
+
+
+@pytest.mark.unit
+def test_windows_without_special_tokens(pipeline):
+    windows = pipeline.encode_windows("a b c d e", max_length=2, add_special_tokens=False)
+    assert [ids for ids, _ in windows] == [[3, 4], [5, 6], [7]]
+
+
+@pytest.mark.unit
+def test_short_and_empty_texts_give_one_window(pipeline):
+    windows = pipeline.encode_windows("a b", max_length=8, stride=2)
+    assert windows == [([1, 3, 4, 2], [(0, 0), (0, 1), (2, 3), (0, 0)])]
+    assert pipeline.encode_windows("", max_length=8) == [([1, 2], [(0, 0), (0, 0)])]
+
+
+@pytest.mark.unit
+def test_byte_offsets(pipeline):
+    windows = pipeline.encode_windows("é a", max_length=8, add_special_tokens=False, unit="byte")
+    assert windows == [([8, 3], [(0, 2), (3, 4)])]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("max_length,stride", [(2, 0), (4, 2), (4, 3)])
+def test_invalid_window_sizes(pipeline, word_level, max_length, stride):
+    for tokenizer in (pipeline, word_level):
+        with pytest.raises(ValueError):
+            tokenizer.encode_windows("a b c", max_length=max_length, stride=stride)
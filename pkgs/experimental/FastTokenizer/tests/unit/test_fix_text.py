@@ -0,0 +1,45 @@
+import pytest
+
+from fasttokenizer import fix_text, fix_text_batch
+
+
+@pytest.mark.unit
+class TestFixText:
+    """Unit tests for mojibake and encoding repair."""
+
+    @pytest.mark.parametrize(
+        "broken, fixed",
+        [
+            ("cafÃ©", "café"),
+            ("â€œquotedâ€\x9d", "“quoted”"),
+            ("The Mona Lisa doesnÃ¢â‚¬â„¢t have eyebrows.", "The Mona Lisa doesn’t have eyebrows."),
+            ("ÐŸÑ€Ð¸Ð²ÐµÑ‚ Ð¼Ð¸Ñ€", "Привет мир"),
+            ("It\x92s \x93here\x94", "It’s “here”"),
+            ("Fish &amp; chips", "Fish & chips"),
+            ("&amp;quot;double&amp;quot;", '"double"'),
+            ("caf&Atilde;&copy;", "café"),
+        ],
+    )
+    def test_repairs(self, broken, fixed):
+        assert fix_text(broken) == fixed
+
+    @pytest.mark.parametrize(
+        "text",
+        ["naïve façade", "Ünïcödé “quotes” — dashes", "日本語のテキスト", "1 < 2 &amp; 3 > 2", "plain ascii", ""],
+    )
+    def test_correct_text_unchanged(self, text):
+        assert fix_text(text) == text
+
+    def test_options(self):
+        assert fix_text("cafÃ© &amp;", fix_encoding=False) == "cafÃ© &"
+        assert fix_text("It\x92s", fix_c1_controls=False) == "It\x92s"
+        assert fix_text("<b>&amp;</b>") == "<b>&amp;</b>"
+        assert fix_text("<b>&amp;</b>", unescape_html="always") == "<b>&</b>"
+        assert fix_text("&amp;", unescape_html="never") == "&amp;"
+        with pytest.raises(ValueError):
+            fix_text("x", unescape_html="sometimes")
+
+    def test_batch(self):
+        texts = ["cafÃ©", "fine", "It\x92s"]
+        assert fix_text_batch(texts) == [fix_text(t) for t in texts]
+        assert fix_text_batch(texts, fix_encoding=False)[0] == "cafÃ©"
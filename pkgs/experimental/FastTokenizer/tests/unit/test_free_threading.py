@@ -0,0 +1,37 @@
+import sys
+import sysconfig
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+
+from fasttokenizer import RegexTokenizer, StringCache, TokenizerPipeline, Vocab
+
+TEXTS = [f"the cat sat on mat number {i % 13}" for i in range(4000)]
+
+
+@pytest.mark.unit
+def test_import_keeps_gil_disabled():
+    if not sysconfig.get_config_var("Py_GIL_DISABLED"):
+        pytest.skip("needs a free-threaded build of CPython")
+    assert not sys._is_gil_enabled()
+
+
+@pytest.mark.unit
+def test_string_cache_shared_by_threads():
+    tokenizer = RegexTokenizer(r"\w+")
+    cache = StringCache()
+    batches = [TEXTS[i : i + 200] for i in range(0, len(TEXTS), 200)]
+    with ThreadPoolExecutor(max_workers=8) as pool:
+        results = list(pool.map(lambda batch: tokenizer.tokenize_batch(batch, intern=cache), batches))
+    assert [row for batch in results for row in batch] == tokenizer.tokenize_batch(TEXTS)
+    assert len(cache) == 6 + 13
+    first, last = results[0][0], results[-1][0]
+    assert first[1] is last[1]
+
+
+@pytest.mark.unit
+def test_parallel_encode_with_shared_vocab():
+    pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), vocab=Vocab())
+    with ThreadPoolExecutor(max_workers=8) as pool:
+        results = list(pool.map(pipeline.encode, TEXTS))
+    assert results == [pipeline.encode(text) for text in TEXTS]
@@ -0,0 +1,53 @@
+import pytest
+
+from fasttokenizer import GraphemeTokenizer, TokenizerIO, Vocab
+
+FAMILY = "\U0001f468\u200d\U0001f469\u200d\U0001f467"  # man ZWJ woman ZWJ girl
+FLAG = "\U0001f1e9\U0001f1ea"  # regional indicators D E
+E_ACUTE = "e\u0301"  # e + combining acute accent
+THUMBS = "\U0001f44d\U0001f3fd"  # thumbs up + skin tone
+
+
+@pytest.mark.unit
+def test_clusters_are_single_tokens():
+    text = f"{FAMILY} {FLAG}{E_ACUTE}{THUMBS}\r\n"
+    assert GraphemeTokenizer().tokenize(text) == [FAMILY, " ", FLAG, E_ACUTE, THUMBS, "\r\n"]
+
+
+@pytest.mark.unit
+def test_skip_whitespace():
+    assert GraphemeTokenizer(skip_whitespace=True).tokenize("a b\tc") == ["a", "b", "c"]
+
+
+@pytest.mark.unit
+def test_offsets():
+    tokenizer = GraphemeTokenizer()
+    text = f"a{E_ACUTE}{FLAG}"
+    assert tokenizer.tokenize_with_offsets(text) == [("a", 0, 1), (E_ACUTE, 1, 3), (FLAG, 3, 5)]
+    assert tokenizer.tokenize_with_offsets(text, unit="byte")[-1] == (FLAG, 4, 12)
+
+
+@pytest.mark.unit
+def test_count_and_truncate():
+    tokenizer = GraphemeTokenizer()
+    text = f"{FAMILY}{E_ACUTE}xyz"
+    assert tokenizer.count(text) == 5
+    assert len(text) > 5
+    assert tokenizer.truncate(text, 2) == FAMILY + E_ACUTE
+    assert tokenizer.truncate(text, 10) == text
+
+
+@pytest.mark.unit
+def test_encode_decode():
+    tokenizer = GraphemeTokenizer(vocab=Vocab())
+    ids = tokenizer.encode(f"{FLAG}{FLAG}!")
+    assert ids == [0, 0, 1]
+    assert tokenizer.decode(ids) == f"{FLAG}{FLAG}!"
+
+
+@pytest.mark.unit
+def test_usable_with_tokenizer_io(tmp_path):
+    path = tmp_path / "emoji.txt"
+    path.write_text(f"{FAMILY} {FLAG}", encoding="utf-8")
+    batches = TokenizerIO().tokenize_file(str(path), GraphemeTokenizer(skip_whitespace=True))
+    assert [t for batch in batches for t in batch] == [FAMILY, FLAG]
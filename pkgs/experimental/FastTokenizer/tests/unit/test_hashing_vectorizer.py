@@ -0,0 +1,54 @@
+import pytest
+
+from fasttokenizer import HashingVectorizer, WhitespaceTokenizer
+
+
+@pytest.mark.unit
+def test_columns_match_murmurhash3():
+    vectorizer = HashingVectorizer(WhitespaceTokenizer(), n_features=2**31 - 1)
+    # murmurhash3_32(b"foo", seed=0) == -156908512
+    assert vectorizer.token_column("foo") == (156908512, -1)
+    assert vectorizer.token_column("hello") == (613153351, 1)
+    assert HashingVectorizer(WhitespaceTokenizer(), seed=1).token_column("foo") != (
+        HashingVectorizer(WhitespaceTokenizer()).token_column("foo")
+    )
+
+
+@pytest.mark.unit
+def test_transform_counts_and_signs():
+    vectorizer = HashingVectorizer(WhitespaceTokenizer(), n_features=16, norm=None)
+    matrix = vectorizer.transform(["foo foo hello", ""])
+    assert matrix.shape == (2, 16)
+    foo, sign = vectorizer.token_column("foo")
+    hello, _ = vectorizer.token_column("hello")
+    row = dict(matrix.row(0))
+    assert row[foo] == 2 * sign
+    assert row[hello] == 1
+    assert matrix.row(1) == []
+
+
+@pytest.mark.unit
+def test_binary_unsigned_and_normalized():
+    tokenizer = WhitespaceTokenizer()
+    binary = HashingVectorizer(tokenizer, n_features=8, alternate_sign=False, binary=True, norm=None)
+    assert all(value == 1.0 for _, value in binary.transform(["a a b c c c"]).row(0))
+    normalized = HashingVectorizer(tokenizer, n_features=1024).transform(["a b c d"])
+    assert sum(v * v for v in normalized.data) == pytest.approx(1.0)
+
+
+@pytest.mark.unit
+def test_stateless_batches_agree():
+    vectorizer = HashingVectorizer(WhitespaceTokenizer(), n_features=64)
+    texts = ["one two", "three four five", "one"]
+    whole = vectorizer.transform(texts)
+    parts = [vectorizer.transform([text]) for text in texts]
+    assert [whole.row(i) for i in range(3)] == [part.row(0) for part in parts]
+    assert (vectorizer.n_features, vectorizer.seed) == (64, 0)
+
+
+@pytest.mark.unit
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        HashingVectorizer(WhitespaceTokenizer(), n_features=0)
+    with pytest.raises(ValueError):
+        HashingVectorizer(WhitespaceTokenizer(), norm="max")
@@ -0,0 +1,273 @@
+import json
+
+import pytest
+
+from fasttokenizer import BpeTokenizer, Tokenizer, TokenizerPipeline
+
+from conftest import bytes_to_unicode
+
+
+def special(token, id):
+    return {"id": id, "content": token, "special": True, "lstrip": False, "rstrip": False}
+
+
+BERT_VOCAB = ["[PAD]", "[UNK]", "[CLS]", "[SEP]", "hello", "world", "##s", ",", "!", "中"]
+
+
+@pytest.fixture
+def bert():
+    vocab = {token: i for i, token in enumerate(BERT_VOCAB)}
+    return Tokenizer.from_str(
+        json.dumps(
+            {
+                "added_tokens": [special(t, vocab[t]) for t in BERT_VOCAB[:4]],
+                "normalizer": {"type": "BertNormalizer", "lowercase": True},
+                "pre_tokenizer": {"type": "BertPreTokenizer"},
+                "model": {"type": "WordPiece", "vocab": vocab, "unk_token": "[UNK]"},
+                "post_processor": {
+                    "type": "TemplateProcessing",
+                    "single": [
+                        {"SpecialToken": {"id": "[CLS]", "type_id": 0}},
+                        {"Sequence": {"id": "A", "type_id": 0}},
+                        {"SpecialToken": {"id": "[SEP]", "type_id": 0}},
+                    ],
+                    "pair": [
+                        {"SpecialToken": {"id": "[CLS]", "type_id": 0}},
+                        {"Sequence": {"id": "A", "type_id": 0}},
+                        {"SpecialToken": {"id": "[SEP]", "type_id": 0}},
+                        {"Sequence": {"id": "B", "type_id": 1}},
+                        {"SpecialToken": {"id": "[SEP]", "type_id": 1}},
+                    ],
+                    "special_tokens": {
+                        "[CLS]": {"id": "[CLS]", "ids": [2], "tokens": ["[CLS]"]},
+                        "[SEP]": {"id": "[SEP]", "ids": [3], "tokens": ["[SEP]"]},
+                    },
+                },
+                "decoder": {"type": "WordPiece", "prefix": "##", "cleanup": True},
+            }
+        )
+    )
+
+
+GPT2_MERGES = ["h e", "l l", "he ll", "hell o", "Ġ w"]
+
+
+@pytest.fixture
+def gpt2_vocab():
+    tokens = list(bytes_to_unicode().values())
+    tokens += [m.replace(" ", "") for m in GPT2_MERGES]
+    return {token: i for i, token in enumerate(tokens)}
+
+
+@pytest.fixture
+def gpt2(gpt2_vocab):
+    return Tokenizer.from_str(
+        json.dumps(
+            {
+                "pre_tokenizer": {
+                    "type": "ByteLevel",
+                    "add_prefix_space": False,
+                    "use_regex": True,
+                },
+                "model": {"type": "BPE", "vocab": gpt2_vocab, "merges": GPT2_MERGES},
+                "post_processor": {"type": "ByteLevel", "trim_offsets": True},
+                "decoder": {"type": "ByteLevel"},
+            }
+        )
+    )
+
+
+@pytest.fixture
+def llama():
+    """SentencePiece-style BPE with byte fallback, as in Llama's tokenizer.json."""
+    tokens = ["<unk>", "<s>", "</s>"] + [f"<0x{b:02X}>" for b in range(256)]
+    tokens += ["▁", "h", "e", "l", "o", "w", "r", "d", "▁h", "ll", "▁he", "▁hell", "▁hello"]
+    vocab = {token: i for i, token in enumerate(tokens)}
+    return Tokenizer.from_str(
+        json.dumps(
+            {
+                "added_tokens": [special("<s>", 1), special("</s>", 2)],
+                "normalizer": {
+                    "type": "Sequence",
+                    "normalizers": [
+                        {"type": "Prepend", "prepend": "▁"},
+                        {"type": "Replace", "pattern": {"String": " "}, "content": "▁"},
+                    ],
+                },
+                "pre_tokenizer": None,
+                "model": {
+                    "type": "BPE",
+                    "vocab": vocab,
+                    "merges": [["▁", "h"], ["l", "l"], ["▁h", "e"], ["▁he", "ll"], ["▁hell", "o"]],
+                    "unk_token": "<unk>",
+                    "fuse_unk": True,
+                    "byte_fallback": True,
+                },
+                "decoder": {
+                    "type": "Sequence",
+                    "decoders": [
+                        {"type": "Replace", "pattern": {"String": "▁"}, "content": " "},
+                        {"type": "ByteFallback"},
+                        {"type": "Fuse"},
+                        {"type": "Strip", "content": " ", "start": 1, "stop": 0},
+                    ],
+                },
+            }
+        )
+    )
+
+
+@pytest.mark.unit
+class TestBertTokenizerJson:
+    """A BERT-style tokenizer.json: BertNormalizer, WordPiece and a template."""
+
+    def test_tokenize(self, bert):
+        assert bert.tokenize("Hello, Worlds!") == ["hello", ",", "world", "##s", "!"]
+
+    def test_offsets(self, bert):
+        text = "Héllo, worlds"
+        assert bert.tokenize_with_offsets(text) == [
+            ("hello", 0, 5),
+            (",", 5, 6),
+            ("world", 7, 12),
+            ("##s", 12, 13),
+        ]
+
+    def test_encode_with_template(self, bert):
+        assert bert.encode("hello world") == [2, 4, 5, 3]
+        assert bert.encode("hello world", add_special_tokens=False) == [4, 5]
+        assert bert.encode("hello", pair="world") == [2, 4, 3, 5, 3]
+        assert bert.encode_batch(["hello", "中"]) == [[2, 4, 3], [2, 9, 3]]
+
+    def test_added_tokens_are_matched_first(self, bert):
+        assert bert.encode("hello [SEP] xyz", add_special_tokens=False) == [4, 3, 1]
+
+    def test_decode(self, bert):
+        ids = bert.encode("Hello, worlds!")
+        assert bert.decode(ids) == "hello, worlds!"
+        assert bert.decode(ids, skip_special_tokens=False) == "[CLS] hello, worlds! [SEP]"
+
+    def test_vocab(self, bert):
+        assert bert.token_to_id("[SEP]") == 3
+        assert bert.id_to_token(6) == "##s"
+        assert bert.vocab_size() == len(BERT_VOCAB)
+
+
+@pytest.mark.unit
+class TestByteLevelTokenizerJson:
+    """A GPT-2 style tokenizer.json gives the same ids as BpeTokenizer."""
+
+    @pytest.mark.parametrize("text", ["hello world", "a  b\n\nc", "héllo wörld 🎉"])
+    def test_matches_bpe_tokenizer(self, gpt2, gpt2_vocab, text):
+        merges = [tuple(m.split(" ")) for m in GPT2_MERGES]
+        bpe = BpeTokenizer(gpt2_vocab, merges)
+        assert gpt2.encode(text) == bpe.encode(text)
+        assert gpt2.decode(gpt2.encode(text)) == text
+
+    def test_offsets(self, gpt2):
+        assert gpt2.tokenize_with_offsets("hello world")[:2] == [
+            ("hello", 0, 5),
+            ("Ġw", 5, 7),
+        ]
+
+
+@pytest.mark.unit
+class TestSentencePieceBpeTokenizerJson:
+    """A Llama-style tokenizer.json with byte fallback and a decoder sequence."""
+
+    def test_tokenize(self, llama):
+        assert llama.tokenize("hello world") == ["▁hello", "▁", "w", "o", "r", "l", "d"]
+
+    def test_byte_fallback(self, llama):
+        assert llama.tokenize("hé") == ["▁h", "<0xC3>", "<0xA9>"]
+
+    def test_decode(self, llama):
+        for text in ["hello world", "hé wörld"]:
+            assert llama.decode(llama.encode(text)) == text
+        # Every stretch of text between added tokens gets the prefix.
+        ids = llama.encode("<s>hello")
+        assert llama.tokenize("<s>hello") == ["<s>", "▁hello"]
+        assert llama.decode(ids, skip_special_tokens=False) == "<s> hello"
+        assert llama.decode(ids) == "hello"
+
+
+@pytest.mark.unit
+def test_unigram_with_metaspace():
+    tokenizer = Tokenizer.from_str(
+        json.dumps(
+            {
+                "normalizer": {"type": "NFKC"},
+                "pre_tokenizer": {
+                    "type": "Metaspace",
+                    "replacement": "▁",
+                    "prepend_scheme": "always",
+                    "split": True,
+                },
+                "model": {
+                    "type": "Unigram",
+                    "unk_id": 0,
+                    "vocab": [
+                        ["<unk>", 0.0],
+                        ["▁hello", -1.0],
+                        ["▁world", -1.5],
+                        ["▁", -2.0],
+                        ["x", -3.0],
+                    ],
+                },
+                "decoder": {"type": "Metaspace", "replacement": "▁", "prepend_scheme": "always"},
+            }
+        )
+    )
+    assert tokenizer.tokenize("hello ｗｏｒｌｄ zz") == ["▁hello", "▁world", "▁", "<unk>"]
+    assert tokenizer.decode(tokenizer.encode("hello world")) == "hello world"
+
+
+@pytest.mark.unit
+def test_word_level_with_split_and_digits():
+    vocab = {"[UNK]": 0, "call": 1, "9": 2, "1": 3, "!": 4, "a": 5, " ": 6, " b": 7}
+    tokenizer = Tokenizer.from_str(
+        json.dumps(
+            {
+                "normalizer": {"type": "Lowercase"},
+                "pre_tokenizer": {
+                    "type": "Sequence",
+                    "pretokenizers": [
+                        {
+                            "type": "Split",
+                            "pattern": {"Regex": r" ?\p{L}++|\p{N}+|[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+"},
+                            "behavior": "Isolated",
+                            "invert": False,
+                        },
+                        {"type": "Digits", "individual_digits": True},
+                    ],
+                },
+                "model": {"type": "WordLevel", "vocab": vocab, "unk_token": "[UNK]"},
+            }
+        )
+    )
+    assert tokenizer.tokenize("CALL 911!?") == ["call", " ", "9", "1", "1", "[UNK]"]
+    assert tokenizer.tokenize("a  b") == ["a", " ", " b"]
+
+
+@pytest.mark.unit
+def test_tokenizer_in_pipeline(bert):
+    pipeline = TokenizerPipeline(bert)
+    assert pipeline.tokenize("Hello world") == ["hello", "world"]
+
+
+@pytest.mark.unit
+def test_from_file(tmp_path):
+    path = tmp_path / "tokenizer.json"
+    path.write_text(
+        json.dumps({"model": {"type": "WordLevel", "vocab": {"a": 0}, "unk_token": "a"}}),
+        encoding="utf-8",
+    )
+    assert Tokenizer.from_file(str(path)).encode("anything") == [0]
+
+
+@pytest.mark.unit
+def test_unsupported_component():
+    with pytest.raises(ValueError):
+        Tokenizer.from_str(
+            json.dumps({"normalizer": {"type": "Nope"}, "model": {"type": "WordLevel", "vocab": {}}})
+        )
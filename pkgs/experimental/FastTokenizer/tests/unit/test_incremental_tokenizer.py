@@ -0,0 +1,56 @@
+import random
+
+import pytest
+
+from fasttokenizer import IncrementalTokenizer, RegexTokenizer, WhitespaceTokenizer
+
+TEXT = "Streaming  tokens from a model, one fragment at a time: naïve café!\nDone."
+
+
+def fragments(text, seed):
+    rng = random.Random(seed)
+    pieces, start = [], 0
+    while start < len(text):
+        end = start + rng.randint(1, 6)
+        pieces.append(text[start:end])
+        start = end
+    return pieces
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("seed", range(5))
+def test_fragments_match_whole_text(seed):
+    tokenizer = RegexTokenizer(r"\w+|[^\w\s]")
+    stream = IncrementalTokenizer(tokenizer, with_offsets=True)
+    tokens = []
+    for piece in fragments(TEXT, seed):
+        tokens += stream.push(piece)
+    tokens += stream.finish()
+    assert tokens == tokenizer.tokenize_with_offsets(TEXT)
+    assert all(TEXT[start:end] == token for token, start, end in tokens)
+
+
+@pytest.mark.unit
+def test_tokens_are_held_back_until_complete():
+    stream = IncrementalTokenizer(WhitespaceTokenizer())
+    assert stream.push("Hel") == []
+    assert stream.push("lo wor") == ["Hello"]
+    assert stream.pending == " wor"
+    assert stream.push("ld") == []
+    assert stream.finish() == ["world"]
+    assert stream.pending == ""
+
+
+@pytest.mark.unit
+def test_finish_starts_a_new_stream():
+    stream = IncrementalTokenizer(WhitespaceTokenizer(), with_offsets=True, unit="byte")
+    assert stream.push("é a ") == [("é", 0, 2), ("a", 3, 4)]
+    assert stream.finish() == []
+    assert stream.push("b c") == [("b", 0, 1)]
+    assert stream.finish() == [("c", 2, 3)]
+
+
+@pytest.mark.unit
+def test_invalid_unit():
+    with pytest.raises(ValueError):
+        IncrementalTokenizer(WhitespaceTokenizer(), unit="word")
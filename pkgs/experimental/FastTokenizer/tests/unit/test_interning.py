@@ -0,0 +1,51 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer, StringCache, WhitespaceTokenizer
+
+TEXT = "the cat and the dog and the bird"
+
+
+@pytest.mark.unit
+def test_repeated_tokens_share_one_string():
+    tokens = WhitespaceTokenizer().tokenize(TEXT, intern=True)
+    assert tokens == TEXT.split()
+    assert tokens[0] is tokens[3] is tokens[6]
+    assert tokens[2] is tokens[5]
+
+
+@pytest.mark.unit
+def test_batch_shares_strings_across_inputs():
+    rows = RegexTokenizer(r"\w+").tokenize_batch(["the cat", "the dog"], intern=True)
+    assert rows == [["the", "cat"], ["the", "dog"]]
+    assert rows[0][0] is rows[1][0]
+
+
+@pytest.mark.unit
+def test_cache_is_reused_across_calls():
+    tokenizer = WhitespaceTokenizer()
+    cache = StringCache()
+    first = tokenizer.tokenize("the cat", intern=cache)
+    second = tokenizer.tokenize_batch(["the dog"], intern=cache)
+    assert first[0] is second[0][0]
+    assert len(cache) == 3
+    assert "dog" in cache
+    cache.clear()
+    assert len(cache) == 0
+
+
+@pytest.mark.unit
+def test_cache_size_limit():
+    cache = StringCache(max_size=2)
+    tokens = WhitespaceTokenizer().tokenize(TEXT, intern=cache)
+    assert tokens == TEXT.split()
+    assert len(cache) == 2
+    assert tokens[0] is tokens[3]
+    assert "and" not in cache
+
+
+@pytest.mark.unit
+def test_intern_argument_validation():
+    tokenizer = WhitespaceTokenizer()
+    assert tokenizer.tokenize(TEXT, intern=False) == TEXT.split()
+    with pytest.raises(TypeError):
+        tokenizer.tokenize(TEXT, intern="yes")
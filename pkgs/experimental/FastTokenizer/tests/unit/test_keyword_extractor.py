@@ -0,0 +1,99 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import (
+    KeywordExtractor,
+    Normalizer,
+    RegexTokenizer,
+    StopWordFilter,
+    TokenizerPipeline,
+)
+
+TEXT = (
+    "Compatibility of systems of linear constraints over the set of natural numbers. "
+    "Criteria of compatibility of a system of linear Diophantine equations, strict inequations, "
+    "and nonstrict inequations are considered. Upper bounds for components of a minimal set of "
+    "solutions and algorithms of construction of minimal generating sets of solutions for all "
+    "types of systems are given."
+)
+
+
+@pytest.mark.unit
+class TestRake:
+    """Unit tests for RAKE keyword extraction."""
+
+    def test_paper_example(self):
+        # The example abstract of the RAKE paper (Rose et al., 2010).
+        keywords = KeywordExtractor("rake", max_ngram=4, top_k=4).extract(TEXT)
+        assert [phrase for phrase, _ in keywords] == [
+            "linear diophantine equations",
+            "minimal generating sets",
+            "linear constraints",
+            "minimal set",
+        ]
+        assert keywords[0][1] == pytest.approx(8.5)
+        assert [score for _, score in keywords] == sorted((s for _, s in keywords), reverse=True)
+
+    def test_phrases_break_at_stop_words_and_punctuation(self):
+        extractor = KeywordExtractor("rake", stop_words=StopWordFilter(words=["and"]))
+        phrases = {phrase for phrase, _ in extractor.extract("red apples and green pears, ripe plums")}
+        assert phrases == {"red apples", "green pears", "ripe plums"}
+
+    def test_max_ngram(self):
+        extractor = KeywordExtractor("rake", max_ngram=1)
+        assert [p for p, _ in extractor.extract("big data pipelines and clusters")] == ["clusters"]
+
+
+@pytest.mark.unit
+class TestYake:
+    """Unit tests for YAKE keyword extraction."""
+
+    def test_ranking(self):
+        text = (
+            "Google is acquiring Kaggle, a platform that hosts data science and machine learning "
+            "competitions. Kaggle co-founder Anthony Goldbloom said Google will keep Kaggle running. "
+            "Data science competitions on Kaggle attract many machine learning experts."
+        )
+        words = KeywordExtractor(max_ngram=1, top_k=3).extract(text)
+        assert [word for word, _ in words] == ["kaggle", "google", "platform"]
+        keywords = KeywordExtractor(top_k=5).extract(text)
+        assert len(keywords) == 5
+        scores = [score for _, score in keywords]
+        assert scores == sorted(scores) and scores[0] > 0
+        stop_words = StopWordFilter("en")
+        for phrase, _ in keywords:
+            parts = phrase.split()
+            assert parts[0] not in stop_words and parts[-1] not in stop_words
+
+    def test_empty(self):
+        assert KeywordExtractor().extract("") == []
+        assert KeywordExtractor().extract("the and of, to") == []
+
+
+@pytest.mark.unit
+class TestKeywordExtractor:
+    """Unit tests for the shared behaviour of KeywordExtractor."""
+
+    def test_pipeline_tokenizer(self):
+        pipeline = TokenizerPipeline(RegexTokenizer(r"\w+|[^\w\s]"), normalizer=Normalizer(lowercase=True))
+        extractor = KeywordExtractor("rake", tokenizer=pipeline)
+        assert extractor.extract("Deep Learning, and Neural Networks")[0][0] in {"deep learning", "neural networks"}
+
+    def test_batch_matches_single(self):
+        extractor = KeywordExtractor(top_k=3)
+        texts = [TEXT, "Fast tokenizers speed up search indexing.", ""]
+        assert extractor.extract_batch(texts) == [extractor.extract(t) for t in texts]
+        assert len(extractor.extract(TEXT, top_k=1)) == 1
+
+    def test_invalid(self):
+        with pytest.raises(ValueError):
+            KeywordExtractor("textrank")
+        with pytest.raises(ValueError):
+            KeywordExtractor(max_ngram=0)
+
+    def test_pickle(self):
+        extractor = KeywordExtractor("rake", language="de", max_ngram=2, top_k=5)
+        copy = pickle.loads(pickle.dumps(extractor))
+        assert (copy.method, copy.max_ngram, copy.top_k) == ("rake", 2, 5)
+        assert copy.extract(TEXT) == extractor.extract(TEXT)
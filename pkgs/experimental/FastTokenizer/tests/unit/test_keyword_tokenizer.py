@@ -0,0 +1,47 @@
+import pytest
+
+from fasttokenizer import KeywordTokenizer, TokenizerPipeline
+
+
+@pytest.mark.unit
+def test_leftmost_longest():
+    tokenizer = KeywordTokenizer(["New York", "New York City", "York"])
+    assert tokenizer.tokenize_with_offsets("I love New York City and York.") == [
+        ("New York City", 7, 20),
+        ("York", 25, 29),
+    ]
+
+
+@pytest.mark.unit
+def test_whole_words():
+    text = "concatenate the cat"
+    assert KeywordTokenizer(["cat"]).tokenize_with_offsets(text) == [("cat", 16, 19)]
+    assert len(KeywordTokenizer(["cat"], whole_words=False).tokenize(text)) == 2
+
+
+@pytest.mark.unit
+def test_case_insensitive_unicode():
+    tokenizer = KeywordTokenizer(["münchen", "straße"], case_insensitive=True)
+    text = "In MÜNCHEN an der Straße"
+    assert tokenizer.tokenize_with_offsets(text) == [("MÜNCHEN", 3, 10), ("Straße", 18, 24)]
+    assert KeywordTokenizer(["münchen"]).tokenize(text) == []
+
+
+@pytest.mark.unit
+def test_labels_and_extract():
+    tokenizer = KeywordTokenizer({"Acme Corp": "ORG", "Paris": "LOC"}, case_insensitive=True)
+    assert len(tokenizer) == 2
+    assert tokenizer.extract("acme corp opened in PARIS") == [
+        ("acme corp", 0, 9, "ORG"),
+        ("PARIS", 20, 25, "LOC"),
+    ]
+    assert tokenizer.extract_batch(["Paris", "nothing"]) == [[("Paris", 0, 5, "LOC")], []]
+    assert KeywordTokenizer(["a b"]).extract("x a b") == [("a b", 2, 5, "a b")]
+
+
+@pytest.mark.unit
+def test_empty_keyword_and_pipeline():
+    with pytest.raises(ValueError):
+        KeywordTokenizer(["ok", ""])
+    pipeline = TokenizerPipeline(KeywordTokenizer(["machine learning"]))
+    assert pipeline.tokenize("Machine learning and machine learning") == ["machine learning"]
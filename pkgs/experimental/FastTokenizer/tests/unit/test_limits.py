@@ -0,0 +1,108 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    RegexTokenizer,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+)
+
+
+@pytest.mark.unit
+class TestRegexLimits:
+    """Unit tests for the input, token and match limits of RegexTokenizer."""
+
+    def test_max_input_length(self):
+        tokenizer = RegexTokenizer(r"\w+", max_input_length=10)
+        assert tokenizer.tokenize("short text") == ["short", "text"]
+        with pytest.raises(ValueError, match="max_input_length=10"):
+            tokenizer.tokenize("a bit too long")
+        with pytest.raises(ValueError):
+            tokenizer.tokenize_batch(["ok", "a bit too long"])
+
+    def test_max_token_length(self):
+        tokenizer = RegexTokenizer(r"\S+", max_token_length=8)
+        assert tokenizer.tokenize("fine words") == ["fine", "words"]
+        with pytest.raises(ValueError, match="at byte 3 exceeds max_token_length=8"):
+            tokenizer.tokenize("ok " + "x" * 100)
+        with pytest.raises(ValueError):
+            tokenizer.tokenize_with_offsets("x" * 9)
+
+    def test_split_and_captures(self):
+        split = RegexTokenizer(r",", mode="split", max_token_length=3)
+        assert split.tokenize("a,bc,def") == ["a", "bc", "def"]
+        with pytest.raises(ValueError):
+            split.tokenize("a,long")
+        captures = RegexTokenizer(r"(?P<word>[a-z]+)=\d+", mode="captures", max_token_length=3)
+        assert captures.tokenize_with_types("ab=12345") == [("ab", "word")]
+        with pytest.raises(ValueError):
+            captures.tokenize_with_types("abcd=1")
+
+    def test_max_matches(self):
+        tokenizer = RegexTokenizer([r"\d+", r"[a-z]+"], max_matches=3)
+        assert tokenizer.tokenize("a 1 b") == ["a", "1", "b"]
+        with pytest.raises(ValueError, match="max_matches=3"):
+            tokenizer.tokenize("a 1 b 2")
+        with pytest.raises(ValueError):
+            RegexTokenizer(r".", max_matches=100).tokenize("x" * 101)
+
+    def test_encode(self):
+        tokenizer = RegexTokenizer(r"\w+", vocab=Vocab(["hi"]), max_input_length=5)
+        assert tokenizer.encode("hi") == [0]
+        with pytest.raises(ValueError):
+            tokenizer.encode("hi there")
+
+    def test_unlimited_by_default(self):
+        tokenizer = RegexTokenizer(r"\S+")
+        assert tokenizer.limits == {"max_input_length": None, "max_token_length": None, "max_matches": None}
+        assert tokenizer.tokenize("x" * 100_000) == ["x" * 100_000]
+
+    def test_pickle(self):
+        tokenizer = RegexTokenizer(r"\w+", max_input_length=100, max_token_length=10, max_matches=5)
+        copy = pickle.loads(pickle.dumps(tokenizer))
+        assert copy.limits == tokenizer.limits
+        assert RegexTokenizer.from_json(tokenizer.to_json()).limits == tokenizer.limits
+
+
+@pytest.mark.unit
+class TestPipelineLimits:
+    """Unit tests for the limits of TokenizerPipeline."""
+
+    def test_own_limits(self):
+        pipeline = TokenizerPipeline(
+            WhitespaceTokenizer(),
+            normalizer=Normalizer(lowercase=True),
+            max_input_length=20,
+            max_token_length=5,
+        )
+        assert pipeline.tokenize("Hello World") == ["hello", "world"]
+        with pytest.raises(ValueError, match="max_input_length"):
+            pipeline.tokenize("x " * 20)
+        with pytest.raises(ValueError, match="max_token_length"):
+            pipeline.tokenize("tokenizer")
+        with pytest.raises(ValueError):
+            pipeline.tokenize_with_offsets("tokenizer", original=True)
+
+    def test_tokenizer_limits(self):
+        pipeline = TokenizerPipeline(
+            RegexTokenizer(r"\w+", max_matches=2),
+            pre_tokenizer=RegexTokenizer(r"\S+", max_token_length=6),
+            vocab=Vocab(),
+        )
+        assert pipeline.tokenize("ab-cd ef") == ["ab", "cd", "ef"]
+        with pytest.raises(ValueError, match="max_matches"):
+            pipeline.tokenize("a-b-c")
+        with pytest.raises(ValueError, match="max_token_length"):
+            pipeline.encode("ok toolong")
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["ok", "toolong"])
+
+    def test_pickle(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), max_input_length=10)
+        copy = pickle.loads(pickle.dumps(pipeline))
+        assert copy.limits == {"max_input_length": 10, "max_token_length": None}
+        with pytest.raises(ValueError):
+            copy.tokenize("far too long for it")
@@ -0,0 +1,77 @@
+import logging
+
+import pytest
+
+from fasttokenizer import (
+    RegexTokenizer,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+    get_log_level,
+    set_log_level,
+)
+
+
+class Collector(logging.Handler):
+    def __init__(self):
+        super().__init__(level=1)
+        self.records = []
+
+    def emit(self, record):
+        self.records.append(record)
+
+
+@pytest.fixture
+def records():
+    handler = Collector()
+    logger = logging.getLogger("fasttokenizer")
+    previous = logger.level
+    logger.addHandler(handler)
+    logger.setLevel(1)
+    yield handler.records
+    logger.removeHandler(handler)
+    logger.setLevel(previous)
+    set_log_level("off")
+
+
+@pytest.mark.unit
+class TestLogging:
+    """Unit tests for forwarding Rust log records to Python logging."""
+
+    def test_off_by_default(self, records):
+        assert get_log_level() == "off"
+        RegexTokenizer(r"\w+").tokenize("no noise here")
+        TokenizerPipeline(WhitespaceTokenizer()).tokenize_batch(["a b", "c"])
+        assert records == []
+
+    def test_set_levels(self):
+        for level in ["error", "warning", "info", "debug", "trace", "off"]:
+            set_log_level(level)
+            assert get_log_level() == level
+        set_log_level("WARN")
+        assert get_log_level() == "warning"
+        set_log_level(logging.DEBUG)
+        assert get_log_level() == "debug"
+        set_log_level(None)
+        assert get_log_level() == "off"
+
+    def test_unknown_level(self):
+        with pytest.raises(ValueError, match="Unknown log level 'loud'"):
+            set_log_level("loud")
+
+    def test_batch_span_at_debug(self, records):
+        set_log_level("debug")
+        pipeline = TokenizerPipeline(WhitespaceTokenizer())
+        assert pipeline.tokenize_batch(["a b", "c"]) == [["a", "b"], ["c"]]
+        pipeline.tokenize("not logged below trace")
+        [record] = records
+        assert record.name == "fasttokenizer.pipeline"
+        assert record.levelno == logging.DEBUG
+        assert record.getMessage().startswith("tokenize_batch{inputs=2}: done in ")
+
+    def test_single_string_at_trace(self, records):
+        set_log_level("trace")
+        RegexTokenizer(r"\w+").tokenize("two words")
+        [record] = records
+        assert record.name == "fasttokenizer.regex_tokenizer"
+        assert record.levelno == 5
+        assert record.getMessage().startswith("regex_tokenize{bytes=9}: done in ")
@@ -0,0 +1,64 @@
+import pytest
+
+from fasttokenizer import Normalizer, strip_html, strip_markdown
+
+
+@pytest.mark.unit
+def test_strip_html_tags_and_entities():
+    html = '<p class="x">Fish &amp; chips</p><p>caf&eacute; &#8364;5 &#x1F389;</p>'
+    assert strip_html(html) == "Fish & chips\ncafé €5 🎉"
+
+
+@pytest.mark.unit
+def test_strip_html_skips_scripts_comments_and_styles():
+    html = (
+        "<!DOCTYPE html><html><head><style>p { color: red }</style>"
+        "<script>if (a < b) { alert('</p>') }</SCRIPT></head>"
+        "<body><!-- hidden -->Hello<br/>world</body></html>"
+    )
+    assert strip_html(html) == "Hello\nworld"
+
+
+@pytest.mark.unit
+def test_strip_html_keeps_literal_angle_brackets():
+    assert strip_html("a < b and 3<4, &lt;tag&gt;") == "a < b and 3<4, <tag>"
+    assert strip_html('<a href="x>y">link</a> text') == "link text"
+
+
+@pytest.mark.unit
+def test_strip_html_tables():
+    html = "<table><tr><td>a</td><td>b</td></tr><tr><td>c</td></tr></table>"
+    assert strip_html(html) == "a b\nc"
+
+
+@pytest.mark.unit
+def test_strip_markdown():
+    markdown = (
+        "# Title\n\n"
+        "Some **bold** and _italic_ text with a [link](https://example.com)\n"
+        "and `code`.\n\n"
+        "- item one\n"
+        "- ![alt text](img.png)\n\n"
+        "```python\nprint('hi')\n```\n\n"
+        "> quoted <b>html</b>\n"
+    )
+    assert strip_markdown(markdown) == (
+        "Title\n"
+        "Some bold and italic text with a link and code.\n"
+        "item one\n"
+        "alt text\n"
+        "print('hi')\n"
+        "quoted html"
+    )
+
+
+@pytest.mark.unit
+def test_strip_markdown_table():
+    markdown = "| a | b |\n|---|---|\n| 1 | 2 |\n"
+    assert strip_markdown(markdown) == "a b\n1 2"
+
+
+@pytest.mark.unit
+def test_normalizer_strip_html():
+    normalizer = Normalizer(strip_html=True, collapse_whitespace=True, lowercase=True)
+    assert normalizer.normalize("<h1>Hello</h1>\n<p>World &amp; more</p>") == "hello world & more"
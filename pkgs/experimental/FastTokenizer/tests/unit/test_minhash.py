@@ -0,0 +1,80 @@
+import pytest
+
+from fasttokenizer import (
+    LshIndex,
+    WhitespaceTokenizer,
+    minhash,
+    minhash_batch,
+    minhash_similarity,
+)
+
+BASE = " ".join(f"word{i}" for i in range(100))
+NEAR = " ".join(f"word{i}" for i in range(95)) + " other1 other2 other3 other4 other5"
+FAR = " ".join(f"token{i}" for i in range(100))
+
+
+@pytest.mark.unit
+def test_signature_is_deterministic():
+    signature = minhash(BASE)
+    assert len(signature) == 128
+    assert signature == minhash(BASE.split())
+    assert signature == minhash(WhitespaceTokenizer().tokenize(BASE))
+    assert minhash(BASE, seed=2) != signature
+    assert len(minhash("", num_perm=16)) == 16
+
+
+@pytest.mark.unit
+def test_similarity_estimates_jaccard():
+    a, b, c = minhash(BASE, num_perm=256), minhash(NEAR, num_perm=256), minhash(FAR, num_perm=256)
+    # True Jaccard similarity of BASE and NEAR is 95 / 105.
+    assert minhash_similarity(a, b) == pytest.approx(95 / 105, abs=0.08)
+    assert minhash_similarity(a, c) < 0.05
+    assert minhash_similarity(a, a) == 1.0
+    with pytest.raises(ValueError):
+        minhash_similarity(a, a[:10])
+
+
+@pytest.mark.unit
+def test_shingles_and_batch():
+    assert minhash("a b c", ngram=2) != minhash("c b a", ngram=2)
+    assert minhash("a b c") == minhash("c b a")
+    assert minhash_batch([BASE, NEAR.split()], num_perm=64) == [
+        minhash(BASE, num_perm=64),
+        minhash(NEAR, num_perm=64),
+    ]
+    with pytest.raises(ValueError):
+        minhash(BASE, ngram=0)
+
+
+@pytest.mark.unit
+def test_lsh_index_finds_near_duplicates():
+    index = LshIndex(num_perm=128, threshold=0.8)
+    assert index.bands * index.rows <= 128
+    index.insert("base", minhash(BASE))
+    index.insert("far", minhash(FAR))
+    results = index.query(minhash(NEAR))
+    assert [key for key, _ in results] == ["base"]
+    assert results[0][1] > 0.8
+    assert index.query(minhash("completely unrelated words here")) == []
+    assert len(index) == 2 and "far" in index
+
+
+@pytest.mark.unit
+def test_lsh_index_remove_and_errors():
+    index = LshIndex(num_perm=32, bands=8, rows=4)
+    signature = minhash(BASE, num_perm=32)
+    index.insert("a", signature)
+    assert index.get("a") == signature
+    with pytest.raises(KeyError):
+        index.insert("a", signature)
+    with pytest.raises(ValueError):
+        index.insert("b", minhash(BASE))
+    index.remove("a")
+    assert index.query(signature, verify=False) == []
+    assert index.keys() == []
+    with pytest.raises(KeyError):
+        index.remove("a")
+    with pytest.raises(ValueError):
+        LshIndex(num_perm=32, bands=8, rows=8)
+    with pytest.raises(ValueError):
+        LshIndex(num_perm=32, bands=8)
@@ -0,0 +1,77 @@
+import pickle
+import struct
+
+import pytest
+
+from fasttokenizer import Vocab, WhitespaceTokenizer
+
+TOKENS = ["[UNK]", "the", "cat", "ü", "日本", "the"]
+
+
+@pytest.fixture
+def path(tmp_path):
+    path = str(tmp_path / "vocab.bin")
+    Vocab(TOKENS, unk_token="[UNK]", frozen=True).save_binary(path)
+    return path
+
+
+@pytest.mark.unit
+def test_mapped_matches_copied(path):
+    mapped = Vocab.load_binary(path, mmap=True)
+    copied = Vocab.load_binary(path)
+    assert mapped.memory_mapped and not copied.memory_mapped
+    assert mapped.tokens() == copied.tokens() == ["[UNK]", "the", "cat", "ü", "日本"]
+    assert mapped.to_dict() == copied.to_dict()
+    assert len(mapped) == 5
+    assert "日本" in mapped and "dog" not in mapped
+    assert mapped.token_to_id("cat") == 2
+    assert mapped.id_to_token(4) == "日本"
+    assert mapped.id_to_token(5) is None
+
+
+@pytest.mark.unit
+def test_mapped_vocab_encodes(path):
+    tokenizer = WhitespaceTokenizer(vocab=Vocab.load_binary(path, mmap=True))
+    assert tokenizer.encode("the cat dog") == [1, 2, 0]
+    assert tokenizer.decode([1, 2]) == "the cat"
+
+
+@pytest.mark.unit
+def test_unfrozen_mapped_vocab_grows(tmp_path):
+    path = str(tmp_path / "vocab.bin")
+    Vocab(["a", "b"]).save_binary(path)
+    vocab = Vocab.load_binary(path, mmap=True)
+    assert vocab.add_tokens(["b", "c", "a", "d"]) == [1, 2, 0, 3]
+    assert vocab.tokens() == ["a", "b", "c", "d"]
+    assert Vocab.from_bytes(vocab.to_bytes()).tokens() == ["a", "b", "c", "d"]
+
+
+@pytest.mark.unit
+def test_mapped_vocab_pickles_as_a_copy(path):
+    restored = pickle.loads(pickle.dumps(Vocab.load_binary(path, mmap=True)))
+    assert not restored.memory_mapped
+    assert restored.tokens() == Vocab.load_binary(path).tokens()
+
+
+@pytest.mark.unit
+def test_version_1_files_still_load(tmp_path):
+    tokens = ["x", "yz"]
+    data = b"FTVB" + bytes([1, 0]) + struct.pack("<II", 0, len(tokens))
+    for token in tokens:
+        data += struct.pack("<I", len(token)) + token.encode()
+    path = tmp_path / "old.bin"
+    path.write_bytes(data)
+    assert Vocab.load_binary(str(path)).tokens() == tokens
+    with pytest.raises(ValueError, match="version 1"):
+        Vocab.load_binary(str(path), mmap=True)
+
+
+@pytest.mark.unit
+def test_corrupt_file_is_rejected(path, tmp_path):
+    data = open(path, "rb").read()
+    truncated = tmp_path / "truncated.bin"
+    truncated.write_bytes(data[:-3])
+    with pytest.raises(ValueError):
+        Vocab.load_binary(str(truncated), mmap=True)
+    with pytest.raises(OSError):
+        Vocab.load_binary(str(tmp_path / "missing.bin"), mmap=True)
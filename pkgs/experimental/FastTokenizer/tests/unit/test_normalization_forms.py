@@ -0,0 +1,59 @@
+import pytest
+
+from fasttokenizer import Normalizer, is_normalized, normalize_unicode
+
+COMPOSED = "caf\u00e9"
+DECOMPOSED = "cafe\u0301"
+LIGATURE = "\ufb01ne"  # "fine" written with the "fi" ligature
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "form, text, expected",
+    [
+        ("NFC", DECOMPOSED, COMPOSED),
+        ("NFD", COMPOSED, DECOMPOSED),
+        ("NFKC", LIGATURE + DECOMPOSED, "fine" + COMPOSED),
+        ("NFKD", LIGATURE + COMPOSED, "fine" + DECOMPOSED),
+        ("nfkc", "\u2460", "1"),
+    ],
+)
+def test_normalize_unicode_forms(form, text, expected):
+    assert normalize_unicode(text, form) == expected
+    assert is_normalized(expected, form)
+
+
+@pytest.mark.unit
+def test_normalize_unicode_keeps_case():
+    assert normalize_unicode("Hello, World!") == "Hello, World!"
+    assert normalize_unicode(DECOMPOSED.upper()) == COMPOSED.upper()
+
+
+@pytest.mark.unit
+def test_is_normalized():
+    assert is_normalized(COMPOSED)
+    assert not is_normalized(DECOMPOSED)
+    assert is_normalized(DECOMPOSED, form="NFD")
+    assert not is_normalized(LIGATURE, form="NFKC")
+    assert is_normalized(LIGATURE, form="NFC")
+
+
+@pytest.mark.unit
+def test_unknown_form():
+    with pytest.raises(ValueError):
+        normalize_unicode("x", "NFX")
+    with pytest.raises(ValueError):
+        Normalizer(form="nope")
+
+
+@pytest.mark.unit
+def test_normalizer_form():
+    assert Normalizer().form is None
+    assert Normalizer(normalize_unicode=True).form == "NFC"
+    normalizer = Normalizer(form="NFKC", lowercase=True)
+    assert normalizer.form == "NFKC"
+    assert normalizer.normalize(LIGATURE.upper() + DECOMPOSED) == "fine" + COMPOSED
+    assert normalizer.normalize_unicode("\ufb01NE") == "fiNE"
+    assert normalizer.normalize_unicode(COMPOSED, form="NFD") == DECOMPOSED
+    assert normalizer.is_normalized("fine")
+    assert not normalizer.is_normalized(LIGATURE)
@@ -0,0 +1,90 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import Normalizer, RegexTokenizer, TokenizerPipeline, normalize_numbers
+
+
+@pytest.mark.parametrize(
+    "text,expected",
+    [
+        ("1,234,567.50", "1234567.50"),
+        ("1 234", "1 234"),
+        ("3'500", "3500"),
+        ("12,34", "12,34"),
+        ("v1.2.3 and 1.2.3", "v1.2.3 and 1.2.3"),
+        ("A4 10km 3rd", "A4 10km 3rd"),
+        ("-42.", "-42."),
+    ],
+)
+@pytest.mark.unit
+def test_canonical_numbers(text, expected):
+    assert normalize_numbers(text, dates=None) == expected
+
+
+@pytest.mark.parametrize(
+    "text,expected",
+    [
+        ("2024-3-5", "2024-03-05"),
+        ("2024/03/05", "2024-03-05"),
+        ("03/05/2024", "2024-03-05"),
+        ("3.5.24", "2024-03-05"),
+        ("25/12/99", "1999-12-25"),
+        ("March 5th, 2024", "2024-03-05"),
+        ("5 mar 2024", "2024-03-05"),
+        ("the 1st of Sept. 2024", "the 2024-09-01"),
+        ("2024-02-29 2023-02-29", "2024-02-29 2023-02-29"),
+        ("2024-03/05", "2024-03/05"),
+    ],
+)
+@pytest.mark.unit
+def test_canonical_dates(text, expected):
+    assert normalize_numbers(text, numbers=None) == expected
+
+
+@pytest.mark.unit
+class TestNumberNormalization:
+    """Unit tests for the number and date normalization stage."""
+
+    def test_placeholders(self):
+        text = "Paid $1,200.50 on 03/05/2024 for 3 items (v2.0.1)"
+        assert normalize_numbers(text, "placeholder", "placeholder") == (
+            "Paid $<NUM> on <DATE> for <NUM> items (v2.0.1)"
+        )
+        assert normalize_numbers(text, "placeholder", None) == (
+            "Paid $<NUM> on <NUM>/<NUM>/<NUM> for <NUM> items (v2.0.1)"
+        )
+
+    def test_european_conventions(self):
+        text = "1.234,5 EUR am 05.03.2024"
+        assert normalize_numbers(text, decimal_comma=True, day_first=True) == "1234.5 EUR am 2024-03-05"
+        assert normalize_numbers(text) == "1.234,5 EUR am 2024-05-03"
+
+    def test_invalid_mode(self):
+        with pytest.raises(ValueError, match="Unknown number mode"):
+            normalize_numbers("1", numbers="iso")
+        with pytest.raises(ValueError, match="Unknown number mode"):
+            Normalizer(dates="words")
+
+    def test_normalizer_shields_output(self):
+        normalizer = Normalizer(
+            lowercase=True, remove_punctuation=True, numbers="canonical", dates="placeholder"
+        )
+        assert normalizer.normalize("Due MARCH 5, 2024: pay 1,234.50!") == "due <DATE> pay 1234.50"
+        alignment = normalizer.normalize_with_alignment("Due MARCH 5, 2024")
+        assert alignment.to_original(4, 10) == (4, 17)
+        restored = pickle.loads(pickle.dumps(normalizer))
+        assert restored.normalize("on 3/5/24") == normalizer.normalize("on 3/5/24")
+
+    def test_pipeline_keeps_output_whole(self):
+        normalizer = Normalizer(numbers="canonical", dates="canonical")
+        pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), normalizer=normalizer)
+        text = "on 3/5/24 paid 1,234.5"
+        assert pipeline.tokenize(text) == ["on", "2024-03-05", "paid", "1234.5"]
+        assert pipeline.tokenize_with_offsets(text, original=True)[1:4:2] == [
+            ("2024-03-05", 3, 9),
+            ("1234.5", 15, 22),
+        ]
+        placeholders = Normalizer(numbers="placeholder", dates="placeholder")
+        pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), normalizer=placeholders)
+        assert pipeline.tokenize("paid 12 on 2024-03-05") == ["paid", "<NUM>", "on", "<DATE>"]
@@ -0,0 +1,50 @@
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    RegexTokenizer,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+TEXT = "naïve café 🎉 ok"
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "tokenizer", [WhitespaceTokenizer(), RegexTokenizer(r"\S+")], ids=["whitespace", "regex"]
+)
+def test_char_and_byte_offsets(tokenizer):
+    chars = tokenizer.tokenize_with_offsets(TEXT)
+    assert chars == tokenizer.tokenize_with_offsets(TEXT, unit="char")
+    data = TEXT.encode("utf-8")
+    for (token, start, end), (_, byte_start, byte_end) in zip(
+        chars, tokenizer.tokenize_with_offsets(TEXT, unit="byte")
+    ):
+        assert TEXT[start:end] == token
+        assert data[byte_start:byte_end].decode("utf-8") == token
+    assert tokenizer.tokenize_with_offsets(TEXT, unit="byte")[2] == ("🎉", 13, 17)
+
+
+@pytest.mark.unit
+def test_wordpiece_offsets_point_into_original_text():
+    vocab = {"[UNK]": 0, "naive": 1, "cafe": 2}
+    tokenizer = WordPieceTokenizer(vocab)
+    assert tokenizer.tokenize_with_offsets("Naïve café") == [("naive", 0, 5), ("cafe", 6, 10)]
+    assert tokenizer.tokenize_with_offsets("Naïve café", unit="byte") == [
+        ("naive", 0, 6),
+        ("cafe", 7, 12),
+    ]
+
+
+@pytest.mark.unit
+def test_pipeline_offsets_refer_to_normalized_text():
+    pipeline = TokenizerPipeline(WhitespaceTokenizer(), normalizer=Normalizer(lowercase=True))
+    assert pipeline.tokenize_with_offsets("Ünï Code", unit="byte") == [("ünï", 0, 5), ("code", 6, 10)]
+
+
+@pytest.mark.unit
+def test_unknown_unit():
+    with pytest.raises(ValueError):
+        WhitespaceTokenizer().tokenize_with_offsets("a b", unit="word")
@@ -0,0 +1,122 @@
+import json
+
+import pytest
+
+from fasttokenizer import (
+    BatchEncoding,
+    RegexTokenizer,
+    SpecialTokens,
+    Tokenizer,
+    TokenizerPipeline,
+    Vocab,
+)
+
+
+@pytest.fixture
+def pipeline():
+    special = SpecialTokens(cls="[CLS]", sep="[SEP]", pad="[PAD]")
+    vocab = Vocab(["[PAD]", "[CLS]", "[SEP]", "a", "b", "c", "d", "e"])
+    return TokenizerPipeline(RegexTokenizer(r"\w+"), vocab=vocab, special_tokens=special)
+
+
+@pytest.fixture
+def word_level():
+    vocab = {"[PAD]": 0, "[CLS]": 1, "[SEP]": 2, "a": 3, "b": 4, "c": 5}
+    return Tokenizer.from_str(
+        json.dumps(
+            {
+                "pre_tokenizer": {"type": "WhitespaceSplit"},
+                "model": {"type": "WordLevel", "vocab": vocab, "unk_token": "[PAD]"},
+                "post_processor": {"type": "BertProcessing", "cls": ["[CLS]", 1], "sep": ["[SEP]", 2]},
+                "padding": {"pad_id": 0, "pad_token": "[PAD]"},
+            }
+        )
+    )
+
+
+@pytest.mark.unit
+class TestPipelinePadding:
+    def test_plain_lists_without_options(self, pipeline):
+        assert pipeline.encode_batch(["a b", "c"]) == [[1, 3, 4, 2], [1, 5, 2]]
+
+    def test_pad_to_longest(self, pipeline):
+        batch = pipeline.encode_batch(["a b", "c"], padding="longest")
+        assert isinstance(batch, BatchEncoding)
+        assert batch.input_ids == [[1, 3, 4, 2], [1, 5, 2, 0]]
+        assert batch["attention_mask"] == [[1, 1, 1, 1], [1, 1, 1, 0]]
+        assert len(batch) == 2 and batch.keys() == ["input_ids", "attention_mask"]
+
+    def test_truncate_and_pad_to_max_length(self, pipeline):
+        batch = pipeline.encode_batch(["a b c d e", "a"], max_length=4, padding="max_length")
+        assert batch.input_ids == [[1, 3, 4, 2], [1, 3, 2, 0]]
+        assert batch.attention_mask == [[1, 1, 1, 1], [1, 1, 1, 0]]
+
+    def test_left_padding(self, pipeline):
+        batch = pipeline.encode_batch(["a b", "c"], padding="longest", padding_side="left")
+        assert batch.input_ids[1] == [0, 1, 5, 2]
+        assert batch.attention_mask[1] == [0, 1, 1, 1]
+
+    def test_pair_truncation_strategies(self, pipeline):
+        pairs = [("a b c d", "e")]
+        longest = pipeline.encode_batch(pairs, max_length=6)
+        assert longest.input_ids == [[1, 3, 4, 2, 7, 2]]
+        only_second = pipeline.encode_batch([("a", "b c d e")], max_length=6, truncation="only_second")
+        assert only_second.input_ids == [[1, 3, 2, 4, 5, 2]]
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(pairs, max_length=5, truncation="only_second")
+
+    def test_no_truncation(self, pipeline):
+        batch = pipeline.encode_batch(["a b c"], max_length=3, truncation=None)
+        assert batch.input_ids == [[1, 3, 4, 5, 2]]
+
+    def test_padding_needs_pad_token(self):
+        pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), vocab=Vocab())
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["a b", "c"], padding="longest")
+        assert pipeline.encode_batch(["a b", "c"], padding="longest", pad_id=99).input_ids[1] == [2, 99]
+
+    def test_invalid_options(self, pipeline):
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["a"], padding="max_length")
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["a"], max_length=2, truncation="sideways")
+
+
+@pytest.mark.unit
+def test_tokenizer_json_padding(word_level):
+    batch = word_level.encode_batch(["a b c", "a"], max_length=4, padding="max_length")
+    assert batch.input_ids == [[1, 3, 4, 2], [1, 3, 2, 0]]
+    assert batch.attention_mask == [[1, 1, 1, 1], [1, 1, 1, 0]]
+    assert word_level.encode_batch([("a b", "c")]) == [[1, 3, 4, 2, 5, 2]]
+
+
+@pytest.mark.unit
+class TestNumpyOutput:
+    def test_arrays(self, pipeline):
+        np = pytest.importorskip("numpy")
+        arrays = pipeline.encode_batch(["a b", "c"], padding="longest", return_tensors="np")
+        assert arrays["input_ids"].dtype == np.int64
+        assert arrays["input_ids"].tolist() == [[1, 3, 4, 2], [1, 5, 2, 0]]
+        assert arrays["attention_mask"].tolist() == [[1, 1, 1, 1], [1, 1, 1, 0]]
+
+    def test_int32(self, word_level):
+        np = pytest.importorskip("numpy")
+        arrays = word_level.encode_batch(["a b", "c"], padding="longest", return_tensors="np", dtype="int32")
+        assert arrays["input_ids"].dtype == np.int32
+        assert arrays["input_ids"].shape == (2, 4)
+
+    def test_batch_encoding_to_numpy(self, pipeline):
+        pytest.importorskip("numpy")
+        batch = pipeline.encode_batch(["a", "b c"], padding="longest")
+        assert batch.to_numpy()["attention_mask"].tolist() == batch.attention_mask
+
+    def test_ragged_batch_needs_padding(self, pipeline):
+        pytest.importorskip("numpy")
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["a b", "c"], return_tensors="np")
+
+    def test_invalid_options(self, pipeline):
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["a"], return_tensors="pt")
+        with pytest.raises(ValueError):
+            pipeline.encode_batch(["a"], padding="longest").to_numpy(dtype="float16")
@@ -0,0 +1,79 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import (
+    PhraseMerger,
+    RegexTokenizer,
+    StopWordFilter,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+)
+
+
+@pytest.mark.unit
+class TestPhraseMerger:
+    """Unit tests for merging multi-word expressions into single tokens."""
+
+    def test_merge(self):
+        merger = PhraseMerger(["New York", "machine learning"])
+        tokens = "I study machine learning in New York".split()
+        assert merger.merge(tokens) == ["I", "study", "machine learning", "in", "New York"]
+        assert merger.merge(["New", "Jersey", "York"]) == ["New", "Jersey", "York"]
+        assert merger.merge([]) == []
+
+    def test_longest_match_wins(self):
+        merger = PhraseMerger(["New York", "New York City", "York City"], separator="_")
+        assert merger.merge("in New York City today".split()) == ["in", "New_York_City", "today"]
+        assert merger.merge("New York York City".split()) == ["New_York", "York_City"]
+
+    def test_case(self):
+        tokens = ["MACHINE", "Learning"]
+        assert PhraseMerger(["machine learning"]).merge(tokens) == ["MACHINE Learning"]
+        assert PhraseMerger(["machine learning"], case_sensitive=True).merge(tokens) == tokens
+
+    def test_token_list_phrases(self):
+        merger = PhraseMerger([["state", "-", "of", "-", "the", "-", "art"]], separator="")
+        tokens = RegexTokenizer(r"\w+|[^\w\s]").tokenize("a state-of-the-art model")
+        assert merger.merge(tokens) == ["a", "state-of-the-art", "model"]
+
+    def test_offsets(self):
+        text = "flights to  New   York"
+        tokens = WhitespaceTokenizer().tokenize_with_offsets(text)
+        merged = PhraseMerger(["new york"]).merge_with_offsets(tokens)
+        assert merged[-1] == ("New York", 12, 22)
+        assert text[12:22] == "New   York"
+
+    def test_invalid(self):
+        with pytest.raises(ValueError):
+            PhraseMerger(["   "])
+
+    def test_pickle(self):
+        merger = PhraseMerger(["New York"], separator="_", case_sensitive=True)
+        copy = pickle.loads(pickle.dumps(merger))
+        assert copy.phrases == [["New", "York"]]
+        assert copy.separator == "_" and copy.case_sensitive
+        assert len(copy) == 1
+
+
+@pytest.mark.unit
+class TestPipelinePhrases:
+    """Unit tests for the phrase stage of TokenizerPipeline."""
+
+    def test_before_filters(self):
+        pipeline = TokenizerPipeline(
+            WhitespaceTokenizer(),
+            phrases=PhraseMerger(["bank of america"]),
+            filters=[StopWordFilter(words=["of", "at"])],
+        )
+        text = "work at Bank of America of course"
+        assert pipeline.tokenize(text) == ["work", "Bank of America", "course"]
+        assert pipeline.tokenize_with_offsets(text)[1] == ("Bank of America", 8, 23)
+
+    def test_config_round_trip(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), phrases=PhraseMerger(["New York"]))
+        copy = TokenizerPipeline.from_json(pipeline.to_json())
+        assert copy.tokenize("New York") == ["New York"]
+        assert pickle.loads(pickle.dumps(pipeline)).tokenize("New York") == ["New York"]
+        stats = pipeline.benchmark(["to New York"], iterations=1)
+        assert "phrases" in stats["stages"]
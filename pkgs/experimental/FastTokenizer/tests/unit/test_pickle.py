@@ -0,0 +1,98 @@
+import copy
+import pickle
+
+import pytest
+
+from fasttokenizer import (
+    CodeTokenizer,
+    GraphemeTokenizer,
+    LengthFilter,
+    Normalizer,
+    NumericFilter,
+    RegexFilter,
+    RegexTokenizer,
+    SentenceSplitter,
+    SocialTokenizer,
+    SpecialTokens,
+    StopWordFilter,
+    TokenizerPipeline,
+    TrieTokenizer,
+    Vocab,
+    WhitespaceTokenizer,
+)
+
+TEXT = "The Quick  brown fox, 42 jumps!\nOver the lazy dog. e.g. Привет"
+
+
+def roundtrip(obj):
+    return pickle.loads(pickle.dumps(obj))
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "tokenizer",
+    [
+        RegexTokenizer([r"\w+", r"[^\w\s]"], mode="find", case_insensitive=True),
+        RegexTokenizer(r"\s+", mode="split"),
+        WhitespaceTokenizer(preserve_delimiters=True, keep_newlines=True),
+        WhitespaceTokenizer(max_splits=2),
+        GraphemeTokenizer(skip_whitespace=True),
+        SocialTokenizer(),
+        CodeTokenizer("python", split_identifiers=True),
+    ],
+)
+def test_tokenizers_roundtrip(tokenizer):
+    for copied in (roundtrip(tokenizer), copy.copy(tokenizer), copy.deepcopy(tokenizer)):
+        assert type(copied) is type(tokenizer)
+        assert copied.tokenize(TEXT) == tokenizer.tokenize(TEXT)
+
+
+@pytest.fixture
+def pipeline():
+    return TokenizerPipeline(
+        RegexTokenizer(r"\w+|[^\w\s]"),
+        normalizer=Normalizer(lowercase=True, allowed_scripts=["Latin"], keep_punctuation="!", strip=True),
+        pre_tokenizer=WhitespaceTokenizer(),
+        vocab=Vocab(["[CLS]", "[SEP]", "quick", "brown", "fox"], unk_token="[CLS]"),
+        special_tokens=SpecialTokens(cls="[CLS]", sep="[SEP]"),
+        filters=[StopWordFilter("en", words=["lazy"]), LengthFilter(2, 10), NumericFilter(), RegexFilter(r"\W+")],
+    )
+
+
+@pytest.mark.unit
+def test_pipeline_roundtrip(pipeline):
+    restored = roundtrip(pipeline)
+    assert restored.tokenize(TEXT) == pipeline.tokenize(TEXT) == ["quick", "brown", "fox", "jumps", "dog"]
+    assert restored.encode("quick fox") == pipeline.encode("quick fox") == [0, 2, 4, 1]
+    assert copy.deepcopy(pipeline).tokenize(TEXT) == pipeline.tokenize(TEXT)
+
+
+@pytest.mark.unit
+def test_components_roundtrip():
+    special = roundtrip(SpecialTokens(bos="<s>", eos="</s>", additional=["<mask>"]))
+    assert special.tokens() == ["<s>", "</s>", "<mask>"]
+    assert special.pair == "<s> $A </s> $B </s>"
+    normalizer = roundtrip(Normalizer(form="NFKC", casefold=True, locale="tr"))
+    assert normalizer.form == "NFKC"
+    assert normalizer.normalize("İSTANBUL") == "istanbul"
+    splitter = SentenceSplitter(["approx"], split_on_newlines=True)
+    assert roundtrip(splitter).split(TEXT) == splitter.split(TEXT)
+    assert roundtrip(RegexFilter(r"\d+", mode="keep")).mode == "keep"
+    assert roundtrip(StopWordFilter(words=["Foo"], case_sensitive=True)).words() == ["Foo"]
+
+
+@pytest.mark.unit
+def test_deepcopied_vocab_is_independent():
+    vocab = Vocab(["a", "b"])
+    copied = copy.deepcopy(vocab)
+    copied.add_tokens(["c"])
+    assert len(vocab) == 2
+    assert len(copied) == 3
+    assert roundtrip(vocab).tokens() == ["a", "b"]
+
+
+@pytest.mark.unit
+def test_unsupported_tokenizer_in_pipeline():
+    pipeline = TokenizerPipeline(TrieTokenizer(["a"]))
+    with pytest.raises(TypeError):
+        pickle.dumps(pipeline)
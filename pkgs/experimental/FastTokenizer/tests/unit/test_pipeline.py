@@ -0,0 +1,71 @@
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    RegexTokenizer,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+)
+
+
+@pytest.fixture
+def pipeline():
+    """Pipeline that lowercases, splits on whitespace, then extracts words."""
+    return TokenizerPipeline(
+        RegexTokenizer(r"\w+"),
+        normalizer=Normalizer(lowercase=True),
+        pre_tokenizer=WhitespaceTokenizer(),
+    )
+
+
+@pytest.mark.unit
+class TestTokenizerPipeline:
+    """Unit tests for the TokenizerPipeline class."""
+
+    def test_tokenize(self, pipeline):
+        assert pipeline.tokenize("Hello, World! foo-bar") == [
+            "hello",
+            "world",
+            "foo",
+            "bar",
+        ]
+
+    def test_normalize_text(self, pipeline):
+        assert pipeline.normalize_text("ABC def") == "abc def"
+
+    def test_tokenize_batch(self, pipeline):
+        assert pipeline.tokenize_batch(["A b", "", "C"]) == [["a", "b"], [], ["c"]]
+
+    def test_tokenize_with_offsets(self, pipeline):
+        text = "Héllo  wörld!"
+        tokens = pipeline.tokenize_with_offsets(text)
+        assert tokens == [("héllo", 0, 5), ("wörld", 7, 12)]
+        normalized = pipeline.normalize_text(text)
+        for token, start, end in tokens:
+            assert normalized[start:end] == token
+
+    def test_tokenizer_only(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer())
+        assert pipeline.tokenize("Keep Case, please") == ["Keep", "Case,", "please"]
+
+    def test_nested_pipeline(self, pipeline):
+        outer = TokenizerPipeline(pipeline)
+        assert outer.tokenize("A B") == ["a", "b"]
+
+    def test_invalid_tokenizer(self):
+        with pytest.raises(TypeError):
+            TokenizerPipeline("not a tokenizer")
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "tokenizer",
+    [WhitespaceTokenizer(), RegexTokenizer(r"\S+")],
+)
+def test_tokenizer_interface(tokenizer):
+    """Every tokenizer exposes tokenize, tokenize_batch and tokenize_with_offsets."""
+    text = "naïve  café au lait"
+    assert tokenizer.tokenize(text) == ["naïve", "café", "au", "lait"]
+    assert tokenizer.tokenize_batch([text, "x"]) == [tokenizer.tokenize(text), ["x"]]
+    for token, start, end in tokenizer.tokenize_with_offsets(text):
+        assert text[start:end] == token
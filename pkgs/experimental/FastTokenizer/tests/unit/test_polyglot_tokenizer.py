@@ -0,0 +1,105 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import (
+    CjkTokenizer,
+    Normalizer,
+    PolyglotTokenizer,
+    RegexTokenizer,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+    detect_language,
+)
+
+
+@pytest.mark.parametrize(
+    "text,language",
+    [
+        ("The printer is not working and I need help", "en"),
+        ("Hola, mi pedido no ha llegado todavía", "es"),
+        ("Der Drucker funktioniert nicht mehr", "de"),
+        ("Mon colis n'est pas arrivé", "fr"),
+        ("Il mio ordine non è arrivato", "it"),
+        ("De printer werkt niet meer", "nl"),
+        ("O meu pedido não chegou", "pt"),
+        ("Мой заказ до сих пор не пришёл", "ru"),
+        ("東京は日本の首都です", "ja"),
+        ("北京是中国的首都", "zh"),
+        ("주문이 아직 도착하지 않았습니다", "ko"),
+    ],
+)
+@pytest.mark.unit
+def test_detect_language(text, language):
+    code, confidence = detect_language(text)
+    assert code == language and 0.0 < confidence <= 1.0
+
+
+@pytest.mark.unit
+class TestPolyglotTokenizer:
+    """Unit tests for language-routed tokenization."""
+
+    def test_undetermined(self):
+        assert detect_language("") == ("und", 0.0)
+        assert detect_language("12345 !!") == ("und", 0.0)
+        assert PolyglotTokenizer({}).detect("xyz") == "und"
+
+    def test_routes_by_language(self):
+        polyglot = PolyglotTokenizer({"ja": CjkTokenizer(), "en": RegexTokenizer(r"\w+")})
+        assert polyglot.tokenize("Where is my order?") == [
+            ("Where", "en"),
+            ("is", "en"),
+            ("my", "en"),
+            ("order", "en"),
+        ]
+        tokens = polyglot.tokenize("東京は日本の首都です")
+        assert {language for _, language in tokens} == {"ja"}
+        assert "".join(token for token, _ in tokens) == "東京は日本の首都です"
+        # Languages without a tokenizer of their own use the default.
+        assert polyglot.tokenize("Мой заказ не пришёл") == [
+            ("Мой", "ru"),
+            ("заказ", "ru"),
+            ("не", "ru"),
+            ("пришёл", "ru"),
+        ]
+
+    def test_by_line_and_offsets(self):
+        polyglot = PolyglotTokenizer(
+            {"ja": CjkTokenizer()},
+            normalizers={"en": Normalizer(lowercase=True)},
+            by_line=True,
+        )
+        text = "The printer is BROKEN\n東京です\nok"
+        tokens = polyglot.tokenize_with_offsets(text)
+        assert tokens[3] == ("broken", 15, 21, "en")
+        assert tokens[4] == ("東", 22, 23, "ja")
+        # The short last line takes the language of the whole text.
+        assert tokens[-1] == ("ok", 27, 29, "en")
+        for _, start, end, _ in tokens:
+            assert text[start:end].strip()
+        assert polyglot.tokenize_with_offsets("né in the end", unit="byte")[1] == ("in", 4, 6, "en")
+
+    def test_min_confidence(self):
+        text = "the el der"
+        assert PolyglotTokenizer({}).detect(text) != "und"
+        assert PolyglotTokenizer({}, min_confidence=0.9).detect(text) == "und"
+        with pytest.raises(ValueError, match="min_confidence"):
+            PolyglotTokenizer({}, min_confidence=2.0)
+
+    def test_batch_and_pipeline(self):
+        polyglot = PolyglotTokenizer({"en": WhitespaceTokenizer()}, default=RegexTokenizer(r"\w+"))
+        texts = ["the end of it", "Hola, ¿qué tal?"]
+        assert polyglot.tokenize_batch(texts) == [polyglot.tokenize(t) for t in texts]
+        assert polyglot.tokenize("Hola, ¿qué tal?")[0] == ("Hola", "es")
+        pipeline = TokenizerPipeline(polyglot)
+        assert pipeline.tokenize("the end of it") == ["the", "end", "of", "it"]
+
+    def test_pickle(self):
+        polyglot = PolyglotTokenizer(
+            {"en": RegexTokenizer(r"\w+")},
+            normalizers={"en": Normalizer(lowercase=True)},
+            by_line=True,
+        )
+        restored = pickle.loads(pickle.dumps(polyglot))
+        assert restored.tokenize("This is THE end") == polyglot.tokenize("This is THE end")
+        assert repr(restored) == "PolyglotTokenizer(languages=[en], by_line=True)"
@@ -0,0 +1,57 @@
+import pytest
+
+from fasttokenizer import Normalizer, casefold, lowercase, remove_punctuation
+
+
+@pytest.mark.unit
+def test_remove_punctuation_ascii_by_default():
+    assert remove_punctuation("Hi, «you»! $5") == "Hi «you» 5"
+
+
+@pytest.mark.unit
+def test_remove_unicode_punctuation():
+    text = "«Bonjour», dit\u2010il… ¿Qué? 「はい」。 $5"
+    assert remove_punctuation(text, unicode=True) == "Bonjour ditil Qué はい $5"
+
+
+@pytest.mark.unit
+def test_remove_punctuation_keep():
+    assert remove_punctuation("don't stop—now!", unicode=True, keep="'") == "don't stopnow"
+    assert remove_punctuation("a-b, c", keep="-") == "a-b c"
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "text, expected",
+    [
+        ("Straße", "strasse"),
+        ("ΣΊΣΥΦΟΣ", "σίσυφοσ"),
+        ("\ufb01ne", "fine"),
+    ],
+)
+def test_casefold(text, expected):
+    assert casefold(text) == expected
+    assert lowercase(text) != expected
+
+
+@pytest.mark.unit
+def test_casefold_turkish():
+    assert casefold("DİYARBAKIR", locale="tr") == "diyarbakır"
+    assert casefold("Iğdır", locale="tr-TR") == "ığdır"
+    assert casefold("Iğdır") == "iğdır"
+    assert casefold("\u0130", locale="en") == "i\u0307"
+
+
+@pytest.mark.unit
+def test_normalizer_options():
+    normalizer = Normalizer(
+        casefold=True,
+        locale="tr",
+        remove_punctuation=True,
+        unicode_punctuation=True,
+        keep_punctuation="'",
+    )
+    assert normalizer.normalize("«İSTANBUL'DA» Straße!") == "istanbul'da strasse"
+    assert normalizer.casefold("KIŞ") == "kış"
+    assert normalizer.remove_punctuation("¿Sí?") == "Sí"
+    assert Normalizer(remove_punctuation=True).normalize("«a»!") == "«a»"
@@ -0,0 +1,55 @@
+import pytest
+
+from fasttokenizer import Redactor
+
+
+@pytest.mark.unit
+def test_find_builtin_entities_with_offsets():
+    text = "Mail jane.doe@example.com or call (555) 123-4567 from 192.168.0.1"
+    assert Redactor().find(text) == [
+        ("email", "jane.doe@example.com", 5, 25),
+        ("phone", "(555) 123-4567", 34, 48),
+        ("ip_address", "192.168.0.1", 54, 65),
+    ]
+    assert Redactor().find("é a@b.co", unit="byte") == [("email", "a@b.co", 3, 9)]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "text,expected",
+    [
+        ("card 4111 1111 1111 1111", "card [credit_card]"),
+        ("card 4111-1111-1111-1112", "card 4111-1111-1111-1112"),
+        ("ssn 123-45-6789", "ssn [ssn]"),
+        ("ssn 000-12-3456", "ssn 000-12-3456"),
+        ("ip 2001:db8::1", "ip [ip_address]"),
+        ("ip 999.1.1.1 at 12:30:45", "ip 999.1.1.1 at 12:30:45"),
+        ("call +44 20 7946 0958.", "call [phone]."),
+        ("on 2024-10-16", "on 2024-10-16"),
+    ],
+)
+def test_redact_validates_matches(text, expected):
+    assert Redactor().redact(text) == expected
+
+
+@pytest.mark.unit
+def test_entities_and_custom_patterns():
+    redactor = Redactor(entities=["email"], patterns={"employee_id": r"EMP-\d{4}"})
+    assert redactor.labels == ["employee_id", "email"]
+    text = "EMP-1234 wrote to a@b.co from 10.0.0.1"
+    assert redactor.redact(text, "<{label}>") == "<employee_id> wrote to <email> from 10.0.0.1"
+    assert redactor.redact(text, "***") == "*** wrote to *** from 10.0.0.1"
+    assert redactor.redact_batch([text, "nothing"]) == [
+        "[employee_id] wrote to [email] from 10.0.0.1",
+        "nothing",
+    ]
+
+
+@pytest.mark.unit
+def test_invalid_configuration():
+    with pytest.raises(ValueError):
+        Redactor(entities=["passport"])
+    with pytest.raises(ValueError):
+        Redactor(patterns={"bad": "("})
+    with pytest.raises(ValueError):
+        Redactor(entities=[])
@@ -0,0 +1,50 @@
+import threading
+
+import pytest
+
+from fasttokenizer import RegexFilter, RegexTokenizer
+
+
+@pytest.mark.unit
+def test_same_pattern_and_flags_compile_once():
+    RegexTokenizer.clear_cache()
+    assert RegexTokenizer.cache_size() == 0
+    RegexTokenizer(r"\w+")
+    RegexTokenizer(r"\w+", mode="split")
+    assert RegexTokenizer.cache_size() == 1
+    RegexTokenizer(r"\w+", case_insensitive=True)
+    RegexTokenizer([r"\w+", r"\d+"])
+    assert RegexTokenizer.cache_size() == 3
+
+
+@pytest.mark.unit
+def test_invalid_patterns_are_not_cached():
+    RegexTokenizer.clear_cache()
+    with pytest.raises(ValueError):
+        RegexTokenizer(r"(")
+    assert RegexTokenizer.cache_size() == 0
+
+
+@pytest.mark.unit
+def test_clearing_keeps_existing_tokenizers_working():
+    tokenizer = RegexTokenizer(r"[a-z]+")
+    RegexTokenizer.clear_cache()
+    assert tokenizer.tokenize("ab 12 cd") == ["ab", "cd"]
+    assert RegexFilter(r"\d+").filter(["12", "ab"]) == ["ab"]
+    assert RegexTokenizer.cache_size() == 1
+
+
+@pytest.mark.unit
+def test_shared_across_threads():
+    tokenizer = RegexTokenizer(r"\w+")
+    results = [None] * 8
+
+    def work(i):
+        results[i] = tokenizer.tokenize_batch([f"doc {i} text"] * 50)
+
+    threads = [threading.Thread(target=work, args=(i,)) for i in range(8)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert all(r == [["doc", str(i), "text"]] * 50 for i, r in enumerate(results))
@@ -0,0 +1,42 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer
+
+
+@pytest.mark.unit
+def test_case_insensitive():
+    tokenizer = RegexTokenizer(r"error|warn", case_insensitive=True)
+    assert tokenizer.tokenize("ERROR then Warn") == ["ERROR", "Warn"]
+    assert RegexTokenizer(r"error").tokenize("ERROR") == []
+
+
+@pytest.mark.unit
+def test_multi_line_and_dot_all():
+    text = "# a\nb\n# c"
+    assert RegexTokenizer(r"^#.*$", multi_line=True).tokenize(text) == ["# a", "# c"]
+    assert RegexTokenizer(r"<.+>", dot_matches_new_line=True).tokenize("<a\nb>") == ["<a\nb>"]
+    assert RegexTokenizer(r"<.+>").tokenize("<a\nb>") == []
+
+
+@pytest.mark.unit
+def test_unicode_classes():
+    text = "naïve café 42"
+    assert RegexTokenizer(r"\w+").tokenize(text) == ["naïve", "café", "42"]
+    assert RegexTokenizer(r"\w+", unicode=False).tokenize(text) == ["na", "ve", "caf", "42"]
+    assert RegexTokenizer(r"\p{Greek}+").tokenize("alpha αβγ") == ["αβγ"]
+
+
+@pytest.mark.unit
+def test_ignore_whitespace():
+    tokenizer = RegexTokenizer(r"\d+  # digits", ignore_whitespace=True)
+    assert tokenizer.tokenize("a 12 b 3") == ["12", "3"]
+
+
+@pytest.mark.unit
+def test_size_limit():
+    with pytest.raises(ValueError):
+        RegexTokenizer(r"\w{1000}", size_limit=1000)
+    tokenizer = RegexTokenizer(r"\w+", size_limit=1 << 20, case_insensitive=True)
+    assert tokenizer.flags["size_limit"] == 1 << 20
+    assert tokenizer.flags["case_insensitive"] is True
+    assert tokenizer.flags["unicode"] is True
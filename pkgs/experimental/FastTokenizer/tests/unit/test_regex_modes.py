@@ -0,0 +1,62 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer, TokenizerPipeline
+
+
+@pytest.mark.unit
+def test_find_is_the_default():
+    tokenizer = RegexTokenizer(r"\w+")
+    assert tokenizer.mode == "find"
+    assert tokenizer.tokenize("a, b") == ["a", "b"]
+
+
+@pytest.mark.unit
+def test_split_mode():
+    tokenizer = RegexTokenizer(r"\s*[,;]\s*", mode="split")
+    assert tokenizer.tokenize_with_offsets("a, b;;c ,d,") == [
+        ("a", 0, 1),
+        ("b", 3, 4),
+        ("c", 6, 7),
+        ("d", 9, 10),
+    ]
+    assert RegexTokenizer(r"\|", mode="split").tokenize("no delimiters") == ["no delimiters"]
+
+
+@pytest.mark.unit
+def test_captures_mode():
+    tokenizer = RegexTokenizer(r"(?P<key>\w+)=(?P<value>[^&]*)", mode="captures")
+    assert tokenizer.tokenize_with_types("a=1&name=bob") == [
+        ("a", "key"),
+        ("1", "value"),
+        ("name", "key"),
+        ("bob", "value"),
+    ]
+    unnamed = RegexTokenizer([r"(?P<num>\d+)", r"[a-z]+"], mode="captures")
+    assert unnamed.tokenize_with_types("ab12") == [("ab", None), ("12", "num")]
+
+
+@pytest.mark.unit
+def test_pattern_list_first_match_wins():
+    tokenizer = RegexTokenizer([r"\d+\.\d+", r"\d+", r"\w+", r"[^\w\s]"])
+    assert tokenizer.patterns == [r"\d+\.\d+", r"\d+", r"\w+", r"[^\w\s]"]
+    assert tokenizer.tokenize("pi is 3.14, e is 2.") == [
+        "pi", "is", "3.14", ",", "e", "is", "2", ".",
+    ]
+    earlier_wins = RegexTokenizer([r"ab", r"abc"])
+    assert earlier_wins.tokenize("abc") == ["ab"]
+
+
+@pytest.mark.unit
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        RegexTokenizer(r"\w+", mode="gaps")
+    with pytest.raises(ValueError):
+        RegexTokenizer([])
+    with pytest.raises(ValueError):
+        RegexTokenizer([r"\w+", r"(unclosed"])
+
+
+@pytest.mark.unit
+def test_modes_in_pipeline():
+    pipeline = TokenizerPipeline(RegexTokenizer(r"\s+", mode="split"))
+    assert pipeline.tokenize("one  two\tthree") == ["one", "two", "three"]
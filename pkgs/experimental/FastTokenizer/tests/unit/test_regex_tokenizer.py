@@ -1,6 +1,6 @@
 import logging
 import pytest
-from FastTokenizer.regex_tokenizer import RegexTokenizer
+from fasttokenizer import RegexTokenizer
 
 # Set up logging configuration
 logging.basicConfig(level=logging.INFO)
@@ -49,6 +49,6 @@ class TestRegexTokenizer:
             RegexTokenizer(pattern="[Invalid pattern")
 
     def test_none_pattern(self):
-        """Test that a None regex pattern raises a ValueError."""
-        with pytest.raises(ValueError):
+        """Test that a None regex pattern raises a TypeError."""
+        with pytest.raises(TypeError):
             RegexTokenizer(pattern=None)
@@ -0,0 +1,151 @@
+import gzip
+import json
+import subprocess
+import sys
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+from fasttokenizer import TokenizerIO, WhitespaceTokenizer
+
+LINES = [f"remote line {i}" for i in range(2000)]
+TEXT = "\n".join(LINES) + "\n"
+OBJECTS = {
+    "corpus.txt": TEXT.encode(),
+    "corpus.txt.gz": gzip.compress(TEXT.encode()),
+    "bucket/data/part%2001.txt": b"from s3\n",
+}
+
+# Served from its own process, so that requests and server keep separate
+# environments and logs.
+SERVER = """
+import json, pathlib, sys
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+root, log = pathlib.Path(sys.argv[1]), open(sys.argv[2], "a")
+
+class Handler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        log.write(json.dumps([self.path, {k.lower(): v for k, v in self.headers.items()}]) + "\\n")
+        log.flush()
+        path = root / self.path.lstrip("/")
+        if not path.is_file():
+            self.send_response(404)
+            self.end_headers()
+            return
+        body = path.read_bytes()
+        self.send_response(200)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, *args):
+        pass
+
+httpd = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+print(httpd.server_port, flush=True)
+httpd.serve_forever()
+"""
+
+
+@pytest.fixture
+def server(tmp_path):
+    root = tmp_path / "objects"
+    for name, body in OBJECTS.items():
+        (root / name).parent.mkdir(parents=True, exist_ok=True)
+        (root / name).write_bytes(body)
+    log = tmp_path / "requests.jsonl"
+    log.touch()
+    process = subprocess.Popen(
+        [sys.executable, "-c", SERVER, str(root), str(log)], stdout=subprocess.PIPE, text=True
+    )
+    port = process.stdout.readline().strip()
+    yield f"http://127.0.0.1:{port}", log
+    process.terminate()
+    process.wait()
+
+
+def last_request(log):
+    return json.loads(log.read_text().splitlines()[-1])
+
+
+@pytest.mark.unit
+class TestRemoteIO:
+    """Unit tests for reading http:// and s3:// URLs without staging them."""
+
+    def test_read_http(self, server):
+        server, _ = server
+        io = TokenizerIO()
+        assert io.read_file(f"{server}/corpus.txt") == TEXT
+        assert io.read_file(f"{server}/corpus.txt.gz") == TEXT
+
+    def test_stream_lines_and_tokens(self, server):
+        server, _ = server
+        io = TokenizerIO()
+        assert list(io.iter_lines(f"{server}/corpus.txt.gz")) == LINES
+        batches = io.tokenize_file(f"{server}/corpus.txt.gz", WhitespaceTokenizer(), chunk_size=1024)
+        assert [t for batch in batches for t in batch] == TEXT.split()
+
+    def test_tokenize_corpus(self, server):
+        server, _ = server
+        urls = [f"{server}/corpus.txt", f"{server}/corpus.txt.gz"]
+        tokens = TokenizerIO().tokenize_corpus(urls, WhitespaceTokenizer(), workers=2)
+        assert tokens == [TEXT.split(), TEXT.split()]
+
+    def test_network_waits_release_the_gil(self):
+        # Served from a thread of this process, which could never answer if
+        # the readers held the GIL while waiting on the network.
+        class Handler(BaseHTTPRequestHandler):
+            def do_GET(self):
+                body = TEXT.encode()
+                self.send_response(200)
+                self.send_header("Content-Length", str(len(body)))
+                self.end_headers()
+                self.wfile.write(body)
+
+            def log_message(self, *args):
+                pass
+
+        httpd = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+        thread = threading.Thread(target=httpd.serve_forever, daemon=True)
+        thread.start()
+        try:
+            url = f"http://127.0.0.1:{httpd.server_port}/corpus.txt"
+            io = TokenizerIO()
+            assert io.read_file(url) == TEXT
+            assert list(io.iter_lines(url)) == LINES
+            assert list(io.iter_csv(url, 0, has_header=False)) == LINES
+        finally:
+            httpd.shutdown()
+            httpd.server_close()
+
+    def test_missing_object(self, server):
+        server, _ = server
+        with pytest.raises(IOError, match="Failed to open file: .*404"):
+            TokenizerIO().read_file(f"{server}/missing.txt")
+
+    def test_s3_signed(self, server, monkeypatch):
+        server, log = server
+        monkeypatch.setenv("AWS_ENDPOINT_URL", server)
+        monkeypatch.setenv("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE")
+        monkeypatch.setenv("AWS_SECRET_ACCESS_KEY", "secret")
+        monkeypatch.setenv("AWS_REGION", "eu-west-1")
+        assert TokenizerIO().read_file("s3://bucket/data/part 01.txt") == "from s3\n"
+        path, headers = last_request(log)
+        assert path == "/bucket/data/part%2001.txt"
+        assert headers["authorization"].startswith("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/")
+        assert "/eu-west-1/s3/aws4_request" in headers["authorization"]
+        assert "SignedHeaders=host;x-amz-content-sha256;x-amz-date" in headers["authorization"]
+
+    def test_s3_anonymous(self, server, monkeypatch):
+        server, log = server
+        monkeypatch.setenv("AWS_ENDPOINT_URL", server)
+        monkeypatch.delenv("AWS_ACCESS_KEY_ID", raising=False)
+        monkeypatch.delenv("AWS_SECRET_ACCESS_KEY", raising=False)
+        assert TokenizerIO().read_file("s3://bucket/data/part 01.txt") == "from s3\n"
+        assert "authorization" not in last_request(log)[1]
+
+    def test_invalid_s3_url(self):
+        with pytest.raises(ValueError, match="expected s3://bucket/key"):
+            TokenizerIO().read_file("s3://bucket-only")
@@ -0,0 +1,47 @@
+import pytest
+
+from fasttokenizer import Normalizer, detect_scripts, filter_scripts
+
+
+@pytest.mark.unit
+def test_detect_scripts_reports_proportions():
+    scripts = detect_scripts("Hello мир 123!")
+    assert [name for name, _ in scripts] == ["Latin", "Cyrillic"]
+    assert scripts[0][1] == pytest.approx(5 / 8)
+    assert sum(proportion for _, proportion in scripts) == pytest.approx(1.0)
+    assert detect_scripts("日本語です")[0] == ("Han", pytest.approx(0.6))
+    assert detect_scripts("123 !?") == []
+
+
+@pytest.mark.unit
+def test_filter_scripts_keeps_shared_characters():
+    assert filter_scripts("Hello мир, café 42!", ["Latin"]) == "Hello , café 42!"
+    assert filter_scripts("Привет world", ["Cyrl"]) == "Привет "
+    assert filter_scripts("ab\u0301c", ["Latin"]) == "ab\u0301c"
+    # The Arabic comma is only used by Arabic-like scripts.
+    assert filter_scripts("a،b", ["Latin"]) == "ab"
+    assert filter_scripts("a،b", ["Latin", "Arabic"]) == "a،b"
+
+
+@pytest.mark.unit
+def test_filter_scripts_replacement_and_errors():
+    assert filter_scripts("abc мир", ["Latin"], replacement="?") == "abc ???"
+    with pytest.raises(ValueError):
+        filter_scripts("abc", ["Klingon"])
+    with pytest.raises(ValueError):
+        filter_scripts("abc", [])
+
+
+@pytest.mark.unit
+def test_normalizer_script_stage():
+    normalizer = Normalizer(
+        allowed_scripts=["Latin"],
+        script_replacement=" ",
+        collapse_whitespace=True,
+        strip=True,
+        lowercase=True,
+    )
+    assert normalizer.normalize("Привет Hello мир World") == "hello world"
+    alignment = normalizer.normalize_with_alignment("aπb")
+    assert alignment.normalized == "a b"
+    assert alignment.to_original(1, 2) == (1, 2)
@@ -0,0 +1,72 @@
+import pytest
+
+from fasttokenizer import RecursiveChunker, SentenceChunker, SentenceSplitter, WhitespaceTokenizer
+
+DOC = (
+    "Intro paragraph here.\n\n"
+    "Second paragraph has two sentences. It is longer than the first one.\n\n"
+    "End."
+)
+
+
+@pytest.mark.unit
+def test_sentence_chunker_packs_whole_sentences():
+    chunker = SentenceChunker(WhitespaceTokenizer(), chunk_size=5)
+    text = "One two three. Four five. Six seven. Eight."
+    assert chunker.chunk(text) == [
+        ("One two three. Four five.", 0, 25),
+        ("Six seven. Eight.", 26, 43),
+    ]
+
+
+@pytest.mark.unit
+def test_sentence_chunker_overlap_and_long_sentences():
+    text = "A b. C d. E f. G h."
+    assert [c for c, _, _ in SentenceChunker(WhitespaceTokenizer(), chunk_size=4, overlap=1).chunk(text)] == [
+        "A b. C d.",
+        "C d. E f.",
+        "E f. G h.",
+    ]
+    long = "One two three four five six seven. End."
+    assert [c for c, _, _ in SentenceChunker(WhitespaceTokenizer(), chunk_size=3).chunk(long)] == [
+        "One two three",
+        "four five six",
+        "seven.",
+        "End.",
+    ]
+
+
+@pytest.mark.unit
+def test_sentence_chunker_custom_splitter():
+    text = "a b\nc d"
+    by_line = SentenceChunker(WhitespaceTokenizer(), chunk_size=3, splitter=SentenceSplitter(split_on_newlines=True))
+    assert by_line.chunk(text) == [("a b", 0, 3), ("c d", 4, 7)]
+    # The default splitter joins wrapped lines, so the sentence is split by tokens.
+    assert SentenceChunker(WhitespaceTokenizer(), chunk_size=3).chunk(text) == [("a b\nc", 0, 5), ("d", 6, 7)]
+
+
+@pytest.mark.unit
+def test_recursive_chunker_falls_back_to_finer_separators():
+    tokenizer = WhitespaceTokenizer()
+    assert RecursiveChunker(tokenizer, chunk_size=100).chunk(DOC) == [(DOC, 0, len(DOC))]
+    assert [c for c, _, _ in RecursiveChunker(tokenizer, chunk_size=6).chunk(DOC)] == [
+        "Intro paragraph here.",
+        "Second paragraph has two sentences.",
+        "It is longer than the first",
+        "one.",
+        "End.",
+    ]
+    for chunk, start, end in RecursiveChunker(tokenizer, chunk_size=3).chunk(DOC):
+        assert DOC[start:end] == chunk
+        assert len(chunk.split()) <= 3
+
+
+@pytest.mark.unit
+def test_recursive_chunker_literal_separators():
+    chunker = RecursiveChunker(WhitespaceTokenizer(), chunk_size=4, separators=["\n\n", " "])
+    assert [c for c, _, _ in chunker.chunk(DOC)][:2] == ["Intro paragraph here.", "Second paragraph has two"]
+    assert chunker.chunk_batch(["", "a b"]) == [[], [("a b", 0, 3)]]
+    with pytest.raises(ValueError):
+        RecursiveChunker(WhitespaceTokenizer(), separators=[""])
+    with pytest.raises(ValueError):
+        SentenceChunker(WhitespaceTokenizer(), chunk_size=0)
@@ -0,0 +1,66 @@
+import pytest
+
+from fasttokenizer import SentenceSplitter, TokenizerPipeline, WhitespaceTokenizer
+
+
+@pytest.mark.unit
+def test_split_keeps_abbreviations_and_decimals():
+    splitter = SentenceSplitter()
+    text = "Use a solvent, e.g. Ethanol. It costs $3.50 per litre. Dr. Smith agrees!"
+    assert splitter.split(text) == [
+        "Use a solvent, e.g. Ethanol.",
+        "It costs $3.50 per litre.",
+        "Dr. Smith agrees!",
+    ]
+
+
+@pytest.mark.unit
+def test_initials_do_not_end_sentences():
+    splitter = SentenceSplitter()
+    assert splitter.split("J. R. R. Tolkien wrote it. Really?") == [
+        "J. R. R. Tolkien wrote it.",
+        "Really?",
+    ]
+
+
+@pytest.mark.unit
+def test_custom_abbreviations():
+    text = "See Sec. Four for details. Thanks."
+    assert len(SentenceSplitter().split(text)) == 3
+    assert SentenceSplitter(abbreviations=["sec."]).split(text) == [
+        "See Sec. Four for details.",
+        "Thanks.",
+    ]
+    assert len(SentenceSplitter(use_default_abbreviations=False).split("Dr. Who.")) == 2
+
+
+@pytest.mark.unit
+def test_newlines():
+    text = "This sentence is\nwrapped. Next one.\n\nNew paragraph"
+    assert SentenceSplitter().split(text) == [
+        "This sentence is\nwrapped.",
+        "Next one.",
+        "New paragraph",
+    ]
+    assert SentenceSplitter(split_on_newlines=True).split(text)[0] == "This sentence is"
+
+
+@pytest.mark.unit
+def test_split_with_offsets():
+    text = "Héllo wörld.  Ça va?"
+    splitter = SentenceSplitter()
+    spans = splitter.split_with_offsets(text)
+    assert [s for s, _, _ in spans] == ["Héllo wörld.", "Ça va?"]
+    for sentence, start, end in spans:
+        assert text[start:end] == sentence
+    encoded = text.encode("utf-8")
+    for sentence, start, end in splitter.split_with_offsets(text, unit="byte"):
+        assert encoded[start:end].decode("utf-8") == sentence
+
+
+@pytest.mark.unit
+def test_split_batch_and_pipeline():
+    splitter = SentenceSplitter()
+    assert splitter.split_batch(["A b. C d.", ""]) == [["A b.", "C d."], []]
+    pipeline = TokenizerPipeline(WhitespaceTokenizer(), pre_tokenizer=splitter)
+    assert pipeline.tokenize("One two. Three.") == ["One", "two.", "Three."]
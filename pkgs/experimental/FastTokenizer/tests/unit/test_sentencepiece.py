@@ -0,0 +1,159 @@
+import struct
+
+import pytest
+
+from fasttokenizer import SentencePieceTokenizer
+
+NORMAL, UNKNOWN, CONTROL, BYTE = 1, 2, 3, 6
+
+
+def _varint(value):
+    out = bytearray()
+    value &= (1 << 64) - 1
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def _bytes_field(tag, payload):
+    return _varint(tag << 3 | 2) + _varint(len(payload)) + payload
+
+
+def _varint_field(tag, value):
+    return _varint(tag << 3) + _varint(value)
+
+
+def make_model(pieces, model_type=1, byte_fallback=False):
+    """Serialize a minimal sentencepiece ModelProto."""
+    data = b""
+    for piece, score, kind in pieces:
+        data += _bytes_field(
+            1,
+            _bytes_field(1, piece.encode("utf-8"))
+            + _varint(2 << 3 | 5)
+            + struct.pack("<f", score)
+            + _varint_field(3, kind),
+        )
+    trainer = _varint_field(3, model_type) + _varint_field(35, int(byte_fallback))
+    trainer += _varint_field(43, -1)
+    return data + _bytes_field(2, trainer)
+
+
+SPECIAL = [("<unk>", 0.0, UNKNOWN), ("<s>", 0.0, CONTROL), ("</s>", 0.0, CONTROL)]
+CHARS = [(c, -5.0, NORMAL) for c in "▁helowrd"]
+
+
+@pytest.fixture
+def unigram():
+    pieces = SPECIAL + CHARS + [
+        ("▁hello", -1.0, NORMAL),
+        ("▁he", -3.0, NORMAL),
+        ("llo", -3.0, NORMAL),
+        ("▁world", -1.5, NORMAL),
+    ]
+    return SentencePieceTokenizer.from_bytes(make_model(pieces))
+
+
+@pytest.fixture
+def bpe():
+    pieces = SPECIAL + CHARS + [
+        ("▁h", -1.0, NORMAL),
+        ("ll", -2.0, NORMAL),
+        ("▁he", -3.0, NORMAL),
+        ("llo", -4.0, NORMAL),
+        ("▁hello", -5.0, NORMAL),
+    ]
+    return SentencePieceTokenizer.from_bytes(make_model(pieces, model_type=2))
+
+
+@pytest.mark.unit
+class TestSentencePieceTokenizer:
+    """Unit tests for the SentencePieceTokenizer class."""
+
+    def test_unigram(self, unigram):
+        assert unigram.model_type == "unigram"
+        assert unigram.tokenize("hello world") == ["▁hello", "▁world"]
+        assert unigram.tokenize("helo") == ["▁he", "l", "o"]
+
+    def test_bpe(self, bpe):
+        assert bpe.model_type == "bpe"
+        assert bpe.tokenize("hello hell") == ["▁hello", "▁he", "ll"]
+
+    def test_extra_whitespace(self, unigram):
+        assert unigram.tokenize("  hello   world ") == ["▁hello", "▁world"]
+
+    def test_unknown_characters_are_fused(self, unigram):
+        assert unigram.tokenize("hello ZZ") == ["▁hello", "▁", "<unk>"]
+        assert unigram.decode(unigram.encode("hello ZZ")) == "hello  ⁇ "
+
+    def test_byte_fallback(self):
+        pieces = SPECIAL + CHARS + [(f"<0x{b:02X}>", 0.0, BYTE) for b in range(256)]
+        model = SentencePieceTokenizer.from_bytes(make_model(pieces, byte_fallback=True))
+        assert model.tokenize("hé") == ["▁", "h", "<0xC3>", "<0xA9>"]
+        assert model.decode(model.encode("hé world")) == "hé world"
+
+    def test_encode_decode(self, unigram):
+        ids = unigram.encode("hello world", add_bos=True, add_eos=True)
+        assert ids[0] == unigram.bos_id == 1
+        assert ids[-1] == unigram.eos_id == 2
+        assert unigram.pad_id is None
+        assert unigram.decode(ids) == "hello world"
+        assert unigram.encode_batch(["hello", ""]) == [[unigram.token_to_id("▁hello")], []]
+
+    def test_offsets(self, unigram):
+        text = "hello  world"
+        assert unigram.tokenize_with_offsets(text) == [
+            ("▁hello", 0, 5),
+            ("▁world", 5, 12),
+        ]
+
+    def test_from_file(self, tmp_path):
+        path = tmp_path / "test.model"
+        path.write_bytes(
+            make_model(SPECIAL + CHARS + [("▁hello", -1.0, NORMAL)])
+        )
+        loaded = SentencePieceTokenizer.from_file(str(path))
+        assert loaded.vocab_size() == len(SPECIAL + CHARS) + 1
+        assert loaded.tokenize("hello") == ["▁hello"]
+
+    def test_from_pieces(self):
+        pieces = [(p, s) for p, s, _ in SPECIAL + CHARS] + [("▁hello", -1.0)]
+        model = SentencePieceTokenizer(pieces, bos_id=1, eos_id=2)
+        assert model.tokenize("hello he") == ["▁hello", "▁", "h", "e"]
+        assert model.decode(model.encode("hello", add_bos=True)) == "hello"
+
+    def test_invalid_model(self):
+        with pytest.raises(ValueError):
+            SentencePieceTokenizer.from_bytes(b"\xff\xff")
+
+    def test_unigram_sampling(self, unigram):
+        text = "hello world"
+        best = unigram.encode(text)
+        samples = {tuple(unigram.encode(text, alpha=0.1, seed=s)) for s in range(50)}
+        assert len(samples) > 5
+        for ids in samples:
+            assert unigram.decode(list(ids)) == text
+        # A large alpha concentrates the samples on the best segmentation.
+        sharp = [unigram.encode(text, alpha=10.0, seed=s) for s in range(20)]
+        assert sum(ids == best for ids in sharp) >= 18
+        assert unigram.encode(text, alpha=0.1, seed=3) == unigram.encode(text, alpha=0.1, seed=3)
+        batch = unigram.encode_batch([text, "helo"], add_bos=True, alpha=0.1, seed=7)
+        assert batch[1] == unigram.encode("helo", add_bos=True, alpha=0.1, seed=8)
+        with pytest.raises(ValueError):
+            unigram.encode(text, alpha=0.0)
+
+    def test_bpe_dropout(self, bpe):
+        text = "hello hell"
+        assert bpe.encode(text, alpha=0.0) == bpe.encode(text)
+        chars = bpe.encode(text, alpha=1.0)
+        assert [bpe.id_to_token(i) for i in chars] == list("▁hello▁hell")
+        samples = {tuple(bpe.encode(text, alpha=0.3, seed=s)) for s in range(30)}
+        assert len(samples) > 3
+        assert all(bpe.decode(list(ids)) == text for ids in samples)
+        with pytest.raises(ValueError):
+            bpe.encode(text, alpha=2.0)
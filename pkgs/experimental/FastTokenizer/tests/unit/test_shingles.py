@@ -0,0 +1,58 @@
+import pytest
+
+from fasttokenizer import shingle_overlap, shingles, shingles_batch, simhash
+
+SOURCE = "the quick brown fox jumps over the lazy dog"
+COPY = "as we know the quick brown fox jumps over the lazy dog again"
+OTHER = "stock markets fell while interest rates kept rising"
+
+
+@pytest.mark.unit
+def test_shingles_are_distinct():
+    assert shingles("a b a b a", k=2, hashed=False) == ["a b", "b a"]
+    hashes = shingles("a b a b a", k=2)
+    assert len(hashes) == 2 and hashes == sorted(hashes)
+    assert shingles("a b a b a".split(), 2) == hashes
+    assert shingles("too short", k=5, hashed=False) == ["too short"]
+    assert shingles("", k=3) == []
+
+
+@pytest.mark.unit
+def test_hashes_match_simhash_shingles():
+    assert len(shingles(SOURCE, k=3)) == 7
+    # The fingerprint of a single shingle is its hash.
+    assert shingles("too short", k=5) == [simhash("too short", ngram=5)]
+
+
+@pytest.mark.unit
+def test_batch():
+    assert shingles_batch([SOURCE, OTHER.split()], k=3, hashed=False) == [
+        shingles(SOURCE, 3, hashed=False),
+        shingles(OTHER, 3, hashed=False),
+    ]
+
+
+@pytest.mark.unit
+def test_overlap_within_batch():
+    pairs = shingle_overlap([SOURCE, OTHER, COPY], k=3)
+    assert [(i, j) for i, j, _ in pairs] == [(0, 2)]
+    # 7 shingles shared out of 7 + 11 - 7.
+    assert pairs[0][2] == pytest.approx(7 / 11)
+    assert shingle_overlap([SOURCE, OTHER, COPY], k=3, metric="containment") == [(0, 2, 1.0)]
+    assert shingle_overlap([SOURCE, COPY], k=3, threshold=0.9) == []
+
+
+@pytest.mark.unit
+def test_overlap_against_others():
+    pairs = shingle_overlap([COPY, OTHER], others=[OTHER, SOURCE], k=3, metric="containment")
+    assert pairs == [(0, 1, 1.0), (1, 0, 1.0)]
+
+
+@pytest.mark.unit
+def test_invalid_arguments():
+    with pytest.raises(ValueError, match="k must be at least 1"):
+        shingles(SOURCE, k=0)
+    with pytest.raises(ValueError, match="Unknown overlap metric"):
+        shingle_overlap([SOURCE], metric="cosine")
+    with pytest.raises(ValueError, match="threshold must be between 0 and 1"):
+        shingle_overlap([SOURCE], threshold=1.5)
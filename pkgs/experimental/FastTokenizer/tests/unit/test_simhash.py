@@ -0,0 +1,52 @@
+import pytest
+
+from fasttokenizer import hamming_distance, simhash, simhash_batch, simhash_pairs
+
+BASE = "the quick brown fox jumps over the lazy dog near the river bank today"
+NEAR = "the quick brown fox jumps over the lazy dog near the river bank tonight"
+FAR = "completely different words about stock markets and interest rates rising"
+
+
+@pytest.mark.unit
+def test_fingerprints_are_stable_64_bit_ints():
+    fingerprint = simhash(BASE)
+    assert 0 <= fingerprint < 2**64
+    assert fingerprint == simhash(BASE.split())
+    assert simhash("") == 0
+    assert simhash_batch([BASE, NEAR.split()]) == [fingerprint, simhash(NEAR)]
+
+
+@pytest.mark.unit
+def test_similar_texts_are_close():
+    assert hamming_distance(simhash(BASE), simhash(NEAR)) < hamming_distance(simhash(BASE), simhash(FAR))
+    assert hamming_distance(0b1011, 0b0001) == 2
+    assert hamming_distance(2**64 - 1, 0) == 64
+
+
+@pytest.mark.unit
+def test_pairs_within_distance():
+    fingerprints = [0, 0b111, 0b1, 2**63, 0]
+    assert simhash_pairs(fingerprints, max_distance=1) == [
+        (0, 2, 1),
+        (0, 3, 1),
+        (0, 4, 0),
+        (2, 4, 1),
+        (3, 4, 1),
+    ]
+    assert (0, 1, 3) in simhash_pairs(fingerprints, max_distance=3)
+    assert simhash_pairs([], max_distance=3) == []
+    with pytest.raises(ValueError):
+        simhash_pairs(fingerprints, max_distance=64)
+
+
+@pytest.mark.unit
+def test_pairs_agree_with_brute_force():
+    texts = [BASE, NEAR, FAR, BASE + " again", FAR.upper(), "short text"]
+    fingerprints = simhash_batch(texts, ngram=2)
+    expected = sorted(
+        (i, j, hamming_distance(a, b))
+        for i, a in enumerate(fingerprints)
+        for j, b in enumerate(fingerprints)
+        if i < j and hamming_distance(a, b) <= 12
+    )
+    assert simhash_pairs(fingerprints, max_distance=12) == expected
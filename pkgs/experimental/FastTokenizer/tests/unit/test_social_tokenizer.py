@@ -0,0 +1,69 @@
+import pytest
+
+from fasttokenizer import SocialTokenizer, TokenizerPipeline
+
+THUMBS_UP = "\U0001F44D\U0001F3FD"
+FAMILY = "\U0001F468\u200d\U0001F469\u200d\U0001F467"
+FLAG = "\U0001F1E9\U0001F1EA"
+
+
+@pytest.mark.unit
+def test_entities_are_single_tokens():
+    text = "@support see https://example.com/a?b=1 or mail help.desk+x@example.co.uk #Bug42"
+    assert SocialTokenizer().tokenize_with_types(text) == [
+        ("@support", "mention"),
+        ("see", "word"),
+        ("https://example.com/a?b=1", "url"),
+        ("or", "word"),
+        ("mail", "word"),
+        ("help.desk+x@example.co.uk", "email"),
+        ("#Bug42", "hashtag"),
+    ]
+
+
+@pytest.mark.unit
+def test_emoji_sequences_and_emoticons():
+    text = f"love it{THUMBS_UP}{FAMILY} {FLAG} <3 :-)"
+    assert SocialTokenizer().tokenize_with_types(text) == [
+        ("love", "word"),
+        ("it", "word"),
+        (THUMBS_UP, "emoji"),
+        (FAMILY, "emoji"),
+        (FLAG, "emoji"),
+        ("<3", "emoticon"),
+        (":-)", "emoticon"),
+    ]
+
+
+@pytest.mark.unit
+def test_url_trailing_punctuation():
+    tokenizer = SocialTokenizer()
+    assert tokenizer.tokenize("Docs (www.example.com/x).") == [
+        "Docs", "(", "www.example.com/x", ")", ".",
+    ]
+    assert tokenizer.tokenize("See https://en.wikipedia.org/wiki/Rust_(language)!")[1] == (
+        "https://en.wikipedia.org/wiki/Rust_(language)"
+    )
+
+
+@pytest.mark.unit
+def test_words_numbers_and_punctuation():
+    assert SocialTokenizer().tokenize_with_types("It's 3.5x, costs 1,200!") == [
+        ("It's", "word"),
+        ("3.5x", "word"),
+        (",", "punct"),
+        ("costs", "word"),
+        ("1,200", "number"),
+        ("!", "punct"),
+    ]
+    assert "hashtag" in SocialTokenizer.types()
+
+
+@pytest.mark.unit
+def test_offsets_and_pipeline():
+    text = f"héllo {FAMILY} @bob"
+    spans = SocialTokenizer().tokenize_with_offsets(text)
+    for token, start, end in spans:
+        assert text[start:end] == token
+    pipeline = TokenizerPipeline(SocialTokenizer())
+    assert pipeline.tokenize("#rust @bob") == ["#rust", "@bob"]
@@ -0,0 +1,83 @@
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    RegexTokenizer,
+    SpecialTokens,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+)
+
+
+@pytest.fixture
+def bert_special():
+    return SpecialTokens(cls="[CLS]", sep="[SEP]", pad="[PAD]", unk="[UNK]")
+
+
+@pytest.fixture
+def pipeline(bert_special):
+    return TokenizerPipeline(
+        RegexTokenizer(r"\w+|[^\w\s]"),
+        normalizer=Normalizer(lowercase=True),
+        vocab=Vocab(unk_token="[UNK]"),
+        special_tokens=bert_special,
+    )
+
+
+@pytest.mark.unit
+class TestSpecialTokens:
+    def test_default_templates(self, bert_special):
+        assert bert_special.single == "[CLS] $A [SEP]"
+        assert bert_special.pair == "[CLS] $A [SEP] $B [SEP]"
+        assert set(bert_special.tokens()) == {"[CLS]", "[SEP]", "[PAD]", "[UNK]"}
+
+    def test_bos_eos_templates(self):
+        special = SpecialTokens(bos="<s>", eos="</s>")
+        assert special.single == "<s> $A </s>"
+        assert special.pair == "<s> $A </s> $B </s>"
+
+    def test_custom_templates_register_tokens(self):
+        special = SpecialTokens(single="<bos> $A", pair="<bos> $A <mid> $B")
+        assert "<mid>" in special
+        assert len(special) == 2
+
+    def test_single_template_rejects_b(self):
+        with pytest.raises(ValueError):
+            SpecialTokens(single="$A $B")
+
+
+@pytest.mark.unit
+class TestPipelineSpecialTokens:
+    def test_special_tokens_get_the_first_ids(self, pipeline):
+        assert pipeline.vocab.tokens() == ["[UNK]", "[PAD]", "[CLS]", "[SEP]"]
+
+    def test_not_split_or_normalized(self, pipeline):
+        assert pipeline.tokenize("Hello[SEP]World") == ["hello", "[SEP]", "world"]
+
+    def test_offsets_around_special_tokens(self, pipeline):
+        assert pipeline.tokenize_with_offsets("Hi [SEP] you") == [
+            ("hi", 0, 2),
+            ("[SEP]", 3, 8),
+            ("you", 9, 12),
+        ]
+
+    def test_encode_inserts_template(self, pipeline):
+        vocab = pipeline.vocab
+        ids = pipeline.encode("Hello world")
+        assert vocab.decode(ids) == ["[CLS]", "hello", "world", "[SEP]"]
+        ids = pipeline.encode("Hello", pair="world!")
+        assert vocab.decode(ids) == ["[CLS]", "hello", "[SEP]", "world", "!", "[SEP]"]
+        assert vocab.decode(pipeline.encode("Hello", add_special_tokens=False)) == ["hello"]
+
+    def test_decode_skips_special_tokens(self, pipeline):
+        ids = pipeline.encode("Hello", pair="world")
+        assert pipeline.decode(ids) == "hello world"
+        assert pipeline.decode(ids, skip_special_tokens=False) == "[CLS] hello [SEP] world [SEP]"
+
+    def test_add_special_tokens(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), pre_tokenizer=RegexTokenizer(r"\S+"))
+        assert pipeline.tokenize("a<mask>b") == ["a<mask>b"]
+        pipeline.add_special_tokens(["<mask>"])
+        assert pipeline.tokenize("a<mask>b") == ["a", "<mask>", "b"]
+        assert "<mask>" in pipeline.special_tokens
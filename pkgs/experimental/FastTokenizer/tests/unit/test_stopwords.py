@@ -0,0 +1,67 @@
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    RegexTokenizer,
+    SpecialTokens,
+    StopWordFilter,
+    TokenizerPipeline,
+)
+
+
+@pytest.mark.unit
+def test_builtin_languages():
+    assert {"en", "de", "fr", "es"} <= set(StopWordFilter.languages())
+    english = StopWordFilter("english")
+    assert "the" in english and "The" in english
+    assert "tokenizer" not in english
+    assert len(StopWordFilter(["en", "de"])) > len(english)
+    assert "und" in StopWordFilter("de")
+    assert "и" in StopWordFilter("RU")
+
+
+@pytest.mark.unit
+def test_unknown_language():
+    with pytest.raises(ValueError):
+        StopWordFilter("klingon")
+
+
+@pytest.mark.unit
+def test_filter_tokens():
+    stop = StopWordFilter("en")
+    assert stop.filter(["The", "cat", "is", "on", "the", "mat"]) == ["cat", "mat"]
+
+
+@pytest.mark.unit
+def test_custom_words_and_editing():
+    stop = StopWordFilter("en", words=["Foo"])
+    assert stop.is_stop_word("foo")
+    stop.remove(["not", "no"])
+    stop.add(["bar"])
+    assert stop.filter(["this", "is", "not", "bar", "good"]) == ["not", "good"]
+    assert StopWordFilter(words=["a", "b"]).words() == ["a", "b"]
+
+
+@pytest.mark.unit
+def test_case_sensitive():
+    stop = StopWordFilter(words=["US"], case_sensitive=True)
+    assert stop.case_sensitive
+    assert stop.filter(["us", "US"]) == ["us"]
+
+
+@pytest.mark.unit
+def test_pipeline_filter_stage():
+    pipeline = TokenizerPipeline(
+        RegexTokenizer(r"\w+"),
+        normalizer=Normalizer(lowercase=True),
+        special_tokens=SpecialTokens(additional=["[MASK]"]),
+        filters=[StopWordFilter("en", words=["a"])],
+    )
+    assert pipeline.tokenize("The cat sat on a [MASK] mat") == ["cat", "sat", "[MASK]", "mat"]
+    assert pipeline.tokenize_with_offsets("The cat") == [("cat", 4, 7)]
+
+
+@pytest.mark.unit
+def test_pipeline_rejects_non_filters():
+    with pytest.raises(TypeError):
+        TokenizerPipeline(RegexTokenizer(r"\w+"), filters=["the"])
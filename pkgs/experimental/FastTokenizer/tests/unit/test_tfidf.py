@@ -0,0 +1,89 @@
+import math
+
+import pytest
+
+from fasttokenizer import (
+    CsrMatrix,
+    Normalizer,
+    RegexTokenizer,
+    TfidfVectorizer,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+)
+
+CORPUS = ["the cat sat", "the dog sat", "the cat ran away"]
+
+
+@pytest.mark.unit
+def test_fit_learns_sorted_vocabulary_and_idf():
+    vectorizer = TfidfVectorizer(WhitespaceTokenizer())
+    vectorizer.fit(iter(CORPUS), batch_size=2)
+    assert vectorizer.feature_names() == ["away", "cat", "dog", "ran", "sat", "the"]
+    assert vectorizer.vocabulary.token_to_id("sat") == 4
+    idf = dict(zip(vectorizer.feature_names(), vectorizer.idf))
+    assert idf["the"] == pytest.approx(1.0)
+    assert idf["cat"] == pytest.approx(math.log(4 / 3) + 1)
+    assert idf["dog"] == pytest.approx(math.log(4 / 2) + 1)
+
+
+@pytest.mark.unit
+def test_transform_rows_are_normalized():
+    vectorizer = TfidfVectorizer(WhitespaceTokenizer())
+    matrix = vectorizer.fit_transform(CORPUS)
+    assert isinstance(matrix, CsrMatrix)
+    assert matrix.shape == (3, 6)
+    assert matrix.nnz == 10
+    assert matrix.indptr == [0, 3, 6, 10]
+    assert matrix.indices[:3] == [1, 4, 5]
+    for row in matrix.to_dense():
+        assert sum(v * v for v in row) == pytest.approx(1.0)
+
+    unseen = vectorizer.transform(["zebra", "cat cat"])
+    assert unseen.row(0) == []
+    assert unseen.row(1) == [(1, pytest.approx(1.0))]
+
+
+@pytest.mark.unit
+def test_options_match_reference_weights():
+    vectorizer = TfidfVectorizer(WhitespaceTokenizer(), sublinear_tf=True, smooth_idf=False, norm=None)
+    matrix = vectorizer.fit_transform(["a a a b", "b c"])
+    row = dict(matrix.row(0))
+    assert row[0] == pytest.approx((1 + math.log(3)) * (math.log(2) + 1))
+    assert row[1] == pytest.approx(1.0)
+    assert vectorizer.norm is None
+
+
+@pytest.mark.unit
+def test_document_frequency_limits_and_pipeline():
+    pipeline = TokenizerPipeline(RegexTokenizer(r"\w+"), normalizer=Normalizer(lowercase=True))
+    corpus = ["The cat", "the dog", "THE bird", "a cat"]
+    vectorizer = TfidfVectorizer(pipeline, min_df=2, max_df=0.5)
+    vectorizer.fit(corpus)
+    assert vectorizer.feature_names() == ["cat"]
+    top = TfidfVectorizer(pipeline, max_features=2)
+    top.fit(corpus)
+    assert top.feature_names() == ["cat", "the"]
+
+
+@pytest.mark.unit
+def test_errors():
+    vectorizer = TfidfVectorizer(WhitespaceTokenizer())
+    assert vectorizer.feature_names() is None
+    with pytest.raises(ValueError):
+        vectorizer.transform(["not fitted"])
+    with pytest.raises(ValueError):
+        TfidfVectorizer(WhitespaceTokenizer(), max_df=1.5)
+    with pytest.raises(ValueError):
+        TfidfVectorizer(WhitespaceTokenizer(), norm="l3")
+    with pytest.raises(ValueError):
+        TfidfVectorizer(WhitespaceTokenizer(), min_df=5).fit(CORPUS)
+    with pytest.raises(IndexError):
+        TfidfVectorizer(WhitespaceTokenizer()).fit_transform(CORPUS).row(3)
+
+
+@pytest.mark.unit
+def test_to_scipy():
+    sparse = pytest.importorskip("scipy.sparse")
+    matrix = TfidfVectorizer(WhitespaceTokenizer()).fit_transform(CORPUS).to_scipy()
+    assert sparse.issparse(matrix)
+    assert matrix.shape == (3, 6)
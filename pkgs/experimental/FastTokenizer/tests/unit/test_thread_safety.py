@@ -0,0 +1,115 @@
+import threading
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+
+from fasttokenizer import (
+    BpeTokenizer,
+    CjkTokenizer,
+    GraphemeTokenizer,
+    RegexTokenizer,
+    SocialTokenizer,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+)
+
+TEXTS = [f"document {i} with words like w{i % 97} and 中文分词 #tag{i % 7}" for i in range(2000)]
+
+
+def hammer(fn, texts=TEXTS, workers=8):
+    with ThreadPoolExecutor(max_workers=workers) as pool:
+        return list(pool.map(fn, texts))
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "tokenizer",
+    [
+        RegexTokenizer(r"\w+"),
+        WhitespaceTokenizer(),
+        GraphemeTokenizer(skip_whitespace=True),
+        SocialTokenizer(),
+        CjkTokenizer(["中文", "分词"]),
+        BpeTokenizer({"a": 0}, [], byte_level=False, unk_token="a"),
+    ],
+)
+def test_concurrent_tokenize_matches_serial(tokenizer):
+    expected = [tokenizer.tokenize(text) for text in TEXTS]
+    assert hammer(tokenizer.tokenize) == expected
+
+
+def while_running(change, work):
+    """Call `change` in a loop on another thread until `work` is done,
+    returning what `work` returned and any errors `change` raised."""
+    stop = threading.Event()
+    errors = []
+
+    def loop():
+        while not stop.is_set():
+            try:
+                change()
+            except Exception as e:  # noqa: BLE001
+                errors.append(e)
+
+    thread = threading.Thread(target=loop)
+    thread.start()
+    try:
+        result = work()
+    finally:
+        stop.set()
+        thread.join()
+    return result, errors
+
+
+BATCHES = [TEXTS[i : i + 100] for i in range(0, len(TEXTS), 100)]
+
+
+@pytest.mark.unit
+def test_swapping_vocab_while_tokenizing():
+    tokenizer = RegexTokenizer(r"\w+", vocab=Vocab())
+    expected = [tokenizer.tokenize_batch(batch) for batch in BATCHES]
+
+    def swap():
+        tokenizer.vocab = Vocab()
+
+    results, errors = while_running(swap, lambda: hammer(tokenizer.tokenize_batch, BATCHES))
+    assert errors == []
+    assert results == expected
+
+
+@pytest.mark.unit
+def test_changing_pipeline_while_encoding():
+    pipeline = TokenizerPipeline(RegexTokenizer(r"\w+|\S"), vocab=Vocab())
+    counter = iter(range(10**9))
+
+    def add():
+        pipeline.add_special_tokens([f"[S{next(counter) % 5}]"])
+
+    results, errors = while_running(add, lambda: hammer(pipeline.encode_batch, BATCHES))
+    assert errors == []
+    assert [len(ids) for ids in results] == [len(batch) for batch in BATCHES]
+
+
+@pytest.mark.unit
+def test_adding_words_while_segmenting():
+    tokenizer = CjkTokenizer(["中文"])
+    counter = iter(range(10**9))
+
+    def add():
+        tokenizer.add_words([f"词{next(counter)}"])
+
+    results, errors = while_running(add, lambda: hammer(tokenizer.tokenize_batch, BATCHES))
+    assert errors == []
+    assert results[0][0] == tokenizer.tokenize(TEXTS[0])
+
+
+@pytest.mark.unit
+def test_shared_vocab_assigns_unique_ids():
+    vocab = Vocab()
+    tokenizer = WhitespaceTokenizer(vocab=vocab)
+    texts = [f"t{i} t{i + 1} t{i + 2}" for i in range(3000)]
+    hammer(tokenizer.encode, texts)
+    assert len(vocab) == 3002
+    ids = vocab.encode([f"t{i}" for i in range(3002)])
+    assert sorted(ids) == list(range(3002))
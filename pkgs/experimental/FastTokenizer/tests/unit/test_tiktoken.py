@@ -0,0 +1,99 @@
+import base64
+
+import pytest
+
+from fasttokenizer import TiktokenEncoder
+
+MERGES = [b"he", b"ll", b"hell", b"hello", b" w", b" wo", b"\n\n"]
+
+
+@pytest.fixture
+def ranks():
+    ranks = {bytes([b]): b for b in range(256)}
+    for token in MERGES:
+        ranks[token] = len(ranks)
+    return ranks
+
+
+@pytest.fixture
+def encoder(ranks):
+    return TiktokenEncoder.from_ranks(ranks, "cl100k_base")
+
+
+@pytest.mark.unit
+class TestTiktokenEncoder:
+    """Unit tests for the tiktoken-compatible encoder."""
+
+    def test_encode(self, encoder, ranks):
+        assert encoder.encode("hello") == [ranks[b"hello"]]
+        assert encoder.encode("hello wo") == [ranks[b"hello"], ranks[b" wo"]]
+
+    def test_merges_by_rank(self, encoder, ranks):
+        """Pieces without a direct entry are merged pairwise by rank."""
+        assert encoder.encode("hellx") == [ranks[b"hell"], ranks[b"x"]]
+
+    def test_roundtrip(self, encoder):
+        text = "hello wörld 🎉\n\n\tdone  "
+        assert encoder.decode(encoder.encode(text)) == text
+        assert encoder.decode_bytes(encoder.encode(text)) == text.encode("utf-8")
+
+    def test_count_tokens(self, encoder):
+        texts = ["hello world", "", "a  b"]
+        assert encoder.count_tokens_batch(texts) == [
+            len(encoder.encode(text)) for text in texts
+        ]
+        assert encoder.count_tokens("hello") == 1
+
+    def test_whitespace_lookahead(self, encoder):
+        """The last space of a run is attached to the following word."""
+        assert encoder.tokenize("a  wo") == ["a", " ", " wo"]
+        assert encoder.tokenize("a\n\nb") == ["a", "\n\n", "b"]
+
+    def test_special_tokens(self, encoder):
+        text = "hello<|endoftext|>"
+        eot = encoder.special_tokens["<|endoftext|>"]
+        with pytest.raises(ValueError):
+            encoder.encode(text)
+        assert encoder.encode(text, allowed_special="all")[-1] == eot
+        assert encoder.encode(text, allowed_special={"<|endoftext|>"})[-1] == eot
+        assert eot not in encoder.encode(text, disallowed_special=())
+        assert eot not in encoder.encode_ordinary(text)
+        assert encoder.decode([eot]) == "<|endoftext|>"
+
+    def test_offsets(self, encoder):
+        tokens = encoder.tokenize_with_offsets("hello wo")
+        assert tokens == [("hello", 0, 5), (" wo", 5, 8)]
+        # Byte tokens inside a multi-byte character share its span.
+        assert [t[1:] for t in encoder.tokenize_with_offsets("é")] == [(0, 1), (0, 1)]
+
+    def test_from_file(self, tmp_path, ranks, encoder):
+        path = tmp_path / "ranks.tiktoken"
+        path.write_text(
+            "".join(
+                f"{base64.b64encode(token).decode()} {rank}\n"
+                for token, rank in ranks.items()
+            )
+        )
+        loaded = TiktokenEncoder.from_file(str(path), encoding="cl100k_base")
+        assert loaded.name == "cl100k_base"
+        assert loaded.n_vocab == encoder.n_vocab
+        assert loaded.encode("hello world") == encoder.encode("hello world")
+
+    def test_invalid_id(self, encoder):
+        with pytest.raises(ValueError):
+            encoder.decode([10**6])
+
+    def test_unknown_encoding(self, ranks):
+        with pytest.raises(ValueError):
+            TiktokenEncoder.from_ranks(ranks, "nope")
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("encoding", ["r50k_base", "cl100k_base", "o200k_base"])
+def test_matches_tiktoken(encoding):
+    """Ids match the reference implementation when it is installed."""
+    tiktoken = pytest.importorskip("tiktoken")
+    reference = tiktoken.get_encoding(encoding)
+    encoder = TiktokenEncoder.from_ranks(reference._mergeable_ranks, encoding)
+    for text in ["Hello, world!", "  leading\n\n\ttabs  ", "I'm 12345 ünïcödé 🎉"]:
+        assert encoder.encode(text) == reference.encode(text)
@@ -0,0 +1,44 @@
+import pytest
+
+from fasttokenizer import Normalizer, TokenChunker, TokenizerPipeline, WhitespaceTokenizer
+
+
+@pytest.mark.unit
+def test_chunks_with_overlap_and_offsets():
+    chunker = TokenChunker(WhitespaceTokenizer(), chunk_size=3, overlap=1)
+    text = "a b c d e f g"
+    chunks = chunker.chunk(text)
+    assert chunks == [("a b c", 0, 5), ("c d e", 4, 9), ("e f g", 8, 13)]
+    assert all(text[start:end] == chunk for chunk, start, end in chunks)
+
+
+@pytest.mark.unit
+def test_last_chunk_and_empty_text():
+    chunker = TokenChunker(WhitespaceTokenizer(), chunk_size=2)
+    assert chunker.chunk("one two three") == [("one two", 0, 7), ("three", 8, 13)]
+    assert chunker.chunk("") == []
+    assert chunker.chunk_size == 2 and chunker.overlap == 0
+
+
+@pytest.mark.unit
+def test_char_and_byte_offsets():
+    chunker = TokenChunker(WhitespaceTokenizer(), chunk_size=2)
+    assert chunker.chunk("café au lait") == [("café au", 0, 7), ("lait", 8, 12)]
+    assert chunker.chunk("café au lait", unit="byte") == [("café au", 0, 8), ("lait", 9, 13)]
+    assert chunker.chunk_batch(["a b c", "café"]) == [[("a b", 0, 3), ("c", 4, 5)], [("café", 0, 4)]]
+
+
+@pytest.mark.unit
+def test_pipeline_offsets_point_into_source():
+    pipeline = TokenizerPipeline(WhitespaceTokenizer(), normalizer=Normalizer(lowercase=True))
+    assert TokenChunker(pipeline, chunk_size=1).chunk("Hello World") == [("Hello", 0, 5), ("World", 6, 11)]
+
+
+@pytest.mark.unit
+def test_invalid_sizes():
+    with pytest.raises(ValueError):
+        TokenChunker(WhitespaceTokenizer(), chunk_size=0)
+    with pytest.raises(ValueError):
+        TokenChunker(WhitespaceTokenizer(), chunk_size=4, overlap=4)
+    with pytest.raises(TypeError):
+        TokenChunker("not a tokenizer")
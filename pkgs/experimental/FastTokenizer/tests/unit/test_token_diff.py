@@ -0,0 +1,70 @@
+import difflib
+import random
+
+import pytest
+
+from fasttokenizer import Normalizer, TokenizerPipeline, WhitespaceTokenizer, token_diff
+
+
+def apply(ops, old, new):
+    out = []
+    for tag, i1, i2, j1, j2 in ops:
+        out += old[i1:i2] if tag == "equal" else new[j1:j2]
+    return out
+
+
+@pytest.mark.unit
+class TestTokenDiff:
+    """Unit tests for token-level Myers diffs."""
+
+    def test_opcodes(self):
+        ops = token_diff("the quick brown fox", "the slow brown dog jumps", WhitespaceTokenizer())
+        assert ops == [
+            ("equal", 0, 1, 0, 1),
+            ("delete", 1, 2, 1, 1),
+            ("insert", 2, 2, 1, 2),
+            ("equal", 2, 3, 2, 3),
+            ("delete", 3, 4, 3, 3),
+            ("insert", 4, 4, 3, 5),
+        ]
+
+    def test_identical_and_empty(self):
+        tokenizer = WhitespaceTokenizer()
+        assert token_diff("a b", "a b", tokenizer) == [("equal", 0, 2, 0, 2)]
+        assert token_diff("", "a b", tokenizer) == [("insert", 0, 0, 0, 2)]
+        assert token_diff("a b", "", tokenizer) == [("delete", 0, 2, 0, 0)]
+        assert token_diff("", "", tokenizer) == []
+
+    def test_edit_scripts_are_shortest(self):
+        rng = random.Random(0)
+        tokenizer = WhitespaceTokenizer()
+        for _ in range(300):
+            old = [rng.choice("abcd") for _ in range(rng.randint(0, 20))]
+            new = [rng.choice("abcd") for _ in range(rng.randint(0, 20))]
+            ops = token_diff(" ".join(old), " ".join(new), tokenizer)
+            assert apply(ops, old, new) == new
+            matched = sum(i2 - i1 for tag, i1, i2, _, _ in ops if tag == "equal")
+            # difflib is not always optimal, so it only bounds the match count.
+            lcs = [[0] * (len(new) + 1) for _ in range(len(old) + 1)]
+            for i in range(len(old) - 1, -1, -1):
+                for j in range(len(new) - 1, -1, -1):
+                    lcs[i][j] = lcs[i + 1][j + 1] + 1 if old[i] == new[j] else max(lcs[i + 1][j], lcs[i][j + 1])
+            assert matched == lcs[0][0]
+            blocks = difflib.SequenceMatcher(a=old, b=new, autojunk=False).get_matching_blocks()
+            assert matched >= sum(block.size for block in blocks)
+
+    def test_text_offsets(self):
+        old, new = "héllo wörld", "héllo big  wörld!"
+        ops = token_diff(old, new, WhitespaceTokenizer(), unit="char")
+        assert ops == [("equal", 0, 5, 0, 5), ("delete", 6, 11, 5, 5), ("insert", 11, 11, 6, 17)]
+        assert new[6:17] == "big  wörld!"
+        assert token_diff(old, new, WhitespaceTokenizer(), unit="byte")[1] == ("delete", 7, 13, 6, 6)
+        with pytest.raises(ValueError):
+            token_diff(old, new, WhitespaceTokenizer(), unit="token")
+
+    def test_pipeline_compares_normalized_tokens(self):
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), normalizer=Normalizer(lowercase=True))
+        ops = token_diff("Hello World", "hello world again", pipeline, unit="char")
+        assert ops == [("equal", 0, 11, 0, 11), ("insert", 11, 11, 12, 17)]
+        with pytest.raises(TypeError):
+            token_diff("a", "b", "not a tokenizer")
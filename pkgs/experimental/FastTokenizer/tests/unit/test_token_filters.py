@@ -0,0 +1,54 @@
+import pytest
+
+from fasttokenizer import (
+    LengthFilter,
+    NumericFilter,
+    RegexFilter,
+    StopWordFilter,
+    TokenizerPipeline,
+    WhitespaceTokenizer,
+)
+
+
+@pytest.mark.unit
+def test_length_filter():
+    tokens = ["a", "to", "café", "tokenization", "日本"]
+    assert LengthFilter(min_length=2, max_length=4).filter(tokens) == ["to", "café", "日本"]
+    assert LengthFilter(3).filter(tokens) == ["café", "tokenization"]
+    with pytest.raises(ValueError):
+        LengthFilter(5, 2)
+
+
+@pytest.mark.unit
+def test_numeric_filter():
+    tokens = ["42", "-3.5", "1,000", "²", "٣", "v2", "3rd", "-", "1e5", "abc"]
+    assert NumericFilter().filter(tokens) == ["v2", "3rd", "-", "1e5", "abc"]
+
+
+@pytest.mark.unit
+def test_regex_filter_drop_and_keep():
+    tokens = ["hello", "@user", "#tag", "world", "a@b"]
+    assert RegexFilter(r"[@#]\w+").filter(tokens) == ["hello", "world", "a@b"]
+    keep = RegexFilter(r"[a-z]+", mode="keep")
+    assert keep.mode == "keep"
+    assert keep.filter(tokens) == ["hello", "world"]
+    with pytest.raises(ValueError):
+        RegexFilter("(")
+    with pytest.raises(ValueError):
+        RegexFilter("x", mode="maybe")
+
+
+@pytest.mark.unit
+def test_filters_chain_in_pipeline():
+    pipeline = TokenizerPipeline(
+        WhitespaceTokenizer(),
+        filters=[
+            LengthFilter(min_length=2),
+            NumericFilter(),
+            StopWordFilter("en"),
+            RegexFilter(r"https?://\S+"),
+        ],
+    )
+    text = "In 2024 a cat visited https://example.com and ate 3 fish"
+    assert pipeline.tokenize(text) == ["cat", "visited", "ate", "fish"]
+    assert pipeline.tokenize_with_offsets("a big 7 dog") == [("big", 2, 5), ("dog", 8, 11)]
@@ -0,0 +1,58 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer, WhitespaceTokenizer, token_shapes
+
+
+@pytest.mark.unit
+class TestTokenShapes:
+    """Unit tests for token shape strings and casing flags."""
+
+    @pytest.mark.parametrize(
+        "token, shape",
+        [
+            ("John", "Xxxx"),
+            ("Hello", "Xxxxx"),
+            ("2024", "dddd"),
+            ("ab-cd", "xx-xx"),
+            ("USA", "XXX"),
+            ("iPhone15", "xXxxxxdd"),
+            ("Émilie", "Xxxxx"),
+            ("...", "..."),
+        ],
+    )
+    def test_shapes(self, token, shape):
+        assert token_shapes([token])["shapes"] == [shape]
+
+    def test_max_repeat(self):
+        assert token_shapes(["Hello", "123456"], max_repeat=2)["shapes"] == ["Xxx", "dd"]
+        with pytest.raises(ValueError):
+            token_shapes(["a"], max_repeat=0)
+
+    def test_flags_match_str_methods(self):
+        tokens = ["Hello", "hello", "HELLO", "Hello World", "O'Neil", "42", "a1", "!?", "", "Ünïcode"]
+        result = token_shapes(tokens)
+        assert result["is_alpha"] == [t.isalpha() for t in tokens]
+        assert result["is_digit"] == [t.isdigit() for t in tokens]
+        assert result["is_lower"] == [t.islower() for t in tokens]
+        assert result["is_upper"] == [t.isupper() for t in tokens]
+        assert result["is_title"] == [t.istitle() for t in tokens]
+        assert result["is_punct"] == [False] * 7 + [True, False, False]
+
+    def test_tokenize_in_same_pass(self):
+        result = token_shapes("Call Bob at 555-1234", tokenizer=WhitespaceTokenizer())
+        assert result["tokens"] == ["Call", "Bob", "at", "555-1234"]
+        assert result["shapes"] == ["Xxxx", "Xxx", "xx", "ddd-dddd"]
+        assert "tokens" not in token_shapes(["a"])
+        with pytest.raises(ValueError):
+            token_shapes("Call Bob")
+        with pytest.raises(ValueError):
+            token_shapes(["Call"], tokenizer=RegexTokenizer(r"\w+"))
+
+    def test_numpy(self):
+        np = pytest.importorskip("numpy")
+        result = token_shapes(["Hi", "42"], return_tensors="np")
+        assert result["is_title"].dtype == np.bool_
+        assert result["is_digit"].tolist() == [False, True]
+        assert result["shapes"] == ["Xx", "dd"]
+        with pytest.raises(ValueError):
+            token_shapes(["Hi"], return_tensors="pt")
@@ -0,0 +1,111 @@
+import pytest
+
+import fasttokenizer
+from fasttokenizer import (
+    CjkTokenizer,
+    CodeTokenizer,
+    GraphemeTokenizer,
+    KeywordTokenizer,
+    Normalizer,
+    RegexTokenizer,
+    SocialTokenizer,
+    TokenizerPipeline,
+    TrieTokenizer,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+TEXT = "The quick brown fox, jumps over the lazy dog."
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "tokenizer",
+    [
+        WhitespaceTokenizer(),
+        RegexTokenizer(r"\w+|[^\w\s]"),
+        GraphemeTokenizer(),
+        CjkTokenizer(),
+        CodeTokenizer(),
+        SocialTokenizer(),
+        KeywordTokenizer(["quick brown", "lazy"]),
+        TrieTokenizer(["quick", "fox"]),
+        TrieTokenizer(["quick", "fox"], unknown="token"),
+        WordPieceTokenizer({"[UNK]": 0, "the": 1, "quick": 2, "fox": 3, "##es": 4}, lowercase=True),
+        TokenizerPipeline(RegexTokenizer(r"\w+"), pre_tokenizer=WhitespaceTokenizer()),
+        TokenizerPipeline(WhitespaceTokenizer(), normalizer=Normalizer(lowercase=True)),
+    ],
+)
+def test_iter_and_foreach_match_tokenize(tokenizer):
+    expected = tokenizer.tokenize(TEXT)
+    assert list(tokenizer.tokenize_iter(TEXT)) == expected
+    seen = []
+    assert tokenizer.tokenize_foreach(TEXT, seen.append) == len(expected)
+    assert seen == expected
+
+
+@pytest.mark.unit
+def test_foreach_stops_when_callback_returns_false():
+    seen = []
+
+    def visit(token):
+        seen.append(token)
+        return token != "brown"
+
+    assert WhitespaceTokenizer().tokenize_foreach(TEXT, visit) == 3
+    assert seen == ["The", "quick", "brown"]
+
+
+@pytest.mark.unit
+def test_foreach_propagates_callback_errors():
+    def fail(token):
+        raise KeyError(token)
+
+    with pytest.raises(KeyError):
+        WhitespaceTokenizer().tokenize_foreach(TEXT, fail)
+
+
+@pytest.mark.unit
+def test_iter_and_foreach_check_limits():
+    tokenizer = RegexTokenizer(r"\S+", max_token_length=4)
+    with pytest.raises(ValueError, match="exceeds max_token_length=4"):
+        tokenizer.tokenize_iter(TEXT)
+    with pytest.raises(ValueError, match="exceeds max_token_length=4"):
+        tokenizer.tokenize_foreach(TEXT, print)
+
+
+@pytest.mark.unit
+def test_iter_is_lazy():
+    tokens = WhitespaceTokenizer().tokenize_iter("a b c")
+    assert iter(tokens) is tokens
+    assert next(tokens) == "a"
+    assert tokens.__length_hint__() == 2
+    assert list(tokens) == ["b", "c"]
+    assert tokens.__length_hint__() == 0
+    assert sum(1 for _ in WhitespaceTokenizer().tokenize_iter("")) == 0
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "name",
+    [
+        "BpeTokenizer",
+        "CjkTokenizer",
+        "CodeTokenizer",
+        "GraphemeTokenizer",
+        "KeywordTokenizer",
+        "RegexTokenizer",
+        "SentencePieceTokenizer",
+        "SocialTokenizer",
+        "TiktokenEncoder",
+        "Tokenizer",
+        "TokenizerPipeline",
+        "TrieTokenizer",
+        "WhitespaceTokenizer",
+        "WordPieceTokenizer",
+    ],
+)
+def test_every_tokenizer_class_has_the_shared_methods(name):
+    cls = getattr(fasttokenizer, name)
+    for method in ["benchmark", "tokenize_batch", "tokenize_iter", "tokenize_foreach", "tokenize_spans"]:
+        assert getattr(cls, method).__doc__, f"{name}.{method}"
@@ -0,0 +1,110 @@
+import pytest
+
+from fasttokenizer import (
+    CjkTokenizer,
+    CodeTokenizer,
+    GraphemeTokenizer,
+    KeywordTokenizer,
+    Normalizer,
+    PolyglotTokenizer,
+    RegexTokenizer,
+    SentencePieceTokenizer,
+    SentenceSplitter,
+    SocialTokenizer,
+    SpecialTokens,
+    StopWordFilter,
+    TokenizerPipeline,
+    TrieTokenizer,
+    WhitespaceTokenizer,
+    WordPieceTokenizer,
+)
+
+TEXT = "Naïve café, déjà vu!"
+MIXED = "Hi @ann, see https://x.io 東京都 #tag déjà-vu [SEP] x = a_b(1);  Done. Bye"
+WORDPIECE = ["[UNK]", "hi", "see", "de", "##ja", "done", "bye", "x", "a"]
+PIECES = [("<unk>", 0.0), ("▁", -1.0)] + [(c, -2.0) for c in "abcdefhiklnorstuwxy"] + [("▁see", -0.5)]
+
+TOKENIZERS = {
+    "whitespace": lambda: WhitespaceTokenizer(preserve_delimiters=True),
+    "regex": lambda: RegexTokenizer(r"(?P<word>\w+)|(?P<punct>[^\w\s])", mode="captures"),
+    "cjk": lambda: CjkTokenizer(["東京"]),
+    "code": lambda: CodeTokenizer(),
+    "grapheme": lambda: GraphemeTokenizer(),
+    "keyword": lambda: KeywordTokenizer(["see", "Bye"]),
+    "social": lambda: SocialTokenizer(),
+    # Neither has `tokenize_spans` of its own; pipelines ask them for spans.
+    "sentences": lambda: TokenizerPipeline(SentenceSplitter()),
+    "trie": lambda: TrieTokenizer(["Hi", "see", "tag"], unknown="token"),
+    "wordpiece": lambda: WordPieceTokenizer({token: i for i, token in enumerate(WORDPIECE)}, lowercase=True),
+    "sentencepiece": lambda: SentencePieceTokenizer(PIECES),
+    "polyglot": lambda: TokenizerPipeline(PolyglotTokenizer({}, normalizers={"und": Normalizer(lowercase=True)})),
+}
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("unit", ["char", "byte"])
+def test_spans_match_offsets(unit):
+    tokenizer = RegexTokenizer(r"\w+|[^\w\s]")
+    offsets = tokenizer.tokenize_with_offsets(TEXT, unit=unit)
+    assert tokenizer.tokenize_spans(TEXT, unit=unit) == [(start, end) for _, start, end in offsets]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("name", TOKENIZERS)
+@pytest.mark.parametrize("unit", ["char", "byte"])
+def test_spans_match_offsets_of_every_tokenizer(name, unit):
+    tokenizer = TOKENIZERS[name]()
+    offsets = tokenizer.tokenize_with_offsets(MIXED, unit=unit)
+    assert offsets
+    assert tokenizer.tokenize_spans(MIXED, unit=unit) == [(start, end) for _, start, end in offsets]
+
+
+@pytest.mark.unit
+def test_spans_slice_the_input():
+    spans = WhitespaceTokenizer().tokenize_spans(TEXT)
+    assert [TEXT[start:end] for start, end in spans] == ["Naïve", "café,", "déjà", "vu!"]
+    assert WhitespaceTokenizer().tokenize_spans("") == []
+
+
+@pytest.mark.unit
+def test_pipeline_spans_point_into_the_raw_input():
+    pipeline = TokenizerPipeline(WhitespaceTokenizer(), normalizer=Normalizer(lowercase=True, strip=True))
+    text = "  Hello  WORLD"
+    assert [text[start:end] for start, end in pipeline.tokenize_spans(text)] == ["Hello", "WORLD"]
+
+
+@pytest.mark.unit
+def test_pipeline_spans_match_those_of_its_tokens():
+    def pipeline(**kwargs):
+        return TokenizerPipeline(
+            WordPieceTokenizer({token: i for i, token in enumerate(WORDPIECE)}),
+            normalizer=Normalizer(lowercase=True, normalize_unicode=True, numbers="placeholder"),
+            pre_tokenizer=RegexTokenizer(r"\S+"),
+            special_tokens=SpecialTokens(sep="[SEP]"),
+            **kwargs,
+        )
+
+    # A token length limit makes the pipeline take its spans from its tokens.
+    spans = pipeline().tokenize_spans(MIXED)
+    assert spans == pipeline(max_token_length=100).tokenize_spans(MIXED)
+    assert len(spans) == len(pipeline().tokenize(MIXED))
+    assert MIXED[slice(*spans[-1])] == "Bye"
+
+    filtered = TokenizerPipeline(WhitespaceTokenizer(), filters=[StopWordFilter(words=["see"])])
+    assert [MIXED[start:end] for start, end in filtered.tokenize_spans(MIXED)][:2] == ["Hi", "@ann,"]
+
+
+@pytest.mark.unit
+def test_spans_as_numpy_array():
+    np = pytest.importorskip("numpy")
+    array = WhitespaceTokenizer().tokenize_spans(TEXT, return_tensors="np")
+    assert array.dtype == np.int64
+    assert array.tolist() == [[0, 5], [6, 11], [12, 16], [17, 20]]
+
+
+@pytest.mark.unit
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        WhitespaceTokenizer().tokenize_spans(TEXT, unit="word")
+    with pytest.raises(ValueError):
+        WhitespaceTokenizer().tokenize_spans(TEXT, return_tensors="pt")
@@ -0,0 +1,420 @@
+import pytest
+
+from fasttokenizer import RegexTokenizer, TokenizerIO, WhitespaceTokenizer
+
+TEXT = "The quick brown fox jumps over the lazy dog.\n" * 50 + "naïve café 🎉 résumé\n"
+
+
+@pytest.fixture
+def corpus(tmp_path):
+    path = tmp_path / "corpus.txt"
+    path.write_text(TEXT, encoding="utf-8")
+    return str(path)
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("chunk_size", [1, 7, 64, 1 << 20])
+def test_tokenize_file_matches_whole_text(corpus, chunk_size):
+    tokenizer = RegexTokenizer(r"\w+|[^\w\s]")
+    batches = list(TokenizerIO().tokenize_file(corpus, tokenizer, chunk_size=chunk_size))
+    assert [t for batch in batches for t in batch] == tokenizer.tokenize(TEXT)
+    assert all(batches)
+
+
+@pytest.mark.unit
+def test_tokenize_file_is_lazy(corpus):
+    stream = TokenizerIO().tokenize_file(corpus, WhitespaceTokenizer(), chunk_size=16)
+    assert iter(stream) is stream
+    first = next(stream)
+    assert first[0] == "The"
+    assert len(first) < len(TEXT.split())
+
+
+@pytest.mark.unit
+def test_tokenize_file_offsets_are_file_bytes(corpus):
+    data = TEXT.encode("utf-8")
+    stream = TokenizerIO().tokenize_file(corpus, WhitespaceTokenizer(), chunk_size=5, with_offsets=True)
+    tokens = [t for batch in stream for t in batch]
+    assert len(tokens) == len(TEXT.split())
+    for token, start, end in tokens:
+        assert data[start:end].decode("utf-8") == token
+
+
+@pytest.mark.unit
+def test_tokenize_file_keeps_leading_spaces(corpus):
+    # GPT-2 style pieces carry their leading space; cutting before the
+    # whitespace run keeps them intact.
+    tokenizer = RegexTokenizer(r" ?\w+| ?[^\w\s]+|\s+")
+    batches = TokenizerIO().tokenize_file(corpus, tokenizer, chunk_size=10)
+    tokens = [t for batch in batches for t in batch]
+    assert tokens == tokenizer.tokenize(TEXT)
+
+
+@pytest.mark.unit
+def test_tokenize_file_invalid_utf8(tmp_path):
+    path = tmp_path / "bad.txt"
+    path.write_bytes(b"hello \xff world")
+    with pytest.raises(IOError):
+        list(TokenizerIO().tokenize_file(str(path), WhitespaceTokenizer()))
+
+
+@pytest.mark.unit
+def test_tokenize_file_missing(tmp_path):
+    with pytest.raises(IOError):
+        TokenizerIO().tokenize_file(str(tmp_path / "missing.txt"), WhitespaceTokenizer())
+
+
+@pytest.mark.unit
+def test_iter_lines(tmp_path):
+    path = tmp_path / "lines.txt"
+    path.write_bytes("first\r\nsecond\n\nthird ünï\nlast".encode("utf-8"))
+    lines = TokenizerIO().iter_lines(str(path))
+    assert next(lines) == "first"
+    assert list(lines) == ["second", "", "third ünï", "last"]
+
+
+@pytest.mark.unit
+def test_iter_lines_trailing_newline(corpus):
+    assert list(TokenizerIO().iter_lines(corpus)) == TEXT.splitlines()
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("delimiter", ["\n\n", "\x1e", "<END>"])
+def test_iter_records(tmp_path, delimiter):
+    records = ["one\nrecord", "two", "", "three 🎉"]
+    path = tmp_path / "records.txt"
+    path.write_text(delimiter.join(records) + delimiter, encoding="utf-8")
+    assert list(TokenizerIO().iter_records(str(path), delimiter)) == records
+
+
+@pytest.mark.unit
+def test_iter_records_rejects_empty_delimiter(corpus):
+    with pytest.raises(ValueError):
+        TokenizerIO().iter_records(corpus, "")
+
+
+@pytest.mark.unit
+def test_iter_lines_invalid_utf8(tmp_path):
+    path = tmp_path / "bad.txt"
+    path.write_bytes(b"ok\nbad \xff\n")
+    lines = TokenizerIO().iter_lines(str(path))
+    assert next(lines) == "ok"
+    with pytest.raises(IOError):
+        next(lines)
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("codec", ["gzip", "bz2"])
+def test_compressed_input_is_detected(tmp_path, codec):
+    module = pytest.importorskip(codec)
+    path = tmp_path / f"corpus.txt.{codec}"
+    path.write_bytes(module.compress(TEXT.encode("utf-8")))
+    compressed = str(path)
+    io = TokenizerIO()
+    assert io.read_file(compressed) == TEXT
+    assert list(io.iter_lines(compressed)) == TEXT.splitlines()
+    batches = io.tokenize_file(compressed, WhitespaceTokenizer(), chunk_size=64)
+    assert [t for batch in batches for t in batch] == TEXT.split()
+
+
+@pytest.mark.unit
+def test_zstd_input_is_detected(tmp_path):
+    zstandard = pytest.importorskip("zstandard")
+    path = tmp_path / "corpus.txt.zst"
+    path.write_bytes(zstandard.ZstdCompressor().compress(TEXT.encode("utf-8")))
+    assert TokenizerIO().read_file(str(path)) == TEXT
+
+
+@pytest.mark.unit
+def test_concatenated_gzip_members(tmp_path):
+    import gzip
+
+    path = tmp_path / "parts.gz"
+    path.write_bytes(gzip.compress(b"one\n") + gzip.compress(b"two\n"))
+    assert list(TokenizerIO().iter_lines(str(path))) == ["one", "two"]
+
+
+@pytest.mark.unit
+def test_explicit_compression(tmp_path):
+    import gzip
+
+    path = tmp_path / "data.bin"
+    path.write_bytes(gzip.compress(b"hello world"))
+    io = TokenizerIO()
+    assert io.read_file(str(path), compression="gzip") == "hello world"
+    with pytest.raises(IOError):
+        io.read_file(str(path), compression="none")
+    with pytest.raises(IOError):
+        io.read_file(str(path), compression="bz2")
+
+
+@pytest.mark.unit
+def test_unknown_compression(corpus):
+    with pytest.raises(ValueError):
+        TokenizerIO().iter_lines(corpus, compression="lzma")
+
+
+@pytest.fixture
+def jsonl(tmp_path):
+    path = tmp_path / "corpus.jsonl"
+    path.write_text(
+        '{"text": "first doc", "id": 1}\n'
+        "\n"
+        '{"id": 2}\n'
+        "not json\n"
+        '{"text": "naïve 🎉", "meta": {"text": 3}}\r\n'
+        '{"text": 5}\n'
+        '{"body": "other field"}\n',
+        encoding="utf-8",
+    )
+    return str(path)
+
+
+@pytest.mark.unit
+def test_iter_jsonl_raises_on_malformed_line(jsonl):
+    docs = TokenizerIO().iter_jsonl(jsonl)
+    assert next(docs) == "first doc"
+    with pytest.raises(ValueError, match="line 3"):
+        next(docs)
+
+
+@pytest.mark.unit
+def test_iter_jsonl_skip(jsonl):
+    docs = TokenizerIO().iter_jsonl(jsonl, on_error="skip")
+    assert list(docs) == ["first doc", "naïve 🎉"]
+    assert docs.skipped == 4
+
+
+@pytest.mark.unit
+def test_iter_jsonl_warn(jsonl):
+    with pytest.warns(UserWarning, match="line 4"):
+        docs = list(TokenizerIO().iter_jsonl(jsonl, on_error="warn"))
+    assert docs == ["first doc", "naïve 🎉"]
+
+
+@pytest.mark.unit
+def test_iter_jsonl_field(jsonl):
+    docs = TokenizerIO().iter_jsonl(jsonl, field="body", on_error="skip")
+    assert list(docs) == ["other field"]
+
+
+@pytest.mark.unit
+def test_iter_jsonl_rejects_unknown_policy(jsonl):
+    with pytest.raises(ValueError):
+        TokenizerIO().iter_jsonl(jsonl, on_error="ignore")
+
+
+@pytest.fixture
+def csv_file(tmp_path):
+    path = tmp_path / "corpus.csv"
+    path.write_text(
+        'id,text,label\n1,plain,a\n2,"quoted, with ""comma""",b\n3,"multi\nline",c\n',
+        encoding="utf-8",
+    )
+    return str(path)
+
+
+@pytest.mark.unit
+def test_iter_csv_by_name_and_index(csv_file):
+    io = TokenizerIO()
+    expected = ["plain", 'quoted, with "comma"', "multi\nline"]
+    assert list(io.iter_csv(csv_file, "text")) == expected
+    assert list(io.iter_csv(csv_file, 1)) == expected
+    assert list(io.iter_csv(csv_file, 0, has_header=False)) == ["id", "1", "2", "3"]
+
+
+@pytest.mark.unit
+def test_iter_csv_tsv(tmp_path):
+    path = tmp_path / "corpus.tsv"
+    path.write_text("a\tb, c\nd\te f\n", encoding="utf-8")
+    assert list(TokenizerIO().iter_csv(str(path), 1, delimiter="\t", has_header=False)) == [
+        "b, c",
+        "e f",
+    ]
+
+
+@pytest.mark.unit
+def test_iter_csv_errors(csv_file, tmp_path):
+    io = TokenizerIO()
+    with pytest.raises(ValueError):
+        io.iter_csv(csv_file, "missing")
+    with pytest.raises(ValueError):
+        io.iter_csv(csv_file, "text", has_header=False)
+    with pytest.raises(ValueError):
+        io.iter_csv(csv_file, 0, delimiter=";;")
+    short = tmp_path / "short.csv"
+    short.write_text("a,b\nc\n", encoding="utf-8")
+    rows = io.iter_csv(str(short), 1, has_header=False)
+    assert next(rows) == "b"
+    with pytest.raises(ValueError, match="line 2"):
+        next(rows)
+
+
+@pytest.mark.unit
+def test_iter_csv_compressed(tmp_path):
+    import gzip
+
+    path = tmp_path / "corpus.csv.gz"
+    path.write_bytes(gzip.compress(b"text\nhello\nworld\n"))
+    assert list(TokenizerIO().iter_csv(str(path), "text")) == ["hello", "world"]
+
+
+@pytest.fixture
+def tree(tmp_path):
+    (tmp_path / "sub" / "deep").mkdir(parents=True)
+    files = {
+        "a.txt": "alpha beta",
+        "b.jsonl": '{"text": "x"}',
+        "sub/c.txt": "gamma",
+        "sub/deep/d.txt": "delta epsilon zeta " * 20,
+        "sub/e.txt.gz": "",
+    }
+    for name, text in files.items():
+        (tmp_path / name).write_text(text, encoding="utf-8")
+    return tmp_path
+
+
+@pytest.mark.unit
+def test_walk_directory(tree):
+    io = TokenizerIO()
+
+    def names(paths):
+        return [p[len(str(tree)) + 1 :].replace("\\", "/") for p in paths]
+
+    assert names(io.walk(str(tree))) == [
+        "a.txt",
+        "b.jsonl",
+        "sub/c.txt",
+        "sub/deep/d.txt",
+        "sub/e.txt.gz",
+    ]
+    assert names(io.walk(str(tree), recursive=False)) == ["a.txt", "b.jsonl"]
+    assert names(io.walk(str(tree), extensions=["txt", ".txt.gz"])) == [
+        "a.txt",
+        "sub/c.txt",
+        "sub/deep/d.txt",
+        "sub/e.txt.gz",
+    ]
+    assert names(io.walk(str(tree), min_size=1, max_size=20)) == ["a.txt", "b.jsonl", "sub/c.txt"]
+
+
+@pytest.mark.unit
+def test_walk_glob(tree):
+    paths = TokenizerIO().walk(str(tree / "**" / "*.txt"))
+    assert [p.rsplit("/", 1)[-1] for p in paths] == ["a.txt", "c.txt", "d.txt"]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("workers", [None, 1, 3])
+def test_tokenize_corpus(tree, workers):
+    io = TokenizerIO()
+    paths = io.walk(str(tree), extensions=["txt"])
+    tokens = io.tokenize_corpus(paths, WhitespaceTokenizer(), workers=workers)
+    assert tokens == [open(p, encoding="utf-8").read().split() for p in paths]
+
+
+@pytest.mark.unit
+def test_tokenize_corpus_errors(tree):
+    io = TokenizerIO()
+    with pytest.raises(IOError):
+        io.tokenize_corpus([str(tree / "missing.txt")], WhitespaceTokenizer())
+    with pytest.raises(ValueError):
+        io.tokenize_corpus([], WhitespaceTokenizer(), workers=0)
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("workers", [1, 3])
+def test_tokenize_corpus_to_output_dir(tmp_path, workers):
+    import gzip
+    import json
+
+    from fasttokenizer import Normalizer
+
+    (tmp_path / "one.txt").write_text("Hello World\n\nfoo bar\n", encoding="utf-8")
+    (tmp_path / "two.txt.gz").write_bytes(gzip.compress("Naïve Café".encode("utf-8")))
+    paths = [str(tmp_path / "one.txt"), str(tmp_path / "two.txt.gz")]
+    calls = []
+    io = TokenizerIO()
+    outputs = io.tokenize_corpus(
+        paths,
+        WhitespaceTokenizer(),
+        output_dir=str(tmp_path / "out"),
+        workers=workers,
+        normalizer=Normalizer(lowercase=True),
+        progress=lambda done, total: calls.append((done, total)),
+        format="jsonl",
+    )
+    assert outputs == [str(tmp_path / "out" / n) for n in ["one.txt.tokens.jsonl", "two.txt.tokens.jsonl"]]
+    lines = [json.loads(line) for line in open(outputs[0], encoding="utf-8")]
+    assert lines == [["hello", "world"], [], ["foo", "bar"]]
+    assert open(outputs[1], encoding="utf-8").read() == '["naïve","café"]\n'
+    assert calls == [(1, 2), (2, 2)]
+    assert sorted(p.name for p in (tmp_path / "out").iterdir()) == ["one.txt.tokens.jsonl", "two.txt.tokens.jsonl"]
+
+    io.tokenize_corpus(paths[:1], WhitespaceTokenizer(), output_dir=str(tmp_path / "txt"))
+    assert (tmp_path / "txt" / "one.txt.tokens.txt").read_text(encoding="utf-8") == "Hello World\n\nfoo bar\n"
+
+
+@pytest.mark.unit
+def test_tokenize_corpus_progress_can_stop(tree):
+    io = TokenizerIO()
+    paths = io.walk(str(tree), extensions=["txt"])
+
+    def progress(done, total):
+        raise KeyboardInterrupt
+
+    with pytest.raises(KeyboardInterrupt):
+        io.tokenize_corpus(paths, WhitespaceTokenizer(), output_dir=str(tree / "out"), workers=1, progress=progress)
+    with pytest.raises(ValueError):
+        io.tokenize_corpus([str(tree / "a.txt"), str(tree / "a.txt")], WhitespaceTokenizer(), output_dir=str(tree / "out"))
+    with pytest.raises(ValueError):
+        io.tokenize_corpus(paths, WhitespaceTokenizer(), output_dir=str(tree / "out"), format="csv")
+    assert not any(p.name.endswith(".partial") for p in (tree / "out").iterdir())
+
+
+ROWS = [["the", "quick", "fox"], [], ["naïve", "🎉"]]
+
+
+@pytest.mark.unit
+def test_write_tokens_parquet(tmp_path):
+    path = tmp_path / "tokens.parquet"
+    assert TokenizerIO().write_tokens_parquet(str(path), iter(ROWS), row_group_size=2) == 3
+    data = path.read_bytes()
+    assert data[:4] == b"PAR1" and data[-4:] == b"PAR1"
+    pq = pytest.importorskip("pyarrow.parquet")
+    assert pq.read_table(path).to_pydict() == {"tokens": ROWS}
+
+
+@pytest.mark.unit
+def test_write_tokens_arrow_ids(tmp_path):
+    path = tmp_path / "ids.arrow"
+    rows = [[], [1, 2, 3], [4]]
+    assert TokenizerIO().write_tokens_arrow(str(path), rows, batch_size=1) == 3
+    assert path.read_bytes()[:6] == b"ARROW1"
+    feather = pytest.importorskip("pyarrow.feather")
+    assert feather.read_table(path).to_pydict() == {"input_ids": rows}
+
+
+@pytest.mark.unit
+def test_write_tokens_from_stream(corpus, tmp_path):
+    io = TokenizerIO()
+    stream = io.tokenize_file(corpus, WhitespaceTokenizer(), chunk_size=64)
+    path = tmp_path / "stream.parquet"
+    rows = io.write_tokens_parquet(str(path), stream, column="words", compression="zstd")
+    assert rows > 1
+    pq = pytest.importorskip("pyarrow.parquet")
+    table = pq.read_table(path)
+    assert table.column_names == ["words"]
+    assert [t for row in table.column("words").to_pylist() for t in row] == TEXT.split()
+
+
+@pytest.mark.unit
+def test_write_tokens_errors(tmp_path):
+    io = TokenizerIO()
+    path = str(tmp_path / "out.parquet")
+    with pytest.raises(TypeError):
+        io.write_tokens_parquet(path, [["a"], [1]])
+    with pytest.raises(TypeError):
+        io.write_tokens_parquet(path, ["not a list"])
+    with pytest.raises(ValueError):
+        io.write_tokens_parquet(path, [["a"]], compression="lz4")
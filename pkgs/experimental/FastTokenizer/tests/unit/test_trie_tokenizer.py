@@ -0,0 +1,55 @@
+import pytest
+
+from fasttokenizer import TokenizerPipeline, TrieTokenizer, Vocab
+
+VOCAB = ["anti", "antibody", "body", "bod", "mono", "clonal", "monoclonal", "é"]
+
+
+@pytest.mark.unit
+def test_longest_match():
+    tokenizer = TrieTokenizer(VOCAB)
+    assert tokenizer.tokenize("monoclonal antibodyé") == ["monoclonal", "antibody", "é"]
+    assert tokenizer.tokenize("antibod") == ["anti", "bod"]
+
+
+@pytest.mark.unit
+def test_unknown_behaviors():
+    text = "antixy body"
+    assert TrieTokenizer(VOCAB).tokenize(text) == ["anti", "x", "y", "body"]
+    assert TrieTokenizer(VOCAB, unknown="skip").tokenize(text) == ["anti", "body"]
+    assert TrieTokenizer(VOCAB, unknown="token").tokenize_with_offsets(text) == [
+        ("anti", 0, 4),
+        ("[UNK]", 4, 6),
+        ("body", 7, 11),
+    ]
+    with pytest.raises(ValueError):
+        TrieTokenizer(VOCAB, unknown="raise")
+
+
+@pytest.mark.unit
+def test_whitespace_in_vocab():
+    tokenizer = TrieTokenizer(["New York", "New", "York"])
+    assert tokenizer.tokenize("New York New  York") == ["New York", "New", "York"]
+    assert TrieTokenizer(["a"], skip_whitespace=False).tokenize("a a") == ["a", " ", "a"]
+
+
+@pytest.mark.unit
+def test_encode_decode_with_vocab_object():
+    vocab = Vocab(VOCAB, unk_token="[UNK]", frozen=True)
+    tokenizer = TrieTokenizer(vocab, unknown="token")
+    ids = tokenizer.encode("antibodyzz")
+    assert ids == [vocab.token_to_id("antibody"), vocab.unk_id]
+    assert tokenizer.decode(ids[:1]) == "antibody"
+    assert tokenizer.vocab.token_to_id("anti") == 0
+
+
+@pytest.mark.unit
+def test_from_file_and_pipeline(tmp_path):
+    path = tmp_path / "vocab.txt"
+    path.write_text("un\nhappy\nunhappy\nness\n", encoding="utf-8")
+    tokenizer = TrieTokenizer.from_file(str(path))
+    assert tokenizer.tokenize("unhappyiness") == ["unhappy", "i", "ness"]
+    assert tokenizer.encode("unhappy") == [2]
+    with pytest.raises(IOError):
+        TrieTokenizer.from_file(str(tmp_path / "missing.txt"))
+    assert TokenizerPipeline(tokenizer).tokenize("unhappy ness") == ["unhappy", "ness"]
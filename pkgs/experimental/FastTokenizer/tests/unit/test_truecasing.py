@@ -0,0 +1,89 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import (
+    Augmenter,
+    Normalizer,
+    Truecaser,
+    TokenizerPipeline,
+    Vocab,
+    WhitespaceTokenizer,
+    detokenize,
+)
+
+NAMES = ["London", "NASA", "New York", "New York City", "iPhone", "AT&T"]
+
+
+@pytest.mark.unit
+class TestTruecaser:
+    """Unit tests for rule-based case restoration."""
+
+    def test_sentences_and_pronoun(self):
+        truecaser = Truecaser()
+        assert truecaser.truecase("hello there. i'm fine! and you? yes") == (
+            "Hello there. I'm fine! And you? Yes"
+        )
+        assert truecaser.truecase("first line\nsecond line") == "First line\nSecond line"
+
+    def test_abbreviations_do_not_end_sentences(self):
+        truecaser = Truecaser()
+        assert truecaser.truecase("see e.g. the docs. then stop") == (
+            "See e.g. the docs. Then stop"
+        )
+        assert truecaser.truecase("ask dr. who") == "Ask dr. who"
+
+    def test_proper_nouns(self):
+        truecaser = Truecaser(NAMES)
+        assert truecaser.truecase("we flew from london to new york city on nasa's dime") == (
+            "We flew from London to New York City on NASA's dime"
+        )
+        # Dictionary casing wins at the start of a sentence, and matching
+        # ignores the case of the input.
+        assert truecaser.truecase("iphone sales at at&t. NEW YORK loves it") == (
+            "iPhone sales at AT&T. New York loves it"
+        )
+        # Only whole words match.
+        assert truecaser.truecase("londoner") == "Londoner"
+
+    def test_options(self):
+        truecaser = Truecaser(["Paris"], capitalize_sentences=False, capitalize_i=False)
+        assert truecaser.truecase("i love paris. so much") == "i love Paris. so much"
+        assert len(truecaser) == 1
+        assert repr(truecaser) == (
+            "Truecaser(proper_nouns=1, capitalize_sentences=False, capitalize_i=False)"
+        )
+
+    def test_batch_and_detokenize(self):
+        truecaser = Truecaser(NAMES)
+        assert truecaser.truecase_batch(["hi london", "bye"]) == ["Hi London", "Bye"]
+        tokens = ["i", "live", "in", "london", ",", "you", "?"]
+        assert truecaser.detokenize(tokens) == "I live in London, you?"
+        assert detokenize(tokens, truecaser=truecaser) == "I live in London, you?"
+        assert detokenize(tokens) == "i live in london, you?"
+
+    def test_pipeline_detokenize_and_decode(self):
+        pipeline = TokenizerPipeline(
+            WhitespaceTokenizer(),
+            normalizer=Normalizer(lowercase=True),
+            vocab=Vocab(["hello", "from", "london", "."]),
+            truecaser=Truecaser(NAMES),
+        )
+        tokens = pipeline.tokenize("Hello from London")
+        assert tokens == ["hello", "from", "london"]
+        assert pipeline.detokenize(tokens) == "Hello from London"
+        assert pipeline.decode(pipeline.encode("hello from london .")) == "Hello from London."
+        augmenter = Augmenter(pipeline)
+        assert augmenter.augment_text("HELLO FROM LONDON") == "Hello from London"
+
+    def test_pickle_and_config(self):
+        truecaser = Truecaser(NAMES, capitalize_i=False)
+        restored = pickle.loads(pickle.dumps(truecaser))
+        assert repr(restored) == repr(truecaser)
+        assert restored.truecase("i saw nasa") == "I saw NASA"
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), truecaser=truecaser)
+        for copy in (
+            pickle.loads(pickle.dumps(pipeline)),
+            TokenizerPipeline.from_json(pipeline.to_json()),
+        ):
+            assert copy.detokenize(["i", "saw", "nasa"]) == "I saw NASA"
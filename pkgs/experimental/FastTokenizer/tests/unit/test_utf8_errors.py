@@ -0,0 +1,96 @@
+import pytest
+
+from fasttokenizer import (
+    BpeTokenizer,
+    SentencePieceTokenizer,
+    TokenizerIO,
+    WhitespaceTokenizer,
+    corpus_stats,
+)
+
+DATA = b"caf\xc3\xa9 ok\nbad \xff byte\ncut \xe2\x82\n"
+
+
+@pytest.fixture
+def path(tmp_path):
+    path = tmp_path / "corpus.txt"
+    path.write_bytes(DATA)
+    return str(path)
+
+
+@pytest.mark.unit
+class TestReaders:
+    """Unit tests for the errors= policy of TokenizerIO readers."""
+
+    def test_read_file(self, path):
+        io = TokenizerIO()
+        with pytest.raises(IOError, match="byte 13"):
+            io.read_file(path)
+        assert io.read_file(path, errors="replace") == DATA.decode("utf-8", "replace")
+        assert io.read_file(path, errors="ignore") == DATA.decode("utf-8", "ignore")
+        with pytest.raises(ValueError):
+            io.read_file(path, errors="surrogateescape")
+
+    def test_iter_lines(self, path):
+        io = TokenizerIO()
+        lines = io.iter_lines(path)
+        assert next(lines) == "café ok"
+        with pytest.raises(IOError):
+            next(lines)
+        assert list(io.iter_lines(path, errors="replace")) == ["café ok", "bad � byte", "cut �"]
+        assert list(io.iter_records(path, "\n", errors="ignore")) == ["café ok", "bad  byte", "cut "]
+
+    def test_iter_jsonl(self, tmp_path):
+        path = tmp_path / "docs.jsonl"
+        path.write_bytes(b'{"text": "a\xffb"}\n{"text": "ok"}\n')
+        io = TokenizerIO()
+        assert list(io.iter_jsonl(str(path), on_error="skip")) == ["ok"]
+        assert list(io.iter_jsonl(str(path), errors="replace")) == ["a�b", "ok"]
+
+    def test_iter_csv(self, tmp_path):
+        path = tmp_path / "docs.csv"
+        path.write_bytes(b"id,text\n1,a\xffb\n")
+        io = TokenizerIO()
+        with pytest.raises(IOError):
+            list(io.iter_csv(str(path), "text"))
+        assert list(io.iter_csv(str(path), "text", errors="ignore")) == ["ab"]
+
+    @pytest.mark.parametrize("chunk_size", [1, 3, 1 << 20])
+    def test_tokenize_file(self, path, chunk_size):
+        io = TokenizerIO()
+        with pytest.raises(IOError):
+            list(io.tokenize_file(path, WhitespaceTokenizer(), chunk_size=chunk_size))
+        batches = io.tokenize_file(path, WhitespaceTokenizer(), chunk_size=chunk_size, errors="replace")
+        assert [t for batch in batches for t in batch] == ["café", "ok", "bad", "�", "byte", "cut", "�"]
+
+    def test_corpus_jobs(self, path, tmp_path):
+        io = TokenizerIO()
+        with pytest.raises(IOError):
+            io.tokenize_corpus([path], WhitespaceTokenizer())
+        assert io.tokenize_corpus([path], WhitespaceTokenizer(), errors="ignore") == [
+            ["café", "ok", "bad", "byte", "cut"]
+        ]
+        [output] = io.tokenize_corpus([path], WhitespaceTokenizer(), output_dir=str(tmp_path / "out"), errors="replace")
+        assert open(output, encoding="utf-8").read() == "café ok\nbad � byte\ncut �\n"
+        assert corpus_stats([path], WhitespaceTokenizer(), errors="ignore").tokens == 5
+
+
+@pytest.mark.unit
+class TestDecoders:
+    """Unit tests for the errors= policy of byte-level decoders."""
+
+    def test_bpe(self):
+        tokenizer = BpeTokenizer({"â": 0, "Ĥ": 1, "ĤÉ": 2}, [])
+        # Ids 0 and 1 are the first two bytes of "€"; the third is missing.
+        assert tokenizer.decode([0, 1]) == "�"
+        assert tokenizer.decode([0, 1], errors="ignore") == ""
+        with pytest.raises(UnicodeDecodeError):
+            tokenizer.decode([0, 1], errors="strict")
+
+    def test_sentencepiece(self):
+        pieces = [("<unk>", 0.0), ("<0xE2>", 0.0), ("<0x82>", 0.0), ("▁a", -1.0)]
+        model = SentencePieceTokenizer(pieces, byte_fallback=True)
+        assert model.decode([3, 1, 2]) == "a�"
+        assert model.decode([3, 1, 2], errors="ignore") == "a"
+        with pytest.raises(UnicodeDecodeError):
+            model.decode([3, 1, 2], errors="strict")
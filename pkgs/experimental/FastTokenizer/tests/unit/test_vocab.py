@@ -0,0 +1,104 @@
+import json
+
+import pytest
+
+from fasttokenizer import RegexTokenizer, TokenizerPipeline, Vocab, WhitespaceTokenizer
+
+
+@pytest.mark.unit
+class TestVocab:
+    def test_lookup(self):
+        vocab = Vocab(["hello", "world"], unk_token="<unk>")
+        assert len(vocab) == 3
+        assert vocab.token_to_id("world") == 1
+        assert vocab.id_to_token(2) == "<unk>"
+        assert vocab.token_to_id("missing") is None
+        assert vocab.id_to_token(99) is None
+        assert "hello" in vocab and "missing" not in vocab
+        assert vocab.unk_id == 2
+
+    def test_mutable_vocab_grows(self):
+        vocab = Vocab()
+        assert vocab.encode(["a", "b", "a"]) == [0, 1, 0]
+        assert vocab.add("c") == 2
+        assert vocab.add("a") == 0
+        assert vocab.tokens() == ["a", "b", "c"]
+
+    def test_frozen_vocab_uses_unk(self):
+        vocab = Vocab(["[UNK]", "a"], unk_token="[UNK]", frozen=True)
+        assert vocab.encode(["a", "zzz"]) == [1, 0]
+        with pytest.raises(ValueError):
+            vocab.add("zzz")
+        vocab.unfreeze()
+        assert vocab.add("zzz") == 2
+
+    def test_frozen_vocab_without_unk_raises(self):
+        vocab = Vocab(["a"])
+        vocab.freeze()
+        assert vocab.frozen
+        with pytest.raises(ValueError):
+            vocab.encode(["b"])
+
+    def test_decode(self):
+        vocab = Vocab(["a", "b"])
+        assert vocab.decode([1, 0]) == ["b", "a"]
+        with pytest.raises(ValueError):
+            vocab.decode([5])
+
+    def test_json_round_trip(self, tmp_path):
+        vocab = Vocab(["hello", "wörld"], unk_token="<unk>", frozen=True)
+        path = tmp_path / "vocab.json"
+        vocab.save(str(path))
+        loaded = Vocab.load(str(path))
+        assert loaded.to_dict() == vocab.to_dict()
+        assert loaded.unk_token == "<unk>"
+        assert loaded.frozen
+
+    def test_load_plain_mapping(self, tmp_path):
+        path = tmp_path / "vocab.json"
+        path.write_text(json.dumps({"b": 1, "a": 0}), encoding="utf-8")
+        assert Vocab.load(str(path)).tokens() == ["a", "b"]
+        with pytest.raises(ValueError):
+            Vocab.from_json(json.dumps({"a": 0, "b": 2}))
+
+    def test_binary_round_trip(self, tmp_path):
+        vocab = Vocab(["hello", "wörld", "🎉"], unk_token="<unk>")
+        path = tmp_path / "vocab.bin"
+        vocab.save_binary(str(path))
+        loaded = Vocab.load_binary(str(path))
+        assert loaded.tokens() == vocab.tokens()
+        assert loaded.unk_id == 3
+        assert not loaded.frozen
+        assert len(vocab.to_bytes()) < len(vocab.to_json())
+        with pytest.raises(ValueError):
+            Vocab.from_bytes(vocab.to_bytes()[:-1])
+
+
+@pytest.mark.unit
+class TestTokenizerEncode:
+    def test_whitespace_encode_decode(self):
+        tokenizer = WhitespaceTokenizer(vocab=Vocab())
+        ids = tokenizer.encode("the cat saw the dog")
+        assert ids == [0, 1, 2, 0, 3]
+        assert tokenizer.decode(ids) == "the cat saw the dog"
+        assert tokenizer.encode_batch(["the dog", "a cat"]) == [[0, 3], [4, 1]]
+
+    def test_vocab_is_shared(self):
+        vocab = Vocab()
+        tokenizer = RegexTokenizer(r"\w+", vocab=vocab)
+        tokenizer.encode("one, two")
+        assert vocab.tokens() == ["one", "two"]
+        vocab.freeze()
+        assert tokenizer.vocab.frozen
+
+    def test_pipeline_encode(self):
+        vocab = Vocab(["<unk>", "hello"], unk_token="<unk>", frozen=True)
+        pipeline = TokenizerPipeline(WhitespaceTokenizer(), vocab=vocab)
+        assert pipeline.encode("hello there") == [1, 0]
+
+    def test_encode_without_vocab(self):
+        tokenizer = WhitespaceTokenizer()
+        with pytest.raises(ValueError):
+            tokenizer.encode("hello")
+        tokenizer.vocab = Vocab(["hello"])
+        assert tokenizer.encode("hello") == [0]
@@ -0,0 +1,55 @@
+import pytest
+
+from fasttokenizer import Vocab, remap_ids
+
+COUNTS = {"the": 10, "cat": 3, "sat": 3, "mat": 1, "on": 5}
+
+
+@pytest.fixture
+def vocab():
+    return Vocab(["[UNK]", "[PAD]", "the", "cat", "sat", "on", "mat", "rare"], unk_token="[UNK]", frozen=True)
+
+
+@pytest.mark.unit
+class TestVocabPruning:
+    """Unit tests for Vocab.prune and remap_ids."""
+
+    def test_min_freq(self, vocab):
+        pruned, table = vocab.prune(COUNTS, min_freq=3, keep=["[PAD]"])
+        assert pruned.tokens() == ["[UNK]", "[PAD]", "the", "cat", "sat", "on"]
+        assert table == [0, 1, 2, 3, 4, 5, 0, 0]
+        assert pruned.unk_token == "[UNK]" and pruned.frozen
+        assert len(vocab) == 8
+
+    def test_max_size(self, vocab):
+        pruned, table = vocab.prune(COUNTS, max_size=4, keep=["[PAD]"])
+        # "cat" and "sat" tie; the lower id wins.
+        assert pruned.tokens() == ["[UNK]", "[PAD]", "the", "on"]
+        assert table == [0, 1, 2, 0, 0, 3, 0, 0]
+        assert vocab.prune(COUNTS, max_size=5)[0].tokens() == ["[UNK]", "the", "cat", "sat", "on"]
+
+    def test_counts_by_id(self, vocab):
+        by_id = [0, 0, 10, 3, 3, 5, 1]
+        pruned, table = vocab.prune(by_id, min_freq=2)
+        expected, expected_table = vocab.prune(COUNTS, min_freq=2)
+        assert pruned.tokens() == expected.tokens() and table == expected_table
+        with pytest.raises(ValueError):
+            vocab.prune([1] * 9)
+
+    def test_without_unknown_token(self):
+        vocab = Vocab(["a", "b", "c"])
+        pruned, table = vocab.prune({"a": 2, "c": 1}, min_freq=1)
+        assert pruned.tokens() == ["a", "c"] and not pruned.frozen
+        assert table == [0, -1, 1]
+        assert remap_ids([0, 1, 2, 1], table) == [0, 1]
+
+    def test_remap_ids(self, vocab):
+        pruned, table = vocab.prune(COUNTS, min_freq=3)
+        ids = vocab.encode(["the", "mat", "sat", "rare"])
+        assert remap_ids(ids, table) == pruned.encode(["the", "mat", "sat", "rare"])
+        batch = [ids, [], ids[:1]]
+        assert remap_ids(batch, table) == [remap_ids(i, table) for i in batch]
+        with pytest.raises(ValueError):
+            remap_ids([8], table)
+        with pytest.raises(ValueError):
+            remap_ids([[0], [99]], table)
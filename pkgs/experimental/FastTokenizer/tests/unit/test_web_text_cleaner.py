@@ -0,0 +1,75 @@
+import pickle
+
+import pytest
+
+from fasttokenizer import WebTextCleaner
+
+PAGE = """
+<html><head><style>body { color: red }</style><script>track()</script></head>
+<body>
+<nav><a href="/">Home</a> <a href="/news">News</a></nav>
+<div>Skip to content</div>
+<h1>Rust   tokenizers</h1>
+<p>The new   release makes tokenization​ three times faster.</p>
+<p>It&#39;s available on PyPI today, and the cafÃ© demo still works.</p>
+<div>We use cookies to improve your experience.</div>
+<footer>© 2024 Example Corp. All rights reserved.</footer>
+</body></html>
+"""
+
+
+@pytest.mark.unit
+class TestWebTextCleaner:
+    """Unit tests for the web text cleaning preset."""
+
+    def test_page(self):
+        assert WebTextCleaner().clean(PAGE) == (
+            "The new release makes tokenization three times faster.\n"
+            "It's available on PyPI today, and the café demo still works."
+        )
+
+    def test_steps_can_be_disabled(self):
+        cleaner = WebTextCleaner(remove_boilerplate=False, fix_encoding=False)
+        text = cleaner.clean(PAGE)
+        assert text.splitlines()[:3] == ["Home News", "Skip to content", "Rust tokenizers"]
+        assert "cafÃ©" in text and "track()" not in text
+        assert WebTextCleaner(strip_html=False, remove_boilerplate=False).clean("<b>bold</b>") == "<b>bold</b>"
+
+    @pytest.mark.parametrize(
+        "broken, fixed",
+        [
+            ("cafÃ©", "café"),
+            ("Itâ€™s", "It’s"),
+            ("Â£5", "£5"),
+            ("Ã\xa0 la carte", "à la carte"),
+            ("ÐŸÑ€Ð¸Ð²ÐµÑ‚", "Привет"),
+            ("cafÃƒÂ©", "café"),
+            ("naïve façade", "naïve façade"),
+            ("“quoted” – Ü", "“quoted” – Ü"),
+        ],
+    )
+    def test_fix_encoding(self, broken, fixed):
+        cleaner = WebTextCleaner(strip_html=False, remove_boilerplate=False, form=None)
+        assert cleaner.clean(broken) == fixed
+
+    def test_min_words_and_patterns(self):
+        text = "Menu\nShort but final.\nA long enough line here\nSponsored content below"
+        assert WebTextCleaner(strip_html=False).clean(text).splitlines() == [
+            "Short but final.",
+            "A long enough line here",
+            "Sponsored content below",
+        ]
+        cleaner = WebTextCleaner(strip_html=False, min_words=0, boilerplate_patterns=[r"^sponsored\b"])
+        assert cleaner.clean(text).splitlines() == ["Menu", "Short but final.", "A long enough line here"]
+        with pytest.raises(ValueError):
+            WebTextCleaner(boilerplate_patterns=["("])
+        with pytest.raises(ValueError):
+            WebTextCleaner(form="NFX")
+
+    def test_batch_and_pickle(self):
+        cleaner = WebTextCleaner(min_words=2, boilerplate_patterns=["^ad:"], form="NFKC")
+        texts = [PAGE, "<p>Ad: buy now, friends.</p>", ""]
+        assert cleaner.clean_batch(texts) == [cleaner.clean(t) for t in texts]
+        copy = pickle.loads(pickle.dumps(cleaner))
+        assert copy.clean_batch(texts) == cleaner.clean_batch(texts)
+        assert repr(copy) == "WebTextCleaner(remove_boilerplate=True, min_words=2)"
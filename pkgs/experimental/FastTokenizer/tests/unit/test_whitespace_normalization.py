@@ -0,0 +1,46 @@
+import pytest
+
+from fasttokenizer import (
+    Normalizer,
+    collapse_whitespace,
+    normalize_spaces,
+    remove_control_chars,
+)
+
+
+@pytest.mark.unit
+def test_collapse_whitespace():
+    assert collapse_whitespace("  a \t\t b\n\n c  ") == " a b c "
+    assert collapse_whitespace("a \n \n b\u2028c  d", keep_newlines=True) == "a\nb\nc d"
+    assert collapse_whitespace("already clean") == "already clean"
+
+
+@pytest.mark.unit
+def test_remove_control_chars():
+    text = "\ufeffzero\u200bwidth\u200c non\u200djoiner\x00\x07\u202e\tok\n"
+    assert remove_control_chars(text) == "zerowidth nonjoiner\tok\n"
+
+
+@pytest.mark.unit
+def test_normalize_spaces():
+    assert normalize_spaces("a\u00a0b\u2003c\u3000d\u202fe") == "a b c d e"
+    assert normalize_spaces("tab\tstays") == "tab\tstays"
+
+
+@pytest.mark.unit
+def test_normalizer_web_text():
+    normalizer = Normalizer(
+        remove_control=True,
+        normalize_spaces=True,
+        collapse_whitespace=True,
+        strip=True,
+        lowercase=True,
+    )
+    scraped = "\ufeff  Hello\u00a0\u00a0World\u200b!\r\n\r\n  Next\u3000line  "
+    assert normalizer.normalize(scraped) == "hello world! next line"
+
+
+@pytest.mark.unit
+def test_collapse_after_punctuation_removal():
+    normalizer = Normalizer(remove_punctuation=True, collapse_whitespace=True)
+    assert normalizer.normalize("a , b ; c") == "a b c"
@@ -0,0 +1,58 @@
+import pytest
+
+from fasttokenizer import TokenizerPipeline, Vocab, WhitespaceTokenizer
+
+TEXT = "Hello  world\r\n\tbye\n"
+
+
+@pytest.mark.unit
+def test_default_is_unchanged():
+    assert WhitespaceTokenizer().tokenize(TEXT) == ["Hello", "world", "bye"]
+
+
+@pytest.mark.unit
+def test_preserve_delimiters_round_trips():
+    tokens = WhitespaceTokenizer(preserve_delimiters=True).tokenize(TEXT)
+    assert tokens == ["Hello", "  ", "world", "\r\n\t", "bye", "\n"]
+    assert "".join(tokens) == TEXT
+
+
+@pytest.mark.unit
+def test_keep_newlines():
+    assert WhitespaceTokenizer(keep_newlines=True).tokenize(TEXT) == [
+        "Hello", "world", "\r\n", "bye", "\n",
+    ]
+    tokens = WhitespaceTokenizer(preserve_delimiters=True, keep_newlines=True).tokenize(TEXT)
+    assert tokens == ["Hello", "  ", "world", "\r\n", "\t", "bye", "\n"]
+    assert "".join(tokens) == TEXT
+
+
+@pytest.mark.unit
+def test_max_splits():
+    text = " a b  c d "
+    assert WhitespaceTokenizer(max_splits=2).tokenize(text) == ["a", "b", "c d "]
+    assert WhitespaceTokenizer(max_splits=2).tokenize(text) == text.split(maxsplit=2)
+    assert WhitespaceTokenizer(max_splits=0).tokenize(text) == ["a b  c d "]
+    tokens = WhitespaceTokenizer(max_splits=1, preserve_delimiters=True).tokenize(text)
+    assert tokens == [" ", "a", " ", "b  c d "]
+
+
+@pytest.mark.unit
+def test_return_offsets():
+    tokenizer = WhitespaceTokenizer(keep_newlines=True)
+    assert tokenizer.tokenize("é b\nc", return_offsets=True) == [
+        ("é", 0, 1),
+        ("b", 2, 3),
+        ("\n", 3, 4),
+        ("c", 4, 5),
+    ]
+    assert tokenizer.tokenize("é b\nc", return_offsets=True) == tokenizer.tokenize_with_offsets("é b\nc")
+
+
+@pytest.mark.unit
+def test_decode_restores_spacing_and_pipeline():
+    tokenizer = WhitespaceTokenizer(vocab=Vocab(), preserve_delimiters=True)
+    assert tokenizer.decode(tokenizer.encode(TEXT)) == TEXT
+    pipeline = TokenizerPipeline(WhitespaceTokenizer(keep_newlines=True))
+    assert pipeline.tokenize("a\nb") == ["a", "\n", "b"]
+    assert WhitespaceTokenizer(max_splits=3).max_splits == 3
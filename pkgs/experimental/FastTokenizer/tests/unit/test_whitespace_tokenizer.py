@@ -1,11 +1,13 @@
 import logging
 import pytest
-from FastTokenizer.whitespace_tokenizer import whitespace_tokenizer
+from fasttokenizer import WhitespaceTokenizer
 
 # Setting up logging
 logging.basicConfig(level=logging.DEBUG)
 logger = logging.getLogger(__name__)
 
+whitespace_tokenizer = WhitespaceTokenizer().tokenize
+
 
 @pytest.fixture
 def sample_text():
@@ -0,0 +1,95 @@
+import pytest
+
+from fasttokenizer import WordPieceTokenizer
+
+# Vocabulary from the original BERT tokenization tests.
+TOKENS = [
+    "[UNK]",
+    "[CLS]",
+    "[SEP]",
+    "want",
+    "##want",
+    "##ed",
+    "wa",
+    "un",
+    "runn",
+    "##ing",
+    ",",
+    "中",
+    "##中",
+]
+
+
+@pytest.fixture
+def vocab():
+    return {token: i for i, token in enumerate(TOKENS)}
+
+
+@pytest.fixture
+def tokenizer(vocab):
+    return WordPieceTokenizer(vocab)
+
+
+@pytest.mark.unit
+class TestWordPieceTokenizer:
+    """Unit tests for the WordPieceTokenizer class."""
+
+    def test_tokenize(self, tokenizer):
+        assert tokenizer.tokenize("UNwantéd,running") == [
+            "un",
+            "##want",
+            "##ed",
+            ",",
+            "runn",
+            "##ing",
+        ]
+
+    def test_detokenize(self, tokenizer):
+        assert tokenizer.detokenize(["un", "##want", "##ed", "runn", "##ing"]) == "unwanted running"
+
+    def test_unknown_word(self, tokenizer):
+        """A word that cannot be fully covered becomes a single unk token."""
+        assert tokenizer.tokenize("unwantedX running") == ["[UNK]", "runn", "##ing"]
+
+    def test_max_input_chars_per_word(self, vocab):
+        tokenizer = WordPieceTokenizer(vocab, max_input_chars_per_word=4)
+        assert tokenizer.tokenize("runn running") == ["runn", "[UNK]"]
+
+    def test_cased(self, vocab):
+        tokenizer = WordPieceTokenizer(vocab, lowercase=False)
+        assert tokenizer.tokenize("Want want") == ["[UNK]", "want"]
+
+    def test_chinese_chars(self, tokenizer, vocab):
+        assert tokenizer.tokenize("中中") == ["中", "中"]
+        split_off = WordPieceTokenizer(vocab, tokenize_chinese_chars=False)
+        assert split_off.tokenize("中中") == ["中", "##中"]
+
+    def test_offsets(self, tokenizer):
+        text = "UNwantéd, running"
+        tokens = tokenizer.tokenize_with_offsets(text)
+        assert tokens[:3] == [("un", 0, 2), ("##want", 2, 6), ("##ed", 6, 8)]
+        assert [text[start:end] for _, start, end in tokens] == [
+            "UN",
+            "want",
+            "éd",
+            ",",
+            "runn",
+            "ing",
+        ]
+
+    def test_encode_decode(self, tokenizer, vocab):
+        ids = tokenizer.encode("unwanted running")
+        assert ids == [vocab[t] for t in ["un", "##want", "##ed", "runn", "##ing"]]
+        assert tokenizer.decode(ids) == "unwanted running"
+        assert tokenizer.encode_batch(["want", ""]) == [[vocab["want"]], []]
+
+    def test_from_file(self, tmp_path, tokenizer):
+        path = tmp_path / "vocab.txt"
+        path.write_text("\n".join(TOKENS) + "\n", encoding="utf-8")
+        loaded = WordPieceTokenizer.from_file(str(path))
+        assert loaded.vocab_size() == len(TOKENS)
+        assert loaded.encode("unwanted") == tokenizer.encode("unwanted")
+
+    def test_missing_unk_token(self, vocab):
+        with pytest.raises(ValueError):
+            WordPieceTokenizer(vocab, unk_token="<unk>")
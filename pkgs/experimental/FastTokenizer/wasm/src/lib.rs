@@ -0,0 +1,150 @@
+//! WebAssembly bindings for `fasttokenizer-core`, so web pages can count
+//! and split tokens exactly as the `fasttokenizer` Python extension does on
+//! the server. Build with `wasm-pack build wasm --target web`.
+
+use fasttokenizer_core::{Flags, Limits, Mode, Tokenize};
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+
+/// Splits text on Unicode whitespace.
+#[wasm_bindgen]
+pub struct WhitespaceTokenizer {
+    inner: fasttokenizer_core::WhitespaceTokenizer,
+}
+
+#[wasm_bindgen]
+impl WhitespaceTokenizer {
+    /// Create a whitespace tokenizer; the options are those of the Python
+    /// class.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        preserve_delimiters: Option<bool>,
+        keep_newlines: Option<bool>,
+        max_splits: Option<usize>,
+    ) -> WhitespaceTokenizer {
+        WhitespaceTokenizer {
+            inner: fasttokenizer_core::WhitespaceTokenizer {
+                preserve_delimiters: preserve_delimiters.unwrap_or(false),
+                keep_newlines: keep_newlines.unwrap_or(false),
+                max_splits,
+            },
+        }
+    }
+
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.inner.tokenize(text)
+    }
+
+    /// Number of tokens in `text`.
+    #[wasm_bindgen(js_name = countTokens)]
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.inner.tokenize_with_offsets(text).len()
+    }
+}
+
+/// Extracts matches of one or more regular expressions, or the text
+/// between them.
+#[wasm_bindgen]
+pub struct RegexTokenizer {
+    inner: fasttokenizer_core::RegexTokenizer,
+}
+
+#[wasm_bindgen]
+impl RegexTokenizer {
+    /// Create a regex tokenizer from patterns tried in order, in `"find"`,
+    /// `"split"` or `"captures"` mode.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        patterns: Vec<String>,
+        mode: Option<String>,
+        case_insensitive: Option<bool>,
+        max_input_length: Option<usize>,
+    ) -> Result<RegexTokenizer, JsError> {
+        let mode = Mode::parse(mode.as_deref().unwrap_or("find"))?;
+        let flags = Flags {
+            case_insensitive: case_insensitive.unwrap_or(false),
+            ..Flags::default()
+        };
+        let limits = Limits {
+            max_input_length,
+            ..Limits::NONE
+        };
+        Ok(RegexTokenizer {
+            inner: fasttokenizer_core::RegexTokenizer::new(&patterns, mode, flags, limits)?,
+        })
+    }
+
+    /// Tokenize `text`, failing when it is longer than `max_input_length`.
+    pub fn tokenize(&self, text: &str) -> Result<Vec<String>, JsError> {
+        Ok(self.inner.try_tokenize(text)?)
+    }
+
+    /// Number of tokens in `text`.
+    #[wasm_bindgen(js_name = countTokens)]
+    pub fn count_tokens(&self, text: &str) -> Result<usize, JsError> {
+        Ok(self.inner.try_tokenize_with_offsets(text)?.len())
+    }
+}
+
+/// Byte-level BPE encoder producing the same ids as OpenAI's tiktoken.
+#[wasm_bindgen]
+pub struct TiktokenEncoder {
+    inner: fasttokenizer_core::TiktokenEncoder,
+    name: String,
+}
+
+#[wasm_bindgen]
+impl TiktokenEncoder {
+    /// Build one of the standard encodings (`cl100k_base`, `o200k_base`,
+    /// `p50k_base` or `r50k_base`) from the text of its `.tiktoken` ranks
+    /// file, e.g. as fetched by the page.
+    #[wasm_bindgen(js_name = fromRanks)]
+    pub fn from_ranks(ranks: &str, encoding: &str) -> Result<TiktokenEncoder, JsError> {
+        let ranks = fasttokenizer_core::tiktoken::parse_ranks(ranks)?;
+        Ok(TiktokenEncoder {
+            inner: fasttokenizer_core::TiktokenEncoder::from_ranks(ranks, encoding)?,
+            name: encoding.to_string(),
+        })
+    }
+
+    /// Encode text like `tiktoken.Encoding.encode`: the special tokens in
+    /// `allowed_special` become single tokens, and any other special token
+    /// in the text is an error.
+    pub fn encode(
+        &self,
+        text: &str,
+        allowed_special: Option<Vec<String>>,
+    ) -> Result<Vec<u32>, JsError> {
+        let allowed: HashSet<String> = allowed_special.unwrap_or_default().into_iter().collect();
+        let disallowed = self.inner.special_tokens().keys().cloned().collect();
+        Ok(self.inner.encode(text, &allowed, &disallowed)?)
+    }
+
+    /// Encode text treating special tokens as ordinary text.
+    #[wasm_bindgen(js_name = encodeOrdinary)]
+    pub fn encode_ordinary(&self, text: &str) -> Vec<u32> {
+        self.inner.encode_ordinary(text)
+    }
+
+    /// Number of tokens in `text`, counted like `encodeOrdinary`.
+    #[wasm_bindgen(js_name = countTokens)]
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.inner.count_tokens(text)
+    }
+
+    /// Decode ids into text, replacing invalid UTF-8 with U+FFFD.
+    pub fn decode(&self, ids: Vec<u32>) -> Result<String, JsError> {
+        Ok(self.inner.decode(&ids)?)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Number of ordinary plus special tokens.
+    #[wasm_bindgen(getter, js_name = nVocab)]
+    pub fn n_vocab(&self) -> usize {
+        self.inner.n_vocab()
+    }
+}
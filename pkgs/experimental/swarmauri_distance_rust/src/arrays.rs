@@ -0,0 +1,269 @@
+use numpy::ndarray::Array2;
+use numpy::{
+    Element, IntoPyArray, PyArray1, PyArray2, PyArrayDyn, PyReadonlyArrayDyn, PyUntypedArrayMethods,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::kernels::Scalar;
+use crate::metric::Metric;
+use crate::search;
+
+/// Pairs times dimensions below which a batch runs on the calling thread,
+/// since splitting it across threads would cost more than it saves.
+pub const PARALLEL_MIN: usize = 1 << 15;
+
+/// Whether every argument is already a `float32` array. Those are compared
+/// in single precision; anything else is converted to `float64`.
+fn all_f32(args: &[&Bound<'_, PyAny>]) -> bool {
+    args.iter().all(|arg| arg.cast::<PyArrayDyn<f32>>().is_ok())
+}
+
+/// `obj` as a C-contiguous array of `T` with `ndim` dimensions, converting
+/// lists, other dtypes and strided views with NumPy.
+pub fn array<'py, T: Element>(
+    obj: &Bound<'py, PyAny>,
+    ndim: usize,
+    name: &str,
+) -> PyResult<PyReadonlyArrayDyn<'py, T>> {
+    let py = obj.py();
+    let array: PyReadonlyArrayDyn<'py, T> = py
+        .import("numpy")?
+        .call_method1("ascontiguousarray", (obj, T::get_dtype(py)))?
+        .extract()?;
+    if array.ndim() != ndim {
+        return Err(PyValueError::new_err(format!(
+            "{} must be a {}-D array, got {}-D",
+            name,
+            ndim,
+            array.ndim()
+        )));
+    }
+    Ok(array)
+}
+
+/// The dimensionality shared by two arrays of vectors.
+fn dimensions<T: Element>(
+    a: &PyReadonlyArrayDyn<'_, T>,
+    b: &PyReadonlyArrayDyn<'_, T>,
+) -> PyResult<usize> {
+    let (a_dim, b_dim) = (a.shape()[a.ndim() - 1], b.shape()[b.ndim() - 1]);
+    if a_dim != b_dim {
+        return Err(PyValueError::new_err(format!(
+            "Vectors must have the same dimensionality, got {} and {}",
+            a_dim, b_dim
+        )));
+    }
+    if a_dim == 0 {
+        return Err(PyValueError::new_err("Vectors must not be empty"));
+    }
+    Ok(a_dim)
+}
+
+/// Distances from every row of `queries` to every row of `vectors`, in
+/// row-major order, spread over the Rayon threads for large batches.
+pub fn pairwise<T: Scalar>(metric: Metric, queries: &[T], vectors: &[T], dim: usize) -> Vec<T> {
+    let query_prepared = metric.prepare_rows(queries, dim);
+    let vector_prepared = metric.prepare_rows(vectors, dim);
+    let columns = vector_prepared.len();
+    let mut out = vec![T::ZERO; query_prepared.len() * columns];
+    let fill = |(i, out): (usize, &mut T)| {
+        let (q, v) = (i / columns, i % columns);
+        *out = metric.distance(
+            &queries[q * dim..(q + 1) * dim],
+            query_prepared[q],
+            &vectors[v * dim..(v + 1) * dim],
+            vector_prepared[v],
+        );
+    };
+    if out.len() * dim >= PARALLEL_MIN {
+        out.par_iter_mut().enumerate().for_each(fill);
+    } else {
+        out.iter_mut().enumerate().for_each(fill);
+    }
+    out
+}
+
+fn distance_of<T: Scalar + Element>(
+    a: &Bound<'_, PyAny>,
+    b: &Bound<'_, PyAny>,
+    metric: Metric,
+) -> PyResult<f64>
+where
+    f64: From<T>,
+{
+    let (a, b) = (array::<T>(a, 1, "a")?, array::<T>(b, 1, "b")?);
+    dimensions(&a, &b)?;
+    let (a, b) = (a.as_slice()?, b.as_slice()?);
+    Ok(metric
+        .distance(a, metric.prepare(a), b, metric.prepare(b))
+        .into())
+}
+
+/// The distances of `pairwise` with their matrix shape, for `queries` given
+/// as one vector (`query_ndim` 1) or as rows of a matrix (2).
+fn pairwise_of<T: Scalar + Element>(
+    queries: &Bound<'_, PyAny>,
+    vectors: &Bound<'_, PyAny>,
+    metric: Metric,
+    query_ndim: usize,
+) -> PyResult<(Vec<T>, (usize, usize))> {
+    let py = queries.py();
+    let name = if query_ndim == 1 { "query" } else { "queries" };
+    let queries = array::<T>(queries, query_ndim, name)?;
+    let vectors = array::<T>(vectors, 2, "vectors")?;
+    let dim = dimensions(&queries, &vectors)?;
+    let (queries, vectors) = (queries.as_slice()?, vectors.as_slice()?);
+    let out = py.detach(|| pairwise(metric, queries, vectors, dim));
+    Ok((out, (queries.len() / dim, vectors.len() / dim)))
+}
+
+fn matrix_of<'py, T: Scalar + Element>(
+    queries: &Bound<'py, PyAny>,
+    vectors: &Bound<'py, PyAny>,
+    metric: Metric,
+) -> PyResult<Bound<'py, PyArray2<T>>> {
+    let (out, shape) = pairwise_of::<T>(queries, vectors, metric, 2)?;
+    let matrix = Array2::from_shape_vec(shape, out).expect("one distance per pair");
+    Ok(matrix.into_pyarray(queries.py()))
+}
+
+fn top_k_of<'py, T: Scalar + Element>(
+    queries: &Bound<'py, PyAny>,
+    corpus: &Bound<'py, PyAny>,
+    k: usize,
+    metric: Metric,
+) -> PyResult<(Bound<'py, PyArray2<i64>>, Bound<'py, PyAny>)> {
+    let py = queries.py();
+    let queries = array::<T>(queries, 2, "queries")?;
+    let corpus = array::<T>(corpus, 2, "corpus")?;
+    let dim = dimensions(&queries, &corpus)?;
+    let (queries, corpus) = (queries.as_slice()?, corpus.as_slice()?);
+    let found = py.detach(|| search::top_k(metric, queries, corpus, dim, k));
+    let shape = (found.len(), k.min(corpus.len() / dim));
+    let indices = found.iter().flatten().map(|n| n.index as i64).collect();
+    let scores = found.iter().flatten().map(|n| n.distance).collect();
+    Ok((
+        Array2::from_shape_vec(shape, indices)
+            .expect("k neighbors per query")
+            .into_pyarray(py),
+        Array2::from_shape_vec(shape, scores)
+            .expect("k neighbors per query")
+            .into_pyarray(py)
+            .into_any(),
+    ))
+}
+
+/// Distance between two vectors.
+///
+/// Args:
+///     a (ArrayLike): A 1-D array or list of numbers.
+///     b (ArrayLike): Another of the same length.
+///     metric (str): `"cosine"`, `"dot"` (`1 - a·b`, for normalized
+///         vectors), `"euclidean"`, `"manhattan"` or `"hamming"` (the number
+///         of positions that differ).
+///
+/// Returns:
+///     float: The distance. Two `float32` arrays are compared in single
+///     precision, anything else in double precision.
+#[pyfunction]
+#[pyo3(signature = (a, b, metric="cosine"))]
+pub fn distance(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>, metric: &str) -> PyResult<f64> {
+    let metric = Metric::parse(metric)?;
+    if all_f32(&[a, b]) {
+        distance_of::<f32>(a, b, metric)
+    } else {
+        distance_of::<f64>(a, b, metric)
+    }
+}
+
+/// Distances from one vector to each row of a matrix, computed on all cores
+/// without holding the GIL when the matrix is large.
+///
+/// Args:
+///     query (ArrayLike): A 1-D array of `d` numbers.
+///     vectors (ArrayLike): A 2-D array of shape `(n, d)`.
+///     metric (str): See `distance`.
+///
+/// Returns:
+///     numpy.ndarray: The `n` distances, `float32` when both inputs are and
+///     `float64` otherwise.
+#[pyfunction]
+#[pyo3(signature = (query, vectors, metric="cosine"))]
+pub fn distances<'py>(
+    query: &Bound<'py, PyAny>,
+    vectors: &Bound<'py, PyAny>,
+    metric: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let metric = Metric::parse(metric)?;
+    let py = query.py();
+    Ok(if all_f32(&[query, vectors]) {
+        let (out, _) = pairwise_of::<f32>(query, vectors, metric, 1)?;
+        PyArray1::from_vec(py, out).into_any()
+    } else {
+        let (out, _) = pairwise_of::<f64>(query, vectors, metric, 1)?;
+        PyArray1::from_vec(py, out).into_any()
+    })
+}
+
+/// Distances between every row of one matrix and every row of another,
+/// computed on all cores without holding the GIL when the batch is large.
+///
+/// Args:
+///     queries (ArrayLike): A 2-D array of shape `(m, d)`.
+///     vectors (ArrayLike): A 2-D array of shape `(n, d)`.
+///     metric (str): See `distance`.
+///
+/// Returns:
+///     numpy.ndarray: An `(m, n)` matrix whose row `i` holds the distances
+///     from `queries[i]`, `float32` when both inputs are and `float64`
+///     otherwise.
+#[pyfunction]
+#[pyo3(signature = (queries, vectors, metric="cosine"))]
+pub fn pairwise_distances<'py>(
+    queries: &Bound<'py, PyAny>,
+    vectors: &Bound<'py, PyAny>,
+    metric: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let metric = Metric::parse(metric)?;
+    Ok(if all_f32(&[queries, vectors]) {
+        matrix_of::<f32>(queries, vectors, metric)?.into_any()
+    } else {
+        matrix_of::<f64>(queries, vectors, metric)?.into_any()
+    })
+}
+
+/// The `k` nearest rows of a corpus to each query, by exact brute-force
+/// search on all cores without holding the GIL.
+///
+/// Args:
+///     queries (ArrayLike): A 2-D array of shape `(m, d)`.
+///     corpus (ArrayLike): A 2-D array of shape `(n, d)`.
+///     k (int): Neighbors to find per query; all `n` rows when the corpus
+///         has fewer.
+///     metric (str): See `distance`.
+///
+/// Returns:
+///     Tuple[numpy.ndarray, numpy.ndarray]: `(indices, scores)`, both of
+///     shape `(m, min(k, n))`: the `int64` corpus rows nearest each query and
+///     their distances, nearest first, with ties going to the lower row.
+///     Scores are `float32` when both inputs are and `float64` otherwise.
+#[pyfunction]
+#[pyo3(signature = (queries, corpus, k, metric="cosine"))]
+pub fn top_k<'py>(
+    queries: &Bound<'py, PyAny>,
+    corpus: &Bound<'py, PyAny>,
+    k: usize,
+    metric: &str,
+) -> PyResult<(Bound<'py, PyArray2<i64>>, Bound<'py, PyAny>)> {
+    if k == 0 {
+        return Err(PyValueError::new_err("k must be at least 1"));
+    }
+    let metric = Metric::parse(metric)?;
+    if all_f32(&[queries, corpus]) {
+        top_k_of::<f32>(queries, corpus, k, metric)
+    } else {
+        top_k_of::<f64>(queries, corpus, k, metric)
+    }
+}
@@ -0,0 +1,496 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::metric::Metric;
+use crate::search::Neighbor;
+
+/// Magic bytes at the start of a saved index.
+const MAGIC: &[u8; 4] = b"SDHN";
+const VERSION: u8 = 1;
+/// Written in place of the entry point of an empty index.
+const NO_ENTRY: u32 = u32::MAX;
+/// Layers above this are never drawn; with `m = 2` the odds of reaching it
+/// are one in 2^32 per node.
+const MAX_LEVEL: usize = 32;
+
+/// A small seeded SplitMix64 generator for drawing node levels, so that the
+/// same inserts build the same graph on every platform.
+#[derive(Clone, Debug)]
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut x = self.state;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+
+    /// A uniform float in `(0, 1]`.
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The nodes one search has visited. Marking a node stores the number of
+/// the current pass, so starting another pass over the next layer forgets
+/// the marks without clearing them.
+struct Visited {
+    marks: Vec<u32>,
+    pass: u32,
+}
+
+impl Visited {
+    fn new(nodes: usize) -> Self {
+        Visited {
+            marks: vec![0; nodes],
+            pass: 0,
+        }
+    }
+
+    fn next_pass(&mut self) {
+        self.pass += 1;
+    }
+
+    /// Mark `node`, returning whether it was not marked in this pass yet.
+    fn insert(&mut self, node: u32) -> bool {
+        let mark = &mut self.marks[node as usize];
+        let new = *mark != self.pass;
+        *mark = self.pass;
+        new
+    }
+}
+
+/// A hierarchical navigable small world graph (Malkov and Yashunin, 2016)
+/// over `f32` vectors.
+///
+/// Every node is on layer 0 and on each layer above it with probability
+/// `1 / m`, linked to up to `m` near nodes per layer (`2 m` on layer 0).
+/// Searches descend greedily from the single node of the top layer and then
+/// explore layer 0 from the nearest node found.
+///
+/// Deleted nodes are tombstoned: they stay in the graph so that the paths
+/// through them still lead somewhere, but searches only collect live nodes
+/// and keep going until they have found enough of them.
+#[derive(Debug)]
+pub struct Graph {
+    pub metric: Metric,
+    pub dim: usize,
+    pub m: usize,
+    pub ef_construction: usize,
+    pub ef_search: usize,
+    vectors: Vec<f32>,
+    /// What `Metric::prepare` returned for each node.
+    prepared: Vec<f32>,
+    ids: Vec<i64>,
+    /// The neighbors of each node on each of its layers, from layer 0 up.
+    links: Vec<Vec<Vec<u32>>>,
+    deleted: Vec<bool>,
+    /// The node of each id that is not deleted.
+    live: HashMap<i64, u32>,
+    entry: Option<u32>,
+    /// One more than the largest id ever inserted, the next id to assign.
+    next_id: i64,
+    rng: Rng,
+}
+
+impl Graph {
+    pub fn new(
+        metric: Metric,
+        dim: usize,
+        m: usize,
+        ef_construction: usize,
+        ef_search: usize,
+        seed: u64,
+    ) -> Self {
+        Graph {
+            metric,
+            dim,
+            m,
+            ef_construction,
+            ef_search,
+            vectors: Vec::new(),
+            prepared: Vec::new(),
+            ids: Vec::new(),
+            links: Vec::new(),
+            deleted: Vec::new(),
+            live: HashMap::new(),
+            entry: None,
+            next_id: 0,
+            rng: Rng { state: seed },
+        }
+    }
+
+    /// The number of ids that are not deleted.
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    /// The number of nodes, deleted ones included.
+    pub fn nodes(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn contains(&self, id: i64) -> bool {
+        self.live.contains_key(&id)
+    }
+
+    pub fn next_id(&self) -> i64 {
+        self.next_id
+    }
+
+    fn vector(&self, node: u32) -> &[f32] {
+        let node = node as usize;
+        &self.vectors[node * self.dim..(node + 1) * self.dim]
+    }
+
+    fn distance_to(&self, query: &[f32], prepared: f32, node: u32) -> f32 {
+        self.metric.distance(
+            query,
+            prepared,
+            self.vector(node),
+            self.prepared[node as usize],
+        )
+    }
+
+    fn neighbor(&self, query: &[f32], prepared: f32, node: u32) -> Neighbor<f32> {
+        Neighbor {
+            distance: self.distance_to(query, prepared, node),
+            index: node as usize,
+        }
+    }
+
+    /// The `ef` nodes nearest the query found on `layer` by best-first
+    /// search from `entries`, nearest first.
+    ///
+    /// With `live_only`, deleted nodes are still walked through but not
+    /// collected, and the search goes on until it has `ef` live nodes or
+    /// runs out of candidates, as hnswlib does.
+    #[allow(clippy::too_many_arguments)]
+    fn search_layer(
+        &self,
+        query: &[f32],
+        prepared: f32,
+        entries: &[Neighbor<f32>],
+        ef: usize,
+        layer: usize,
+        live_only: bool,
+        visited: &mut Visited,
+    ) -> Vec<Neighbor<f32>> {
+        visited.next_pass();
+        for entry in entries {
+            visited.insert(entry.index as u32);
+        }
+        let collect = |n: &Neighbor<f32>| !(live_only && self.deleted[n.index]);
+        let mut candidates: BinaryHeap<Reverse<Neighbor<f32>>> =
+            entries.iter().copied().map(Reverse).collect();
+        let mut found: BinaryHeap<Neighbor<f32>> =
+            entries.iter().copied().filter(collect).collect();
+        while found.len() > ef {
+            found.pop();
+        }
+        while let Some(Reverse(nearest)) = candidates.pop() {
+            if found.len() >= ef && found.peek().is_some_and(|farthest| nearest > *farthest) {
+                break;
+            }
+            for &node in &self.links[nearest.index][layer] {
+                if !visited.insert(node) {
+                    continue;
+                }
+                let neighbor = self.neighbor(query, prepared, node);
+                if found.len() < ef || found.peek().is_some_and(|farthest| neighbor < *farthest) {
+                    candidates.push(Reverse(neighbor));
+                    if collect(&neighbor) {
+                        found.push(neighbor);
+                        if found.len() > ef {
+                            found.pop();
+                        }
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    /// Up to `m` of `candidates` (nearest first) to link a node to, skipping
+    /// any that is nearer to an already chosen one than to the node, so that
+    /// links spread out in different directions rather than all into the
+    /// nearest cluster.
+    fn select(&self, candidates: &[Neighbor<f32>], m: usize) -> Vec<u32> {
+        let mut selected: Vec<u32> = Vec::with_capacity(m);
+        for candidate in candidates {
+            if selected.len() == m {
+                break;
+            }
+            let node = candidate.index as u32;
+            let (vector, prepared) = (self.vector(node), self.prepared[node as usize]);
+            if selected
+                .iter()
+                .all(|&other| self.distance_to(vector, prepared, other) >= candidate.distance)
+            {
+                selected.push(node);
+            }
+        }
+        selected
+    }
+
+    /// The nearest node to the query on `layer`, descending greedily from
+    /// the entry point through the layers above.
+    fn descend(
+        &self,
+        query: &[f32],
+        prepared: f32,
+        entry: u32,
+        layer: usize,
+        visited: &mut Visited,
+    ) -> Neighbor<f32> {
+        let mut nearest = self.neighbor(query, prepared, entry);
+        for upper in (layer + 1..self.links[entry as usize].len()).rev() {
+            nearest = self.search_layer(query, prepared, &[nearest], 1, upper, false, visited)[0];
+        }
+        nearest
+    }
+
+    fn random_level(&mut self) -> usize {
+        let scale = 1.0 / (self.m as f64).ln();
+        ((-self.rng.next_f64().ln() * scale) as usize).min(MAX_LEVEL)
+    }
+
+    /// Add a vector of `dim` values under `id`, which must not be live.
+    pub fn insert(&mut self, id: i64, vector: &[f32]) {
+        let node = self.ids.len() as u32;
+        let prepared = self.metric.prepare(vector);
+        let level = self.random_level();
+        self.vectors.extend_from_slice(vector);
+        self.prepared.push(prepared);
+        self.ids.push(id);
+        self.links.push(vec![Vec::new(); level + 1]);
+        self.deleted.push(false);
+        self.live.insert(id, node);
+        self.next_id = self.next_id.max(id.saturating_add(1));
+        let Some(entry) = self.entry else {
+            self.entry = Some(node);
+            return;
+        };
+        let top = self.links[entry as usize].len() - 1;
+        let mut visited = Visited::new(self.ids.len());
+        let mut nearest = vec![self.descend(vector, prepared, entry, level.min(top), &mut visited)];
+        for layer in (0..=level.min(top)).rev() {
+            nearest = self.search_layer(
+                vector,
+                prepared,
+                &nearest,
+                self.ef_construction,
+                layer,
+                false,
+                &mut visited,
+            );
+            let selected = self.select(&nearest, self.m);
+            let max_links = if layer == 0 { 2 * self.m } else { self.m };
+            for &other in &selected {
+                self.links[other as usize][layer].push(node);
+                if self.links[other as usize][layer].len() > max_links {
+                    self.shrink(other, layer, max_links);
+                }
+            }
+            self.links[node as usize][layer] = selected;
+        }
+        if level > top {
+            self.entry = Some(node);
+        }
+    }
+
+    /// Cut the links of `node` on `layer` back to `max_links`.
+    fn shrink(&mut self, node: u32, layer: usize, max_links: usize) {
+        let (vector, prepared) = (self.vector(node), self.prepared[node as usize]);
+        let mut candidates: Vec<Neighbor<f32>> = self.links[node as usize][layer]
+            .iter()
+            .map(|&other| self.neighbor(vector, prepared, other))
+            .collect();
+        candidates.sort_unstable();
+        self.links[node as usize][layer] = self.select(&candidates, max_links);
+    }
+
+    /// Tombstone `id`, returning whether it was live.
+    pub fn delete(&mut self, id: i64) -> bool {
+        match self.live.remove(&id) {
+            Some(node) => {
+                self.deleted[node as usize] = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The `k` live ids nearest the query with their distances, nearest
+    /// first, exploring `ef` candidates (at least `k`) on layer 0.
+    pub fn search(&self, query: &[f32], k: usize, ef: usize) -> Vec<(i64, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        let prepared = self.metric.prepare(query);
+        let mut visited = Visited::new(self.ids.len());
+        let nearest = self.descend(query, prepared, entry, 0, &mut visited);
+        self.search_layer(
+            query,
+            prepared,
+            &[nearest],
+            ef.max(k),
+            0,
+            true,
+            &mut visited,
+        )
+        .into_iter()
+        .take(k)
+        .map(|n| (self.ids[n.index], n.distance))
+        .collect()
+    }
+
+    /// Binary layout, all integers little-endian:
+    /// `b"SDHN"`, version `u8`, the metric name as its length `u8` and
+    /// bytes, `dim`, `m`, `ef_construction`, `ef_search`, the node count and
+    /// the entry node as `u32`s (`u32::MAX` when empty), the generator state
+    /// `u64`, then per node its id `i64`, a deleted flag `u8`, its layer
+    /// count `u8` and per layer the neighbor count `u32` and neighbors
+    /// `u32`, and last the vectors as `f32`s in node order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let name = self.metric.name();
+        let mut out = Vec::with_capacity(64 + self.vectors.len() * 4 + self.ids.len() * 16);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(name.len() as u8);
+        out.extend_from_slice(name.as_bytes());
+        for value in [
+            self.dim,
+            self.m,
+            self.ef_construction,
+            self.ef_search,
+            self.ids.len(),
+        ] {
+            out.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        out.extend_from_slice(&self.entry.unwrap_or(NO_ENTRY).to_le_bytes());
+        out.extend_from_slice(&self.rng.state.to_le_bytes());
+        for (node, layers) in self.links.iter().enumerate() {
+            out.extend_from_slice(&self.ids[node].to_le_bytes());
+            out.push(self.deleted[node] as u8);
+            out.push(layers.len() as u8);
+            for links in layers {
+                out.extend_from_slice(&(links.len() as u32).to_le_bytes());
+                for link in links {
+                    out.extend_from_slice(&link.to_le_bytes());
+                }
+            }
+        }
+        for value in &self.vectors {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader { data, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err("Not an HNSW index file".to_string());
+        }
+        let version = reader.take(1)?[0];
+        if version == 0 || version > VERSION {
+            return Err(format!("Unsupported HNSW index version {}", version));
+        }
+        let name_len = reader.take(1)?[0] as usize;
+        let name = std::str::from_utf8(reader.take(name_len)?)
+            .map_err(|e| format!("Invalid metric name: {}", e))?;
+        let metric = Metric::parse(name).map_err(|_| format!("Unknown metric '{}'", name))?;
+        let dim = reader.u32()? as usize;
+        let m = reader.u32()? as usize;
+        let ef_construction = reader.u32()? as usize;
+        let ef_search = reader.u32()? as usize;
+        let count = reader.u32()? as usize;
+        let entry = reader.u32()?;
+        if dim == 0 || m < 2 {
+            return Err("Invalid parameters in HNSW index data".to_string());
+        }
+        let mut graph = Graph::new(metric, dim, m, ef_construction, ef_search, reader.u64()?);
+        let invalid_node = |node: u32| format!("Invalid node {} in HNSW index data", node);
+        for node in 0..count {
+            let id = reader.u64()? as i64;
+            let deleted = reader.take(1)?[0] != 0;
+            let layers = reader.take(1)?[0] as usize;
+            let mut node_links = Vec::with_capacity(layers);
+            for _ in 0..layers {
+                let len = reader.u32()? as usize;
+                let links = (0..len)
+                    .map(|_| reader.u32())
+                    .collect::<Result<Vec<u32>, String>>()?;
+                if let Some(&link) = links.iter().find(|&&link| link as usize >= count) {
+                    return Err(invalid_node(link));
+                }
+                node_links.push(links);
+            }
+            graph.ids.push(id);
+            graph.deleted.push(deleted);
+            graph.links.push(node_links);
+            graph.next_id = graph.next_id.max(id.saturating_add(1));
+            if !deleted && graph.live.insert(id, node as u32).is_some() {
+                return Err(format!("Duplicate id {} in HNSW index data", id));
+            }
+        }
+        graph.vectors = reader
+            .take(count.saturating_mul(dim * 4))?
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        graph.prepared = metric.prepare_rows(&graph.vectors, dim);
+        graph.entry = match entry {
+            NO_ENTRY if count == 0 => None,
+            entry if (entry as usize) < count => Some(entry),
+            entry => return Err(invalid_node(entry)),
+        };
+        // A node without layers, links to a layer the other node is not
+        // on, or a node above the entry point would break searches.
+        let top = graph
+            .entry
+            .map_or(0, |entry| graph.links[entry as usize].len());
+        for layers in &graph.links {
+            if layers.is_empty() || layers.len() > top {
+                return Err("Invalid layers in HNSW index data".to_string());
+            }
+            for (layer, links) in layers.iter().enumerate() {
+                if let Some(&link) = links
+                    .iter()
+                    .find(|&&link| graph.links[link as usize].len() <= layer)
+                {
+                    return Err(invalid_node(link));
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(n))
+            .ok_or("Truncated HNSW index data")?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
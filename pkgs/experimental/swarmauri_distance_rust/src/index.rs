@@ -0,0 +1,265 @@
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::fs;
+use std::sync::RwLock;
+
+use crate::arrays::array;
+use crate::hnsw::Graph;
+use crate::metric::Metric;
+
+/// The ids and distances `search` returns.
+type Neighbors<'py> = (Bound<'py, PyArray2<i64>>, Bound<'py, PyArray2<f32>>);
+
+/// An approximate nearest-neighbor index over `float32` vectors, built as a
+/// hierarchical navigable small world (HNSW) graph.
+///
+/// Vectors are stored under integer ids. Searches visit a small part of the
+/// graph instead of every vector, trading a little recall for speed on
+/// large collections; `ef_search` sets how much of it they explore.
+/// Deleting an id tombstones its vector, which stays in the graph to keep
+/// it connected but is never returned; rebuild an index that has had most
+/// of its vectors deleted.
+///
+/// Inserts and searches release the GIL, and searches of several queries
+/// run on all cores. An index can be shared between threads: searches run
+/// concurrently, inserts and deletes one at a time.
+///
+/// Args:
+///     dim (int): The dimensionality of the vectors.
+///     metric (str): `"cosine"`, `"dot"`, `"euclidean"`, `"manhattan"` or
+///         `"hamming"`, as in `distance`.
+///     m (int): Links per node on each layer, twice as many on the bottom
+///         one. More links raise recall and memory use.
+///     ef_construction (int): Candidates considered when linking a new
+///         vector. Higher values build a better graph, more slowly.
+///     ef_search (int): Candidates considered per search by default, at
+///         least `k`.
+///     seed (int): Seeds the layer assignment, so the same inserts build
+///         the same graph.
+#[pyclass(frozen, skip_from_py_object, module = "swarmauri_distance_rust")]
+pub struct HnswIndex {
+    graph: RwLock<Graph>,
+}
+
+#[pymethods]
+impl HnswIndex {
+    #[new]
+    #[pyo3(signature = (dim, metric="cosine", m=16, ef_construction=200, ef_search=50, seed=0))]
+    fn new(
+        dim: usize,
+        metric: &str,
+        m: usize,
+        ef_construction: usize,
+        ef_search: usize,
+        seed: u64,
+    ) -> PyResult<Self> {
+        let metric = Metric::parse(metric)?;
+        if dim == 0 {
+            return Err(PyValueError::new_err("dim must be at least 1"));
+        }
+        if m < 2 {
+            return Err(PyValueError::new_err("m must be at least 2"));
+        }
+        if ef_construction == 0 || ef_search == 0 {
+            return Err(PyValueError::new_err(
+                "ef_construction and ef_search must be at least 1",
+            ));
+        }
+        Ok(HnswIndex {
+            graph: RwLock::new(Graph::new(metric, dim, m, ef_construction, ef_search, seed)),
+        })
+    }
+
+    /// Add vectors to the index.
+    ///
+    /// Args:
+    ///     vectors (ArrayLike): A 2-D array of shape `(n, dim)`.
+    ///     ids (Optional[Sequence[int]]): An id for each vector. Ids must not
+    ///         already be in the index, though deleted ones may be reused.
+    ///         By default vectors are numbered on from the largest id ever
+    ///         inserted.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: The `int64` ids of the vectors.
+    #[pyo3(signature = (vectors, ids=None))]
+    fn insert<'py>(
+        &self,
+        vectors: &Bound<'py, PyAny>,
+        ids: Option<Vec<i64>>,
+    ) -> PyResult<Bound<'py, PyArray1<i64>>> {
+        let py = vectors.py();
+        let vectors = array::<f32>(vectors, 2, "vectors")?;
+        let (count, columns) = (vectors.shape()[0], vectors.shape()[1]);
+        let vectors = vectors.as_slice()?;
+        let ids = py.detach(|| -> PyResult<Vec<i64>> {
+            let mut graph = self.graph.write().unwrap();
+            if columns != graph.dim {
+                return Err(PyValueError::new_err(format!(
+                    "vectors must have {} columns, got {}",
+                    graph.dim, columns
+                )));
+            }
+            let ids = match ids {
+                Some(ids) if ids.len() != count => {
+                    return Err(PyValueError::new_err(format!(
+                        "Got {} ids for {} vectors",
+                        ids.len(),
+                        count
+                    )));
+                }
+                Some(ids) => ids,
+                None => (0..count as i64).map(|i| graph.next_id() + i).collect(),
+            };
+            let mut seen = HashSet::with_capacity(count);
+            for &id in &ids {
+                if graph.contains(id) {
+                    return Err(PyValueError::new_err(format!(
+                        "Id {} is already in the index",
+                        id
+                    )));
+                }
+                if !seen.insert(id) {
+                    return Err(PyValueError::new_err(format!(
+                        "Id {} is given more than once",
+                        id
+                    )));
+                }
+            }
+            for (&id, vector) in ids.iter().zip(vectors.chunks_exact(columns)) {
+                graph.insert(id, vector);
+            }
+            Ok(ids)
+        })?;
+        Ok(PyArray1::from_vec(py, ids))
+    }
+
+    /// Find the nearest vectors to each query.
+    ///
+    /// Args:
+    ///     queries (ArrayLike): A 2-D array of shape `(m, dim)`.
+    ///     k (int): Neighbors to find per query.
+    ///     ef (Optional[int]): Candidates to consider per query, `ef_search`
+    ///         by default. It is raised to `k` when lower.
+    ///
+    /// Returns:
+    ///     Tuple[numpy.ndarray, numpy.ndarray]: `(ids, distances)`, both of
+    ///     shape `(m, k)`: the `int64` ids nearest each query and their
+    ///     `float32` distances, nearest first. When fewer than `k` vectors
+    ///     are found the rest of the row holds id -1 and distance `inf`.
+    #[pyo3(signature = (queries, k, ef=None))]
+    fn search<'py>(
+        &self,
+        queries: &Bound<'py, PyAny>,
+        k: usize,
+        ef: Option<usize>,
+    ) -> PyResult<Neighbors<'py>> {
+        if k == 0 {
+            return Err(PyValueError::new_err("k must be at least 1"));
+        }
+        let py = queries.py();
+        let queries = array::<f32>(queries, 2, "queries")?;
+        let (count, columns) = (queries.shape()[0], queries.shape()[1]);
+        let queries = queries.as_slice()?;
+        let found = py.detach(|| -> PyResult<Vec<Vec<(i64, f32)>>> {
+            let graph = self.graph.read().unwrap();
+            if columns != graph.dim {
+                return Err(PyValueError::new_err(format!(
+                    "queries must have {} columns, got {}",
+                    graph.dim, columns
+                )));
+            }
+            let ef = ef.unwrap_or(graph.ef_search);
+            Ok(queries
+                .par_chunks_exact(columns)
+                .map(|query| graph.search(query, k, ef))
+                .collect())
+        })?;
+        let mut ids = Array2::from_elem((count, k), -1i64);
+        let mut distances = Array2::from_elem((count, k), f32::INFINITY);
+        for (row, neighbors) in found.into_iter().enumerate() {
+            for (col, (id, distance)) in neighbors.into_iter().enumerate() {
+                ids[(row, col)] = id;
+                distances[(row, col)] = distance;
+            }
+        }
+        Ok((ids.into_pyarray(py), distances.into_pyarray(py)))
+    }
+
+    /// Delete ids from the index, ignoring any that are not in it.
+    ///
+    /// Returns:
+    ///     int: The number of ids deleted.
+    fn delete(&self, py: Python<'_>, ids: Vec<i64>) -> usize {
+        py.detach(|| {
+            let mut graph = self.graph.write().unwrap();
+            ids.into_iter().filter(|&id| graph.delete(id)).count()
+        })
+    }
+
+    /// Save the index in a binary file.
+    fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let data = py.detach(|| self.graph.read().unwrap().to_bytes());
+        fs::write(path, data)
+            .map_err(|e| PyIOError::new_err(format!("Failed to write index file: {}", e)))
+    }
+
+    /// Load an index saved with `save`.
+    #[staticmethod]
+    fn load(py: Python<'_>, path: &str) -> PyResult<Self> {
+        let data = fs::read(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read index file: {}", e)))?;
+        let graph = py
+            .detach(|| Graph::from_bytes(&data))
+            .map_err(PyValueError::new_err)?;
+        Ok(HnswIndex {
+            graph: RwLock::new(graph),
+        })
+    }
+
+    #[getter]
+    fn dim(&self) -> usize {
+        self.graph.read().unwrap().dim
+    }
+
+    #[getter]
+    fn metric(&self) -> &'static str {
+        self.graph.read().unwrap().metric.name()
+    }
+
+    #[getter]
+    fn ef_search(&self) -> usize {
+        self.graph.read().unwrap().ef_search
+    }
+
+    #[setter]
+    fn set_ef_search(&self, ef_search: usize) -> PyResult<()> {
+        if ef_search == 0 {
+            return Err(PyValueError::new_err("ef_search must be at least 1"));
+        }
+        self.graph.write().unwrap().ef_search = ef_search;
+        Ok(())
+    }
+
+    fn __len__(&self) -> usize {
+        self.graph.read().unwrap().len()
+    }
+
+    fn __contains__(&self, id: i64) -> bool {
+        self.graph.read().unwrap().contains(id)
+    }
+
+    fn __repr__(&self) -> String {
+        let graph = self.graph.read().unwrap();
+        format!(
+            "HnswIndex(dim={}, metric='{}', len={}, deleted={})",
+            graph.dim,
+            graph.metric.name(),
+            graph.len(),
+            graph.nodes() - graph.len()
+        )
+    }
+}
@@ -0,0 +1,100 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Partial sums kept by the kernels. Eight independent accumulators let the
+/// compiler turn each loop into SIMD instructions (one AVX register of
+/// `f32`, two of `f64`), which a single running sum would not allow since
+/// float addition is not associative.
+const LANES: usize = 8;
+
+/// Element types of the vectors the kernels compare.
+pub trait Scalar:
+    Copy
+    + Send
+    + Sync
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn from_f64(value: f64) -> Self;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    <$t>::total_cmp(self, other)
+                }
+            }
+        )*
+    };
+}
+
+scalar!(f32, f64);
+
+/// Sum of `term(a[i], b[i])` over two vectors of the same length.
+#[inline(always)]
+fn sum_by<T: Scalar>(a: &[T], b: &[T], term: impl Fn(T, T) -> T) -> T {
+    let (a_chunks, b_chunks) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let (a_rest, b_rest) = (a_chunks.remainder(), b_chunks.remainder());
+    let mut lanes = [T::ZERO; LANES];
+    for (x, y) in a_chunks.zip(b_chunks) {
+        for ((lane, &x), &y) in lanes.iter_mut().zip(x).zip(y) {
+            *lane = *lane + term(x, y);
+        }
+    }
+    let mut sum = lanes.into_iter().fold(T::ZERO, |sum, lane| sum + lane);
+    for (&x, &y) in a_rest.iter().zip(b_rest) {
+        sum = sum + term(x, y);
+    }
+    sum
+}
+
+pub fn dot<T: Scalar>(a: &[T], b: &[T]) -> T {
+    sum_by(a, b, |x, y| x * y)
+}
+
+pub fn norm<T: Scalar>(a: &[T]) -> T {
+    dot(a, a).sqrt()
+}
+
+pub fn squared_euclidean<T: Scalar>(a: &[T], b: &[T]) -> T {
+    sum_by(a, b, |x, y| {
+        let d = x - y;
+        d * d
+    })
+}
+
+pub fn manhattan<T: Scalar>(a: &[T], b: &[T]) -> T {
+    sum_by(a, b, |x, y| (x - y).abs())
+}
+
+/// Number of positions where `a` and `b` differ.
+pub fn mismatches<T: Scalar>(a: &[T], b: &[T]) -> T {
+    sum_by(a, b, |x, y| if x == y { T::ZERO } else { T::ONE })
+}
@@ -0,0 +1,29 @@
+use pyo3::prelude::*;
+
+mod arrays;
+mod hnsw;
+mod index;
+mod kernels;
+mod metric;
+mod quantize;
+mod quantizers;
+mod search;
+
+use arrays::{distance, distances, pairwise_distances, top_k};
+use index::HnswIndex;
+use quantizers::{BinaryQuantizer, ScalarQuantizer};
+
+/// A Python module implemented in Rust.
+#[pymodule(gil_used = false)]
+fn swarmauri_distance_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Register standalone functions
+    m.add_function(wrap_pyfunction!(distance, m)?)?;
+    m.add_function(wrap_pyfunction!(distances, m)?)?;
+    m.add_function(wrap_pyfunction!(pairwise_distances, m)?)?;
+    m.add_function(wrap_pyfunction!(top_k, m)?)?;
+    // Register classes
+    m.add_class::<BinaryQuantizer>()?;
+    m.add_class::<HnswIndex>()?;
+    m.add_class::<ScalarQuantizer>()?;
+    Ok(())
+}
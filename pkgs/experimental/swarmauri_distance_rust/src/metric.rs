@@ -0,0 +1,84 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::kernels::{self, Scalar};
+
+/// Norms below this make a vector count as zero for cosine distance, as in
+/// the SDK's `CosineDistance`.
+const MIN_NORM: f64 = 1e-10;
+
+/// The distances the kernels compute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// `1 - cos(a, b)`, or 1 when either vector is zero.
+    Cosine,
+    /// `1 - a·b`, the cosine distance of normalized vectors without the
+    /// division.
+    Dot,
+    Euclidean,
+    Manhattan,
+    /// The number of positions where the vectors differ.
+    Hamming,
+}
+
+impl Metric {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "cosine" => Ok(Metric::Cosine),
+            "dot" => Ok(Metric::Dot),
+            "euclidean" | "l2" => Ok(Metric::Euclidean),
+            "manhattan" | "l1" => Ok(Metric::Manhattan),
+            "hamming" => Ok(Metric::Hamming),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown metric '{}', expected 'cosine', 'dot', 'euclidean', 'manhattan' or 'hamming'",
+                other
+            ))),
+        }
+    }
+
+    /// The name `parse` reads back as this metric.
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Cosine => "cosine",
+            Metric::Dot => "dot",
+            Metric::Euclidean => "euclidean",
+            Metric::Manhattan => "manhattan",
+            Metric::Hamming => "hamming",
+        }
+    }
+
+    /// What the metric precomputes per vector, so that comparing one vector
+    /// with many computes it once: the norm for cosine, nothing otherwise.
+    pub fn prepare<T: Scalar>(self, v: &[T]) -> T {
+        match self {
+            Metric::Cosine => kernels::norm(v),
+            _ => T::ZERO,
+        }
+    }
+
+    /// `prepare` for each row of a matrix with `dim` columns.
+    pub fn prepare_rows<T: Scalar>(self, rows: &[T], dim: usize) -> Vec<T> {
+        rows.chunks_exact(dim)
+            .map(|row| self.prepare(row))
+            .collect()
+    }
+
+    /// The distance between `a` and `b`, given what `prepare` returned for
+    /// each.
+    pub fn distance<T: Scalar>(self, a: &[T], prepared_a: T, b: &[T], prepared_b: T) -> T {
+        match self {
+            Metric::Cosine => {
+                let min = T::from_f64(MIN_NORM);
+                if prepared_a < min || prepared_b < min {
+                    T::ONE
+                } else {
+                    T::ONE - kernels::dot(a, b) / (prepared_a * prepared_b)
+                }
+            }
+            Metric::Dot => T::ONE - kernels::dot(a, b),
+            Metric::Euclidean => kernels::squared_euclidean(a, b).sqrt(),
+            Metric::Manhattan => kernels::manhattan(a, b),
+            Metric::Hamming => kernels::mismatches(a, b),
+        }
+    }
+}
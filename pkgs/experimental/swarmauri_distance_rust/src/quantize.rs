@@ -0,0 +1,192 @@
+use rayon::prelude::*;
+
+use crate::arrays::PARALLEL_MIN;
+use crate::metric::Metric;
+
+/// Codes of the int8 range, from -128 to 127.
+const LEVELS: f32 = 255.0;
+
+/// Maps each dimension of `f32` vectors linearly from its `[lower, upper]`
+/// range onto the 256 values of an `i8`, clamping values outside it.
+#[derive(Clone, Debug)]
+pub struct ScalarCodec {
+    pub lower: Vec<f32>,
+    pub upper: Vec<f32>,
+    /// The value between two codes in each dimension, 0 when the range is
+    /// a single value.
+    step: Vec<f32>,
+}
+
+impl ScalarCodec {
+    pub fn new(lower: Vec<f32>, upper: Vec<f32>) -> Self {
+        let step = lower
+            .iter()
+            .zip(&upper)
+            .map(|(&lower, &upper)| (upper - lower) / LEVELS)
+            .collect();
+        ScalarCodec { lower, upper, step }
+    }
+
+    /// The range of each dimension over the rows of `vectors`, which must
+    /// not be empty.
+    pub fn fit(vectors: &[f32], dim: usize) -> Self {
+        let mut lower = vec![f32::INFINITY; dim];
+        let mut upper = vec![f32::NEG_INFINITY; dim];
+        for row in vectors.chunks_exact(dim) {
+            for ((lower, upper), &value) in lower.iter_mut().zip(upper.iter_mut()).zip(row) {
+                *lower = lower.min(value);
+                *upper = upper.max(value);
+            }
+        }
+        ScalarCodec::new(lower, upper)
+    }
+
+    pub fn dim(&self) -> usize {
+        self.lower.len()
+    }
+
+    pub fn encode(&self, row: &[f32], out: &mut [i8]) {
+        for (((out, &value), &lower), &step) in
+            out.iter_mut().zip(row).zip(&self.lower).zip(&self.step)
+        {
+            let level = if step > 0.0 {
+                ((value - lower) / step).round().clamp(0.0, LEVELS)
+            } else {
+                0.0
+            };
+            *out = (level as i32 - 128) as i8;
+        }
+    }
+
+    pub fn decode(&self, codes: &[i8], out: &mut [f32]) {
+        for (((out, &code), &lower), &step) in
+            out.iter_mut().zip(codes).zip(&self.lower).zip(&self.step)
+        {
+            *out = lower + step * (code as i32 + 128) as f32;
+        }
+    }
+}
+
+/// Bytes holding the sign bits of a `dim`-dimensional vector.
+pub fn packed_len(dim: usize) -> usize {
+    dim.div_ceil(8)
+}
+
+/// Pack a bit per value, set for the positive ones, most significant bit
+/// first as `numpy.packbits` does. Trailing bits of the last byte are 0.
+pub fn encode_signs(row: &[f32], out: &mut [u8]) {
+    out.fill(0);
+    for (i, &value) in row.iter().enumerate() {
+        if value > 0.0 {
+            out[i / 8] |= 0x80 >> (i % 8);
+        }
+    }
+}
+
+/// Unpack sign bits into 1 for each set bit and -1 for each clear one.
+pub fn decode_signs(codes: &[u8], out: &mut [f32]) {
+    for (i, out) in out.iter_mut().enumerate() {
+        *out = if codes[i / 8] & (0x80 >> (i % 8)) != 0 {
+            1.0
+        } else {
+            -1.0
+        };
+    }
+}
+
+/// The number of bits that differ between two packed vectors.
+pub fn bit_mismatches(a: &[u8], b: &[u8]) -> f32 {
+    let chunks = a.chunks_exact(8).zip(b.chunks_exact(8));
+    let words: u32 = chunks
+        .map(|(a, b)| {
+            let a = u64::from_le_bytes(a.try_into().unwrap());
+            let b = u64::from_le_bytes(b.try_into().unwrap());
+            (a ^ b).count_ones()
+        })
+        .sum();
+    let rest = a.len() - a.len() % 8;
+    let bytes: u32 = a[rest..]
+        .iter()
+        .zip(&b[rest..])
+        .map(|(a, b)| (a ^ b).count_ones())
+        .sum();
+    (words + bytes) as f32
+}
+
+/// The distances of `queries` query vectors to `rows` encoded vectors in
+/// row-major order, given `distances(scratch, row)` that computes one
+/// encoded vector's distances to all queries. Large batches are spread over
+/// the Rayon threads, each with its own `scratch()`.
+fn by_row<S>(
+    queries: usize,
+    rows: usize,
+    work: usize,
+    scratch: impl Fn() -> S + Sync + Send,
+    distances: impl Fn(&mut S, usize) -> Vec<f32> + Sync + Send,
+) -> Vec<f32> {
+    let columns: Vec<Vec<f32>> = if queries * rows * work >= PARALLEL_MIN {
+        (0..rows)
+            .into_par_iter()
+            .map_init(scratch, distances)
+            .collect()
+    } else {
+        let mut scratch = scratch();
+        (0..rows).map(|row| distances(&mut scratch, row)).collect()
+    };
+    let mut out = vec![0.0; queries * rows];
+    for (row, column) in columns.into_iter().enumerate() {
+        for (query, distance) in column.into_iter().enumerate() {
+            out[query * rows + row] = distance;
+        }
+    }
+    out
+}
+
+/// Distances from every row of `queries` to each of `rows` encoded vectors,
+/// computed on the vectors `decode(row, buffer)` writes into a buffer of
+/// `dim` values. Rows are decoded one at a time, so the encoded vectors are
+/// never all held as floats.
+pub fn pairwise_decoded(
+    metric: Metric,
+    queries: &[f32],
+    rows: usize,
+    dim: usize,
+    decode: impl Fn(usize, &mut [f32]) + Sync,
+) -> Vec<f32> {
+    let query_prepared = metric.prepare_rows(queries, dim);
+    by_row(
+        query_prepared.len(),
+        rows,
+        dim,
+        || vec![0.0; dim],
+        |buffer: &mut Vec<f32>, row| {
+            decode(row, buffer);
+            let prepared = metric.prepare(buffer);
+            queries
+                .chunks_exact(dim)
+                .zip(&query_prepared)
+                .map(|(query, &query_prepared)| {
+                    metric.distance(query, query_prepared, buffer, prepared)
+                })
+                .collect()
+        },
+    )
+}
+
+/// The number of sign bits that differ between every row of `queries` and
+/// each row of `codes`, both packed into `len` bytes per vector.
+pub fn pairwise_bit_mismatches(queries: &[u8], codes: &[u8], len: usize) -> Vec<f32> {
+    by_row(
+        queries.len() / len,
+        codes.len() / len,
+        len,
+        || (),
+        |_, row| {
+            let code = &codes[row * len..(row + 1) * len];
+            queries
+                .chunks_exact(len)
+                .map(|query| bit_mismatches(query, code))
+                .collect()
+        },
+    )
+}
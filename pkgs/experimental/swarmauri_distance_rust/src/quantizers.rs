@@ -0,0 +1,386 @@
+use numpy::ndarray::Array2;
+use numpy::{Element, IntoPyArray, PyArray1, PyArray2, PyReadonlyArrayDyn, PyUntypedArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::arrays::array;
+use crate::metric::Metric;
+use crate::quantize::{self, ScalarCodec};
+
+/// `obj` as a C-contiguous 2-D array of `T` with `columns` columns, and its
+/// number of rows.
+fn matrix<'py, T: Element>(
+    obj: &Bound<'py, PyAny>,
+    columns: usize,
+    name: &str,
+) -> PyResult<(PyReadonlyArrayDyn<'py, T>, usize)> {
+    let matrix = array::<T>(obj, 2, name)?;
+    if matrix.shape()[1] != columns {
+        return Err(PyValueError::new_err(format!(
+            "{} must have {} columns, got {}",
+            name,
+            columns,
+            matrix.shape()[1]
+        )));
+    }
+    let rows = matrix.shape()[0];
+    Ok((matrix, rows))
+}
+
+/// Encode each row of `input` into the row of the same index of `out`.
+fn map_rows<A, B>(input: &[A], out: &mut [B], columns: (usize, usize), f: impl Fn(&[A], &mut [B])) {
+    for (row, out) in input
+        .chunks_exact(columns.0)
+        .zip(out.chunks_exact_mut(columns.1))
+    {
+        f(row, out);
+    }
+}
+
+/// Compresses `float32` vectors to one `int8` per dimension, a quarter of
+/// their size.
+///
+/// Each dimension is mapped linearly from its `[lower, upper]` range onto
+/// the 256 codes, so a value is decoded to within half a step,
+/// `(upper - lower) / 510`, of where it was; values outside the range are
+/// clamped to it. `fit` takes the ranges from sample vectors.
+///
+/// Distances are asymmetric: queries stay in full precision and are
+/// compared with the decoded vectors, so only the stored side loses
+/// precision.
+///
+/// Args:
+///     lower (ArrayLike): The lowest value of each dimension.
+///     upper (ArrayLike): The highest value of each dimension.
+#[pyclass(frozen, skip_from_py_object, module = "swarmauri_distance_rust")]
+pub struct ScalarQuantizer {
+    codec: ScalarCodec,
+}
+
+#[pymethods]
+impl ScalarQuantizer {
+    #[new]
+    fn new(lower: &Bound<'_, PyAny>, upper: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let lower = array::<f32>(lower, 1, "lower")?.as_slice()?.to_vec();
+        let upper = array::<f32>(upper, 1, "upper")?.as_slice()?.to_vec();
+        if lower.len() != upper.len() {
+            return Err(PyValueError::new_err(format!(
+                "lower and upper must have the same length, got {} and {}",
+                lower.len(),
+                upper.len()
+            )));
+        }
+        if lower.is_empty() {
+            return Err(PyValueError::new_err("Vectors must not be empty"));
+        }
+        if lower
+            .iter()
+            .zip(&upper)
+            .any(|(lower, upper)| lower > upper || lower.is_nan() || upper.is_nan())
+        {
+            return Err(PyValueError::new_err(
+                "lower must not be above upper in any dimension",
+            ));
+        }
+        Ok(ScalarQuantizer {
+            codec: ScalarCodec::new(lower, upper),
+        })
+    }
+
+    /// A quantizer for the range of each dimension over sample vectors.
+    ///
+    /// Args:
+    ///     vectors (ArrayLike): A 2-D array of shape `(n, dim)` with at
+    ///         least one row.
+    #[staticmethod]
+    fn fit(vectors: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = vectors.py();
+        let vectors = array::<f32>(vectors, 2, "vectors")?;
+        let dim = vectors.shape()[1];
+        if vectors.shape()[0] == 0 || dim == 0 {
+            return Err(PyValueError::new_err(
+                "Cannot fit a quantizer to no vectors",
+            ));
+        }
+        let vectors = vectors.as_slice()?;
+        let codec = py.detach(|| ScalarCodec::fit(vectors, dim));
+        Ok(ScalarQuantizer { codec })
+    }
+
+    /// Encode vectors of shape `(n, dim)` as `int8` codes of the same shape.
+    fn encode<'py>(&self, vectors: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyArray2<i8>>> {
+        let dim = self.codec.dim();
+        let (vectors, rows) = matrix::<f32>(vectors, dim, "vectors")?;
+        let mut codes = vec![0i8; rows * dim];
+        map_rows(vectors.as_slice()?, &mut codes, (dim, dim), |row, out| {
+            self.codec.encode(row, out)
+        });
+        Ok(Array2::from_shape_vec((rows, dim), codes)
+            .expect("a code per value")
+            .into_pyarray(vectors.py()))
+    }
+
+    /// Decode `int8` codes of shape `(n, dim)` back to `float32` vectors.
+    fn decode<'py>(&self, codes: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let dim = self.codec.dim();
+        let (codes, rows) = matrix::<i8>(codes, dim, "codes")?;
+        let mut vectors = vec![0.0; rows * dim];
+        map_rows(codes.as_slice()?, &mut vectors, (dim, dim), |row, out| {
+            self.codec.decode(row, out)
+        });
+        Ok(Array2::from_shape_vec((rows, dim), vectors)
+            .expect("a value per code")
+            .into_pyarray(codes.py()))
+    }
+
+    /// Distances from a full-precision query to each encoded vector.
+    ///
+    /// Args:
+    ///     query (ArrayLike): A 1-D array of `dim` numbers.
+    ///     codes (ArrayLike): `int8` codes of shape `(n, dim)`.
+    ///     metric (str): See `distance`.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: The `n` `float32` distances.
+    #[pyo3(signature = (query, codes, metric="cosine"))]
+    fn distances<'py>(
+        &self,
+        query: &Bound<'py, PyAny>,
+        codes: &Bound<'py, PyAny>,
+        metric: &str,
+    ) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let query = array::<f32>(query, 1, "query")?;
+        let (out, _) = self.pairwise_of(query.as_slice()?, codes, metric, 1)?;
+        Ok(PyArray1::from_vec(codes.py(), out))
+    }
+
+    /// Distances from each full-precision query to each encoded vector.
+    ///
+    /// Args:
+    ///     queries (ArrayLike): A 2-D array of shape `(m, dim)`.
+    ///     codes (ArrayLike): `int8` codes of shape `(n, dim)`.
+    ///     metric (str): See `distance`.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: An `(m, n)` matrix of `float32` distances.
+    #[pyo3(signature = (queries, codes, metric="cosine"))]
+    fn pairwise_distances<'py>(
+        &self,
+        queries: &Bound<'py, PyAny>,
+        codes: &Bound<'py, PyAny>,
+        metric: &str,
+    ) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let (queries, count) = matrix::<f32>(queries, self.codec.dim(), "queries")?;
+        let (out, rows) = self.pairwise_of(queries.as_slice()?, codes, metric, count)?;
+        Ok(Array2::from_shape_vec((count, rows), out)
+            .expect("one distance per pair")
+            .into_pyarray(codes.py()))
+    }
+
+    /// The lowest value of each dimension.
+    #[getter]
+    fn lower<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
+        PyArray1::from_slice(py, &self.codec.lower)
+    }
+
+    /// The highest value of each dimension.
+    #[getter]
+    fn upper<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
+        PyArray1::from_slice(py, &self.codec.upper)
+    }
+
+    #[getter]
+    fn dim(&self) -> usize {
+        self.codec.dim()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ScalarQuantizer(dim={})", self.codec.dim())
+    }
+}
+
+impl ScalarQuantizer {
+    /// The distances from `count` queries to each encoded vector, and the
+    /// number of encoded vectors.
+    fn pairwise_of(
+        &self,
+        queries: &[f32],
+        codes: &Bound<'_, PyAny>,
+        metric: &str,
+        count: usize,
+    ) -> PyResult<(Vec<f32>, usize)> {
+        let py = codes.py();
+        let metric = Metric::parse(metric)?;
+        let dim = self.codec.dim();
+        if queries.len() != count * dim {
+            return Err(PyValueError::new_err(format!(
+                "query must have {} values, got {}",
+                dim,
+                queries.len()
+            )));
+        }
+        let (codes, rows) = matrix::<i8>(codes, dim, "codes")?;
+        let codes = codes.as_slice()?;
+        let out = py.detach(|| {
+            quantize::pairwise_decoded(metric, queries, rows, dim, |row, out| {
+                self.codec.decode(&codes[row * dim..(row + 1) * dim], out)
+            })
+        });
+        Ok((out, rows))
+    }
+}
+
+/// Compresses `float32` vectors to one bit per dimension, a 32nd of their
+/// size, keeping only whether each value is positive.
+///
+/// Codes are `uint8` arrays of shape `(n, ceil(dim / 8))` with the bits
+/// packed most significant first, as `numpy.packbits(vectors > 0, axis=1)`
+/// would, and decode to vectors of 1 and -1.
+///
+/// With the default `"hamming"` metric, distances count the dimensions
+/// where a query and a vector differ in sign, using the CPU's popcount.
+/// Other metrics are asymmetric: the full-precision query is compared with
+/// the decoded vector of 1s and -1s, which keeps more of the query's
+/// information and ranks better, at the speed of a float distance.
+///
+/// Args:
+///     dim (int): The dimensionality of the vectors.
+#[pyclass(frozen, skip_from_py_object, module = "swarmauri_distance_rust")]
+pub struct BinaryQuantizer {
+    dim: usize,
+}
+
+#[pymethods]
+impl BinaryQuantizer {
+    #[new]
+    fn new(dim: usize) -> PyResult<Self> {
+        if dim == 0 {
+            return Err(PyValueError::new_err("dim must be at least 1"));
+        }
+        Ok(BinaryQuantizer { dim })
+    }
+
+    /// Encode vectors of shape `(n, dim)` as packed sign bits.
+    fn encode<'py>(&self, vectors: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyArray2<u8>>> {
+        let (vectors, rows) = matrix::<f32>(vectors, self.dim, "vectors")?;
+        let len = quantize::packed_len(self.dim);
+        let mut codes = vec![0u8; rows * len];
+        map_rows(
+            vectors.as_slice()?,
+            &mut codes,
+            (self.dim, len),
+            quantize::encode_signs,
+        );
+        Ok(Array2::from_shape_vec((rows, len), codes)
+            .expect("packed bits per row")
+            .into_pyarray(vectors.py()))
+    }
+
+    /// Decode packed sign bits to `float32` vectors of 1 and -1.
+    fn decode<'py>(&self, codes: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let len = quantize::packed_len(self.dim);
+        let (codes, rows) = matrix::<u8>(codes, len, "codes")?;
+        let mut vectors = vec![0.0; rows * self.dim];
+        map_rows(
+            codes.as_slice()?,
+            &mut vectors,
+            (len, self.dim),
+            quantize::decode_signs,
+        );
+        Ok(Array2::from_shape_vec((rows, self.dim), vectors)
+            .expect("a value per bit")
+            .into_pyarray(codes.py()))
+    }
+
+    /// Distances from a full-precision query to each encoded vector.
+    ///
+    /// Args:
+    ///     query (ArrayLike): A 1-D array of `dim` numbers.
+    ///     codes (ArrayLike): Packed codes of shape `(n, ceil(dim / 8))`.
+    ///     metric (str): `"hamming"` for the number of signs that differ, or
+    ///         another metric of `distance` for the distance to the decoded
+    ///         vectors.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: The `n` `float32` distances.
+    #[pyo3(signature = (query, codes, metric="hamming"))]
+    fn distances<'py>(
+        &self,
+        query: &Bound<'py, PyAny>,
+        codes: &Bound<'py, PyAny>,
+        metric: &str,
+    ) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let query = array::<f32>(query, 1, "query")?;
+        let (out, _) = self.pairwise_of(query.as_slice()?, codes, metric, 1)?;
+        Ok(PyArray1::from_vec(codes.py(), out))
+    }
+
+    /// Distances from each full-precision query to each encoded vector.
+    ///
+    /// Args:
+    ///     queries (ArrayLike): A 2-D array of shape `(m, dim)`.
+    ///     codes (ArrayLike): Packed codes of shape `(n, ceil(dim / 8))`.
+    ///     metric (str): See `distances`.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: An `(m, n)` matrix of `float32` distances.
+    #[pyo3(signature = (queries, codes, metric="hamming"))]
+    fn pairwise_distances<'py>(
+        &self,
+        queries: &Bound<'py, PyAny>,
+        codes: &Bound<'py, PyAny>,
+        metric: &str,
+    ) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let (queries, count) = matrix::<f32>(queries, self.dim, "queries")?;
+        let (out, rows) = self.pairwise_of(queries.as_slice()?, codes, metric, count)?;
+        Ok(Array2::from_shape_vec((count, rows), out)
+            .expect("one distance per pair")
+            .into_pyarray(codes.py()))
+    }
+
+    #[getter]
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BinaryQuantizer(dim={})", self.dim)
+    }
+}
+
+impl BinaryQuantizer {
+    /// The distances from `count` queries to each encoded vector, and the
+    /// number of encoded vectors.
+    fn pairwise_of(
+        &self,
+        queries: &[f32],
+        codes: &Bound<'_, PyAny>,
+        metric: &str,
+        count: usize,
+    ) -> PyResult<(Vec<f32>, usize)> {
+        let py = codes.py();
+        let metric = Metric::parse(metric)?;
+        let (dim, len) = (self.dim, quantize::packed_len(self.dim));
+        if queries.len() != count * dim {
+            return Err(PyValueError::new_err(format!(
+                "query must have {} values, got {}",
+                dim,
+                queries.len()
+            )));
+        }
+        let (codes, rows) = matrix::<u8>(codes, len, "codes")?;
+        let codes = codes.as_slice()?;
+        let out = py.detach(|| {
+            if metric == Metric::Hamming {
+                let mut packed = vec![0u8; count * len];
+                map_rows(queries, &mut packed, (dim, len), quantize::encode_signs);
+                quantize::pairwise_bit_mismatches(&packed, codes, len)
+            } else {
+                quantize::pairwise_decoded(metric, queries, rows, dim, |row, out| {
+                    quantize::decode_signs(&codes[row * len..(row + 1) * len], out)
+                })
+            }
+        });
+        Ok((out, rows))
+    }
+}
@@ -0,0 +1,140 @@
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::kernels::Scalar;
+use crate::metric::Metric;
+
+/// Corpus rows compared per block: 1024 rows of 384 `f32`s are 1.5 MB, which
+/// stays in L2 cache while every query of a batch is compared with them.
+const BLOCK_ROWS: usize = 1024;
+
+/// Queries compared with each corpus block in one task.
+const QUERY_BATCH: usize = 16;
+
+/// A corpus row and its distance from a query, ordered by distance and then
+/// by index so that ties rank the same on every run.
+#[derive(Clone, Copy, Debug)]
+pub struct Neighbor<T> {
+    pub distance: T,
+    pub index: usize,
+}
+
+impl<T: Scalar> PartialEq for Neighbor<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Scalar> Eq for Neighbor<T> {}
+
+impl<T: Scalar> PartialOrd for Neighbor<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Scalar> Ord for Neighbor<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// The `k` nearest rows seen so far for one query, in a max-heap whose top
+/// is the farthest of them and the first to go.
+struct Nearest<T> {
+    k: usize,
+    heap: BinaryHeap<Neighbor<T>>,
+}
+
+impl<T: Scalar> Nearest<T> {
+    fn new(k: usize) -> Self {
+        Nearest {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, neighbor: Neighbor<T>) {
+        if self.heap.len() < self.k {
+            self.heap.push(neighbor);
+        } else if self
+            .heap
+            .peek()
+            .is_some_and(|farthest| neighbor < *farthest)
+        {
+            self.heap.pop();
+            self.heap.push(neighbor);
+        }
+    }
+
+    fn merge(&mut self, other: Nearest<T>) {
+        for neighbor in other.heap {
+            self.push(neighbor);
+        }
+    }
+}
+
+/// The `k` rows of `corpus` nearest to each row of `queries`, nearest first,
+/// by brute force over the Rayon threads.
+///
+/// Queries are taken in batches and the corpus in cache-sized blocks, so
+/// each block is read from memory once per batch rather than once per
+/// query. When there are fewer batches than threads the corpus is split
+/// between them as well, so that even a single query uses every core.
+pub fn top_k<T: Scalar>(
+    metric: Metric,
+    queries: &[T],
+    corpus: &[T],
+    dim: usize,
+    k: usize,
+) -> Vec<Vec<Neighbor<T>>> {
+    let (query_count, corpus_count) = (queries.len() / dim, corpus.len() / dim);
+    let query_prepared = metric.prepare_rows(queries, dim);
+    let corpus_prepared = metric.prepare_rows(corpus, dim);
+    let batches = query_count.div_ceil(QUERY_BATCH);
+    let parts = (rayon::current_num_threads() / batches.max(1))
+        .clamp(1, corpus_count.div_ceil(BLOCK_ROWS).max(1));
+    let part_rows = corpus_count.div_ceil(parts);
+    let found: Vec<Vec<Nearest<T>>> = (0..batches * parts)
+        .into_par_iter()
+        .map(|task| {
+            let (batch, part) = (task / parts, task % parts);
+            let batch = batch * QUERY_BATCH..((batch + 1) * QUERY_BATCH).min(query_count);
+            let part = part * part_rows..((part + 1) * part_rows).min(corpus_count);
+            let mut nearest: Vec<Nearest<T>> = batch.clone().map(|_| Nearest::new(k)).collect();
+            for start in part.clone().step_by(BLOCK_ROWS) {
+                let block = start..(start + BLOCK_ROWS).min(part.end);
+                for (q, nearest) in batch.clone().zip(&mut nearest) {
+                    let query = &queries[q * dim..(q + 1) * dim];
+                    for index in block.clone() {
+                        let distance = metric.distance(
+                            query,
+                            query_prepared[q],
+                            &corpus[index * dim..(index + 1) * dim],
+                            corpus_prepared[index],
+                        );
+                        nearest.push(Neighbor { distance, index });
+                    }
+                }
+            }
+            nearest
+        })
+        .collect();
+    // Tasks come back in order, the parts of each batch together.
+    let mut found = found.into_iter();
+    let mut results = Vec::with_capacity(query_count);
+    for _ in 0..batches {
+        let mut nearest = found.next().expect("a task per part");
+        for _ in 1..parts {
+            let part = found.next().expect("a task per part");
+            for (nearest, other) in nearest.iter_mut().zip(part) {
+                nearest.merge(other);
+            }
+        }
+        results.extend(nearest.into_iter().map(|n| n.heap.into_sorted_vec()));
+    }
+    results
+}
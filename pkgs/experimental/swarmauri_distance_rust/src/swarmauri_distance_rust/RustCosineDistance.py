@@ -0,0 +1,41 @@
+from typing import List, Literal
+
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.distances.DistanceBase import DistanceBase
+from swarmauri_core.vectors.IVector import IVector
+
+from ._vectors import as_array, as_matrix
+from .swarmauri_distance_rust import distance, distances
+
+
+@ComponentBase.register_type(DistanceBase, "RustCosineDistance")
+class RustCosineDistance(DistanceBase):
+    """
+    Cosine distance, `1 - cosine similarity`, computed by the Rust kernels.
+
+    A drop-in replacement for `CosineDistance`: the distance ranges from 0
+    for vectors pointing the same way to 2 for opposite ones, and is 1 when
+    either vector is zero. The similarity is `1 - distance`.
+    """
+
+    type: Literal["RustCosineDistance"] = "RustCosineDistance"
+
+    def distance(self, vector_a: IVector, vector_b: IVector) -> float:
+        return distance(as_array(vector_a), as_array(vector_b), "cosine")
+
+    def distances(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        if not vectors_b:
+            return []
+        return distances(
+            as_array(vector_a), as_matrix(vectors_b), "cosine"
+        ).tolist()
+
+    def similarity(self, vector_a: IVector, vector_b: IVector) -> float:
+        return 1 - self.distance(vector_a, vector_b)
+
+    def similarities(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        return [1 - d for d in self.distances(vector_a, vectors_b)]
@@ -0,0 +1,40 @@
+from typing import List, Literal
+
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.distances.DistanceBase import DistanceBase
+from swarmauri_core.vectors.IVector import IVector
+
+from ._vectors import as_array, as_matrix
+from .swarmauri_distance_rust import distance, distances
+
+
+@ComponentBase.register_type(DistanceBase, "RustDotProductDistance")
+class RustDotProductDistance(DistanceBase):
+    """
+    Dot-product distance, `1 - a·b`, computed by the Rust kernels.
+
+    For normalized embeddings this equals the cosine distance while skipping
+    the norms. The similarity is the dot product itself.
+    """
+
+    type: Literal["RustDotProductDistance"] = "RustDotProductDistance"
+
+    def distance(self, vector_a: IVector, vector_b: IVector) -> float:
+        return distance(as_array(vector_a), as_array(vector_b), "dot")
+
+    def distances(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        if not vectors_b:
+            return []
+        return distances(
+            as_array(vector_a), as_matrix(vectors_b), "dot"
+        ).tolist()
+
+    def similarity(self, vector_a: IVector, vector_b: IVector) -> float:
+        return 1 - self.distance(vector_a, vector_b)
+
+    def similarities(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        return [1 - d for d in self.distances(vector_a, vectors_b)]
@@ -0,0 +1,40 @@
+from typing import List, Literal
+
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.distances.DistanceBase import DistanceBase
+from swarmauri_core.vectors.IVector import IVector
+
+from ._vectors import as_array, as_matrix
+from .swarmauri_distance_rust import distance, distances
+
+
+@ComponentBase.register_type(DistanceBase, "RustEuclideanDistance")
+class RustEuclideanDistance(DistanceBase):
+    """
+    Euclidean (L2) distance computed by the Rust kernels.
+
+    A drop-in replacement for `EuclideanDistance`; the similarity is
+    `1 / (1 + distance)`.
+    """
+
+    type: Literal["RustEuclideanDistance"] = "RustEuclideanDistance"
+
+    def distance(self, vector_a: IVector, vector_b: IVector) -> float:
+        return distance(as_array(vector_a), as_array(vector_b), "euclidean")
+
+    def distances(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        if not vectors_b:
+            return []
+        return distances(
+            as_array(vector_a), as_matrix(vectors_b), "euclidean"
+        ).tolist()
+
+    def similarity(self, vector_a: IVector, vector_b: IVector) -> float:
+        return 1 / (1 + self.distance(vector_a, vector_b))
+
+    def similarities(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        return [1 / (1 + d) for d in self.distances(vector_a, vectors_b)]
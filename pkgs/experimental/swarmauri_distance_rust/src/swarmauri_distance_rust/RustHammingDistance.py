@@ -0,0 +1,41 @@
+from typing import List, Literal
+
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.distances.DistanceBase import DistanceBase
+from swarmauri_core.vectors.IVector import IVector
+
+from ._vectors import as_array, as_matrix
+from .swarmauri_distance_rust import distance, distances
+
+
+@ComponentBase.register_type(DistanceBase, "RustHammingDistance")
+class RustHammingDistance(DistanceBase):
+    """
+    Hamming distance, the number of positions where two vectors differ,
+    computed by the Rust kernels.
+
+    The similarity is the fraction of positions where they agree.
+    """
+
+    type: Literal["RustHammingDistance"] = "RustHammingDistance"
+
+    def distance(self, vector_a: IVector, vector_b: IVector) -> float:
+        return distance(as_array(vector_a), as_array(vector_b), "hamming")
+
+    def distances(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        if not vectors_b:
+            return []
+        return distances(
+            as_array(vector_a), as_matrix(vectors_b), "hamming"
+        ).tolist()
+
+    def similarity(self, vector_a: IVector, vector_b: IVector) -> float:
+        return 1 - self.distance(vector_a, vector_b) / len(vector_a.value)
+
+    def similarities(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        size = len(vector_a.value)
+        return [1 - d / size for d in self.distances(vector_a, vectors_b)]
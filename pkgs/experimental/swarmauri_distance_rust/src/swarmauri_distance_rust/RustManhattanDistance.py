@@ -0,0 +1,46 @@
+from typing import List, Literal
+
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.distances.DistanceBase import DistanceBase
+from swarmauri_core.vectors.IVector import IVector
+
+from ._vectors import as_array, as_matrix
+from .swarmauri_distance_rust import distance, distances
+
+
+@ComponentBase.register_type(DistanceBase, "RustManhattanDistance")
+class RustManhattanDistance(DistanceBase):
+    """
+    Manhattan (L1) distance computed by the Rust kernels.
+
+    A drop-in replacement for `ManhattanDistance`, which likewise offers no
+    similarity.
+    """
+
+    type: Literal["RustManhattanDistance"] = "RustManhattanDistance"
+
+    def distance(self, vector_a: IVector, vector_b: IVector) -> float:
+        return distance(as_array(vector_a), as_array(vector_b), "manhattan")
+
+    def distances(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        if not vectors_b:
+            return []
+        return distances(
+            as_array(vector_a), as_matrix(vectors_b), "manhattan"
+        ).tolist()
+
+    def similarity(self, vector_a: IVector, vector_b: IVector) -> float:
+        raise NotImplementedError(
+            "RustManhattanDistance does not directly provide a similarity "
+            "metric."
+        )
+
+    def similarities(
+        self, vector_a: IVector, vectors_b: List[IVector]
+    ) -> List[float]:
+        raise NotImplementedError(
+            "RustManhattanDistance does not directly provide a similarity "
+            "metric."
+        )
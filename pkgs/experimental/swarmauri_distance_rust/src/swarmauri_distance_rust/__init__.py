@@ -0,0 +1,24 @@
+# Import all symbols from the Rust extension
+from .swarmauri_distance_rust import *  # noqa: F403
+
+# Swarmauri distance components backed by the Rust kernels
+from .RustCosineDistance import RustCosineDistance
+from .RustDotProductDistance import RustDotProductDistance
+from .RustEuclideanDistance import RustEuclideanDistance
+from .RustHammingDistance import RustHammingDistance
+from .RustManhattanDistance import RustManhattanDistance
+
+__all__ = [
+    "distance",
+    "distances",
+    "pairwise_distances",
+    "top_k",
+    "HnswIndex",
+    "BinaryQuantizer",
+    "ScalarQuantizer",
+    "RustCosineDistance",
+    "RustDotProductDistance",
+    "RustEuclideanDistance",
+    "RustHammingDistance",
+    "RustManhattanDistance",
+]
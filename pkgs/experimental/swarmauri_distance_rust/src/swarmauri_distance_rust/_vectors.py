@@ -0,0 +1,15 @@
+from typing import List
+
+import numpy as np
+
+from swarmauri_core.vectors.IVector import IVector
+
+
+def as_array(vector: IVector) -> np.ndarray:
+    """The values of a vector as a float64 array for the Rust kernels."""
+    return np.asarray(vector.value, dtype=np.float64)
+
+
+def as_matrix(vectors: List[IVector]) -> np.ndarray:
+    """The values of equally long vectors as the rows of a float64 matrix."""
+    return np.asarray([vector.value for vector in vectors], dtype=np.float64)
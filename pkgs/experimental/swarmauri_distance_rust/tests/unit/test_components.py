@@ -0,0 +1,65 @@
+import pytest
+from swarmauri_standard.distances.CosineDistance import CosineDistance
+from swarmauri_standard.distances.EuclideanDistance import EuclideanDistance
+from swarmauri_standard.distances.ManhattanDistance import ManhattanDistance
+from swarmauri_standard.vectors.Vector import Vector
+
+from swarmauri_distance_rust import (
+    RustCosineDistance,
+    RustDotProductDistance,
+    RustEuclideanDistance,
+    RustHammingDistance,
+    RustManhattanDistance,
+)
+
+QUERY = Vector(value=[1.0, 2.0, 3.0])
+OTHERS = [
+    Vector(value=[1.0, 2.0, 3.0]),
+    Vector(value=[-1.0, 0.5, 2.0]),
+    Vector(value=[0.0, 0.0, 0.0]),
+]
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "rust, standard",
+    [
+        (RustCosineDistance, CosineDistance),
+        (RustEuclideanDistance, EuclideanDistance),
+        (RustManhattanDistance, ManhattanDistance),
+    ],
+)
+def test_matches_standard_distances(rust, standard):
+    expected = standard().distances(QUERY, OTHERS)
+    assert rust().distances(QUERY, OTHERS) == pytest.approx(expected)
+    assert rust().distance(QUERY, OTHERS[1]) == pytest.approx(expected[1])
+    assert rust().distances(QUERY, []) == []
+
+
+@pytest.mark.unit
+def test_similarities():
+    assert RustCosineDistance().similarities(QUERY, OTHERS[:1]) == pytest.approx([1.0])
+    assert RustEuclideanDistance().similarity(QUERY, OTHERS[0]) == 1.0
+    assert RustDotProductDistance().similarity(QUERY, OTHERS[1]) == pytest.approx(6.0)
+    hamming = RustHammingDistance()
+    assert hamming.distance(QUERY, Vector(value=[1.0, 0.0, 3.0])) == 1.0
+    assert hamming.similarities(QUERY, OTHERS) == pytest.approx([1.0, 0.0, 0.0])
+    with pytest.raises(NotImplementedError):
+        RustManhattanDistance().similarity(QUERY, OTHERS[0])
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize(
+    "component",
+    [
+        RustCosineDistance,
+        RustDotProductDistance,
+        RustEuclideanDistance,
+        RustHammingDistance,
+        RustManhattanDistance,
+    ],
+)
+def test_serialization(component):
+    distance = component()
+    assert distance.id == component.model_validate_json(distance.model_dump_json()).id
+    assert distance.type == component.__name__
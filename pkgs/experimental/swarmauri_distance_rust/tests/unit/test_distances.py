@@ -0,0 +1,96 @@
+import numpy as np
+import pytest
+
+from swarmauri_distance_rust import distance, distances, pairwise_distances
+
+METRICS = ["cosine", "dot", "euclidean", "manhattan", "hamming"]
+
+RNG = np.random.default_rng(0)
+QUERIES = RNG.standard_normal((5, 37))
+VECTORS = RNG.standard_normal((7, 37))
+
+
+def reference(a, b, metric):
+    if metric == "cosine":
+        return 1 - a @ b / (np.linalg.norm(a) * np.linalg.norm(b))
+    if metric == "dot":
+        return 1 - a @ b
+    if metric == "euclidean":
+        return np.linalg.norm(a - b)
+    if metric == "manhattan":
+        return np.abs(a - b).sum()
+    return np.count_nonzero(a != b)
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("metric", METRICS)
+def test_distance_matches_numpy(metric):
+    a, b = QUERIES[0], VECTORS[0]
+    assert distance(a, b, metric) == pytest.approx(reference(a, b, metric))
+    assert distance(a.tolist(), b.tolist(), metric) == pytest.approx(reference(a, b, metric))
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("metric", METRICS)
+def test_batches_match_numpy(metric):
+    row = distances(QUERIES[0], VECTORS, metric)
+    assert row.shape == (7,)
+    assert row.dtype == np.float64
+    np.testing.assert_allclose(row, [reference(QUERIES[0], b, metric) for b in VECTORS])
+
+    matrix = pairwise_distances(QUERIES, VECTORS, metric)
+    assert matrix.shape == (5, 7)
+    np.testing.assert_allclose(matrix, [[reference(a, b, metric) for b in VECTORS] for a in QUERIES])
+
+
+@pytest.mark.unit
+def test_large_batch_runs_in_parallel():
+    queries = RNG.standard_normal((64, 128))
+    vectors = RNG.standard_normal((500, 128))
+    expected = 1 - (queries @ vectors.T) / np.outer(
+        np.linalg.norm(queries, axis=1), np.linalg.norm(vectors, axis=1)
+    )
+    np.testing.assert_allclose(pairwise_distances(queries, vectors), expected, atol=1e-12)
+    np.testing.assert_allclose(distances(queries[3], vectors), expected[3], atol=1e-12)
+
+
+@pytest.mark.unit
+def test_float32_stays_float32():
+    queries, vectors = QUERIES.astype(np.float32), VECTORS.astype(np.float32)
+    assert distances(queries[0], vectors).dtype == np.float32
+    matrix = pairwise_distances(queries, vectors, "euclidean")
+    assert matrix.dtype == np.float32
+    np.testing.assert_allclose(matrix, pairwise_distances(QUERIES, VECTORS, "euclidean"), rtol=1e-5)
+    # Mixed precisions are compared in float64.
+    assert distances(queries[0], VECTORS).dtype == np.float64
+
+
+@pytest.mark.unit
+def test_non_contiguous_inputs():
+    np.testing.assert_allclose(
+        pairwise_distances(QUERIES[:, ::2], VECTORS[::2]),
+        pairwise_distances(QUERIES[:, ::2].copy(), VECTORS[::2].copy()),
+    )
+
+
+@pytest.mark.unit
+def test_special_cases():
+    assert distance([0.0, 0.0, 0.0], [1.0, 2.0, 3.0]) == 1.0
+    assert distance([1, 0, 1, 1], [1, 1, 1, 0], "hamming") == 2.0
+    assert distance([1, 2], [4, 6], "l2") == 5.0
+    assert distance([1, 2], [4, 6], "l1") == 7.0
+    assert distances([1.0, 2.0], np.empty((0, 2))).shape == (0,)
+
+
+@pytest.mark.unit
+def test_invalid_arguments():
+    with pytest.raises(ValueError, match="same dimensionality, got 37 and 3"):
+        distances(QUERIES[0], np.ones((2, 3)))
+    with pytest.raises(ValueError, match="Unknown metric 'jaccard'"):
+        distance([1.0], [1.0], "jaccard")
+    with pytest.raises(ValueError, match="query must be a 1-D array, got 2-D"):
+        distances(QUERIES, VECTORS)
+    with pytest.raises(ValueError, match="vectors must be a 2-D array, got 1-D"):
+        pairwise_distances(QUERIES, VECTORS[0])
+    with pytest.raises(ValueError, match="must not be empty"):
+        distance([], [])
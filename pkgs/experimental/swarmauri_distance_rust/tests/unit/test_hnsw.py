@@ -0,0 +1,130 @@
+import numpy as np
+import pytest
+
+from swarmauri_distance_rust import HnswIndex, top_k
+
+RNG = np.random.default_rng(2)
+VECTORS = RNG.standard_normal((2000, 16)).astype(np.float32)
+QUERIES = RNG.standard_normal((50, 16)).astype(np.float32)
+
+
+@pytest.fixture(scope="module")
+def index():
+    index = HnswIndex(16, metric="euclidean")
+    index.insert(VECTORS)
+    return index
+
+
+def recall(ids, exact):
+    return np.mean([len(set(row) & set(want)) / len(want) for row, want in zip(ids, exact)])
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("metric", ["cosine", "euclidean", "manhattan"])
+def test_search_finds_nearest_neighbors(metric):
+    index = HnswIndex(16, metric=metric)
+    assert index.insert(VECTORS).tolist() == list(range(2000))
+    ids, distances = index.search(QUERIES, 10, ef=100)
+    assert ids.shape == distances.shape == (50, 10)
+    assert ids.dtype == np.int64 and distances.dtype == np.float32
+    exact, exact_distances = top_k(QUERIES, VECTORS, 10, metric)
+    assert recall(ids, exact) >= 0.95
+    assert np.all(np.diff(distances, axis=1) >= 0)
+    np.testing.assert_allclose(distances[:, 0], exact_distances[:, 0], rtol=1e-5, atol=1e-6)
+
+
+@pytest.mark.unit
+def test_custom_ids_and_ef_search(index):
+    custom = HnswIndex(16, ef_search=10)
+    ids = np.arange(2000) * 10 + 7
+    custom.insert(VECTORS, ids=ids)
+    assert len(custom) == 2000 and 17 in custom and 18 not in custom
+    found, _ = custom.search(VECTORS[[3]], 1)
+    assert found.tolist() == [[37]]
+    assert custom.insert(VECTORS[:2]).tolist() == [19998, 19999]
+    custom.ef_search = 64
+    assert custom.ef_search == 64
+    assert index.search(QUERIES, 5)[0].shape == (50, 5)
+
+
+@pytest.mark.unit
+def test_delete_tombstones_ids():
+    index = HnswIndex(16, metric="euclidean")
+    index.insert(VECTORS)
+    assert index.delete(list(range(0, 2000, 2)) + [5000]) == 1000
+    assert len(index) == 1000 and 0 not in index and 1 in index
+    assert repr(index) == "HnswIndex(dim=16, metric='euclidean', len=1000, deleted=1000)"
+    ids, _ = index.search(VECTORS[:20], 5)
+    assert np.all(ids % 2 == 1)
+
+    # Deleted ids may be reused for new vectors.
+    index.insert(VECTORS[[1]], ids=[0])
+    found, distances = index.search(VECTORS[[1]], 2)
+    assert sorted(found[0].tolist()) == [0, 1]
+    np.testing.assert_allclose(distances[0], [0.0, 0.0], atol=1e-6)
+
+
+@pytest.mark.unit
+def test_search_walks_past_deleted_neighbors():
+    index = HnswIndex(16, metric="euclidean", ef_search=10)
+    index.insert(VECTORS)
+    query = QUERIES[:1]
+    nearest, _ = top_k(query, VECTORS, 50, "euclidean")
+    index.delete(nearest[0].tolist())
+    ids, distances = index.search(query, 10)
+    assert np.all(ids >= 0) and np.all(np.isfinite(distances))
+    assert not set(ids[0].tolist()) & set(nearest[0].tolist())
+    live = np.setdiff1d(np.arange(2000), nearest[0])
+    exact, _ = top_k(query, VECTORS[live], 10, "euclidean")
+    assert recall(ids, live[exact]) >= 0.8
+
+
+@pytest.mark.unit
+def test_save_and_load(index, tmp_path):
+    index.delete([3])
+    path = tmp_path / "index.hnsw"
+    index.save(str(path))
+    loaded = HnswIndex.load(str(path))
+    assert (loaded.dim, loaded.metric, len(loaded)) == (16, "euclidean", len(index))
+    assert 3 not in loaded
+    for expected, actual in zip(index.search(QUERIES, 8), loaded.search(QUERIES, 8)):
+        np.testing.assert_array_equal(expected, actual)
+
+    path.write_bytes(path.read_bytes()[:100])
+    with pytest.raises(ValueError, match="Truncated HNSW index data"):
+        HnswIndex.load(str(path))
+    path.write_bytes(b"not an index")
+    with pytest.raises(ValueError, match="Not an HNSW index file"):
+        HnswIndex.load(str(path))
+    with pytest.raises(IOError, match="Failed to read index file"):
+        HnswIndex.load(str(tmp_path / "missing.hnsw"))
+
+
+@pytest.mark.unit
+def test_short_results_and_invalid_arguments():
+    index = HnswIndex(4)
+    ids, distances = index.search(np.ones((2, 4)), 3)
+    assert ids.tolist() == [[-1, -1, -1]] * 2
+    assert np.all(np.isinf(distances))
+    index.insert([[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]])
+    ids, distances = index.search([[1.0, 0.1, 0.0, 0.0]], 3)
+    assert ids.tolist() == [[0, 1, -1]]
+    assert np.isinf(distances[0, 2])
+
+    with pytest.raises(ValueError, match="Id 1 is already in the index"):
+        index.insert([[1.0, 1.0, 1.0, 1.0]], ids=[1])
+    with pytest.raises(ValueError, match="Id 7 is given more than once"):
+        index.insert(np.ones((2, 4)), ids=[7, 7])
+    assert len(index) == 2
+    with pytest.raises(ValueError, match="Got 1 ids for 2 vectors"):
+        index.insert(np.ones((2, 4)), ids=[9])
+    with pytest.raises(ValueError, match="vectors must have 4 columns, got 3"):
+        index.insert(np.ones((2, 3)))
+    with pytest.raises(ValueError, match="queries must have 4 columns, got 5"):
+        index.search(np.ones((1, 5)), 1)
+    with pytest.raises(ValueError, match="k must be at least 1"):
+        index.search(np.ones((1, 4)), 0)
+    with pytest.raises(ValueError, match="m must be at least 2"):
+        HnswIndex(4, m=1)
+    with pytest.raises(ValueError, match="Unknown metric"):
+        HnswIndex(4, metric="jaccard")
@@ -0,0 +1,92 @@
+import numpy as np
+import pytest
+
+from swarmauri_distance_rust import BinaryQuantizer, ScalarQuantizer, pairwise_distances, top_k
+
+RNG = np.random.default_rng(3)
+VECTORS = RNG.standard_normal((1000, 37)).astype(np.float32)
+QUERIES = RNG.standard_normal((8, 37)).astype(np.float32)
+
+
+@pytest.mark.unit
+def test_scalar_round_trip():
+    quantizer = ScalarQuantizer.fit(VECTORS)
+    np.testing.assert_array_equal(quantizer.lower, VECTORS.min(axis=0))
+    np.testing.assert_array_equal(quantizer.upper, VECTORS.max(axis=0))
+    codes = quantizer.encode(VECTORS)
+    assert codes.dtype == np.int8 and codes.shape == (1000, 37)
+    assert codes.min() == -128 and codes.max() == 127
+    decoded = quantizer.decode(codes)
+    assert decoded.dtype == np.float32
+    half_step = (quantizer.upper - quantizer.lower) / 510
+    assert np.all(np.abs(decoded - VECTORS) <= half_step * 1.001 + 1e-6)
+
+
+@pytest.mark.unit
+def test_scalar_clamps_and_constant_dimensions():
+    quantizer = ScalarQuantizer([0.0, 1.0], [1.0, 1.0])
+    codes = quantizer.encode([[-5.0, 1.0], [0.6, 3.0], [9.0, 1.0]])
+    assert codes.tolist() == [[-128, -128], [25, -128], [127, -128]]
+    np.testing.assert_allclose(quantizer.decode(codes), [[0.0, 1.0], [0.6, 1.0], [1.0, 1.0]], rtol=1e-6)
+    assert repr(quantizer) == "ScalarQuantizer(dim=2)"
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("metric", ["cosine", "dot", "euclidean", "manhattan"])
+def test_scalar_asymmetric_distances(metric):
+    quantizer = ScalarQuantizer.fit(VECTORS)
+    codes = quantizer.encode(VECTORS)
+    expected = pairwise_distances(QUERIES, quantizer.decode(codes), metric)
+    got = quantizer.pairwise_distances(QUERIES, codes, metric)
+    assert got.shape == (8, 1000) and got.dtype == np.float32
+    np.testing.assert_allclose(got, expected, rtol=1e-4, atol=1e-4)
+    np.testing.assert_allclose(quantizer.distances(QUERIES[0], codes, metric), got[0])
+
+    # The nearest neighbors mostly survive quantization.
+    exact, _ = top_k(QUERIES, VECTORS, 10, metric)
+    approx = np.argsort(got, axis=1, kind="stable")[:, :10]
+    overlap = np.mean([len(set(a) & set(b)) / 10 for a, b in zip(exact, approx)])
+    assert overlap >= 0.8
+
+
+@pytest.mark.unit
+def test_binary_round_trip():
+    quantizer = BinaryQuantizer(37)
+    codes = quantizer.encode(VECTORS)
+    assert codes.dtype == np.uint8 and codes.shape == (1000, 5)
+    np.testing.assert_array_equal(codes, np.packbits(VECTORS > 0, axis=1))
+    decoded = quantizer.decode(codes)
+    np.testing.assert_array_equal(decoded, np.where(VECTORS > 0, 1.0, -1.0))
+    assert repr(quantizer) == "BinaryQuantizer(dim=37)"
+
+
+@pytest.mark.unit
+def test_binary_distances():
+    quantizer = BinaryQuantizer(37)
+    codes = quantizer.encode(VECTORS)
+    hamming = quantizer.pairwise_distances(QUERIES, codes)
+    expected = ((QUERIES[:, None, :] > 0) != (VECTORS[None, :, :] > 0)).sum(axis=2)
+    np.testing.assert_array_equal(hamming, expected)
+    np.testing.assert_array_equal(quantizer.distances(QUERIES[0], codes), expected[0])
+
+    cosine = quantizer.pairwise_distances(QUERIES, codes, "cosine")
+    np.testing.assert_allclose(cosine, pairwise_distances(QUERIES, quantizer.decode(codes)), rtol=1e-4, atol=1e-5)
+
+
+@pytest.mark.unit
+def test_invalid_arguments():
+    with pytest.raises(ValueError, match="lower must not be above upper"):
+        ScalarQuantizer([1.0], [0.0])
+    with pytest.raises(ValueError, match="same length"):
+        ScalarQuantizer([0.0, 0.0], [1.0])
+    with pytest.raises(ValueError, match="Cannot fit a quantizer to no vectors"):
+        ScalarQuantizer.fit(np.empty((0, 4)))
+    quantizer = ScalarQuantizer.fit(VECTORS)
+    with pytest.raises(ValueError, match="vectors must have 37 columns, got 4"):
+        quantizer.encode(np.ones((2, 4)))
+    with pytest.raises(ValueError, match="query must have 37 values, got 3"):
+        quantizer.distances([1.0, 2.0, 3.0], quantizer.encode(VECTORS))
+    with pytest.raises(ValueError, match="codes must have 5 columns, got 37"):
+        BinaryQuantizer(37).decode(np.zeros((1, 37), dtype=np.uint8))
+    with pytest.raises(ValueError, match="dim must be at least 1"):
+        BinaryQuantizer(0)
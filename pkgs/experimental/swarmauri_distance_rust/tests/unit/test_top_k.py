@@ -0,0 +1,60 @@
+import numpy as np
+import pytest
+
+from swarmauri_distance_rust import pairwise_distances, top_k
+
+METRICS = ["cosine", "dot", "euclidean", "manhattan", "hamming"]
+
+RNG = np.random.default_rng(1)
+QUERIES = RNG.standard_normal((40, 24))
+CORPUS = RNG.standard_normal((3000, 24))
+
+
+def expected(queries, corpus, k, metric):
+    distances = pairwise_distances(queries, corpus, metric)
+    indices = np.argsort(distances, axis=1, kind="stable")[:, :k]
+    return indices, np.take_along_axis(distances, indices, axis=1)
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("metric", METRICS)
+def test_matches_sorted_pairwise_distances(metric):
+    indices, scores = top_k(QUERIES, CORPUS, 10, metric)
+    assert indices.shape == scores.shape == (40, 10)
+    assert indices.dtype == np.int64
+    want_indices, want_scores = expected(QUERIES, CORPUS, 10, metric)
+    np.testing.assert_allclose(scores, want_scores)
+    if metric != "hamming":  # every row differs everywhere, so all tie
+        np.testing.assert_array_equal(indices, want_indices)
+
+
+@pytest.mark.unit
+def test_single_query_splits_the_corpus():
+    corpus = RNG.standard_normal((20_000, 16)).astype(np.float32)
+    indices, scores = top_k(corpus[[123]], corpus, 5, "euclidean")
+    assert scores.dtype == np.float32
+    assert indices[0, 0] == 123
+    assert scores[0, 0] == 0.0
+    want_indices, _ = expected(corpus[[123]], corpus, 5, "euclidean")
+    np.testing.assert_array_equal(indices, want_indices)
+
+
+@pytest.mark.unit
+def test_ties_go_to_the_lower_row():
+    corpus = np.array([[1.0, 0.0], [0.0, 1.0], [1.0, 0.0], [2.0, 0.0]])
+    indices, scores = top_k([[1.0, 0.0]], corpus, 3)
+    assert indices.tolist() == [[0, 2, 3]]
+    np.testing.assert_allclose(scores, [[0.0, 0.0, 0.0]], atol=1e-12)
+
+
+@pytest.mark.unit
+def test_small_corpus_and_invalid_arguments():
+    indices, scores = top_k(QUERIES[:2], CORPUS[:3], 10)
+    assert indices.shape == scores.shape == (2, 3)
+    assert top_k(np.empty((0, 24)), CORPUS, 4)[0].shape == (0, 4)
+    with pytest.raises(ValueError, match="k must be at least 1"):
+        top_k(QUERIES, CORPUS, 0)
+    with pytest.raises(ValueError, match="queries must be a 2-D array"):
+        top_k(QUERIES[0], CORPUS, 3)
+    with pytest.raises(ValueError, match="same dimensionality"):
+        top_k(QUERIES, CORPUS[:, :5], 3)
@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use crate::limit::Limit;
+
+/// A token bucket: up to `capacity` tokens, refilled continuously at
+/// `refill_rate` tokens per second. Bursts may spend the whole bucket at
+/// once, while the long-run rate cannot exceed the refill rate.
+#[derive(Debug)]
+pub struct Bucket {
+    pub capacity: f64,
+    pub refill_rate: f64,
+    tokens: f64,
+    /// When `tokens` was last brought up to date.
+    updated: Instant,
+}
+
+impl Bucket {
+    pub fn new(capacity: f64, refill_rate: f64, tokens: f64, now: Instant) -> Self {
+        Bucket {
+            capacity,
+            refill_rate,
+            tokens,
+            updated: now,
+        }
+    }
+
+    /// Add the tokens refilled since the last update.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.updated = now;
+    }
+
+    pub fn tokens(&mut self, now: Instant) -> f64 {
+        self.refill(now);
+        self.tokens
+    }
+
+    pub fn reset(&mut self, now: Instant) {
+        self.tokens = self.capacity;
+        self.updated = now;
+    }
+}
+
+impl Limit for Bucket {
+    fn max(&self) -> f64 {
+        self.capacity
+    }
+
+    fn wait(&mut self, n: u64, now: Instant) -> Duration {
+        self.refill(now);
+        let missing = n as f64 - self.tokens;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(missing / self.refill_rate).unwrap_or(Duration::MAX)
+        }
+    }
+
+    fn consume(&mut self, n: u64, _now: Instant) {
+        self.tokens -= n as f64;
+    }
+}
@@ -0,0 +1,17 @@
+use pyo3::prelude::*;
+
+mod bucket;
+mod limit;
+mod limiters;
+mod window;
+
+use limiters::{SlidingWindow, TokenBucket};
+
+/// A Python module implemented in Rust.
+#[pymodule(gil_used = false)]
+fn swarmauri_ratelimit_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Register classes
+    m.add_class::<SlidingWindow>()?;
+    m.add_class::<TokenBucket>()?;
+    Ok(())
+}
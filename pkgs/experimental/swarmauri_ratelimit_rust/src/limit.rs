@@ -0,0 +1,28 @@
+use std::time::{Duration, Instant};
+
+/// The state of a rate limiter, brought up to date with the time it is
+/// given. Callers keep it behind a lock and read the clock while holding
+/// it, so that times only move forward.
+pub trait Limit {
+    /// The most units one acquisition may take.
+    fn max(&self) -> f64;
+
+    /// How long until `n` units can be acquired, zero when they can now.
+    /// `n` must not exceed `max`.
+    fn wait(&mut self, n: u64, now: Instant) -> Duration;
+
+    /// Take `n` units, after `wait` returned zero for them.
+    fn consume(&mut self, n: u64, now: Instant);
+
+    /// Take `n` units if they can be acquired now, and otherwise return how
+    /// long until they can.
+    fn try_take(&mut self, n: u64, now: Instant) -> Result<(), Duration> {
+        match self.wait(n, now) {
+            Duration::ZERO => {
+                self.consume(n, now);
+                Ok(())
+            }
+            wait => Err(wait),
+        }
+    }
+}
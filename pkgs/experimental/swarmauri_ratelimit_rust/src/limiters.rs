@@ -0,0 +1,286 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bucket::Bucket;
+use crate::limit::Limit;
+use crate::window::Window;
+
+/// The longest a blocked `acquire` sleeps between checks for
+/// `KeyboardInterrupt` and other signals.
+const SIGNAL_CHECK: Duration = Duration::from_millis(100);
+
+/// A duration of `seconds`, or `None` when it is negative, NaN or too long
+/// to represent.
+fn seconds(seconds: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+fn check<L: Limit>(limit: &L, n: u64) -> PyResult<()> {
+    if n as f64 > limit.max() {
+        return Err(PyValueError::new_err(format!(
+            "Cannot acquire {} at once from a limiter that allows at most {}",
+            n,
+            limit.max()
+        )));
+    }
+    Ok(())
+}
+
+fn try_acquire<L: Limit>(state: &Mutex<L>, n: u64) -> PyResult<bool> {
+    let mut state = state.lock().unwrap();
+    check(&*state, n)?;
+    Ok(state.try_take(n, Instant::now()).is_ok())
+}
+
+/// Wait without the GIL until `n` units are acquired, or until `timeout`
+/// seconds have passed.
+fn acquire<L: Limit + Send>(
+    py: Python<'_>,
+    state: &Mutex<L>,
+    n: u64,
+    timeout: Option<f64>,
+) -> PyResult<bool> {
+    check(&*state.lock().unwrap(), n)?;
+    let timeout = match timeout {
+        Some(timeout) => Some(seconds(timeout).ok_or_else(|| {
+            PyValueError::new_err("timeout must be a non-negative number of seconds")
+        })?),
+        None => None,
+    };
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    py.detach(|| loop {
+        let wait = match state.lock().unwrap().try_take(n, Instant::now()) {
+            Ok(()) => return Ok(true),
+            Err(wait) => wait,
+        };
+        // Other callers can only make the wait longer, so there is no
+        // point sleeping past the deadline.
+        let ready = Instant::now().checked_add(wait);
+        if deadline.is_some_and(|deadline| ready.is_none_or(|ready| ready > deadline)) {
+            return Ok(false);
+        }
+        thread::sleep(wait.min(SIGNAL_CHECK));
+        Python::attach(|py| py.check_signals())?;
+    })
+}
+
+fn wait_time<L: Limit>(state: &Mutex<L>, n: u64) -> PyResult<f64> {
+    let mut state = state.lock().unwrap();
+    check(&*state, n)?;
+    Ok(state.wait(n, Instant::now()).as_secs_f64())
+}
+
+/// A thread-safe token bucket rate limiter.
+///
+/// The bucket holds up to `capacity` tokens and refills continuously at
+/// `refill_rate` tokens per second, so bursts may spend the whole bucket at
+/// once while the long-run rate cannot exceed `refill_rate`. Time is read
+/// from a monotonic clock, so changes to the system clock do not affect it.
+///
+/// A limiter can be shared between threads, including on free-threaded
+/// Python; `acquire` waits without holding the GIL.
+///
+/// Args:
+///     capacity (float): The most tokens the bucket holds.
+///     refill_rate (float): Tokens added per second.
+///     tokens (Optional[float]): The tokens to start with, a full bucket by
+///         default.
+#[pyclass(frozen, skip_from_py_object, module = "swarmauri_ratelimit_rust")]
+pub struct TokenBucket {
+    state: Mutex<Bucket>,
+}
+
+#[pymethods]
+impl TokenBucket {
+    #[new]
+    #[pyo3(signature = (capacity, refill_rate, tokens=None))]
+    fn new(capacity: f64, refill_rate: f64, tokens: Option<f64>) -> PyResult<Self> {
+        if !(capacity.is_finite() && capacity > 0.0) {
+            return Err(PyValueError::new_err("capacity must be a positive number"));
+        }
+        if !(refill_rate.is_finite() && refill_rate > 0.0) {
+            return Err(PyValueError::new_err(
+                "refill_rate must be a positive number",
+            ));
+        }
+        let tokens = tokens.unwrap_or(capacity);
+        if !(0.0..=capacity).contains(&tokens) {
+            return Err(PyValueError::new_err(
+                "tokens must be between 0 and capacity",
+            ));
+        }
+        Ok(TokenBucket {
+            state: Mutex::new(Bucket::new(capacity, refill_rate, tokens, Instant::now())),
+        })
+    }
+
+    /// Take `n` tokens if the bucket holds them, without waiting.
+    ///
+    /// Returns:
+    ///     bool: Whether the tokens were taken. Either all `n` are taken or
+    ///     none.
+    #[pyo3(signature = (n=1))]
+    fn try_acquire(&self, n: u64) -> PyResult<bool> {
+        try_acquire(&self.state, n)
+    }
+
+    /// Take `n` tokens, waiting for the bucket to refill if needed.
+    ///
+    /// Args:
+    ///     n (int): Tokens to take, at most `capacity`.
+    ///     timeout (Optional[float]): The most seconds to wait. By default
+    ///         waits as long as it takes.
+    ///
+    /// Returns:
+    ///     bool: Whether the tokens were taken. Gives up at once, returning
+    ///     `False`, when they cannot refill before the timeout.
+    #[pyo3(signature = (n=1, timeout=None))]
+    fn acquire(&self, py: Python<'_>, n: u64, timeout: Option<f64>) -> PyResult<bool> {
+        acquire(py, &self.state, n, timeout)
+    }
+
+    /// Seconds until `n` tokens are available, 0 when they are now. Other
+    /// callers may take them first, so `try_acquire` can still fail then.
+    #[pyo3(signature = (n=1))]
+    fn wait_time(&self, n: u64) -> PyResult<f64> {
+        wait_time(&self.state, n)
+    }
+
+    /// Fill the bucket.
+    fn reset(&self) {
+        self.state.lock().unwrap().reset(Instant::now());
+    }
+
+    /// The tokens in the bucket, a fraction while it refills.
+    #[getter]
+    fn tokens(&self) -> f64 {
+        self.state.lock().unwrap().tokens(Instant::now())
+    }
+
+    #[getter]
+    fn capacity(&self) -> f64 {
+        self.state.lock().unwrap().capacity
+    }
+
+    #[getter]
+    fn refill_rate(&self) -> f64 {
+        self.state.lock().unwrap().refill_rate
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.state.lock().unwrap();
+        format!(
+            "TokenBucket(capacity={:?}, refill_rate={:?})",
+            state.capacity, state.refill_rate
+        )
+    }
+}
+
+/// A thread-safe sliding window rate limiter.
+///
+/// Allows at most `limit` units in any `window` seconds, counted exactly
+/// from the acquisitions still inside the window, so that unlike a fixed
+/// window it never lets twice the limit through around a window boundary.
+/// It keeps one entry per acquisition in the window. Time is read from a
+/// monotonic clock, so changes to the system clock do not affect it.
+///
+/// A limiter can be shared between threads, including on free-threaded
+/// Python; `acquire` waits without holding the GIL.
+///
+/// Args:
+///     limit (int): The most units per window.
+///     window (float): The length of the window in seconds.
+#[pyclass(frozen, skip_from_py_object, module = "swarmauri_ratelimit_rust")]
+pub struct SlidingWindow {
+    state: Mutex<Window>,
+}
+
+#[pymethods]
+impl SlidingWindow {
+    #[new]
+    fn new(limit: u64, window: f64) -> PyResult<Self> {
+        if limit == 0 {
+            return Err(PyValueError::new_err("limit must be at least 1"));
+        }
+        let window = seconds(window)
+            .filter(|window| !window.is_zero())
+            .ok_or_else(|| PyValueError::new_err("window must be a positive number of seconds"))?;
+        Ok(SlidingWindow {
+            state: Mutex::new(Window::new(limit, window)),
+        })
+    }
+
+    /// Take `n` units if the window has room for them, without waiting.
+    ///
+    /// Returns:
+    ///     bool: Whether the units were taken. Either all `n` are taken or
+    ///     none.
+    #[pyo3(signature = (n=1))]
+    fn try_acquire(&self, n: u64) -> PyResult<bool> {
+        try_acquire(&self.state, n)
+    }
+
+    /// Take `n` units, waiting for earlier ones to leave the window if
+    /// needed.
+    ///
+    /// Args:
+    ///     n (int): Units to take, at most `limit`.
+    ///     timeout (Optional[float]): The most seconds to wait. By default
+    ///         waits as long as it takes.
+    ///
+    /// Returns:
+    ///     bool: Whether the units were taken. Gives up at once, returning
+    ///     `False`, when there cannot be room before the timeout.
+    #[pyo3(signature = (n=1, timeout=None))]
+    fn acquire(&self, py: Python<'_>, n: u64, timeout: Option<f64>) -> PyResult<bool> {
+        acquire(py, &self.state, n, timeout)
+    }
+
+    /// Seconds until there is room for `n` units, 0 when there is now.
+    /// Other callers may take it first, so `try_acquire` can still fail
+    /// then.
+    #[pyo3(signature = (n=1))]
+    fn wait_time(&self, n: u64) -> PyResult<f64> {
+        wait_time(&self.state, n)
+    }
+
+    /// Forget every acquisition in the window.
+    fn reset(&self) {
+        self.state.lock().unwrap().reset();
+    }
+
+    /// The units acquired within the last `window` seconds.
+    #[getter]
+    fn used(&self) -> u64 {
+        self.state.lock().unwrap().used(Instant::now())
+    }
+
+    /// The units that can be acquired now.
+    #[getter]
+    fn available(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.limit - state.used(Instant::now())
+    }
+
+    #[getter]
+    fn limit(&self) -> u64 {
+        self.state.lock().unwrap().limit
+    }
+
+    #[getter]
+    fn window(&self) -> f64 {
+        self.state.lock().unwrap().window.as_secs_f64()
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.state.lock().unwrap();
+        format!(
+            "SlidingWindow(limit={}, window={:?})",
+            state.limit,
+            state.window.as_secs_f64()
+        )
+    }
+}
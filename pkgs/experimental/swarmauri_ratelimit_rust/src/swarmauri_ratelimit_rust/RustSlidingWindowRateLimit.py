@@ -0,0 +1,50 @@
+from typing import Any, Literal, Optional
+
+from pydantic import PrivateAttr
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.rate_limits.RateLimitBase import RateLimitBase
+
+from .swarmauri_ratelimit_rust import SlidingWindow
+
+
+@ComponentBase.register_type(RateLimitBase, "RustSlidingWindowRateLimit")
+class RustSlidingWindowRateLimit(RateLimitBase):
+    """
+    Sliding window rate limit backed by the Rust `SlidingWindow`.
+
+    Allows at most `capacity` tokens in any `window` seconds, for provider
+    limits stated per minute or per second. `refill_rate` is not used.
+    The limit can be shared between threads, and `acquire` waits for room
+    without holding the GIL.
+    """
+
+    window: float = 1.0
+    type: Literal["RustSlidingWindowRateLimit"] = (
+        "RustSlidingWindowRateLimit"
+    )
+    _window: SlidingWindow = PrivateAttr()
+
+    def model_post_init(self, __context: Any) -> None:
+        super().model_post_init(__context)
+        self._window = SlidingWindow(self.capacity, self.window)
+
+    def allow(self, tokens: int = 1) -> bool:
+        if tokens > self.capacity:
+            return False
+        return self._window.try_acquire(tokens)
+
+    def acquire(
+        self, tokens: int = 1, timeout: Optional[float] = None
+    ) -> bool:
+        """Take `tokens`, waiting up to `timeout` seconds for room."""
+        return self._window.acquire(tokens, timeout)
+
+    def wait_time(self, tokens: int = 1) -> float:
+        """Seconds until there is room for `tokens`, 0 when there is now."""
+        return self._window.wait_time(tokens)
+
+    def available_tokens(self) -> int:
+        return self._window.available
+
+    def refill(self) -> None:
+        """Tokens leave the window as time passes; there is nothing to do."""
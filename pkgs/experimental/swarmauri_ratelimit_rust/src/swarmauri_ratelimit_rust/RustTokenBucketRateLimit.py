@@ -0,0 +1,47 @@
+from typing import Any, Literal, Optional
+
+from pydantic import PrivateAttr
+from swarmauri_base.ComponentBase import ComponentBase
+from swarmauri_base.rate_limits.RateLimitBase import RateLimitBase
+
+from .swarmauri_ratelimit_rust import TokenBucket
+
+
+@ComponentBase.register_type(RateLimitBase, "RustTokenBucketRateLimit")
+class RustTokenBucketRateLimit(RateLimitBase):
+    """
+    Token bucket rate limit backed by the Rust `TokenBucket`.
+
+    A drop-in replacement for `TokenBucketRateLimit` that can be shared
+    between threads: the bucket starts full with `capacity` tokens and
+    refills `refill_rate` tokens per second on a monotonic clock.
+    `acquire` waits for tokens without holding the GIL.
+    """
+
+    type: Literal["RustTokenBucketRateLimit"] = "RustTokenBucketRateLimit"
+    _bucket: TokenBucket = PrivateAttr()
+
+    def model_post_init(self, __context: Any) -> None:
+        super().model_post_init(__context)
+        self._bucket = TokenBucket(self.capacity, self.refill_rate)
+
+    def allow(self, tokens: int = 1) -> bool:
+        if tokens > self.capacity:
+            return False
+        return self._bucket.try_acquire(tokens)
+
+    def acquire(
+        self, tokens: int = 1, timeout: Optional[float] = None
+    ) -> bool:
+        """Take `tokens`, waiting up to `timeout` seconds for them."""
+        return self._bucket.acquire(tokens, timeout)
+
+    def wait_time(self, tokens: int = 1) -> float:
+        """Seconds until `tokens` are available, 0 when they are now."""
+        return self._bucket.wait_time(tokens)
+
+    def available_tokens(self) -> int:
+        return int(self._bucket.tokens)
+
+    def refill(self) -> None:
+        """The bucket refills as time passes; there is nothing to do."""
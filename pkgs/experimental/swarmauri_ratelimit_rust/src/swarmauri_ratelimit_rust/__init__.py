@@ -0,0 +1,13 @@
+# Import all symbols from the Rust extension
+from .swarmauri_ratelimit_rust import *  # noqa: F403
+
+# Swarmauri rate limit components backed by the Rust limiters
+from .RustSlidingWindowRateLimit import RustSlidingWindowRateLimit
+from .RustTokenBucketRateLimit import RustTokenBucketRateLimit
+
+__all__ = [
+    "SlidingWindow",
+    "TokenBucket",
+    "RustSlidingWindowRateLimit",
+    "RustTokenBucketRateLimit",
+]
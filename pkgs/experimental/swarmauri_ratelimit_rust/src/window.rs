@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::limit::Limit;
+
+/// A sliding window: at most `limit` units in any `window` of time, counted
+/// exactly from a log of the acquisitions still inside it. Unlike a fixed
+/// window, it never lets twice the limit through around a window boundary.
+#[derive(Debug)]
+pub struct Window {
+    pub limit: u64,
+    pub window: Duration,
+    /// When each acquisition in the window happened and how many units it
+    /// took, oldest first.
+    log: VecDeque<(Instant, u64)>,
+    /// The units in `log`.
+    used: u64,
+}
+
+impl Window {
+    pub fn new(limit: u64, window: Duration) -> Self {
+        Window {
+            limit,
+            window,
+            log: VecDeque::new(),
+            used: 0,
+        }
+    }
+
+    /// Drop the acquisitions that have left the window.
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(at, n)) = self.log.front() {
+            if now.saturating_duration_since(at) < self.window {
+                break;
+            }
+            self.log.pop_front();
+            self.used -= n;
+        }
+    }
+
+    /// The units acquired within the window.
+    pub fn used(&mut self, now: Instant) -> u64 {
+        self.expire(now);
+        self.used
+    }
+
+    pub fn reset(&mut self) {
+        self.log.clear();
+        self.used = 0;
+    }
+}
+
+impl Limit for Window {
+    fn max(&self) -> f64 {
+        self.limit as f64
+    }
+
+    fn wait(&mut self, n: u64, now: Instant) -> Duration {
+        self.expire(now);
+        // Units have to leave the window, oldest first, until `n` fit.
+        let mut excess = (self.used + n).saturating_sub(self.limit);
+        for &(at, taken) in &self.log {
+            if excess == 0 {
+                break;
+            }
+            excess = excess.saturating_sub(taken);
+            if excess == 0 {
+                return (at + self.window).saturating_duration_since(now);
+            }
+        }
+        Duration::ZERO
+    }
+
+    fn consume(&mut self, n: u64, now: Instant) {
+        self.log.push_back((now, n));
+        self.used += n;
+    }
+}
@@ -0,0 +1,41 @@
+import time
+
+import pytest
+from swarmauri_base.ComponentBase import ResourceTypes
+from swarmauri_standard.rate_limits.TokenBucketRateLimit import TokenBucketRateLimit
+
+from swarmauri_ratelimit_rust import RustSlidingWindowRateLimit, RustTokenBucketRateLimit
+
+
+@pytest.mark.unit
+@pytest.mark.parametrize("component", [RustSlidingWindowRateLimit, RustTokenBucketRateLimit])
+def test_component(component):
+    rate_limit = component(capacity=2)
+    assert rate_limit.type == component.__name__
+    assert rate_limit.resource == ResourceTypes.RATE_LIMIT.value
+    restored = component.model_validate_json(rate_limit.model_dump_json())
+    assert restored.id == rate_limit.id
+    assert restored.allow(2)
+
+
+@pytest.mark.unit
+def test_token_bucket_matches_standard():
+    for component in (TokenBucketRateLimit, RustTokenBucketRateLimit):
+        rate_limit = component(capacity=2, refill_rate=10)
+        assert rate_limit.allow()
+        assert rate_limit.available_tokens() <= 1
+        assert not rate_limit.allow(2)
+        assert not rate_limit.allow(3)
+        time.sleep(0.15)
+        assert rate_limit.allow(2)
+
+
+@pytest.mark.unit
+def test_sliding_window():
+    rate_limit = RustSlidingWindowRateLimit(capacity=3, window=0.2)
+    assert rate_limit.allow(2)
+    assert rate_limit.available_tokens() == 1
+    assert not rate_limit.allow(2)
+    assert rate_limit.wait_time(2) > 0.1
+    assert rate_limit.acquire(2, timeout=1.0)
+    assert not rate_limit.allow(4)
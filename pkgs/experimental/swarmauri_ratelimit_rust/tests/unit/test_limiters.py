@@ -0,0 +1,113 @@
+import threading
+import time
+
+import pytest
+
+from swarmauri_ratelimit_rust import SlidingWindow, TokenBucket
+
+
+@pytest.mark.unit
+class TestTokenBucket:
+    """Unit tests for the Rust token bucket."""
+
+    def test_batch_try_acquire(self):
+        bucket = TokenBucket(10, 5)
+        assert bucket.try_acquire(7)
+        assert not bucket.try_acquire(4)
+        assert bucket.tokens == pytest.approx(3, abs=0.1)
+        assert bucket.try_acquire(3)
+        assert not bucket.try_acquire()
+        assert bucket.wait_time(2) == pytest.approx(0.4, abs=0.1)
+        bucket.reset()
+        assert bucket.tokens == 10
+        assert repr(bucket) == "TokenBucket(capacity=10.0, refill_rate=5.0)"
+
+    def test_refills_over_time(self):
+        bucket = TokenBucket(2, 20, tokens=0)
+        assert not bucket.try_acquire()
+        time.sleep(0.15)
+        assert bucket.tokens == 2
+        assert bucket.try_acquire(2)
+
+    def test_acquire_waits_for_tokens(self):
+        bucket = TokenBucket(5, 10, tokens=0)
+        start = time.monotonic()
+        assert bucket.acquire(3)
+        assert time.monotonic() - start >= 0.25
+        assert bucket.wait_time(5) > 0.3
+        assert not bucket.acquire(5, timeout=0.1)
+        assert bucket.acquire(5, timeout=2.0)
+
+    def test_threads_share_the_bucket(self):
+        bucket = TokenBucket(100, 0.001)
+        taken = []
+
+        def worker():
+            taken.extend(bucket.try_acquire() for _ in range(50))
+
+        threads = [threading.Thread(target=worker) for _ in range(8)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        assert sum(taken) == 100
+
+    def test_invalid_arguments(self):
+        with pytest.raises(ValueError, match="capacity must be a positive number"):
+            TokenBucket(0, 1)
+        with pytest.raises(ValueError, match="refill_rate must be a positive number"):
+            TokenBucket(1, 0)
+        with pytest.raises(ValueError, match="tokens must be between 0 and capacity"):
+            TokenBucket(1, 1, tokens=2)
+        bucket = TokenBucket(5, 1)
+        with pytest.raises(ValueError, match="Cannot acquire 6 at once"):
+            bucket.try_acquire(6)
+        with pytest.raises(ValueError, match="Cannot acquire 6 at once"):
+            bucket.acquire(6)
+        with pytest.raises(ValueError, match="timeout must be a non-negative number"):
+            bucket.acquire(1, timeout=-1)
+
+
+@pytest.mark.unit
+class TestSlidingWindow:
+    """Unit tests for the Rust sliding window."""
+
+    def test_batch_try_acquire(self):
+        window = SlidingWindow(5, 60)
+        assert window.try_acquire(3)
+        assert not window.try_acquire(3)
+        assert window.try_acquire(2)
+        assert (window.used, window.available) == (5, 0)
+        assert window.wait_time() == pytest.approx(60, abs=1)
+        window.reset()
+        assert window.available == 5
+        assert repr(window) == "SlidingWindow(limit=5, window=60.0)"
+
+    def test_units_leave_the_window(self):
+        window = SlidingWindow(3, 0.2)
+        assert window.try_acquire(2)
+        time.sleep(0.1)
+        assert window.try_acquire()
+        assert not window.try_acquire()
+        # The first two leave before the third.
+        assert window.wait_time(2) == pytest.approx(0.1, abs=0.05)
+        assert window.wait_time(3) == pytest.approx(0.2, abs=0.05)
+        time.sleep(0.12)
+        assert window.used == 1
+        assert window.try_acquire(2)
+
+    def test_acquire_waits_for_room(self):
+        window = SlidingWindow(2, 0.3)
+        assert window.acquire(2)
+        start = time.monotonic()
+        assert not window.acquire(timeout=0.1)
+        assert window.acquire(timeout=1.0)
+        assert time.monotonic() - start >= 0.25
+
+    def test_invalid_arguments(self):
+        with pytest.raises(ValueError, match="limit must be at least 1"):
+            SlidingWindow(0, 1)
+        with pytest.raises(ValueError, match="window must be a positive number"):
+            SlidingWindow(1, 0)
+        with pytest.raises(ValueError, match="Cannot acquire 3 at once"):
+            SlidingWindow(2, 1).wait_time(3)
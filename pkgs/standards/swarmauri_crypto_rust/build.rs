@@ -0,0 +1,111 @@
+//! Records the versions of the crypto crates in `Cargo.lock`, so that
+//! `get_version_info` reports the libraries this build actually links.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// One `[[package]]` entry of `Cargo.lock`.
+#[derive(Default)]
+struct Package {
+    name: String,
+    version: String,
+    dependencies: Vec<String>,
+}
+
+fn main() {
+    // Cargo writes the lock file before running build scripts.
+    let lock = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock.display());
+    let text =
+        fs::read_to_string(&lock).unwrap_or_else(|e| panic!("cannot read {}: {e}", lock.display()));
+    let packages = parse_lock(&text);
+    for (feature, name, var) in [
+        ("RING", "ring", "RING_VERSION"),
+        ("RUSTCRYPTO", "chacha20poly1305", "CHACHA20POLY1305_VERSION"),
+    ] {
+        if env::var_os(format!("CARGO_FEATURE_{feature}")).is_none() {
+            continue;
+        }
+        let version = locked_version(&packages, name)
+            .unwrap_or_else(|| panic!("{} does not pin a version of {name}", lock.display()));
+        println!("cargo:rustc-env={var}={version}");
+    }
+}
+
+/// The packages of a `Cargo.lock` file.
+fn parse_lock(text: &str) -> Vec<Package> {
+    let mut packages: Vec<Package> = Vec::new();
+    let mut in_dependencies = false;
+    for line in text.lines().map(str::trim) {
+        if line == "[[package]]" {
+            packages.push(Package::default());
+            in_dependencies = false;
+            continue;
+        }
+        let Some(package) = packages.last_mut() else {
+            continue;
+        };
+        if line.starts_with('[') && !in_dependencies {
+            // Another table, such as `[metadata]`, ends the package list.
+            break;
+        }
+        if in_dependencies {
+            if line.starts_with(']') {
+                in_dependencies = false;
+            } else {
+                package
+                    .dependencies
+                    .push(unquote(line.trim_end_matches(',')));
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "name" => package.name = unquote(value),
+            "version" => package.version = unquote(value),
+            "dependencies" => {
+                let items = value.trim_start_matches('[');
+                in_dependencies = !items.ends_with(']');
+                package.dependencies.extend(
+                    items
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(unquote),
+                );
+            }
+            _ => {}
+        }
+    }
+    packages
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').to_string()
+}
+
+/// The locked version of `name` that this package depends on. The lock
+/// names the version in the dependency list when several are locked.
+fn locked_version<'a>(packages: &'a [Package], name: &str) -> Option<&'a str> {
+    let this = env::var("CARGO_PKG_NAME").unwrap();
+    let root = packages.iter().find(|package| package.name == this)?;
+    let dependency = root
+        .dependencies
+        .iter()
+        .map(|dependency| dependency.split_whitespace().collect::<Vec<_>>())
+        .find(|parts| parts.first() == Some(&name))?;
+    let mut versions = packages
+        .iter()
+        .filter(|package| package.name == name)
+        .map(|package| package.version.as_str())
+        .filter(|version| dependency.get(1).is_none_or(|wanted| wanted == version));
+    match (versions.next(), versions.next()) {
+        (Some(version), None) => Some(version),
+        _ => None,
+    }
+}
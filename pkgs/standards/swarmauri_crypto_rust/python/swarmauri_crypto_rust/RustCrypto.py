@@ -1,7 +1,7 @@
 """Rust-backed crypto provider using ring and maturin.
 
 Implements the ICrypto contract using:
-- ChaCha20-Poly1305 for symmetric AEAD
+- ChaCha20-Poly1305 or AES-256-GCM for symmetric AEAD
 - X25519 for key agreement (simplified implementation)
 - High-performance Rust backend via ring crate
 
@@ -39,6 +39,10 @@ try:
         AEADCiphertext as _RustAEADCiphertext,
         WrappedKey as _RustWrappedKey,
         KeyRef as _RustKeyRef,
+        available_backends as _available_backends,
+        metrics_snapshot as _metrics_snapshot,
+        reset_metrics as _reset_metrics,
+        set_metrics_enabled as _set_metrics_enabled,
     )
 
     _RUST_AVAILABLE = True
@@ -110,10 +114,25 @@ def _convert_core_to_rust_ciphertext(
     )
 
 
+def available_backends() -> list[str]:
+    """Names of the AEAD backends compiled into the Rust extension."""
+    if not _RUST_AVAILABLE:
+        return []
+    return list(_available_backends())
+
+
 class RustCrypto(CryptoBase):
-    """Rust-backed crypto provider using ring crate."""
+    """Rust-backed crypto provider.
+
+    The AEAD primitive is provided by ``ring`` by default; builds may also
+    compile in ``aws-lc-rs`` (for FIPS) or the pure-Rust ``rustcrypto``
+    crates, selectable through ``backend``. Each backend encrypts with its
+    first supported algorithm unless ``alg`` is given: ChaCha20-Poly1305
+    for ``ring`` and ``rustcrypto``, AES-256-GCM for ``aws-lc-rs``.
+    """
 
     type: Literal["RustCrypto"] = "RustCrypto"
+    backend: Optional[str] = None
 
     def __init__(self, **kwargs):
         super().__init__(**kwargs)
@@ -122,7 +141,9 @@ class RustCrypto(CryptoBase):
                 "Rust crypto backend is not available. "
                 "Please ensure the package was built with maturin."
             )
-        self._rust_crypto = _RustCrypto()
+        self._rust_crypto = _RustCrypto(self.backend)
+        self.backend = self._rust_crypto.backend
+        self._aead_algs = tuple(self._rust_crypto.supports()["encrypt"])
 
     # ---------------- capabilities ----------------
     def supports(self) -> Dict[str, Iterable[Alg]]:
@@ -132,7 +153,7 @@ class RustCrypto(CryptoBase):
         rust_supports = self._rust_crypto.supports()
         return {key: tuple(algs) for key, algs in rust_supports.items()}
 
-    # ---------------- AEAD: ChaCha20-Poly1305 ----------------
+    # ---------------- AEAD: ChaCha20-Poly1305 / AES-256-GCM ----------------
     async def encrypt(
         self,
         key: KeyRef,
@@ -142,8 +163,8 @@ class RustCrypto(CryptoBase):
         aad: Optional[bytes] = None,
         nonce: Optional[bytes] = None,
     ) -> CoreAEADCiphertext:
-        alg = alg or _AEAD_DEFAULT
-        if alg != _AEAD_DEFAULT:
+        alg = alg or self._aead_algs[0]
+        if alg not in self._aead_algs:
             raise UnsupportedAlgorithm(f"Unsupported AEAD algorithm: {alg}")
 
         if not key.material or len(key.material) != 32:
@@ -151,7 +172,7 @@ class RustCrypto(CryptoBase):
 
         try:
             rust_key = _convert_key_to_rust(key)
-            rust_ct = self._rust_crypto.encrypt(rust_key, pt, nonce, aad)
+            rust_ct = self._rust_crypto.encrypt(rust_key, pt, nonce, aad, alg)
             return _convert_rust_to_core_ciphertext(rust_ct)
         except Exception as e:
             raise IntegrityError(f"Encryption failed: {e}")
@@ -163,7 +184,7 @@ class RustCrypto(CryptoBase):
         *,
         aad: Optional[bytes] = None,
     ) -> bytes:
-        if ct.alg != _AEAD_DEFAULT:
+        if ct.alg not in self._aead_algs:
             raise UnsupportedAlgorithm(f"Unsupported AEAD algorithm: {ct.alg}")
 
         if not key.material or len(key.material) != 32:
@@ -244,6 +265,7 @@ class RustCrypto(CryptoBase):
             import json
 
             sealed_data = {
+                "alg": ct.alg,
                 "nonce": list(ct.nonce),
                 "ct": list(ct.ct),
                 "tag": list(ct.tag),
@@ -271,7 +293,7 @@ class RustCrypto(CryptoBase):
             ct = CoreAEADCiphertext(
                 kid=recipient_priv.kid,
                 version=recipient_priv.version,
-                alg=_AEAD_DEFAULT,
+                alg=sealed_data.get("alg", _AEAD_DEFAULT),
                 nonce=bytes(sealed_data["nonce"]),
                 ct=bytes(sealed_data["ct"]),
                 tag=bytes(sealed_data["tag"]),
@@ -293,7 +315,7 @@ class RustCrypto(CryptoBase):
         nonce: Optional[bytes] = None,
     ) -> MultiRecipientEnvelope:
         # Simplified implementation using seal for each recipient
-        enc_alg = enc_alg or _AEAD_DEFAULT
+        enc_alg = enc_alg or self._aead_algs[0]
 
         if enc_alg == _SEAL_ALG:
             # Sealed-style variant
@@ -319,7 +341,7 @@ class RustCrypto(CryptoBase):
             )
 
         # KEM+AEAD variant (simplified)
-        if enc_alg != _AEAD_DEFAULT:
+        if enc_alg not in self._aead_algs:
             raise UnsupportedAlgorithm(f"Unsupported enc_alg: {enc_alg}")
 
         # Generate content encryption key
@@ -335,7 +357,7 @@ class RustCrypto(CryptoBase):
 
         # Encrypt the content
         aead_ct = await self.encrypt(
-            cek, pt, alg=_AEAD_DEFAULT, aad=aad, nonce=nonce
+            cek, pt, alg=enc_alg, aad=aad, nonce=nonce
         )
 
         # Wrap the CEK for each recipient
@@ -353,7 +375,7 @@ class RustCrypto(CryptoBase):
             )
 
         return MultiRecipientEnvelope(
-            enc_alg=_AEAD_DEFAULT,
+            enc_alg=enc_alg,
             nonce=aead_ct.nonce,
             ct=aead_ct.ct,
             tag=aead_ct.tag,
@@ -374,3 +396,28 @@ class RustCrypto(CryptoBase):
             return bytes(self._rust_crypto.generate_key(size))
         else:
             return secrets.token_bytes(size)
+
+    # ---------------- metrics ----------------
+    def enable_metrics(self, enabled: bool = True) -> None:
+        """Turn per-call timing and payload-size metrics on or off.
+
+        Metrics are collected process-wide by the Rust extension for the
+        encrypt, decrypt, wrap and unwrap operations.
+        """
+        if _RUST_AVAILABLE:
+            _set_metrics_enabled(enabled)
+
+    def metrics_snapshot(self) -> Dict[str, Dict[str, object]]:
+        """Return call counts plus duration and payload-size histograms.
+
+        Histogram buckets are cumulative ``(le, count)`` pairs, matching the
+        Prometheus exposition model.
+        """
+        if not _RUST_AVAILABLE:
+            return {}
+        return _metrics_snapshot()
+
+    def reset_metrics(self) -> None:
+        """Discard all metrics collected so far."""
+        if _RUST_AVAILABLE:
+            _reset_metrics()
@@ -1,3 +1,3 @@
-from .RustCrypto import RustCrypto
+from .RustCrypto import RustCrypto, available_backends
 
-__all__ = ["RustCrypto"]
+__all__ = ["RustCrypto", "available_backends"]
@@ -0,0 +1,83 @@
+use super::{AeadBackend, Algorithm, BackendError, KEY_LEN, NONCE_LEN, TAG_LEN};
+use aws_lc_rs::rand::SecureRandom;
+use aws_lc_rs::{aead, rand as aws_rand};
+
+/// Backend built on `aws-lc-rs` linked against the AWS-LC FIPS module. Only
+/// FIPS-approved algorithms are offered, so it has no ChaCha20-Poly1305.
+pub struct AwsLcBackend;
+
+impl AwsLcBackend {
+    fn key(alg: Algorithm, key: &[u8; KEY_LEN]) -> Result<aead::LessSafeKey, BackendError> {
+        let alg = match alg {
+            Algorithm::Aes256Gcm => &aead::AES_256_GCM,
+            other => {
+                return Err(BackendError::Unsupported(
+                    other.name().to_string(),
+                    "aws-lc-rs",
+                ))
+            }
+        };
+        let unbound_key = aead::UnboundKey::new(alg, key).map_err(|_| BackendError::Key)?;
+        Ok(aead::LessSafeKey::new(unbound_key))
+    }
+}
+
+impl AeadBackend for AwsLcBackend {
+    fn name(&self) -> &'static str {
+        "aws-lc-rs"
+    }
+
+    fn library_version(&self) -> &'static str {
+        aws_lc_rs::awslc_version()
+    }
+
+    fn algorithms(&self) -> &'static [Algorithm] {
+        &[Algorithm::Aes256Gcm]
+    }
+
+    fn seal(
+        &self,
+        alg: Algorithm,
+        key: &[u8; KEY_LEN],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        in_out: &mut Vec<u8>,
+    ) -> Result<[u8; TAG_LEN], BackendError> {
+        let tag = Self::key(alg, key)?
+            .seal_in_place_separate_tag(
+                aead::Nonce::assume_unique_for_key(*nonce),
+                aead::Aad::from(aad),
+                in_out,
+            )
+            .map_err(|_| BackendError::Seal)?;
+        tag.as_ref().try_into().map_err(|_| BackendError::Seal)
+    }
+
+    fn open(
+        &self,
+        alg: Algorithm,
+        key: &[u8; KEY_LEN],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        in_out: &mut Vec<u8>,
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), BackendError> {
+        let ct_len = in_out.len();
+        in_out.extend_from_slice(tag);
+        Self::key(alg, key)?
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(*nonce),
+                aead::Aad::from(aad),
+                in_out,
+            )
+            .map_err(|_| BackendError::Open)?;
+        in_out.truncate(ct_len);
+        Ok(())
+    }
+
+    fn fill_random(&self, buf: &mut [u8]) -> Result<(), BackendError> {
+        aws_rand::SystemRandom::new()
+            .fill(buf)
+            .map_err(|_| BackendError::Random)
+    }
+}
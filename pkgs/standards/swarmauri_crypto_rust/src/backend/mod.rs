@@ -0,0 +1,134 @@
+//! AEAD backend providers.
+//!
+//! Each provider is compiled in through its cargo feature (`ring`,
+//! `aws-lc-rs`, `rustcrypto`); when several are present the provider is picked
+//! at runtime by name.
+
+use thiserror::Error;
+
+#[cfg(feature = "aws-lc-rs")]
+mod aws_lc;
+#[cfg(feature = "ring")]
+mod ring;
+#[cfg(feature = "rustcrypto")]
+mod rustcrypto;
+
+#[cfg(not(any(feature = "ring", feature = "aws-lc-rs", feature = "rustcrypto")))]
+compile_error!("at least one of the `ring`, `aws-lc-rs` or `rustcrypto` features must be enabled");
+
+pub const KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 12;
+pub const TAG_LEN: usize = 16;
+
+#[derive(Debug, Error)]
+pub enum BackendError {
+    #[error("unknown crypto backend '{0}' (available: {1})")]
+    Unknown(String, String),
+    #[error("the {1} backend does not support {0}")]
+    Unsupported(String, &'static str),
+    #[error("failed to create key")]
+    Key,
+    #[error("encryption failed")]
+    Seal,
+    #[error("decryption failed (authentication error)")]
+    Open,
+    #[error("failed to generate random bytes")]
+    Random,
+}
+
+/// AEAD algorithms with 256-bit keys, 96-bit nonces and 128-bit tags
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    ChaCha20Poly1305,
+    Aes256Gcm,
+}
+
+impl Algorithm {
+    /// Name used in `AEADCiphertext.alg`
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::ChaCha20Poly1305 => "CHACHA20-POLY1305",
+            Algorithm::Aes256Gcm => "AES-256-GCM",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CHACHA20-POLY1305" => Some(Algorithm::ChaCha20Poly1305),
+            "AES-256-GCM" => Some(Algorithm::Aes256Gcm),
+            _ => None,
+        }
+    }
+}
+
+/// AEAD algorithms plus a CSPRNG, as provided by one crypto library
+pub trait AeadBackend: Send + Sync {
+    /// Short name used to select the backend (e.g. `"ring"`)
+    fn name(&self) -> &'static str;
+
+    /// Version of the underlying crypto library
+    fn library_version(&self) -> &'static str;
+
+    /// Algorithms this backend implements; the first is the default
+    fn algorithms(&self) -> &'static [Algorithm];
+
+    /// Look up `name` among the algorithms this backend implements
+    fn algorithm(&self, name: &str) -> Result<Algorithm, BackendError> {
+        Algorithm::from_name(name)
+            .filter(|alg| self.algorithms().contains(alg))
+            .ok_or_else(|| BackendError::Unsupported(name.to_string(), self.name()))
+    }
+
+    /// Encrypt `in_out` in place and return the detached tag
+    fn seal(
+        &self,
+        alg: Algorithm,
+        key: &[u8; KEY_LEN],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        in_out: &mut Vec<u8>,
+    ) -> Result<[u8; TAG_LEN], BackendError>;
+
+    /// Decrypt `in_out` in place after verifying `tag`
+    fn open(
+        &self,
+        alg: Algorithm,
+        key: &[u8; KEY_LEN],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        in_out: &mut Vec<u8>,
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), BackendError>;
+
+    /// Fill `buf` from the backend's secure random generator
+    fn fill_random(&self, buf: &mut [u8]) -> Result<(), BackendError>;
+}
+
+/// Names of the backends compiled into this build, in order of preference
+pub fn available() -> &'static [&'static str] {
+    &[
+        #[cfg(feature = "ring")]
+        "ring",
+        #[cfg(feature = "aws-lc-rs")]
+        "aws-lc-rs",
+        #[cfg(feature = "rustcrypto")]
+        "rustcrypto",
+    ]
+}
+
+/// Look up a backend by name, or the preferred one when `name` is `None`
+pub fn select(name: Option<&str>) -> Result<Box<dyn AeadBackend>, BackendError> {
+    let name = name.unwrap_or_else(|| available()[0]);
+    match name {
+        #[cfg(feature = "ring")]
+        "ring" => Ok(Box::new(ring::RingBackend)),
+        #[cfg(feature = "aws-lc-rs")]
+        "aws-lc-rs" => Ok(Box::new(aws_lc::AwsLcBackend)),
+        #[cfg(feature = "rustcrypto")]
+        "rustcrypto" => Ok(Box::new(rustcrypto::RustCryptoBackend)),
+        other => Err(BackendError::Unknown(
+            other.to_string(),
+            available().join(", "),
+        )),
+    }
+}
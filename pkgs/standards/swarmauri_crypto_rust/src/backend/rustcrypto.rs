@@ -0,0 +1,54 @@
+use super::{AeadBackend, Algorithm, BackendError, KEY_LEN, NONCE_LEN, TAG_LEN};
+use chacha20poly1305::aead::AeadInPlace;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce, Tag};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// Pure-Rust backend built on the RustCrypto `chacha20poly1305` crate
+pub struct RustCryptoBackend;
+
+impl AeadBackend for RustCryptoBackend {
+    fn name(&self) -> &'static str {
+        "rustcrypto"
+    }
+
+    fn library_version(&self) -> &'static str {
+        env!("CHACHA20POLY1305_VERSION")
+    }
+
+    fn algorithms(&self) -> &'static [Algorithm] {
+        &[Algorithm::ChaCha20Poly1305]
+    }
+
+    fn seal(
+        &self,
+        _alg: Algorithm,
+        key: &[u8; KEY_LEN],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        in_out: &mut Vec<u8>,
+    ) -> Result<[u8; TAG_LEN], BackendError> {
+        let tag = ChaCha20Poly1305::new(Key::from_slice(key))
+            .encrypt_in_place_detached(Nonce::from_slice(nonce), aad, in_out)
+            .map_err(|_| BackendError::Seal)?;
+        Ok(tag.into())
+    }
+
+    fn open(
+        &self,
+        _alg: Algorithm,
+        key: &[u8; KEY_LEN],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        in_out: &mut Vec<u8>,
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), BackendError> {
+        ChaCha20Poly1305::new(Key::from_slice(key))
+            .decrypt_in_place_detached(Nonce::from_slice(nonce), aad, in_out, Tag::from_slice(tag))
+            .map_err(|_| BackendError::Open)
+    }
+
+    fn fill_random(&self, buf: &mut [u8]) -> Result<(), BackendError> {
+        OsRng.try_fill_bytes(buf).map_err(|_| BackendError::Random)
+    }
+}
@@ -1,14 +1,41 @@
+// pyo3 0.20's `#[pymethods]` expansion trips this lint on newer toolchains
+#![allow(non_local_definitions)]
+
 use pyo3::prelude::*;
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
-use ring::{aead, rand as ring_rand};
-use ring::rand::SecureRandom;
 use std::collections::HashMap;
 
+mod backend;
+mod metrics;
+
+use backend::{AeadBackend, BackendError, KEY_LEN, NONCE_LEN, TAG_LEN};
+
 /// Rust-based cryptographic operations for Swarmauri
 #[pyclass]
 pub struct RustCrypto {
     #[pyo3(get)]
     pub version: String,
+    backend: Box<dyn AeadBackend>,
+}
+
+impl From<BackendError> for PyErr {
+    fn from(err: BackendError) -> PyErr {
+        match err {
+            BackendError::Unknown(..) | BackendError::Unsupported(..) => {
+                PyValueError::new_err(err.to_string())
+            }
+            _ => PyRuntimeError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// Extract the 32-byte key material from a KeyRef
+fn key_material(key: &KeyRef) -> PyResult<[u8; KEY_LEN]> {
+    let material = key.material.as_ref()
+        .ok_or_else(|| PyValueError::new_err("Key material is required"))?;
+
+    material.as_slice().try_into()
+        .map_err(|_| PyValueError::new_err("Key material must be 32 bytes"))
 }
 
 /// AEAD Ciphertext structure
@@ -66,17 +93,26 @@ pub struct KeyRef {
 #[pymethods]
 impl RustCrypto {
     #[new]
-    pub fn new() -> Self {
-        Self {
+    #[pyo3(signature = (backend=None))]
+    pub fn new(backend: Option<&str>) -> PyResult<Self> {
+        Ok(Self {
             version: "0.1.0".to_string(),
-        }
+            backend: backend::select(backend)?,
+        })
+    }
+
+    /// Name of the AEAD backend in use
+    #[getter]
+    pub fn backend(&self) -> &'static str {
+        self.backend.name()
     }
 
-    /// Get supported algorithms
+    /// Get supported algorithms; the first AEAD algorithm is the default
     pub fn supports(&self) -> PyResult<HashMap<String, Vec<String>>> {
+        let aead: Vec<String> = self.backend.algorithms().iter().map(|alg| alg.name().to_string()).collect();
         let mut supports = HashMap::new();
-        supports.insert("encrypt".to_string(), vec!["CHACHA20-POLY1305".to_string()]);
-        supports.insert("decrypt".to_string(), vec!["CHACHA20-POLY1305".to_string()]);
+        supports.insert("encrypt".to_string(), aead.clone());
+        supports.insert("decrypt".to_string(), aead);
         supports.insert("wrap".to_string(), vec!["ECDH-ES+A256KW".to_string()]);
         supports.insert("unwrap".to_string(), vec!["ECDH-ES+A256KW".to_string()]);
         supports.insert("seal".to_string(), vec!["X25519-SEAL".to_string()]);
@@ -84,95 +120,65 @@ impl RustCrypto {
         Ok(supports)
     }
 
-    /// Encrypt data using AEAD
-    pub fn encrypt(&self, key: &KeyRef, plaintext: &[u8], nonce: Option<&[u8]>, aad: Option<&[u8]>) -> PyResult<AEADCiphertext> {
-        let material = key.material.as_ref()
-            .ok_or_else(|| PyValueError::new_err("Key material is required"))?;
-        
-        if material.len() != 32 {
-            return Err(PyValueError::new_err("Key material must be 32 bytes"));
-        }
-
-        let key_bytes: [u8; 32] = material.as_slice().try_into()
-            .map_err(|_| PyValueError::new_err("Invalid key length"))?;
-
-        let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key_bytes)
-            .map_err(|_| PyRuntimeError::new_err("Failed to create encryption key"))?;
+    /// Encrypt data using AEAD, with the backend's default algorithm unless `alg` is given
+    #[pyo3(signature = (key, plaintext, nonce=None, aad=None, alg=None))]
+    pub fn encrypt(&self, key: &KeyRef, plaintext: &[u8], nonce: Option<&[u8]>, aad: Option<&[u8]>, alg: Option<&str>) -> PyResult<AEADCiphertext> {
+        let mut timer = metrics::start("encrypt", plaintext.len());
+        let key_bytes = key_material(key)?;
+        let alg = match alg {
+            Some(name) => self.backend.algorithm(name)?,
+            None => self.backend.algorithms()[0],
+        };
 
         // Generate or use provided nonce
-        let nonce_bytes = if let Some(n) = nonce {
-            if n.len() != 12 {
-                return Err(PyValueError::new_err("Nonce must be 12 bytes"));
-            }
-            n.to_vec()
+        let nonce_bytes: [u8; NONCE_LEN] = if let Some(n) = nonce {
+            n.try_into()
+                .map_err(|_| PyValueError::new_err("Nonce must be 12 bytes"))?
         } else {
-            let mut nonce = vec![0u8; 12];
-            let rng = ring_rand::SystemRandom::new();
-            rng.fill(&mut nonce)
-                .map_err(|_| PyRuntimeError::new_err("Failed to generate nonce"))?;
+            let mut nonce = [0u8; NONCE_LEN];
+            self.backend.fill_random(&mut nonce)?;
             nonce
         };
 
-        let nonce_seq = aead::Nonce::try_assume_unique_for_key(&nonce_bytes)
-            .map_err(|_| PyRuntimeError::new_err("Invalid nonce"))?;
-
-        let safe_key = aead::LessSafeKey::new(unbound_key);
         let aad_bytes = aad.unwrap_or(&[]);
-        let aad = aead::Aad::from(aad_bytes);
-
         let mut in_out = plaintext.to_vec();
-        let tag = safe_key.seal_in_place_separate_tag(nonce_seq, aad, &mut in_out)
-            .map_err(|_| PyRuntimeError::new_err("Encryption failed"))?;
+        let tag = self.backend.seal(alg, &key_bytes, &nonce_bytes, aad_bytes, &mut in_out)?;
 
+        timer.success();
         Ok(AEADCiphertext {
             kid: key.kid.clone(),
             version: key.version,
-            alg: "CHACHA20-POLY1305".to_string(),
-            nonce: nonce_bytes,
+            alg: alg.name().to_string(),
+            nonce: nonce_bytes.to_vec(),
             ct: in_out,
-            tag: tag.as_ref().to_vec(),
+            tag: tag.to_vec(),
             aad: if aad_bytes.is_empty() { None } else { Some(aad_bytes.to_vec()) },
         })
     }
 
     /// Decrypt data using AEAD
     pub fn decrypt(&self, key: &KeyRef, ciphertext: &AEADCiphertext, aad: Option<&[u8]>) -> PyResult<Vec<u8>> {
-        let material = key.material.as_ref()
-            .ok_or_else(|| PyValueError::new_err("Key material is required"))?;
-        
-        if material.len() != 32 {
-            return Err(PyValueError::new_err("Key material must be 32 bytes"));
-        }
-
-        let key_bytes: [u8; 32] = material.as_slice().try_into()
-            .map_err(|_| PyValueError::new_err("Invalid key length"))?;
-
-        let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key_bytes)
-            .map_err(|_| PyRuntimeError::new_err("Failed to create decryption key"))?;
-
-        let nonce_seq = aead::Nonce::try_assume_unique_for_key(&ciphertext.nonce)
-            .map_err(|_| PyRuntimeError::new_err("Invalid nonce"))?;
+        let mut timer = metrics::start("decrypt", ciphertext.ct.len());
+        let key_bytes = key_material(key)?;
+        let alg = self.backend.algorithm(&ciphertext.alg)?;
 
-        let safe_key = aead::LessSafeKey::new(unbound_key);
-        let aad_bytes = aad.or(ciphertext.aad.as_ref().map(|a| a.as_slice())).unwrap_or(&[]);
-        let aad = aead::Aad::from(aad_bytes);
+        let nonce: [u8; NONCE_LEN] = ciphertext.nonce.as_slice().try_into()
+            .map_err(|_| PyValueError::new_err("Invalid nonce"))?;
+        let tag: [u8; TAG_LEN] = ciphertext.tag.as_slice().try_into()
+            .map_err(|_| PyValueError::new_err("Invalid tag"))?;
+        let aad_bytes = aad.or(ciphertext.aad.as_deref()).unwrap_or(&[]);
 
-        // Combine ciphertext and tag for decryption
-        let mut combined = ciphertext.ct.clone();
-        combined.extend_from_slice(&ciphertext.tag);
+        let mut plaintext = ciphertext.ct.clone();
+        self.backend.open(alg, &key_bytes, &nonce, aad_bytes, &mut plaintext, &tag)?;
 
-        let plaintext = safe_key.open_in_place(nonce_seq, aad, &mut combined)
-            .map_err(|_| PyRuntimeError::new_err("Decryption failed (authentication error)"))?;
-
-        Ok(plaintext.to_vec())
+        timer.success();
+        Ok(plaintext)
     }
 
     /// Generate a random key
     pub fn generate_key(&self, size: usize) -> PyResult<Vec<u8>> {
         let mut key = vec![0u8; size];
-        let rng = ring_rand::SystemRandom::new();
-        rng.fill(&mut key)
-            .map_err(|_| PyRuntimeError::new_err("Failed to generate random key"))?;
+        self.backend.fill_random(&mut key)?;
         Ok(key)
     }
 
@@ -180,9 +186,13 @@ impl RustCrypto {
     pub fn get_version_info(&self) -> PyResult<HashMap<String, String>> {
         let mut info = HashMap::new();
         info.insert("rust_crypto_version".to_string(), self.version.clone());
-        info.insert("ring_version".to_string(), "0.17".to_string());
-        info.insert("backend".to_string(), "ring + Rust".to_string());
-        info.insert("algorithms".to_string(), "ChaCha20-Poly1305, X25519".to_string());
+        #[cfg(feature = "ring")]
+        info.insert("ring_version".to_string(), env!("RING_VERSION").to_string());
+        info.insert("backend".to_string(), format!("{} + Rust", self.backend.name()));
+        info.insert("backend_version".to_string(), self.backend.library_version().to_string());
+        info.insert("available_backends".to_string(), backend::available().join(", "));
+        let aead: Vec<&str> = self.backend.algorithms().iter().map(|alg| alg.name()).collect();
+        info.insert("algorithms".to_string(), format!("{}, X25519", aead.join(", ")));
         Ok(info)
     }
 
@@ -193,6 +203,7 @@ impl RustCrypto {
 
     /// Simple wrap operation (placeholder - would need full ECDH implementation)
     pub fn wrap(&self, kek: &KeyRef, dek: &[u8]) -> PyResult<WrappedKey> {
+        let mut timer = metrics::start("wrap", dek.len());
         if dek.len() != 32 {
             return Err(PyValueError::new_err("DEK must be 32 bytes"));
         }
@@ -202,11 +213,10 @@ impl RustCrypto {
         wrapped.extend_from_slice(dek);
         // Add some randomness for demonstration
         let mut padding = vec![0u8; 16];
-        let rng = ring_rand::SystemRandom::new();
-        rng.fill(&mut padding)
-            .map_err(|_| PyRuntimeError::new_err("Failed to generate padding"))?;
+        self.backend.fill_random(&mut padding)?;
         wrapped.extend_from_slice(&padding);
 
+        timer.success();
         Ok(WrappedKey {
             kek_kid: kek.kid.clone(),
             kek_version: kek.version,
@@ -217,11 +227,13 @@ impl RustCrypto {
 
     /// Simple unwrap operation (placeholder)
     pub fn unwrap(&self, _kek: &KeyRef, wrapped: &WrappedKey) -> PyResult<Vec<u8>> {
+        let mut timer = metrics::start("unwrap", wrapped.wrapped.len());
         if wrapped.wrapped.len() < 32 {
             return Err(PyValueError::new_err("Invalid wrapped key length"));
         }
 
         // This is a simplified implementation - extract the first 32 bytes
+        timer.success();
         Ok(wrapped.wrapped[..32].to_vec())
     }
 }
@@ -285,6 +297,12 @@ impl KeyRef {
     }
 }
 
+/// Names of the AEAD backends compiled into this build
+#[pyfunction]
+fn available_backends() -> Vec<&'static str> {
+    backend::available().to_vec()
+}
+
 /// Python module definition
 #[pymodule]
 fn _rust_crypto(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -292,5 +310,10 @@ fn _rust_crypto(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<AEADCiphertext>()?;
     m.add_class::<WrappedKey>()?;
     m.add_class::<KeyRef>()?;
+    m.add_function(wrap_pyfunction!(available_backends, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::metrics_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::reset_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::set_metrics_enabled, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::metrics_enabled, m)?)?;
     Ok(())
 }
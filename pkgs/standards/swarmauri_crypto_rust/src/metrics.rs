@@ -0,0 +1,176 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Upper bounds (seconds) of the call duration histogram buckets
+const DURATION_BUCKETS: [f64; 12] = [
+    0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0,
+];
+
+/// Upper bounds (bytes) of the payload size histogram buckets
+const PAYLOAD_BUCKETS: [f64; 10] = [
+    64.0, 256.0, 1024.0, 4096.0, 16_384.0, 65_536.0, 262_144.0, 1_048_576.0, 4_194_304.0,
+    16_777_216.0,
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static REGISTRY: Mutex<BTreeMap<&'static str, OpMetrics>> = Mutex::new(BTreeMap::new());
+
+/// Fixed-bucket histogram, non-cumulative internally
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let idx = self
+            .bounds
+            .iter()
+            .position(|&le| value <= le)
+            .unwrap_or(self.bounds.len());
+        self.counts[idx] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// Render as a dict with Prometheus-style cumulative `(le, count)` buckets
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("count", self.count)?;
+        dict.set_item("sum", self.sum)?;
+        let buckets = PyList::empty(py);
+        let mut cumulative = 0u64;
+        for (i, count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            let le = self.bounds.get(i).copied().unwrap_or(f64::INFINITY);
+            buckets.append((le, cumulative))?;
+        }
+        dict.set_item("buckets", buckets)?;
+        Ok(dict)
+    }
+}
+
+/// Per-operation counters and histograms
+struct OpMetrics {
+    calls: u64,
+    errors: u64,
+    duration: Histogram,
+    payload: Histogram,
+}
+
+impl OpMetrics {
+    fn new() -> Self {
+        Self {
+            calls: 0,
+            errors: 0,
+            duration: Histogram::new(&DURATION_BUCKETS),
+            payload: Histogram::new(&PAYLOAD_BUCKETS),
+        }
+    }
+}
+
+/// Times a single operation and records it when dropped.
+///
+/// Calls that return early (e.g. through `?`) are counted as errors unless
+/// `success()` was called first.
+pub struct CallTimer {
+    op: &'static str,
+    payload_len: usize,
+    start: Option<Instant>,
+    ok: bool,
+    _span: tracing::span::EnteredSpan,
+}
+
+impl CallTimer {
+    pub fn success(&mut self) {
+        self.ok = true;
+    }
+}
+
+impl Drop for CallTimer {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed().as_secs_f64();
+        let mut registry = match REGISTRY.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let entry = registry.entry(self.op).or_insert_with(OpMetrics::new);
+        entry.calls += 1;
+        if !self.ok {
+            entry.errors += 1;
+        }
+        entry.duration.observe(elapsed);
+        entry.payload.observe(self.payload_len as f64);
+    }
+}
+
+/// Start timing an operation; a no-op unless metrics are enabled
+pub fn start(op: &'static str, payload_len: usize) -> CallTimer {
+    let span = tracing::debug_span!("rust_crypto", op, payload_len).entered();
+    CallTimer {
+        op,
+        payload_len,
+        start: ENABLED.load(Ordering::Relaxed).then(Instant::now),
+        ok: false,
+        _span: span,
+    }
+}
+
+/// Enable or disable collection of per-call metrics
+#[pyfunction]
+pub fn set_metrics_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether per-call metrics are currently being collected
+#[pyfunction]
+pub fn metrics_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Snapshot of the collected metrics keyed by operation name
+#[pyfunction]
+pub fn metrics_snapshot(py: Python<'_>) -> PyResult<PyObject> {
+    let registry = match REGISTRY.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let snapshot = PyDict::new(py);
+    for (op, metrics) in registry.iter() {
+        let entry = PyDict::new(py);
+        entry.set_item("calls", metrics.calls)?;
+        entry.set_item("errors", metrics.errors)?;
+        entry.set_item("duration_seconds", metrics.duration.to_dict(py)?)?;
+        entry.set_item("payload_bytes", metrics.payload.to_dict(py)?)?;
+        snapshot.set_item(*op, entry)?;
+    }
+    Ok(snapshot.into())
+}
+
+/// Discard all collected metrics
+#[pyfunction]
+pub fn reset_metrics() {
+    let mut registry = match REGISTRY.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    registry.clear();
+}
@@ -9,7 +9,7 @@ from swarmauri_core.crypto.types import (
     WrappedKey,
 )
 
-from swarmauri_crypto_rust import RustCrypto
+from swarmauri_crypto_rust import RustCrypto, available_backends
 
 
 @pytest.fixture
@@ -280,3 +280,89 @@ async def test_error_handling_wrong_algorithm(rust_crypto, symmetric_key):
         await rust_crypto.encrypt(
             symmetric_key, b"test", alg="UNSUPPORTED-ALG"
         )
+
+
+@pytest.mark.asyncio
+async def test_metrics_snapshot(rust_crypto, symmetric_key):
+    rust_crypto.reset_metrics()
+    rust_crypto.enable_metrics()
+    try:
+        ciphertext = await rust_crypto.encrypt(symmetric_key, b"x" * 100)
+        await rust_crypto.decrypt(symmetric_key, ciphertext)
+        snapshot = rust_crypto.metrics_snapshot()
+    finally:
+        rust_crypto.enable_metrics(False)
+        rust_crypto.reset_metrics()
+
+    assert snapshot["encrypt"]["calls"] == 1
+    assert snapshot["encrypt"]["errors"] == 0
+    assert snapshot["decrypt"]["calls"] == 1
+    payload = snapshot["encrypt"]["payload_bytes"]
+    assert payload["sum"] == 100
+    assert payload["buckets"][-1] == (float("inf"), 1)
+    assert snapshot["encrypt"]["duration_seconds"]["count"] == 1
+    assert rust_crypto.metrics_snapshot() == {}
+
+
+@pytest.mark.asyncio
+async def test_metrics_disabled_by_default(rust_crypto, symmetric_key):
+    rust_crypto.reset_metrics()
+    await rust_crypto.encrypt(symmetric_key, b"test")
+    assert rust_crypto.metrics_snapshot() == {}
+
+
+@pytest.mark.unit
+def test_default_backend(rust_crypto):
+    assert rust_crypto.backend == available_backends()[0]
+    assert rust_crypto.get_version_info()["backend"].startswith(rust_crypto.backend)
+
+
+@pytest.mark.unit
+def test_unknown_backend():
+    with pytest.raises(ValueError):
+        RustCrypto(backend="does-not-exist")
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("backend", available_backends())
+async def test_backends_interoperate(backend, symmetric_key):
+    sealer = RustCrypto(backend=backend)
+    plaintext = b"portable ciphertext"
+    for alg in sealer.supports()["encrypt"]:
+        ciphertext = await sealer.encrypt(
+            symmetric_key, plaintext, alg=alg, aad=b"aad"
+        )
+        assert ciphertext.alg == alg
+        for other in available_backends():
+            opener = RustCrypto(backend=other)
+            if alg not in opener.supports()["decrypt"]:
+                continue
+            assert await opener.decrypt(symmetric_key, ciphertext, aad=b"aad") == plaintext
+
+
+@pytest.mark.asyncio
+async def test_aes_256_gcm_roundtrip(symmetric_key):
+    crypto = next(
+        (
+            RustCrypto(backend=backend)
+            for backend in available_backends()
+            if "AES-256-GCM" in RustCrypto(backend=backend).supports()["encrypt"]
+        ),
+        None,
+    )
+    if crypto is None:
+        pytest.skip("no compiled-in backend supports AES-256-GCM")
+    ciphertext = await crypto.encrypt(symmetric_key, b"approved", alg="AES-256-GCM")
+    assert ciphertext.alg == "AES-256-GCM"
+    assert len(ciphertext.tag) == 16
+    assert await crypto.decrypt(symmetric_key, ciphertext) == b"approved"
+
+
+@pytest.mark.unit
+@pytest.mark.skipif(
+    "aws-lc-rs" not in available_backends(), reason="aws-lc-rs not compiled in"
+)
+def test_aws_lc_offers_only_fips_approved_aead():
+    crypto = RustCrypto(backend="aws-lc-rs")
+    assert tuple(crypto.supports()["encrypt"]) == ("AES-256-GCM",)
+    assert crypto.get_version_info()["backend_version"][0].isdigit()